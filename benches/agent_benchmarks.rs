@@ -3,6 +3,7 @@
 //! Benchmarks cover:
 //!   - Tool dispatch (XML parsing, native parsing)
 //!   - Memory store/recall cycles (SQLite backend)
+//!   - Vector search: brute-force scan vs IVF ANN index
 //!   - Agent turn cycle (full orchestration loop)
 //!
 //! Run: `cargo bench`
@@ -17,7 +18,9 @@ use zeroclaw::agent::agent::Agent;
 use zeroclaw::agent::dispatcher::{NativeToolDispatcher, ToolDispatcher, XmlToolDispatcher};
 use zeroclaw::config::MemoryConfig;
 use zeroclaw::memory;
-use zeroclaw::memory::{Memory, MemoryCategory};
+use zeroclaw::memory::ann::{IvfIndex, ANN_DEFAULT_NPROBE};
+use zeroclaw::memory::vector;
+use zeroclaw::memory::{Memory, MemoryCategory, SqliteMemory};
 use zeroclaw::observability::{NoopObserver, Observer};
 use zeroclaw::providers::{ChatRequest, ChatResponse, Provider, ToolCall};
 use zeroclaw::tools::{Tool, ToolResult};
@@ -275,6 +278,61 @@ fn bench_memory_operations(c: &mut Criterion) {
     });
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// Benchmark: Vector search (brute force vs ANN index)
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Deterministic pseudo-random embedding (xorshift) so runs are comparable.
+fn bench_embedding(seed: u64, dims: usize) -> Vec<f32> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..dims)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            #[allow(clippy::cast_precision_loss)]
+            let unit = (state % 10_000) as f32 / 10_000.0;
+            unit
+        })
+        .collect()
+}
+
+fn bench_vector_search(c: &mut Criterion) {
+    const ENTRIES: u64 = 20_000;
+    const DIMS: usize = 256;
+
+    let tmp = tempfile::TempDir::new().unwrap();
+    let mem = SqliteMemory::new(tmp.path()).unwrap();
+    let conn = mem.connection().lock();
+    conn.execute_batch("BEGIN").unwrap();
+    for i in 0..ENTRIES {
+        conn.execute(
+            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at)
+             VALUES (?1, ?1, 'bench', 'core', ?2, 'now', 'now')",
+            rusqlite::params![
+                format!("m{i}"),
+                vector::vec_to_bytes(&bench_embedding(i, DIMS))
+            ],
+        )
+        .unwrap();
+    }
+    conn.execute_batch("COMMIT").unwrap();
+    let index = IvfIndex::rebuild(&conn).unwrap().unwrap();
+    let query = bench_embedding(ENTRIES + 1, DIMS);
+
+    c.bench_function("vector_search_brute_force_20k", |b| {
+        b.iter(|| SqliteMemory::vector_search(&conn, black_box(&query), 10, None, None).unwrap());
+    });
+
+    c.bench_function("vector_search_ann_20k", |b| {
+        b.iter(|| {
+            index
+                .search(&conn, black_box(&query), 10, ANN_DEFAULT_NPROBE, None, None)
+                .unwrap()
+        });
+    });
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Benchmark: Full agent turn cycle
 // ─────────────────────────────────────────────────────────────────────────────
@@ -324,6 +382,7 @@ criterion_group!(
     bench_xml_parsing,
     bench_native_parsing,
     bench_memory_operations,
+    bench_vector_search,
     bench_agent_turn,
 );
 criterion_main!(benches);
@@ -3,10 +3,11 @@ use crate::agent::dispatcher::{
 };
 use crate::agent::enrich_user_message;
 use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
-use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
+use crate::agent::prompt::{McpResourcesSection, PromptContext, SystemPromptBuilder};
 use crate::config::Config;
 use crate::i18n::ToolDescriptions;
 use crate::memory::{self, Memory, MemoryCategory};
+use crate::observability::trace_context::{SpanKind, TraceSpan};
 use crate::observability::{self, Observer, ObserverEvent};
 use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
 use crate::runtime;
@@ -67,6 +68,10 @@ pub struct Agent {
     security_summary: Option<String>,
     /// Autonomy level from config; controls safety prompt instructions.
     autonomy_level: crate::security::AutonomyLevel,
+    /// `[[autonomy.rules]]`; `deny` matches are refused before execution.
+    tool_rules: Arc<crate::security::tool_rules::ToolPolicy>,
+    /// Owner of background processes started by this agent's tool calls.
+    process_session: String,
     /// Activated MCP tools for deferred loading mode.
     /// When MCP deferred loading is enabled, tools are activated via `tool_search`
     /// and stored here for lookup during tool execution.
@@ -98,6 +103,7 @@ pub struct AgentBuilder {
     tool_descriptions: Option<ToolDescriptions>,
     security_summary: Option<String>,
     autonomy_level: Option<crate::security::AutonomyLevel>,
+    tool_rules: Option<Arc<crate::security::tool_rules::ToolPolicy>>,
     activated_tools: Option<Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>>,
 }
 
@@ -128,6 +134,7 @@ impl AgentBuilder {
             tool_descriptions: None,
             security_summary: None,
             autonomy_level: None,
+            tool_rules: None,
             activated_tools: None,
         }
     }
@@ -261,6 +268,11 @@ impl AgentBuilder {
         self
     }
 
+    pub fn tool_rules(mut self, rules: Arc<crate::security::tool_rules::ToolPolicy>) -> Self {
+        self.tool_rules = Some(rules);
+        self
+    }
+
     pub fn activated_tools(
         mut self,
         activated: Option<Arc<std::sync::Mutex<tools::ActivatedToolSet>>>,
@@ -324,6 +336,8 @@ impl AgentBuilder {
             autonomy_level: self
                 .autonomy_level
                 .unwrap_or(crate::security::AutonomyLevel::Supervised),
+            tool_rules: self.tool_rules.unwrap_or_default(),
+            process_session: format!("agent-{}", uuid::Uuid::new_v4()),
             activated_tools: self.activated_tools,
         })
     }
@@ -346,6 +360,12 @@ impl Agent {
         self.memory_session_id = session_id;
     }
 
+    /// Kill the background processes this agent started. Call when the
+    /// conversation it serves ends (connection closed, session stopped).
+    pub fn end_process_session(&self) {
+        crate::tools::process::end_session(&self.process_session);
+    }
+
     /// Hydrate the agent with prior chat messages (e.g. from a session backend).
     ///
     /// Ensures a system prompt is prepended if history is empty, then appends all
@@ -365,15 +385,32 @@ impl Agent {
         }
     }
 
+    /// Drop history entries past `len`, e.g. to discard a cancelled turn.
+    pub fn truncate_history(&mut self, len: usize) {
+        self.history.truncate(len);
+    }
+
+    /// Replace the tool registry, e.g. to wrap every tool for a front end
+    /// that reports or delegates tool calls. Tool specs are rebuilt from the
+    /// returned tools; `allowed_tools` is not re-applied.
+    pub fn replace_tools(
+        &mut self,
+        replace: impl FnOnce(Vec<Box<dyn Tool>>) -> Vec<Box<dyn Tool>>,
+    ) {
+        self.tools = replace(std::mem::take(&mut self.tools));
+        self.tool_specs = self.tools.iter().map(|tool| tool.spec()).collect();
+    }
+
     pub async fn from_config(config: &Config) -> Result<Self> {
         let observer: Arc<dyn Observer> =
             Arc::from(observability::create_observer(&config.observability));
-        let runtime: Arc<dyn runtime::RuntimeAdapter> =
-            Arc::from(runtime::create_runtime(&config.runtime)?);
-        let security = Arc::new(SecurityPolicy::from_config(
-            &config.autonomy,
-            &config.workspace_dir,
-        ));
+        let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(
+            runtime::create_runtime_with_observer(&config.runtime, Some(observer.clone()))?,
+        );
+        let security = Arc::new(
+            SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+                .with_trust(crate::trust::runtime::init(config)),
+        );
 
         let memory: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
             &config.memory,
@@ -394,7 +431,7 @@ impl Agent {
             None
         };
 
-        let sop_engine = crate::sop::create_sop_engine(&config.sop, &config.workspace_dir);
+        let sop_engine = crate::sop::shared_sop_engine(&config.sop, &config.workspace_dir);
         let (
             mut tools,
             delegate_handle,
@@ -425,12 +462,13 @@ impl Agent {
         // and webhook paths (loop_.rs) so that the WebSocket/daemon UI
         // path also has access to MCP tools.
         let mut activated_tools: Option<Arc<std::sync::Mutex<tools::ActivatedToolSet>>> = None;
+        let mut mcp_context_section = String::new();
         if config.mcp.enabled && !config.mcp.servers.is_empty() {
             tracing::info!(
                 "Initializing MCP client — {} server(s) configured",
                 config.mcp.servers.len()
             );
-            match tools::McpRegistry::connect_all(&config.mcp.servers).await {
+            match tools::McpRegistry::connect_from_config(config).await {
                 Ok(registry) => {
                     let registry = std::sync::Arc::new(registry);
                     if config.mcp.deferred_loading {
@@ -474,6 +512,14 @@ impl Agent {
                             registry.server_count()
                         );
                     }
+                    let resource_servers = registry.resource_servers().await;
+                    if !resource_servers.is_empty() {
+                        tools.push(Box::new(tools::McpResourcesTool::new(
+                            std::sync::Arc::clone(&registry),
+                            resource_servers,
+                        )));
+                    }
+                    mcp_context_section = registry.resource_context_section().await;
                 }
                 Err(e) => {
                     tracing::error!("MCP registry failed to initialize: {e:#}");
@@ -489,7 +535,8 @@ impl Agent {
             .unwrap_or("anthropic/claude-sonnet-4-20250514")
             .to_string();
 
-        let provider_runtime_options = providers::provider_runtime_options_from_config(config);
+        let provider_runtime_options = providers::provider_runtime_options_from_config(config)
+            .with_budget_observer(observer.clone());
 
         let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
             provider_name,
@@ -529,6 +576,12 @@ impl Agent {
             None
         };
 
+        let mut prompt_builder = SystemPromptBuilder::with_defaults();
+        if !mcp_context_section.is_empty() {
+            prompt_builder =
+                prompt_builder.add_section(Box::new(McpResourcesSection(mcp_context_section)));
+        }
+
         Agent::builder()
             .provider(provider)
             .tools(tools)
@@ -540,7 +593,7 @@ impl Agent {
                 5,
                 config.memory.min_relevance_score,
             )))
-            .prompt_builder(SystemPromptBuilder::with_defaults())
+            .prompt_builder(prompt_builder)
             .config(config.agent.clone())
             .model_name(model_name)
             .temperature(config.default_temperature)
@@ -557,6 +610,7 @@ impl Agent {
             .auto_save(config.memory.auto_save)
             .security_summary(Some(security.prompt_summary()))
             .autonomy_level(config.autonomy.level)
+            .tool_rules(Arc::clone(&security.tool_rules))
             .activated_tools(activated_tools)
             .build()
     }
@@ -605,12 +659,65 @@ impl Agent {
         self.prompt_builder.build(&ctx)
     }
 
+    /// Span for one provider call, nested under the turn's span.
+    fn chat_span(&self, model: &str, turn_span: &TraceSpan) -> TraceSpan {
+        TraceSpan::start_with_parent(
+            format!("chat {model}"),
+            SpanKind::Client,
+            Some(turn_span.context()),
+        )
+        .with_attribute("gen_ai.operation.name", "chat")
+        .with_attribute("gen_ai.request.model", model)
+        .with_attribute("gen_ai.request.temperature", self.temperature)
+    }
+
     async fn execute_tool_call(&self, call: &ParsedToolCall) -> ToolExecutionResult {
         let start = Instant::now();
+        let mut span = TraceSpan::start(format!("execute_tool {}", call.name), SpanKind::Internal)
+            .with_attribute("gen_ai.operation.name", "execute_tool")
+            .with_attribute("gen_ai.tool.name", call.name.as_str());
+        if let Some(id) = call.tool_call_id.as_deref() {
+            span.set_attribute("gen_ai.tool.call.id", id);
+        }
+
+        // An agent session has no chat of its own, so a file delivery to any
+        // channel is off-conversation and needs an allow rule.
+        let denial = crate::agent::tool_execution::policy_denial(
+            &self.tool_rules,
+            &call.name,
+            &call.arguments,
+        )
+        .or_else(|| {
+            crate::tools::file_delivery::off_conversation_target(
+                Some(&self.tool_rules),
+                &call.name,
+                &call.arguments,
+                "",
+                None,
+            )
+            .map(|target| {
+                format!(
+                    "Not delivered: {target} is outside this conversation and needs an allow rule."
+                )
+            })
+        });
+        if let Some(reason) = denial {
+            span.set_error(reason.clone());
+            return ToolExecutionResult {
+                name: call.name.clone(),
+                output: reason,
+                success: false,
+                tool_call_id: call.tool_call_id.clone(),
+            };
+        }
 
         // First try to find tool in static registry, then in activated MCP tools.
         let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
-            match tool.execute(call.arguments.clone()).await {
+            let execution = crate::tools::process::PROCESS_SESSION.scope(
+                self.process_session.clone(),
+                tool.execute(call.arguments.clone()),
+            );
+            match span.scope(execution).await {
                 Ok(r) => {
                     self.observer.record_event(&ObserverEvent::ToolCall {
                         tool: call.name.clone(),
@@ -636,7 +743,11 @@ impl Agent {
             // Try to find in activated MCP tools.
             let activated_opt = activated_arc.lock().unwrap().get_resolved(&call.name);
             if let Some(tool) = activated_opt {
-                match tool.execute(call.arguments.clone()).await {
+                let execution = crate::tools::process::PROCESS_SESSION.scope(
+                    self.process_session.clone(),
+                    tool.execute(call.arguments.clone()),
+                );
+                match span.scope(execution).await {
                     Ok(r) => {
                         self.observer.record_event(&ObserverEvent::ToolCall {
                             tool: call.name.clone(),
@@ -664,6 +775,9 @@ impl Agent {
         } else {
             format!("Unknown tool: {}", call.name)
         };
+        if result.starts_with("Error") || result.starts_with("Unknown tool:") {
+            span.set_error(result.clone());
+        }
 
         ToolExecutionResult {
             name: call.name.clone(),
@@ -768,6 +882,10 @@ impl Agent {
             .push(ConversationMessage::Chat(ChatMessage::user(enriched)));
 
         let effective_model = self.classify_model(user_message);
+        let turn_span = TraceSpan::start("invoke_agent zeroclaw", SpanKind::Internal)
+            .with_attribute("gen_ai.operation.name", "invoke_agent")
+            .with_attribute("gen_ai.agent.name", "zeroclaw")
+            .with_attribute("gen_ai.request.model", effective_model.as_str());
 
         for _ in 0..self.config.max_tool_iterations {
             let messages = self.tool_dispatcher.to_provider_messages(&self.history);
@@ -812,6 +930,7 @@ impl Agent {
                 });
             }
 
+            let mut chat_span = self.chat_span(&effective_model, &turn_span);
             let response = match self
                 .provider
                 .chat(
@@ -822,6 +941,7 @@ impl Agent {
                         } else {
                             None
                         },
+                        response_format: None,
                     },
                     &effective_model,
                     self.temperature,
@@ -829,8 +949,13 @@ impl Agent {
                 .await
             {
                 Ok(resp) => resp,
-                Err(err) => return Err(err),
+                Err(err) => {
+                    chat_span.set_error(err.to_string());
+                    return Err(err);
+                }
             };
+            record_chat_usage(&mut chat_span, &response);
+            chat_span.end();
 
             let (text, calls) = self.tool_dispatcher.parse_response(&response);
             if calls.is_empty() {
@@ -875,7 +1000,7 @@ impl Agent {
                 reasoning_content: response.reasoning_content.clone(),
             });
 
-            let results = self.execute_tools(&calls).await;
+            let results = turn_span.scope(self.execute_tools(&calls)).await;
             let formatted = self.tool_dispatcher.format_results(&results);
             self.history.push(formatted);
             self.trim_history();
@@ -937,6 +1062,10 @@ impl Agent {
             .push(ConversationMessage::Chat(ChatMessage::user(enriched)));
 
         let effective_model = self.classify_model(user_message);
+        let turn_span = TraceSpan::start("invoke_agent zeroclaw", SpanKind::Internal)
+            .with_attribute("gen_ai.operation.name", "invoke_agent")
+            .with_attribute("gen_ai.agent.name", "zeroclaw")
+            .with_attribute("gen_ai.request.model", effective_model.as_str());
 
         // ── Turn loop ──────────────────────────────────────────────────
         for _ in 0..self.config.max_tool_iterations {
@@ -987,6 +1116,7 @@ impl Agent {
             // forward deltas.  Otherwise fall back to non-streaming chat.
             use futures_util::StreamExt;
 
+            let mut chat_span = self.chat_span(&effective_model, &turn_span);
             let stream_opts = crate::providers::traits::StreamOptions::new(true);
             let mut stream = self.provider.stream_chat(
                 crate::providers::ChatRequest {
@@ -996,6 +1126,7 @@ impl Agent {
                     } else {
                         None
                     },
+                    response_format: None,
                 },
                 &effective_model,
                 self.temperature,
@@ -1082,6 +1213,7 @@ impl Agent {
                             } else {
                                 None
                             },
+                            response_format: None,
                         },
                         &effective_model,
                         self.temperature,
@@ -1089,9 +1221,14 @@ impl Agent {
                     .await
                 {
                     Ok(resp) => resp,
-                    Err(err) => return Err(err),
+                    Err(err) => {
+                        chat_span.set_error(err.to_string());
+                        return Err(err);
+                    }
                 }
             };
+            record_chat_usage(&mut chat_span, &response);
+            chat_span.end();
 
             let (text, calls) = self.tool_dispatcher.parse_response(&response);
             if calls.is_empty() {
@@ -1154,7 +1291,7 @@ impl Agent {
                     .await;
             }
 
-            let results = self.execute_tools(&calls).await;
+            let results = turn_span.scope(self.execute_tools(&calls)).await;
 
             // Notify about each tool result
             for result in &results {
@@ -1208,6 +1345,20 @@ impl Agent {
     }
 }
 
+fn record_chat_usage(span: &mut TraceSpan, response: &providers::ChatResponse) {
+    if let Some(usage) = response.usage.as_ref() {
+        if let Some(tokens) = usage.input_tokens {
+            span.set_attribute("gen_ai.usage.input_tokens", tokens);
+        }
+        if let Some(tokens) = usage.output_tokens {
+            span.set_attribute("gen_ai.usage.output_tokens", tokens);
+        }
+    }
+    if !response.tool_calls.is_empty() {
+        span.set_attribute("gen_ai.response.finish_reasons", "tool_calls");
+    }
+}
+
 pub async fn run(
     config: Config,
     message: Option<String>,
@@ -34,6 +34,7 @@ pub(crate) fn record_tool_loop_cost_usage(
     usage: &crate::providers::traits::TokenUsage,
 ) -> Option<(u64, f64)> {
     let input_tokens = usage.input_tokens.unwrap_or(0);
+    let cached_input_tokens = usage.cached_input_tokens.unwrap_or(0);
     let output_tokens = usage.output_tokens.unwrap_or(0);
     let total_tokens = input_tokens.saturating_add(output_tokens);
     if total_tokens == 0 {
@@ -54,11 +55,13 @@ pub(crate) fn record_tool_loop_cost_usage(
                 .rsplit_once('/')
                 .and_then(|(_, suffix)| ctx.prices.get(suffix))
         });
-    let cost_usage = CostTokenUsage::new(
+    let cost_usage = CostTokenUsage::with_cached_input(
         model,
         input_tokens,
+        cached_input_tokens,
         output_tokens,
         pricing.map_or(0.0, |entry| entry.input),
+        pricing.map_or(0.0, ModelPricing::cached_input_price),
         pricing.map_or(0.0, |entry| entry.output),
     );
 
@@ -65,6 +65,7 @@ impl GovernedCaseDraft {
             topic: Some(self.webhook_path.clone()),
             payload: Some(raw_message.to_string()),
             timestamp: chrono::Utc::now().to_rfc3339(),
+            idempotency_key: None,
         }
     }
 }
@@ -153,6 +154,23 @@ pub fn summarize_dispatch_results(results: &[DispatchResult]) -> GovernedDispatc
                 sop_name: Some(sop_name.clone()),
                 run_id: Some(run_id.clone()),
             },
+            SopRunAction::FanOut { actions, .. } => GovernedDispatchSummary {
+                response_mode: GovernedResponseMode::ApplySop,
+                detail: format!(
+                    "matched SOP '{sop_name}' (run {run_id}); {} parallel steps are ready for bounded execution",
+                    actions.len()
+                ),
+                sop_name: Some(sop_name.clone()),
+                run_id: Some(run_id.clone()),
+            },
+            SopRunAction::Retry { .. } | SopRunAction::AwaitParallel { .. } => {
+                GovernedDispatchSummary {
+                    response_mode: GovernedResponseMode::ApplySop,
+                    detail: format!("matched SOP '{sop_name}' (run {run_id}); the run is in progress"),
+                    sop_name: Some(sop_name.clone()),
+                    run_id: Some(run_id.clone()),
+                }
+            }
             SopRunAction::Completed { .. } => GovernedDispatchSummary {
                 response_mode: GovernedResponseMode::ApplySop,
                 detail: format!("matched SOP '{sop_name}' (run {run_id}) and it completed immediately"),
@@ -8,12 +8,14 @@ use crate::cost::types::BudgetCheck;
 use crate::i18n::ToolDescriptions;
 use crate::memory::{self, decay, Memory, MemoryCategory};
 use crate::multimodal;
+use crate::observability::trace_context::{SpanKind, TraceSpan};
 use crate::observability::{self, runtime_trace, Observer, ObserverEvent};
 use crate::providers::traits::StreamEvent;
 use crate::providers::{
     self, ChatMessage, ChatRequest, Provider, ProviderCapabilityError, ToolCall,
 };
 use crate::runtime;
+use crate::security::tool_rules::ToolPolicy;
 use crate::security::{AutonomyLevel, SecurityPolicy};
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
@@ -2010,6 +2012,7 @@ async fn consume_provider_streaming_response(
         ChatRequest {
             messages,
             tools: request_tools,
+            response_format: None,
         },
         model,
         temperature,
@@ -2122,6 +2125,7 @@ pub(crate) async fn agent_turn(
     multimodal_config: &crate::config::MultimodalConfig,
     max_tool_iterations: usize,
     approval: Option<&ApprovalManager>,
+    tool_rules: Option<&ToolPolicy>,
     excluded_tools: &[String],
     dedup_exempt_tools: &[String],
     activated_tools: Option<&std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>>,
@@ -2137,6 +2141,7 @@ pub(crate) async fn agent_turn(
         temperature,
         silent,
         approval,
+        tool_rules,
         channel_name,
         channel_reply_target,
         multimodal_config,
@@ -2192,6 +2197,31 @@ fn maybe_inject_channel_delivery_defaults(
         return;
     }
 
+    // File-producing tools deliver back to the current conversation when the
+    // model asks for delivery without naming a destination.
+    if crate::tools::file_delivery::FILE_DELIVERY_TOOLS.contains(&tool_name) {
+        if channel_name == "cli" || !crate::tools::file_delivery::delivery_requested(tool_args) {
+            return;
+        }
+        let Some(reply_target) = channel_reply_target
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+        else {
+            return;
+        };
+        if let Some(args) = tool_args.as_object_mut() {
+            let channel_usable = args
+                .get("channel")
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty());
+            if !channel_usable {
+                args.insert("channel".into(), serde_json::json!(channel_name));
+                args.insert("recipient".into(), serde_json::json!(reply_target));
+            }
+        }
+        return;
+    }
+
     if tool_name != "cron_add" {
         return;
     }
@@ -2305,6 +2335,7 @@ pub(crate) async fn run_tool_call_loop(
     temperature: f64,
     silent: bool,
     approval: Option<&ApprovalManager>,
+    tool_rules: Option<&ToolPolicy>,
     channel_name: &str,
     channel_reply_target: Option<&str>,
     multimodal_config: &crate::config::MultimodalConfig,
@@ -2329,6 +2360,25 @@ pub(crate) async fn run_tool_call_loop(
 
     let turn_id = Uuid::new_v4().to_string();
     let loop_started_at = Instant::now();
+    let trust_conversation = format!("{channel_name}:{}", channel_reply_target.unwrap_or(""));
+    // Channels scope turns by their history key; elsewhere the channel and
+    // reply target identify the conversation across turns.
+    let conversation_id = crate::observability::trace_context::current_conversation()
+        .unwrap_or_else(|| trust_conversation.clone());
+    let turn_span = TraceSpan::start("invoke_agent zeroclaw", SpanKind::Internal)
+        .with_attribute("gen_ai.operation.name", "invoke_agent")
+        .with_attribute("gen_ai.agent.name", "zeroclaw")
+        .with_attribute("gen_ai.provider.name", provider_name)
+        .with_attribute("gen_ai.request.model", model)
+        .with_attribute("gen_ai.conversation.id", conversation_id.as_str())
+        .with_attribute("zeroclaw.turn.id", turn_id.as_str())
+        .with_attribute("zeroclaw.channel", channel_name);
+    let trust = crate::trust::runtime::global();
+    if let Some(trust) = trust {
+        if let Some(message) = history.iter().rev().find(|m| m.role == "user") {
+            trust.observe_user_message(&trust_conversation, &message.content);
+        }
+    }
     let loop_ignore_tools: HashSet<&str> = pacing
         .loop_ignore_tools
         .iter()
@@ -2521,6 +2571,15 @@ pub(crate) async fn run_tool_call_loop(
         );
 
         let llm_started_at = Instant::now();
+        let mut chat_span = TraceSpan::start_with_parent(
+            format!("chat {active_model}"),
+            SpanKind::Client,
+            Some(turn_span.context()),
+        )
+        .with_attribute("gen_ai.operation.name", "chat")
+        .with_attribute("gen_ai.provider.name", active_provider_name)
+        .with_attribute("gen_ai.request.model", active_model)
+        .with_attribute("gen_ai.request.temperature", temperature);
 
         // Fire void hook before LLM call
         if let Some(hooks) = hooks {
@@ -2608,6 +2667,7 @@ pub(crate) async fn run_tool_call_loop(
                             ChatRequest {
                                 messages: &prepared_messages.messages,
                                 tools: request_tools,
+                                response_format: None,
                             },
                             active_model,
                             temperature,
@@ -2630,6 +2690,7 @@ pub(crate) async fn run_tool_call_loop(
                 ChatRequest {
                     messages: &prepared_messages.messages,
                     tools: request_tools,
+                    response_format: None,
                 },
                 active_model,
                 temperature,
@@ -2697,6 +2758,16 @@ pub(crate) async fn run_tool_call_loop(
                     input_tokens: resp_input_tokens,
                     output_tokens: resp_output_tokens,
                 });
+                if let Some(tokens) = resp_input_tokens {
+                    chat_span.set_attribute("gen_ai.usage.input_tokens", tokens);
+                }
+                if let Some(tokens) = resp_output_tokens {
+                    chat_span.set_attribute("gen_ai.usage.output_tokens", tokens);
+                }
+                if !resp.tool_calls.is_empty() {
+                    chat_span.set_attribute("gen_ai.response.finish_reasons", "tool_calls");
+                }
+                chat_span.end();
 
                 // Record cost via task-local tracker (no-op when not scoped)
                 let _ = resp
@@ -2812,6 +2883,8 @@ pub(crate) async fn run_tool_call_loop(
                     input_tokens: None,
                     output_tokens: None,
                 });
+                chat_span.set_error(safe_error.as_str());
+                chat_span.end();
                 runtime_trace::record_event(
                     "llm_response",
                     Some(channel_name),
@@ -3022,25 +3095,74 @@ pub(crate) async fn run_tool_call_loop(
                 channel_name,
                 channel_reply_target,
             );
+            let off_conversation = crate::tools::file_delivery::off_conversation_target(
+                tool_rules,
+                &tool_name,
+                &tool_args,
+                channel_name,
+                channel_reply_target,
+            );
 
             // ── Approval hook ────────────────────────────────
+            // Files sent outside the current conversation always ask; with no
+            // approval manager nobody can approve them, so they are refused.
+            if approval.is_none() {
+                if let Some(target) = off_conversation.as_deref() {
+                    let refused = format!(
+                        "Not delivered: {target} is outside this conversation and needs approval or an allow rule."
+                    );
+                    runtime_trace::record_event(
+                        "tool_call_result",
+                        Some(channel_name),
+                        Some(provider_name),
+                        Some(model),
+                        Some(&turn_id),
+                        Some(false),
+                        Some(&refused),
+                        serde_json::json!({
+                            "iteration": iteration + 1,
+                            "tool": tool_name.clone(),
+                            "arguments": scrub_credentials(&tool_args.to_string()),
+                        }),
+                    );
+                    ordered_results[idx] = Some((
+                        tool_name.clone(),
+                        call.tool_call_id.clone(),
+                        ToolExecutionOutcome {
+                            output: refused.clone(),
+                            success: false,
+                            error_reason: Some(refused),
+                            duration: Duration::ZERO,
+                        },
+                    ));
+                    continue;
+                }
+            }
             if let Some(mgr) = approval {
-                if mgr.needs_approval(&tool_name) {
+                if off_conversation.is_some() || mgr.needs_approval_for_call(&tool_name, &tool_args)
+                {
                     let request = ApprovalRequest {
                         tool_name: tool_name.clone(),
                         arguments: tool_args.clone(),
                     };
 
                     // Interactive CLI: prompt the operator.
-                    // Non-interactive (channels): auto-deny since no operator
-                    // is present to approve.
-                    let decision = if mgr.is_non_interactive() {
-                        ApprovalResponse::No
+                    // Non-interactive (channels): prompt in the chat when
+                    // channel approvals are enabled, otherwise auto-deny
+                    // since no operator is present to approve.
+                    let (decision, approver) = if mgr.is_non_interactive() {
+                        mgr.request_channel_approval(&request).await
                     } else {
-                        mgr.prompt_cli(&request)
+                        (mgr.prompt_cli(&request), None)
                     };
 
-                    mgr.record_decision(&tool_name, &tool_args, decision, channel_name);
+                    mgr.record_decision_by(
+                        &tool_name,
+                        &tool_args,
+                        decision,
+                        channel_name,
+                        approver.as_deref(),
+                    );
 
                     if decision == ApprovalResponse::No {
                         let denied = "Denied by user.".to_string();
@@ -3176,23 +3298,27 @@ pub(crate) async fn run_tool_call_loop(
         }
 
         let executed_outcomes = if allow_parallel_execution && executable_calls.len() > 1 {
-            execute_tools_parallel(
-                &executable_calls,
-                tools_registry,
-                activated_tools,
-                observer,
-                cancellation_token.as_ref(),
-            )
-            .await?
+            turn_span
+                .scope(execute_tools_parallel(
+                    &executable_calls,
+                    tools_registry,
+                    activated_tools,
+                    observer,
+                    tool_rules,
+                    cancellation_token.as_ref(),
+                ))
+                .await?
         } else {
-            execute_tools_sequential(
-                &executable_calls,
-                tools_registry,
-                activated_tools,
-                observer,
-                cancellation_token.as_ref(),
-            )
-            .await?
+            turn_span
+                .scope(execute_tools_sequential(
+                    &executable_calls,
+                    tools_registry,
+                    activated_tools,
+                    observer,
+                    tool_rules,
+                    cancellation_token.as_ref(),
+                ))
+                .await?
         };
 
         for ((idx, call), outcome) in executable_indices
@@ -3216,6 +3342,15 @@ pub(crate) async fn run_tool_call_loop(
                 }),
             );
 
+            if let Some(trust) = trust {
+                trust.record_tool_outcome(
+                    &trust_conversation,
+                    &call.name,
+                    outcome.success,
+                    outcome.error_reason.as_deref(),
+                );
+            }
+
             // ── Hook: after_tool_call (void) ─────────────────
             if let Some(hooks) = hooks {
                 let tool_result_obj = crate::tools::ToolResult {
@@ -3420,6 +3555,7 @@ pub(crate) async fn run_tool_call_loop(
     let summary_request = crate::providers::ChatRequest {
         messages: history,
         tools: None, // No tools — force a text response
+        response_format: None,
     };
     match provider.chat(summary_request, model, temperature).await {
         Ok(resp) => {
@@ -3491,14 +3627,16 @@ pub async fn run(
     allowed_tools: Option<Vec<String>>,
 ) -> Result<String> {
     // ── Wire up agnostic subsystems ──────────────────────────────
+    let trust = crate::trust::runtime::init(&config);
     let base_observer = observability::create_observer(&config.observability);
     let observer: Arc<dyn Observer> = Arc::from(base_observer);
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
+    let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(
+        runtime::create_runtime_with_observer(&config.runtime, Some(observer.clone()))?,
+    );
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_trust(trust.clone()),
+    );
 
     // ── Memory (the brain) ────────────────────────────────────────
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
@@ -3527,7 +3665,7 @@ pub async fn run(
     } else {
         (None, None)
     };
-    let sop_engine = crate::sop::create_sop_engine(&config.sop, &config.workspace_dir);
+    let sop_engine = crate::sop::shared_sop_engine(&config.sop, &config.workspace_dir);
     let (
         mut tools_registry,
         delegate_handle,
@@ -3584,6 +3722,8 @@ pub async fn run(
     // eagerly. Instead, a `tool_search` built-in is registered so the LLM can
     // fetch schemas on demand. This reduces context window waste.
     let mut deferred_section = String::new();
+    let mut mcp_context_section = String::new();
+    let mut mcp_registry: Option<std::sync::Arc<crate::tools::McpRegistry>> = None;
     let mut activated_handle: Option<
         std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>,
     > = None;
@@ -3592,7 +3732,7 @@ pub async fn run(
             "Initializing MCP client — {} server(s) configured",
             config.mcp.servers.len()
         );
-        match crate::tools::McpRegistry::connect_all(&config.mcp.servers).await {
+        match crate::tools::McpRegistry::connect_from_config(&config).await {
             Ok(registry) => {
                 let registry = std::sync::Arc::new(registry);
                 if config.mcp.deferred_loading {
@@ -3641,6 +3781,15 @@ pub async fn run(
                         registry.server_count()
                     );
                 }
+                let resource_servers = registry.resource_servers().await;
+                if !resource_servers.is_empty() {
+                    tools_registry.push(Box::new(crate::tools::McpResourcesTool::new(
+                        std::sync::Arc::clone(&registry),
+                        resource_servers,
+                    )));
+                }
+                mcp_context_section = registry.resource_context_section().await;
+                mcp_registry = Some(registry);
             }
             Err(e) => {
                 tracing::error!("MCP registry failed to initialize: {e:#}");
@@ -3661,7 +3810,8 @@ pub async fn run(
         .unwrap_or("anthropic/claude-sonnet-4")
         .to_string();
 
-    let provider_runtime_options = providers::provider_runtime_options_from_config(&config);
+    let provider_runtime_options = providers::provider_runtime_options_from_config(&config)
+        .with_budget_observer(observer.clone());
 
     let mut provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
         &provider_name,
@@ -3865,13 +4015,24 @@ pub async fn run(
         system_prompt.push_str(&deferred_section);
     }
 
+    // Append configured MCP context resources
+    if !mcp_context_section.is_empty() {
+        system_prompt.push_str(&mcp_context_section);
+    }
+
     // ── Approval manager (supervised mode) ───────────────────────
     let approval_manager = if interactive {
-        Some(ApprovalManager::from_config(&config.autonomy))
+        Some(
+            ApprovalManager::from_config(&config.autonomy)
+                .with_trust(trust)
+                .with_rules(Arc::clone(&security.tool_rules)),
+        )
     } else {
         None
     };
     let channel_name = if interactive { "cli" } else { "daemon" };
+    // Background processes belong to this run, not to a shared default session.
+    let process_session = format!("{channel_name}-{}", Uuid::new_v4());
     let memory_session_id = session_state_file.as_deref().and_then(|path| {
         let raw = path.to_string_lossy().trim().to_string();
         if raw.is_empty() {
@@ -3970,7 +4131,7 @@ pub async fn run(
         #[allow(unused_assignments)]
         let mut response = String::new();
         loop {
-            match run_tool_call_loop(
+            let turn = run_tool_call_loop(
                 provider.as_ref(),
                 &mut history,
                 &tools_registry,
@@ -3980,6 +4141,7 @@ pub async fn run(
                 effective_temperature,
                 false,
                 approval_manager.as_ref(),
+                Some(&security.tool_rules),
                 channel_name,
                 None,
                 &config.multimodal,
@@ -3995,8 +4157,10 @@ pub async fn run(
                 config.agent.max_tool_result_chars,
                 config.agent.max_context_tokens,
                 None, // shared_budget
-            )
-            .await
+            );
+            match crate::tools::process::PROCESS_SESSION
+                .scope(process_session.clone(), turn)
+                .await
             {
                 Ok(resp) => {
                     response = resp;
@@ -4103,7 +4267,20 @@ pub async fn run(
                     println!("  /help             Show this help message");
                     println!("  /clear /new       Clear conversation history");
                     println!("  /quit /exit       Exit interactive mode");
-                    println!("  /think:<level>    Set reasoning depth (off|minimal|low|medium|high|max)\n");
+                    println!(
+                        "  /think:<level>    Set reasoning depth (off|minimal|low|medium|high|max)"
+                    );
+                    let prompt_commands = match mcp_registry.as_deref() {
+                        Some(registry) => registry.prompt_commands().await,
+                        None => Vec::new(),
+                    };
+                    if !prompt_commands.is_empty() {
+                        println!("\nMCP prompts:");
+                        for (usage, description) in prompt_commands {
+                            println!("  {usage}  {description}");
+                        }
+                    }
+                    println!();
                     continue;
                 }
                 "/clear" | "/new" => {
@@ -4155,6 +4332,19 @@ pub async fn run(
                 _ => {}
             }
 
+            // ── Expand MCP prompt commands (`/<server>:<prompt> ...`) ───
+            let user_input = match mcp_registry.as_deref() {
+                Some(registry) => match registry.expand_prompt_command(&user_input).await {
+                    Some(Ok(expanded)) => expanded,
+                    Some(Err(e)) => {
+                        eprintln!("\nMCP prompt failed: {e}\n");
+                        continue;
+                    }
+                    None => user_input,
+                },
+                None => user_input,
+            };
+
             // ── Parse thinking directive from interactive input ───
             let (thinking_directive, effective_input) =
                 match crate::agent::thinking::parse_thinking_directive(&user_input) {
@@ -4269,7 +4459,7 @@ pub async fn run(
             });
 
             let response = loop {
-                match run_tool_call_loop(
+                let turn = run_tool_call_loop(
                     provider.as_ref(),
                     &mut history,
                     &tools_registry,
@@ -4279,6 +4469,7 @@ pub async fn run(
                     turn_temperature,
                     true,
                     approval_manager.as_ref(),
+                    Some(&security.tool_rules),
                     channel_name,
                     None,
                     &config.multimodal,
@@ -4294,8 +4485,10 @@ pub async fn run(
                     config.agent.max_tool_result_chars,
                     config.agent.max_context_tokens,
                     None, // shared_budget
-                )
-                .await
+                );
+                match crate::tools::process::PROCESS_SESSION
+                    .scope(process_session.clone(), turn)
+                    .await
                 {
                     Ok(resp) => break resp,
                     Err(e) => {
@@ -4464,15 +4657,19 @@ pub async fn process_message(
     message: &str,
     session_id: Option<&str>,
 ) -> Result<String> {
+    let trust = crate::trust::runtime::init(&config);
     let observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
-    let approval_manager = ApprovalManager::for_non_interactive(&config.autonomy);
+    let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(
+        runtime::create_runtime_with_observer(&config.runtime, Some(observer.clone()))?,
+    );
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_trust(trust.clone()),
+    );
+    let approval_manager = ApprovalManager::for_non_interactive(&config.autonomy)
+        .with_trust(trust)
+        .with_rules(Arc::clone(&security.tool_rules));
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
         &config.memory,
         &config.embedding_routes,
@@ -4489,7 +4686,7 @@ pub async fn process_message(
     } else {
         (None, None)
     };
-    let sop_engine = crate::sop::create_sop_engine(&config.sop, &config.workspace_dir);
+    let sop_engine = crate::sop::shared_sop_engine(&config.sop, &config.workspace_dir);
     if let Some(governed_case) = draft_incident_case(message) {
         tracing::info!(
             case_type = %governed_case.case_type,
@@ -4507,7 +4704,7 @@ pub async fn process_message(
                     governed_case.to_sop_event(message),
                 )
                 .await;
-                crate::sop::dispatch::process_headless_results(&results);
+                crate::sop::dispatch::process_headless_results(engine, &results);
                 Some(summarize_dispatch_results(&results))
             } else {
                 Some(disabled_sop_summary())
@@ -4562,6 +4759,7 @@ pub async fn process_message(
     // injected after filter_primary_agent_tools_or_fail (or equivalent built-in
     // tool allow/deny filtering) to avoid MCP tools being silently dropped.
     let mut deferred_section = String::new();
+    let mut mcp_context_section = String::new();
     let mut activated_handle_pm: Option<
         std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>,
     > = None;
@@ -4570,7 +4768,7 @@ pub async fn process_message(
             "Initializing MCP client — {} server(s) configured",
             config.mcp.servers.len()
         );
-        match crate::tools::McpRegistry::connect_all(&config.mcp.servers).await {
+        match crate::tools::McpRegistry::connect_from_config(&config).await {
             Ok(registry) => {
                 let registry = std::sync::Arc::new(registry);
                 if config.mcp.deferred_loading {
@@ -4617,6 +4815,14 @@ pub async fn process_message(
                         registry.server_count()
                     );
                 }
+                let resource_servers = registry.resource_servers().await;
+                if !resource_servers.is_empty() {
+                    tools_registry.push(Box::new(crate::tools::McpResourcesTool::new(
+                        std::sync::Arc::clone(&registry),
+                        resource_servers,
+                    )));
+                }
+                mcp_context_section = registry.resource_context_section().await;
             }
             Err(e) => {
                 tracing::error!("MCP registry failed to initialize: {e:#}");
@@ -4629,7 +4835,8 @@ pub async fn process_message(
         .default_model
         .clone()
         .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
-    let provider_runtime_options = providers::provider_runtime_options_from_config(&config);
+    let provider_runtime_options = providers::provider_runtime_options_from_config(&config)
+        .with_budget_observer(observer.clone());
     let provider: Box<dyn Provider> = providers::create_routed_provider_with_options(
         provider_name,
         config.api_key.as_deref(),
@@ -4763,6 +4970,11 @@ pub async fn process_message(
         system_prompt.push_str(&deferred_section);
     }
 
+    // Append configured MCP context resources
+    if !mcp_context_section.is_empty() {
+        system_prompt.push_str(&mcp_context_section);
+    }
+
     // ── Parse thinking directive from user message ─────────────
     let (thinking_directive, effective_message) =
         match crate::agent::thinking::parse_thinking_directive(message) {
@@ -4816,7 +5028,9 @@ pub async fn process_message(
         excluded_tools.extend(config.autonomy.non_cli_excluded_tools.iter().cloned());
     }
 
-    agent_turn(
+    let process_session =
+        session_id.map_or_else(|| format!("daemon-{}", Uuid::new_v4()), str::to_string);
+    let turn = agent_turn(
         provider.as_ref(),
         &mut history,
         &tools_registry,
@@ -4830,12 +5044,15 @@ pub async fn process_message(
         &config.multimodal,
         config.agent.max_tool_iterations,
         Some(&approval_manager),
+        Some(&security.tool_rules),
         &excluded_tools,
         &config.agent.tool_call_dedup_exempt,
         activated_handle_pm.as_ref(),
         None,
-    )
-    .await
+    );
+    crate::tools::process::PROCESS_SESSION
+        .scope(process_session, turn)
+        .await
 }
 
 #[cfg(test)]
@@ -5345,8 +5562,16 @@ mod tests {
             .expect("should produce a sample whose byte index 300 is not a char boundary");
 
         let observer = NoopObserver;
-        let result =
-            execute_one_tool("unknown_tool", call_arguments, &[], None, &observer, None).await;
+        let result = execute_one_tool(
+            "unknown_tool",
+            call_arguments,
+            &[],
+            None,
+            &observer,
+            None,
+            None,
+        )
+        .await;
         assert!(result.is_ok(), "execute_one_tool should not panic or error");
 
         let outcome = result.unwrap();
@@ -5375,6 +5600,7 @@ mod tests {
             Some(&activated),
             &observer,
             None,
+            None,
         )
         .await
         .expect("suffix alias should execute the unique activated tool");
@@ -5420,6 +5646,7 @@ mod tests {
                 native_tool_calling: false,
                 vision: true,
                 prompt_caching: false,
+                structured_output: false,
             }
         }
 
@@ -5624,6 +5851,7 @@ mod tests {
                 native_tool_calling: true,
                 vision: false,
                 prompt_caching: false,
+                structured_output: false,
             }
         }
 
@@ -6004,6 +6232,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -6059,6 +6288,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &multimodal,
@@ -6107,6 +6337,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -6155,6 +6386,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -6210,6 +6442,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &multimodal,
@@ -6265,6 +6498,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &multimodal,
@@ -6321,6 +6555,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &multimodal,
@@ -6375,6 +6610,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &multimodal,
@@ -6429,6 +6665,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &multimodal,
@@ -6566,6 +6803,7 @@ mod tests {
             0.0,
             true,
             Some(&approval_mgr),
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -6640,6 +6878,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "telegram",
             Some("chat-42"),
             &crate::config::MultimodalConfig::default(),
@@ -6706,6 +6945,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "telegram",
             Some("chat-42"),
             &crate::config::MultimodalConfig::default(),
@@ -6767,6 +7007,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -6840,6 +7081,7 @@ mod tests {
             0.0,
             true,
             Some(&approval_mgr),
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -6904,6 +7146,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -6988,6 +7231,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -7049,6 +7293,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "cli",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -7134,6 +7379,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -7203,6 +7449,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -7274,6 +7521,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -7349,6 +7597,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -7410,6 +7659,7 @@ mod tests {
                 Route {
                     provider_name: "fast".to_string(),
                     model: "routed-model".to_string(),
+                    context_window: None,
                 },
             )],
             "default-model".to_string(),
@@ -7433,6 +7683,7 @@ mod tests {
             0.0,
             true,
             None,
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -7530,6 +7781,7 @@ mod tests {
                 &crate::config::MultimodalConfig::default(),
                 4,
                 None,
+                None,
                 &[],
                 &[],
                 Some(&activated),
@@ -9444,6 +9696,7 @@ Let me check the result."#;
             0.0,
             true,
             None,
+            None,
             "telegram",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -9579,6 +9832,7 @@ Let me check the result."#;
             ModelPricing {
                 input: 3.0,
                 output: 15.0,
+                cached_input: None,
             },
         )]);
         let tracker = Arc::new(CostTracker::new(cost_config.clone(), workspace.path()).unwrap());
@@ -9601,6 +9855,7 @@ Let me check the result."#;
                     0.0,
                     true,
                     None,
+                    None,
                     "test",
                     None,
                     &crate::config::MultimodalConfig::default(),
@@ -9665,6 +9920,7 @@ Let me check the result."#;
                 ModelPricing {
                     input: 1.0,
                     output: 1.0,
+                    cached_input: None,
                 },
             )])),
         );
@@ -9683,6 +9939,7 @@ Let me check the result."#;
                     0.0,
                     true,
                     None,
+                    None,
                     "test",
                     None,
                     &crate::config::MultimodalConfig::default(),
@@ -9741,6 +9998,7 @@ Let me check the result."#;
             0.0,
             true,
             None,
+            None,
             "test",
             None,
             &crate::config::MultimodalConfig::default(),
@@ -9762,4 +10020,33 @@ Let me check the result."#;
 
         assert_eq!(result, "ok");
     }
+
+    #[test]
+    fn file_delivery_defaults_target_current_conversation() {
+        let mut args = serde_json::json!({ "prompt": "a cat", "deliver": true });
+        maybe_inject_channel_delivery_defaults("image_gen", &mut args, "telegram", Some("42"));
+        assert_eq!(args["channel"], "telegram");
+        assert_eq!(args["recipient"], "42");
+        let target = |tool: &str, args: &serde_json::Value| {
+            crate::tools::file_delivery::off_conversation_target(
+                None,
+                tool,
+                args,
+                "telegram",
+                Some("42"),
+            )
+        };
+        assert_eq!(target("image_gen", &args), None);
+
+        let mut args = serde_json::json!({ "prompt": "a cat" });
+        maybe_inject_channel_delivery_defaults("image_gen", &mut args, "telegram", Some("42"));
+        assert!(args.get("channel").is_none());
+
+        let mut args =
+            serde_json::json!({ "deliver": true, "channel": "slack", "recipient": "C1" });
+        maybe_inject_channel_delivery_defaults("screenshot", &mut args, "telegram", Some("42"));
+        assert_eq!(args["channel"], "slack");
+        assert_eq!(args["recipient"], "C1");
+        assert_eq!(target("screenshot", &args).as_deref(), Some("slack:C1"));
+    }
 }
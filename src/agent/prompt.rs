@@ -88,6 +88,9 @@ pub struct RuntimeSection;
 pub struct DateTimeSection;
 pub struct ChannelMediaSection;
 
+/// Pre-rendered `context_resources` from connected MCP servers.
+pub struct McpResourcesSection(pub String);
+
 impl PromptSection for IdentitySection {
     fn name(&self) -> &str {
         "identity"
@@ -296,6 +299,16 @@ impl PromptSection for ChannelMediaSection {
     }
 }
 
+impl PromptSection for McpResourcesSection {
+    fn name(&self) -> &str {
+        "mcp_resources"
+    }
+
+    fn build(&self, _ctx: &PromptContext<'_>) -> Result<String> {
+        Ok(self.0.clone())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -509,6 +522,31 @@ mod tests {
         assert!(payload.contains("Time:"));
     }
 
+    #[test]
+    fn prompt_builder_appends_mcp_resources_section() {
+        let tools: Vec<Box<dyn Tool>> = vec![];
+        let ctx = PromptContext {
+            workspace_dir: Path::new("/tmp"),
+            model_name: "test-model",
+            tools: &tools,
+            skills: &[],
+            skills_prompt_mode: crate::config::SkillsPromptInjectionMode::Full,
+            identity_config: None,
+            dispatcher_instructions: "",
+            tool_descriptions: None,
+            security_summary: None,
+            autonomy_level: AutonomyLevel::Supervised,
+        };
+
+        let section = "\n## MCP Resources\n\n### docs: file:///readme\n\nhello\n";
+        let prompt = SystemPromptBuilder::with_defaults()
+            .add_section(Box::new(McpResourcesSection(section.into())))
+            .build(&ctx)
+            .unwrap();
+        assert!(prompt.trim_end().ends_with("hello"));
+        assert!(prompt.contains("## MCP Resources"));
+    }
+
     #[test]
     fn prompt_builder_inlines_and_escapes_skills() {
         let tools: Vec<Box<dyn Tool>> = vec![];
@@ -1362,3 +1362,66 @@ async fn run_single_delegates_to_turn() {
         "Expected non-empty response from run_single"
     );
 }
+
+// ═══════════════════════════════════════════════════════════════════════════
+// 26. Tool calls run in the agent's own process session
+// ═══════════════════════════════════════════════════════════════════════════
+
+/// A tool that records the process session it ran in.
+struct SessionProbeTool {
+    seen: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Tool for SessionProbeTool {
+    fn name(&self) -> &str {
+        "session_probe"
+    }
+
+    fn description(&self) -> &str {
+        "Reports the process session"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        serde_json::json!({"type": "object"})
+    }
+
+    async fn execute(&self, _args: serde_json::Value) -> Result<ToolResult> {
+        let session = crate::tools::process::PROCESS_SESSION
+            .try_with(Clone::clone)
+            .unwrap_or_default();
+        self.seen.lock().unwrap().push(session);
+        Ok(ToolResult {
+            success: true,
+            output: "ok".into(),
+            error: None,
+        })
+    }
+}
+
+#[tokio::test]
+async fn tool_calls_run_in_a_per_agent_process_session() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    for _ in 0..2 {
+        let provider = Box::new(ScriptedProvider::new(vec![
+            tool_response(vec![ToolCall {
+                id: "tc1".into(),
+                name: "session_probe".into(),
+                arguments: "{}".into(),
+            }]),
+            text_response("done"),
+        ]));
+        let tool = SessionProbeTool { seen: seen.clone() };
+        let mut agent = build_agent_with(
+            provider,
+            vec![Box::new(tool)],
+            Box::new(NativeToolDispatcher),
+        );
+        agent.turn("probe").await.unwrap();
+    }
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 2);
+    assert!(seen.iter().all(|session| session.starts_with("agent-")));
+    assert_ne!(seen[0], seen[1]);
+}
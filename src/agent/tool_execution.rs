@@ -9,7 +9,10 @@ use std::time::{Duration, Instant};
 use tokio_util::sync::CancellationToken;
 
 use crate::approval::ApprovalManager;
+use crate::config::ToolRuleAction;
+use crate::observability::trace_context::{SpanKind, TraceSpan};
 use crate::observability::{Observer, ObserverEvent};
+use crate::security::tool_rules::ToolPolicy;
 use crate::tools::Tool;
 use crate::util::truncate_with_ellipsis;
 
@@ -23,6 +26,17 @@ pub(crate) fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'
     tools.iter().find(|t| t.name() == name).map(|t| t.as_ref())
 }
 
+/// Refusal message when a `deny` rule in `[[autonomy.rules]]` matches the call.
+pub(crate) fn policy_denial(
+    rules: &ToolPolicy,
+    call_name: &str,
+    args: &serde_json::Value,
+) -> Option<String> {
+    let decision = rules.evaluate(call_name, args)?;
+    (decision.action == ToolRuleAction::Deny)
+        .then(|| format!("Denied by policy: {}", decision.reason))
+}
+
 // ── Outcome ──────────────────────────────────────────────────────────────
 
 pub(crate) struct ToolExecutionOutcome {
@@ -40,6 +54,7 @@ pub(crate) async fn execute_one_tool(
     tools_registry: &[Box<dyn Tool>],
     activated_tools: Option<&std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>>,
     observer: &dyn Observer,
+    tool_rules: Option<&ToolPolicy>,
     cancellation_token: Option<&CancellationToken>,
 ) -> Result<ToolExecutionOutcome> {
     let args_summary = truncate_with_ellipsis(&call_arguments.to_string(), 300);
@@ -48,6 +63,27 @@ pub(crate) async fn execute_one_tool(
         arguments: Some(args_summary),
     });
     let start = Instant::now();
+    let mut span = TraceSpan::start(format!("execute_tool {call_name}"), SpanKind::Internal)
+        .with_attribute("gen_ai.operation.name", "execute_tool")
+        .with_attribute("gen_ai.tool.name", call_name);
+
+    if let Some(reason) =
+        tool_rules.and_then(|rules| policy_denial(rules, call_name, &call_arguments))
+    {
+        span.set_error(reason.as_str());
+        let duration = start.elapsed();
+        observer.record_event(&ObserverEvent::ToolCall {
+            tool: call_name.to_string(),
+            duration,
+            success: false,
+        });
+        return Ok(ToolExecutionOutcome {
+            output: reason.clone(),
+            success: false,
+            error_reason: Some(reason),
+            duration,
+        });
+    }
 
     let static_tool = find_tool(tools_registry, call_name);
     let activated_arc = if static_tool.is_none() {
@@ -57,6 +93,7 @@ pub(crate) async fn execute_one_tool(
     };
     let Some(tool) = static_tool.or(activated_arc.as_deref()) else {
         let reason = format!("Unknown tool: {call_name}");
+        span.set_error(reason.as_str());
         let duration = start.elapsed();
         observer.record_event(&ObserverEvent::ToolCall {
             tool: call_name.to_string(),
@@ -71,7 +108,8 @@ pub(crate) async fn execute_one_tool(
         });
     };
 
-    let tool_future = tool.execute(call_arguments);
+    // Sub-agents and outgoing requests made by the tool nest under its span.
+    let tool_future = span.scope(tool.execute(call_arguments));
     let tool_result = if let Some(token) = cancellation_token {
         tokio::select! {
             () = token.cancelled() => return Err(ToolLoopCancelled.into()),
@@ -98,6 +136,7 @@ pub(crate) async fn execute_one_tool(
                 })
             } else {
                 let reason = r.error.unwrap_or(r.output);
+                span.set_error(scrub_credentials(&reason));
                 Ok(ToolExecutionOutcome {
                     output: format!("Error: {reason}"),
                     success: false,
@@ -114,6 +153,7 @@ pub(crate) async fn execute_one_tool(
                 success: false,
             });
             let reason = format!("Error executing {call_name}: {e}");
+            span.set_error(scrub_credentials(&reason));
             Ok(ToolExecutionOutcome {
                 output: reason.clone(),
                 success: false,
@@ -143,7 +183,10 @@ pub(crate) fn should_execute_tools_in_parallel(
     }
 
     if let Some(mgr) = approval {
-        if tool_calls.iter().any(|call| mgr.needs_approval(&call.name)) {
+        if tool_calls
+            .iter()
+            .any(|call| mgr.needs_approval_for_call(&call.name, &call.arguments))
+        {
             // Approval-gated calls must keep sequential handling so the caller can
             // enforce CLI prompt/deny policy consistently.
             return false;
@@ -160,6 +203,7 @@ pub(crate) async fn execute_tools_parallel(
     tools_registry: &[Box<dyn Tool>],
     activated_tools: Option<&std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>>,
     observer: &dyn Observer,
+    tool_rules: Option<&ToolPolicy>,
     cancellation_token: Option<&CancellationToken>,
 ) -> Result<Vec<ToolExecutionOutcome>> {
     let futures: Vec<_> = tool_calls
@@ -171,6 +215,7 @@ pub(crate) async fn execute_tools_parallel(
                 tools_registry,
                 activated_tools,
                 observer,
+                tool_rules,
                 cancellation_token,
             )
         })
@@ -187,6 +232,7 @@ pub(crate) async fn execute_tools_sequential(
     tools_registry: &[Box<dyn Tool>],
     activated_tools: Option<&std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>>,
     observer: &dyn Observer,
+    tool_rules: Option<&ToolPolicy>,
     cancellation_token: Option<&CancellationToken>,
 ) -> Result<Vec<ToolExecutionOutcome>> {
     let mut outcomes = Vec::with_capacity(tool_calls.len());
@@ -199,6 +245,7 @@ pub(crate) async fn execute_tools_sequential(
                 tools_registry,
                 activated_tools,
                 observer,
+                tool_rules,
                 cancellation_token,
             )
             .await?,
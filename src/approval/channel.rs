@@ -0,0 +1,638 @@
+//! Approval prompts routed to chat channels.
+//!
+//! Channel-driven runs have no stdin to prompt on. When
+//! `[autonomy.channel_approval]` is enabled, the tool-call loop instead sends
+//! an [`ApprovalPrompt`] to the originating channel (or the configured
+//! operator channel) and waits for an answer. Answers arrive as ordinary
+//! inbound messages — keyword replies or button clicks translated by the
+//! channel into `/approve <id>`, `/deny <id>` or `/always <id>` — and are
+//! claimed by the channel dispatch loop through
+//! [`ApprovalManager::resolve_channel_reply`] before normal routing.
+
+use super::{summarize_args, ApprovalManager, ApprovalRequest, ApprovalResponse};
+use crate::channels::traits::ChannelMessage;
+use crate::channels::{ApprovalPrompt, Channel, SendMessage};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+tokio::task_local! {
+    /// Where approval prompts for the current channel turn are sent.
+    pub static CHANNEL_APPROVAL_CONTEXT: Option<ChannelApprovalContext>;
+}
+
+/// Destination and requester of approval prompts for one channel turn.
+#[derive(Clone)]
+pub struct ChannelApprovalContext {
+    /// Channel the prompt is sent on.
+    pub channel: Arc<dyn Channel>,
+    /// Name inbound answers must carry (`ChannelMessage::channel`).
+    pub channel_name: String,
+    pub reply_target: String,
+    pub thread_ts: Option<String>,
+    /// Sender whose message triggered the turn.
+    pub requester: String,
+    /// Channel of the conversation the turn belongs to, which differs from
+    /// `channel_name` when prompts go to an operator channel.
+    pub origin_channel: String,
+    /// Reply target of the conversation the turn belongs to.
+    pub origin_reply_target: String,
+}
+
+impl ChannelApprovalContext {
+    /// Key under which "Always" answers given for this turn are remembered,
+    /// so they only apply to the same conversation and requester.
+    pub(super) fn allowlist_scope(&self) -> String {
+        format!(
+            "{}\u{1f}{}\u{1f}{}",
+            self.origin_channel, self.origin_reply_target, self.requester
+        )
+    }
+}
+
+/// An approval request waiting for an answer.
+pub(super) struct PendingChannelApproval {
+    channel_name: String,
+    reply_target: String,
+    requester: String,
+    tool_name: String,
+    responder: oneshot::Sender<ChannelApprovalAnswer>,
+}
+
+pub(super) type PendingChannelApprovals = HashMap<String, PendingChannelApproval>;
+
+struct ChannelApprovalAnswer {
+    decision: ApprovalResponse,
+    approver: String,
+}
+
+/// What happened to an inbound message offered to the pending approvals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalReplyOutcome {
+    /// Not an answer to a pending approval; route the message normally.
+    NotAnApprovalReply,
+    /// The answer was accepted and handed to the waiting tool call.
+    Resolved {
+        request_id: String,
+        tool_name: String,
+        decision: ApprovalResponse,
+    },
+    /// The sender may not answer this request.
+    Unauthorized { request_id: String },
+    /// The referenced request does not exist or has already been answered.
+    UnknownRequest { request_id: String },
+    /// A bare keyword matched several pending requests in this conversation.
+    Ambiguous,
+}
+
+impl ApprovalReplyOutcome {
+    /// Acknowledgement to post back to the conversation, if any.
+    pub fn acknowledgement(&self) -> Option<String> {
+        match self {
+            Self::NotAnApprovalReply => None,
+            Self::Resolved {
+                request_id,
+                tool_name,
+                decision,
+            } => Some(match decision {
+                ApprovalResponse::Yes => format!("✅ Approved `{tool_name}` ({request_id})."),
+                ApprovalResponse::Always => {
+                    format!("✅ Approved `{tool_name}` ({request_id}) for the rest of this chat.")
+                }
+                ApprovalResponse::No => format!("🚫 Denied `{tool_name}` ({request_id})."),
+            }),
+            Self::Unauthorized { request_id } => Some(format!(
+                "⛔ You are not allowed to answer approval request {request_id}."
+            )),
+            Self::UnknownRequest { request_id } => Some(format!(
+                "No pending approval request {request_id}; it may have expired or been answered."
+            )),
+            Self::Ambiguous => Some(
+                "Several approvals are pending here; reply with the request ID, e.g. `/approve <id>`."
+                    .to_string(),
+            ),
+        }
+    }
+}
+
+/// Parse an approval answer: `approve|yes`, `deny|no` or `always`, with an
+/// optional leading `/`, `@bot` suffix and request ID.
+pub fn parse_approval_reply(content: &str) -> Option<(ApprovalResponse, Option<String>)> {
+    let mut words = content.split_whitespace();
+    let verb = words.next()?;
+    let request_id = words.next().map(str::to_ascii_lowercase);
+    if words.next().is_some() {
+        return None;
+    }
+
+    let verb = verb.strip_prefix('/').unwrap_or(verb);
+    let verb = verb.split('@').next().unwrap_or(verb).to_ascii_lowercase();
+    let decision = match verb.as_str() {
+        "approve" | "yes" => ApprovalResponse::Yes,
+        "deny" | "no" => ApprovalResponse::No,
+        "always" => ApprovalResponse::Always,
+        _ => return None,
+    };
+    Some((decision, request_id))
+}
+
+/// Removes a pending request when the waiting tool call finishes or is
+/// cancelled, so stale IDs never linger.
+struct PendingGuard<'a> {
+    manager: &'a ApprovalManager,
+    request_id: &'a str,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        self.manager.pending.lock().remove(self.request_id);
+    }
+}
+
+impl ApprovalManager {
+    /// Whether channel runs prompt for approval instead of auto-denying.
+    pub fn channel_approvals_enabled(&self) -> bool {
+        self.non_interactive && self.channel_approval.enabled
+    }
+
+    /// How long a channel approval prompt waits for an answer.
+    pub fn channel_approval_timeout_secs(&self) -> u64 {
+        self.channel_approval.timeout_secs
+    }
+
+    /// Configured operator channel and recipient for approval prompts.
+    pub fn approval_operator(&self) -> Option<(&str, &str)> {
+        Some((
+            self.channel_approval.operator_channel.as_deref()?,
+            self.channel_approval.operator_target.as_deref()?,
+        ))
+    }
+
+    /// Ask for approval on the current turn's channel and wait for an answer.
+    ///
+    /// Returns the decision and the identity of the approver. Without an
+    /// approval context (or with channel approvals disabled) the call is
+    /// denied, and so is a prompt that cannot be delivered or times out.
+    pub async fn request_channel_approval(
+        &self,
+        request: &ApprovalRequest,
+    ) -> (ApprovalResponse, Option<String>) {
+        if !self.channel_approvals_enabled() {
+            return (ApprovalResponse::No, None);
+        }
+        let Ok(Some(context)) = CHANNEL_APPROVAL_CONTEXT.try_with(Clone::clone) else {
+            return (ApprovalResponse::No, None);
+        };
+
+        let request_id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let (responder, answer) = oneshot::channel();
+        self.pending.lock().insert(
+            request_id.clone(),
+            PendingChannelApproval {
+                channel_name: context.channel_name.clone(),
+                reply_target: context.reply_target.clone(),
+                requester: context.requester.clone(),
+                tool_name: request.tool_name.clone(),
+                responder,
+            },
+        );
+        let _guard = PendingGuard {
+            manager: self,
+            request_id: &request_id,
+        };
+
+        let prompt = ApprovalPrompt {
+            request_id: request_id.clone(),
+            tool_name: request.tool_name.clone(),
+            arguments_summary: summarize_args(&request.arguments),
+            requester: context.requester.clone(),
+            timeout_secs: self.channel_approval.timeout_secs,
+        };
+        if let Err(e) = context
+            .channel
+            .send_approval_prompt(&context.reply_target, context.thread_ts.as_deref(), &prompt)
+            .await
+        {
+            tracing::warn!(
+                channel = %context.channel_name,
+                tool = %request.tool_name,
+                "Failed to send approval prompt: {e}"
+            );
+            return (ApprovalResponse::No, None);
+        }
+
+        let timeout = Duration::from_secs(self.channel_approval.timeout_secs);
+        match tokio::time::timeout(timeout, answer).await {
+            Ok(Ok(answer)) => {
+                tracing::info!(
+                    request_id = %request_id,
+                    tool = %request.tool_name,
+                    approver = %answer.approver,
+                    decision = ?answer.decision,
+                    "Channel approval answered"
+                );
+                (answer.decision, Some(answer.approver))
+            }
+            _ => {
+                let notice = format!(
+                    "⏱ Approval request {request_id} for `{}` expired; the call was denied.",
+                    request.tool_name
+                );
+                let _ = context
+                    .channel
+                    .send(
+                        &SendMessage::new(notice, &context.reply_target)
+                            .in_thread(context.thread_ts.clone()),
+                    )
+                    .await;
+                (ApprovalResponse::No, None)
+            }
+        }
+    }
+
+    /// Offer an inbound channel message to the pending approval requests.
+    ///
+    /// Answers naming a request ID must come from the prompt's channel and an
+    /// authorized approver. A second word only counts as a request ID when the
+    /// answer is a `/command` or the ID is pending, so "no thanks" stays an
+    /// ordinary message. Bare keywords (`yes`, `/deny`, …) only match when
+    /// exactly one request is pending in the same conversation and the sender
+    /// may answer it; otherwise the message is routed normally.
+    pub fn resolve_channel_reply(&self, msg: &ChannelMessage) -> ApprovalReplyOutcome {
+        let Some((decision, request_id)) = parse_approval_reply(&msg.content) else {
+            return ApprovalReplyOutcome::NotAnApprovalReply;
+        };
+        let is_command = msg.content.trim_start().starts_with('/');
+
+        let mut pending = self.pending.lock();
+        let request_id = match request_id {
+            Some(request_id) => match pending.get(&request_id) {
+                Some(entry) if entry.channel_name == msg.channel => {
+                    if !self.may_answer(entry, &msg.sender) {
+                        return ApprovalReplyOutcome::Unauthorized { request_id };
+                    }
+                    request_id
+                }
+                _ if is_command => return ApprovalReplyOutcome::UnknownRequest { request_id },
+                _ => return ApprovalReplyOutcome::NotAnApprovalReply,
+            },
+            None => {
+                let candidates: Vec<&String> = pending
+                    .iter()
+                    .filter(|(_, entry)| {
+                        entry.channel_name == msg.channel
+                            && entry.reply_target == msg.reply_target
+                            && self.may_answer(entry, &msg.sender)
+                    })
+                    .map(|(id, _)| id)
+                    .collect();
+                match candidates.as_slice() {
+                    [] => return ApprovalReplyOutcome::NotAnApprovalReply,
+                    [id] => (*id).clone(),
+                    _ => return ApprovalReplyOutcome::Ambiguous,
+                }
+            }
+        };
+
+        let Some(entry) = pending.remove(&request_id) else {
+            return ApprovalReplyOutcome::UnknownRequest { request_id };
+        };
+        let tool_name = entry.tool_name.clone();
+        if entry
+            .responder
+            .send(ChannelApprovalAnswer {
+                decision,
+                approver: msg.sender.clone(),
+            })
+            .is_err()
+        {
+            return ApprovalReplyOutcome::UnknownRequest { request_id };
+        }
+        ApprovalReplyOutcome::Resolved {
+            request_id,
+            tool_name,
+            decision,
+        }
+    }
+
+    fn may_answer(&self, entry: &PendingChannelApproval, sender: &str) -> bool {
+        let approvers = &self.channel_approval.approvers;
+        if approvers.is_empty() {
+            return entry.requester == sender;
+        }
+        approvers
+            .iter()
+            .any(|approver| approver == "*" || approver == sender)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AutonomyConfig, ChannelApprovalConfig};
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct PromptRecorder {
+        prompts: Mutex<Vec<ApprovalPrompt>>,
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Channel for PromptRecorder {
+        fn name(&self) -> &str {
+            "slack"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.sent.lock().push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn send_approval_prompt(
+            &self,
+            _recipient: &str,
+            _thread_ts: Option<&str>,
+            prompt: &ApprovalPrompt,
+        ) -> anyhow::Result<()> {
+            self.prompts.lock().push(prompt.clone());
+            Ok(())
+        }
+    }
+
+    fn manager(approvers: Vec<String>, timeout_secs: u64) -> Arc<ApprovalManager> {
+        let config = AutonomyConfig {
+            channel_approval: ChannelApprovalConfig {
+                enabled: true,
+                timeout_secs,
+                approvers,
+                ..ChannelApprovalConfig::default()
+            },
+            ..AutonomyConfig::default()
+        };
+        Arc::new(ApprovalManager::for_non_interactive(&config))
+    }
+
+    fn context(channel: Arc<PromptRecorder>) -> Option<ChannelApprovalContext> {
+        Some(ChannelApprovalContext {
+            channel,
+            channel_name: "slack".into(),
+            reply_target: "C1".into(),
+            thread_ts: None,
+            requester: "alice".into(),
+            origin_channel: "slack".into(),
+            origin_reply_target: "C1".into(),
+        })
+    }
+
+    fn reply(sender: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "m1".into(),
+            sender: sender.into(),
+            reply_target: "C1".into(),
+            content: content.into(),
+            channel: "slack".into(),
+            timestamp: 0,
+            thread_ts: None,
+            interruption_scope_id: None,
+            attachments: vec![],
+        }
+    }
+
+    fn shell_request() -> ApprovalRequest {
+        ApprovalRequest {
+            tool_name: "shell".into(),
+            arguments: serde_json::json!({"command": "make deploy"}),
+        }
+    }
+
+    /// Wait until the prompt has been sent and return its request ID.
+    async fn wait_for_prompt(channel: &PromptRecorder) -> String {
+        for _ in 0..100 {
+            if let Some(prompt) = channel.prompts.lock().first() {
+                return prompt.request_id.clone();
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("approval prompt was never sent");
+    }
+
+    #[tokio::test]
+    async fn always_answer_only_applies_to_its_conversation() {
+        let mgr = manager(vec![], 30);
+        let channel = Arc::new(PromptRecorder::default());
+        let alice = context(Arc::clone(&channel));
+        let bob = alice.clone().map(|context| ChannelApprovalContext {
+            requester: "bob".into(),
+            ..context
+        });
+
+        CHANNEL_APPROVAL_CONTEXT
+            .scope(alice.clone(), async {
+                mgr.record_decision(
+                    "file_write",
+                    &serde_json::json!({}),
+                    ApprovalResponse::Always,
+                    "slack",
+                );
+                assert!(!mgr.needs_approval("file_write"));
+            })
+            .await;
+        CHANNEL_APPROVAL_CONTEXT
+            .scope(bob, async { assert!(mgr.needs_approval("file_write")) })
+            .await;
+        assert!(mgr.needs_approval("file_write"));
+    }
+
+    #[tokio::test]
+    async fn always_answer_via_operator_is_scoped_to_originating_chat() {
+        let mgr = manager(vec![], 30);
+        let channel = Arc::new(PromptRecorder::default());
+        let operator = |origin_reply_target: &str| {
+            context(Arc::clone(&channel)).map(|context| ChannelApprovalContext {
+                reply_target: "ops".into(),
+                origin_reply_target: origin_reply_target.into(),
+                ..context
+            })
+        };
+
+        CHANNEL_APPROVAL_CONTEXT
+            .scope(operator("C1"), async {
+                mgr.record_decision(
+                    "file_write",
+                    &serde_json::json!({}),
+                    ApprovalResponse::Always,
+                    "slack",
+                );
+                assert!(!mgr.needs_approval("file_write"));
+            })
+            .await;
+        CHANNEL_APPROVAL_CONTEXT
+            .scope(operator("C2"), async {
+                assert!(mgr.needs_approval("file_write"));
+            })
+            .await;
+    }
+
+    #[test]
+    fn parse_approval_reply_accepts_keywords_and_commands() {
+        assert_eq!(
+            parse_approval_reply("/approve AB12cd34"),
+            Some((ApprovalResponse::Yes, Some("ab12cd34".into())))
+        );
+        assert_eq!(
+            parse_approval_reply("/always@zeroclaw_bot ab12cd34"),
+            Some((ApprovalResponse::Always, Some("ab12cd34".into())))
+        );
+        assert_eq!(
+            parse_approval_reply("no"),
+            Some((ApprovalResponse::No, None))
+        );
+        assert_eq!(parse_approval_reply("yes please do it"), None);
+        assert_eq!(parse_approval_reply("deploy it"), None);
+    }
+
+    #[tokio::test]
+    async fn prompt_is_answered_by_requester() {
+        let mgr = manager(vec![], 30);
+        let channel = Arc::new(PromptRecorder::default());
+        let waiter = {
+            let mgr = Arc::clone(&mgr);
+            let channel = Arc::clone(&channel);
+            tokio::spawn(
+                CHANNEL_APPROVAL_CONTEXT.scope(context(channel), async move {
+                    mgr.request_channel_approval(&shell_request()).await
+                }),
+            )
+        };
+
+        let id = wait_for_prompt(&channel).await;
+        assert_eq!(
+            mgr.resolve_channel_reply(&reply("mallory", &format!("/approve {id}"))),
+            ApprovalReplyOutcome::Unauthorized {
+                request_id: id.clone()
+            }
+        );
+        // Bare keywords from other users are ordinary messages.
+        assert_eq!(
+            mgr.resolve_channel_reply(&reply("mallory", "yes")),
+            ApprovalReplyOutcome::NotAnApprovalReply
+        );
+        assert!(matches!(
+            mgr.resolve_channel_reply(&reply("alice", "always")),
+            ApprovalReplyOutcome::Resolved {
+                decision: ApprovalResponse::Always,
+                ..
+            }
+        ));
+
+        let (decision, approver) = waiter.await.unwrap();
+        assert_eq!(decision, ApprovalResponse::Always);
+        assert_eq!(approver.as_deref(), Some("alice"));
+        assert!(mgr.pending.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn keyword_with_trailing_word_is_not_taken_as_request_id() {
+        let mgr = manager(vec![], 30);
+        let channel = Arc::new(PromptRecorder::default());
+        let waiter = {
+            let mgr = Arc::clone(&mgr);
+            let channel = Arc::clone(&channel);
+            tokio::spawn(
+                CHANNEL_APPROVAL_CONTEXT.scope(context(channel), async move {
+                    mgr.request_channel_approval(&shell_request()).await
+                }),
+            )
+        };
+        let id = wait_for_prompt(&channel).await;
+
+        // A message in another chat while this one waits.
+        let mut elsewhere = reply("bob", "no thanks");
+        elsewhere.reply_target = "C2".into();
+        assert_eq!(
+            mgr.resolve_channel_reply(&elsewhere),
+            ApprovalReplyOutcome::NotAnApprovalReply
+        );
+        assert_eq!(
+            mgr.resolve_channel_reply(&reply("alice", "/deny thanks")),
+            ApprovalReplyOutcome::UnknownRequest {
+                request_id: "thanks".into()
+            }
+        );
+        assert!(matches!(
+            mgr.resolve_channel_reply(&reply("alice", &format!("yes {id}"))),
+            ApprovalReplyOutcome::Resolved {
+                decision: ApprovalResponse::Yes,
+                ..
+            }
+        ));
+        assert_eq!(waiter.await.unwrap().0, ApprovalResponse::Yes);
+    }
+
+    #[tokio::test]
+    async fn configured_approvers_replace_requester() {
+        let mgr = manager(vec!["ops-lead".into()], 30);
+        let channel = Arc::new(PromptRecorder::default());
+        let waiter = {
+            let mgr = Arc::clone(&mgr);
+            let channel = Arc::clone(&channel);
+            tokio::spawn(
+                CHANNEL_APPROVAL_CONTEXT.scope(context(channel), async move {
+                    mgr.request_channel_approval(&shell_request()).await
+                }),
+            )
+        };
+
+        let id = wait_for_prompt(&channel).await;
+        assert!(matches!(
+            mgr.resolve_channel_reply(&reply("alice", &format!("/approve {id}"))),
+            ApprovalReplyOutcome::Unauthorized { .. }
+        ));
+        assert!(matches!(
+            mgr.resolve_channel_reply(&reply("ops-lead", &format!("/deny {id}"))),
+            ApprovalReplyOutcome::Resolved {
+                decision: ApprovalResponse::No,
+                ..
+            }
+        ));
+        assert_eq!(
+            waiter.await.unwrap(),
+            (ApprovalResponse::No, Some("ops-lead".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn unanswered_prompt_times_out_as_denied() {
+        let mgr = manager(vec![], 0);
+        let channel = Arc::new(PromptRecorder::default());
+        let (decision, approver) = CHANNEL_APPROVAL_CONTEXT
+            .scope(context(Arc::clone(&channel)), async {
+                mgr.request_channel_approval(&shell_request()).await
+            })
+            .await;
+
+        assert_eq!(decision, ApprovalResponse::No);
+        assert!(approver.is_none());
+        assert!(channel.sent.lock()[0].contains("expired"));
+        assert!(mgr.pending.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn without_context_channel_approval_denies() {
+        let mgr = manager(vec![], 30);
+        assert_eq!(
+            mgr.request_channel_approval(&shell_request()).await,
+            (ApprovalResponse::No, None)
+        );
+    }
+}
@@ -3,13 +3,37 @@
 //! Provides a pre-execution hook that prompts the user before tool calls,
 //! with session-scoped "Always" allowlists and audit logging.
 
-use crate::config::AutonomyConfig;
+pub mod channel;
+
+#[allow(unused_imports)]
+pub use channel::{
+    parse_approval_reply, ApprovalReplyOutcome, ChannelApprovalContext, CHANNEL_APPROVAL_CONTEXT,
+};
+
+use crate::config::{AutonomyConfig, ChannelApprovalConfig, ToolRuleAction};
+use crate::security::tool_rules::{ToolPolicy, ToolRuleDecision};
 use crate::security::AutonomyLevel;
+use crate::trust::runtime::PersistentTrust;
 use chrono::Utc;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+/// Scope of "Always" answers: the conversation and requester of the current
+/// channel turn, or one shared scope outside channel turns (CLI).
+fn allowlist_scope() -> String {
+    CHANNEL_APPROVAL_CONTEXT
+        .try_with(|context| {
+            context
+                .as_ref()
+                .map(ChannelApprovalContext::allowlist_scope)
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+}
 
 // ── Types ────────────────────────────────────────────────────────
 
@@ -40,6 +64,9 @@ pub struct ApprovalLogEntry {
     pub arguments_summary: String,
     pub decision: ApprovalResponse,
     pub channel: String,
+    /// Who answered a channel approval prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approver: Option<String>,
 }
 
 // ── ApprovalManager ──────────────────────────────────────────────
@@ -47,6 +74,7 @@ pub struct ApprovalLogEntry {
 /// Manages the approval workflow for tool calls.
 ///
 /// - Checks config-level `auto_approve` / `always_ask` lists
+/// - Applies argument-aware `[[autonomy.rules]]`
 /// - Maintains a session-scoped "always" allowlist
 /// - Records an audit trail of all decisions
 ///
@@ -55,19 +83,31 @@ pub struct ApprovalLogEntry {
 /// - **Non-interactive** (channels): tools needing approval are auto-denied
 ///   because there is no interactive operator to approve them. `auto_approve`
 ///   policy is still enforced, and `always_ask` / supervised-default tools are
-///   denied rather than silently allowed.
+///   denied rather than silently allowed. With
+///   `[autonomy.channel_approval]` enabled they are instead sent to the chat
+///   as an approval prompt (see [`channel`]).
 pub struct ApprovalManager {
     /// Tools that never need approval (from config).
     auto_approve: HashSet<String>,
     /// Tools that always need approval, ignoring session allowlist.
     always_ask: HashSet<String>,
+    /// Argument-aware rules (`[[autonomy.rules]]`), shared with the
+    /// [`SecurityPolicy`](crate::security::SecurityPolicy) that compiled them.
+    rules: Arc<ToolPolicy>,
     /// Autonomy level from config.
     autonomy_level: AutonomyLevel,
+    /// Trust tracker that tightens autonomy for regressed domains.
+    trust: Option<Arc<PersistentTrust>>,
     /// When `true`, tools that would require interactive approval are
     /// auto-denied instead. Used for channel-driven (non-CLI) runs.
     non_interactive: bool,
-    /// Session-scoped allowlist built from "Always" responses.
-    session_allowlist: Mutex<HashSet<String>>,
+    /// Chat-based approval prompts for non-interactive runs.
+    channel_approval: ChannelApprovalConfig,
+    /// Channel approval prompts awaiting an answer, keyed by request ID.
+    pending: Mutex<channel::PendingChannelApprovals>,
+    /// Session-scoped allowlists built from "Always" responses, keyed by
+    /// conversation scope (see [`allowlist_scope`]).
+    session_allowlist: Mutex<HashMap<String, HashSet<String>>>,
     /// Audit trail of approval decisions.
     audit_log: Mutex<Vec<ApprovalLogEntry>>,
 }
@@ -78,9 +118,13 @@ impl ApprovalManager {
         Self {
             auto_approve: config.auto_approve.iter().cloned().collect(),
             always_ask: config.always_ask.iter().cloned().collect(),
+            rules: Arc::default(),
             autonomy_level: config.level,
+            trust: None,
             non_interactive: false,
-            session_allowlist: Mutex::new(HashSet::new()),
+            channel_approval: ChannelApprovalConfig::default(),
+            pending: Mutex::new(HashMap::new()),
+            session_allowlist: Mutex::new(HashMap::new()),
             audit_log: Mutex::new(Vec::new()),
         }
     }
@@ -94,13 +138,40 @@ impl ApprovalManager {
         Self {
             auto_approve: config.auto_approve.iter().cloned().collect(),
             always_ask: config.always_ask.iter().cloned().collect(),
+            rules: Arc::default(),
             autonomy_level: config.level,
+            trust: None,
             non_interactive: true,
-            session_allowlist: Mutex::new(HashSet::new()),
+            channel_approval: config.channel_approval.clone(),
+            pending: Mutex::new(HashMap::new()),
+            session_allowlist: Mutex::new(HashMap::new()),
             audit_log: Mutex::new(Vec::new()),
         }
     }
 
+    /// Apply `rules` (normally [`SecurityPolicy::tool_rules`]) to individual
+    /// calls. Without them only the name-based lists decide.
+    ///
+    /// [`SecurityPolicy::tool_rules`]: crate::security::SecurityPolicy::tool_rules
+    #[must_use]
+    pub fn with_rules(mut self, rules: Arc<ToolPolicy>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// The argument-aware rules this manager applies.
+    pub fn tool_rules(&self) -> &ToolPolicy {
+        &self.rules
+    }
+
+    /// Consult `trust` for per-domain autonomy and report denied approvals
+    /// to it.
+    #[must_use]
+    pub fn with_trust(mut self, trust: Option<Arc<PersistentTrust>>) -> Self {
+        self.trust = trust;
+        self
+    }
+
     /// Returns `true` when this manager operates in non-interactive mode
     /// (i.e. for channel-driven runs where no operator can approve).
     pub fn is_non_interactive(&self) -> bool {
@@ -110,15 +181,54 @@ impl ApprovalManager {
     /// Check whether a tool call requires interactive approval.
     ///
     /// Returns `true` if the call needs a prompt, `false` if it can proceed.
+    ///
+    /// With a trust tracker (see [`Self::with_trust`]), a tool whose domain is
+    /// in regression is handled one autonomy level lower than configured.
     pub fn needs_approval(&self, tool_name: &str) -> bool {
+        self.needs_approval_with_rule(tool_name, None)
+    }
+
+    /// Check whether a specific tool call requires interactive approval,
+    /// taking `[[autonomy.rules]]` into account.
+    ///
+    /// A matching `ask` rule behaves like `always_ask` and a matching `allow`
+    /// rule like `auto_approve` for this call. A matching `deny` rule needs no
+    /// prompt: the executor refuses the call.
+    pub fn needs_approval_for_call(&self, tool_name: &str, args: &serde_json::Value) -> bool {
+        let rule = self.rule_decision(tool_name, args).map(|d| d.action);
+        self.needs_approval_with_rule(tool_name, rule)
+    }
+
+    /// The first `[[autonomy.rules]]` entry matching this call, if any.
+    pub fn rule_decision(
+        &self,
+        tool_name: &str,
+        args: &serde_json::Value,
+    ) -> Option<ToolRuleDecision> {
+        self.rules.evaluate(tool_name, args)
+    }
+
+    fn needs_approval_with_rule(&self, tool_name: &str, rule: Option<ToolRuleAction>) -> bool {
+        let level = self.trust.as_ref().map_or(self.autonomy_level, |trust| {
+            trust.effective_autonomy(tool_name, self.autonomy_level)
+        });
+
         // Full autonomy never prompts.
-        if self.autonomy_level == AutonomyLevel::Full {
+        if level == AutonomyLevel::Full {
             return false;
         }
 
         // ReadOnly blocks everything — handled elsewhere; no prompt needed.
-        if self.autonomy_level == AutonomyLevel::ReadOnly {
-            return false;
+        // A supervised domain demoted by low trust instead requires approval
+        // for every call, ignoring auto_approve and the session allowlist.
+        if level == AutonomyLevel::ReadOnly {
+            return self.autonomy_level != AutonomyLevel::ReadOnly;
+        }
+
+        match rule {
+            Some(ToolRuleAction::Ask) => return true,
+            Some(ToolRuleAction::Allow | ToolRuleAction::Deny) => return false,
+            None => {}
         }
 
         // always_ask overrides everything.
@@ -140,9 +250,12 @@ impl ApprovalManager {
             return false;
         }
 
-        // Session allowlist (from prior "Always" responses).
+        // Session allowlist (from prior "Always" responses in this scope).
         let allowlist = self.session_allowlist.lock();
-        if allowlist.contains(tool_name) {
+        if allowlist
+            .get(&allowlist_scope())
+            .is_some_and(|tools| tools.contains(tool_name))
+        {
             return false;
         }
 
@@ -158,10 +271,32 @@ impl ApprovalManager {
         decision: ApprovalResponse,
         channel: &str,
     ) {
-        // If "Always", add to session allowlist.
+        self.record_decision_by(tool_name, args, decision, channel, None);
+    }
+
+    /// Record an approval decision together with the identity that made it.
+    pub fn record_decision_by(
+        &self,
+        tool_name: &str,
+        args: &serde_json::Value,
+        decision: ApprovalResponse,
+        channel: &str,
+        approver: Option<&str>,
+    ) {
+        // If "Always", add to the current scope's session allowlist.
         if decision == ApprovalResponse::Always {
             let mut allowlist = self.session_allowlist.lock();
-            allowlist.insert(tool_name.to_string());
+            allowlist
+                .entry(allowlist_scope())
+                .or_default()
+                .insert(tool_name.to_string());
+        }
+
+        // An operator saying no is a user override of the agent's judgment.
+        if decision == ApprovalResponse::No && (!self.non_interactive || approver.is_some()) {
+            if let Some(trust) = &self.trust {
+                trust.record_user_override(tool_name, "approval denied");
+            }
         }
 
         // Append to audit log.
@@ -172,6 +307,7 @@ impl ApprovalManager {
             arguments_summary: summary,
             decision,
             channel: channel.to_string(),
+            approver: approver.map(str::to_string),
         };
         let mut log = self.audit_log.lock();
         log.push(entry);
@@ -182,15 +318,19 @@ impl ApprovalManager {
         self.audit_log.lock().clone()
     }
 
-    /// Get the current session allowlist.
+    /// Get the session allowlist of the current scope.
     pub fn session_allowlist(&self) -> HashSet<String> {
-        self.session_allowlist.lock().clone()
+        self.session_allowlist
+            .lock()
+            .get(&allowlist_scope())
+            .cloned()
+            .unwrap_or_default()
     }
 
     /// Prompt the user on the CLI and return their decision.
     ///
     /// Only called for interactive (CLI) managers. Non-interactive managers
-    /// auto-deny in the tool-call loop before reaching this point.
+    /// go through [`Self::request_channel_approval`] instead.
     pub fn prompt_cli(&self, request: &ApprovalRequest) -> ApprovalResponse {
         prompt_cli_interactive(request)
     }
@@ -301,6 +441,27 @@ mod tests {
         assert!(mgr.needs_approval("http_request"));
     }
 
+    #[test]
+    fn injected_trust_tightens_only_its_own_manager() {
+        let tmp = tempfile::tempdir().unwrap();
+        let trust = Arc::new(PersistentTrust::open(
+            crate::trust::TrustConfig {
+                enabled: true,
+                initial_score: 0.6,
+                correction_penalty: 0.2,
+                ..crate::trust::TrustConfig::default()
+            },
+            tmp.path(),
+        ));
+        trust.record_user_override("git_operations", "approval denied");
+
+        let tracked = ApprovalManager::from_config(&full_config()).with_trust(Some(trust));
+        let untracked = ApprovalManager::from_config(&full_config());
+        assert!(tracked.needs_approval("git_operations"));
+        assert!(!tracked.needs_approval("shell"));
+        assert!(!untracked.needs_approval("git_operations"));
+    }
+
     #[test]
     fn full_autonomy_never_prompts() {
         let mgr = ApprovalManager::from_config(&full_config());
@@ -608,4 +769,40 @@ mod tests {
             "always_ask must override auto_approve"
         );
     }
+    #[test]
+    fn tool_rules_decide_per_call() {
+        use crate::config::{ToolRuleAction, ToolRuleConfig};
+
+        let rule = |action, paths: &[&str]| ToolRuleConfig {
+            tool: "file_write".into(),
+            action,
+            reason: None,
+            paths: paths.iter().map(|p| (*p).to_string()).collect(),
+            hosts: Vec::new(),
+            recipient_domains: Vec::new(),
+            git_subcommands: Vec::new(),
+        };
+        let mut config = supervised_config();
+        config.auto_approve.push("file_write".into());
+        config.rules = vec![
+            rule(ToolRuleAction::Ask, &["config/**"]),
+            rule(ToolRuleAction::Allow, &["notes/*.md"]),
+        ];
+        let rules = Arc::new(ToolPolicy::compile(&config.rules));
+        let mgr = ApprovalManager::from_config(&config).with_rules(Arc::clone(&rules));
+
+        let ask = serde_json::json!({"path": "config/app.toml"});
+        assert!(mgr.needs_approval_for_call("file_write", &ask));
+        assert!(
+            !mgr.needs_approval_for_call("file_write", &serde_json::json!({"path": "src/a.rs"}))
+        );
+
+        config.auto_approve.clear();
+        let mgr = ApprovalManager::from_config(&config).with_rules(rules);
+        assert!(
+            !mgr.needs_approval_for_call("file_write", &serde_json::json!({"path": "notes/a.md"}))
+        );
+        assert!(mgr.needs_approval_for_call("file_write", &serde_json::json!({"path": "src/a.rs"})));
+        assert_eq!(mgr.rule_decision("file_write", &ask).unwrap().rule, 0);
+    }
 }
@@ -1,30 +1,61 @@
-//! ACP (Agent Control Protocol) Server — JSON-RPC 2.0 over stdio.
+//! ACP (Agent Client Protocol) Server — JSON-RPC 2.0 over stdio.
 //!
-//! Provides an IDE-friendly interface for spawning and managing isolated agent
-//! sessions. Each session wraps an [`Agent`] built from the global config with
-//! streaming support via JSON-RPC notifications.
+//! Lets editors such as Zed drive ZeroClaw as a coding agent. Each session
+//! wraps an [`Agent`] built from the global config; the editor is the
+//! operator, so tool approvals, file access and command execution are routed
+//! back to it when it advertises the matching capabilities.
 //!
 //! ## Protocol
 //!
-//! Requests and responses are newline-delimited JSON objects on stdin/stdout.
+//! Messages are newline-delimited JSON objects on stdin/stdout. Both sides
+//! send requests: the client's requests are handled concurrently so the
+//! agent can call back into the client while a prompt is running.
 //!
-//! | Method            | Description                              |
-//! |-------------------|------------------------------------------|
-//! | `initialize`      | Handshake — returns server capabilities  |
-//! | `session/new`     | Create an isolated agent session          |
-//! | `session/prompt`  | Send a prompt, stream back events         |
-//! | `session/stop`    | Gracefully terminate a session            |
+//! | Client → agent          | Description                                       |
+//! |-------------------------|---------------------------------------------------|
+//! | `initialize`            | Negotiate protocol version and capabilities       |
+//! | `session/new`           | Create an agent session                           |
+//! | `session/load`          | Resume a persisted session, replaying its history |
+//! | `session/prompt`        | Run a turn; returns the stop reason               |
+//! | `session/cancel`        | Notification — abort the running turn             |
+//! | `session/stop`          | Drop a session (ZeroClaw extension)               |
+//!
+//! | Agent → client               | Description                                  |
+//! |------------------------------|----------------------------------------------|
+//! | `session/update`             | Message/thought chunks, plans, tool calls    |
+//! | `session/request_permission` | Ask before a tool call that needs approval   |
+//! | `fs/read_text_file`          | `file_read`, when the client supports it     |
+//! | `fs/write_text_file`         | `file_write`, when the client supports it    |
+//! | `terminal/*`                 | `shell`, when the client supports terminals  |
+//!
+//! A delegated `shell` command gets only the safe environment baseline plus
+//! `shell_env_passthrough`, but runs outside ZeroClaw's sandbox and cgroup
+//! limits. When either is configured, `shell` runs locally instead.
+//!
+//! Tool calls that do not go through the session's tool registry (deferred
+//! MCP tools activated by `tool_search`, tools pre-executed by a proxy
+//! provider) are not reported as `tool_call` updates.
 
 use crate::agent::agent::{Agent, TurnEvent};
+use crate::approval::{ApprovalManager, ApprovalResponse};
+use crate::channels::session_store::SessionStore;
 use crate::config::Config;
+use crate::providers::ChatMessage;
+use crate::security::SecurityPolicy;
+use crate::tools::file_read::MAX_FILE_SIZE_BYTES;
+use crate::tools::{Tool, ToolResult};
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use std::collections::HashMap;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::Mutex;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{oneshot, Mutex};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
@@ -49,6 +80,19 @@ impl Default for AcpServerConfig {
     }
 }
 
+/// ACP protocol version implemented by this server.
+const PROTOCOL_VERSION: u64 = 1;
+
+/// Maximum terminal output retained by the client for a delegated `shell` call.
+const TERMINAL_OUTPUT_LIMIT: u64 = 1_048_576;
+
+/// Commands delegated to a client terminal are killed after this long,
+/// matching the local `shell` tool.
+const TERMINAL_TIMEOUT_SECS: u64 = 60;
+
+/// Channel name recorded in the approval audit log.
+const APPROVAL_CHANNEL: &str = "acp";
+
 // ── JSON-RPC types ───────────────────────────────────────────────
 
 #[derive(Debug, Deserialize)]
@@ -60,6 +104,24 @@ struct JsonRpcRequest {
     id: Option<Value>,
 }
 
+/// A client's reply to a request the agent sent.
+#[derive(Debug, Deserialize)]
+struct JsonRpcIncomingResponse {
+    id: Value,
+    #[serde(default)]
+    result: Option<Value>,
+    #[serde(default)]
+    error: Option<JsonRpcIncomingError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcIncomingError {
+    code: i32,
+    message: String,
+    #[serde(default)]
+    data: Option<Value>,
+}
+
 #[derive(Debug, Serialize)]
 struct JsonRpcResponse {
     jsonrpc: &'static str,
@@ -77,6 +139,15 @@ struct JsonRpcNotification {
     params: Value,
 }
 
+/// A request from the agent to the client.
+#[derive(Debug, Serialize)]
+struct JsonRpcOutgoingRequest {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'static str,
+    params: Value,
+}
+
 #[derive(Debug, Serialize)]
 struct JsonRpcError {
     code: i32,
@@ -85,6 +156,26 @@ struct JsonRpcError {
     data: Option<Value>,
 }
 
+/// One line read from stdin.
+#[derive(Debug)]
+enum IncomingMessage {
+    Request(JsonRpcRequest),
+    Response(JsonRpcIncomingResponse),
+}
+
+impl IncomingMessage {
+    /// Messages with a `method` are requests or notifications; everything
+    /// else is a response to one of the agent's own requests.
+    fn parse(line: &str) -> serde_json::Result<Self> {
+        let value: Value = serde_json::from_str(line)?;
+        if value.get("method").is_some() {
+            serde_json::from_value(value).map(Self::Request)
+        } else {
+            serde_json::from_value(value).map(Self::Response)
+        }
+    }
+}
+
 // Standard JSON-RPC error codes
 const PARSE_ERROR: i32 = -32700;
 const INVALID_REQUEST: i32 = -32600;
@@ -95,57 +186,745 @@ const INTERNAL_ERROR: i32 = -32603;
 // Custom error codes
 const SESSION_NOT_FOUND: i32 = -32000;
 const SESSION_LIMIT_REACHED: i32 = -32001;
+const SESSION_BUSY: i32 = -32002;
+
+// ── Client connection ────────────────────────────────────────────
+
+/// What the client offered in `initialize`.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ClientCapabilities {
+    fs: FileSystemCapability,
+    terminal: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct FileSystemCapability {
+    read_text_file: bool,
+    write_text_file: bool,
+}
+
+/// Output side of the connection: serialized writes plus the table of
+/// agent → client requests awaiting a response.
+struct AcpClient {
+    output: Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    next_request_id: AtomicU64,
+    pending: parking_lot::Mutex<HashMap<u64, oneshot::Sender<RpcResult>>>,
+    capabilities: parking_lot::RwLock<ClientCapabilities>,
+}
+
+impl AcpClient {
+    fn new(output: Box<dyn AsyncWrite + Send + Unpin>) -> Self {
+        Self {
+            output: Mutex::new(output),
+            next_request_id: AtomicU64::new(0),
+            pending: parking_lot::Mutex::new(HashMap::new()),
+            capabilities: parking_lot::RwLock::new(ClientCapabilities::default()),
+        }
+    }
+
+    fn capabilities(&self) -> ClientCapabilities {
+        *self.capabilities.read()
+    }
+
+    /// Send a request to the client and wait for its response.
+    async fn request(&self, method: &'static str, params: Value) -> Result<Value> {
+        let id = self.next_request_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id, tx);
+        self.write_json(&JsonRpcOutgoingRequest {
+            jsonrpc: "2.0",
+            id,
+            method,
+            params,
+        })
+        .await;
+        match rx.await {
+            Ok(Ok(result)) => Ok(result),
+            Ok(Err(e)) => anyhow::bail!("{method} failed ({}): {}", e.code, e.message),
+            Err(_) => anyhow::bail!("{method} failed: connection closed"),
+        }
+    }
+
+    /// Hand a client response to the request waiting for it.
+    fn resolve(&self, response: JsonRpcIncomingResponse) {
+        let waiter = response
+            .id
+            .as_u64()
+            .and_then(|id| self.pending.lock().remove(&id));
+        let Some(waiter) = waiter else {
+            warn!("Ignoring response to unknown request id {}", response.id);
+            return;
+        };
+        let result = match response.error {
+            Some(e) => Err(RpcError {
+                code: e.code,
+                message: e.message,
+                data: e.data,
+            }),
+            None => Ok(response.result.unwrap_or(Value::Null)),
+        };
+        let _ = waiter.send(result);
+    }
+
+    /// Fail every outstanding request, e.g. once stdin is closed.
+    fn close(&self) {
+        self.pending.lock().clear();
+    }
+
+    async fn session_update(&self, session_id: &str, update: Value) {
+        self.write_notification(&JsonRpcNotification {
+            jsonrpc: "2.0",
+            method: "session/update",
+            params: json!({
+                "sessionId": session_id,
+                "update": update,
+            }),
+        })
+        .await;
+    }
+
+    // ── I/O helpers ──────────────────────────────────────────────
+
+    async fn write_result(&self, id: Value, result: Value) {
+        let response = JsonRpcResponse {
+            jsonrpc: "2.0",
+            result: Some(result),
+            error: None,
+            id,
+        };
+        self.write_json(&response).await;
+    }
+
+    async fn write_error(&self, id: Value, code: i32, message: &str) {
+        let response = JsonRpcResponse {
+            jsonrpc: "2.0",
+            result: None,
+            error: Some(JsonRpcError {
+                code,
+                message: message.to_string(),
+                data: None,
+            }),
+            id,
+        };
+        self.write_json(&response).await;
+    }
+
+    async fn write_notification(&self, notification: &JsonRpcNotification) {
+        self.write_json(notification).await;
+    }
+
+    async fn write_json<T: Serialize>(&self, value: &T) {
+        match serde_json::to_string(value) {
+            Ok(mut json) => {
+                // Write as a single line followed by newline
+                json.push('\n');
+                let mut output = self.output.lock().await;
+                if let Err(e) = output.write_all(json.as_bytes()).await {
+                    error!("Failed to write to stdout: {e}");
+                    return;
+                }
+                if let Err(e) = output.flush().await {
+                    error!("Failed to flush stdout: {e}");
+                }
+            }
+            Err(e) => {
+                error!("Failed to serialize JSON-RPC message: {e}");
+            }
+        }
+    }
+}
 
 // ── Session state ────────────────────────────────────────────────
 
 struct Session {
     agent: Agent,
+    context: Arc<SessionContext>,
     created_at: Instant,
     last_active: Instant,
     workspace_dir: String,
 }
 
+/// Per-session state shared with the session's ACP tool wrappers.
+struct SessionContext {
+    session_id: String,
+    client: Arc<AcpClient>,
+    /// Approval policy; the ACP client answers the prompts.
+    approvals: ApprovalManager,
+    security: Arc<SecurityPolicy>,
+    /// Whether `shell` may run in the client's terminal: not when local
+    /// commands are sandboxed or placed in a cgroup.
+    terminal_shell: bool,
+    /// Tools the user chose "always reject" for in this session.
+    rejected_tools: parking_lot::Mutex<HashSet<String>>,
+    next_tool_call: AtomicU64,
+}
+
+impl SessionContext {
+    async fn update(&self, update: Value) {
+        self.client.session_update(&self.session_id, update).await;
+    }
+
+    fn next_tool_call_id(&self) -> String {
+        format!(
+            "call_{}",
+            self.next_tool_call.fetch_add(1, Ordering::Relaxed)
+        )
+    }
+
+    /// Ask the client whether a call may run. Returns the refusal to report
+    /// back to the model, or `None` when the call is allowed.
+    async fn authorize(&self, tool_call: &Value, name: &str, args: &Value) -> Option<String> {
+        const DENIED: &str = "Denied by user.";
+        if self.rejected_tools.lock().contains(name) {
+            return Some(DENIED.into());
+        }
+        if !self.approvals.needs_approval_for_call(name, args) {
+            return None;
+        }
+
+        let response = self
+            .client
+            .request(
+                "session/request_permission",
+                json!({
+                    "sessionId": self.session_id,
+                    "toolCall": tool_call,
+                    "options": permission_options(),
+                }),
+            )
+            .await;
+        let (decision, reject_always) = match response {
+            Ok(response) => permission_decision(&response),
+            Err(e) => {
+                warn!(tool = name, "Permission request failed: {e}");
+                (ApprovalResponse::No, false)
+            }
+        };
+        self.approvals
+            .record_decision(name, args, decision, APPROVAL_CHANNEL);
+        if reject_always {
+            self.rejected_tools.lock().insert(name.to_string());
+        }
+        (decision == ApprovalResponse::No).then(|| DENIED.into())
+    }
+
+    /// `file_read` through `fs/read_text_file`, formatted like the local tool.
+    async fn read_text_file(&self, args: &Value) -> Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        if self.security.is_rate_limited() {
+            return Ok(tool_failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+        if !self.security.is_path_allowed(path) {
+            return Ok(tool_failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+        if !self.security.record_action() {
+            return Ok(tool_failure("Rate limit exceeded: action budget exhausted"));
+        }
+
+        // Resolve locally before asking the client, so a symlink inside the
+        // workspace cannot hand the editor a path outside it.
+        let full_path = self.security.resolve_tool_path(path);
+        let resolved_path = match tokio::fs::canonicalize(&full_path).await {
+            Ok(p) => p,
+            Err(e) => return Ok(tool_failure(format!("Failed to resolve file path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&resolved_path) {
+            return Ok(tool_failure(
+                self.security
+                    .resolved_path_violation_message(&resolved_path),
+            ));
+        }
+        match tokio::fs::metadata(&resolved_path).await {
+            Ok(meta) if meta.len() > MAX_FILE_SIZE_BYTES => {
+                return Ok(tool_failure(format!(
+                    "File too large: {} bytes (limit: {MAX_FILE_SIZE_BYTES} bytes)",
+                    meta.len()
+                )));
+            }
+            Ok(_) => {}
+            Err(e) => return Ok(tool_failure(format!("Failed to read file metadata: {e}"))),
+        }
+
+        let offset = args
+            .get("offset")
+            .and_then(Value::as_u64)
+            .unwrap_or(1)
+            .max(1);
+        let mut params = json!({
+            "sessionId": self.session_id,
+            "path": resolved_path,
+            "line": offset,
+        });
+        if let Some(limit) = args.get("limit").and_then(Value::as_u64) {
+            params["limit"] = json!(limit);
+        }
+        let response = self.client.request("fs/read_text_file", params).await?;
+        let content = response
+            .get("content")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let numbered = content
+            .lines()
+            .zip(offset..)
+            .map(|(line, n)| format!("{n}: {line}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(ToolResult {
+            success: true,
+            output: numbered,
+            error: None,
+        })
+    }
+
+    /// `file_write` through `fs/write_text_file`, so the editor applies the
+    /// change to its open buffers.
+    async fn write_text_file(&self, args: &Value) -> Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+        let content = args
+            .get("content")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'content' parameter"))?;
+        if !self.security.can_act() {
+            return Ok(tool_failure("Action blocked: autonomy is read-only"));
+        }
+        if self.security.is_rate_limited() {
+            return Ok(tool_failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+        if !self.security.is_path_allowed(path) {
+            return Ok(tool_failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+        let full_path = self.security.resolve_tool_path(path);
+        let resolved_path = match resolve_write_target(&full_path).await {
+            Ok(p) => p,
+            Err(e) => return Ok(tool_failure(format!("Failed to resolve file path: {e}"))),
+        };
+        if !self.security.is_resolved_path_allowed(&resolved_path) {
+            return Ok(tool_failure(
+                self.security
+                    .resolved_path_violation_message(&resolved_path),
+            ));
+        }
+        if self.security.is_runtime_config_path(&resolved_path) {
+            return Ok(tool_failure(
+                self.security
+                    .runtime_config_violation_message(&resolved_path),
+            ));
+        }
+        if let Ok(meta) = tokio::fs::symlink_metadata(&resolved_path).await {
+            if meta.file_type().is_symlink() {
+                return Ok(tool_failure(format!(
+                    "Refusing to write through symlink: {}",
+                    resolved_path.display()
+                )));
+            }
+        }
+        if !self.security.record_action() {
+            return Ok(tool_failure("Rate limit exceeded: action budget exhausted"));
+        }
+
+        self.client
+            .request(
+                "fs/write_text_file",
+                json!({
+                    "sessionId": self.session_id,
+                    "path": resolved_path,
+                    "content": content,
+                }),
+            )
+            .await?;
+        Ok(ToolResult {
+            success: true,
+            output: format!("Written {} bytes to {path}", content.len()),
+            error: None,
+        })
+    }
+
+    /// `shell` in a client terminal, which the editor shows live inside the
+    /// tool call.
+    async fn run_in_terminal(&self, tool_call_id: &str, args: &Value) -> Result<ToolResult> {
+        let command = args
+            .get("command")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
+        let approved = args
+            .get("approved")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if let Err(reason) =
+            crate::tools::shell::authorize_command(&self.security, command, approved)
+        {
+            return Ok(tool_failure(reason));
+        }
+
+        let created = self
+            .client
+            .request(
+                "terminal/create",
+                json!({
+                    "sessionId": self.session_id,
+                    "command": "sh",
+                    "args": ["-c", command],
+                    "env": terminal_env(&self.security),
+                    "cwd": self.security.workspace_dir,
+                    "outputByteLimit": TERMINAL_OUTPUT_LIMIT,
+                }),
+            )
+            .await?;
+        let terminal_id = created
+            .get("terminalId")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("terminal/create returned no terminalId"))?;
+        let terminal = ClientTerminal {
+            client: Arc::clone(&self.client),
+            session_id: self.session_id.clone(),
+            terminal_id: terminal_id.to_string(),
+            released: false,
+        };
+        self.update(json!({
+            "sessionUpdate": "tool_call_update",
+            "toolCallId": tool_call_id,
+            "content": [{ "type": "terminal", "terminalId": terminal_id }],
+        }))
+        .await;
+
+        let wait = self
+            .client
+            .request("terminal/wait_for_exit", terminal.params());
+        let timed_out =
+            match tokio::time::timeout(Duration::from_secs(TERMINAL_TIMEOUT_SECS), wait).await {
+                Ok(exit) => {
+                    exit?;
+                    false
+                }
+                Err(_) => {
+                    self.client
+                        .request("terminal/kill", terminal.params())
+                        .await?;
+                    true
+                }
+            };
+        let output = self
+            .client
+            .request("terminal/output", terminal.params())
+            .await?;
+        terminal.release().await;
+
+        let text = output
+            .get("output")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let exit_code = output
+            .pointer("/exitStatus/exitCode")
+            .and_then(Value::as_i64);
+        if timed_out {
+            return Ok(ToolResult {
+                success: false,
+                output: text,
+                error: Some(format!(
+                    "Command timed out after {TERMINAL_TIMEOUT_SECS}s and was killed"
+                )),
+            });
+        }
+        Ok(match exit_code {
+            Some(0) => ToolResult {
+                success: true,
+                output: text,
+                error: None,
+            },
+            code => ToolResult {
+                success: false,
+                output: text,
+                error: Some(code.map_or_else(
+                    || "Command terminated by signal".to_string(),
+                    |code| format!("Command exited with status {code}"),
+                )),
+            },
+        })
+    }
+}
+
+/// `terminal/create` environment: the same variables a local `shell` keeps.
+fn terminal_env(security: &SecurityPolicy) -> Vec<Value> {
+    crate::tools::shell::allowed_shell_env(security)
+        .into_iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+/// Whether local `shell` commands run in a sandbox or cgroup, which a client
+/// terminal cannot apply.
+fn shell_is_confined(config: &Config) -> bool {
+    config.runtime.cgroup.enabled
+        || crate::security::create_sandbox(&config.security, &config.http_request.allowed_domains)
+            .name()
+            != "none"
+}
+
+/// A client terminal, released when dropped so a cancelled turn does not
+/// leave the command running.
+struct ClientTerminal {
+    client: Arc<AcpClient>,
+    session_id: String,
+    terminal_id: String,
+    released: bool,
+}
+
+impl ClientTerminal {
+    fn params(&self) -> Value {
+        json!({
+            "sessionId": self.session_id,
+            "terminalId": self.terminal_id,
+        })
+    }
+
+    async fn release(mut self) {
+        self.released = true;
+        if let Err(e) = self.client.request("terminal/release", self.params()).await {
+            debug!("terminal/release failed: {e}");
+        }
+    }
+}
+
+impl Drop for ClientTerminal {
+    fn drop(&mut self) {
+        if self.released {
+            return;
+        }
+        let client = Arc::clone(&self.client);
+        let params = self.params();
+        tokio::spawn(async move {
+            let _ = client.request("terminal/release", params).await;
+        });
+    }
+}
+
+// ── ACP tools ────────────────────────────────────────────────────
+
+/// Wraps a session tool to report it as an ACP tool call, ask the client
+/// for approval, and delegate file and shell access to the client.
+struct AcpTool {
+    inner: Box<dyn Tool>,
+    session: Arc<SessionContext>,
+}
+
+impl AcpTool {
+    async fn run(&self, tool_call_id: &str, args: Value) -> Result<ToolResult> {
+        let capabilities = self.session.client.capabilities();
+        match self.inner.name() {
+            "file_read" if capabilities.fs.read_text_file => {
+                self.session.read_text_file(&args).await
+            }
+            "file_write" if capabilities.fs.write_text_file => {
+                self.session.write_text_file(&args).await
+            }
+            "shell" if capabilities.terminal && self.session.terminal_shell => {
+                self.session.run_in_terminal(tool_call_id, &args).await
+            }
+            _ => self.inner.execute(args).await,
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for AcpTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> Value {
+        self.inner.parameters_schema()
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        let name = self.inner.name();
+        let tool_call_id = self.session.next_tool_call_id();
+        let mut tool_call = json!({
+            "toolCallId": tool_call_id,
+            "title": tool_title(name, &args),
+            "kind": tool_kind(name),
+            "status": "pending",
+            "rawInput": args,
+        });
+        if let Some(path) = args.get("path").and_then(Value::as_str) {
+            tool_call["locations"] =
+                json!([{ "path": self.session.security.resolve_tool_path(path) }]);
+        }
+        let mut update = tool_call.clone();
+        update["sessionUpdate"] = json!("tool_call");
+        self.session.update(update).await;
+
+        if let Some(reason) = self.session.authorize(&tool_call, name, &args).await {
+            self.session
+                .update(tool_call_update(&tool_call_id, "failed", &reason))
+                .await;
+            return Ok(tool_failure(reason));
+        }
+
+        self.session
+            .update(json!({
+                "sessionUpdate": "tool_call_update",
+                "toolCallId": tool_call_id,
+                "status": "in_progress",
+            }))
+            .await;
+        let result = self.run(&tool_call_id, args).await;
+        let update = match &result {
+            Ok(r) if r.success => tool_call_update(&tool_call_id, "completed", &r.output),
+            Ok(r) => tool_call_update(
+                &tool_call_id,
+                "failed",
+                r.error.as_deref().unwrap_or(&r.output),
+            ),
+            Err(e) => tool_call_update(&tool_call_id, "failed", &e.to_string()),
+        };
+        self.session.update(update).await;
+        result
+    }
+}
+
+/// Publishes the agent's plan as an ACP `plan` update.
+struct AcpPlanTool {
+    session: Arc<SessionContext>,
+}
+
+#[async_trait]
+impl Tool for AcpPlanTool {
+    fn name(&self) -> &str {
+        "update_plan"
+    }
+
+    fn description(&self) -> &str {
+        "Share your plan for the current task with the user's editor. Send the complete list of steps every time; update statuses as you work."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "entries": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "content": { "type": "string", "description": "What this step does" },
+                            "priority": { "type": "string", "enum": ["high", "medium", "low"] },
+                            "status": { "type": "string", "enum": ["pending", "in_progress", "completed"] }
+                        },
+                        "required": ["content", "status"]
+                    }
+                }
+            },
+            "required": ["entries"]
+        })
+    }
+
+    async fn execute(&self, args: Value) -> Result<ToolResult> {
+        let entries = plan_entries(&args)
+            .ok_or_else(|| anyhow::anyhow!("Missing or invalid 'entries' parameter"))?;
+        let count = entries.len();
+        self.session
+            .update(json!({
+                "sessionUpdate": "plan",
+                "entries": entries,
+            }))
+            .await;
+        Ok(ToolResult {
+            success: true,
+            output: format!("Plan updated ({count} entries)"),
+            error: None,
+        })
+    }
+}
+
 // ── ACP Server ───────────────────────────────────────────────────
 
 pub struct AcpServer {
+    state: Arc<AcpState>,
+}
+
+struct AcpState {
     config: Config,
     acp_config: AcpServerConfig,
-    sessions: Arc<Mutex<HashMap<String, Session>>>,
+    client: Arc<AcpClient>,
+    /// Idle sessions. A session is taken out while a turn runs.
+    sessions: Mutex<HashMap<String, Session>>,
+    /// Cancellation handles of running turns, keyed by session ID.
+    running: parking_lot::Mutex<HashMap<String, CancellationToken>>,
+    /// Transcript persistence for `session/load`.
+    store: Option<SessionStore>,
 }
 
 impl AcpServer {
     pub fn new(config: Config, acp_config: AcpServerConfig) -> Self {
+        Self::with_output(config, acp_config, Box::new(tokio::io::stdout()))
+    }
+
+    fn with_output(
+        config: Config,
+        acp_config: AcpServerConfig,
+        output: Box<dyn AsyncWrite + Send + Unpin>,
+    ) -> Self {
+        let store = match SessionStore::new(&config.workspace_dir) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                warn!("ACP session persistence disabled: {e}");
+                None
+            }
+        };
         Self {
-            config,
-            acp_config,
-            sessions: Arc::new(Mutex::new(HashMap::new())),
+            state: Arc::new(AcpState {
+                config,
+                acp_config,
+                client: Arc::new(AcpClient::new(output)),
+                sessions: Mutex::new(HashMap::new()),
+                running: parking_lot::Mutex::new(HashMap::new()),
+                store,
+            }),
         }
     }
 
-    /// Run the ACP server, reading JSON-RPC requests from stdin and writing
+    /// Run the ACP server, reading JSON-RPC messages from stdin and writing
     /// responses/notifications to stdout.
     pub async fn run(&self) -> Result<()> {
         info!(
             "ACP server starting (max_sessions={}, timeout={}s)",
-            self.acp_config.max_sessions, self.acp_config.session_timeout_secs
+            self.state.acp_config.max_sessions, self.state.acp_config.session_timeout_secs
         );
 
-        let stdin = tokio::io::stdin();
-        let mut reader = BufReader::new(stdin);
-        let mut line = String::new();
-
         // Spawn session reaper
-        let sessions = Arc::clone(&self.sessions);
-        let timeout = Duration::from_secs(self.acp_config.session_timeout_secs);
+        let state = Arc::clone(&self.state);
+        let timeout = Duration::from_secs(self.state.acp_config.session_timeout_secs);
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(60));
             loop {
                 interval.tick().await;
-                let mut sessions = sessions.lock().await;
+                let mut sessions = state.sessions.lock().await;
                 let before = sessions.len();
                 sessions.retain(|id, session| {
                     let expired = session.last_active.elapsed() > timeout;
                     if expired {
+                        session.agent.end_process_session();
                         info!("Session {id} expired after inactivity");
                     }
                     !expired
@@ -157,6 +936,11 @@ impl AcpServer {
             }
         });
 
+        self.serve(BufReader::new(tokio::io::stdin())).await
+    }
+
+    async fn serve<R: AsyncBufRead + Unpin>(&self, mut reader: R) -> Result<()> {
+        let mut line = String::new();
         loop {
             line.clear();
             let bytes_read = reader.read_line(&mut line).await?;
@@ -170,28 +954,39 @@ impl AcpServer {
                 continue;
             }
 
-            match serde_json::from_str::<JsonRpcRequest>(trimmed) {
-                Ok(request) => {
+            match IncomingMessage::parse(trimmed) {
+                Ok(IncomingMessage::Response(response)) => self.state.client.resolve(response),
+                Ok(IncomingMessage::Request(request)) => {
                     if request.jsonrpc != "2.0" {
                         if let Some(id) = request.id {
-                            self.write_error(id, INVALID_REQUEST, "Invalid JSON-RPC version")
+                            self.state
+                                .client
+                                .write_error(id, INVALID_REQUEST, "Invalid JSON-RPC version")
                                 .await;
                         }
                         continue;
                     }
-                    self.handle_request(request).await;
+                    // Handled concurrently: a running prompt waits on client
+                    // responses that arrive on this same stream.
+                    let state = Arc::clone(&self.state);
+                    tokio::spawn(async move { Box::pin(state.handle_request(request)).await });
                 }
                 Err(e) => {
-                    warn!("Failed to parse JSON-RPC request: {e}");
-                    self.write_error(Value::Null, PARSE_ERROR, &format!("Parse error: {e}"))
+                    warn!("Failed to parse JSON-RPC message: {e}");
+                    self.state
+                        .client
+                        .write_error(Value::Null, PARSE_ERROR, &format!("Parse error: {e}"))
                         .await;
                 }
             }
         }
 
+        self.state.client.close();
         Ok(())
     }
+}
 
+impl AcpState {
     async fn handle_request(&self, request: JsonRpcRequest) {
         let id = request.id.clone().unwrap_or(Value::Null);
         let is_notification = request.id.is_none();
@@ -199,7 +994,9 @@ impl AcpServer {
         let result = match request.method.as_str() {
             "initialize" => self.handle_initialize(&request.params),
             "session/new" => self.handle_session_new(&request.params).await,
-            "session/prompt" => self.handle_session_prompt(&request.params, &id).await,
+            "session/load" => self.handle_session_load(&request.params).await,
+            "session/prompt" => self.handle_session_prompt(&request.params).await,
+            "session/cancel" => self.handle_session_cancel(&request.params),
             "session/stop" => self.handle_session_stop(&request.params).await,
             _ => Err(RpcError {
                 code: METHOD_NOT_FOUND,
@@ -211,287 +1008,545 @@ impl AcpServer {
         // Only send response for requests (with id), not notifications
         if !is_notification {
             match result {
-                Ok(value) => self.write_result(id, value).await,
-                Err(e) => self.write_error(id, e.code, &e.message).await,
+                Ok(value) => self.client.write_result(id, value).await,
+                Err(e) => self.client.write_error(id, e.code, &e.message).await,
             }
         }
     }
 
     // ── Method handlers ──────────────────────────────────────────
 
-    fn handle_initialize(&self, _params: &Value) -> RpcResult {
-        Ok(serde_json::json!({
-            "protocolVersion": "1.0",
-            "serverInfo": {
-                "name": "zeroclaw-acp",
-                "version": env!("CARGO_PKG_VERSION"),
+    fn handle_initialize(&self, params: &Value) -> RpcResult {
+        if let Some(capabilities) = params.get("clientCapabilities") {
+            let capabilities: ClientCapabilities = serde_json::from_value(capabilities.clone())
+                .map_err(|e| RpcError {
+                    code: INVALID_PARAMS,
+                    message: format!("Invalid clientCapabilities: {e}"),
+                    data: None,
+                })?;
+            *self.client.capabilities.write() = capabilities;
+        }
+
+        Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "agentCapabilities": {
+                "loadSession": self.store.is_some(),
+                "promptCapabilities": {
+                    "image": false,
+                    "audio": false,
+                    "embeddedContext": true,
+                },
             },
-            "capabilities": {
-                "streaming": true,
-                "maxSessions": self.acp_config.max_sessions,
-                "sessionTimeoutSecs": self.acp_config.session_timeout_secs,
+            "authMethods": [],
+            "agentInfo": {
+                "name": "zeroclaw",
+                "title": "ZeroClaw",
+                "version": env!("CARGO_PKG_VERSION"),
             },
-            "methods": [
-                "initialize",
-                "session/new",
-                "session/prompt",
-                "session/stop",
-            ],
         }))
     }
 
     async fn handle_session_new(&self, params: &Value) -> RpcResult {
-        let mut sessions = self.sessions.lock().await;
-
-        if sessions.len() >= self.acp_config.max_sessions {
-            return Err(RpcError {
-                code: SESSION_LIMIT_REACHED,
-                message: format!(
-                    "Maximum session limit reached ({})",
-                    self.acp_config.max_sessions
-                ),
-                data: None,
-            });
-        }
-
-        let workspace_dir = params
-            .get("cwd")
-            .or_else(|| params.get("workspaceDir"))
-            .or_else(|| params.get("workspace_dir"))
-            .and_then(|v| v.as_str())
-            .unwrap_or_else(|| self.config.workspace_dir.to_str().unwrap_or("."))
-            .to_string();
-
         let session_id = Uuid::new_v4().to_string();
+        let session = self.create_session(&session_id, params, &[]).await?;
+        info!(
+            "Created session {session_id} (workspace: {})",
+            session.workspace_dir
+        );
+        self.insert_session(session_id.clone(), session).await?;
 
-        // Build agent from global config
-        let agent = Agent::from_config(&self.config)
-            .await
-            .map_err(|e| RpcError {
-                code: INTERNAL_ERROR,
-                message: format!("Failed to create agent: {e}"),
-                data: None,
-            })?;
+        Ok(json!({ "sessionId": session_id }))
+    }
 
-        let now = Instant::now();
-        sessions.insert(
-            session_id.clone(),
-            Session {
-                agent,
-                created_at: now,
-                last_active: now,
-                workspace_dir: workspace_dir.clone(),
-            },
-        );
+    async fn handle_session_load(&self, params: &Value) -> RpcResult {
+        let session_id = session_id_param(params)?;
+        if self.running.lock().contains_key(&session_id) {
+            return Err(session_busy(&session_id));
+        }
+        let transcript = self
+            .store
+            .as_ref()
+            .map(|store| store.load(&store_key(&session_id)))
+            .unwrap_or_default();
+
+        let loaded = self.sessions.lock().await.contains_key(&session_id);
+        if !loaded {
+            if transcript.is_empty() {
+                return Err(session_not_found(&session_id));
+            }
+            let session = self
+                .create_session(&session_id, params, &transcript)
+                .await?;
+            self.insert_session(session_id.clone(), session).await?;
+            info!(
+                "Loaded session {session_id} ({} messages)",
+                transcript.len()
+            );
+        }
 
-        info!("Created session {session_id} (workspace: {workspace_dir})");
+        for message in &transcript {
+            if let Some(update) = replay_update(message) {
+                self.client.session_update(&session_id, update).await;
+            }
+        }
 
-        Ok(serde_json::json!({
-            "sessionId": session_id,
-            "workspaceDir": workspace_dir,
-        }))
+        Ok(json!({}))
     }
 
-    async fn handle_session_prompt(&self, params: &Value, _request_id: &Value) -> RpcResult {
-        let session_id = params
-            .get("sessionId")
-            .or_else(|| params.get("session_id"))
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| RpcError {
-                code: INVALID_PARAMS,
-                message: "Missing required parameter: sessionId".to_string(),
-                data: None,
-            })?
-            .to_string();
-
+    async fn handle_session_prompt(&self, params: &Value) -> RpcResult {
+        let session_id = session_id_param(params)?;
         let prompt = params
             .get("prompt")
-            .and_then(|v| v.as_str())
+            .and_then(prompt_text)
             .ok_or_else(|| RpcError {
                 code: INVALID_PARAMS,
                 message: "Missing required parameter: prompt".to_string(),
                 data: None,
-            })?
-            .to_string();
+            })?;
 
         // Remove the session from the map so we can take mutable ownership of
         // the Agent for the duration of the turn. It will be reinserted after.
         let mut session = {
             let mut sessions = self.sessions.lock().await;
-            sessions.remove(&session_id).ok_or_else(|| RpcError {
-                code: SESSION_NOT_FOUND,
-                message: format!("Session not found: {session_id}"),
-                data: None,
-            })?
+            match sessions.remove(&session_id) {
+                Some(session) => session,
+                None if self.running.lock().contains_key(&session_id) => {
+                    return Err(session_busy(&session_id));
+                }
+                None => return Err(session_not_found(&session_id)),
+            }
         };
+        let cancel = CancellationToken::new();
+        self.running
+            .lock()
+            .insert(session_id.clone(), cancel.clone());
 
         let (event_tx, mut event_rx) = tokio::sync::mpsc::channel::<TurnEvent>(100);
 
-        let sessions_ref = Arc::clone(&self.sessions);
-        let sid = session_id.clone();
-
         // Run turn_streamed in a spawned task. The task takes ownership of
         // the whole Session and returns it alongside the result so we can
-        // put the session back into the map afterwards.
+        // put the session back into the map afterwards. Cancelling drops the
+        // turn future; history from the partial turn is discarded.
+        let turn_prompt = prompt.clone();
         let turn_handle = tokio::spawn(async move {
-            let result = session.agent.turn_streamed(&prompt, event_tx).await;
-            (session, result)
+            let history_len = session.agent.history().len();
+            let outcome = tokio::select! {
+                result = session.agent.turn_streamed(&turn_prompt, event_tx) => Some(result),
+                () = cancel.cancelled() => None,
+            };
+            if !matches!(outcome, Some(Ok(_))) {
+                session.agent.truncate_history(history_len);
+            }
+            (session, outcome)
         });
 
-        // Forward events as they arrive
+        // Forward events as they arrive. Tool calls are reported by the
+        // session's ACP tool wrappers, which know the toolCallId.
         while let Some(event) = event_rx.recv().await {
-            let notification = match &event {
-                TurnEvent::Chunk { delta } => JsonRpcNotification {
-                    jsonrpc: "2.0",
-                    method: "session/event",
-                    params: serde_json::json!({
-                        "sessionId": session_id,
-                        "type": "chunk",
-                        "content": delta,
-                    }),
-                },
-                TurnEvent::ToolCall { name, args } => JsonRpcNotification {
-                    jsonrpc: "2.0",
-                    method: "session/event",
-                    params: serde_json::json!({
-                        "sessionId": session_id,
-                        "type": "tool_call",
-                        "name": name,
-                        "args": args,
-                    }),
-                },
-                TurnEvent::ToolResult { name, output } => JsonRpcNotification {
-                    jsonrpc: "2.0",
-                    method: "session/event",
-                    params: serde_json::json!({
-                        "sessionId": session_id,
-                        "type": "tool_result",
-                        "name": name,
-                        "output": output,
-                    }),
-                },
-                TurnEvent::Thinking { delta } => JsonRpcNotification {
-                    jsonrpc: "2.0",
-                    method: "session/event",
-                    params: serde_json::json!({
-                        "sessionId": session_id,
-                        "type": "thinking",
-                        "content": delta,
-                    }),
-                },
-            };
-            self.write_notification(&notification).await;
+            if let Some(update) = turn_event_update(&event) {
+                self.client.session_update(&session_id, update).await;
+            }
         }
 
         // Wait for the turn to complete and recover the session
-        let (mut session, turn_result) = turn_handle.await.map_err(|e| RpcError {
+        let joined = turn_handle.await;
+        self.running.lock().remove(&session_id);
+        let (mut session, outcome) = joined.map_err(|e| RpcError {
             code: INTERNAL_ERROR,
             message: format!("Agent task panicked: {e}"),
             data: None,
         })?;
 
-        let result = turn_result.map_err(|e| RpcError {
-            code: INTERNAL_ERROR,
-            message: format!("Agent turn failed: {e}"),
-            data: None,
-        })?;
-
         // Put the session back
-        {
-            session.last_active = Instant::now();
-            let mut sessions = sessions_ref.lock().await;
-            sessions.insert(sid, session);
+        session.last_active = Instant::now();
+        self.sessions
+            .lock()
+            .await
+            .insert(session_id.clone(), session);
+
+        match outcome {
+            None => {
+                info!("Cancelled turn in session {session_id}");
+                Ok(json!({ "stopReason": "cancelled" }))
+            }
+            Some(Err(e)) => Err(RpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Agent turn failed: {e}"),
+                data: None,
+            }),
+            Some(Ok(response)) => {
+                self.persist(&session_id, &prompt, &response);
+                Ok(json!({ "stopReason": "end_turn" }))
+            }
         }
+    }
 
-        Ok(serde_json::json!({
-            "sessionId": session_id,
-            "content": result,
-        }))
+    fn handle_session_cancel(&self, params: &Value) -> RpcResult {
+        let session_id = session_id_param(params)?;
+        if let Some(cancel) = self.running.lock().get(&session_id) {
+            cancel.cancel();
+        }
+        Ok(Value::Null)
     }
 
     async fn handle_session_stop(&self, params: &Value) -> RpcResult {
-        let session_id = params
-            .get("sessionId")
-            .or_else(|| params.get("session_id"))
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| RpcError {
-                code: INVALID_PARAMS,
-                message: "Missing required parameter: sessionId".to_string(),
-                data: None,
-            })?;
+        let session_id = session_id_param(params)?;
 
         let mut sessions = self.sessions.lock().await;
-        if sessions.remove(session_id).is_some() {
+        if let Some(session) = sessions.remove(&session_id) {
+            session.agent.end_process_session();
             info!("Stopped session {session_id}");
-            Ok(serde_json::json!({
+            Ok(json!({
                 "sessionId": session_id,
                 "stopped": true,
             }))
         } else {
-            Err(RpcError {
-                code: SESSION_NOT_FOUND,
-                message: format!("Session not found: {session_id}"),
-                data: None,
-            })
+            Err(session_not_found(&session_id))
         }
     }
 
-    // ── I/O helpers ──────────────────────────────────────────────
+    // ── Sessions ─────────────────────────────────────────────────
 
-    async fn write_result(&self, id: Value, result: Value) {
-        let response = JsonRpcResponse {
-            jsonrpc: "2.0",
-            result: Some(result),
-            error: None,
-            id,
-        };
-        self.write_json(&response).await;
+    /// Build an agent from the global config and wrap its tools for ACP.
+    async fn create_session(
+        &self,
+        session_id: &str,
+        params: &Value,
+        transcript: &[ChatMessage],
+    ) -> Result<Session, RpcError> {
+        let workspace_dir = params
+            .get("cwd")
+            .or_else(|| params.get("workspaceDir"))
+            .or_else(|| params.get("workspace_dir"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_else(|| self.config.workspace_dir.to_str().unwrap_or("."))
+            .to_string();
+        // Tools are sandboxed to the configured workspace, so a client cwd
+        // outside it would have every file request refused.
+        let workspace = tokio::fs::canonicalize(&self.config.workspace_dir)
+            .await
+            .unwrap_or_else(|_| self.config.workspace_dir.clone());
+        let cwd = tokio::fs::canonicalize(&workspace_dir)
+            .await
+            .unwrap_or_else(|_| PathBuf::from(&workspace_dir));
+        if !cwd.starts_with(&workspace) {
+            return Err(RpcError {
+                code: INVALID_PARAMS,
+                message: format!(
+                    "cwd {} is outside the workspace {}",
+                    cwd.display(),
+                    workspace.display()
+                ),
+                data: None,
+            });
+        }
+
+        let mut agent = Agent::from_config(&self.config)
+            .await
+            .map_err(|e| RpcError {
+                code: INTERNAL_ERROR,
+                message: format!("Failed to create agent: {e}"),
+                data: None,
+            })?;
+        agent.set_memory_session_id(Some(session_id.to_string()));
+
+        let trust = crate::trust::runtime::init(&self.config);
+        let security = Arc::new(
+            SecurityPolicy::from_config(&self.config.autonomy, &self.config.workspace_dir)
+                .with_trust(trust.clone()),
+        );
+        let context = Arc::new(SessionContext {
+            session_id: session_id.to_string(),
+            client: Arc::clone(&self.client),
+            approvals: ApprovalManager::from_config(&self.config.autonomy)
+                .with_trust(trust)
+                .with_rules(Arc::clone(&security.tool_rules)),
+            security,
+            terminal_shell: !shell_is_confined(&self.config),
+            rejected_tools: parking_lot::Mutex::new(HashSet::new()),
+            next_tool_call: AtomicU64::new(0),
+        });
+        agent.replace_tools(|tools| {
+            let mut wrapped: Vec<Box<dyn Tool>> = tools
+                .into_iter()
+                .map(|inner| {
+                    Box::new(AcpTool {
+                        inner,
+                        session: Arc::clone(&context),
+                    }) as Box<dyn Tool>
+                })
+                .collect();
+            wrapped.push(Box::new(AcpPlanTool {
+                session: Arc::clone(&context),
+            }));
+            wrapped
+        });
+        if !transcript.is_empty() {
+            agent.seed_history(transcript);
+        }
+
+        let now = Instant::now();
+        Ok(Session {
+            agent,
+            context,
+            created_at: now,
+            last_active: now,
+            workspace_dir,
+        })
     }
 
-    async fn write_error(&self, id: Value, code: i32, message: &str) {
-        let response = JsonRpcResponse {
-            jsonrpc: "2.0",
-            result: None,
-            error: Some(JsonRpcError {
-                code,
-                message: message.to_string(),
+    async fn insert_session(&self, session_id: String, session: Session) -> Result<(), RpcError> {
+        let mut sessions = self.sessions.lock().await;
+        let active = sessions.len() + self.running.lock().len();
+        if active >= self.acp_config.max_sessions {
+            return Err(RpcError {
+                code: SESSION_LIMIT_REACHED,
+                message: format!(
+                    "Maximum session limit reached ({})",
+                    self.acp_config.max_sessions
+                ),
                 data: None,
-            }),
-            id,
+            });
+        }
+        sessions.insert(session_id, session);
+        Ok(())
+    }
+
+    /// Append a completed exchange to the session transcript.
+    fn persist(&self, session_id: &str, prompt: &str, response: &str) {
+        let Some(store) = self.store.as_ref() else {
+            return;
         };
-        self.write_json(&response).await;
+        let key = store_key(session_id);
+        for message in [ChatMessage::user(prompt), ChatMessage::assistant(response)] {
+            if let Err(e) = store.append(&key, &message) {
+                warn!("Failed to persist ACP session {session_id}: {e}");
+                return;
+            }
+        }
     }
+}
 
-    async fn write_notification(&self, notification: &JsonRpcNotification) {
-        self.write_json(notification).await;
+// ── Protocol helpers ─────────────────────────────────────────────
+
+fn session_id_param(params: &Value) -> Result<String, RpcError> {
+    params
+        .get("sessionId")
+        .or_else(|| params.get("session_id"))
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| RpcError {
+            code: INVALID_PARAMS,
+            message: "Missing required parameter: sessionId".to_string(),
+            data: None,
+        })
+}
+
+fn session_not_found(session_id: &str) -> RpcError {
+    RpcError {
+        code: SESSION_NOT_FOUND,
+        message: format!("Session not found: {session_id}"),
+        data: None,
     }
+}
 
-    async fn write_json<T: Serialize>(&self, value: &T) {
-        match serde_json::to_string(value) {
-            Ok(json) => {
-                let mut stdout = tokio::io::stdout();
-                // Write as a single line followed by newline
-                if let Err(e) = stdout.write_all(json.as_bytes()).await {
-                    error!("Failed to write to stdout: {e}");
-                    return;
-                }
-                if let Err(e) = stdout.write_all(b"\n").await {
-                    error!("Failed to write newline to stdout: {e}");
-                    return;
-                }
-                if let Err(e) = stdout.flush().await {
-                    error!("Failed to flush stdout: {e}");
-                }
+fn session_busy(session_id: &str) -> RpcError {
+    RpcError {
+        code: SESSION_BUSY,
+        message: format!("Session {session_id} is already running a prompt"),
+        data: None,
+    }
+}
+
+/// Session store key for an ACP session transcript.
+fn store_key(session_id: &str) -> String {
+    format!("acp_{session_id}")
+}
+
+/// Flatten an ACP prompt (content blocks, or a plain string from older
+/// clients) into the user message text.
+fn prompt_text(prompt: &Value) -> Option<String> {
+    if let Some(text) = prompt.as_str() {
+        return Some(text.to_string());
+    }
+    let parts: Vec<String> = prompt
+        .as_array()?
+        .iter()
+        .filter_map(|block| match block.get("type")?.as_str()? {
+            "text" => block.get("text")?.as_str().map(str::to_string),
+            "resource_link" => {
+                let uri = block.get("uri")?.as_str()?;
+                let name = block.get("name").and_then(Value::as_str).unwrap_or(uri);
+                Some(format!("[{name}]({uri})"))
             }
-            Err(e) => {
-                error!("Failed to serialize JSON-RPC message: {e}");
+            "resource" => {
+                let resource = block.get("resource")?;
+                let uri = resource.get("uri").and_then(Value::as_str).unwrap_or("");
+                let text = resource.get("text")?.as_str()?;
+                Some(format!("Context from {uri}:\n```\n{text}\n```"))
             }
+            _ => None,
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join("\n\n"))
+}
+
+/// `session/update` payload for a streamed turn event.
+fn turn_event_update(event: &TurnEvent) -> Option<Value> {
+    match event {
+        TurnEvent::Chunk { delta } => Some(json!({
+            "sessionUpdate": "agent_message_chunk",
+            "content": { "type": "text", "text": delta },
+        })),
+        TurnEvent::Thinking { delta } => Some(json!({
+            "sessionUpdate": "agent_thought_chunk",
+            "content": { "type": "text", "text": delta },
+        })),
+        TurnEvent::ToolCall { .. } | TurnEvent::ToolResult { .. } => None,
+    }
+}
+
+/// `session/update` payload replaying a stored message on `session/load`.
+fn replay_update(message: &ChatMessage) -> Option<Value> {
+    let kind = match message.role.as_str() {
+        "user" => "user_message_chunk",
+        "assistant" => "agent_message_chunk",
+        _ => return None,
+    };
+    Some(json!({
+        "sessionUpdate": kind,
+        "content": { "type": "text", "text": message.content },
+    }))
+}
+
+fn tool_call_update(tool_call_id: &str, status: &str, text: &str) -> Value {
+    json!({
+        "sessionUpdate": "tool_call_update",
+        "toolCallId": tool_call_id,
+        "status": status,
+        "content": [{
+            "type": "content",
+            "content": { "type": "text", "text": text },
+        }],
+    })
+}
+
+/// Canonicalise the nearest existing ancestor of `path` and re-append the
+/// rest, since the client may create the file and its missing directories.
+async fn resolve_write_target(path: &Path) -> std::io::Result<PathBuf> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        match tokio::fs::canonicalize(existing).await {
+            Ok(resolved) => {
+                return Ok(missing
+                    .iter()
+                    .rev()
+                    .fold(resolved, |acc, part| acc.join(part)));
+            }
+            Err(e) => match (existing.parent(), existing.file_name()) {
+                (Some(parent), Some(name)) => {
+                    missing.push(name.to_os_string());
+                    existing = parent;
+                }
+                _ => return Err(e),
+            },
+        }
+    }
+}
+
+fn tool_failure(reason: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(reason.into()),
+    }
+}
+
+/// ACP tool kind, which clients use to pick an icon.
+fn tool_kind(name: &str) -> &'static str {
+    match name {
+        "file_read" | "pdf_read" | "read_skill" | "image_info" => "read",
+        "file_write" | "file_edit" | "apply_patch" => "edit",
+        "glob_search" | "content_search" | "web_search_tool" | "memory_recall" | "tool_search" => {
+            "search"
         }
+        "shell" | "process" => "execute",
+        "http_request" | "web_fetch" => "fetch",
+        _ => "other",
     }
 }
 
+/// Short human-readable title for a tool call.
+fn tool_title(name: &str, args: &Value) -> String {
+    let detail = ["command", "path", "pattern", "query", "url"]
+        .iter()
+        .find_map(|key| args.get(*key).and_then(Value::as_str));
+    match detail {
+        Some(detail) => format!(
+            "{name}: {}",
+            crate::util::truncate_with_ellipsis(detail, 80)
+        ),
+        None => name.to_string(),
+    }
+}
+
+fn permission_options() -> Value {
+    json!([
+        { "optionId": "allow_once", "name": "Allow", "kind": "allow_once" },
+        { "optionId": "allow_always", "name": "Always allow", "kind": "allow_always" },
+        { "optionId": "reject_once", "name": "Reject", "kind": "reject_once" },
+        { "optionId": "reject_always", "name": "Always reject", "kind": "reject_always" },
+    ])
+}
+
+/// Map a `session/request_permission` response to an approval decision and
+/// whether the tool should be rejected for the rest of the session.
+fn permission_decision(response: &Value) -> (ApprovalResponse, bool) {
+    let outcome = response.get("outcome");
+    let selected = outcome
+        .filter(|o| o.get("outcome").and_then(Value::as_str) == Some("selected"))
+        .and_then(|o| o.get("optionId"))
+        .and_then(Value::as_str);
+    match selected {
+        Some("allow_once") => (ApprovalResponse::Yes, false),
+        Some("allow_always") => (ApprovalResponse::Always, false),
+        Some("reject_always") => (ApprovalResponse::No, true),
+        _ => (ApprovalResponse::No, false),
+    }
+}
+
+/// Validate `update_plan` entries, filling in the default priority.
+fn plan_entries(args: &Value) -> Option<Vec<Value>> {
+    args.get("entries")?
+        .as_array()?
+        .iter()
+        .map(|entry| {
+            let content = entry.get("content")?.as_str()?;
+            let status = entry
+                .get("status")
+                .and_then(Value::as_str)
+                .unwrap_or("pending");
+            let priority = entry
+                .get("priority")
+                .and_then(Value::as_str)
+                .unwrap_or("medium");
+            let valid = matches!(status, "pending" | "in_progress" | "completed")
+                && matches!(priority, "high" | "medium" | "low");
+            valid.then(|| {
+                json!({
+                    "content": content,
+                    "priority": priority,
+                    "status": status,
+                })
+            })
+        })
+        .collect()
+}
+
 // ── Error helper ─────────────────────────────────────────────────
 
+#[derive(Debug)]
 struct RpcError {
     code: i32,
     message: String,
@@ -503,6 +1558,7 @@ type RpcResult = std::result::Result<Value, RpcError>;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::DuplexStream;
 
     #[test]
     fn acp_server_config_defaults() {
@@ -537,12 +1593,29 @@ mod tests {
 
     #[test]
     fn json_rpc_request_parse_notification() {
-        let json = r#"{"jsonrpc":"2.0","method":"session/event","params":{}}"#;
+        let json = r#"{"jsonrpc":"2.0","method":"session/cancel","params":{}}"#;
         let req: JsonRpcRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(req.method, "session/event");
+        assert_eq!(req.method, "session/cancel");
         assert!(req.id.is_none());
     }
 
+    #[test]
+    fn incoming_message_distinguishes_requests_and_responses() {
+        let request =
+            IncomingMessage::parse(r#"{"jsonrpc":"2.0","method":"session/new","id":1}"#).unwrap();
+        assert!(matches!(request, IncomingMessage::Request(r) if r.method == "session/new"));
+
+        let response = IncomingMessage::parse(
+            r#"{"jsonrpc":"2.0","id":7,"error":{"code":-32603,"message":"boom"}}"#,
+        )
+        .unwrap();
+        let IncomingMessage::Response(response) = response else {
+            panic!("expected a response");
+        };
+        assert_eq!(response.id, 7);
+        assert_eq!(response.error.unwrap().message, "boom");
+    }
+
     #[test]
     fn json_rpc_response_serialize() {
         let resp = JsonRpcResponse {
@@ -582,11 +1655,215 @@ mod tests {
     fn json_rpc_notification_serialize() {
         let notif = JsonRpcNotification {
             jsonrpc: "2.0",
-            method: "session/event",
-            params: serde_json::json!({"type": "chunk", "content": "hello"}),
+            method: "session/update",
+            params: serde_json::json!({"sessionId": "s", "update": {"sessionUpdate": "plan"}}),
         };
         let json = serde_json::to_string(&notif).unwrap();
-        assert!(json.contains(r#""method":"session/event""#));
-        assert!(json.contains(r#""content":"hello""#));
+        assert!(json.contains(r#""method":"session/update""#));
+        assert!(json.contains(r#""sessionUpdate":"plan""#));
+    }
+
+    #[test]
+    fn prompt_text_flattens_content_blocks() {
+        let prompt = json!([
+            { "type": "text", "text": "Fix the bug" },
+            { "type": "resource_link", "uri": "file:///src/lib.rs", "name": "lib.rs" },
+            { "type": "resource", "resource": { "uri": "file:///a.rs", "text": "fn a() {}" } },
+            { "type": "image", "data": "...", "mimeType": "image/png" },
+        ]);
+        let text = prompt_text(&prompt).unwrap();
+        assert!(text.starts_with("Fix the bug\n\n[lib.rs](file:///src/lib.rs)"));
+        assert!(text.contains("Context from file:///a.rs:\n```\nfn a() {}\n```"));
+        assert_eq!(prompt_text(&json!("plain")).as_deref(), Some("plain"));
+        assert!(prompt_text(&json!([{ "type": "image" }])).is_none());
+    }
+
+    #[test]
+    fn permission_decision_maps_options() {
+        let selected = |id: &str| json!({ "outcome": { "outcome": "selected", "optionId": id } });
+        assert_eq!(
+            permission_decision(&selected("allow_once")),
+            (ApprovalResponse::Yes, false)
+        );
+        assert_eq!(
+            permission_decision(&selected("allow_always")),
+            (ApprovalResponse::Always, false)
+        );
+        assert_eq!(
+            permission_decision(&selected("reject_always")),
+            (ApprovalResponse::No, true)
+        );
+        assert_eq!(
+            permission_decision(&json!({ "outcome": { "outcome": "cancelled" } })),
+            (ApprovalResponse::No, false)
+        );
+    }
+
+    #[test]
+    fn plan_entries_validate_status_and_default_priority() {
+        let entries = plan_entries(&json!({
+            "entries": [{ "content": "Write tests", "status": "in_progress" }]
+        }))
+        .unwrap();
+        assert_eq!(entries[0]["priority"], "medium");
+        assert_eq!(entries[0]["status"], "in_progress");
+        assert!(plan_entries(&json!({
+            "entries": [{ "content": "x", "status": "done" }]
+        }))
+        .is_none());
+    }
+
+    #[test]
+    fn turn_events_map_to_session_updates() {
+        let chunk = turn_event_update(&TurnEvent::Chunk { delta: "hi".into() }).unwrap();
+        assert_eq!(chunk["sessionUpdate"], "agent_message_chunk");
+        assert_eq!(chunk["content"]["text"], "hi");
+        let thought = turn_event_update(&TurnEvent::Thinking { delta: "hm".into() }).unwrap();
+        assert_eq!(thought["sessionUpdate"], "agent_thought_chunk");
+        assert!(turn_event_update(&TurnEvent::ToolResult {
+            name: "shell".into(),
+            output: String::new(),
+        })
+        .is_none());
+        assert_eq!(
+            replay_update(&ChatMessage::user("q")).unwrap()["sessionUpdate"],
+            "user_message_chunk"
+        );
+    }
+
+    fn test_server() -> (AcpServer, BufReader<DuplexStream>, tempfile::TempDir) {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let (output, peer) = tokio::io::duplex(64 * 1024);
+        let server = AcpServer::with_output(config, AcpServerConfig::default(), Box::new(output));
+        (server, BufReader::new(peer), tmp)
+    }
+
+    async fn next_message(reader: &mut BufReader<DuplexStream>) -> Value {
+        let mut line = String::new();
+        reader.read_line(&mut line).await.unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    #[test]
+    fn terminal_env_is_the_shell_baseline() {
+        let security = SecurityPolicy {
+            shell_env_passthrough: vec!["PATH".into()],
+            ..SecurityPolicy::default()
+        };
+        let env = terminal_env(&security);
+        assert!(env.iter().any(|var| var["name"] == "PATH"));
+        assert!(env.iter().all(|var| var["name"]
+            .as_str()
+            .is_some_and(|name| !name.contains("KEY"))));
+    }
+
+    #[test]
+    fn confined_shell_is_not_delegated() {
+        let mut config = Config::default();
+        config.security.sandbox.backend = crate::config::SandboxBackend::None;
+        assert!(!shell_is_confined(&config));
+        config.runtime.cgroup.enabled = true;
+        assert!(shell_is_confined(&config));
+    }
+
+    #[tokio::test]
+    async fn initialize_stores_client_capabilities() {
+        let (server, mut peer, _tmp) = test_server();
+        server
+            .state
+            .handle_request(JsonRpcRequest {
+                jsonrpc: "2.0".into(),
+                method: "initialize".into(),
+                params: json!({
+                    "protocolVersion": 1,
+                    "clientCapabilities": {
+                        "fs": { "readTextFile": true, "writeTextFile": false },
+                        "terminal": true,
+                    },
+                }),
+                id: Some(json!(0)),
+            })
+            .await;
+
+        let response = next_message(&mut peer).await;
+        assert_eq!(response["result"]["protocolVersion"], 1);
+        assert_eq!(response["result"]["agentCapabilities"]["loadSession"], true);
+        let capabilities = server.state.client.capabilities();
+        assert!(capabilities.fs.read_text_file);
+        assert!(!capabilities.fs.write_text_file);
+        assert!(capabilities.terminal);
+    }
+
+    #[tokio::test]
+    async fn client_requests_resolve_with_matching_response() {
+        let (server, mut peer, _tmp) = test_server();
+        let client = Arc::clone(&server.state.client);
+        let request = tokio::spawn(async move {
+            client
+                .request("fs/read_text_file", json!({ "path": "/tmp/a" }))
+                .await
+        });
+
+        let sent = next_message(&mut peer).await;
+        assert_eq!(sent["method"], "fs/read_text_file");
+        server.state.client.resolve(JsonRpcIncomingResponse {
+            id: sent["id"].clone(),
+            result: Some(json!({ "content": "hello" })),
+            error: None,
+        });
+        let result = request.await.unwrap().unwrap();
+        assert_eq!(result["content"], "hello");
+    }
+
+    #[tokio::test]
+    async fn prompt_for_unknown_session_is_rejected() {
+        let (server, mut peer, _tmp) = test_server();
+        server
+            .state
+            .handle_request(JsonRpcRequest {
+                jsonrpc: "2.0".into(),
+                method: "session/prompt".into(),
+                params: json!({
+                    "sessionId": "missing",
+                    "prompt": [{ "type": "text", "text": "hi" }],
+                }),
+                id: Some(json!(3)),
+            })
+            .await;
+        let response = next_message(&mut peer).await;
+        assert_eq!(response["error"]["code"], SESSION_NOT_FOUND);
+
+        server
+            .state
+            .handle_request(JsonRpcRequest {
+                jsonrpc: "2.0".into(),
+                method: "session/load".into(),
+                params: json!({ "sessionId": "missing", "cwd": "/tmp", "mcpServers": [] }),
+                id: Some(json!(4)),
+            })
+            .await;
+        let response = next_message(&mut peer).await;
+        assert_eq!(response["error"]["code"], SESSION_NOT_FOUND);
+    }
+    #[tokio::test]
+    async fn new_session_outside_workspace_is_rejected() {
+        let (server, mut peer, _tmp) = test_server();
+        let outside = tempfile::tempdir().unwrap();
+        server
+            .state
+            .handle_request(JsonRpcRequest {
+                jsonrpc: "2.0".into(),
+                method: "session/new".into(),
+                params: json!({ "cwd": outside.path(), "mcpServers": [] }),
+                id: Some(json!(5)),
+            })
+            .await;
+        let response = next_message(&mut peer).await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        assert!(server.state.sessions.lock().await.is_empty());
     }
 }
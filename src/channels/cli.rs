@@ -79,6 +79,7 @@ mod tests {
                 subject: None,
                 thread_ts: None,
                 cancellation_token: None,
+                attachments: vec![],
             })
             .await;
         assert!(result.is_ok());
@@ -94,6 +95,7 @@ mod tests {
                 subject: None,
                 thread_ts: None,
                 cancellation_token: None,
+                attachments: vec![],
             })
             .await;
         assert!(result.is_ok());
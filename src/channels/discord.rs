@@ -1,4 +1,4 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::traits::{ApprovalPrompt, Channel, ChannelMessage, OutboundAttachment, SendMessage};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use parking_lot::Mutex;
@@ -114,6 +114,71 @@ impl DiscordChannel {
         self.allowed_users.iter().any(|u| u == "*" || u == user_id)
     }
 
+    /// Translate an approval prompt button click (a message component
+    /// interaction) into the matching reply command from the clicking user.
+    fn parse_approval_interaction(&self, d: &serde_json::Value) -> Option<ChannelMessage> {
+        // Type 3 = MESSAGE_COMPONENT
+        if d.get("type").and_then(serde_json::Value::as_u64) != Some(3) {
+            return None;
+        }
+        let custom_id = d
+            .get("data")
+            .and_then(|data| data.get("custom_id"))
+            .and_then(serde_json::Value::as_str)?;
+        crate::approval::parse_approval_reply(custom_id)?;
+
+        // Guild interactions carry the user under `member`, DMs under `user`.
+        let user_id = d
+            .get("member")
+            .and_then(|member| member.get("user"))
+            .or_else(|| d.get("user"))
+            .and_then(|user| user.get("id"))
+            .and_then(serde_json::Value::as_str)?;
+        if !self.is_user_allowed(user_id) {
+            tracing::warn!("Discord: ignoring approval click from unauthorized user: {user_id}");
+            return None;
+        }
+        let channel_id = d.get("channel_id").and_then(serde_json::Value::as_str)?;
+        let interaction_id = d.get("id").and_then(serde_json::Value::as_str)?;
+
+        Some(ChannelMessage {
+            id: format!("discord_interaction_{interaction_id}"),
+            sender: user_id.to_string(),
+            reply_target: channel_id.to_string(),
+            content: custom_id.to_string(),
+            channel: "discord".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+            interruption_scope_id: None,
+            attachments: vec![],
+        })
+    }
+
+    /// Acknowledge a component interaction so Discord does not show it as
+    /// failed. Must happen within three seconds of the click.
+    async fn acknowledge_interaction(&self, d: &serde_json::Value) {
+        let (Some(id), Some(token)) = (
+            d.get("id").and_then(serde_json::Value::as_str),
+            d.get("token").and_then(serde_json::Value::as_str),
+        ) else {
+            return;
+        };
+        let url = format!("https://discord.com/api/v10/interactions/{id}/{token}/callback");
+        // Type 6 = DEFERRED_UPDATE_MESSAGE
+        if let Err(e) = self
+            .http_client()
+            .post(&url)
+            .json(&json!({ "type": 6 }))
+            .send()
+            .await
+        {
+            tracing::debug!("Discord: failed to acknowledge interaction {id}: {e}");
+        }
+    }
+
     fn bot_user_id_from_token(token: &str) -> Option<String> {
         // Discord bot tokens are base64(bot_user_id).timestamp.hmac
         let part = token.split('.').next()?;
@@ -412,6 +477,7 @@ async fn send_discord_message_with_files(
     recipient: &str,
     content: &str,
     files: &[PathBuf],
+    attachments: &[OutboundAttachment],
 ) -> anyhow::Result<()> {
     let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
 
@@ -435,6 +501,13 @@ async fn send_discord_message_with_files(
         );
     }
 
+    for (idx, attachment) in attachments.iter().enumerate() {
+        let part = Part::bytes(attachment.data.clone())
+            .file_name(attachment.file_name.clone())
+            .mime_str(attachment.mime())?;
+        form = form.part(format!("files[{}]", files.len() + idx), part);
+    }
+
     let resp = client
         .post(&url)
         .header("Authorization", format!("Bot {bot_token}"))
@@ -819,24 +892,34 @@ impl Channel for DiscordChannel {
             );
             local_files.truncate(10);
         }
+        let attachments =
+            &message.attachments[..message.attachments.len().min(10 - local_files.len())];
+        if attachments.len() < message.attachments.len() {
+            tracing::warn!(
+                count = message.attachments.len(),
+                "discord: dropping outbound attachments beyond the 10-file limit"
+            );
+        }
+        let has_files = !local_files.is_empty() || !attachments.is_empty();
 
         let content =
             with_inline_attachment_urls(&cleaned_content, &remote_urls, &unresolved_markers);
 
         // MultiMessage mode: split at paragraph boundaries and send each as a
         // separate message with a configurable delay between them.
-        if self.stream_mode == crate::config::StreamMode::MultiMessage {
+        if self.stream_mode == crate::config::StreamMode::MultiMessage && attachments.is_empty() {
             let chunks = split_message_for_discord_multi(&content, DISCORD_MAX_MESSAGE_LENGTH);
             let client = self.http_client();
 
             for (i, chunk) in chunks.iter().enumerate() {
-                if i == 0 && !local_files.is_empty() {
+                if i == 0 && has_files {
                     send_discord_message_with_files(
                         &client,
                         &self.bot_token,
                         &message.recipient,
                         chunk,
                         &local_files,
+                        attachments,
                     )
                     .await?;
                 } else {
@@ -873,13 +956,14 @@ impl Channel for DiscordChannel {
         let client = self.http_client();
 
         for (i, chunk) in chunks.iter().enumerate() {
-            if i == 0 && !local_files.is_empty() {
+            if i == 0 && has_files {
                 send_discord_message_with_files(
                     &client,
                     &self.bot_token,
                     &message.recipient,
                     chunk,
                     &local_files,
+                    attachments,
                 )
                 .await?;
             } else {
@@ -1035,8 +1119,22 @@ impl Channel for DiscordChannel {
                         _ => {}
                     }
 
-                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     let event_type = event.get("t").and_then(|t| t.as_str()).unwrap_or("");
+
+                    // Approval prompt buttons arrive as component interactions
+                    if event_type == "INTERACTION_CREATE" {
+                        if let Some(d) = event.get("d") {
+                            self.acknowledge_interaction(d).await;
+                            if let Some(channel_msg) = self.parse_approval_interaction(d) {
+                                if tx.send(channel_msg).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        continue;
+                    }
+
+                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     if event_type != "MESSAGE_CREATE" {
                         continue;
                     }
@@ -1230,6 +1328,10 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     fn supports_draft_updates(&self) -> bool {
         self.stream_mode != crate::config::StreamMode::Off
     }
@@ -1478,6 +1580,7 @@ impl Channel for DiscordChannel {
                         recipient,
                         chunk,
                         &local_files,
+                        &[],
                     )
                     .await?;
                 } else {
@@ -1536,6 +1639,52 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        _thread_ts: Option<&str>,
+        prompt: &ApprovalPrompt,
+    ) -> anyhow::Result<()> {
+        // Button styles: 2 = secondary, 3 = success, 4 = danger
+        let button = |verb: &str, label: &str, style: u8| {
+            json!({
+                "type": 2,
+                "style": style,
+                "label": label,
+                "custom_id": prompt.reply_command(verb)
+            })
+        };
+        let body = json!({
+            "content": prompt.text(),
+            "components": [{
+                "type": 1,
+                "components": [
+                    button("approve", "Approve", 3),
+                    button("always", "Always", 2),
+                    button("deny", "Deny", 4)
+                ]
+            }]
+        });
+
+        let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
+        let resp = self
+            .http_client()
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord approval prompt failed ({status}): {err}");
+        }
+        Ok(())
+    }
+
     async fn add_reaction(
         &self,
         channel_id: &str,
@@ -1602,6 +1751,35 @@ mod tests {
         assert_eq!(ch.name(), "discord");
     }
 
+    #[test]
+    fn approval_button_interaction_maps_to_reply_command() {
+        let ch = DiscordChannel::new("fake".into(), None, vec!["111".into()], false, false);
+        let interaction = |user_id: &str, custom_id: &str| {
+            serde_json::json!({
+                "id": "999",
+                "token": "tok",
+                "type": 3,
+                "channel_id": "222",
+                "member": { "user": { "id": user_id } },
+                "data": { "custom_id": custom_id, "component_type": 2 }
+            })
+        };
+
+        let msg = ch
+            .parse_approval_interaction(&interaction("111", "/always ab12cd34"))
+            .expect("approval click should parse");
+        assert_eq!(msg.sender, "111");
+        assert_eq!(msg.reply_target, "222");
+        assert_eq!(msg.content, "/always ab12cd34");
+
+        assert!(ch
+            .parse_approval_interaction(&interaction("333", "/always ab12cd34"))
+            .is_none());
+        assert!(ch
+            .parse_approval_interaction(&interaction("111", "some_other_button"))
+            .is_none());
+    }
+
     #[test]
     fn base64_decode_bot_id() {
         // "MTIzNDU2" decodes to "123456"
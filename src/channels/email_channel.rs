@@ -14,7 +14,8 @@ use async_imap::types::Fetch;
 use async_imap::Session;
 use async_trait::async_trait;
 use futures_util::TryStreamExt;
-use lettre::message::SinglePart;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 use mail_parser::{MessageParser, MimeHeaders};
@@ -135,6 +136,43 @@ impl EmailChannel {
         }
     }
 
+    /// Build the outgoing message, as multipart/mixed when it carries attachments.
+    fn build_email(&self, message: &SendMessage) -> Result<Message> {
+        // Use explicit subject if provided, otherwise fall back to legacy parsing or default
+        let default_subject = self.config.default_subject.as_str();
+        let (subject, body) = if let Some(ref subj) = message.subject {
+            (subj.as_str(), message.content.as_str())
+        } else if message.content.starts_with("Subject: ") {
+            if let Some(pos) = message.content.find('\n') {
+                (&message.content[9..pos], message.content[pos + 1..].trim())
+            } else {
+                (default_subject, message.content.as_str())
+            }
+        } else {
+            (default_subject, message.content.as_str())
+        };
+
+        let builder = Message::builder()
+            .from(self.config.from_address.parse()?)
+            .to(message.recipient.parse()?)
+            .subject(subject);
+
+        if message.attachments.is_empty() {
+            return Ok(builder.singlepart(SinglePart::plain(body.to_string()))?);
+        }
+
+        let mut parts = MultiPart::mixed().singlepart(SinglePart::plain(body.to_string()));
+        for attachment in &message.attachments {
+            let content_type = ContentType::parse(attachment.mime())
+                .or_else(|_| ContentType::parse("application/octet-stream"))?;
+            parts = parts.singlepart(
+                Attachment::new(attachment.file_name.clone())
+                    .body(attachment.data.clone(), content_type),
+            );
+        }
+        Ok(builder.multipart(parts)?)
+    }
+
     /// Check if a sender email is in the allowlist
     pub fn is_sender_allowed(&self, email: &str) -> bool {
         if self.config.allowed_senders.is_empty() {
@@ -531,32 +569,17 @@ impl Channel for EmailChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> Result<()> {
-        // Use explicit subject if provided, otherwise fall back to legacy parsing or default
-        let default_subject = self.config.default_subject.as_str();
-        let (subject, body) = if let Some(ref subj) = message.subject {
-            (subj.as_str(), message.content.as_str())
-        } else if message.content.starts_with("Subject: ") {
-            if let Some(pos) = message.content.find('\n') {
-                (&message.content[9..pos], message.content[pos + 1..].trim())
-            } else {
-                (default_subject, message.content.as_str())
-            }
-        } else {
-            (default_subject, message.content.as_str())
-        };
-
-        let email = Message::builder()
-            .from(self.config.from_address.parse()?)
-            .to(message.recipient.parse()?)
-            .subject(subject)
-            .singlepart(SinglePart::plain(body.to_string()))?;
-
+        let email = self.build_email(message)?;
         let transport = self.create_smtp_transport()?;
         transport.send(&email)?;
         info!("Email sent to {}", message.recipient);
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> Result<()> {
         info!(
             "Starting email channel with IDLE support on {}",
@@ -649,6 +672,30 @@ mod tests {
         assert_eq!(seen.len(), 2);
     }
 
+    #[test]
+    fn build_email_adds_attachments_as_mixed_parts() {
+        let channel = EmailChannel::new(EmailConfig {
+            from_address: "bot@example.com".into(),
+            ..EmailConfig::default()
+        });
+        let message = SendMessage::with_subject("See attached", "user@example.com", "Report")
+            .with_attachments(vec![crate::channels::OutboundAttachment::new(
+                "report.csv",
+                b"a,b\n1,2\n".to_vec(),
+            )]);
+
+        let formatted =
+            String::from_utf8(channel.build_email(&message).unwrap().formatted()).unwrap();
+        assert!(formatted.contains("multipart/mixed"));
+        assert!(formatted.contains("Content-Disposition: attachment; filename=\"report.csv\""));
+        assert!(formatted.contains("See attached"));
+
+        let plain = SendMessage::new("Hello", "user@example.com");
+        let formatted =
+            String::from_utf8(channel.build_email(&plain).unwrap().formatted()).unwrap();
+        assert!(!formatted.contains("multipart"));
+    }
+
     // EmailConfig tests
 
     #[test]
@@ -1,4 +1,4 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::traits::{ApprovalPrompt, Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use base64::Engine as _;
 use futures_util::{SinkExt, StreamExt};
@@ -270,6 +270,52 @@ fn build_interactive_card_body(recipient: &str, markdown: &str) -> serde_json::V
     })
 }
 
+/// Key under which approval card buttons carry their reply command in the
+/// `card.action.trigger` callback value.
+const LARK_APPROVAL_ACTION_KEY: &str = "zeroclaw_approval";
+
+/// Build an approval card: the prompt as markdown plus Approve / Always /
+/// Deny buttons whose callback value is the equivalent reply command.
+fn build_approval_card_body(recipient: &str, prompt: &ApprovalPrompt) -> serde_json::Value {
+    let columns: Vec<serde_json::Value> = [
+        ("Approve", "approve", "primary"),
+        ("Always", "always", "default"),
+        ("Deny", "deny", "danger"),
+    ]
+    .iter()
+    .map(|(label, verb, style)| {
+        serde_json::json!({
+            "tag": "column",
+            "width": "auto",
+            "elements": [{
+                "tag": "button",
+                "text": { "tag": "plain_text", "content": label },
+                "type": style,
+                "behaviors": [{
+                    "type": "callback",
+                    "value": { LARK_APPROVAL_ACTION_KEY: prompt.reply_command(verb) }
+                }]
+            }]
+        })
+    })
+    .collect();
+
+    let content = serde_json::json!({
+        "schema": "2.0",
+        "body": {
+            "elements": [
+                { "tag": "markdown", "content": prompt.text() },
+                { "tag": "column_set", "columns": columns }
+            ]
+        }
+    });
+    serde_json::json!({
+        "receive_id": recipient,
+        "msg_type": "interactive",
+        "content": content.to_string(),
+    })
+}
+
 /// Split markdown content into chunks that fit within the card size limit.
 /// Splits on line boundaries to avoid breaking markdown syntax.
 fn split_markdown_chunks(text: &str, max_bytes: usize) -> Vec<&str> {
@@ -882,6 +928,12 @@ impl LarkChannel {
                         Ok(e) => e,
                         Err(e) => { tracing::error!("Lark: event JSON: {e}"); continue; }
                     };
+                    if event.header.event_type == "card.action.trigger" {
+                        if let Some(channel_msg) = self.parse_approval_card_action(&event.event) {
+                            if tx.send(channel_msg).await.is_err() { break; }
+                        }
+                        continue;
+                    }
                     if event.header.event_type != "im.message.receive_v1" { continue; }
 
                     let event_payload = event.event;
@@ -1044,6 +1096,71 @@ impl LarkChannel {
         self.allowed_users.iter().any(|u| u == "*" || u == open_id)
     }
 
+    /// Map an approval card button click (`card.action.trigger`) to the
+    /// equivalent reply command so it flows through the normal approval
+    /// reply path. Other card actions are ignored.
+    fn parse_approval_card_action(&self, event: &serde_json::Value) -> Option<ChannelMessage> {
+        let command = event
+            .pointer("/action/value")
+            .and_then(|v| v.get(LARK_APPROVAL_ACTION_KEY))
+            .and_then(|v| v.as_str())?;
+        crate::approval::parse_approval_reply(command)?;
+
+        let open_id = event
+            .pointer("/operator/open_id")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        if !self.is_user_allowed(open_id) {
+            tracing::warn!("Lark: ignoring approval click from {open_id} (not in allowed_users)");
+            return None;
+        }
+
+        // Regular messages use the chat id as sender, so approvals match the
+        // requester recorded when the prompt was sent.
+        let chat_id = event
+            .pointer("/context/open_chat_id")
+            .and_then(|v| v.as_str())?;
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: chat_id.to_string(),
+            reply_target: chat_id.to_string(),
+            content: command.to_string(),
+            channel: self.channel_name().to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: None,
+            interruption_scope_id: None,
+            attachments: vec![],
+        })
+    }
+
+    /// Send a message body, refreshing the tenant token once if it expired.
+    async fn send_body(&self, token: &str, body: &serde_json::Value) -> anyhow::Result<()> {
+        let url = self.send_message_url();
+        let (status, response) = self.send_text_once(&url, token, body).await?;
+
+        if should_refresh_lark_tenant_token(status, &response) {
+            // Token expired/invalid, invalidate and retry once.
+            self.invalidate_token().await;
+            let new_token = self.get_tenant_access_token().await?;
+            let (retry_status, retry_response) =
+                self.send_text_once(&url, &new_token, body).await?;
+
+            if should_refresh_lark_tenant_token(retry_status, &retry_response) {
+                anyhow::bail!(
+                    "Lark send failed after token refresh: status={retry_status}, body={retry_response}"
+                );
+            }
+
+            ensure_lark_send_success(retry_status, &retry_response, "after token refresh")
+        } else {
+            ensure_lark_send_success(status, &response, "without token refresh")
+        }
+    }
+
     /// Get or refresh tenant access token
     async fn get_tenant_access_token(&self) -> anyhow::Result<String> {
         // Check cache first
@@ -1466,6 +1583,13 @@ impl LarkChannel {
             .pointer("/header/event_type")
             .and_then(|e| e.as_str())
             .unwrap_or("");
+        if event_type == "card.action.trigger" {
+            return payload
+                .get("event")
+                .and_then(|event| self.parse_approval_card_action(event))
+                .into_iter()
+                .collect();
+        }
         if event_type != "im.message.receive_v1" {
             return vec![];
         }
@@ -1786,36 +1910,27 @@ impl Channel for LarkChannel {
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         let token = self.get_tenant_access_token().await?;
-        let url = self.send_message_url();
 
         let chunks = split_markdown_chunks(&message.content, LARK_CARD_MARKDOWN_MAX_BYTES);
         for chunk in &chunks {
             let body = build_interactive_card_body(&message.recipient, chunk);
-
-            let (status, response) = self.send_text_once(&url, &token, &body).await?;
-
-            if should_refresh_lark_tenant_token(status, &response) {
-                // Token expired/invalid, invalidate and retry once.
-                self.invalidate_token().await;
-                let new_token = self.get_tenant_access_token().await?;
-                let (retry_status, retry_response) =
-                    self.send_text_once(&url, &new_token, &body).await?;
-
-                if should_refresh_lark_tenant_token(retry_status, &retry_response) {
-                    anyhow::bail!(
-                        "Lark send failed after token refresh: status={retry_status}, body={retry_response}"
-                    );
-                }
-
-                ensure_lark_send_success(retry_status, &retry_response, "after token refresh")?;
-            } else {
-                ensure_lark_send_success(status, &response, "without token refresh")?;
-            }
+            self.send_body(&token, &body).await?;
         }
 
         Ok(())
     }
 
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        _thread_ts: Option<&str>,
+        prompt: &ApprovalPrompt,
+    ) -> anyhow::Result<()> {
+        let token = self.get_tenant_access_token().await?;
+        let body = build_approval_card_body(recipient, prompt);
+        self.send_body(&token, &body).await
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         use crate::config::schema::LarkReceiveMode;
         match self.receive_mode {
@@ -3488,6 +3603,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn approval_card_click_maps_to_reply_command() {
+        let ch = make_channel();
+        let prompt = ApprovalPrompt {
+            request_id: "ab12cd34".into(),
+            tool_name: "shell".into(),
+            arguments_summary: "ls".into(),
+            requester: "oc_chat1".into(),
+            timeout_secs: 60,
+        };
+        let body = build_approval_card_body("oc_chat1", &prompt);
+        let card: serde_json::Value =
+            serde_json::from_str(body["content"].as_str().unwrap()).unwrap();
+        let deny = &card["body"]["elements"][1]["columns"][2]["elements"][0];
+        assert_eq!(
+            deny["behaviors"][0]["value"][LARK_APPROVAL_ACTION_KEY],
+            "/deny ab12cd34"
+        );
+
+        let event = serde_json::json!({
+            "operator": { "open_id": "ou_testuser123" },
+            "action": { "value": { LARK_APPROVAL_ACTION_KEY: "/approve ab12cd34" } },
+            "context": { "open_chat_id": "oc_chat1", "open_message_id": "om_1" }
+        });
+        let msg = ch.parse_approval_card_action(&event).unwrap();
+        assert_eq!(msg.content, "/approve ab12cd34");
+        assert_eq!(msg.sender, "oc_chat1");
+        assert_eq!(msg.reply_target, "oc_chat1");
+
+        let stranger = serde_json::json!({
+            "operator": { "open_id": "ou_stranger" },
+            "action": { "value": { LARK_APPROVAL_ACTION_KEY: "/approve ab12cd34" } },
+            "context": { "open_chat_id": "oc_chat1" }
+        });
+        assert!(ch.parse_approval_card_action(&stranger).is_none());
+
+        let other = serde_json::json!({
+            "operator": { "open_id": "ou_testuser123" },
+            "action": { "value": { "other": "x" } },
+            "context": { "open_chat_id": "oc_chat1" }
+        });
+        assert!(ch.parse_approval_card_action(&other).is_none());
+    }
+
     #[test]
     fn split_markdown_chunks_single_chunk_for_small_content() {
         let text = "Hello world";
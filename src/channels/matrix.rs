@@ -1,6 +1,7 @@
 use crate::channels::traits::{Channel, ChannelMessage, SendMessage};
 use async_trait::async_trait;
 use matrix_sdk::{
+    attachment::AttachmentConfig,
     authentication::matrix::MatrixSession,
     config::SyncSettings,
     ruma::{
@@ -841,7 +842,25 @@ impl Channel for MatrixChannel {
             }
         }
 
-        room.send(content).await?;
+        if !message.content.trim().is_empty() || message.attachments.is_empty() {
+            room.send(content).await?;
+        }
+
+        // The SDK encrypts uploads for E2EE rooms, so attachments go through it
+        // rather than the raw media endpoint used for voice replies below.
+        for attachment in &message.attachments {
+            let mime: mime_guess::mime::Mime = attachment
+                .mime()
+                .parse()
+                .unwrap_or(mime_guess::mime::APPLICATION_OCTET_STREAM);
+            room.send_attachment(
+                attachment.file_name.clone(),
+                &mime,
+                attachment.data.clone(),
+                AttachmentConfig::new(),
+            )
+            .await?;
+        }
 
         // Voice reply: generate TTS audio and send as m.audio when voice_mode is active
         if self.voice_mode.load(Ordering::Relaxed) {
@@ -1504,6 +1523,10 @@ impl Channel for MatrixChannel {
 
     // ── Streaming support ──────────────────────────────────────────
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     fn supports_draft_updates(&self) -> bool {
         self.stream_mode != crate::config::StreamMode::Off
     }
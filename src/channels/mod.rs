@@ -89,7 +89,9 @@ pub use reddit::RedditChannel;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
 pub use telegram::TelegramChannel;
-pub use traits::{Channel, SendMessage};
+#[allow(unused_imports)]
+pub use traits::OutboundAttachment;
+pub use traits::{ApprovalPrompt, Channel, SendMessage};
 #[allow(unused_imports)]
 pub use tts::{TtsManager, TtsProvider};
 pub use twitter::TwitterChannel;
@@ -112,6 +114,7 @@ use crate::approval::ApprovalManager;
 use crate::config::Config;
 use crate::identity;
 use crate::memory::{self, Memory};
+use crate::observability::trace_context::{SpanKind, TraceSpan};
 use crate::observability::traits::{ObserverEvent, ObserverMetric};
 use crate::observability::{self, runtime_trace, Observer};
 use crate::providers::reliable::{scope_provider_fallback, take_last_provider_fallback};
@@ -388,8 +391,9 @@ struct ChannelRuntimeContext {
     session_store: Option<Arc<session_store::SessionStore>>,
     /// Non-interactive approval manager for channel-driven runs.
     /// Enforces `auto_approve` / `always_ask` / supervised policy from
-    /// `[autonomy]` config; auto-denies tools that would need interactive
-    /// approval since no operator is present on channel runs.
+    /// `[autonomy]` config; tools that would need interactive approval are
+    /// prompted for in the chat when `[autonomy.channel_approval]` is enabled
+    /// and auto-denied otherwise.
     approval_manager: Arc<ApprovalManager>,
     activated_tools: Option<std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>>,
     cost_tracking: Option<ChannelCostTrackingState>,
@@ -452,6 +456,46 @@ fn conversation_history_key(msg: &traits::ChannelMessage) -> String {
     }
 }
 
+/// Where approval prompts for this message's tool calls go: the configured
+/// operator channel when it is registered, otherwise the originating chat.
+fn channel_approval_context(
+    ctx: &ChannelRuntimeContext,
+    msg: &traits::ChannelMessage,
+    target_channel: Option<&Arc<dyn Channel>>,
+) -> Option<crate::approval::ChannelApprovalContext> {
+    if msg.channel == "cli" || !ctx.approval_manager.channel_approvals_enabled() {
+        return None;
+    }
+
+    if let Some((operator_channel, operator_target)) = ctx.approval_manager.approval_operator() {
+        if let Some(channel) = ctx.channels_by_name.get(operator_channel) {
+            return Some(crate::approval::ChannelApprovalContext {
+                channel: Arc::clone(channel),
+                channel_name: operator_channel.to_string(),
+                reply_target: operator_target.to_string(),
+                thread_ts: None,
+                requester: msg.sender.clone(),
+                origin_channel: msg.channel.clone(),
+                origin_reply_target: msg.reply_target.clone(),
+            });
+        }
+        tracing::warn!(
+            channel = %operator_channel,
+            "Approval operator channel is not running; prompting in the originating chat"
+        );
+    }
+
+    Some(crate::approval::ChannelApprovalContext {
+        channel: Arc::clone(target_channel?),
+        channel_name: msg.channel.clone(),
+        reply_target: msg.reply_target.clone(),
+        thread_ts: msg.thread_ts.clone(),
+        requester: msg.sender.clone(),
+        origin_channel: msg.channel.clone(),
+        origin_reply_target: msg.reply_target.clone(),
+    })
+}
+
 fn followup_thread_id(msg: &traits::ChannelMessage) -> Option<String> {
     msg.thread_ts.clone().or_else(|| Some(msg.id.clone()))
 }
@@ -1050,6 +1094,8 @@ fn clear_sender_history(ctx: &ChannelRuntimeContext, sender_key: &str) {
         .lock()
         .unwrap_or_else(|e| e.into_inner())
         .remove(sender_key);
+    // Background processes belong to the conversation session that ended.
+    crate::tools::process::end_session(sender_key);
 }
 
 fn mark_sender_for_new_session(ctx: &ChannelRuntimeContext, sender_key: &str) {
@@ -2320,6 +2366,12 @@ async fn process_channel_message(
         msg
     };
 
+    crate::routines::bus::publish(crate::routines::RoutineEvent::new(
+        crate::routines::bus::SOURCE_CHANNEL,
+        msg.channel.clone(),
+        Some(msg.content.clone()),
+    ));
+
     // ── Media pipeline: enrich inbound message with media annotations ──
     if ctx.media_pipeline.enabled && !msg.attachments.is_empty() {
         let vision = ctx.provider.supports_vision();
@@ -2752,52 +2804,68 @@ async fn process_channel_message(
     let cost_tracking_context = ctx.cost_tracking.clone().map(|state| {
         crate::agent::loop_::ToolLoopCostTrackingContext::new(state.tracker, state.prices)
     });
+    let approval_context = channel_approval_context(ctx.as_ref(), &msg, target_channel.as_ref());
+    // Leave room for one approval wait on top of the normal turn budget.
+    let timeout_budget_secs = if approval_context.is_some() {
+        timeout_budget_secs.saturating_add(ctx.approval_manager.channel_approval_timeout_secs())
+    } else {
+        timeout_budget_secs
+    };
     let llm_call_start = Instant::now();
     #[allow(clippy::cast_possible_truncation)]
     let elapsed_before_llm_ms = started_at.elapsed().as_millis() as u64;
     tracing::info!(elapsed_before_llm_ms, "⏱ Starting LLM call");
     let (llm_result, fallback_info) = scope_provider_fallback(async {
         let llm_result = loop {
+            let turn = crate::agent::loop_::TOOL_LOOP_COST_TRACKING_CONTEXT.scope(
+                cost_tracking_context.clone(),
+                crate::approval::CHANNEL_APPROVAL_CONTEXT.scope(
+                    approval_context.clone(),
+                    crate::tools::process::PROCESS_SESSION.scope(
+                        history_key.clone(),
+                        crate::observability::trace_context::scope_conversation(
+                            history_key.clone(),
+                            Box::pin(run_tool_call_loop(
+                                active_provider.as_ref(),
+                                &mut history,
+                                ctx.tools_registry.as_ref(),
+                                notify_observer.as_ref() as &dyn Observer,
+                                route.provider.as_str(),
+                                route.model.as_str(),
+                                runtime_defaults.temperature,
+                                true,
+                                Some(&*ctx.approval_manager),
+                                Some(ctx.approval_manager.tool_rules()),
+                                msg.channel.as_str(),
+                                Some(msg.reply_target.as_str()),
+                                &ctx.multimodal,
+                                ctx.max_tool_iterations,
+                                Some(cancellation_token.clone()),
+                                delta_tx.clone(),
+                                ctx.hooks.as_deref(),
+                                if msg.channel == "cli" || ctx.autonomy_level == AutonomyLevel::Full
+                                {
+                                    &[]
+                                } else {
+                                    ctx.non_cli_excluded_tools.as_ref()
+                                },
+                                ctx.tool_call_dedup_exempt.as_ref(),
+                                ctx.activated_tools.as_ref(),
+                                Some(model_switch_callback.clone()),
+                                &ctx.pacing,
+                                ctx.max_tool_result_chars,
+                                ctx.context_token_budget,
+                                None, // shared_budget
+                            )),
+                        ),
+                    ),
+                ),
+            );
             let loop_result = tokio::select! {
                 () = cancellation_token.cancelled() => LlmExecutionResult::Cancelled,
-                result = tokio::time::timeout(
-                    Duration::from_secs(timeout_budget_secs),
-                    crate::agent::loop_::TOOL_LOOP_COST_TRACKING_CONTEXT.scope(
-                        cost_tracking_context.clone(),
-                    run_tool_call_loop(
-                        active_provider.as_ref(),
-                        &mut history,
-                        ctx.tools_registry.as_ref(),
-                        notify_observer.as_ref() as &dyn Observer,
-                        route.provider.as_str(),
-                        route.model.as_str(),
-                        runtime_defaults.temperature,
-                        true,
-                        Some(&*ctx.approval_manager),
-                        msg.channel.as_str(),
-                        Some(msg.reply_target.as_str()),
-                        &ctx.multimodal,
-                        ctx.max_tool_iterations,
-                        Some(cancellation_token.clone()),
-                        delta_tx.clone(),
-                        ctx.hooks.as_deref(),
-                        if msg.channel == "cli"
-                            || ctx.autonomy_level == AutonomyLevel::Full
-                        {
-                            &[]
-                        } else {
-                            ctx.non_cli_excluded_tools.as_ref()
-                        },
-                        ctx.tool_call_dedup_exempt.as_ref(),
-                        ctx.activated_tools.as_ref(),
-                        Some(model_switch_callback.clone()),
-                        &ctx.pacing,
-                        ctx.max_tool_result_chars,
-                        ctx.context_token_budget,
-                        None, // shared_budget
-                    ),
-                    ),
-                ) => LlmExecutionResult::Completed(result),
+                result = tokio::time::timeout(Duration::from_secs(timeout_budget_secs), turn) => {
+                    LlmExecutionResult::Completed(result)
+                }
             };
 
             // Handle model switch: re-create the provider and retry
@@ -3315,7 +3383,19 @@ async fn dispatch_worker(
         }
     }
 
-    process_channel_message(ctx, msg, cancellation_token).await;
+    // Root of the trace: the turn, LLM calls and tool calls nest under it.
+    let message_span = TraceSpan::start(format!("process {}", msg.channel), SpanKind::Consumer)
+        .with_attribute("messaging.system", msg.channel.as_str())
+        .with_attribute("messaging.operation.type", "process")
+        .with_attribute("messaging.message.id", msg.id.as_str());
+    message_span
+        .scope(Box::pin(process_channel_message(
+            ctx,
+            msg,
+            cancellation_token,
+        )))
+        .await;
+    message_span.end();
 
     if register_in_flight {
         let mut active = in_flight.lock().await;
@@ -3387,6 +3467,30 @@ async fn run_message_dispatch_loop(
             continue;
         }
 
+        // Fast path: answers to pending approval prompts. The conversation that
+        // asked is blocked waiting for them, so they must bypass per-sender
+        // serialization and never reach the agent.
+        if msg.channel != "cli" {
+            let outcome = ctx.approval_manager.resolve_channel_reply(&msg);
+            if let Some(ack) = outcome.acknowledgement() {
+                if let Some(channel) = ctx.channels_by_name.get(&msg.channel).or_else(|| {
+                    msg.channel
+                        .split_once(':')
+                        .and_then(|(base, _)| ctx.channels_by_name.get(base))
+                }) {
+                    let channel = Arc::clone(channel);
+                    let reply_target = msg.reply_target.clone();
+                    let thread_ts = msg.thread_ts.clone();
+                    tokio::spawn(async move {
+                        let _ = channel
+                            .send(&SendMessage::new(ack, &reply_target).in_thread(thread_ts))
+                            .await;
+                    });
+                }
+                continue;
+            }
+        }
+
         // ── Debounce: accumulate rapid messages per sender ──────────
         // CLI messages bypass debouncing so the interactive loop stays responsive.
         let msg = if msg.channel != "cli" && ctx.debouncer.enabled() {
@@ -4832,6 +4936,7 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
 /// Start all configured channels and route messages to the agent
 #[allow(clippy::too_many_lines)]
 pub async fn start_channels(config: Config) -> Result<()> {
+    let trust = crate::trust::runtime::init(&config);
     let provider_name = resolved_default_provider(&config);
     let provider_runtime_options = providers::ProviderRuntimeOptions {
         auth_profile_override: None,
@@ -4844,6 +4949,11 @@ pub async fn start_channels(config: Config) -> Result<()> {
         extra_headers: config.extra_headers.clone(),
         api_path: config.api_path.clone(),
         provider_max_tokens: config.provider_max_tokens,
+        provider_record_path: config
+            .provider_record_path
+            .as_ref()
+            .map(std::path::PathBuf::from),
+        budget_routing: providers::router::BudgetRouting::from_config(&config),
     };
     let provider: Arc<dyn Provider> = Arc::from(
         create_resilient_provider_nonblocking(
@@ -4878,12 +4988,13 @@ pub async fn start_channels(config: Config) -> Result<()> {
 
     let observer: Arc<dyn Observer> =
         Arc::from(observability::create_observer(&config.observability));
-    let runtime: Arc<dyn runtime::RuntimeAdapter> =
-        Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
+    let runtime: Arc<dyn runtime::RuntimeAdapter> = Arc::from(
+        runtime::create_runtime_with_observer(&config.runtime, Some(observer.clone()))?,
+    );
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_trust(trust.clone()),
+    );
     let model = resolved_default_model(&config);
     let temperature = config.default_temperature;
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory_with_storage_and_routes(
@@ -4903,12 +5014,12 @@ pub async fn start_channels(config: Config) -> Result<()> {
     };
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
-    let sop_engine = crate::sop::create_sop_engine(&config.sop, &config.workspace_dir);
+    let sop_engine = crate::sop::shared_sop_engine(&config.sop, &config.workspace_dir);
     let (
         mut built_tools,
         delegate_handle_ch,
         reaction_handle_ch,
-        _channel_map_handle,
+        channel_map_handle_ch,
         ask_user_handle_ch,
         escalate_handle_ch,
     ) = tools::all_tools_with_runtime(
@@ -4933,6 +5044,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
     // When `deferred_loading` is enabled, MCP tools are NOT added eagerly.
     // Instead, a `tool_search` built-in is registered for on-demand loading.
     let mut deferred_section = String::new();
+    let mut mcp_context_section = String::new();
     let mut ch_activated_handle: Option<
         std::sync::Arc<std::sync::Mutex<crate::tools::ActivatedToolSet>>,
     > = None;
@@ -4941,7 +5053,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
             "Initializing MCP client — {} server(s) configured",
             config.mcp.servers.len()
         );
-        match crate::tools::McpRegistry::connect_all(&config.mcp.servers).await {
+        match crate::tools::McpRegistry::connect_from_config(&config).await {
             Ok(registry) => {
                 let registry = std::sync::Arc::new(registry);
                 if config.mcp.deferred_loading {
@@ -4988,6 +5100,14 @@ pub async fn start_channels(config: Config) -> Result<()> {
                         registry.server_count()
                     );
                 }
+                let resource_servers = registry.resource_servers().await;
+                if !resource_servers.is_empty() {
+                    built_tools.push(Box::new(crate::tools::McpResourcesTool::new(
+                        std::sync::Arc::clone(&registry),
+                        resource_servers,
+                    )));
+                }
+                mcp_context_section = registry.resource_context_section().await;
             }
             Err(e) => {
                 // Non-fatal — daemon continues with the tools registered above.
@@ -5116,6 +5236,11 @@ pub async fn start_channels(config: Config) -> Result<()> {
         system_prompt.push_str(&deferred_section);
     }
 
+    // Append configured MCP context resources
+    if !mcp_context_section.is_empty() {
+        system_prompt.push_str(&mcp_context_section);
+    }
+
     if !skills.is_empty() {
         println!(
             "  🧩 Skills:   {}",
@@ -5210,6 +5335,14 @@ pub async fn start_channels(config: Config) -> Result<()> {
         }
     }
 
+    // Populate the channel map shared by the poll and file-delivering tools.
+    {
+        let mut map = channel_map_handle_ch.write();
+        for (name, ch) in channels_by_name.as_ref() {
+            map.insert(name.clone(), Arc::clone(ch));
+        }
+    }
+
     // Populate the ask_user tool's channel map now that channels are initialized.
     if let Some(ref handle) = ask_user_handle_ch {
         let mut map = handle.write();
@@ -5329,7 +5462,11 @@ pub async fn start_channels(config: Config) -> Result<()> {
         } else {
             None
         },
-        approval_manager: Arc::new(ApprovalManager::for_non_interactive(&config.autonomy)),
+        approval_manager: Arc::new(
+            ApprovalManager::for_non_interactive(&config.autonomy)
+                .with_trust(trust)
+                .with_rules(Arc::clone(&security.tool_rules)),
+        ),
         activated_tools: ch_activated_handle,
         cost_tracking: crate::cost::CostTracker::get_or_init_global(
             config.cost.clone(),
@@ -10431,6 +10568,7 @@ This is an example JSON object for profile settings."#;
             provider: "vision-provider".into(),
             model: "gpt-4-vision".into(),
             api_key: None,
+            context_window: None,
         }];
 
         let runtime_ctx = Arc::new(ChannelRuntimeContext {
@@ -10549,6 +10687,7 @@ This is an example JSON object for profile settings."#;
             provider: "vision-provider".into(),
             model: "gpt-4-vision".into(),
             api_key: None,
+            context_window: None,
         }];
 
         let runtime_ctx = Arc::new(ChannelRuntimeContext {
@@ -10659,6 +10798,7 @@ This is an example JSON object for profile settings."#;
             provider: "vision-provider".into(),
             model: "gpt-4-vision".into(),
             api_key: None,
+            context_window: None,
         }];
 
         let runtime_ctx = Arc::new(ChannelRuntimeContext {
@@ -10782,12 +10922,14 @@ This is an example JSON object for profile settings."#;
                 provider: "fast-provider".into(),
                 model: "fast-model".into(),
                 api_key: None,
+                context_window: None,
             },
             crate::config::ModelRouteConfig {
                 hint: "code".into(),
                 provider: "code-provider".into(),
                 model: "code-model".into(),
                 api_key: None,
+                context_window: None,
             },
         ];
 
@@ -70,10 +70,11 @@ pub async fn run_mqtt_sop_listener(
                     topic: Some(topic),
                     payload: Some(payload),
                     timestamp: now_iso8601(),
+                    idempotency_key: None,
                 };
 
                 let results = dispatch_sop_event(&engine, &audit, event).await;
-                process_headless_results(&results);
+                process_headless_results(&engine, &results);
             }
             Ok(Event::Incoming(Packet::ConnAck(_))) => {
                 crate::health::mark_component_ok("mqtt");
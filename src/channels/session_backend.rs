@@ -2,7 +2,8 @@
 //!
 //! Backends store per-sender conversation histories. The trait is intentionally
 //! minimal — load, append, remove_last, list — so that JSONL and SQLite (and
-//! future backends) share a common interface.
+//! future backends) share a common interface. Backends that keep a branch tree
+//! (SQLite) additionally support forking and rewinding via [`SessionBackend::checkout`].
 
 use crate::providers::traits::ChatMessage;
 use chrono::{DateTime, Utc};
@@ -22,6 +23,32 @@ pub struct SessionMetadata {
     pub message_count: usize,
 }
 
+/// A persisted message together with its position in the session's branch tree.
+#[derive(Debug, Clone)]
+pub struct SessionMessage {
+    /// Stable message ID, usable as a fork/rewind target.
+    pub id: i64,
+    /// ID of the preceding message on the same branch (`None` for the first message).
+    pub parent_id: Option<i64>,
+    /// The message itself.
+    pub message: ChatMessage,
+}
+
+/// A branch of a session's conversation tree, identified by its leaf message.
+#[derive(Debug, Clone)]
+pub struct SessionBranch {
+    /// ID of the last message on the branch.
+    pub head_id: i64,
+    /// ID of the message this branch diverged from (`None` if it diverges at the root).
+    pub fork_id: Option<i64>,
+    /// Number of messages from the root to `head_id`.
+    pub message_count: usize,
+    /// When the branch head was written.
+    pub last_activity: DateTime<Utc>,
+    /// Whether the session currently continues from this branch.
+    pub active: bool,
+}
+
 /// Query parameters for listing sessions.
 #[derive(Debug, Clone, Default)]
 pub struct SessionQuery {
@@ -120,6 +147,42 @@ pub trait SessionBackend: Send + Sync {
     fn list_stuck_sessions(&self, _threshold_secs: u64) -> Vec<SessionMetadata> {
         Vec::new()
     }
+
+    /// Load the active branch with message IDs. Linear backends number messages
+    /// by position.
+    fn load_messages(&self, session_key: &str) -> Vec<SessionMessage> {
+        self.load(session_key)
+            .into_iter()
+            .zip(1_i64..)
+            .map(|(message, id)| SessionMessage {
+                id,
+                parent_id: (id > 1).then(|| id - 1),
+                message,
+            })
+            .collect()
+    }
+
+    /// ID of the message the session currently continues from. `None` if the
+    /// session is empty, rewound to the start, or the backend doesn't track branches.
+    fn active_head(&self, _session_key: &str) -> Option<i64> {
+        None
+    }
+
+    /// List all branches of a session's conversation tree. Empty if the backend
+    /// doesn't track branches.
+    fn list_branches(&self, _session_key: &str) -> Vec<SessionBranch> {
+        Vec::new()
+    }
+
+    /// Move the session's active head to `message_id` (`None` rewinds to before the
+    /// first message). Later appends start a new branch from there; existing
+    /// branches are kept. Returns `false` if the message isn't part of the session.
+    fn checkout(&self, _session_key: &str, _message_id: Option<i64>) -> std::io::Result<bool> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "session backend does not support branching",
+        ))
+    }
 }
 
 /// Session state information.
@@ -150,6 +213,31 @@ mod tests {
         assert_eq!(meta.message_count, 5);
     }
 
+    #[test]
+    fn default_load_messages_numbers_linear_history() {
+        struct Linear;
+        impl SessionBackend for Linear {
+            fn load(&self, _session_key: &str) -> Vec<ChatMessage> {
+                vec![ChatMessage::user("a"), ChatMessage::assistant("b")]
+            }
+            fn append(&self, _session_key: &str, _message: &ChatMessage) -> std::io::Result<()> {
+                Ok(())
+            }
+            fn remove_last(&self, _session_key: &str) -> std::io::Result<bool> {
+                Ok(false)
+            }
+            fn list_sessions(&self) -> Vec<String> {
+                Vec::new()
+            }
+        }
+
+        let msgs = Linear.load_messages("s");
+        assert_eq!(msgs[0].id, 1);
+        assert_eq!(msgs[0].parent_id, None);
+        assert_eq!(msgs[1].parent_id, Some(1));
+        assert!(Linear.checkout("s", Some(1)).is_err());
+    }
+
     #[test]
     fn session_query_defaults() {
         let q = SessionQuery::default();
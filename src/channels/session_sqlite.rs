@@ -3,17 +3,30 @@
 //! Stores sessions in `{workspace}/sessions/sessions.db` using WAL mode.
 //! Provides full-text search via FTS5 and automatic TTL-based cleanup.
 //! Designed as the default backend, replacing JSONL for new installations.
+//!
+//! Messages form a tree: each row points at its predecessor via `parent_id`,
+//! and `session_metadata.head_id` marks where the session currently continues.
+//! Forking, rewinding and editing a message all move the head; nothing is
+//! deleted, so every earlier branch stays reachable.
 
 use crate::channels::session_backend::{
-    SessionBackend, SessionMetadata, SessionQuery, SessionState,
+    SessionBackend, SessionBranch, SessionMessage, SessionMetadata, SessionQuery, SessionState,
 };
 use crate::providers::traits::ChatMessage;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Duration, Utc};
 use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::path::{Path, PathBuf};
 
+/// Recursive CTE walking from the message bound to `?1` back to the root of its branch.
+/// `depth` is 0 at the starting message.
+const BRANCH_PATH_CTE: &str = "WITH RECURSIVE path(id, parent_id, depth) AS (
+    SELECT id, parent_id, 0 FROM sessions WHERE id = ?1
+    UNION ALL
+    SELECT s.id, s.parent_id, p.depth + 1 FROM sessions s JOIN path p ON s.id = p.parent_id
+)";
+
 /// SQLite-backed session store with FTS5 and WAL mode.
 pub struct SqliteSessionBackend {
     conn: Mutex<Connection>,
@@ -44,7 +57,8 @@ impl SqliteSessionBackend {
                 session_key TEXT NOT NULL,
                 role        TEXT NOT NULL,
                 content     TEXT NOT NULL,
-                created_at  TEXT NOT NULL
+                created_at  TEXT NOT NULL,
+                parent_id   INTEGER
              );
              CREATE INDEX IF NOT EXISTS idx_sessions_key ON sessions(session_key);
              CREATE INDEX IF NOT EXISTS idx_sessions_key_id ON sessions(session_key, id);
@@ -54,7 +68,8 @@ impl SqliteSessionBackend {
                 created_at   TEXT NOT NULL,
                 last_activity TEXT NOT NULL,
                 message_count INTEGER NOT NULL DEFAULT 0,
-                name         TEXT
+                name         TEXT,
+                head_id      INTEGER
              );
 
              CREATE VIRTUAL TABLE IF NOT EXISTS sessions_fts USING fts5(
@@ -104,6 +119,45 @@ impl SqliteSessionBackend {
             );
         }
 
+        // Migration: link existing linear histories into a branch tree
+        let has_parent: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('sessions') WHERE name = 'parent_id'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+        if !has_parent {
+            conn.execute_batch(
+                "ALTER TABLE sessions ADD COLUMN parent_id INTEGER;
+                 UPDATE sessions SET parent_id = (
+                    SELECT MAX(p.id) FROM sessions p
+                    WHERE p.session_key = sessions.session_key AND p.id < sessions.id
+                 );",
+            )
+            .context("Failed to migrate session messages to branch tree")?;
+        }
+        let has_head: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('session_metadata') WHERE name = 'head_id'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+        if !has_head {
+            conn.execute_batch(
+                "ALTER TABLE session_metadata ADD COLUMN head_id INTEGER;
+                 UPDATE session_metadata SET head_id = (
+                    SELECT MAX(id) FROM sessions WHERE sessions.session_key = session_metadata.session_key
+                 );",
+            )
+            .context("Failed to migrate session heads")?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_sessions_parent ON sessions(parent_id);",
+        )
+        .context("Failed to index session branches")?;
+
         Ok(Self {
             conn: Mutex::new(conn),
             db_path,
@@ -162,22 +216,60 @@ impl SqliteSessionBackend {
 
         Ok(migrated)
     }
+
+    fn head_id(conn: &Connection, session_key: &str) -> Option<i64> {
+        conn.query_row(
+            "SELECT head_id FROM session_metadata WHERE session_key = ?1",
+            params![session_key],
+            |row| row.get(0),
+        )
+        .ok()
+        .flatten()
+    }
+
+    fn branch_len(conn: &Connection, head_id: Option<i64>) -> rusqlite::Result<i64> {
+        let Some(head_id) = head_id else {
+            return Ok(0);
+        };
+        conn.query_row(
+            &format!("{BRANCH_PATH_CTE} SELECT COUNT(*) FROM path"),
+            params![head_id],
+            |row| row.get(0),
+        )
+    }
 }
 
 impl SessionBackend for SqliteSessionBackend {
     fn load(&self, session_key: &str) -> Vec<ChatMessage> {
+        self.load_messages(session_key)
+            .into_iter()
+            .map(|m| m.message)
+            .collect()
+    }
+
+    fn load_messages(&self, session_key: &str) -> Vec<SessionMessage> {
         let conn = self.conn.lock();
-        let mut stmt = match conn
-            .prepare("SELECT role, content FROM sessions WHERE session_key = ?1 ORDER BY id ASC")
-        {
+        let Some(head_id) = Self::head_id(&conn, session_key) else {
+            return Vec::new();
+        };
+        let mut stmt = match conn.prepare(&format!(
+            "{BRANCH_PATH_CTE}
+             SELECT s.id, s.parent_id, s.role, s.content
+             FROM path JOIN sessions s ON s.id = path.id
+             ORDER BY path.depth DESC"
+        )) {
             Ok(s) => s,
             Err(_) => return Vec::new(),
         };
 
-        let rows = match stmt.query_map(params![session_key], |row| {
-            Ok(ChatMessage {
-                role: row.get(0)?,
-                content: row.get(1)?,
+        let rows = match stmt.query_map(params![head_id], |row| {
+            Ok(SessionMessage {
+                id: row.get(0)?,
+                parent_id: row.get(1)?,
+                message: ChatMessage {
+                    role: row.get(2)?,
+                    content: row.get(3)?,
+                },
             })
         }) {
             Ok(r) => r,
@@ -190,22 +282,25 @@ impl SessionBackend for SqliteSessionBackend {
     fn append(&self, session_key: &str, message: &ChatMessage) -> std::io::Result<()> {
         let conn = self.conn.lock();
         let now = Utc::now().to_rfc3339();
+        let parent_id = Self::head_id(&conn, session_key);
 
         conn.execute(
-            "INSERT INTO sessions (session_key, role, content, created_at)
-             VALUES (?1, ?2, ?3, ?4)",
-            params![session_key, message.role, message.content, now],
+            "INSERT INTO sessions (session_key, role, content, created_at, parent_id)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session_key, message.role, message.content, now, parent_id],
         )
         .map_err(std::io::Error::other)?;
+        let id = conn.last_insert_rowid();
 
-        // Upsert metadata
+        // Upsert metadata; the new message becomes the head of the active branch
         conn.execute(
-            "INSERT INTO session_metadata (session_key, created_at, last_activity, message_count)
-             VALUES (?1, ?2, ?3, 1)
+            "INSERT INTO session_metadata (session_key, created_at, last_activity, message_count, head_id)
+             VALUES (?1, ?2, ?3, 1, ?4)
              ON CONFLICT(session_key) DO UPDATE SET
                 last_activity = excluded.last_activity,
-                message_count = message_count + 1",
-            params![session_key, now, now],
+                message_count = message_count + 1,
+                head_id = excluded.head_id",
+            params![session_key, now, now, id],
         )
         .map_err(std::io::Error::other)?;
 
@@ -215,26 +310,35 @@ impl SessionBackend for SqliteSessionBackend {
     fn remove_last(&self, session_key: &str) -> std::io::Result<bool> {
         let conn = self.conn.lock();
 
-        let last_id: Option<i64> = conn
+        let Some(id) = Self::head_id(&conn, session_key) else {
+            return Ok(false);
+        };
+        let parent_id: Option<i64> = conn
             .query_row(
-                "SELECT id FROM sessions WHERE session_key = ?1 ORDER BY id DESC LIMIT 1",
-                params![session_key],
+                "SELECT parent_id FROM sessions WHERE id = ?1",
+                params![id],
                 |row| row.get(0),
             )
-            .ok();
-
-        let Some(id) = last_id else {
-            return Ok(false);
-        };
+            .map_err(std::io::Error::other)?;
 
-        conn.execute("DELETE FROM sessions WHERE id = ?1", params![id])
+        // Messages that other branches continue from are kept; only the head moves.
+        let has_children: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM sessions WHERE parent_id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
             .map_err(std::io::Error::other)?;
+        if !has_children {
+            conn.execute("DELETE FROM sessions WHERE id = ?1", params![id])
+                .map_err(std::io::Error::other)?;
+        }
 
-        // Update metadata count
+        // Update metadata head and count
         conn.execute(
-            "UPDATE session_metadata SET message_count = MAX(0, message_count - 1)
-             WHERE session_key = ?1",
-            params![session_key],
+            "UPDATE session_metadata SET head_id = ?1, message_count = MAX(0, message_count - 1)
+             WHERE session_key = ?2",
+            params![parent_id, session_key],
         )
         .map_err(std::io::Error::other)?;
 
@@ -509,6 +613,96 @@ impl SessionBackend for SqliteSessionBackend {
         rows.filter_map(|r| r.ok()).collect()
     }
 
+    fn active_head(&self, session_key: &str) -> Option<i64> {
+        let conn = self.conn.lock();
+        Self::head_id(&conn, session_key)
+    }
+
+    fn list_branches(&self, session_key: &str) -> Vec<SessionBranch> {
+        let conn = self.conn.lock();
+        let head_id = Self::head_id(&conn, session_key);
+
+        // Every leaf message terminates exactly one branch
+        let leaves: Vec<(i64, String)> = {
+            let mut stmt = match conn.prepare(
+                "SELECT s.id, s.created_at FROM sessions s
+                 WHERE s.session_key = ?1
+                   AND NOT EXISTS (SELECT 1 FROM sessions c WHERE c.parent_id = s.id)
+                 ORDER BY s.id ASC",
+            ) {
+                Ok(s) => s,
+                Err(_) => return Vec::new(),
+            };
+            let rows =
+                match stmt.query_map(params![session_key], |row| Ok((row.get(0)?, row.get(1)?))) {
+                    Ok(r) => r,
+                    Err(_) => return Vec::new(),
+                };
+            rows.filter_map(|r| r.ok()).collect()
+        };
+
+        leaves
+            .into_iter()
+            .filter_map(|(id, created_str)| {
+                let count = Self::branch_len(&conn, Some(id)).ok()?;
+                let fork_id: Option<i64> = conn
+                    .query_row(
+                        &format!(
+                            "{BRANCH_PATH_CTE}
+                             SELECT path.id FROM path
+                             WHERE path.depth > 0
+                               AND (SELECT COUNT(*) FROM sessions c WHERE c.parent_id = path.id) > 1
+                             ORDER BY path.depth ASC LIMIT 1"
+                        ),
+                        params![id],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .ok()?;
+                #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+                Some(SessionBranch {
+                    head_id: id,
+                    fork_id,
+                    message_count: count as usize,
+                    last_activity: DateTime::parse_from_rfc3339(&created_str)
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(|_| Utc::now()),
+                    active: head_id == Some(id),
+                })
+            })
+            .collect()
+    }
+
+    fn checkout(&self, session_key: &str, message_id: Option<i64>) -> std::io::Result<bool> {
+        let conn = self.conn.lock();
+
+        let exists: bool = match message_id {
+            Some(id) => conn.query_row(
+                "SELECT COUNT(*) > 0 FROM sessions WHERE id = ?1 AND session_key = ?2",
+                params![id, session_key],
+                |row| row.get(0),
+            ),
+            None => conn.query_row(
+                "SELECT COUNT(*) > 0 FROM session_metadata WHERE session_key = ?1",
+                params![session_key],
+                |row| row.get(0),
+            ),
+        }
+        .map_err(std::io::Error::other)?;
+        if !exists {
+            return Ok(false);
+        }
+
+        let count = Self::branch_len(&conn, message_id).map_err(std::io::Error::other)?;
+        conn.execute(
+            "UPDATE session_metadata SET head_id = ?1, message_count = ?2 WHERE session_key = ?3",
+            params![message_id, count, session_key],
+        )
+        .map_err(std::io::Error::other)?;
+
+        Ok(true)
+    }
+
     fn search(&self, query: &SessionQuery) -> Vec<SessionMetadata> {
         let Some(keyword) = &query.keyword else {
             return self.list_sessions_with_metadata();
@@ -912,6 +1106,121 @@ mod tests {
         assert_eq!(state.state, "idle");
     }
 
+    // ── branching tests ─────────────────────────────────────────────
+
+    #[test]
+    fn checkout_forks_new_branch_and_keeps_old_one() {
+        let tmp = TempDir::new().unwrap();
+        let backend = SqliteSessionBackend::new(tmp.path()).unwrap();
+
+        backend.append("s1", &ChatMessage::user("q1")).unwrap();
+        backend.append("s1", &ChatMessage::assistant("a1")).unwrap();
+        backend.append("s1", &ChatMessage::user("q2")).unwrap();
+        backend.append("s1", &ChatMessage::assistant("a2")).unwrap();
+        let msgs = backend.load_messages("s1");
+        let (fork_at, original_head) = (msgs[1].id, msgs[3].id);
+
+        // Rewind to the first answer and continue from there
+        assert!(backend.checkout("s1", Some(fork_at)).unwrap());
+        assert_eq!(backend.load("s1").len(), 2);
+        backend
+            .append("s1", &ChatMessage::user("q2 again"))
+            .unwrap();
+
+        let msgs = backend.load("s1");
+        assert_eq!(msgs.len(), 3);
+        assert_eq!(msgs[2].content, "q2 again");
+        assert_eq!(backend.list_sessions_with_metadata()[0].message_count, 3);
+
+        let branches = backend.list_branches("s1");
+        assert_eq!(branches.len(), 2);
+        assert!(branches.iter().all(|b| b.fork_id == Some(fork_at)));
+        assert_eq!(branches.iter().filter(|b| b.active).count(), 1);
+
+        // Switch back to the original branch
+        assert!(backend.checkout("s1", Some(original_head)).unwrap());
+        let msgs = backend.load("s1");
+        assert_eq!(msgs.len(), 4);
+        assert_eq!(msgs[3].content, "a2");
+    }
+
+    #[test]
+    fn checkout_rejects_foreign_messages_and_rewinds_to_start() {
+        let tmp = TempDir::new().unwrap();
+        let backend = SqliteSessionBackend::new(tmp.path()).unwrap();
+
+        backend.append("s1", &ChatMessage::user("hello")).unwrap();
+        backend.append("s2", &ChatMessage::user("other")).unwrap();
+        let foreign = backend.load_messages("s2")[0].id;
+
+        assert!(!backend.checkout("s1", Some(foreign)).unwrap());
+        assert!(!backend.checkout("missing", None).unwrap());
+
+        // Editing the first message starts a second root
+        assert!(backend.checkout("s1", None).unwrap());
+        assert!(backend.load("s1").is_empty());
+        backend
+            .append("s1", &ChatMessage::user("hello, edited"))
+            .unwrap();
+        assert_eq!(backend.load("s1")[0].content, "hello, edited");
+        assert_eq!(backend.list_branches("s1").len(), 2);
+    }
+
+    #[test]
+    fn remove_last_keeps_messages_shared_with_other_branches() {
+        let tmp = TempDir::new().unwrap();
+        let backend = SqliteSessionBackend::new(tmp.path()).unwrap();
+
+        backend.append("s1", &ChatMessage::user("a")).unwrap();
+        backend.append("s1", &ChatMessage::assistant("b")).unwrap();
+        let first = backend.load_messages("s1")[0].id;
+        assert!(backend.checkout("s1", Some(first)).unwrap());
+        backend.append("s1", &ChatMessage::assistant("b'")).unwrap();
+        assert!(backend.checkout("s1", Some(first)).unwrap());
+
+        // Head is an interior message: it moves back without deleting anything
+        assert!(backend.remove_last("s1").unwrap());
+        assert!(backend.load("s1").is_empty());
+        assert_eq!(backend.list_branches("s1").len(), 2);
+    }
+
+    #[test]
+    fn branch_migration_links_legacy_histories() {
+        let tmp = TempDir::new().unwrap();
+        let sessions_dir = tmp.path().join("sessions");
+        std::fs::create_dir_all(&sessions_dir).unwrap();
+        {
+            let conn = Connection::open(sessions_dir.join("sessions.db")).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sessions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT, session_key TEXT NOT NULL,
+                    role TEXT NOT NULL, content TEXT NOT NULL, created_at TEXT NOT NULL
+                 );
+                 CREATE TABLE session_metadata (
+                    session_key TEXT PRIMARY KEY, created_at TEXT NOT NULL,
+                    last_activity TEXT NOT NULL, message_count INTEGER NOT NULL DEFAULT 0
+                 );
+                 INSERT INTO sessions (session_key, role, content, created_at) VALUES
+                    ('s1', 'user', 'one', '2025-01-01T00:00:00Z'),
+                    ('s2', 'user', 'elsewhere', '2025-01-01T00:00:00Z'),
+                    ('s1', 'assistant', 'two', '2025-01-01T00:00:00Z');
+                 INSERT INTO session_metadata VALUES
+                    ('s1', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z', 2),
+                    ('s2', '2025-01-01T00:00:00Z', '2025-01-01T00:00:00Z', 1);",
+            )
+            .unwrap();
+        }
+
+        let backend = SqliteSessionBackend::new(tmp.path()).unwrap();
+        let msgs = backend.load_messages("s1");
+        assert_eq!(msgs.len(), 2);
+        assert_eq!(msgs[1].parent_id, Some(msgs[0].id));
+        assert_eq!(backend.load("s2")[0].content, "elsewhere");
+
+        backend.append("s1", &ChatMessage::user("three")).unwrap();
+        assert_eq!(backend.load("s1").len(), 3);
+    }
+
     #[test]
     fn empty_name_clears_to_none() {
         let tmp = TempDir::new().unwrap();
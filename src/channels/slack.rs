@@ -1,4 +1,4 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::traits::{ApprovalPrompt, Channel, ChannelMessage, OutboundAttachment, SendMessage};
 use anyhow::Context;
 use async_trait::async_trait;
 use base64::Engine as _;
@@ -2433,6 +2433,8 @@ impl SlackChannel {
     /// payload from the `/config` Block Kit UI.  Translates provider/model
     /// dropdown selections into synthetic `/models <provider>` or `/model <id>`
     /// commands so the existing runtime command handler can apply them.
+    /// Approval prompt buttons carry their reply command (`/approve <id>`,
+    /// …) as the button value and are passed through as-is.
     fn parse_block_action_as_command(
         envelope: &serde_json::Value,
         _bot_user_id: &str,
@@ -2448,15 +2450,19 @@ impl SlackChannel {
         let action = actions.first()?;
 
         let action_id = action.get("action_id").and_then(|v| v.as_str())?;
-        let selected_value = action
-            .get("selected_option")
-            .and_then(|o| o.get("value"))
-            .and_then(|v| v.as_str())?;
-
-        let command = match action_id {
-            "zeroclaw_config_provider" => format!("/models {selected_value}"),
-            "zeroclaw_config_model" => format!("/model {selected_value}"),
-            _ => return None,
+        let command = if action_id.starts_with("zeroclaw_approval_") {
+            action.get("value").and_then(|v| v.as_str())?.to_string()
+        } else {
+            let selected_value = action
+                .get("selected_option")
+                .and_then(|o| o.get("value"))
+                .and_then(|v| v.as_str())?;
+
+            match action_id {
+                "zeroclaw_config_provider" => format!("/models {selected_value}"),
+                "zeroclaw_config_model" => format!("/model {selected_value}"),
+                _ => return None,
+            }
         };
 
         let user = payload
@@ -2878,6 +2884,71 @@ impl SlackChannel {
         true
     }
 
+    /// Upload a file through Slack's external upload flow:
+    /// `files.getUploadURLExternal`, a raw upload, then
+    /// `files.completeUploadExternal` to share it in the channel.
+    async fn upload_file(
+        &self,
+        channel_id: &str,
+        thread_ts: Option<&str>,
+        attachment: &OutboundAttachment,
+    ) -> anyhow::Result<()> {
+        let client = self.http_client();
+        let resp = client
+            .post("https://slack.com/api/files.getUploadURLExternal")
+            .bearer_auth(&self.bot_token)
+            .form(&[
+                ("filename", attachment.file_name.clone()),
+                ("length", attachment.data.len().to_string()),
+            ])
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        if !Self::slack_api_call_succeeded(status, &body) {
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Slack files.getUploadURLExternal failed ({status}): {sanitized}");
+        }
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        let (Some(upload_url), Some(file_id)) = (
+            parsed.get("upload_url").and_then(|v| v.as_str()),
+            parsed.get("file_id").and_then(|v| v.as_str()),
+        ) else {
+            anyhow::bail!("Slack files.getUploadURLExternal returned no upload_url or file_id");
+        };
+
+        let resp = client
+            .post(upload_url)
+            .header("Content-Type", attachment.mime())
+            .body(attachment.data.clone())
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("Slack file upload failed ({})", resp.status());
+        }
+
+        let mut complete = serde_json::json!({
+            "files": [{ "id": file_id, "title": attachment.file_name }],
+            "channel_id": channel_id
+        });
+        if let Some(ts) = thread_ts {
+            complete["thread_ts"] = serde_json::json!(ts);
+        }
+        let resp = client
+            .post("https://slack.com/api/files.completeUploadExternal")
+            .bearer_auth(&self.bot_token)
+            .json(&complete)
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        if !Self::slack_api_call_succeeded(status, &body) {
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!("Slack files.completeUploadExternal failed ({status}): {sanitized}");
+        }
+        Ok(())
+    }
+
     fn slack_api_call_succeeded(status: reqwest::StatusCode, body: &str) -> bool {
         if !status.is_success() {
             return false;
@@ -3202,6 +3273,25 @@ impl Channel for SlackChannel {
     }
 
     async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        if !message.attachments.is_empty() {
+            if !message.content.trim().is_empty() {
+                let text_only = SendMessage {
+                    attachments: Vec::new(),
+                    ..message.clone()
+                };
+                self.send(&text_only).await?;
+            }
+            for attachment in &message.attachments {
+                self.upload_file(
+                    &message.recipient,
+                    self.outbound_thread_ts(message),
+                    attachment,
+                )
+                .await?;
+            }
+            return Ok(());
+        }
+
         // Detect Block Kit payloads produced by the `/config` command.
         let body = if let Some(blocks_json) = message.content.strip_prefix(super::BLOCK_KIT_PREFIX)
         {
@@ -3297,6 +3387,10 @@ impl Channel for SlackChannel {
         Ok(())
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     fn supports_draft_updates(&self) -> bool {
         self.stream_drafts
     }
@@ -3494,6 +3588,66 @@ impl Channel for SlackChannel {
         self.send(&SendMessage::new(text, recipient)).await
     }
 
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        thread_ts: Option<&str>,
+        prompt: &ApprovalPrompt,
+    ) -> anyhow::Result<()> {
+        let button = |verb: &str, label: &str, style: Option<&str>| {
+            let mut button = serde_json::json!({
+                "type": "button",
+                "action_id": format!("zeroclaw_approval_{verb}"),
+                "text": { "type": "plain_text", "text": label },
+                "value": prompt.reply_command(verb)
+            });
+            if let Some(style) = style {
+                button["style"] = serde_json::json!(style);
+            }
+            button
+        };
+        let text = prompt.text();
+        let mut body = serde_json::json!({
+            "channel": recipient,
+            "text": text,
+            "blocks": [
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": text }
+                },
+                {
+                    "type": "actions",
+                    "block_id": format!("zeroclaw_approval_{}", prompt.request_id),
+                    "elements": [
+                        button("approve", "Approve", Some("primary")),
+                        button("always", "Always", None),
+                        button("deny", "Deny", Some("danger"))
+                    ]
+                }
+            ]
+        });
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = serde_json::json!(ts);
+        }
+
+        let resp = self
+            .http_client()
+            .post("https://slack.com/api/chat.postMessage")
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        if !Self::slack_api_call_succeeded(status, &body) {
+            let sanitized = crate::providers::sanitize_api_error(&body);
+            anyhow::bail!(
+                "Slack chat.postMessage (approval prompt) failed ({status}): {sanitized}"
+            );
+        }
+        Ok(())
+    }
+
     async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
         self.last_draft_edit
             .lock()
@@ -4477,6 +4631,30 @@ mod tests {
         assert!(SlackChannel::evaluate_health(true, true, true));
     }
 
+    #[test]
+    fn block_action_approval_button_maps_to_reply_command() {
+        let envelope = serde_json::json!({
+            "type": "interactive",
+            "payload": {
+                "type": "block_actions",
+                "user": { "id": "U123" },
+                "channel": { "id": "C456" },
+                "message": { "ts": "1741234567.000100", "thread_ts": "1741234500.000001" },
+                "actions": [{
+                    "action_id": "zeroclaw_approval_deny",
+                    "value": "/deny ab12cd34"
+                }]
+            }
+        });
+
+        let msg = SlackChannel::parse_block_action_as_command(&envelope, "UBOT")
+            .expect("approval button should parse");
+        assert_eq!(msg.sender, "U123");
+        assert_eq!(msg.reply_target, "C456");
+        assert_eq!(msg.content, "/deny ab12cd34");
+        assert_eq!(msg.thread_ts.as_deref(), Some("1741234500.000001"));
+    }
+
     #[test]
     fn slack_api_call_succeeded_requires_ok_true_in_body() {
         assert!(!SlackChannel::slack_api_call_succeeded(
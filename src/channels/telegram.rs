@@ -1,4 +1,5 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::media_pipeline::MediaKind;
+use super::traits::{ApprovalPrompt, Channel, ChannelMessage, OutboundAttachment, SendMessage};
 use crate::config::{Config, StreamMode};
 use crate::security::pairing::PairingGuard;
 use anyhow::Context;
@@ -190,6 +191,22 @@ fn format_attachment_content(
     }
 }
 
+/// Largest photo `sendPhoto` accepts; bigger images go out as documents.
+const TELEGRAM_MAX_PHOTO_BYTES: usize = 10 * 1024 * 1024;
+
+/// Bot API method and multipart field used to upload an outbound attachment.
+fn telegram_upload_method(attachment: &OutboundAttachment) -> (&'static str, &'static str) {
+    match attachment.kind() {
+        MediaKind::Image if attachment.data.len() <= TELEGRAM_MAX_PHOTO_BYTES => {
+            ("sendPhoto", "photo")
+        }
+        MediaKind::Video => ("sendVideo", "video"),
+        MediaKind::Audio if attachment.is_voice_note() => ("sendVoice", "voice"),
+        MediaKind::Audio => ("sendAudio", "audio"),
+        _ => ("sendDocument", "document"),
+    }
+}
+
 fn is_http_url(target: &str) -> bool {
     target.starts_with("http://") || target.starts_with("https://")
 }
@@ -1416,6 +1433,74 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Some(format!("> @{reply_sender}:\n{quoted_lines}"))
     }
 
+    /// Translate an inline-keyboard click on an approval prompt into the
+    /// matching reply command from the user who clicked.
+    fn parse_approval_callback(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
+        let callback = update.get("callback_query")?;
+        let data = callback.get("data").and_then(serde_json::Value::as_str)?;
+        crate::approval::parse_approval_reply(data)?;
+
+        let (username, sender_id, sender_identity) = Self::extract_sender_info(callback);
+        let mut identities = vec![username.as_str()];
+        if let Some(id) = sender_id.as_deref() {
+            identities.push(id);
+        }
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            return None;
+        }
+
+        let message = callback.get("message")?;
+        let chat_id = message
+            .get("chat")
+            .and_then(|chat| chat.get("id"))
+            .and_then(serde_json::Value::as_i64)?
+            .to_string();
+        let thread_id = message
+            .get("message_thread_id")
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string());
+        let reply_target = match &thread_id {
+            Some(tid) => format!("{chat_id}:{tid}"),
+            None => chat_id.clone(),
+        };
+        let callback_id = callback
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+
+        Some(ChannelMessage {
+            id: format!("telegram_{chat_id}_callback_{callback_id}"),
+            sender: sender_identity,
+            reply_target,
+            content: data.to_string(),
+            channel: "telegram".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            thread_ts: thread_id,
+            interruption_scope_id: None,
+            attachments: vec![],
+        })
+    }
+
+    /// Stop the loading indicator on a clicked inline button.
+    async fn answer_callback_query(&self, update: &serde_json::Value) {
+        let Some(callback_id) = update
+            .get("callback_query")
+            .and_then(|callback| callback.get("id"))
+            .and_then(serde_json::Value::as_str)
+        else {
+            return;
+        };
+        let _ = self
+            .http_client()
+            .post(self.api_url("answerCallbackQuery"))
+            .json(&serde_json::json!({ "callback_query_id": callback_id }))
+            .send()
+            .await;
+    }
+
     fn parse_update_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
         let message = update.get("message")?;
 
@@ -1926,6 +2011,45 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         }
     }
 
+    /// Upload an in-memory attachment with the method matching its kind.
+    async fn send_outbound_attachment(
+        &self,
+        chat_id: &str,
+        thread_id: Option<&str>,
+        attachment: &OutboundAttachment,
+    ) -> anyhow::Result<()> {
+        let (method, field) = telegram_upload_method(attachment);
+        let part = Part::bytes(attachment.data.clone())
+            .file_name(attachment.file_name.clone())
+            .mime_str(attachment.mime())?;
+
+        let mut form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .part(field, part);
+
+        if let Some(tid) = thread_id {
+            form = form.text("message_thread_id", tid.to_string());
+        }
+
+        let resp = self
+            .http_client()
+            .post(self.api_url(method))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Telegram {method} failed: {err}");
+        }
+
+        tracing::info!(
+            "Telegram {field} sent to {chat_id}: {}",
+            attachment.file_name
+        );
+        Ok(())
+    }
+
     /// Send a document/file to a Telegram chat
     pub async fn send_document(
         &self,
@@ -2341,6 +2465,10 @@ impl Channel for TelegramChannel {
         "telegram"
     }
 
+    fn supports_attachments(&self) -> bool {
+        true
+    }
+
     fn supports_draft_updates(&self) -> bool {
         self.stream_mode != StreamMode::Off
     }
@@ -2620,6 +2748,41 @@ impl Channel for TelegramChannel {
         }
     }
 
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        _thread_ts: Option<&str>,
+        prompt: &ApprovalPrompt,
+    ) -> anyhow::Result<()> {
+        let (chat_id, thread_id) = Self::parse_reply_target(recipient);
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": prompt.text(),
+            "reply_markup": {
+                "inline_keyboard": [[
+                    { "text": "✅ Approve", "callback_data": prompt.reply_command("approve") },
+                    { "text": "♾️ Always", "callback_data": prompt.reply_command("always") },
+                    { "text": "🚫 Deny", "callback_data": prompt.reply_command("deny") }
+                ]]
+            }
+        });
+        if let Some(tid) = thread_id {
+            body["message_thread_id"] = serde_json::Value::String(tid);
+        }
+
+        let resp = self
+            .http_client()
+            .post(self.api_url("sendMessage"))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Telegram sendMessage (approval prompt) failed: {err}");
+        }
+        Ok(())
+    }
+
     async fn cancel_draft(&self, recipient: &str, message_id: &str) -> anyhow::Result<()> {
         let (chat_id, _) = Self::parse_reply_target(recipient);
         self.last_draft_edit.lock().remove(&chat_id);
@@ -2661,6 +2824,17 @@ impl Channel for TelegramChannel {
             None => (message.recipient.as_str(), None),
         };
 
+        if !message.attachments.is_empty() {
+            if !content.trim().is_empty() {
+                self.send_text_chunks(&content, chat_id, thread_id).await?;
+            }
+            for attachment in &message.attachments {
+                self.send_outbound_attachment(chat_id, thread_id, attachment)
+                    .await?;
+            }
+            return Ok(());
+        }
+
         // Voice chat mode: send text normally AND queue a voice note of the
         // final answer. Text in → text out. Voice in → text + voice out.
         let is_voice_chat = self
@@ -2782,7 +2956,7 @@ impl Channel for TelegramChannel {
             let probe = serde_json::json!({
                 "offset": offset,
                 "timeout": 0,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
             match self.http_client().post(&url).json(&probe).send().await {
                 Err(e) => {
@@ -2855,7 +3029,7 @@ impl Channel for TelegramChannel {
             let body = serde_json::json!({
                 "offset": offset,
                 "timeout": 30,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
 
             let resp = match self.http_client().post(&url).json(&body).send().await {
@@ -2916,6 +3090,16 @@ Ensure only one `zeroclaw` process is using this bot token."
                         offset = uid + 1;
                     }
 
+                    if update.get("callback_query").is_some() {
+                        self.answer_callback_query(update).await;
+                        if let Some(m) = self.parse_approval_callback(update) {
+                            if tx.send(m).await.is_err() {
+                                return Ok(());
+                            }
+                        }
+                        continue;
+                    }
+
                     let msg = if let Some(m) = self.parse_update_message(update) {
                         m
                     } else if let Some(m) = self.try_parse_voice_message(update).await {
@@ -3374,6 +3558,40 @@ mod tests {
         assert_eq!(msg.id, "telegram_-100200300_33");
     }
 
+    #[test]
+    fn parse_approval_callback_maps_click_to_reply_command() {
+        let ch = TelegramChannel::new("token".into(), vec!["alice".into()], false);
+        let click = |user: &str, data: &str| {
+            serde_json::json!({
+                "update_id": 2,
+                "callback_query": {
+                    "id": "cb1",
+                    "data": data,
+                    "from": { "id": 555, "username": user },
+                    "message": {
+                        "message_id": 40,
+                        "chat": { "id": -100_200_300 },
+                        "message_thread_id": 7
+                    }
+                }
+            })
+        };
+
+        let msg = ch
+            .parse_approval_callback(&click("alice", "/approve ab12cd34"))
+            .expect("approval click should parse");
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "-100200300:7");
+        assert_eq!(msg.content, "/approve ab12cd34");
+
+        assert!(ch
+            .parse_approval_callback(&click("mallory", "/approve ab12cd34"))
+            .is_none());
+        assert!(ch
+            .parse_approval_callback(&click("alice", "other_button"))
+            .is_none());
+    }
+
     #[test]
     fn parse_update_message_allows_numeric_id_without_username() {
         let ch = TelegramChannel::new("token".into(), vec!["555".into()], false);
@@ -3475,6 +3693,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn telegram_upload_method_follows_attachment_kind() {
+        let method = |name: &str, size: usize| {
+            telegram_upload_method(&OutboundAttachment::new(name, vec![0; size])).0
+        };
+        assert_eq!(method("chart.png", 10), "sendPhoto");
+        assert_eq!(
+            method("huge.png", TELEGRAM_MAX_PHOTO_BYTES + 1),
+            "sendDocument"
+        );
+        assert_eq!(method("clip.mp4", 10), "sendVideo");
+        assert_eq!(method("reply.ogg", 10), "sendVoice");
+        assert_eq!(method("song.mp3", 10), "sendAudio");
+        assert_eq!(method("report.pdf", 10), "sendDocument");
+    }
+
     // ── File sending integration tests (with mock server) ──────────
 
     #[tokio::test]
@@ -34,6 +34,10 @@ pub struct SendMessage {
     pub thread_ts: Option<String>,
     /// Optional cancellation token for interruptible delivery (e.g. multi-message mode).
     pub cancellation_token: Option<CancellationToken>,
+    /// Files and media to deliver with the message. Send through
+    /// [`Channel::send_with_attachments`] so channels without upload support
+    /// fall back to text links.
+    pub attachments: Vec<OutboundAttachment>,
 }
 
 impl SendMessage {
@@ -45,6 +49,7 @@ impl SendMessage {
             subject: None,
             thread_ts: None,
             cancellation_token: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -60,6 +65,7 @@ impl SendMessage {
             subject: Some(subject.into()),
             thread_ts: None,
             cancellation_token: None,
+            attachments: Vec::new(),
         }
     }
 
@@ -74,6 +80,161 @@ impl SendMessage {
         self.cancellation_token = Some(token);
         self
     }
+
+    /// Attach files or media to the message.
+    pub fn with_attachments(mut self, attachments: Vec<OutboundAttachment>) -> Self {
+        self.attachments = attachments;
+        self
+    }
+
+    /// Replace attachments with one text line each, for channels that cannot
+    /// upload files.
+    pub fn attachments_as_links(mut self) -> Self {
+        let links: Vec<String> = self
+            .attachments
+            .drain(..)
+            .map(|attachment| attachment.link_line())
+            .collect();
+        if !links.is_empty() {
+            if !self.content.trim().is_empty() {
+                self.content.push_str("\n\n");
+            }
+            self.content.push_str(&links.join("\n"));
+        }
+        self
+    }
+}
+
+/// A file or media item delivered with an outbound message.
+#[derive(Debug, Clone)]
+pub struct OutboundAttachment {
+    /// File name shown to the recipient (e.g. `report.pdf`).
+    pub file_name: String,
+    /// Raw bytes of the file.
+    pub data: Vec<u8>,
+    /// MIME type; guessed from the file name when not given.
+    pub mime_type: Option<String>,
+    /// Local path or URL of the file, shown instead of uploading on channels
+    /// without file support.
+    pub link: Option<String>,
+}
+
+impl OutboundAttachment {
+    /// Create an attachment from in-memory bytes.
+    pub fn new(file_name: impl Into<String>, data: Vec<u8>) -> Self {
+        let file_name = file_name.into();
+        let mime_type = mime_guess::from_path(&file_name)
+            .first()
+            .map(|mime| mime.essence_str().to_string());
+        Self {
+            file_name,
+            data,
+            mime_type,
+            link: None,
+        }
+    }
+
+    /// Read a file from disk; the path doubles as the fallback link.
+    pub async fn from_path(path: &std::path::Path) -> anyhow::Result<Self> {
+        let data = tokio::fs::read(path).await.map_err(|error| {
+            anyhow::anyhow!("Failed to read attachment '{}': {error}", path.display())
+        })?;
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("attachment.bin")
+            .to_string();
+        Ok(Self::new(file_name, data).with_link(path.display().to_string()))
+    }
+
+    /// Set the fallback link.
+    pub fn with_link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// MIME type, defaulting to `application/octet-stream`.
+    pub fn mime(&self) -> &str {
+        self.mime_type
+            .as_deref()
+            .unwrap_or("application/octet-stream")
+    }
+
+    /// Classify the attachment by MIME type.
+    pub fn kind(&self) -> super::media_pipeline::MediaKind {
+        use super::media_pipeline::MediaKind;
+        let mime = self.mime().to_ascii_lowercase();
+        if mime.starts_with("image/") {
+            MediaKind::Image
+        } else if mime.starts_with("audio/") {
+            MediaKind::Audio
+        } else if mime.starts_with("video/") {
+            MediaKind::Video
+        } else {
+            MediaKind::Unknown
+        }
+    }
+
+    /// Whether this is an Ogg/Opus audio clip that platforms render as a
+    /// voice note rather than a music file.
+    pub fn is_voice_note(&self) -> bool {
+        let mime = self.mime().to_ascii_lowercase();
+        mime == "audio/ogg" || mime == "audio/opus"
+    }
+
+    fn link_line(&self) -> String {
+        match &self.link {
+            Some(link) => format!("📎 {}: {link}", self.file_name),
+            None => format!(
+                "📎 {} ({} KB, this channel does not support file uploads)",
+                self.file_name,
+                self.data.len().div_ceil(1024)
+            ),
+        }
+    }
+}
+
+/// A tool call awaiting operator approval on a channel.
+#[derive(Debug, Clone)]
+pub struct ApprovalPrompt {
+    /// Short ID that replies and button callbacks refer to.
+    pub request_id: String,
+    pub tool_name: String,
+    pub arguments_summary: String,
+    /// Sender whose message led to the tool call.
+    pub requester: String,
+    pub timeout_secs: u64,
+}
+
+impl ApprovalPrompt {
+    /// Prompt body without reply instructions, for channels that render buttons.
+    pub fn text(&self) -> String {
+        format!(
+            "🔐 Approval needed ({id})\nTool: {tool}\nArgs: {args}\nRequested by: {requester}",
+            id = self.request_id,
+            tool = self.tool_name,
+            args = self.arguments_summary,
+            requester = self.requester,
+        )
+    }
+
+    /// Reply command a button or keyword answer maps to: `/approve <id>`,
+    /// `/deny <id>` or `/always <id>`.
+    pub fn reply_command(&self, verb: &str) -> String {
+        format!("/{verb} {}", self.request_id)
+    }
+
+    /// Prompt body including keyword reply instructions.
+    pub fn text_with_reply_hint(&self) -> String {
+        format!(
+            "{}\nReply `{}`, `{}` or `{}` within {}s.",
+            self.text(),
+            self.reply_command("approve"),
+            self.reply_command("always"),
+            self.reply_command("deny"),
+            self.timeout_secs
+        )
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -109,6 +270,21 @@ pub trait Channel: Send + Sync {
         false
     }
 
+    /// Whether `send` uploads [`SendMessage::attachments`] natively.
+    fn supports_attachments(&self) -> bool {
+        false
+    }
+
+    /// Send a message that may carry attachments. Channels without native
+    /// upload support get a text link per attachment instead.
+    async fn send_with_attachments(&self, message: &SendMessage) -> anyhow::Result<()> {
+        if message.attachments.is_empty() || self.supports_attachments() {
+            self.send(message).await
+        } else {
+            self.send(&message.clone().attachments_as_links()).await
+        }
+    }
+
     /// Whether this channel supports multi-message streaming delivery, where
     /// the response is sent as multiple separate messages at paragraph
     /// boundaries as tokens arrive from the provider.
@@ -198,6 +374,25 @@ pub trait Channel: Send + Sync {
         Ok(())
     }
 
+    /// Ask the recipient to approve a tool call.
+    ///
+    /// Channels with interactive components render Approve / Always / Deny
+    /// buttons and deliver clicks through `listen` as the matching
+    /// [`ApprovalPrompt::reply_command`] text. The default sends a plain
+    /// message asking for a keyword reply.
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        thread_ts: Option<&str>,
+        prompt: &ApprovalPrompt,
+    ) -> anyhow::Result<()> {
+        self.send(
+            &SendMessage::new(prompt.text_with_reply_hint(), recipient)
+                .in_thread(thread_ts.map(str::to_string)),
+        )
+        .await
+    }
+
     /// Redact (delete) a message from the channel.
     ///
     /// `channel_id` is the platform channel/conversation identifier.
@@ -330,6 +525,104 @@ mod tests {
         assert_eq!(received.channel, "dummy");
     }
 
+    #[tokio::test]
+    async fn default_approval_prompt_sends_keyword_instructions() {
+        struct RecordingChannel(parking_lot::Mutex<Vec<String>>);
+
+        #[async_trait]
+        impl Channel for RecordingChannel {
+            fn name(&self) -> &str {
+                "recording"
+            }
+
+            async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+                self.0.lock().push(message.content.clone());
+                Ok(())
+            }
+
+            async fn listen(
+                &self,
+                _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+            ) -> anyhow::Result<()> {
+                Ok(())
+            }
+        }
+
+        let channel = RecordingChannel(parking_lot::Mutex::new(Vec::new()));
+        let prompt = ApprovalPrompt {
+            request_id: "ab12cd34".into(),
+            tool_name: "shell".into(),
+            arguments_summary: "command: make deploy".into(),
+            requester: "alice".into(),
+            timeout_secs: 60,
+        };
+        channel
+            .send_approval_prompt("room", None, &prompt)
+            .await
+            .unwrap();
+
+        let sent = channel.0.lock();
+        assert!(sent[0].contains("Tool: shell"));
+        assert!(sent[0].contains("`/approve ab12cd34`"));
+        assert!(sent[0].contains("within 60s"));
+    }
+
+    #[tokio::test]
+    async fn attachments_fall_back_to_links_without_upload_support() {
+        struct RecordingChannel(parking_lot::Mutex<Vec<SendMessage>>);
+
+        #[async_trait]
+        impl Channel for RecordingChannel {
+            fn name(&self) -> &str {
+                "recording"
+            }
+
+            async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+                self.0.lock().push(message.clone());
+                Ok(())
+            }
+
+            async fn listen(
+                &self,
+                _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+            ) -> anyhow::Result<()> {
+                Ok(())
+            }
+        }
+
+        let channel = RecordingChannel(parking_lot::Mutex::new(Vec::new()));
+        let message = SendMessage::new("Here you go", "room").with_attachments(vec![
+            OutboundAttachment::new("chart.png", vec![0; 10]).with_link("/ws/images/chart.png"),
+            OutboundAttachment::new("data.csv", vec![0; 2048]),
+        ]);
+        channel.send_with_attachments(&message).await.unwrap();
+
+        let sent = channel.0.lock();
+        assert!(sent[0].attachments.is_empty());
+        assert_eq!(
+            sent[0].content,
+            "Here you go\n\n📎 chart.png: /ws/images/chart.png\n\
+             📎 data.csv (2 KB, this channel does not support file uploads)"
+        );
+    }
+
+    #[test]
+    fn outbound_attachment_guesses_mime_and_kind() {
+        use crate::channels::media_pipeline::MediaKind;
+
+        let image = OutboundAttachment::new("plot.png", vec![]);
+        assert_eq!(image.mime(), "image/png");
+        assert_eq!(image.kind(), MediaKind::Image);
+
+        let voice = OutboundAttachment::new("reply.ogg", vec![]);
+        assert_eq!(voice.kind(), MediaKind::Audio);
+        assert!(voice.is_voice_note());
+
+        let unknown = OutboundAttachment::new("blob", vec![]);
+        assert_eq!(unknown.mime(), "application/octet-stream");
+        assert_eq!(unknown.kind(), MediaKind::Unknown);
+    }
+
     #[tokio::test]
     async fn default_redact_message_returns_success() {
         let channel = DummyChannel;
@@ -1,4 +1,5 @@
-use super::traits::{Channel, ChannelMessage, SendMessage};
+use super::media_pipeline::MediaKind;
+use super::traits::{Channel, ChannelMessage, OutboundAttachment, SendMessage};
 use async_trait::async_trait;
 use regex::Regex;
 use uuid::Uuid;
@@ -18,6 +19,31 @@ fn ensure_https(url: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Cloud API message body referencing an uploaded media ID.
+fn media_message_body(
+    to: &str,
+    media_id: &str,
+    attachment: &OutboundAttachment,
+) -> serde_json::Value {
+    let media_type = match attachment.kind() {
+        MediaKind::Image => "image",
+        MediaKind::Video => "video",
+        MediaKind::Audio => "audio",
+        MediaKind::Unknown => "document",
+    };
+    let mut media = serde_json::json!({ "id": media_id });
+    if media_type == "document" {
+        media["filename"] = serde_json::json!(attachment.file_name);
+    }
+    serde_json::json!({
+        "messaging_product": "whatsapp",
+        "recipient_type": "individual",
+        "to": to,
+        "type": media_type,
+        media_type: media
+    })
+}
+
 ///
 /// # Runtime Negotiation
 ///
@@ -158,6 +184,71 @@ impl WhatsAppChannel {
             .is_some_and(|s| !s.is_empty())
     }
 
+    /// POST a message body to the Cloud API `/messages` endpoint.
+    async fn post_message(&self, body: &serde_json::Value) -> anyhow::Result<()> {
+        let url = format!(
+            "https://graph.facebook.com/v18.0/{}/messages",
+            self.endpoint_id
+        );
+        ensure_https(&url)?;
+
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .header("Content-Type", "application/json")
+            .json(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp send failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp API error: {status}");
+        }
+
+        Ok(())
+    }
+
+    /// Upload an attachment to the Cloud API `/media` endpoint and return its media ID.
+    async fn upload_media(&self, attachment: &OutboundAttachment) -> anyhow::Result<String> {
+        let url = format!(
+            "https://graph.facebook.com/v18.0/{}/media",
+            self.endpoint_id
+        );
+        ensure_https(&url)?;
+
+        let part = reqwest::multipart::Part::bytes(attachment.data.clone())
+            .file_name(attachment.file_name.clone())
+            .mime_str(attachment.mime())?;
+        let form = reqwest::multipart::Form::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", attachment.mime().to_string())
+            .part("file", part);
+
+        let resp = self
+            .http_client()
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp media upload failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp media upload error: {status}");
+        }
+
+        let body: serde_json::Value = resp.json().await?;
+        body.get("id")
+            .and_then(|id| id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("WhatsApp media upload returned no id"))
+    }
+
     fn http_client(&self) -> reqwest::Client {
         crate::config::build_channel_proxy_client("channel.whatsapp", self.proxy_url.as_deref())
     }
@@ -293,46 +384,35 @@ impl Channel for WhatsAppChannel {
         "whatsapp"
     }
 
-    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
-        let url = format!(
-            "https://graph.facebook.com/v18.0/{}/messages",
-            self.endpoint_id
-        );
+    fn supports_attachments(&self) -> bool {
+        true
+    }
 
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
         // Normalize recipient (remove leading + if present for API)
         let to = message
             .recipient
             .strip_prefix('+')
             .unwrap_or(&message.recipient);
 
-        let body = serde_json::json!({
-            "messaging_product": "whatsapp",
-            "recipient_type": "individual",
-            "to": to,
-            "type": "text",
-            "text": {
-                "preview_url": false,
-                "body": message.content
-            }
-        });
-
-        ensure_https(&url)?;
-
-        let resp = self
-            .http_client()
-            .post(&url)
-            .bearer_auth(&self.access_token)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+        if !message.content.trim().is_empty() || message.attachments.is_empty() {
+            let body = serde_json::json!({
+                "messaging_product": "whatsapp",
+                "recipient_type": "individual",
+                "to": to,
+                "type": "text",
+                "text": {
+                    "preview_url": false,
+                    "body": message.content
+                }
+            });
+            self.post_message(&body).await?;
+        }
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let error_body = resp.text().await.unwrap_or_default();
-            tracing::error!("WhatsApp send failed: {status} — {error_body}");
-            anyhow::bail!("WhatsApp API error: {status}");
+        for attachment in &message.attachments {
+            let media_id = self.upload_media(attachment).await?;
+            self.post_message(&media_message_body(to, &media_id, attachment))
+                .await?;
         }
 
         Ok(())
@@ -396,6 +476,19 @@ mod tests {
         assert_eq!(ch.verify_token(), "verify-me");
     }
 
+    #[test]
+    fn whatsapp_media_message_body_uses_attachment_kind() {
+        let image = OutboundAttachment::new("chart.png", vec![1]);
+        let body = media_message_body("15551234567", "media-1", &image);
+        assert_eq!(body["type"], "image");
+        assert_eq!(body["image"]["id"], "media-1");
+
+        let report = OutboundAttachment::new("report.pdf", vec![1]);
+        let body = media_message_body("15551234567", "media-2", &report);
+        assert_eq!(body["type"], "document");
+        assert_eq!(body["document"]["filename"], "report.pdf");
+    }
+
     #[test]
     fn whatsapp_number_allowed_exact() {
         let ch = make_channel();
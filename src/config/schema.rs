@@ -110,6 +110,14 @@ pub struct Config {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub provider_max_tokens: Option<u32>,
 
+    /// Record every provider request/response pair to this JSON session trace.
+    ///
+    /// The resulting file can be replayed offline with
+    /// `default_provider = "replay:<path>"`. Can also be set via the
+    /// `ZEROCLAW_PROVIDER_RECORD_PATH` environment variable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_record_path: Option<String>,
+
     /// Extra HTTP headers to include in LLM provider API requests.
     ///
     /// Some providers require specific headers (e.g., `User-Agent`, `HTTP-Referer`,
@@ -210,6 +218,10 @@ pub struct Config {
     #[serde(default)]
     pub cron: CronConfig,
 
+    /// Hands (scheduled autonomous agents) configuration (`[hands]`).
+    #[serde(default)]
+    pub hands: HandsConfig,
+
     /// Channel configurations: Telegram, Discord, Slack, etc. (`[channels_config]`).
     #[serde(default)]
     pub channels_config: ChannelsConfig,
@@ -354,6 +366,10 @@ pub struct Config {
     #[serde(default, alias = "mcpServers")]
     pub mcp: McpConfig,
 
+    /// MCP server mode (`[mcp_serve]`).
+    #[serde(default)]
+    pub mcp_serve: McpServeConfig,
+
     /// Dynamic node discovery configuration (`[nodes]`).
     #[serde(default)]
     pub nodes: NodesConfig,
@@ -993,6 +1009,47 @@ pub struct McpServerConfig {
     /// Optional per-call timeout in seconds (hard capped in validation).
     #[serde(default)]
     pub tool_timeout_secs: Option<u64>,
+    /// Resource URIs read at startup and injected into the system prompt.
+    #[serde(default)]
+    pub context_resources: Vec<String>,
+    /// Servicing of this server's `sampling/createMessage` requests.
+    #[serde(default)]
+    pub sampling: McpSamplingConfig,
+}
+
+/// Per-server sampling budget (`[mcp.servers.sampling]`).
+///
+/// When enabled, the server may ask ZeroClaw's configured provider for
+/// completions. Both limits apply for the lifetime of the connection.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpSamplingConfig {
+    /// Advertise the sampling capability to this server (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+    /// Maximum number of sampling requests serviced (default: 20).
+    #[serde(default = "default_mcp_sampling_max_requests")]
+    pub max_requests: u32,
+    /// Maximum tokens (prompt + completion) spent on sampling (default: 20000).
+    #[serde(default = "default_mcp_sampling_max_tokens")]
+    pub max_tokens: u64,
+}
+
+fn default_mcp_sampling_max_requests() -> u32 {
+    20
+}
+
+fn default_mcp_sampling_max_tokens() -> u64 {
+    20_000
+}
+
+impl Default for McpSamplingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_requests: default_mcp_sampling_max_requests(),
+            max_tokens: default_mcp_sampling_max_tokens(),
+        }
+    }
 }
 
 /// External MCP client configuration (`[mcp]` section).
@@ -1026,6 +1083,35 @@ impl Default for McpConfig {
     }
 }
 
+/// MCP server mode configuration (`[mcp_serve]` section).
+///
+/// Controls what ZeroClaw exposes when acting as an MCP server, either over
+/// stdio (`zeroclaw mcp serve`) or streamable HTTP on the gateway (`POST /mcp`).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct McpServeConfig {
+    /// Mount the streamable-HTTP MCP endpoint on the gateway (default: false).
+    /// Requests use the same pairing bearer token as `/webhook`.
+    #[serde(default)]
+    pub gateway: bool,
+    /// Tool names to expose. Empty exposes every tool the security policy and
+    /// approval settings allow.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// Expose memory entries and recall as MCP resources (default: true).
+    #[serde(default = "default_true")]
+    pub memory_resources: bool,
+}
+
+impl Default for McpServeConfig {
+    fn default() -> Self {
+        Self {
+            gateway: false,
+            tools: Vec::new(),
+            memory_resources: true,
+        }
+    }
+}
+
 /// Verifiable Intent (VI) credential verification and issuance (`[verifiable_intent]` section).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct VerifiableIntentConfig {
@@ -1070,6 +1156,9 @@ pub struct NodesConfig {
     /// Optional bearer token for node authentication.
     #[serde(default)]
     pub auth_token: Option<String>,
+    /// Settings used by `zeroclaw node connect` on the node side.
+    #[serde(default)]
+    pub client: NodeClientConfig,
 }
 
 fn default_max_nodes() -> usize {
@@ -1082,6 +1171,56 @@ impl Default for NodesConfig {
             enabled: false,
             max_nodes: default_max_nodes(),
             auth_token: None,
+            client: NodeClientConfig::default(),
+        }
+    }
+}
+
+/// Headless node client configuration (`[nodes.client]`).
+///
+/// Used when this machine runs `zeroclaw node connect` to offer some of its
+/// local tools to a central gateway. Invocations run under this machine's own
+/// `[autonomy]` policy.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct NodeClientConfig {
+    /// Gateway node endpoint, e.g. `wss://gateway.example.com/ws/nodes`.
+    #[serde(default)]
+    pub gateway_url: Option<String>,
+    /// Node ID to register under. Defaults to the hostname.
+    #[serde(default)]
+    pub node_id: Option<String>,
+    /// Local tools to expose: `shell`, `file` (or individual file tools),
+    /// `screenshot` and `hardware`.
+    #[serde(default = "default_node_client_tools")]
+    pub tools: Vec<String>,
+    /// Seconds between heartbeats sent to the gateway.
+    #[serde(default = "default_node_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+    /// Upper bound for the reconnect backoff in seconds.
+    #[serde(default = "default_node_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+}
+
+fn default_node_client_tools() -> Vec<String> {
+    vec!["shell".into(), "file".into(), "screenshot".into()]
+}
+
+fn default_node_heartbeat_interval_secs() -> u64 {
+    15
+}
+
+fn default_node_max_backoff_secs() -> u64 {
+    60
+}
+
+impl Default for NodeClientConfig {
+    fn default() -> Self {
+        Self {
+            gateway_url: None,
+            node_id: None,
+            tools: default_node_client_tools(),
+            heartbeat_interval_secs: default_node_heartbeat_interval_secs(),
+            max_backoff_secs: default_node_max_backoff_secs(),
         }
     }
 }
@@ -1852,6 +1991,10 @@ pub struct CostConfig {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CostEnforcementConfig {
     /// Enforcement mode: "warn", "block", or "route_down".
+    ///
+    /// With "route_down", routed providers move requests to cheaper
+    /// `[[model_routes]]` once spend crosses `warn_at_percent` of a limit, and
+    /// reject them only when the limit itself is reached.
     #[serde(default = "default_cost_enforcement_mode")]
     pub mode: String,
     /// Model hint to route to when budget is exceeded (used with "route_down" mode).
@@ -1890,6 +2033,18 @@ pub struct ModelPricing {
     /// Output price per 1M tokens
     #[serde(default)]
     pub output: f64,
+
+    /// Price per 1M input tokens served from the provider's prompt cache.
+    /// Falls back to `input` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Effective price for cached input tokens.
+    pub fn cached_input_price(&self) -> f64 {
+        self.cached_input.unwrap_or(self.input)
+    }
 }
 
 fn default_daily_limit() -> f64 {
@@ -1932,6 +2087,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cached_input: Some(0.30),
         },
     );
     prices.insert(
@@ -1939,6 +2095,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 75.0,
+            cached_input: Some(1.50),
         },
     );
     prices.insert(
@@ -1946,6 +2103,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cached_input: Some(0.30),
         },
     );
     prices.insert(
@@ -1953,6 +2111,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.25,
             output: 1.25,
+            cached_input: Some(0.03),
         },
     );
 
@@ -1962,6 +2121,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 5.0,
             output: 15.0,
+            cached_input: Some(2.50),
         },
     );
     prices.insert(
@@ -1969,6 +2129,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.15,
             output: 0.60,
+            cached_input: Some(0.075),
         },
     );
     prices.insert(
@@ -1976,6 +2137,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 60.0,
+            cached_input: Some(7.50),
         },
     );
 
@@ -1985,6 +2147,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.10,
             output: 0.40,
+            cached_input: Some(0.025),
         },
     );
     prices.insert(
@@ -1992,6 +2155,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 1.25,
             output: 5.0,
+            cached_input: Some(0.3125),
         },
     );
 
@@ -2792,16 +2956,32 @@ pub struct ShellToolConfig {
     /// Maximum shell command execution time in seconds (default: 60).
     #[serde(default = "default_shell_tool_timeout_secs")]
     pub timeout_secs: u64,
+    /// Background processes (`process` tool) a session may run at once (default: 8).
+    #[serde(default = "default_shell_tool_max_background_processes")]
+    pub max_background_processes: usize,
+    /// Terminal output kept per background process, in bytes (default: 262144).
+    #[serde(default = "default_shell_tool_background_output_bytes")]
+    pub background_output_bytes: usize,
 }
 
 fn default_shell_tool_timeout_secs() -> u64 {
     60
 }
 
+fn default_shell_tool_max_background_processes() -> usize {
+    crate::tools::process::DEFAULT_MAX_PROCESSES
+}
+
+fn default_shell_tool_background_output_bytes() -> usize {
+    crate::tools::process::DEFAULT_OUTPUT_BUFFER_BYTES
+}
+
 impl Default for ShellToolConfig {
     fn default() -> Self {
         Self {
             timeout_secs: default_shell_tool_timeout_secs(),
+            max_background_processes: default_shell_tool_max_background_processes(),
+            background_output_bytes: default_shell_tool_background_output_bytes(),
         }
     }
 }
@@ -4136,6 +4316,10 @@ fn validate_mcp_config(config: &McpConfig) -> Result<()> {
             }
         }
 
+        if server.sampling.enabled && server.transport != McpTransport::Stdio {
+            anyhow::bail!("mcp.servers[{i}].sampling is only supported with transport=stdio");
+        }
+
         match server.transport {
             McpTransport::Stdio => {
                 if server.command.trim().is_empty() {
@@ -5325,6 +5509,102 @@ pub struct AutonomyConfig {
     /// model in tool specs.
     #[serde(default)]
     pub non_cli_excluded_tools: Vec<String>,
+
+    /// Interactive approval prompts on chat channels (`[autonomy.channel_approval]`).
+    #[serde(default)]
+    pub channel_approval: ChannelApprovalConfig,
+
+    /// Argument-aware tool rules (`[[autonomy.rules]]`), evaluated in order
+    /// before every tool call. The first matching rule decides; calls no rule
+    /// matches fall back to `auto_approve` / `always_ask`.
+    #[serde(default)]
+    pub rules: Vec<ToolRuleConfig>,
+}
+
+/// Outcome of a matching `[[autonomy.rules]]` entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolRuleAction {
+    /// Run without an approval prompt.
+    Allow,
+    /// Require approval, even for `auto_approve` tools or after "Always".
+    Ask,
+    /// Refuse the call.
+    Deny,
+}
+
+impl ToolRuleAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Ask => "ask",
+            Self::Deny => "deny",
+        }
+    }
+}
+
+/// A declarative tool rule (`[[autonomy.rules]]`).
+///
+/// A rule matches when `tool` matches the tool name and every non-empty
+/// argument predicate matches the call's arguments. A predicate whose
+/// argument is missing does not match.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ToolRuleConfig {
+    /// Tool name, or a glob such as `"file_*"` or `"*"`.
+    pub tool: String,
+    /// What to do when the rule matches.
+    pub action: ToolRuleAction,
+    /// Explanation shown when the rule denies or asks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Globs matched against the `path` argument (e.g. `"**/.env"`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    /// Host patterns matched against the `url` argument (e.g. `"*.internal"`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+    /// Domain patterns matched against `to` / `cc` / `bcc` recipients.
+    /// `deny` and `ask` match when any recipient matches; `allow` only when
+    /// all of them do.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recipient_domains: Vec<String>,
+    /// Subcommands matched against the `operation` argument of `git_operations`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub git_subcommands: Vec<String>,
+}
+
+/// Channel approval configuration (`[autonomy.channel_approval]`).
+///
+/// When enabled, tool calls that need approval during channel-driven runs are
+/// sent to the chat as an Approve / Deny / Always prompt instead of being
+/// auto-denied.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ChannelApprovalConfig {
+    /// Route approval prompts to chat channels. Default: `false` (auto-deny).
+    pub enabled: bool,
+    /// Seconds to wait for an answer before denying the call. Default: `300`.
+    pub timeout_secs: u64,
+    /// Sender identities allowed to answer prompts (`"*"` for anyone).
+    /// Empty means only the user whose message triggered the call, so it
+    /// must be set when `operator_channel` is.
+    pub approvers: Vec<String>,
+    /// Send prompts to this channel instead of the originating one.
+    pub operator_channel: Option<String>,
+    /// Recipient on `operator_channel` (chat, room or channel ID).
+    pub operator_target: Option<String>,
+}
+
+impl Default for ChannelApprovalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_secs: 300,
+            approvers: Vec::new(),
+            operator_channel: None,
+            operator_target: None,
+        }
+    }
 }
 
 fn default_auto_approve() -> Vec<String> {
@@ -5420,6 +5700,8 @@ impl Default for AutonomyConfig {
             always_ask: default_always_ask(),
             allowed_roots: Vec::new(),
             non_cli_excluded_tools: Vec::new(),
+            channel_approval: ChannelApprovalConfig::default(),
+            rules: Vec::new(),
         }
     }
 }
@@ -5437,6 +5719,10 @@ pub struct RuntimeConfig {
     #[serde(default)]
     pub docker: DockerRuntimeConfig,
 
+    /// cgroup v2 limits for shell and CLI tool subprocesses (used when `kind = "native"`).
+    #[serde(default)]
+    pub cgroup: CgroupConfig,
+
     /// Global reasoning override for providers that expose explicit controls.
     /// - `None`: provider default behavior
     /// - `Some(true)`: request reasoning/thinking when supported
@@ -5480,6 +5766,97 @@ pub struct DockerRuntimeConfig {
     pub allowed_workspace_roots: Vec<String>,
 }
 
+/// cgroup v2 resource limits for native runtime commands (`[runtime.cgroup]` section).
+///
+/// Each shell or CLI tool command runs in a transient cgroup under `parent`, which
+/// must be delegated to the daemon user (no systemd required). Top-level limits
+/// apply to every tool; `[runtime.cgroup.tools.<name>]` overrides them per tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CgroupConfig {
+    /// Place tool subprocesses in transient cgroups (default: false).
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Delegated cgroup to create transient groups under, relative to
+    /// `/sys/fs/cgroup` (default: the daemon's own cgroup).
+    #[serde(default)]
+    pub parent: Option<String>,
+
+    /// Memory limit in MB (`memory.max`; `None` = no limit).
+    #[serde(default = "default_cgroup_memory_max_mb")]
+    pub memory_max_mb: Option<u64>,
+
+    /// CPU limit in cores (`cpu.max`; e.g. `0.5`; `None` = no limit).
+    #[serde(default)]
+    pub cpu_limit: Option<f64>,
+
+    /// Maximum number of processes (`pids.max`; `None` = no limit).
+    #[serde(default = "default_cgroup_pids_max")]
+    pub pids_max: Option<u64>,
+
+    /// Proportional IO weight, 1-10000 (`io.weight`; `None` = kernel default).
+    #[serde(default)]
+    pub io_weight: Option<u16>,
+
+    /// Per-tool overrides keyed by tool name (`shell`, `claude_code`, ...).
+    #[serde(default)]
+    pub tools: HashMap<String, CgroupLimitsConfig>,
+}
+
+/// Per-tool cgroup limit overrides (`[runtime.cgroup.tools.<name>]`).
+///
+/// Unset fields inherit the top-level `[runtime.cgroup]` value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CgroupLimitsConfig {
+    /// Memory limit in MB.
+    #[serde(default)]
+    pub memory_max_mb: Option<u64>,
+    /// CPU limit in cores.
+    #[serde(default)]
+    pub cpu_limit: Option<f64>,
+    /// Maximum number of processes.
+    #[serde(default)]
+    pub pids_max: Option<u64>,
+    /// Proportional IO weight, 1-10000.
+    #[serde(default)]
+    pub io_weight: Option<u16>,
+}
+
+fn default_cgroup_memory_max_mb() -> Option<u64> {
+    Some(1024)
+}
+
+fn default_cgroup_pids_max() -> Option<u64> {
+    Some(512)
+}
+
+impl Default for CgroupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            parent: None,
+            memory_max_mb: default_cgroup_memory_max_mb(),
+            cpu_limit: None,
+            pids_max: default_cgroup_pids_max(),
+            io_weight: None,
+            tools: HashMap::new(),
+        }
+    }
+}
+
+impl CgroupConfig {
+    /// Effective limits for `tool`: per-tool overrides over the top-level values.
+    pub fn limits_for(&self, tool: &str) -> CgroupLimitsConfig {
+        let overrides = self.tools.get(tool).cloned().unwrap_or_default();
+        CgroupLimitsConfig {
+            memory_max_mb: overrides.memory_max_mb.or(self.memory_max_mb),
+            cpu_limit: overrides.cpu_limit.or(self.cpu_limit),
+            pids_max: overrides.pids_max.or(self.pids_max),
+            io_weight: overrides.io_weight.or(self.io_weight),
+        }
+    }
+}
+
 fn default_runtime_kind() -> String {
     "native".into()
 }
@@ -5519,6 +5896,7 @@ impl Default for RuntimeConfig {
         Self {
             kind: default_runtime_kind(),
             docker: DockerRuntimeConfig::default(),
+            cgroup: CgroupConfig::default(),
             reasoning_enabled: None,
             reasoning_effort: None,
         }
@@ -5669,6 +6047,10 @@ pub struct ModelRouteConfig {
     /// Optional API key override for this route's provider
     #[serde(default)]
     pub api_key: Option<String>,
+    /// Context window of the model in tokens. Budget downgrades skip this
+    /// route for requests that would not fit (unset = assume it fits).
+    #[serde(default)]
+    pub context_window: Option<usize>,
 }
 
 // ── Embedding routing ───────────────────────────────────────────
@@ -5962,6 +6344,34 @@ impl Default for CronConfig {
     }
 }
 
+// ── Hands ───────────────────────────────────────────────────────
+
+/// Hands scheduler configuration (`[hands]` section).
+///
+/// Independent of `[cron]`: the daemon runs hands whenever this is enabled.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HandsConfig {
+    /// Run hands on their schedules from the daemon. Default: `true`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Learned facts kept per hand; the oldest are dropped first. Default: `200`.
+    #[serde(default = "default_hands_max_facts")]
+    pub max_facts: usize,
+}
+
+fn default_hands_max_facts() -> usize {
+    200
+}
+
+impl Default for HandsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_facts: default_hands_max_facts(),
+        }
+    }
+}
+
 // ── Tunnel ──────────────────────────────────────────────────────
 
 /// Tunnel configuration for exposing the gateway publicly (`[tunnel]` section).
@@ -7497,6 +7907,24 @@ pub struct SandboxConfig {
     /// Custom Firejail arguments (when backend = firejail)
     #[serde(default)]
     pub firejail_args: Vec<String>,
+
+    /// Restrict TCP for sandboxed commands via Landlock (ABI v4+, default: false).
+    /// Follows `[http_request] allowed_domains`: `"*"` leaves TCP unrestricted,
+    /// otherwise TCP bind is denied and connect is limited to `allowed_ports`.
+    #[serde(default)]
+    pub restrict_network: bool,
+
+    /// TCP ports sandboxed commands may connect to when network is restricted
+    #[serde(default = "default_sandbox_allowed_ports")]
+    pub allowed_ports: Vec<u16>,
+
+    /// seccomp-bpf syscall filter, stacked on Landlock or used alone
+    #[serde(default)]
+    pub seccomp: SeccompConfig,
+}
+
+fn default_sandbox_allowed_ports() -> Vec<u16> {
+    vec![80, 443]
 }
 
 impl Default for SandboxConfig {
@@ -7505,10 +7933,44 @@ impl Default for SandboxConfig {
             enabled: None, // Auto-detect
             backend: SandboxBackend::Auto,
             firejail_args: Vec::new(),
+            restrict_network: false,
+            allowed_ports: default_sandbox_allowed_ports(),
+            seccomp: SeccompConfig::default(),
         }
     }
 }
 
+/// seccomp-bpf filter configuration (`[security.sandbox.seccomp]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SeccompConfig {
+    /// Install the filter in sandboxed commands (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Base syscall denylist
+    #[serde(default)]
+    pub profile: SeccompProfile,
+
+    /// Extra syscall names to deny on top of the profile
+    #[serde(default)]
+    pub deny_syscalls: Vec<String>,
+
+    /// Syscall names to remove from the profile's denylist
+    #[serde(default)]
+    pub allow_syscalls: Vec<String>,
+}
+
+/// Built-in seccomp denylists
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SeccompProfile {
+    /// ptrace, mount, kexec, module loading, bpf, keyring and similar
+    #[default]
+    Default,
+    /// Default plus unshare, chroot, clock and other host-level syscalls
+    Strict,
+}
+
 /// Sandbox backend selection
 #[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
 #[serde(rename_all = "lowercase")]
@@ -7527,6 +7989,8 @@ pub enum SandboxBackend {
     /// macOS sandbox-exec (Seatbelt)
     #[serde(alias = "sandbox-exec")]
     SandboxExec,
+    /// seccomp-bpf syscall filtering only (Linux)
+    Seccomp,
     /// No sandboxing (application-layer only)
     None,
 }
@@ -8267,6 +8731,7 @@ impl Default for Config {
             default_temperature: default_temperature(),
             provider_timeout_secs: default_provider_timeout_secs(),
             provider_max_tokens: None,
+            provider_record_path: None,
             extra_headers: HashMap::new(),
             observability: ObservabilityConfig::default(),
             autonomy: AutonomyConfig::default(),
@@ -8288,6 +8753,7 @@ impl Default for Config {
             embedding_routes: Vec::new(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
+            hands: HandsConfig::default(),
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
@@ -8320,6 +8786,7 @@ impl Default for Config {
             transcription: TranscriptionConfig::default(),
             tts: TtsConfig::default(),
             mcp: McpConfig::default(),
+            mcp_serve: McpServeConfig::default(),
             nodes: NodesConfig::default(),
             workspace: WorkspaceConfig::default(),
             notion: NotionConfig::default(),
@@ -9461,6 +9928,16 @@ impl Config {
                 );
             }
         }
+        crate::security::tool_rules::ToolPolicy::from_rules(&self.autonomy.rules)?;
+        let channel_approval = &self.autonomy.channel_approval;
+        if channel_approval.enabled
+            && channel_approval.operator_channel.is_some()
+            && channel_approval.approvers.is_empty()
+        {
+            anyhow::bail!(
+                "autonomy.channel_approval.approvers must not be empty when operator_channel is set"
+            );
+        }
 
         // Security OTP / estop
         if self.security.otp.challenge_max_attempts == 0 {
@@ -9694,6 +10171,57 @@ impl Config {
             validate_mcp_config(&self.mcp)?;
         }
 
+        // Runtime cgroup limits
+        if self.runtime.cgroup.enabled {
+            let cgroup = &self.runtime.cgroup;
+            let mut tools: Vec<&String> = cgroup.tools.keys().collect();
+            tools.sort();
+            for (scope, limits) in
+                std::iter::once(("runtime.cgroup".to_string(), cgroup.limits_for(""))).chain(
+                    tools.into_iter().map(|tool| {
+                        (
+                            format!("runtime.cgroup.tools.{tool}"),
+                            cgroup.limits_for(tool),
+                        )
+                    }),
+                )
+            {
+                if limits.memory_max_mb == Some(0) {
+                    anyhow::bail!("{scope}.memory_max_mb must be greater than 0");
+                }
+                if limits.pids_max == Some(0) {
+                    anyhow::bail!("{scope}.pids_max must be greater than 0");
+                }
+                if limits
+                    .cpu_limit
+                    .is_some_and(|cpus| !cpus.is_finite() || cpus <= 0.0)
+                {
+                    anyhow::bail!("{scope}.cpu_limit must be a positive number of cores");
+                }
+                if limits.io_weight.is_some_and(|w| !(1..=10_000).contains(&w)) {
+                    anyhow::bail!("{scope}.io_weight must be between 1 and 10000");
+                }
+            }
+        }
+
+        // Sandbox seccomp overrides
+        #[cfg(target_os = "linux")]
+        {
+            let seccomp = &self.security.sandbox.seccomp;
+            for (field, names) in [
+                ("deny_syscalls", &seccomp.deny_syscalls),
+                ("allow_syscalls", &seccomp.allow_syscalls),
+            ] {
+                let unknown = crate::security::seccomp::unknown_syscalls(names);
+                if !unknown.is_empty() {
+                    anyhow::bail!(
+                        "security.sandbox.seccomp.{field} contains unsupported syscall(s): {}",
+                        unknown.join(", ")
+                    );
+                }
+            }
+        }
+
         // Knowledge graph
         if self.knowledge.enabled {
             if self.knowledge.max_nodes == 0 {
@@ -10059,6 +10587,14 @@ impl Config {
             }
         }
 
+        // Provider session recording: ZEROCLAW_PROVIDER_RECORD_PATH
+        if let Ok(path) = std::env::var("ZEROCLAW_PROVIDER_RECORD_PATH") {
+            let path = path.trim();
+            if !path.is_empty() {
+                self.provider_record_path = Some(path.to_string());
+            }
+        }
+
         // Extra provider headers: ZEROCLAW_EXTRA_HEADERS
         // Format: "Key:Value,Key2:Value2"
         // Env var headers override config file headers with the same name.
@@ -11284,6 +11820,7 @@ auto_save = true
             default_temperature: 0.5,
             provider_timeout_secs: 120,
             provider_max_tokens: None,
+            provider_record_path: None,
             extra_headers: HashMap::new(),
             observability: ObservabilityConfig {
                 backend: "log".into(),
@@ -11303,6 +11840,8 @@ auto_save = true
                 always_ask: vec![],
                 allowed_roots: vec![],
                 non_cli_excluded_tools: vec![],
+                channel_approval: ChannelApprovalConfig::default(),
+                rules: vec![],
             },
             trust: crate::trust::TrustConfig::default(),
             backup: BackupConfig::default(),
@@ -11332,6 +11871,7 @@ auto_save = true
                 ..HeartbeatConfig::default()
             },
             cron: CronConfig::default(),
+            hands: HandsConfig::default(),
             channels_config: ChannelsConfig {
                 cli: true,
                 telegram: Some(TelegramConfig {
@@ -11414,6 +11954,7 @@ auto_save = true
             transcription: TranscriptionConfig::default(),
             tts: TtsConfig::default(),
             mcp: McpConfig::default(),
+            mcp_serve: McpServeConfig::default(),
             nodes: NodesConfig::default(),
             workspace: WorkspaceConfig::default(),
             notion: NotionConfig::default(),
@@ -11891,6 +12432,7 @@ default_temperature = 0.7
             default_temperature: 0.9,
             provider_timeout_secs: 120,
             provider_max_tokens: None,
+            provider_record_path: None,
             extra_headers: HashMap::new(),
             observability: ObservabilityConfig::default(),
             autonomy: AutonomyConfig::default(),
@@ -11911,6 +12453,7 @@ default_temperature = 0.7
             query_classification: QueryClassificationConfig::default(),
             heartbeat: HeartbeatConfig::default(),
             cron: CronConfig::default(),
+            hands: HandsConfig::default(),
             channels_config: ChannelsConfig::default(),
             memory: MemoryConfig::default(),
             storage: StorageConfig::default(),
@@ -11944,6 +12487,7 @@ default_temperature = 0.7
             transcription: TranscriptionConfig::default(),
             tts: TtsConfig::default(),
             mcp: McpConfig::default(),
+            mcp_serve: McpServeConfig::default(),
             nodes: NodesConfig::default(),
             workspace: WorkspaceConfig::default(),
             notion: NotionConfig::default(),
@@ -14278,6 +14822,65 @@ default_model = "persisted-profile"
         clear_proxy_env_test_vars();
     }
 
+    #[test]
+    async fn runtime_cgroup_tool_overrides_inherit_defaults() {
+        let runtime: RuntimeConfig = toml::from_str(
+            r#"
+[cgroup]
+enabled = true
+cpu_limit = 1.0
+
+[cgroup.tools.shell]
+memory_max_mb = 2048
+"#,
+        )
+        .unwrap();
+        let shell = runtime.cgroup.limits_for("shell");
+        assert_eq!(shell.memory_max_mb, Some(2048));
+        assert_eq!(shell.cpu_limit, Some(1.0));
+        assert_eq!(shell.pids_max, Some(512));
+        assert_eq!(
+            runtime.cgroup.limits_for("codex_cli").memory_max_mb,
+            Some(1024)
+        );
+    }
+
+    #[test]
+    async fn runtime_cgroup_rejects_invalid_io_weight() {
+        let mut config = Config::default();
+        config.runtime.cgroup.enabled = true;
+        config.runtime.cgroup.tools.insert(
+            "shell".into(),
+            CgroupLimitsConfig {
+                io_weight: Some(0),
+                ..CgroupLimitsConfig::default()
+            },
+        );
+
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("runtime.cgroup.tools.shell.io_weight"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    async fn sandbox_seccomp_rejects_unknown_syscalls() {
+        let mut config = Config::default();
+        config.security.sandbox.seccomp.deny_syscalls = vec!["not_a_syscall".into()];
+
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("security.sandbox.seccomp.deny_syscalls"));
+        assert!(err.contains("not_a_syscall"));
+    }
+
+    #[test]
+    async fn sandbox_network_defaults_allow_web_ports() {
+        let sandbox: SandboxConfig = toml::from_str("restrict_network = true").unwrap();
+        assert!(sandbox.restrict_network);
+        assert_eq!(sandbox.allowed_ports, vec![80, 443]);
+        assert!(!sandbox.seccomp.enabled);
+        assert_eq!(sandbox.seccomp.profile, SeccompProfile::Default);
+    }
+
     #[test]
     async fn google_workspace_allowed_operations_require_methods() {
         let mut config = Config::default();
@@ -14818,6 +15421,28 @@ require_otp_to_resume = true
         );
     }
 
+    #[test]
+    async fn validate_rejects_operator_channel_without_approvers() {
+        let mut config = Config::default();
+        config.autonomy.channel_approval = ChannelApprovalConfig {
+            enabled: true,
+            operator_channel: Some("slack".into()),
+            operator_target: Some("C-ops".into()),
+            ..ChannelApprovalConfig::default()
+        };
+
+        let err = config
+            .validate()
+            .expect_err("expected validation to reject operator_channel without approvers");
+        assert!(
+            err.to_string().contains("channel_approval.approvers"),
+            "got: {err}"
+        );
+
+        config.autonomy.channel_approval.approvers = vec!["ops-lead".into()];
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     async fn validate_rejects_unknown_transcription_default_provider() {
         let mut config = Config::default();
@@ -15082,6 +15707,19 @@ require_otp_to_resume = true
         );
     }
 
+    #[test]
+    async fn validate_mcp_config_rejects_sampling_over_http() {
+        let mut server = http_server("svc", "https://example.com/mcp");
+        server.sampling.enabled = true;
+        let cfg = McpConfig {
+            enabled: true,
+            servers: vec![server],
+            ..Default::default()
+        };
+        let err = validate_mcp_config(&cfg).expect_err("sampling over http should fail");
+        assert!(err.to_string().contains("sampling"), "got: {err}");
+    }
+
     #[test]
     async fn validate_mcp_config_rejects_http_without_url() {
         let cfg = McpConfig {
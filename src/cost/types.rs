@@ -7,6 +7,9 @@ pub struct TokenUsage {
     pub model: String,
     /// Input/prompt tokens
     pub input_tokens: u64,
+    /// Portion of `input_tokens` served from the provider's prompt cache
+    #[serde(default)]
+    pub cached_input_tokens: u64,
     /// Output/completion tokens
     pub output_tokens: u64,
     /// Total tokens
@@ -33,20 +36,48 @@ impl TokenUsage {
         output_tokens: u64,
         input_price_per_million: f64,
         output_price_per_million: f64,
+    ) -> Self {
+        Self::with_cached_input(
+            model,
+            input_tokens,
+            0,
+            output_tokens,
+            input_price_per_million,
+            input_price_per_million,
+            output_price_per_million,
+        )
+    }
+
+    /// Create a usage record where `cached_input_tokens` of the
+    /// `input_tokens` were prompt-cache hits, billed at the cached rate.
+    pub fn with_cached_input(
+        model: impl Into<String>,
+        input_tokens: u64,
+        cached_input_tokens: u64,
+        output_tokens: u64,
+        input_price_per_million: f64,
+        cached_input_price_per_million: f64,
+        output_price_per_million: f64,
     ) -> Self {
         let model = model.into();
         let input_price_per_million = Self::sanitize_price(input_price_per_million);
+        let cached_input_price_per_million = Self::sanitize_price(cached_input_price_per_million);
         let output_price_per_million = Self::sanitize_price(output_price_per_million);
+        let cached_input_tokens = cached_input_tokens.min(input_tokens);
+        let uncached_input_tokens = input_tokens - cached_input_tokens;
         let total_tokens = input_tokens.saturating_add(output_tokens);
 
         // Calculate cost: (tokens / 1M) * price_per_million
-        let input_cost = (input_tokens as f64 / 1_000_000.0) * input_price_per_million;
+        let input_cost = (uncached_input_tokens as f64 / 1_000_000.0) * input_price_per_million;
+        let cached_input_cost =
+            (cached_input_tokens as f64 / 1_000_000.0) * cached_input_price_per_million;
         let output_cost = (output_tokens as f64 / 1_000_000.0) * output_price_per_million;
-        let cost_usd = input_cost + output_cost;
+        let cost_usd = input_cost + cached_input_cost + output_cost;
 
         Self {
             model,
             input_tokens,
+            cached_input_tokens,
             output_tokens,
             total_tokens,
             cost_usd,
@@ -167,6 +198,32 @@ mod tests {
         assert_eq!(usage.total_tokens, 1500);
     }
 
+    #[test]
+    fn token_usage_prices_cached_input_separately() {
+        let usage =
+            TokenUsage::with_cached_input("test/model", 10_000, 8_000, 1000, 3.0, 0.3, 15.0);
+
+        // Expected: (2000/1M)*3 + (8000/1M)*0.3 + (1000/1M)*15 = 0.006 + 0.0024 + 0.015
+        assert!((usage.cost_usd - 0.0234).abs() < 0.000_001);
+        assert_eq!(usage.input_tokens, 10_000);
+        assert_eq!(usage.cached_input_tokens, 8_000);
+        assert_eq!(usage.total_tokens, 11_000);
+    }
+
+    #[test]
+    fn token_usage_clamps_cached_tokens_to_input() {
+        let usage = TokenUsage::with_cached_input("test/model", 100, 500, 0, 1.0, 0.5, 1.0);
+        assert_eq!(usage.cached_input_tokens, 100);
+        assert!((usage.cost_usd - 0.000_05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn token_usage_deserializes_records_without_cached_tokens() {
+        let json = r#"{"model":"m","input_tokens":1,"output_tokens":2,"total_tokens":3,"cost_usd":0.0,"timestamp":"2026-01-01T00:00:00Z"}"#;
+        let usage: TokenUsage = serde_json::from_str(json).unwrap();
+        assert_eq!(usage.cached_input_tokens, 0);
+    }
+
     #[test]
     fn token_usage_zero_tokens() {
         let usage = TokenUsage::new("test/model", 0, 0, 3.0, 15.0);
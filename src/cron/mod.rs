@@ -15,11 +15,12 @@ pub use schedule::{
 #[allow(unused_imports)]
 pub use store::{
     add_agent_job, all_overdue_jobs, due_jobs, get_job, list_jobs, list_runs, record_last_run,
-    record_run, remove_job, reschedule_after_run, sync_declarative_jobs, update_job,
+    record_run, remove_job, reschedule_after_run, skip_scheduled_run, sync_declarative_jobs,
+    update_job,
 };
 pub use types::{
-    deserialize_maybe_stringified, CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType,
-    Schedule, SessionTarget,
+    deserialize_maybe_stringified, ConcurrencyPolicy, CronJob, CronJobPatch, CronRun,
+    DeliveryConfig, JobType, RunStatus, Schedule, SessionTarget,
 };
 
 /// Validate a shell command against the full security policy (allowlist + risk gate).
@@ -27,7 +28,8 @@ pub use types::{
 /// Returns `Ok(())` if the command passes all checks, or an error describing
 /// why it was blocked.
 pub fn validate_shell_command(config: &Config, command: &str, approved: bool) -> Result<()> {
-    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+        .with_trust(crate::trust::runtime::init(config));
     validate_shell_command_with_security(&security, command, approved)
 }
 
@@ -133,6 +135,35 @@ pub fn add_once_at_validated(
     add_shell_job_with_approval(config, None, schedule, command, None, approved)
 }
 
+/// Check that every job named in the `depends_on` / `on_success` /
+/// `on_failure` lists of `options` exists, before creating a job with them.
+pub fn validate_job_references(config: &Config, options: &CronJobPatch) -> Result<()> {
+    for id in options
+        .depends_on
+        .iter()
+        .chain(&options.on_success)
+        .chain(&options.on_failure)
+        .flatten()
+    {
+        get_job(config, id)?;
+    }
+    Ok(())
+}
+
+/// Apply creation-time options (chaining, concurrency policy, timeout) to a
+/// job that was just added. A patch with none of them set is a no-op.
+pub fn apply_job_options(config: &Config, job: CronJob, options: CronJobPatch) -> Result<CronJob> {
+    if options.depends_on.is_none()
+        && options.on_success.is_none()
+        && options.on_failure.is_none()
+        && options.concurrency_policy.is_none()
+        && options.timeout_secs.is_none()
+    {
+        return Ok(job);
+    }
+    update_job(config, &job.id, options)
+}
+
 // Convenience wrappers for CLI paths (default approved=false).
 
 pub(crate) fn add_shell_job(
@@ -184,6 +215,25 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
                 if let Some(prompt) = &job.prompt {
                     println!("    prompt: {prompt}");
                 }
+                if !job.depends_on.is_empty() {
+                    println!("    after: {}", job.depends_on.join(", "));
+                }
+                if !job.on_success.is_empty() || !job.on_failure.is_empty() {
+                    println!(
+                        "    on_success: [{}] on_failure: [{}]",
+                        job.on_success.join(", "),
+                        job.on_failure.join(", ")
+                    );
+                }
+                if job.concurrency_policy != ConcurrencyPolicy::Allow || job.timeout_secs.is_some()
+                {
+                    println!(
+                        "    concurrency: {} | timeout: {}",
+                        job.concurrency_policy.as_str(),
+                        job.timeout_secs
+                            .map_or_else(|| "default".into(), |secs| format!("{secs}s"))
+                    );
+                }
             }
             Ok(())
         }
@@ -276,7 +326,10 @@ pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<(
             allowed_tools,
             command,
         } => {
-            let schedule = Schedule::Every { every_ms };
+            let schedule = Schedule::Every {
+                every_ms,
+                jitter_ms: 0,
+            };
             if agent {
                 let job = add_agent_job(
                     config,
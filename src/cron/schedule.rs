@@ -26,11 +26,20 @@ pub fn next_run_for_schedule(schedule: &Schedule, from: DateTime<Utc>) -> Result
             }
         }
         Schedule::At { at } => Ok(*at),
-        Schedule::Every { every_ms } => {
+        Schedule::Every {
+            every_ms,
+            jitter_ms,
+        } => {
             if *every_ms == 0 {
                 anyhow::bail!("Invalid schedule: every_ms must be > 0");
             }
-            let ms = i64::try_from(*every_ms).context("every_ms is too large")?;
+            let jitter = if *jitter_ms == 0 {
+                0
+            } else {
+                rand::random::<u64>() % (jitter_ms + 1)
+            };
+            let ms =
+                i64::try_from(every_ms.saturating_add(jitter)).context("every_ms is too large")?;
             let delta = ChronoDuration::milliseconds(ms);
             from.checked_add_signed(delta)
                 .ok_or_else(|| anyhow::anyhow!("every_ms overflowed DateTime"))
@@ -51,10 +60,16 @@ pub fn validate_schedule(schedule: &Schedule, now: DateTime<Utc>) -> Result<()>
             }
             Ok(())
         }
-        Schedule::Every { every_ms } => {
+        Schedule::Every {
+            every_ms,
+            jitter_ms,
+        } => {
             if *every_ms == 0 {
                 anyhow::bail!("Invalid schedule: every_ms must be > 0");
             }
+            if *jitter_ms >= *every_ms {
+                anyhow::bail!("Invalid schedule: jitter_ms must be less than every_ms");
+            }
             Ok(())
         }
     }
@@ -170,7 +185,10 @@ mod tests {
     #[test]
     fn next_run_for_schedule_supports_every_and_at() {
         let now = Utc::now();
-        let every = Schedule::Every { every_ms: 60_000 };
+        let every = Schedule::Every {
+            every_ms: 60_000,
+            jitter_ms: 0,
+        };
         let next = next_run_for_schedule(&every, now).unwrap();
         assert!(next > now);
 
@@ -180,6 +198,25 @@ mod tests {
         assert_eq!(next_at, at);
     }
 
+    #[test]
+    fn every_jitter_stays_within_bounds() {
+        let now = Utc::now();
+        let every = Schedule::Every {
+            every_ms: 60_000,
+            jitter_ms: 5_000,
+        };
+        for _ in 0..50 {
+            let delay = (next_run_for_schedule(&every, now).unwrap() - now).num_milliseconds();
+            assert!((60_000..=65_000).contains(&delay), "delay {delay}ms");
+        }
+
+        let too_wide = Schedule::Every {
+            every_ms: 1_000,
+            jitter_ms: 1_000,
+        };
+        assert!(validate_schedule(&too_wide, now).is_err());
+    }
+
     #[test]
     fn next_run_for_schedule_supports_timezone() {
         let from = Utc.with_ymd_and_hms(2026, 2, 16, 0, 0, 0).unwrap();
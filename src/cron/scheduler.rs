@@ -9,31 +9,73 @@ use crate::channels::{
 use crate::config::schema::{CronJobDecl, CronScheduleDecl};
 use crate::config::Config;
 use crate::cron::{
-    all_overdue_jobs, due_jobs, next_run_for_schedule, record_last_run, record_run, remove_job,
-    reschedule_after_run, sync_declarative_jobs, update_job, CronJob, CronJobPatch, DeliveryConfig,
-    JobType, Schedule, SessionTarget,
+    all_overdue_jobs, due_jobs, get_job, next_run_for_schedule, record_last_run, record_run,
+    remove_job, reschedule_after_run, skip_scheduled_run, sync_declarative_jobs, update_job,
+    ConcurrencyPolicy, CronJob, CronJobPatch, DeliveryConfig, JobType, RunStatus, Schedule,
+    SessionTarget,
 };
 use crate::security::SecurityPolicy;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use futures_util::{stream, StreamExt};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::process::Stdio;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use tokio::process::Command;
+use tokio::task::AbortHandle;
 use tokio::time::{self, Duration};
 
 const MIN_POLL_SECONDS: u64 = 5;
-const SHELL_JOB_TIMEOUT_SECS: u64 = 120;
+pub(crate) const SHELL_JOB_TIMEOUT_SECS: u64 = 120;
 const SCHEDULER_COMPONENT: &str = "scheduler";
+const TRIGGER_SCHEDULE: &str = "schedule";
+const TIMED_OUT_PREFIX: &str = "job timed out after";
+/// `on_success` / `on_failure` chains stop after this many hops, so jobs that
+/// trigger each other cannot loop forever.
+const MAX_CHAIN_DEPTH: usize = 8;
+
+/// Runs in flight in this process, keyed by job ID. Backs
+/// `concurrency_policy`: `forbid` skips while a run is registered, `replace`
+/// aborts the registered runs first.
+static RUNNING_JOBS: OnceLock<Mutex<RunningJobs>> = OnceLock::new();
+static NEXT_RUN_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+type RunningJobs = HashMap<String, Vec<(u64, AbortHandle)>>;
+
+fn running_jobs() -> &'static Mutex<RunningJobs> {
+    RUNNING_JOBS.get_or_init(Mutex::default)
+}
+
+/// Removes a run from [`RUNNING_JOBS`] when it finishes or its caller goes away.
+struct RunningGuard {
+    job_id: String,
+    token: u64,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        let mut running = running_jobs().lock();
+        if let Some(entries) = running.get_mut(&self.job_id) {
+            entries.retain(|(token, _)| *token != self.token);
+            if entries.is_empty() {
+                running.remove(&self.job_id);
+            }
+        }
+    }
+}
 
 pub async fn run(config: Config) -> Result<()> {
     let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
     let mut interval = time::interval(Duration::from_secs(poll_secs));
     interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_trust(crate::trust::runtime::init(&config)),
+    );
 
     crate::health::mark_component_ok(SCHEDULER_COMPONENT);
 
@@ -134,9 +176,71 @@ async fn catch_up_overdue_jobs(config: &Config, security: &Arc<SecurityPolicy>)
     tracing::info!("Scheduler startup: catch-up complete");
 }
 
-pub async fn execute_job_now(config: &Config, job: &CronJob) -> (bool, String) {
-    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
-    Box::pin(execute_job_with_retry(config, &security, job)).await
+/// Run a job immediately (manual or routine trigger), honouring its
+/// concurrency policy and timeout but not its `depends_on` gate.
+pub async fn execute_job_now(config: &Config, job: &CronJob) -> (RunStatus, String) {
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_trust(crate::trust::runtime::init(config)),
+    );
+    Box::pin(execute_job_guarded(config, &security, job)).await
+}
+
+/// Run a job under its `concurrency_policy`. The run is spawned so that a
+/// later `replace` can abort it; dropping a shell job's child kills it.
+async fn execute_job_guarded(
+    config: &Config,
+    security: &Arc<SecurityPolicy>,
+    job: &CronJob,
+) -> (RunStatus, String) {
+    let token = NEXT_RUN_TOKEN.fetch_add(1, Ordering::Relaxed);
+    let handle = {
+        let mut running = running_jobs().lock();
+        let entries = running.entry(job.id.clone()).or_default();
+        match job.concurrency_policy {
+            ConcurrencyPolicy::Forbid if !entries.is_empty() => {
+                return (
+                    RunStatus::Skipped,
+                    "skipped: previous run still in progress".to_string(),
+                );
+            }
+            ConcurrencyPolicy::Replace => {
+                for (_, abort) in entries.drain(..) {
+                    abort.abort();
+                }
+            }
+            ConcurrencyPolicy::Allow | ConcurrencyPolicy::Forbid => {}
+        }
+
+        let config = config.clone();
+        let security = Arc::clone(security);
+        let owned_job = job.clone();
+        let handle = tokio::spawn(async move {
+            Box::pin(execute_job_with_retry(&config, &security, &owned_job)).await
+        });
+        entries.push((token, handle.abort_handle()));
+        handle
+    };
+    let _guard = RunningGuard {
+        job_id: job.id.clone(),
+        token,
+    };
+
+    match handle.await {
+        Ok((success, output)) => (run_status(success, &output), output),
+        Err(e) if e.is_cancelled() => (RunStatus::Replaced, "replaced by a newer run".to_string()),
+        Err(e) => (RunStatus::Error, format!("job panicked: {e}")),
+    }
+}
+
+fn run_status(success: bool, output: &str) -> RunStatus {
+    if success {
+        RunStatus::Ok
+    } else if output.starts_with(TIMED_OUT_PREFIX) {
+        RunStatus::Timeout
+    } else {
+        RunStatus::Error
+    }
 }
 
 async fn execute_job_with_retry(
@@ -149,9 +253,15 @@ async fn execute_job_with_retry(
     let mut backoff_ms = config.reliability.provider_backoff_ms.max(200);
 
     for attempt in 0..=retries {
-        let (success, output) = match job.job_type {
-            JobType::Shell => run_job_command(config, security, job).await,
-            JobType::Agent => Box::pin(run_agent_job(config, security, job)).await,
+        let (success, output) = match (&job.job_type, job.timeout_secs) {
+            (JobType::Shell, _) => run_job_command(config, security, job).await,
+            (JobType::Agent, Some(secs)) => time::timeout(
+                Duration::from_secs(secs),
+                Box::pin(run_agent_job(config, security, job)),
+            )
+            .await
+            .unwrap_or_else(|_| (false, format!("{TIMED_OUT_PREFIX} {secs}s"))),
+            (JobType::Agent, None) => Box::pin(run_agent_job(config, security, job)).await,
         };
         last_output = output;
 
@@ -159,8 +269,11 @@ async fn execute_job_with_retry(
             return (true, last_output);
         }
 
-        if last_output.starts_with("blocked by security policy:") {
-            // Deterministic policy violations are not retryable.
+        if last_output.starts_with("blocked by security policy:")
+            || last_output.starts_with(TIMED_OUT_PREFIX)
+        {
+            // Deterministic policy violations are not retryable, and a run
+            // that hit its deadline must not get another full attempt.
             return (false, last_output);
         }
 
@@ -191,9 +304,11 @@ async fn process_due_jobs(
         async move {
             Box::pin(execute_and_persist_job(
                 &config,
-                security.as_ref(),
+                &security,
                 &job,
                 &component,
+                TRIGGER_SCHEDULE,
+                0,
             ))
             .await
         }
@@ -209,29 +324,146 @@ async fn process_due_jobs(
 
 async fn execute_and_persist_job(
     config: &Config,
-    security: &SecurityPolicy,
+    security: &Arc<SecurityPolicy>,
     job: &CronJob,
     component: &str,
+    trigger: &str,
+    depth: usize,
 ) -> (String, bool, String) {
     crate::health::mark_component_ok(component);
     warn_if_high_frequency_agent_job(job);
 
     let started_at = Utc::now();
-    let (success, output) = Box::pin(execute_job_with_retry(config, security, job)).await;
+    let (status, output) = match unmet_dependency(config, job) {
+        Some(reason) => (RunStatus::Skipped, reason),
+        None => Box::pin(execute_job_guarded(config, security, job)).await,
+    };
     let finished_at = Utc::now();
+
+    if matches!(status, RunStatus::Skipped | RunStatus::Replaced) {
+        // Nothing to deliver or chain; a replaced run's successor persists
+        // the job state, a skipped scheduled run just moves on.
+        tracing::info!("Cron job '{}' {}: {output}", job.id, status.as_str());
+        let _ = record_run(
+            config,
+            &job.id,
+            started_at,
+            finished_at,
+            status.as_str(),
+            trigger,
+            Some(&output),
+            (finished_at - started_at).num_milliseconds(),
+        );
+        if status == RunStatus::Skipped && trigger == TRIGGER_SCHEDULE {
+            if let Err(e) = skip_scheduled_run(config, job) {
+                tracing::warn!("Failed to advance skipped cron job: {e}");
+            }
+        }
+        return (job.id.clone(), true, output);
+    }
+
     let success = Box::pin(persist_job_result(
         config,
         job,
-        success,
+        status,
         &output,
         started_at,
         finished_at,
+        trigger,
     ))
     .await;
 
+    crate::routines::bus::publish(crate::routines::RoutineEvent::new(
+        crate::routines::bus::SOURCE_CRON,
+        job.name.clone().unwrap_or_else(|| job.id.clone()),
+        Some(
+            serde_json::json!({
+                "job_id": job.id,
+                "status": if success { "ok" } else { "error" },
+                "output": output,
+            })
+            .to_string(),
+        ),
+    ));
+
+    run_chained_jobs(config, security, job, success, component, depth).await;
+
     (job.id.clone(), success, output)
 }
 
+/// Why a job must not run yet: a `depends_on` job is missing or its last run
+/// did not succeed.
+fn unmet_dependency(config: &Config, job: &CronJob) -> Option<String> {
+    job.depends_on
+        .iter()
+        .find_map(|dep_id| match get_job(config, dep_id) {
+            Ok(dep) if dep.last_status.as_deref() == Some("ok") => None,
+            Ok(dep) => Some(format!(
+                "skipped: dependency '{dep_id}' has not succeeded (last status: {})",
+                dep.last_status.as_deref().unwrap_or("never run")
+            )),
+            Err(_) => Some(format!("skipped: dependency '{dep_id}' not found")),
+        })
+}
+
+/// Run a finished job's `on_success` or `on_failure` targets one after another.
+///
+/// Returns a boxed `Send` future to break the async recursion with
+/// [`execute_and_persist_job`].
+fn run_chained_jobs<'a>(
+    config: &'a Config,
+    security: &'a Arc<SecurityPolicy>,
+    job: &'a CronJob,
+    success: bool,
+    component: &'a str,
+    depth: usize,
+) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+    Box::pin(async move {
+        let (targets, kind) = if success {
+            (&job.on_success, "on_success")
+        } else {
+            (&job.on_failure, "on_failure")
+        };
+        if targets.is_empty() {
+            return;
+        }
+        if depth >= MAX_CHAIN_DEPTH {
+            tracing::warn!(
+                "Cron job '{}' reached the chain depth limit ({MAX_CHAIN_DEPTH}); not running its {kind} jobs",
+                job.id
+            );
+            return;
+        }
+
+        let trigger = format!("{kind}:{}", job.id);
+        for target_id in targets {
+            let target = match get_job(config, target_id) {
+                Ok(target) if target.enabled => target,
+                Ok(_) => {
+                    tracing::debug!("Skipping disabled chained cron job '{target_id}'");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::warn!("Chained cron job '{target_id}' unavailable: {e}");
+                    continue;
+                }
+            };
+            let (_, ok, output) = Box::pin(execute_and_persist_job(
+                config,
+                security,
+                &target,
+                component,
+                &trigger,
+                depth + 1,
+            ))
+            .await;
+            if !ok {
+                tracing::warn!("Chained cron job '{target_id}' failed: {output}");
+            }
+        }
+    })
+}
+
 async fn run_agent_job(
     config: &Config,
     security: &SecurityPolicy,
@@ -316,10 +548,11 @@ async fn run_agent_job(
 async fn persist_job_result(
     config: &Config,
     job: &CronJob,
-    mut success: bool,
+    mut status: RunStatus,
     output: &str,
     started_at: DateTime<Utc>,
     finished_at: DateTime<Utc>,
+    trigger: &str,
 ) -> bool {
     let duration_ms = (finished_at - started_at).num_milliseconds();
 
@@ -327,21 +560,32 @@ async fn persist_job_result(
         if job.delivery.best_effort {
             tracing::warn!("Cron delivery failed (best_effort): {e}");
         } else {
-            success = false;
+            if status.is_success() {
+                status = RunStatus::Error;
+            }
             tracing::warn!("Cron delivery failed: {e}");
         }
     }
+    let success = status.is_success();
 
     let _ = record_run(
         config,
         &job.id,
         started_at,
         finished_at,
-        if success { "ok" } else { "error" },
+        status.as_str(),
+        trigger,
         Some(output),
         duration_ms,
     );
 
+    // Chained runs behave like manual ones: they don't move the job's own
+    // schedule or retire one-shot jobs.
+    if trigger != TRIGGER_SCHEDULE {
+        let _ = record_last_run(config, &job.id, finished_at, success, output);
+        return success;
+    }
+
     if is_one_shot_auto_delete(job) {
         if success {
             if let Err(e) = remove_job(config, &job.id) {
@@ -388,7 +632,7 @@ fn warn_if_high_frequency_agent_job(job: &CronJob) {
         return;
     }
     let too_frequent = match &job.schedule {
-        Schedule::Every { every_ms } => *every_ms < 5 * 60 * 1000,
+        Schedule::Every { every_ms, .. } => *every_ms < 5 * 60 * 1000,
         Schedule::Cron { .. } => {
             let now = Utc::now();
             match (
@@ -654,7 +898,7 @@ async fn run_job_command(
         config,
         security,
         job,
-        Duration::from_secs(SHELL_JOB_TIMEOUT_SECS),
+        Duration::from_secs(job.timeout_secs.unwrap_or(SHELL_JOB_TIMEOUT_SECS)),
     )
     .await
 }
@@ -664,6 +908,19 @@ async fn run_job_command_with_timeout(
     security: &SecurityPolicy,
     job: &CronJob,
     timeout: Duration,
+) -> (bool, String) {
+    run_shell_command(config, security, &job.command, timeout).await
+}
+
+/// Run a shell command under the security policy, as scheduled shell jobs do.
+///
+/// Also used by other headless triggers (routines) so every unattended shell
+/// command goes through the same validation, budget and timeout handling.
+pub(crate) async fn run_shell_command(
+    config: &Config,
+    security: &SecurityPolicy,
+    command: &str,
+    timeout: Duration,
 ) -> (bool, String) {
     if !security.can_act() {
         return (
@@ -685,12 +942,12 @@ async fn run_job_command_with_timeout(
     // manually-edited job stores.
     let approved = false; // scheduler runs are never pre-approved
     if let Err(error) =
-        crate::cron::validate_shell_command_with_security(security, &job.command, approved)
+        crate::cron::validate_shell_command_with_security(security, command, approved)
     {
         return (false, error.to_string());
     }
 
-    if let Some(path) = security.forbidden_path_argument(&job.command) {
+    if let Some(path) = security.forbidden_path_argument(command) {
         return (
             false,
             format!("blocked by security policy: forbidden path argument: {path}"),
@@ -704,7 +961,7 @@ async fn run_job_command_with_timeout(
         );
     }
 
-    let child = match build_cron_shell_command(&job.command, &config.workspace_dir) {
+    let child = match build_cron_shell_command(command, &config.workspace_dir) {
         Ok(mut cmd) => match cmd.spawn() {
             Ok(child) => child,
             Err(e) => return (false, format!("spawn error: {e}")),
@@ -727,7 +984,7 @@ async fn run_job_command_with_timeout(
         Ok(Err(e)) => (false, format!("spawn error: {e}")),
         Err(_) => (
             false,
-            format!("job timed out after {}s", timeout.as_secs_f64()),
+            format!("{TIMED_OUT_PREFIX} {}s", timeout.as_secs_f64()),
         ),
     }
 }
@@ -801,6 +1058,11 @@ mod tests {
             delete_after_run: false,
             allowed_tools: None,
             source: "imperative".into(),
+            depends_on: Vec::new(),
+            on_success: Vec::new(),
+            on_failure: Vec::new(),
+            concurrency_policy: ConcurrencyPolicy::Allow,
+            timeout_secs: None,
             created_at: Utc::now(),
             next_run: Utc::now(),
             last_run: None,
@@ -1099,7 +1361,16 @@ mod tests {
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Ok,
+            "ok",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(success);
 
         let runs = cron::list_runs(&config, &job.id, 10).unwrap();
@@ -1128,7 +1399,16 @@ mod tests {
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Ok,
+            "ok",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(success);
         let lookup = cron::get_job(&config, &job.id);
         assert!(lookup.is_err());
@@ -1154,7 +1434,16 @@ mod tests {
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, false, "boom", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Error,
+            "boom",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(!success);
         let updated = cron::get_job(&config, &job.id).unwrap();
         assert!(!updated.enabled);
@@ -1171,7 +1460,16 @@ mod tests {
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Ok,
+            "ok",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(success);
         let lookup = cron::get_job(&config, &job.id);
         assert!(lookup.is_err());
@@ -1187,7 +1485,16 @@ mod tests {
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, false, "boom", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Error,
+            "boom",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(!success);
         let updated = cron::get_job(&config, &job.id).unwrap();
         assert!(!updated.enabled);
@@ -1221,7 +1528,16 @@ mod tests {
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Ok,
+            "ok",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(!success);
 
         let updated = cron::get_job(&config, &job.id).unwrap();
@@ -1260,7 +1576,16 @@ mod tests {
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
 
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Ok,
+            "ok",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(success);
 
         let updated = cron::get_job(&config, &job.id).unwrap();
@@ -1293,7 +1618,16 @@ mod tests {
 
         let started = Utc::now();
         let finished = started + ChronoDuration::milliseconds(10);
-        let success = persist_job_result(&config, &job, true, "ok", started, finished).await;
+        let success = persist_job_result(
+            &config,
+            &job,
+            RunStatus::Ok,
+            "ok",
+            started,
+            finished,
+            TRIGGER_SCHEDULE,
+        )
+        .await;
         assert!(success);
 
         // After reschedule_after_run, At schedule jobs should be disabled
@@ -1447,4 +1781,149 @@ mod tests {
 
         assert_eq!(redacted.as_str(), clean_output);
     }
+
+    fn policy_job(command: &str, policy: ConcurrencyPolicy) -> CronJob {
+        let mut job = test_job(command);
+        job.id = uuid::Uuid::new_v4().to_string();
+        job.concurrency_policy = policy;
+        job
+    }
+
+    #[tokio::test]
+    async fn forbid_policy_skips_overlapping_run() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.autonomy.allowed_commands = vec!["sleep".into()];
+        let job = policy_job("sleep 1", ConcurrencyPolicy::Forbid);
+
+        let (first, second) = tokio::join!(execute_job_now(&config, &job), async {
+            time::sleep(Duration::from_millis(100)).await;
+            execute_job_now(&config, &job).await
+        });
+        assert_eq!(first.0, RunStatus::Ok, "{}", first.1);
+        assert_eq!(second.0, RunStatus::Skipped);
+        assert!(running_jobs().lock().get(&job.id).is_none());
+    }
+
+    #[tokio::test]
+    async fn replace_policy_aborts_running_run() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.autonomy.allowed_commands = vec!["sleep".into()];
+        let job = policy_job("sleep 1", ConcurrencyPolicy::Replace);
+
+        let (first, second) = tokio::join!(execute_job_now(&config, &job), async {
+            time::sleep(Duration::from_millis(100)).await;
+            execute_job_now(&config, &job).await
+        });
+        assert_eq!(first.0, RunStatus::Replaced);
+        assert_eq!(second.0, RunStatus::Ok, "{}", second.1);
+    }
+
+    #[tokio::test]
+    async fn timeout_secs_kills_run_without_retry() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp).await;
+        config.autonomy.allowed_commands = vec!["sleep".into()];
+        config.reliability.scheduler_retries = 3;
+        let mut job = policy_job("sleep 10", ConcurrencyPolicy::Allow);
+        job.timeout_secs = Some(1);
+
+        let started = std::time::Instant::now();
+        let (status, output) = execute_job_now(&config, &job).await;
+        assert_eq!(status, RunStatus::Timeout);
+        assert!(output.starts_with(TIMED_OUT_PREFIX));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn unmet_dependency_skips_run_and_advances_schedule() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        let upstream = cron::add_job(&config, "*/5 * * * *", "echo upstream").unwrap();
+        let job = cron::add_job(&config, "*/5 * * * *", "echo downstream").unwrap();
+        let job = cron::update_job(
+            &config,
+            &job.id,
+            CronJobPatch {
+                depends_on: Some(vec![upstream.id.clone()]),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+        let component = unique_component("scheduler-deps");
+
+        let (_, success, output) = Box::pin(execute_and_persist_job(
+            &config,
+            &security,
+            &job,
+            &component,
+            TRIGGER_SCHEDULE,
+            0,
+        ))
+        .await;
+        assert!(success);
+        assert!(output.contains("has not succeeded"), "{output}");
+
+        let runs = cron::list_runs(&config, &job.id, 10).unwrap();
+        assert_eq!(runs[0].status, "skipped");
+        assert_eq!(runs[0].trigger, "schedule");
+        let stored = cron::get_job(&config, &job.id).unwrap();
+        assert!(stored.last_status.is_none());
+
+        record_last_run(&config, &upstream.id, Utc::now(), true, "done").unwrap();
+        assert!(unmet_dependency(&config, &stored).is_none());
+    }
+
+    #[tokio::test]
+    async fn on_success_runs_chained_job_with_trigger() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp).await;
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        let next = cron::add_job(&config, "*/5 * * * *", "echo next").unwrap();
+        let fallback = cron::add_job(&config, "*/5 * * * *", "echo fallback").unwrap();
+        let first = cron::add_job(&config, "*/5 * * * *", "echo first").unwrap();
+        let first = cron::update_job(
+            &config,
+            &first.id,
+            CronJobPatch {
+                on_success: Some(vec![next.id.clone()]),
+                on_failure: Some(vec![fallback.id.clone()]),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+        let component = unique_component("scheduler-chain");
+
+        let (_, success, _) = Box::pin(execute_and_persist_job(
+            &config,
+            &security,
+            &first,
+            &component,
+            TRIGGER_SCHEDULE,
+            0,
+        ))
+        .await;
+        assert!(success);
+
+        let runs = cron::list_runs(&config, &next.id, 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, "ok");
+        assert_eq!(runs[0].trigger, format!("on_success:{}", first.id));
+        // A chained run leaves the target's own schedule alone.
+        assert_eq!(
+            cron::get_job(&config, &next.id).unwrap().next_run,
+            next.next_run
+        );
+        assert!(cron::list_runs(&config, &fallback.id, 10)
+            .unwrap()
+            .is_empty());
+    }
 }
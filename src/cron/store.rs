@@ -1,7 +1,8 @@
 use crate::config::Config;
 use crate::cron::{
     next_run_for_schedule, schedule_cron_expression, validate_delivery_config, validate_schedule,
-    CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule, SessionTarget,
+    ConcurrencyPolicy, CronJob, CronJobPatch, CronRun, DeliveryConfig, JobType, Schedule,
+    SessionTarget,
 };
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -124,7 +125,8 @@ pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
                     enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
-                    allowed_tools, source
+                    allowed_tools, source, depends_on, on_success, on_failure, concurrency_policy,
+                    timeout_secs
              FROM cron_jobs ORDER BY next_run ASC",
         )?;
 
@@ -143,7 +145,8 @@ pub fn get_job(config: &Config, job_id: &str) -> Result<CronJob> {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
                     enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
-                    allowed_tools, source
+                    allowed_tools, source, depends_on, on_success, on_failure, concurrency_policy,
+                    timeout_secs
              FROM cron_jobs WHERE id = ?1",
         )?;
 
@@ -177,7 +180,8 @@ pub fn due_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJob>> {
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
                     enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
-                    allowed_tools, source
+                    allowed_tools, source, depends_on, on_success, on_failure, concurrency_policy,
+                    timeout_secs
              FROM cron_jobs
              WHERE enabled = 1 AND next_run <= ?1
              ORDER BY next_run ASC
@@ -207,7 +211,8 @@ pub fn all_overdue_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJ
         let mut stmt = conn.prepare(
             "SELECT id, expression, command, schedule, job_type, prompt, name, session_target, model,
                     enabled, delivery, delete_after_run, created_at, next_run, last_run, last_status, last_output,
-                    allowed_tools, source
+                    allowed_tools, source, depends_on, on_success, on_failure, concurrency_policy,
+                    timeout_secs
              FROM cron_jobs
              WHERE enabled = 1 AND next_run <= ?1
              ORDER BY next_run ASC",
@@ -269,6 +274,23 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
             job.allowed_tools = Some(allowed_tools);
         }
     }
+    if let Some(depends_on) = patch.depends_on {
+        job.depends_on = depends_on;
+    }
+    if let Some(on_success) = patch.on_success {
+        job.on_success = on_success;
+    }
+    if let Some(on_failure) = patch.on_failure {
+        job.on_failure = on_failure;
+    }
+    if let Some(policy) = patch.concurrency_policy {
+        job.concurrency_policy = policy;
+    }
+    if let Some(timeout_secs) = patch.timeout_secs {
+        // 0 clears the timeout (shell jobs fall back to the default).
+        job.timeout_secs = (timeout_secs > 0).then_some(timeout_secs);
+    }
+    validate_job_links(config, &job)?;
 
     if schedule_changed {
         job.next_run = next_run_for_schedule(&job.schedule, Utc::now())?;
@@ -279,8 +301,9 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
             "UPDATE cron_jobs
              SET expression = ?1, command = ?2, schedule = ?3, job_type = ?4, prompt = ?5, name = ?6,
                  session_target = ?7, model = ?8, enabled = ?9, delivery = ?10, delete_after_run = ?11,
-                 allowed_tools = ?12, next_run = ?13
-             WHERE id = ?14",
+                 allowed_tools = ?12, next_run = ?13, depends_on = ?14, on_success = ?15,
+                 on_failure = ?16, concurrency_policy = ?17, timeout_secs = ?18
+             WHERE id = ?19",
             params![
                 job.expression,
                 job.command,
@@ -295,6 +318,11 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
                 if job.delete_after_run { 1 } else { 0 },
                 encode_allowed_tools(job.allowed_tools.as_ref())?,
                 job.next_run.to_rfc3339(),
+                encode_job_ids(&job.depends_on)?,
+                encode_job_ids(&job.on_success)?,
+                encode_job_ids(&job.on_failure)?,
+                job.concurrency_policy.as_str(),
+                job.timeout_secs.map(i64::try_from).transpose()?,
                 job.id,
             ],
         )
@@ -305,6 +333,53 @@ pub fn update_job(config: &Config, job_id: &str, patch: CronJobPatch) -> Result<
     get_job(config, job_id)
 }
 
+/// Check that a job's `depends_on` / `on_success` / `on_failure` entries name
+/// other existing jobs and that `depends_on` does not form a cycle.
+fn validate_job_links(config: &Config, job: &CronJob) -> Result<()> {
+    let linked = job
+        .depends_on
+        .iter()
+        .chain(&job.on_success)
+        .chain(&job.on_failure);
+    if linked.clone().next().is_none() {
+        return Ok(());
+    }
+
+    let jobs = list_jobs(config)?;
+    let deps_of = |id: &str| -> Vec<String> {
+        if id == job.id {
+            job.depends_on.clone()
+        } else {
+            jobs.iter()
+                .find(|j| j.id == id)
+                .map(|j| j.depends_on.clone())
+                .unwrap_or_default()
+        }
+    };
+
+    for id in linked {
+        if *id == job.id {
+            anyhow::bail!("Cron job '{}' cannot reference itself", job.id);
+        }
+        if !jobs.iter().any(|j| j.id == *id) {
+            anyhow::bail!("Cron job '{id}' referenced by '{}' not found", job.id);
+        }
+    }
+
+    // Walk the dependency graph from this job; reaching it again is a cycle.
+    let mut stack = job.depends_on.clone();
+    let mut seen = std::collections::HashSet::new();
+    while let Some(id) = stack.pop() {
+        if id == job.id {
+            anyhow::bail!("Cron job '{}' has a circular depends_on chain", job.id);
+        }
+        if seen.insert(id.clone()) {
+            stack.extend(deps_of(&id));
+        }
+    }
+    Ok(())
+}
+
 pub fn record_last_run(
     config: &Config,
     job_id: &str,
@@ -370,12 +445,39 @@ pub fn reschedule_after_run(
     }
 }
 
+/// Move a job past a scheduled run that was skipped, leaving its last run
+/// state untouched. One-shot `At` jobs are disabled instead.
+pub fn skip_scheduled_run(config: &Config, job: &CronJob) -> Result<()> {
+    if matches!(job.schedule, Schedule::At { .. }) {
+        return with_connection(config, |conn| {
+            conn.execute(
+                "UPDATE cron_jobs SET enabled = 0 WHERE id = ?1",
+                params![job.id],
+            )
+            .context("Failed to disable skipped one-shot cron job")?;
+            Ok(())
+        });
+    }
+
+    let next_run = next_run_for_schedule(&job.schedule, Utc::now())?;
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs SET next_run = ?1 WHERE id = ?2",
+            params![next_run.to_rfc3339(), job.id],
+        )
+        .context("Failed to advance skipped cron job")?;
+        Ok(())
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn record_run(
     config: &Config,
     job_id: &str,
     started_at: DateTime<Utc>,
     finished_at: DateTime<Utc>,
     status: &str,
+    trigger: &str,
     output: Option<&str>,
     duration_ms: i64,
 ) -> Result<()> {
@@ -387,8 +489,8 @@ pub fn record_run(
         let tx = conn.unchecked_transaction()?;
 
         tx.execute(
-            "INSERT INTO cron_runs (job_id, started_at, finished_at, status, output, duration_ms)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO cron_runs (job_id, started_at, finished_at, status, output, duration_ms, triggered_by)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 job_id,
                 started_at.to_rfc3339(),
@@ -396,6 +498,7 @@ pub fn record_run(
                 status,
                 bounded_output.as_deref(),
                 duration_ms,
+                trigger,
             ],
         )
         .context("Failed to insert cron run")?;
@@ -443,7 +546,7 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
     with_connection(config, |conn| {
         let lim = i64::try_from(limit.max(1)).context("Run history limit overflow")?;
         let mut stmt = conn.prepare(
-            "SELECT id, job_id, started_at, finished_at, status, output, duration_ms
+            "SELECT id, job_id, started_at, finished_at, status, output, duration_ms, triggered_by
              FROM cron_runs
              WHERE job_id = ?1
              ORDER BY started_at DESC, id DESC
@@ -461,6 +564,7 @@ pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<Cron
                 status: row.get(4)?,
                 output: row.get(5)?,
                 duration_ms: row.get(6)?,
+                trigger: row.get(7)?,
             })
         })?;
 
@@ -496,6 +600,10 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
     let created_at_raw: String = row.get(12)?;
     let allowed_tools_raw: Option<String> = row.get(17)?;
     let source: Option<String> = row.get(18)?;
+    let depends_on_raw: Option<String> = row.get(19)?;
+    let on_success_raw: Option<String> = row.get(20)?;
+    let on_failure_raw: Option<String> = row.get(21)?;
+    let timeout_secs: Option<i64> = row.get(23)?;
 
     Ok(CronJob {
         id: row.get(0)?,
@@ -511,6 +619,11 @@ fn map_cron_job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<CronJob> {
         delivery,
         delete_after_run: row.get::<_, i64>(11)? != 0,
         source: source.unwrap_or_else(|| "imperative".to_string()),
+        depends_on: decode_job_ids(depends_on_raw.as_deref()).map_err(sql_conversion_error)?,
+        on_success: decode_job_ids(on_success_raw.as_deref()).map_err(sql_conversion_error)?,
+        on_failure: decode_job_ids(on_failure_raw.as_deref()).map_err(sql_conversion_error)?,
+        concurrency_policy: ConcurrencyPolicy::parse(&row.get::<_, String>(22)?),
+        timeout_secs: timeout_secs.and_then(|secs| u64::try_from(secs).ok()),
         created_at: parse_rfc3339(&created_at_raw).map_err(sql_conversion_error)?,
         next_run: parse_rfc3339(&next_run_raw).map_err(sql_conversion_error)?,
         last_run: match last_run_raw {
@@ -573,6 +686,23 @@ fn decode_allowed_tools(raw: Option<&str>) -> Result<Option<Vec<String>>> {
     Ok(None)
 }
 
+fn encode_job_ids(ids: &[String]) -> Result<Option<String>> {
+    if ids.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(ids)
+        .map(Some)
+        .context("Failed to serialize cron job references")
+}
+
+fn decode_job_ids(raw: Option<&str>) -> Result<Vec<String>> {
+    match raw.map(str::trim) {
+        Some(trimmed) if !trimmed.is_empty() => serde_json::from_str(trimmed)
+            .with_context(|| format!("Failed to parse cron job references JSON: {trimmed}")),
+        _ => Ok(Vec::new()),
+    }
+}
+
 /// Synchronize declarative cron job definitions from config into the database.
 ///
 /// For each declarative job (identified by `id`):
@@ -822,6 +952,7 @@ fn convert_schedule_decl(decl: &crate::config::schema::CronScheduleDecl) -> Resu
         }),
         CronScheduleDecl::Every { every_ms } => Ok(Schedule::Every {
             every_ms: *every_ms,
+            jitter_ms: 0,
         }),
         CronScheduleDecl::At { at } => {
             let parsed = DateTime::parse_from_rfc3339(at)
@@ -844,8 +975,8 @@ fn convert_delivery_decl(decl: &crate::config::schema::DeliveryConfigDecl) -> De
     }
 }
 
-fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Result<()> {
-    let mut stmt = conn.prepare("PRAGMA table_info(cron_jobs)")?;
+fn add_column_if_missing(conn: &Connection, table: &str, name: &str, sql_type: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
     let mut rows = stmt.query([])?;
     while let Some(row) = rows.next()? {
         let col_name: String = row.get(1)?;
@@ -860,17 +991,17 @@ fn add_column_if_missing(conn: &Connection, name: &str, sql_type: &str) -> Resul
     // Tolerate "duplicate column name" errors to handle the race where
     // another process adds the column between our PRAGMA check and ALTER.
     match conn.execute(
-        &format!("ALTER TABLE cron_jobs ADD COLUMN {name} {sql_type}"),
+        &format!("ALTER TABLE {table} ADD COLUMN {name} {sql_type}"),
         [],
     ) {
         Ok(_) => Ok(()),
         Err(rusqlite::Error::SqliteFailure(err, Some(ref msg)))
             if msg.contains("duplicate column name") =>
         {
-            tracing::debug!("Column cron_jobs.{name} already exists (concurrent migration): {err}");
+            tracing::debug!("Column {table}.{name} already exists (concurrent migration): {err}");
             Ok(())
         }
-        Err(e) => Err(e).with_context(|| format!("Failed to add cron_jobs.{name}")),
+        Err(e) => Err(e).with_context(|| format!("Failed to add {table}.{name}")),
     }
 }
 
@@ -924,17 +1055,48 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
     )
     .context("Failed to initialize cron schema")?;
 
-    add_column_if_missing(&conn, "schedule", "TEXT")?;
-    add_column_if_missing(&conn, "job_type", "TEXT NOT NULL DEFAULT 'shell'")?;
-    add_column_if_missing(&conn, "prompt", "TEXT")?;
-    add_column_if_missing(&conn, "name", "TEXT")?;
-    add_column_if_missing(&conn, "session_target", "TEXT NOT NULL DEFAULT 'isolated'")?;
-    add_column_if_missing(&conn, "model", "TEXT")?;
-    add_column_if_missing(&conn, "enabled", "INTEGER NOT NULL DEFAULT 1")?;
-    add_column_if_missing(&conn, "delivery", "TEXT")?;
-    add_column_if_missing(&conn, "delete_after_run", "INTEGER NOT NULL DEFAULT 0")?;
-    add_column_if_missing(&conn, "allowed_tools", "TEXT")?;
-    add_column_if_missing(&conn, "source", "TEXT DEFAULT 'imperative'")?;
+    add_column_if_missing(&conn, "cron_jobs", "schedule", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "job_type",
+        "TEXT NOT NULL DEFAULT 'shell'",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "prompt", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "name", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "session_target",
+        "TEXT NOT NULL DEFAULT 'isolated'",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "model", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "enabled", "INTEGER NOT NULL DEFAULT 1")?;
+    add_column_if_missing(&conn, "cron_jobs", "delivery", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "delete_after_run",
+        "INTEGER NOT NULL DEFAULT 0",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "allowed_tools", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "source", "TEXT DEFAULT 'imperative'")?;
+    add_column_if_missing(&conn, "cron_jobs", "depends_on", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "on_success", "TEXT")?;
+    add_column_if_missing(&conn, "cron_jobs", "on_failure", "TEXT")?;
+    add_column_if_missing(
+        &conn,
+        "cron_jobs",
+        "concurrency_policy",
+        "TEXT NOT NULL DEFAULT 'allow'",
+    )?;
+    add_column_if_missing(&conn, "cron_jobs", "timeout_secs", "INTEGER")?;
+    add_column_if_missing(
+        &conn,
+        "cron_runs",
+        "triggered_by",
+        "TEXT NOT NULL DEFAULT 'schedule'",
+    )?;
 
     f(&conn)
 }
@@ -987,7 +1149,10 @@ mod tests {
         let recurring = add_shell_job(
             &config,
             None,
-            Schedule::Every { every_ms: 60_000 },
+            Schedule::Every {
+                every_ms: 60_000,
+                jitter_ms: 0,
+            },
             "echo recurring",
             None,
         )
@@ -1185,7 +1350,10 @@ mod tests {
         let job = add_agent_job(
             &config,
             Some("agent".into()),
-            Schedule::Every { every_ms: 60_000 },
+            Schedule::Every {
+                every_ms: 60_000,
+                jitter_ms: 0,
+            },
             "do work",
             SessionTarget::Isolated,
             None,
@@ -1212,7 +1380,10 @@ mod tests {
         let job = add_agent_job(
             &config,
             Some("agent".into()),
-            Schedule::Every { every_ms: 60_000 },
+            Schedule::Every {
+                every_ms: 60_000,
+                jitter_ms: 0,
+            },
             "do work",
             SessionTarget::Isolated,
             None,
@@ -1349,7 +1520,17 @@ mod tests {
         for idx in 0..3 {
             let start = base + ChronoDuration::seconds(idx);
             let end = start + ChronoDuration::milliseconds(100);
-            record_run(&config, &job.id, start, end, "ok", Some("done"), 100).unwrap();
+            record_run(
+                &config,
+                &job.id,
+                start,
+                end,
+                "ok",
+                "schedule",
+                Some("done"),
+                100,
+            )
+            .unwrap();
         }
 
         let runs = list_runs(&config, &job.id, 10).unwrap();
@@ -1368,6 +1549,7 @@ mod tests {
             start,
             start + ChronoDuration::milliseconds(5),
             "ok",
+            "schedule",
             Some("ok"),
             5,
         )
@@ -1378,6 +1560,86 @@ mod tests {
         assert!(runs.is_empty());
     }
 
+    #[test]
+    fn update_job_persists_orchestration_fields_and_validates_links() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let a = add_job(&config, "*/5 * * * *", "echo a").unwrap();
+        let b = add_job(&config, "*/5 * * * *", "echo b").unwrap();
+
+        let b = update_job(
+            &config,
+            &b.id,
+            CronJobPatch {
+                depends_on: Some(vec![a.id.clone()]),
+                on_failure: Some(vec![a.id.clone()]),
+                concurrency_policy: Some(ConcurrencyPolicy::Replace),
+                timeout_secs: Some(45),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+        let stored = get_job(&config, &b.id).unwrap();
+        assert_eq!(stored.depends_on, vec![a.id.clone()]);
+        assert_eq!(stored.on_failure, vec![a.id.clone()]);
+        assert!(stored.on_success.is_empty());
+        assert_eq!(stored.concurrency_policy, ConcurrencyPolicy::Replace);
+        assert_eq!(stored.timeout_secs, Some(45));
+
+        let self_ref = CronJobPatch {
+            on_success: Some(vec![a.id.clone()]),
+            ..CronJobPatch::default()
+        };
+        assert!(update_job(&config, &a.id, self_ref).is_err());
+        let unknown = CronJobPatch {
+            on_success: Some(vec!["missing".into()]),
+            ..CronJobPatch::default()
+        };
+        assert!(update_job(&config, &a.id, unknown).is_err());
+        let cycle = CronJobPatch {
+            depends_on: Some(vec![b.id.clone()]),
+            ..CronJobPatch::default()
+        };
+        let err = update_job(&config, &a.id, cycle).unwrap_err();
+        assert!(err.to_string().contains("circular"), "{err}");
+
+        let cleared = update_job(
+            &config,
+            &b.id,
+            CronJobPatch {
+                timeout_secs: Some(0),
+                depends_on: Some(Vec::new()),
+                ..CronJobPatch::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(cleared.timeout_secs, None);
+        assert!(cleared.depends_on.is_empty());
+    }
+
+    #[test]
+    fn record_run_stores_trigger() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "echo ok").unwrap();
+        let now = Utc::now();
+        record_run(
+            &config,
+            &job.id,
+            now,
+            now,
+            "skipped",
+            "on_success:upstream",
+            None,
+            0,
+        )
+        .unwrap();
+
+        let runs = list_runs(&config, &job.id, 10).unwrap();
+        assert_eq!(runs[0].status, "skipped");
+        assert_eq!(runs[0].trigger, "on_success:upstream");
+    }
+
     #[test]
     fn record_run_truncates_large_output() {
         let tmp = TempDir::new().unwrap();
@@ -1391,6 +1653,7 @@ mod tests {
             Utc::now(),
             Utc::now(),
             "ok",
+            "schedule",
             Some(&output),
             1,
         )
@@ -1648,7 +1911,13 @@ mod tests {
         sync_declarative_jobs(&config, &[decl]).unwrap();
 
         let job = get_job(&config, "interval-job").unwrap();
-        assert!(matches!(job.schedule, Schedule::Every { every_ms: 60000 }));
+        assert!(matches!(
+            job.schedule,
+            Schedule::Every {
+                every_ms: 60000,
+                ..
+            }
+        ));
         assert_eq!(job.command, "echo interval");
     }
 
@@ -84,6 +84,68 @@ impl SessionTarget {
     }
 }
 
+/// What happens when a job is triggered while a previous run of it is still
+/// in progress.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConcurrencyPolicy {
+    /// Start the new run alongside the existing one.
+    #[default]
+    Allow,
+    /// Skip the new run and record it as `skipped`.
+    Forbid,
+    /// Abort the running one (recorded as `replaced`) and start the new run.
+    Replace,
+}
+
+impl ConcurrencyPolicy {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Forbid => "forbid",
+            Self::Replace => "replace",
+        }
+    }
+
+    pub(crate) fn parse(raw: &str) -> Self {
+        match raw.to_ascii_lowercase().as_str() {
+            "forbid" => Self::Forbid,
+            "replace" => Self::Replace,
+            _ => Self::Allow,
+        }
+    }
+}
+
+/// Final status of a single run, as stored in `CronRun::status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    Ok,
+    Error,
+    /// The run exceeded the job's `timeout_secs` and was killed.
+    Timeout,
+    /// The run never started: a dependency has not succeeded or the
+    /// concurrency policy forbade it.
+    Skipped,
+    /// The run was aborted in favour of a newer one (`replace` policy).
+    Replaced,
+}
+
+impl RunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Ok => "ok",
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+            Self::Skipped => "skipped",
+            Self::Replaced => "replaced",
+        }
+    }
+
+    pub fn is_success(self) -> bool {
+        self == Self::Ok
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "kind", rename_all = "lowercase")]
 pub enum Schedule {
@@ -97,6 +159,10 @@ pub enum Schedule {
     },
     Every {
         every_ms: u64,
+        /// Random delay of up to this many milliseconds added to each run,
+        /// so jobs sharing an interval don't all fire at once.
+        #[serde(default, skip_serializing_if = "is_zero")]
+        jitter_ms: u64,
     },
 }
 
@@ -127,6 +193,11 @@ fn default_true() -> bool {
     true
 }
 
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
 fn default_source() -> String {
     "imperative".to_string()
 }
@@ -153,6 +224,22 @@ pub struct CronJob {
     /// How the job was created: `"imperative"` (CLI/API) or `"declarative"` (config).
     #[serde(default = "default_source")]
     pub source: String,
+    /// Job IDs whose last run must have succeeded before this job runs on
+    /// schedule. Otherwise the run is recorded as `skipped`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Job IDs to run as soon as this job succeeds.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_success: Vec<String>,
+    /// Job IDs to run as soon as this job fails or times out.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<String>,
+    #[serde(default)]
+    pub concurrency_policy: ConcurrencyPolicy,
+    /// Kill a run that takes longer than this. Shell jobs default to
+    /// 120 seconds; agent jobs are unbounded unless set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
     pub created_at: DateTime<Utc>,
     pub next_run: DateTime<Utc>,
     pub last_run: Option<DateTime<Utc>>,
@@ -169,6 +256,9 @@ pub struct CronRun {
     pub status: String,
     pub output: Option<String>,
     pub duration_ms: Option<i64>,
+    /// What started the run: `schedule`, `manual`, `routine`, or
+    /// `on_success:<job_id>` / `on_failure:<job_id>` for chained runs.
+    pub trigger: String,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -183,6 +273,12 @@ pub struct CronJobPatch {
     pub session_target: Option<SessionTarget>,
     pub delete_after_run: Option<bool>,
     pub allowed_tools: Option<Vec<String>>,
+    pub depends_on: Option<Vec<String>>,
+    pub on_success: Option<Vec<String>>,
+    pub on_failure: Option<Vec<String>>,
+    pub concurrency_policy: Option<ConcurrencyPolicy>,
+    /// `Some(0)` clears the timeout.
+    pub timeout_secs: Option<u64>,
 }
 
 #[cfg(test)]
@@ -219,7 +315,44 @@ mod tests {
     fn deserialize_every_from_string() {
         let val = serde_json::Value::String(r#"{"kind":"every","every_ms":60000}"#.to_string());
         let sched = deserialize_maybe_stringified::<Schedule>(&val).unwrap();
-        assert!(matches!(sched, Schedule::Every { every_ms: 60000 }));
+        assert!(matches!(
+            sched,
+            Schedule::Every {
+                every_ms: 60000,
+                jitter_ms: 0
+            }
+        ));
+    }
+
+    #[test]
+    fn every_jitter_round_trips_and_is_omitted_when_zero() {
+        let val = serde_json::json!({"kind": "every", "every_ms": 60000, "jitter_ms": 5000});
+        let sched = deserialize_maybe_stringified::<Schedule>(&val).unwrap();
+        assert_eq!(serde_json::to_value(&sched).unwrap(), val);
+
+        let plain = Schedule::Every {
+            every_ms: 60000,
+            jitter_ms: 0,
+        };
+        assert!(serde_json::to_value(&plain)
+            .unwrap()
+            .get("jitter_ms")
+            .is_none());
+    }
+
+    #[test]
+    fn concurrency_policy_parses_and_defaults_to_allow() {
+        assert_eq!(
+            ConcurrencyPolicy::parse("FORBID"),
+            ConcurrencyPolicy::Forbid
+        );
+        assert_eq!(
+            ConcurrencyPolicy::parse("replace"),
+            ConcurrencyPolicy::Replace
+        );
+        assert_eq!(ConcurrencyPolicy::parse("bogus"), ConcurrencyPolicy::Allow);
+        let parsed: ConcurrencyPolicy = serde_json::from_str("\"forbid\"").unwrap();
+        assert_eq!(parsed.as_str(), "forbid");
     }
 
     #[test]
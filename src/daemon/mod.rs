@@ -53,6 +53,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         .max(initial_backoff);
 
     crate::health::mark_component_ok("daemon");
+    crate::trust::runtime::init(&config);
 
     if config.heartbeat.enabled {
         let _ =
@@ -108,6 +109,19 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         ));
     }
 
+    {
+        let routines_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "routines",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = routines_cfg.clone();
+                async move { Box::pin(crate::routines::worker::run(cfg)).await }
+            },
+        ));
+    }
+
     if config.cron.enabled {
         let scheduler_cfg = config.clone();
         handles.push(spawn_component_supervisor(
@@ -124,9 +138,25 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         tracing::info!("Cron disabled; scheduler supervisor not started");
     }
 
+    if config.hands.enabled {
+        let hands_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "hands",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = hands_cfg.clone();
+                async move { Box::pin(crate::hands::scheduler::run(cfg)).await }
+            },
+        ));
+    } else {
+        crate::health::mark_component_ok("hands");
+        tracing::info!("Hands disabled; hands supervisor not started");
+    }
+
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
-    println!("   Components: gateway, channels, heartbeat, scheduler");
+    println!("   Components: gateway, channels, heartbeat, scheduler, hands, routines");
     if config.gateway.require_pairing {
         println!("   Pairing:    enabled (code appears in gateway output above)");
     }
@@ -80,6 +80,11 @@ pub struct CronAddBody {
     pub model: Option<String>,
     pub allowed_tools: Option<Vec<String>>,
     pub delete_after_run: Option<bool>,
+    pub depends_on: Option<Vec<String>>,
+    pub on_success: Option<Vec<String>>,
+    pub on_failure: Option<Vec<String>>,
+    pub concurrency_policy: Option<crate::cron::ConcurrencyPolicy>,
+    pub timeout_secs: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -88,6 +93,11 @@ pub struct CronPatchBody {
     pub schedule: Option<String>,
     pub command: Option<String>,
     pub prompt: Option<String>,
+    pub depends_on: Option<Vec<String>>,
+    pub on_success: Option<Vec<String>>,
+    pub on_failure: Option<Vec<String>>,
+    pub concurrency_policy: Option<crate::cron::ConcurrencyPolicy>,
+    pub timeout_secs: Option<u64>,
 }
 
 // ── Handlers ────────────────────────────────────────────────────
@@ -121,6 +131,11 @@ pub async fn handle_api_status(
         "paired": state.pairing.is_paired(),
         "channels": channels,
         "health": health,
+        "trust": {
+            "enabled": config.trust.enabled,
+            "regression_threshold": config.trust.regression_threshold,
+            "scores": crate::trust::runtime::current_scores(&config),
+        },
     });
 
     Json(body).into_response()
@@ -270,6 +285,11 @@ pub async fn handle_api_cron_add(
         model,
         allowed_tools,
         delete_after_run,
+        depends_on,
+        on_success,
+        on_failure,
+        concurrency_policy,
+        timeout_secs,
     } = body;
 
     let config = state.config.lock().clone();
@@ -277,7 +297,17 @@ pub async fn handle_api_cron_add(
         expr: schedule,
         tz: None,
     };
-    if let Err(e) = crate::cron::validate_delivery_config(delivery.as_ref()) {
+    let options = crate::cron::CronJobPatch {
+        depends_on,
+        on_success,
+        on_failure,
+        concurrency_policy,
+        timeout_secs,
+        ..crate::cron::CronJobPatch::default()
+    };
+    if let Err(e) = crate::cron::validate_delivery_config(delivery.as_ref())
+        .and_then(|()| crate::cron::validate_job_references(&config, &options))
+    {
         return (
             StatusCode::BAD_REQUEST,
             Json(serde_json::json!({"error": format!("Failed to add cron job: {e}")})),
@@ -334,6 +364,7 @@ pub async fn handle_api_cron_add(
 
         crate::cron::add_shell_job_with_approval(&config, name, schedule, command, delivery, false)
     };
+    let result = result.and_then(|job| crate::cron::apply_job_options(&config, job, options));
 
     match result {
         Ok(job) => Json(serde_json::json!({"status": "ok", "job": job})).into_response(),
@@ -381,6 +412,7 @@ pub async fn handle_api_cron_runs(
                         "status": r.status,
                         "output": r.output,
                         "duration_ms": r.duration_ms,
+                        "trigger": r.trigger,
                     })
                 })
                 .collect();
@@ -441,6 +473,11 @@ pub async fn handle_api_cron_patch(
         schedule,
         command: patch_command,
         prompt: patch_prompt,
+        depends_on: body.depends_on,
+        on_success: body.on_success,
+        on_failure: body.on_failure,
+        concurrency_policy: body.concurrency_policy,
+        timeout_secs: body.timeout_secs,
         ..crate::cron::CronJobPatch::default()
     };
 
@@ -475,6 +512,174 @@ pub async fn handle_api_cron_delete(
     }
 }
 
+/// GET /api/hands — list hand definitions with their schedule state
+pub async fn handle_api_hands_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = state.config.lock().clone();
+    let hands = match crate::hands::load_hands(&crate::hands::hands_dir(&config)) {
+        Ok(hands) => hands,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": format!("Failed to load hands: {e}")})),
+            )
+                .into_response();
+        }
+    };
+
+    let mut hands_json = Vec::with_capacity(hands.len());
+    for hand in hands {
+        let hand_state = match crate::hands::store::get_state(&config, &hand.name) {
+            Ok(hand_state) => hand_state,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({"error": format!("Failed to load hand state: {e}")})),
+                )
+                    .into_response();
+            }
+        };
+        hands_json.push(serde_json::json!({
+            "name": hand.name,
+            "description": hand.description,
+            "schedule": hand.schedule,
+            "active": hand.active,
+            "model": hand.model,
+            "allowed_tools": hand.allowed_tools,
+            "next_run": hand_state.as_ref().and_then(|s| s.next_run).map(|t| t.to_rfc3339()),
+            "last_run": hand_state.as_ref().and_then(|s| s.last_run).map(|t| t.to_rfc3339()),
+            "total_runs": hand_state.map_or(0, |s| s.total_runs),
+        }));
+    }
+
+    Json(serde_json::json!({"hands": hands_json})).into_response()
+}
+
+/// GET /api/hands/:name/runs — list recent runs of a hand
+pub async fn handle_api_hand_runs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Query(params): Query<CronRunsQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 100) as usize;
+    let config = state.config.lock().clone();
+
+    if let Err(e) = crate::hands::find_hand(&config, &name) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response();
+    }
+
+    match crate::hands::store::list_runs(&config, &name, limit) {
+        Ok(runs) => Json(serde_json::json!({"runs": runs})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to list hand runs: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// POST /api/hands/:name/run — run a hand immediately and return the run record
+pub async fn handle_api_hand_run(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let config = state.config.lock().clone();
+    let hand = match crate::hands::find_hand(&config, &name) {
+        Ok(hand) => hand,
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(serde_json::json!({"error": e.to_string()})),
+            )
+                .into_response();
+        }
+    };
+
+    let run = Box::pin(crate::hands::runner::run_hand(&config, &hand)).await;
+    Json(serde_json::json!({"run": run})).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct SopRunsQuery {
+    pub limit: Option<u32>,
+    pub sop: Option<String>,
+    #[serde(default)]
+    pub active: bool,
+}
+
+/// GET /api/sop/runs — list journaled SOP runs, newest first
+pub async fn handle_api_sop_runs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<SopRunsQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let limit = params.limit.unwrap_or(20).clamp(1, 100) as usize;
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    let filter = crate::sop::store::RunFilter {
+        sop_name: params.sop,
+        active_only: params.active,
+    };
+
+    match crate::sop::store::list_runs(&workspace_dir, &filter, limit) {
+        Ok(runs) => Json(serde_json::json!({"runs": runs})).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to list SOP runs: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
+/// GET /api/sop/runs/:id — fetch a single journaled SOP run
+pub async fn handle_api_sop_run_get(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(run_id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let workspace_dir = state.config.lock().workspace_dir.clone();
+    match crate::sop::store::get_run(&workspace_dir, &run_id) {
+        Ok(Some(run)) => Json(serde_json::json!({"run": run})).into_response(),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": format!("SOP run not found: {run_id}")})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to load SOP run: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
 /// GET /api/cron/settings — return cron subsystem settings
 pub async fn handle_api_cron_settings_get(
     State(state): State<AppState>,
@@ -1321,10 +1526,10 @@ pub async fn handle_api_session_messages(
     };
 
     let session_key = format!("gw_{id}");
-    let msgs = backend.load(&session_key);
-    let messages: Vec<serde_json::Value> = msgs
-        .into_iter()
-        .map(|m| serde_json::json!({ "role": m.role, "content": m.content }))
+    let messages: Vec<serde_json::Value> = backend
+        .load_messages(&session_key)
+        .iter()
+        .map(session_message_json)
         .collect();
 
     Json(serde_json::json!({
@@ -1335,6 +1540,18 @@ pub async fn handle_api_session_messages(
     .into_response()
 }
 
+/// Serialize a persisted message with the IDs clients need to fork or rewind.
+pub(super) fn session_message_json(
+    m: &crate::channels::session_backend::SessionMessage,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": m.id,
+        "parent_id": m.parent_id,
+        "role": m.message.role,
+        "content": m.message.content,
+    })
+}
+
 /// DELETE /api/sessions/{id} — delete a gateway session
 pub async fn handle_api_session_delete(
     State(state): State<AppState>,
@@ -1499,6 +1716,133 @@ pub async fn handle_api_session_state(
     }
 }
 
+/// GET /api/sessions/{id}/branches — list the branches of a session's conversation tree
+pub async fn handle_api_session_branches(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Some(ref backend) = state.session_backend else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Session persistence is disabled"})),
+        )
+            .into_response();
+    };
+
+    let session_key = format!("gw_{id}");
+    if !backend.list_sessions().contains(&session_key) {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Session not found"})),
+        )
+            .into_response();
+    }
+
+    let branches: Vec<serde_json::Value> = backend
+        .list_branches(&session_key)
+        .into_iter()
+        .map(|b| {
+            serde_json::json!({
+                "head_id": b.head_id,
+                "fork_id": b.fork_id,
+                "message_count": b.message_count,
+                "last_activity": b.last_activity.to_rfc3339(),
+                "active": b.active,
+            })
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "session_id": id,
+        "active_head": backend.active_head(&session_key),
+        "branches": branches,
+    }))
+    .into_response()
+}
+
+/// POST /api/sessions/{id}/branches — fork or rewind a session at a message
+///
+/// Body: `{"message_id": 42}` continues the session from message 42 (switching
+/// to its branch, or starting a new one on the next message); `{"message_id": null}`
+/// rewinds to before the first message. Existing branches are kept.
+pub async fn handle_api_session_checkout(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Json(body): Json<serde_json::Value>,
+) -> impl IntoResponse {
+    if let Err(e) = require_auth(&state, &headers) {
+        return e.into_response();
+    }
+
+    let Some(ref backend) = state.session_backend else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Session persistence is disabled"})),
+        )
+            .into_response();
+    };
+
+    let message_id = match body.get("message_id") {
+        Some(serde_json::Value::Null) => None,
+        Some(v) if v.is_i64() => v.as_i64(),
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({"error": "message_id (integer or null) is required"})),
+            )
+                .into_response();
+        }
+    };
+
+    let session_key = format!("gw_{id}");
+    if let Ok(Some(ss)) = backend.get_session_state(&session_key) {
+        if ss.state == "running" {
+            return (
+                StatusCode::CONFLICT,
+                Json(serde_json::json!({"error": "Session has a turn in progress"})),
+            )
+                .into_response();
+        }
+    }
+
+    match backend.checkout(&session_key, message_id) {
+        Ok(true) => {
+            let messages: Vec<serde_json::Value> = backend
+                .load_messages(&session_key)
+                .iter()
+                .map(session_message_json)
+                .collect();
+            Json(serde_json::json!({
+                "session_id": id,
+                "active_head": message_id,
+                "messages": messages,
+            }))
+            .into_response()
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Message not found in session"})),
+        )
+            .into_response(),
+        Err(e) if e.kind() == std::io::ErrorKind::Unsupported => (
+            StatusCode::NOT_IMPLEMENTED,
+            Json(serde_json::json!({"error": e.to_string()})),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({"error": format!("Failed to update session branch: {e}")})),
+        )
+            .into_response(),
+    }
+}
+
 // ── Claude Code hook endpoint ────────────────────────────────────
 
 /// POST /hooks/claude-code — receives HTTP hook events from Claude Code
@@ -1646,6 +1990,7 @@ mod tests {
             pending_pairings: None,
             path_prefix: String::new(),
             canvas_store: crate::tools::canvas::CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         }
@@ -1707,6 +2052,7 @@ mod tests {
             provider: "openrouter".to_string(),
             model: "anthropic/claude-sonnet-4.6".to_string(),
             api_key: Some("route-model-key".to_string()),
+            context_window: None,
         }];
         cfg.embedding_routes = vec![crate::config::schema::EmbeddingRouteConfig {
             hint: "semantic".to_string(),
@@ -1844,12 +2190,14 @@ mod tests {
                 provider: "openrouter".to_string(),
                 model: "anthropic/claude-sonnet-4.6".to_string(),
                 api_key: Some("route-model-key-1".to_string()),
+                context_window: None,
             },
             crate::config::schema::ModelRouteConfig {
                 hint: "fast".to_string(),
                 provider: "openrouter".to_string(),
                 model: "openai/gpt-4.1-mini".to_string(),
                 api_key: Some("route-model-key-2".to_string()),
+                context_window: None,
             },
         ];
         current.embedding_routes = vec![
@@ -1996,12 +2344,14 @@ mod tests {
                 provider: "openrouter".to_string(),
                 model: "anthropic/claude-sonnet-4.6".to_string(),
                 api_key: Some("route-model-key-1".to_string()),
+                context_window: None,
             },
             crate::config::schema::ModelRouteConfig {
                 hint: "fast".to_string(),
                 provider: "openrouter".to_string(),
                 model: "openai/gpt-4.1-mini".to_string(),
                 api_key: Some("route-model-key-2".to_string()),
+                context_window: None,
             },
         ];
         current.embedding_routes = vec![
@@ -2031,6 +2381,7 @@ mod tests {
                 provider: "openai".to_string(),
                 model: "gpt-4.1".to_string(),
                 api_key: Some(MASKED_SECRET.to_string()),
+                context_window: None,
             });
         incoming
             .embedding_routes
@@ -0,0 +1,46 @@
+//! Streamable-HTTP transport for ZeroClaw's MCP server.
+//!
+//! ```text
+//! POST /mcp   — one JSON-RPC message in, one JSON response out
+//! GET  /mcp   — 405; the server never initiates messages
+//! ```
+//!
+//! Returns 404 unless `[mcp_serve] gateway = true`. Authentication uses the
+//! same pairing bearer token as `/webhook`. Protocol handling lives in
+//! [`McpServer`](crate::tools::mcp_server::McpServer).
+
+use super::{authorize_bearer, AppState};
+use axum::{
+    body::Bytes,
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use std::net::SocketAddr;
+
+/// POST /mcp — handle one JSON-RPC message
+pub async fn handle_mcp_post(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    let Some(server) = state.mcp_server.clone() else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if let Err(rejection) = authorize_bearer(&state, peer_addr, &headers, "/mcp") {
+        return (rejection.status(), rejection.message()).into_response();
+    }
+
+    let raw = String::from_utf8_lossy(&body);
+    match server.handle_message(raw.trim()).await {
+        Some(response) => Json(response).into_response(),
+        // Notifications and client responses are acknowledged without a body.
+        None => StatusCode::ACCEPTED.into_response(),
+    }
+}
+
+/// GET /mcp — no server-initiated stream is offered
+pub async fn handle_mcp_get() -> Response {
+    (StatusCode::METHOD_NOT_ALLOWED, [(header::ALLOW, "POST")]).into_response()
+}
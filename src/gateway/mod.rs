@@ -15,7 +15,9 @@ pub mod api_plugins;
 pub mod api_webauthn;
 pub mod auth_rate_limit;
 pub mod canvas;
+pub mod mcp;
 pub mod nodes;
+pub mod openai_compat;
 pub mod session_queue;
 pub mod sse;
 pub mod static_files;
@@ -310,6 +312,78 @@ fn client_key_from_request(
         .unwrap_or_else(|| "unknown".to_string())
 }
 
+/// Why [`authorize_bearer`] rejected a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AuthRejection {
+    /// The client exceeded the webhook rate limit.
+    RateLimited,
+    /// The client is locked out after failed auth attempts.
+    AuthLockedOut { retry_after_secs: u64 },
+    /// The bearer token is missing or not paired.
+    Unauthorized,
+}
+
+impl AuthRejection {
+    fn status(self) -> StatusCode {
+        match self {
+            Self::RateLimited | Self::AuthLockedOut { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+        }
+    }
+
+    fn message(self) -> String {
+        match self {
+            Self::RateLimited => {
+                format!("Too many requests. Retry after {RATE_LIMIT_WINDOW_SECS}s.")
+            }
+            Self::AuthLockedOut { retry_after_secs } => {
+                format!("Too many auth attempts. Try again in {retry_after_secs}s.")
+            }
+            Self::Unauthorized => {
+                "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
+                    .to_string()
+            }
+        }
+    }
+}
+
+/// Apply webhook rate limiting and pairing-token auth for `route`.
+fn authorize_bearer(
+    state: &AppState,
+    peer_addr: SocketAddr,
+    headers: &HeaderMap,
+    route: &str,
+) -> Result<(), AuthRejection> {
+    let rate_key = client_key_from_request(Some(peer_addr), headers, state.trust_forwarded_headers);
+    if !state.rate_limiter.allow_webhook(&rate_key) {
+        tracing::warn!("{route} rate limit exceeded");
+        return Err(AuthRejection::RateLimited);
+    }
+
+    if !state.pairing.require_pairing() {
+        return Ok(());
+    }
+
+    if let Err(e) = state.auth_limiter.check_rate_limit(&rate_key) {
+        return Err(AuthRejection::AuthLockedOut {
+            retry_after_secs: e.retry_after_secs,
+        });
+    }
+
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if state.pairing.is_authenticated(token) {
+        Ok(())
+    } else {
+        state.auth_limiter.record_attempt(&rate_key);
+        tracing::warn!("{route}: rejected — not paired / invalid bearer token");
+        Err(AuthRejection::Unauthorized)
+    }
+}
+
 fn normalize_max_keys(configured: usize, fallback: usize) -> usize {
     if configured == 0 {
         fallback.max(1)
@@ -370,6 +444,8 @@ pub struct AppState {
     pub pending_pairings: Option<Arc<api_pairing::PairingStore>>,
     /// Shared canvas store for Live Canvas (A2UI) system
     pub canvas_store: CanvasStore,
+    /// MCP server for the streamable-HTTP `/mcp` endpoint (`[mcp_serve] gateway = true`)
+    pub mcp_server: Option<Arc<tools::McpServer>>,
     /// WebAuthn state for hardware key authentication (optional, requires `webauthn` feature)
     #[cfg(feature = "webauthn")]
     pub webauthn: Option<Arc<api_webauthn::WebAuthnState>>,
@@ -418,6 +494,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             extra_headers: config.extra_headers.clone(),
             api_path: config.api_path.clone(),
             provider_max_tokens: config.provider_max_tokens,
+            provider_record_path: config
+                .provider_record_path
+                .as_ref()
+                .map(std::path::PathBuf::from),
+            budget_routing: providers::router::BudgetRouting::from_config(&config),
         },
     )?);
     let model = config
@@ -434,10 +515,10 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     )?);
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
-    let security = Arc::new(SecurityPolicy::from_config(
-        &config.autonomy,
-        &config.workspace_dir,
-    ));
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_trust(crate::trust::runtime::init(&config)),
+    );
 
     let (composio_key, composio_entity_id) = if config.composio.enabled {
         (
@@ -448,7 +529,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         (None, None)
     };
 
-    let sop_engine = crate::sop::create_sop_engine(&config.sop, &config.workspace_dir);
+    let sop_engine = crate::sop::shared_sop_engine(&config.sop, &config.workspace_dir);
     let canvas_store = tools::CanvasStore::new();
 
     let (
@@ -483,7 +564,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             "Gateway: initializing MCP client — {} server(s) configured",
             config.mcp.servers.len()
         );
-        match tools::McpRegistry::connect_all(&config.mcp.servers).await {
+        match tools::McpRegistry::connect_from_config(&config).await {
             Ok(registry) => {
                 let registry = std::sync::Arc::new(registry);
                 if config.mcp.deferred_loading {
@@ -525,6 +606,13 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
                         registry.server_count()
                     );
                 }
+                let resource_servers = registry.resource_servers().await;
+                if !resource_servers.is_empty() {
+                    tools_registry_raw.push(Box::new(tools::McpResourcesTool::new(
+                        std::sync::Arc::clone(&registry),
+                        resource_servers,
+                    )));
+                }
             }
             Err(e) => {
                 tracing::error!("Gateway MCP registry failed to initialize: {e:#}");
@@ -535,6 +623,23 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     let tools_registry: Arc<Vec<ToolSpec>> =
         Arc::new(tools_registry_raw.iter().map(|t| t.spec()).collect());
 
+    // ── MCP server over streamable HTTP (opt-in) ───────────────────
+    let mcp_server = if config.mcp_serve.gateway {
+        let server = tools::McpServer::new(
+            tools_registry_raw,
+            &config.autonomy,
+            &config.mcp_serve,
+            Some(Arc::clone(&mem)),
+        );
+        tracing::info!(
+            "Gateway MCP server: {} tool(s) exposed at /mcp",
+            server.exposed_tool_names().len()
+        );
+        Some(Arc::new(server))
+    } else {
+        None
+    };
+
     // Cost tracker — process-global singleton so channels share the same instance
     let cost_tracker = CostTracker::get_or_init_global(config.cost.clone(), &config.workspace_dir);
 
@@ -778,6 +883,10 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         println!("  POST {pfx}/nextcloud-talk — Nextcloud Talk bot webhook");
     }
     println!("  GET  {pfx}/api/*     — REST API (bearer token required)");
+    println!("  POST {pfx}/v1/chat/completions — OpenAI-compatible agent chat");
+    if config.mcp_serve.gateway {
+        println!("  POST {pfx}/mcp       — MCP server (streamable HTTP)");
+    }
     println!("  GET  {pfx}/ws/chat   — WebSocket agent chat");
     if config.nodes.enabled {
         println!("  GET  {pfx}/ws/nodes  — WebSocket node discovery");
@@ -804,6 +913,9 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
 
     // Node registry for dynamic node discovery
     let node_registry = Arc::new(nodes::NodeRegistry::new(config.nodes.max_nodes));
+    if config.nodes.enabled {
+        nodes::set_global_registry(Arc::clone(&node_registry));
+    }
 
     // Device registry and pairing store (only when pairing is required)
     let device_registry = if config.gateway.require_pairing {
@@ -854,6 +966,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         pending_pairings,
         path_prefix: path_prefix.unwrap_or("").to_string(),
         canvas_store,
+        mcp_server,
         #[cfg(feature = "webauthn")]
         webauthn: if config.security.webauthn.enabled {
             let secret_store = Arc::new(crate::security::SecretStore::new(
@@ -885,6 +998,16 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/api/config", put(api::handle_api_config_put))
         .layer(RequestBodyLimitLayer::new(1_048_576));
 
+    // OpenAI-compatible chat completions carry the full conversation (1MB)
+    let openai_router = Router::new()
+        .route(
+            "/v1/chat/completions",
+            post(openai_compat::handle_v1_chat_completions),
+        )
+        .layer(RequestBodyLimitLayer::new(
+            openai_compat::MAX_CHAT_COMPLETIONS_BODY_SIZE,
+        ));
+
     // Build router with middleware
     let inner = Router::new()
         // ── Admin routes (for CLI management) ──
@@ -921,6 +1044,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
             delete(api::handle_api_cron_delete).patch(api::handle_api_cron_patch),
         )
         .route("/api/cron/{id}/runs", get(api::handle_api_cron_runs))
+        .route("/api/hands", get(api::handle_api_hands_list))
+        .route("/api/hands/{name}/runs", get(api::handle_api_hand_runs))
+        .route("/api/hands/{name}/run", post(api::handle_api_hand_run))
+        .route("/api/sop/runs", get(api::handle_api_sop_runs))
+        .route("/api/sop/runs/{id}", get(api::handle_api_sop_run_get))
         .route("/api/integrations", get(api::handle_api_integrations))
         .route(
             "/api/integrations/settings",
@@ -944,6 +1072,10 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         )
         .route("/api/sessions/{id}", delete(api::handle_api_session_delete).put(api::handle_api_session_rename))
         .route("/api/sessions/{id}/state", get(api::handle_api_session_state))
+        .route(
+            "/api/sessions/{id}/branches",
+            get(api::handle_api_session_branches).post(api::handle_api_session_checkout),
+        )
         // ── Pairing + Device management API ──
         .route("/api/pairing/initiate", post(api_pairing::initiate_pairing))
         .route("/api/pair", post(api_pairing::submit_pairing_enhanced))
@@ -1012,6 +1144,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/ws/nodes", get(nodes::handle_ws_nodes))
         // ── Static assets (web dashboard) ──
         .route("/_app/{*path}", get(static_files::handle_static))
+        // ── OpenAI-compatible API ──
+        .route("/v1/models", get(openai_compat::handle_v1_models))
+        .merge(openai_router)
+        // ── MCP server (streamable HTTP) ──
+        .route(
+            "/mcp",
+            post(mcp::handle_mcp_post).get(mcp::handle_mcp_get),
+        )
         // ── Config PUT with larger body limit ──
         .merge(config_put_router)
         // ── SPA fallback: non-API GET requests serve index.html ──
@@ -1416,6 +1556,12 @@ async fn handle_webhook(
     let message = &webhook_body.message;
     let session_id = webhook_session_id(&headers);
 
+    crate::routines::bus::publish(crate::routines::RoutineEvent::new(
+        crate::routines::bus::SOURCE_WEBHOOK,
+        "/webhook",
+        Some(message.clone()),
+    ));
+
     if state.auto_save && !memory::should_skip_autosave_content(message) {
         let key = webhook_memory_key();
         let _ = state
@@ -2342,6 +2488,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -2410,6 +2557,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -2804,6 +2952,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -2882,6 +3031,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -2972,6 +3122,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -3034,6 +3185,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -3101,6 +3253,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -3173,6 +3326,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -3242,6 +3396,7 @@ mod tests {
             device_registry: None,
             pending_pairings: None,
             canvas_store: CanvasStore::new(),
+            mcp_server: None,
             #[cfg(feature = "webauthn")]
             webauthn: None,
         };
@@ -9,11 +9,23 @@
 //! ```text
 //! Node -> Gateway: {"type":"register","node_id":"phone-1","capabilities":[{"name":"camera.snap","description":"Take a photo","parameters":{...}}]}
 //! Gateway -> Node: {"type":"registered","node_id":"phone-1","capabilities_count":1}
-//! Gateway -> Node: {"type":"invoke","call_id":"uuid","capability":"camera.snap","args":{...}}
+//! Gateway -> Node: {"type":"invoke","call_id":"uuid","capability":"camera.snap","args":{...},"traceparent":"00-..."}
+//! Node -> Gateway: {"type":"chunk","call_id":"uuid","output":"partial..."}
 //! Node -> Gateway: {"type":"result","call_id":"uuid","success":true,"output":"..."}
+//! Node -> Gateway: {"type":"heartbeat"}
+//! Gateway -> Node: {"type":"heartbeat_ack"}
 //! ```
+//!
+//! `chunk` messages stream a call's output ahead of its `result`; the final
+//! output is every chunk followed by the `result` output. An empty chunk is a
+//! keepalive for a long-running call. `invoke` carries the caller's W3C
+//! `traceparent` when the call was made inside a traced span. When `[node_transport]` has a
+//! `shared_secret`, `register` must also carry `timestamp`, `nonce` and an
+//! HMAC-SHA256 `signature` of the node ID (see
+//! [`crate::nodes::transport::sign_request`]).
 
 use super::AppState;
+use crate::nodes::transport::NonceCache;
 use axum::{
     extract::{
         ws::{Message, WebSocket},
@@ -26,14 +38,28 @@ use futures_util::{SinkExt, StreamExt};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use tokio::sync::{mpsc, oneshot};
 
 /// Prefix used in `Sec-WebSocket-Protocol` to carry a bearer token.
 const BEARER_SUBPROTO_PREFIX: &str = "bearer.";
 
 /// The sub-protocol we support for node connections.
-const WS_NODE_PROTOCOL: &str = "zeroclaw.nodes.v1";
+pub const WS_NODE_PROTOCOL: &str = "zeroclaw.nodes.v1";
+
+/// Registry of the gateway running in this process, for tools built outside
+/// the gateway (agent loops, channels) that need to reach connected nodes.
+static GLOBAL_REGISTRY: OnceLock<Arc<NodeRegistry>> = OnceLock::new();
+
+/// Publish the gateway's node registry process-wide. Only the first call wins.
+pub fn set_global_registry(registry: Arc<NodeRegistry>) {
+    let _ = GLOBAL_REGISTRY.set(registry);
+}
+
+/// The node registry of the gateway running in this process, if any.
+pub fn global_registry() -> Option<Arc<NodeRegistry>> {
+    GLOBAL_REGISTRY.get().cloned()
+}
 
 /// A single capability advertised by a node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,7 +92,11 @@ pub struct NodeInvocation {
     pub call_id: String,
     pub capability: String,
     pub args: serde_json::Value,
+    /// W3C trace context of the calling span, forwarded to the node.
+    pub traceparent: Option<String>,
     pub response_tx: oneshot::Sender<NodeInvocationResult>,
+    /// Notified whenever the node streams output for this call.
+    pub progress_tx: Option<mpsc::Sender<()>>,
 }
 
 /// The result of a node invocation.
@@ -82,6 +112,8 @@ pub struct NodeInvocationResult {
 pub struct NodeRegistry {
     nodes: Arc<RwLock<HashMap<String, NodeInfo>>>,
     max_nodes: usize,
+    /// Nonces of accepted signed registrations, for replay rejection.
+    seen_nonces: Arc<NonceCache>,
 }
 
 impl NodeRegistry {
@@ -90,6 +122,7 @@ impl NodeRegistry {
         Self {
             nodes: Arc::new(RwLock::new(HashMap::new())),
             max_nodes,
+            seen_nonces: Arc::new(NonceCache::default()),
         }
     }
 
@@ -108,6 +141,20 @@ impl NodeRegistry {
         self.nodes.write().remove(node_id);
     }
 
+    /// Remove a node only if it is still registered through `invoke_tx`.
+    ///
+    /// A node that reconnects re-registers before its old socket is torn
+    /// down; this keeps the stale connection from removing the new one.
+    pub fn unregister_connection(&self, node_id: &str, invoke_tx: &mpsc::Sender<NodeInvocation>) {
+        let mut nodes = self.nodes.write();
+        if nodes
+            .get(node_id)
+            .is_some_and(|info| info.invoke_tx.same_channel(invoke_tx))
+        {
+            nodes.remove(node_id);
+        }
+    }
+
     /// List all registered node IDs.
     pub fn node_ids(&self) -> Vec<String> {
         self.nodes.read().keys().cloned().collect()
@@ -153,6 +200,12 @@ enum NodeMessage {
     Register {
         node_id: String,
         capabilities: Vec<NodeCapability>,
+        #[serde(default)]
+        timestamp: Option<i64>,
+        #[serde(default)]
+        nonce: Option<String>,
+        #[serde(default)]
+        signature: Option<String>,
     },
     Result {
         call_id: String,
@@ -161,6 +214,12 @@ enum NodeMessage {
         #[serde(default)]
         error: Option<String>,
     },
+    Chunk {
+        call_id: String,
+        #[serde(default)]
+        output: String,
+    },
+    Heartbeat,
 }
 
 /// Messages sent to a node.
@@ -178,7 +237,46 @@ enum GatewayMessage {
         call_id: String,
         capability: String,
         args: serde_json::Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        traceparent: Option<String>,
     },
+    HeartbeatAck,
+}
+
+/// An invocation awaiting its result, with the output streamed so far.
+struct PendingCall {
+    response_tx: oneshot::Sender<NodeInvocationResult>,
+    progress_tx: Option<mpsc::Sender<()>>,
+    output: String,
+}
+
+/// Check a signed registration against the configured shared secret,
+/// rejecting a nonce already used within the replay window.
+fn verify_registration(
+    seen_nonces: &NonceCache,
+    shared_secret: &str,
+    max_age_secs: i64,
+    node_id: &str,
+    timestamp: Option<i64>,
+    nonce: Option<&str>,
+    signature: Option<&str>,
+) -> Result<(), String> {
+    let (Some(timestamp), Some(nonce), Some(signature)) = (timestamp, nonce, signature) else {
+        return Err("registration must be signed with the node shared secret".into());
+    };
+    match crate::nodes::transport::verify_request(
+        shared_secret,
+        node_id.as_bytes(),
+        timestamp,
+        nonce,
+        signature,
+        max_age_secs,
+    ) {
+        Ok(true) if seen_nonces.insert(nonce, timestamp, max_age_secs) => Ok(()),
+        Ok(true) => Err("registration nonce already used".into()),
+        Ok(false) => Err("invalid registration signature".into()),
+        Err(e) => Err(e.to_string()),
+    }
 }
 
 /// Query parameters for the `/ws/nodes` endpoint.
@@ -273,39 +371,61 @@ pub async fn handle_ws_nodes(
         ws
     };
 
+    let transport = state.config.lock().node_transport.clone();
+    let signing = crate::nodes::transport::registration_secret(&transport)
+        .map(|secret| (secret.to_string(), transport.max_request_age_secs));
+
     let registry = state.node_registry.clone();
-    ws.on_upgrade(move |socket| handle_node_socket(socket, registry))
+    ws.on_upgrade(move |socket| handle_node_socket(socket, registry, signing))
         .into_response()
 }
 
-async fn handle_node_socket(socket: WebSocket, registry: Arc<NodeRegistry>) {
+async fn handle_node_socket(
+    socket: WebSocket,
+    registry: Arc<NodeRegistry>,
+    signing: Option<(String, i64)>,
+) {
     let (mut sender, mut receiver) = socket.split();
     let mut registered_node_id: Option<String> = None;
 
     // Channel for forwarding invocations to this node
     let (invoke_tx, mut invoke_rx) = mpsc::channel::<NodeInvocation>(32);
 
+    // Channel for direct replies (acks, errors, heartbeats) to this node
+    let (reply_tx, mut reply_rx) = mpsc::channel::<GatewayMessage>(8);
+
     // Pending invocation responses keyed by call_id
-    let pending: Arc<RwLock<HashMap<String, oneshot::Sender<NodeInvocationResult>>>> =
-        Arc::new(RwLock::new(HashMap::new()));
+    let pending: Arc<RwLock<HashMap<String, PendingCall>>> = Arc::new(RwLock::new(HashMap::new()));
 
     let pending_clone = Arc::clone(&pending);
 
-    // Task to forward invocations to the node via WebSocket
+    // Task that owns the socket sink and serializes all outgoing messages
     let send_task = tokio::spawn(async move {
-        while let Some(invocation) = invoke_rx.recv().await {
-            let msg = GatewayMessage::Invoke {
-                call_id: invocation.call_id.clone(),
-                capability: invocation.capability,
-                args: invocation.args,
+        loop {
+            let msg = tokio::select! {
+                Some(invocation) = invoke_rx.recv() => {
+                    pending_clone.write().insert(
+                        invocation.call_id.clone(),
+                        PendingCall {
+                            response_tx: invocation.response_tx,
+                            progress_tx: invocation.progress_tx,
+                            output: String::new(),
+                        },
+                    );
+                    GatewayMessage::Invoke {
+                        call_id: invocation.call_id,
+                        capability: invocation.capability,
+                        args: invocation.args,
+                        traceparent: invocation.traceparent,
+                    }
+                }
+                Some(reply) = reply_rx.recv() => reply,
+                else => break,
             };
             if let Ok(json) = serde_json::to_string(&msg) {
                 if sender.send(Message::Text(json.into())).await.is_err() {
                     break;
                 }
-                pending_clone
-                    .write()
-                    .insert(invocation.call_id, invocation.response_tx);
             }
         }
     });
@@ -333,13 +453,39 @@ async fn handle_node_socket(socket: WebSocket, registry: Arc<NodeRegistry>) {
             NodeMessage::Register {
                 node_id,
                 capabilities,
+                timestamp,
+                nonce,
+                signature,
             } => {
                 // Validate node_id
                 if node_id.is_empty() || node_id.len() > 128 {
                     tracing::warn!("Node registration rejected: invalid node_id length");
+                    let _ = reply_tx
+                        .send(GatewayMessage::Error {
+                            message: "node_id must be 1-128 characters".into(),
+                        })
+                        .await;
                     continue;
                 }
 
+                if let Some((secret, max_age_secs)) = &signing {
+                    if let Err(reason) = verify_registration(
+                        &registry.seen_nonces,
+                        secret,
+                        *max_age_secs,
+                        &node_id,
+                        timestamp,
+                        nonce.as_deref(),
+                        signature.as_deref(),
+                    ) {
+                        tracing::warn!("Node registration rejected for {node_id}: {reason}");
+                        let _ = reply_tx
+                            .send(GatewayMessage::Error { message: reason })
+                            .await;
+                        continue;
+                    }
+                }
+
                 let caps_count = capabilities.len();
                 let info = NodeInfo {
                     node_id: node_id.clone(),
@@ -350,17 +496,21 @@ async fn handle_node_socket(socket: WebSocket, registry: Arc<NodeRegistry>) {
                 if registry.register(info) {
                     tracing::info!("Node registered: {node_id} with {caps_count} capabilities");
                     registered_node_id = Some(node_id.clone());
-
-                    // Send ack — we can't use `sender` here since it's moved
-                    // into the send task. Instead, send ack via the invoke channel
-                    // pattern isn't ideal. We'll use a workaround: send the ack
-                    // through a special invocation that the send task converts to
-                    // a registered message. For simplicity, we just log and the
-                    // ack is implicit in the protocol.
+                    let _ = reply_tx
+                        .send(GatewayMessage::Registered {
+                            node_id,
+                            capabilities_count: caps_count,
+                        })
+                        .await;
                 } else {
                     tracing::warn!(
                         "Node registration rejected: registry at capacity for {node_id}"
                     );
+                    let _ = reply_tx
+                        .send(GatewayMessage::Error {
+                            message: "node registry is at capacity".into(),
+                        })
+                        .await;
                 }
             }
             NodeMessage::Result {
@@ -369,20 +519,33 @@ async fn handle_node_socket(socket: WebSocket, registry: Arc<NodeRegistry>) {
                 output,
                 error,
             } => {
-                if let Some(tx) = pending.write().remove(&call_id) {
-                    let _ = tx.send(NodeInvocationResult {
+                if let Some(call) = pending.write().remove(&call_id) {
+                    let mut full_output = call.output;
+                    full_output.push_str(&output);
+                    let _ = call.response_tx.send(NodeInvocationResult {
                         success,
-                        output,
+                        output: full_output,
                         error,
                     });
                 }
             }
+            NodeMessage::Chunk { call_id, output } => {
+                if let Some(call) = pending.write().get_mut(&call_id) {
+                    call.output.push_str(&output);
+                    if let Some(progress_tx) = &call.progress_tx {
+                        let _ = progress_tx.try_send(());
+                    }
+                }
+            }
+            NodeMessage::Heartbeat => {
+                let _ = reply_tx.send(GatewayMessage::HeartbeatAck).await;
+            }
         }
     }
 
     // Cleanup: unregister node on disconnect
     if let Some(node_id) = registered_node_id {
-        registry.unregister(&node_id);
+        registry.unregister_connection(&node_id, &invoke_tx);
         tracing::info!("Node disconnected and unregistered: {node_id}");
     }
 
@@ -545,12 +708,15 @@ mod tests {
             NodeMessage::Register {
                 node_id,
                 capabilities,
+                signature,
+                ..
             } => {
                 assert_eq!(node_id, "phone-1");
+                assert!(signature.is_none());
                 assert_eq!(capabilities.len(), 1);
                 assert_eq!(capabilities[0].name, "camera.snap");
             }
-            NodeMessage::Result { .. } => panic!("Expected Register message"),
+            _ => panic!("Expected Register message"),
         }
     }
 
@@ -570,10 +736,75 @@ mod tests {
                 assert_eq!(output, "photo taken");
                 assert!(error.is_none());
             }
-            NodeMessage::Register { .. } => panic!("Expected Result message"),
+            _ => panic!("Expected Result message"),
         }
     }
 
+    #[test]
+    fn node_message_chunk_and_heartbeat_deserialize() {
+        let json = r#"{"type":"chunk","call_id":"abc-123","output":"partial"}"#;
+        match serde_json::from_str::<NodeMessage>(json).unwrap() {
+            NodeMessage::Chunk { call_id, output } => {
+                assert_eq!(call_id, "abc-123");
+                assert_eq!(output, "partial");
+            }
+            _ => panic!("Expected Chunk message"),
+        }
+        let json = r#"{"type":"heartbeat"}"#;
+        assert!(matches!(
+            serde_json::from_str::<NodeMessage>(json).unwrap(),
+            NodeMessage::Heartbeat
+        ));
+    }
+
+    #[test]
+    fn verify_registration_requires_valid_signature() {
+        let seen = NonceCache::default();
+        let ts = chrono::Utc::now().timestamp();
+        let sig = crate::nodes::transport::sign_request("secret", b"phone-1", ts, "n1").unwrap();
+        assert!(verify_registration(
+            &seen,
+            "secret",
+            300,
+            "phone-2",
+            Some(ts),
+            Some("n1"),
+            Some(&sig)
+        )
+        .is_err());
+        assert!(verify_registration(&seen, "secret", 300, "phone-1", None, None, None).is_err());
+        assert!(verify_registration(
+            &seen,
+            "secret",
+            300,
+            "phone-1",
+            Some(ts),
+            Some("n1"),
+            Some(&sig)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn verify_registration_rejects_replayed_nonce() {
+        let seen = NonceCache::default();
+        let ts = chrono::Utc::now().timestamp();
+        let sig = crate::nodes::transport::sign_request("secret", b"phone-1", ts, "n1").unwrap();
+        let register = || {
+            verify_registration(
+                &seen,
+                "secret",
+                300,
+                "phone-1",
+                Some(ts),
+                Some("n1"),
+                Some(&sig),
+            )
+        };
+        assert!(register().is_ok());
+        assert_eq!(register().unwrap_err(), "registration nonce already used");
+    }
+
     #[test]
     fn gateway_message_serialize() {
         let msg = GatewayMessage::Registered {
@@ -592,10 +823,12 @@ mod tests {
             call_id: "call-1".to_string(),
             capability: "camera.snap".to_string(),
             args: serde_json::json!({"resolution": "1080p"}),
+            traceparent: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"type\":\"invoke\""));
         assert!(json.contains("\"capability\":\"camera.snap\""));
+        assert!(!json.contains("traceparent"));
     }
 
     #[test]
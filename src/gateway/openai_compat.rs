@@ -0,0 +1,623 @@
+//! OpenAI-compatible chat completions API.
+//!
+//! Lets OpenAI SDK clients (editors, LangChain apps, scripts) use a ZeroClaw
+//! agent as a drop-in backend:
+//!
+//! ```text
+//! POST /v1/chat/completions   — full agent turn (JSON or SSE when "stream": true)
+//! GET  /v1/models             — default model plus configured route hints
+//! ```
+//!
+//! Every request runs a complete [`Agent`](crate::agent::Agent) turn, so tools
+//! are executed server-side under the gateway's `SecurityPolicy`. Client-side
+//! `tools` in the request body are ignored; server tool invocations are
+//! reported to streaming clients as `tool_calls` deltas for visibility only,
+//! and the stream still finishes with `"stop"`.
+//!
+//! The request `messages` array is treated as the conversation: everything
+//! before the final user message seeds the agent history. Client `system`
+//! messages are ignored because the agent builds its own system prompt.
+//!
+//! Authentication uses the same pairing bearer token as `/webhook`.
+
+use super::{authorize_bearer, webhook_session_id, AppState, AuthRejection};
+use crate::agent::TurnEvent;
+use crate::config::Config;
+use crate::providers::ChatMessage;
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Model id that always resolves to the configured default model.
+pub const DEFAULT_MODEL_ALIAS: &str = "zeroclaw";
+
+/// Maximum request body size for chat completions (1MB) — clients resend the
+/// whole conversation on every call, which quickly exceeds the 64KB default.
+pub const MAX_CHAT_COMPLETIONS_BODY_SIZE: usize = 1_048_576;
+
+// ── Request types ───────────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    pub model: Option<String>,
+    pub messages: Vec<ChatCompletionMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// OpenAI end-user identifier, used as the memory session id when no
+    /// `X-Session-Id` header is present.
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<MessageContent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContentPart {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub image_url: Option<ImageUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImageUrl {
+    pub url: String,
+}
+
+impl MessageContent {
+    /// Flatten content into plain text. Image parts become `[IMAGE:...]`
+    /// markers understood by the multimodal pipeline; only `http(s)` and
+    /// `data:` URLs are accepted so remote clients cannot reference local files.
+    fn to_text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| match part.kind.as_str() {
+                    "text" => part.text.clone(),
+                    "image_url" => part.image_url.as_ref().and_then(|img| {
+                        let url = img.url.trim();
+                        (url.starts_with("https://")
+                            || url.starts_with("http://")
+                            || url.starts_with("data:"))
+                        .then(|| format!("[IMAGE:{url}]"))
+                    }),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+// ── Helpers ─────────────────────────────────────────────────────
+
+fn openai_error(status: StatusCode, message: &str, kind: &str, code: &str) -> Response {
+    let body = serde_json::json!({
+        "error": {
+            "message": message,
+            "type": kind,
+            "code": code,
+        }
+    });
+    (status, Json(body)).into_response()
+}
+
+/// Apply the shared gateway auth, returning an OpenAI-shaped error response
+/// on rejection.
+#[allow(clippy::result_large_err)]
+fn authorize(state: &AppState, peer_addr: SocketAddr, headers: &HeaderMap) -> Result<(), Response> {
+    authorize_bearer(state, peer_addr, headers, "/v1").map_err(|rejection| {
+        let (kind, code) = match rejection {
+            AuthRejection::Unauthorized => ("invalid_request_error", "invalid_api_key"),
+            _ => ("rate_limit_error", "rate_limit_exceeded"),
+        };
+        openai_error(rejection.status(), &rejection.message(), kind, code)
+    })
+}
+
+/// Resolve the model requested by the client into the value passed to the
+/// agent as `default_model`.
+///
+/// Accepts the [`DEFAULT_MODEL_ALIAS`], the configured default model,
+/// `hint:<name>` for any `[[model_routes]]` entry, or the bare hint name.
+/// Returns `None` for unknown models.
+fn resolve_model(config: &Config, default_model: &str, requested: Option<&str>) -> Option<String> {
+    let requested = requested.map(str::trim).unwrap_or("");
+    if requested.is_empty() || requested == DEFAULT_MODEL_ALIAS || requested == default_model {
+        return Some(default_model.to_string());
+    }
+
+    let hint = requested.strip_prefix("hint:").unwrap_or(requested);
+    config
+        .model_routes
+        .iter()
+        .any(|route| route.hint == hint)
+        .then(|| format!("hint:{hint}"))
+}
+
+/// Split an OpenAI `messages` array into seed history and the final user
+/// prompt. Returns `None` when the last message is not a non-empty user turn.
+fn split_conversation(messages: &[ChatCompletionMessage]) -> Option<(Vec<ChatMessage>, String)> {
+    let (last, earlier) = messages.split_last()?;
+    if last.role != "user" {
+        return None;
+    }
+    let prompt = last
+        .content
+        .as_ref()
+        .map(MessageContent::to_text)
+        .unwrap_or_default();
+    if prompt.trim().is_empty() {
+        return None;
+    }
+
+    let history = earlier
+        .iter()
+        .filter_map(|msg| {
+            let text = msg.content.as_ref().map(MessageContent::to_text)?;
+            if text.trim().is_empty() {
+                return None;
+            }
+            match msg.role.as_str() {
+                "user" => Some(ChatMessage::user(text)),
+                "assistant" => Some(ChatMessage::assistant(text)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    Some((history, prompt))
+}
+
+fn completion_chunk(
+    id: &str,
+    created: i64,
+    model: &str,
+    delta: serde_json::Value,
+    finish_reason: Option<&str>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "delta": delta,
+            "finish_reason": finish_reason,
+        }],
+    })
+}
+
+/// Map a streamed agent event to an OpenAI chunk delta. Tool results and
+/// thinking deltas have no OpenAI equivalent and are dropped.
+fn delta_for_event(event: TurnEvent, tool_index: &mut usize) -> Option<serde_json::Value> {
+    match event {
+        TurnEvent::Chunk { delta } => Some(serde_json::json!({ "content": delta })),
+        TurnEvent::ToolCall { name, args } => {
+            let index = *tool_index;
+            *tool_index += 1;
+            Some(serde_json::json!({
+                "tool_calls": [{
+                    "index": index,
+                    "id": format!("call_{}", uuid::Uuid::new_v4().simple()),
+                    "type": "function",
+                    "function": {
+                        "name": name,
+                        "arguments": args.to_string(),
+                    },
+                }],
+            }))
+        }
+        TurnEvent::Thinking { .. } | TurnEvent::ToolResult { .. } => None,
+    }
+}
+
+// ── Handlers ────────────────────────────────────────────────────
+
+/// GET /v1/models — list the default model and configured route hints
+pub async fn handle_v1_models(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Response {
+    if let Err(resp) = authorize(&state, peer_addr, &headers) {
+        return resp;
+    }
+
+    let config = state.config.lock().clone();
+    let default_provider = config
+        .default_provider
+        .clone()
+        .unwrap_or_else(|| "openrouter".to_string());
+
+    let mut data = vec![
+        serde_json::json!({
+            "id": DEFAULT_MODEL_ALIAS,
+            "object": "model",
+            "created": 0,
+            "owned_by": default_provider,
+        }),
+        serde_json::json!({
+            "id": state.model,
+            "object": "model",
+            "created": 0,
+            "owned_by": default_provider,
+        }),
+    ];
+    data.extend(config.model_routes.iter().map(|route| {
+        serde_json::json!({
+            "id": format!("hint:{}", route.hint),
+            "object": "model",
+            "created": 0,
+            "owned_by": route.provider,
+            "root": route.model,
+        })
+    }));
+
+    Json(serde_json::json!({ "object": "list", "data": data })).into_response()
+}
+
+/// POST /v1/chat/completions — run an agent turn and reply in OpenAI format
+pub async fn handle_v1_chat_completions(
+    State(state): State<AppState>,
+    ConnectInfo(peer_addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    body: Result<Json<ChatCompletionRequest>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    if let Err(resp) = authorize(&state, peer_addr, &headers) {
+        return resp;
+    }
+
+    let Json(request) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::warn!("/v1/chat/completions JSON parse error: {e}");
+            return openai_error(
+                StatusCode::BAD_REQUEST,
+                &format!("Invalid request body: {e}"),
+                "invalid_request_error",
+                "invalid_body",
+            );
+        }
+    };
+
+    let Some((history, prompt)) = split_conversation(&request.messages) else {
+        return openai_error(
+            StatusCode::BAD_REQUEST,
+            "The last message must be a non-empty user message",
+            "invalid_request_error",
+            "invalid_messages",
+        );
+    };
+
+    let mut config = state.config.lock().clone();
+    let Some(model) = resolve_model(&config, &state.model, request.model.as_deref()) else {
+        return openai_error(
+            StatusCode::NOT_FOUND,
+            &format!(
+                "The model '{}' does not exist. See GET /v1/models.",
+                request.model.as_deref().unwrap_or_default()
+            ),
+            "invalid_request_error",
+            "model_not_found",
+        );
+    };
+    config.default_model = Some(model.clone());
+    if let Some(temperature) = request.temperature {
+        config.default_temperature = temperature.clamp(0.0, 2.0);
+    }
+
+    let mut agent = match crate::agent::Agent::from_config(&config).await {
+        Ok(a) => a,
+        Err(e) => {
+            tracing::error!(error = %e, "Agent initialization failed");
+            return openai_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &crate::providers::sanitize_api_error(&e.to_string()),
+                "server_error",
+                "agent_init_failed",
+            );
+        }
+    };
+    let session_id = webhook_session_id(&headers).or(request.user.clone());
+    agent.set_memory_session_id(session_id);
+    if !history.is_empty() {
+        agent.seed_history(&history);
+    }
+
+    let provider_label = config
+        .default_provider
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    let _ = state.event_tx.send(serde_json::json!({
+        "type": "agent_start",
+        "provider": provider_label,
+        "model": model,
+    }));
+
+    let completion_id = format!("chatcmpl-{}", uuid::Uuid::new_v4().simple());
+    let created = chrono::Utc::now().timestamp();
+
+    if request.stream {
+        return stream_completion(state, agent, prompt, completion_id, created, model)
+            .into_response();
+    }
+
+    match agent.turn(&prompt).await {
+        Ok(response) => {
+            let _ = state.event_tx.send(serde_json::json!({
+                "type": "agent_end",
+                "provider": provider_label,
+                "model": model,
+            }));
+            Json(serde_json::json!({
+                "id": completion_id,
+                "object": "chat.completion",
+                "created": created,
+                "model": model,
+                "choices": [{
+                    "index": 0,
+                    "message": { "role": "assistant", "content": response },
+                    "finish_reason": "stop",
+                }],
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            let sanitized = crate::providers::sanitize_api_error(&e.to_string());
+            tracing::error!("/v1/chat/completions agent error: {sanitized}");
+            let _ = state.event_tx.send(serde_json::json!({
+                "type": "error",
+                "component": "openai_compat",
+                "message": sanitized,
+            }));
+            openai_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &sanitized,
+                "server_error",
+                "agent_error",
+            )
+        }
+    }
+}
+
+/// Run the turn in a background task and relay events as SSE chunks,
+/// terminated by the `data: [DONE]` sentinel.
+fn stream_completion(
+    state: AppState,
+    mut agent: crate::agent::Agent,
+    prompt: String,
+    completion_id: String,
+    created: i64,
+    model: String,
+) -> impl IntoResponse {
+    let (sse_tx, sse_rx) = tokio::sync::mpsc::channel::<Result<Event, Infallible>>(64);
+
+    tokio::spawn(async move {
+        let send = |value: serde_json::Value| {
+            let sse_tx = sse_tx.clone();
+            async move {
+                let _ = sse_tx
+                    .send(Ok(Event::default().data(value.to_string())))
+                    .await;
+            }
+        };
+
+        send(completion_chunk(
+            &completion_id,
+            created,
+            &model,
+            serde_json::json!({ "role": "assistant", "content": "" }),
+            None,
+        ))
+        .await;
+
+        let (event_tx, mut event_rx) = tokio::sync::mpsc::channel::<TurnEvent>(64);
+        let turn_fut = async { agent.turn_streamed(&prompt, event_tx).await };
+        let forward_fut = async {
+            let mut tool_index = 0usize;
+            while let Some(event) = event_rx.recv().await {
+                if let Some(delta) = delta_for_event(event, &mut tool_index) {
+                    send(completion_chunk(
+                        &completion_id,
+                        created,
+                        &model,
+                        delta,
+                        None,
+                    ))
+                    .await;
+                }
+            }
+        };
+        let (result, ()) = tokio::join!(turn_fut, forward_fut);
+
+        match result {
+            Ok(_) => {
+                send(completion_chunk(
+                    &completion_id,
+                    created,
+                    &model,
+                    serde_json::json!({}),
+                    // The tools already ran server-side; `tool_calls` would
+                    // ask the client to run them again.
+                    Some("stop"),
+                ))
+                .await;
+                let _ = state.event_tx.send(serde_json::json!({
+                    "type": "agent_end",
+                    "model": model,
+                }));
+            }
+            Err(e) => {
+                let sanitized = crate::providers::sanitize_api_error(&e.to_string());
+                tracing::error!("/v1/chat/completions stream error: {sanitized}");
+                send(serde_json::json!({
+                    "error": {
+                        "message": sanitized,
+                        "type": "server_error",
+                        "code": "agent_error",
+                    }
+                }))
+                .await;
+            }
+        }
+
+        let _ = sse_tx.send(Ok(Event::default().data("[DONE]"))).await;
+    });
+
+    Sse::new(ReceiverStream::new(sse_rx)).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, content: &str) -> ChatCompletionMessage {
+        ChatCompletionMessage {
+            role: role.to_string(),
+            content: Some(MessageContent::Text(content.to_string())),
+        }
+    }
+
+    #[test]
+    fn resolve_model_accepts_alias_default_and_hints() {
+        let mut config = Config::default();
+        config.model_routes.push(crate::config::ModelRouteConfig {
+            hint: "fast".into(),
+            provider: "groq".into(),
+            model: "llama-3.3-70b".into(),
+            api_key: None,
+            context_window: None,
+        });
+
+        assert_eq!(
+            resolve_model(&config, "gpt-4o", None).as_deref(),
+            Some("gpt-4o")
+        );
+        assert_eq!(
+            resolve_model(&config, "gpt-4o", Some("zeroclaw")).as_deref(),
+            Some("gpt-4o")
+        );
+        assert_eq!(
+            resolve_model(&config, "gpt-4o", Some("hint:fast")).as_deref(),
+            Some("hint:fast")
+        );
+        assert_eq!(
+            resolve_model(&config, "gpt-4o", Some("fast")).as_deref(),
+            Some("hint:fast")
+        );
+        assert!(resolve_model(&config, "gpt-4o", Some("unknown-model")).is_none());
+    }
+
+    #[test]
+    fn split_conversation_seeds_history_and_skips_system() {
+        let messages = vec![
+            msg("system", "be terse"),
+            msg("user", "hi"),
+            msg("assistant", "hello"),
+            msg("user", "what's up?"),
+        ];
+        let (history, prompt) = split_conversation(&messages).unwrap();
+        assert_eq!(prompt, "what's up?");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].role, "user");
+        assert_eq!(history[1].role, "assistant");
+    }
+
+    #[test]
+    fn split_conversation_requires_trailing_user_message() {
+        assert!(split_conversation(&[]).is_none());
+        assert!(split_conversation(&[msg("user", "hi"), msg("assistant", "yo")]).is_none());
+        assert!(split_conversation(&[msg("user", "   ")]).is_none());
+    }
+
+    #[test]
+    fn content_parts_flatten_text_and_safe_images() {
+        let content: MessageContent = serde_json::from_value(serde_json::json!([
+            {"type": "text", "text": "look"},
+            {"type": "image_url", "image_url": {"url": "https://example.com/a.png"}},
+            {"type": "image_url", "image_url": {"url": "/etc/passwd"}},
+        ]))
+        .unwrap();
+        assert_eq!(content.to_text(), "look\n[IMAGE:https://example.com/a.png]");
+    }
+
+    #[test]
+    fn tool_call_events_become_indexed_deltas() {
+        let mut index = 0;
+        let first = delta_for_event(
+            TurnEvent::ToolCall {
+                name: "shell".into(),
+                args: serde_json::json!({"command": "ls"}),
+            },
+            &mut index,
+        )
+        .unwrap();
+        let second = delta_for_event(
+            TurnEvent::ToolCall {
+                name: "file_read".into(),
+                args: serde_json::json!({}),
+            },
+            &mut index,
+        )
+        .unwrap();
+        assert_eq!(first["tool_calls"][0]["index"], 0);
+        assert_eq!(first["tool_calls"][0]["function"]["name"], "shell");
+        assert_eq!(
+            first["tool_calls"][0]["function"]["arguments"],
+            r#"{"command":"ls"}"#
+        );
+        assert_eq!(second["tool_calls"][0]["index"], 1);
+        assert_eq!(index, 2);
+        assert!(delta_for_event(
+            TurnEvent::ToolResult {
+                name: "shell".into(),
+                output: "ok".into()
+            },
+            &mut index
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn completion_chunk_has_openai_shape() {
+        let chunk = completion_chunk(
+            "chatcmpl-1",
+            42,
+            "m",
+            serde_json::json!({"content": "hi"}),
+            Some("stop"),
+        );
+        assert_eq!(chunk["object"], "chat.completion.chunk");
+        assert_eq!(chunk["choices"][0]["delta"]["content"], "hi");
+        assert_eq!(chunk["choices"][0]["finish_reason"], "stop");
+    }
+}
@@ -12,6 +12,16 @@
 //! Server -> Client: {"type":"done","full_response":"..."}
 //! ```
 //!
+//! Branching (requires session persistence):
+//! ```text
+//! Client -> Server: {"type":"rewind","message_id":42}
+//! Client -> Server: {"type":"edit","message_id":41,"content":"Hello again"}
+//! Server -> Client: {"type":"history","active_head":42,"messages":[{"id":41,"parent_id":null,...}]}
+//! ```
+//! `rewind` continues the session from message 42 (use `null` for the start);
+//! `edit` replaces user message 41 on a new branch and runs a turn for it.
+//! Earlier branches are kept and listed by `GET /api/sessions/{id}/branches`.
+//!
 //! Query params:
 //! - `session_id` — resume or create a session (default: new UUID)
 //! - `name` — optional human-readable label for the session
@@ -193,7 +203,10 @@ async fn handle_socket(
     let mut resumed = false;
     let mut message_count: usize = 0;
     let mut effective_name: Option<String> = None;
+    // Branch head the agent history was last loaded from
+    let mut synced_head: Option<i64> = None;
     if let Some(ref backend) = state.session_backend {
+        synced_head = backend.active_head(&session_key);
         let messages = backend.load(&session_key);
         if !messages.is_empty() {
             message_count = messages.len();
@@ -324,7 +337,7 @@ async fn handle_socket(
         };
 
         let msg_type = parsed["type"].as_str().unwrap_or("");
-        if msg_type != "message" {
+        if !matches!(msg_type, "message" | "rewind" | "edit") {
             let err = serde_json::json!({
                 "type": "error",
                 "message": format!(
@@ -337,7 +350,7 @@ async fn handle_socket(
         }
 
         let content = parsed["content"].as_str().unwrap_or("").to_string();
-        if content.is_empty() {
+        if content.is_empty() && msg_type != "rewind" {
             let err = serde_json::json!({
                 "type": "error",
                 "message": "Message content cannot be empty",
@@ -361,6 +374,32 @@ async fn handle_socket(
             }
         };
 
+        if msg_type == "message" {
+            // Pick up branch changes made through the REST API since the last turn
+            if let Some(ref backend) = state.session_backend {
+                let head = backend.active_head(&session_key);
+                if head != synced_head {
+                    agent.clear_history();
+                    agent.seed_history(&backend.load(&session_key));
+                    synced_head = head;
+                }
+            }
+        } else {
+            match checkout_branch(&state, &mut agent, &parsed, &session_key) {
+                Ok(history) => {
+                    synced_head = history["active_head"].as_i64();
+                    let _ = sender.send(Message::Text(history.to_string().into())).await;
+                }
+                Err(err) => {
+                    let _ = sender.send(Message::Text(err.to_string().into())).await;
+                    continue;
+                }
+            }
+            if msg_type == "rewind" {
+                continue;
+            }
+        }
+
         // Persist user message
         if let Some(ref backend) = state.session_backend {
             let user_msg = crate::providers::ChatMessage::user(&content);
@@ -368,7 +407,93 @@ async fn handle_socket(
         }
 
         process_chat_message(&state, &mut agent, &mut sender, &content, &session_key).await;
+
+        if let Some(ref backend) = state.session_backend {
+            synced_head = backend.active_head(&session_key);
+        }
     }
+
+    agent.end_process_session();
+}
+
+/// Move the session head for a `rewind` or `edit` frame and reload the agent's
+/// history from the new branch.
+///
+/// `rewind` continues from `message_id` itself; `edit` continues from the parent
+/// of the user message being replaced, so the edited text starts a sibling branch.
+/// Returns the `history` frame to send, or an `error` frame.
+fn checkout_branch(
+    state: &AppState,
+    agent: &mut crate::agent::Agent,
+    parsed: &serde_json::Value,
+    session_key: &str,
+) -> Result<serde_json::Value, serde_json::Value> {
+    let error = |message: String, code: &str| serde_json::json!({ "type": "error", "message": message, "code": code });
+
+    let Some(ref backend) = state.session_backend else {
+        return Err(error(
+            "Branching requires gateway session persistence".into(),
+            "PERSISTENCE_DISABLED",
+        ));
+    };
+
+    let message_id = match parsed.get("message_id") {
+        Some(serde_json::Value::Null) if parsed["type"] == "rewind" => None,
+        Some(v) if v.is_i64() => v.as_i64(),
+        _ => {
+            return Err(error(
+                "message_id (integer) is required".into(),
+                "INVALID_MESSAGE_ID",
+            ))
+        }
+    };
+
+    let target = if parsed["type"] == "edit" {
+        let edited = backend
+            .load_messages(session_key)
+            .into_iter()
+            .find(|m| Some(m.id) == message_id);
+        match edited {
+            Some(m) if m.message.role == "user" => m.parent_id,
+            _ => {
+                return Err(error(
+                    "Only user messages on the current branch can be edited".into(),
+                    "INVALID_MESSAGE_ID",
+                ))
+            }
+        }
+    } else {
+        message_id
+    };
+
+    match backend.checkout(session_key, target) {
+        Ok(true) => {}
+        Ok(false) => {
+            return Err(error(
+                "Message not found in this session".into(),
+                "INVALID_MESSAGE_ID",
+            ))
+        }
+        Err(e) => return Err(error(e.to_string(), "BRANCH_FAILED")),
+    }
+
+    let messages = backend.load_messages(session_key);
+    agent.clear_history();
+    agent.seed_history(
+        &messages
+            .iter()
+            .map(|m| m.message.clone())
+            .collect::<Vec<_>>(),
+    );
+
+    Ok(serde_json::json!({
+        "type": "history",
+        "active_head": target,
+        "messages": messages
+            .iter()
+            .map(super::api::session_message_json)
+            .collect::<Vec<_>>(),
+    }))
 }
 
 /// Process a single chat message through the agent and send the response.
@@ -1,9 +1,30 @@
+pub mod runner;
+pub mod scheduler;
+pub mod store;
 pub mod types;
 
 pub use types::{Hand, HandContext, HandRun, HandRunStatus};
 
+use crate::config::Config;
 use anyhow::{Context, Result};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Directory holding hand definitions (`~/.zeroclaw/hands`).
+pub fn hands_dir(config: &Config) -> PathBuf {
+    config.config_path.parent().map_or_else(
+        || config.workspace_dir.join("hands"),
+        |dir| dir.join("hands"),
+    )
+}
+
+/// Load a single hand definition by name.
+pub fn find_hand(config: &Config, name: &str) -> Result<Hand> {
+    let dir = hands_dir(config);
+    load_hands(&dir)?
+        .into_iter()
+        .find(|hand| hand.name == name)
+        .with_context(|| format!("Hand '{name}' not found in {}", dir.display()))
+}
 
 /// Load all hand definitions from TOML files in the given directory.
 ///
@@ -68,6 +89,89 @@ pub fn save_hand_context(hands_dir: &Path, context: &HandContext) -> Result<()>
     Ok(())
 }
 
+pub async fn handle_command(command: crate::HandCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::HandCommands::List => {
+            let dir = hands_dir(config);
+            let hands = load_hands(&dir)?;
+            if hands.is_empty() {
+                println!("No hands defined in {}.", dir.display());
+                return Ok(());
+            }
+
+            println!("🤚 Hands ({}):", hands.len());
+            for hand in hands {
+                let state = store::get_state(config, &hand.name)?;
+                let next_run = state
+                    .as_ref()
+                    .and_then(|s| s.next_run)
+                    .map_or_else(|| "n/a".into(), |d| d.to_rfc3339());
+                let last_run = state
+                    .as_ref()
+                    .and_then(|s| s.last_run)
+                    .map_or_else(|| "never".into(), |d| d.to_rfc3339());
+                let total_runs = state.map_or(0, |s| s.total_runs);
+                println!(
+                    "- {}{} | {:?} | next={} | last={} | runs={}",
+                    hand.name,
+                    if hand.active { "" } else { " (inactive)" },
+                    hand.schedule,
+                    next_run,
+                    last_run,
+                    total_runs,
+                );
+                println!("    {}", hand.description);
+            }
+            Ok(())
+        }
+        crate::HandCommands::Run { name } => {
+            let hand = find_hand(config, &name)?;
+            println!("▶️  Running hand {}...", hand.name);
+            let run = Box::pin(runner::run_hand(config, &hand)).await;
+            if let HandRunStatus::Failed { error } = &run.status {
+                anyhow::bail!("Hand run {} failed: {error}", run.run_id);
+            }
+            println!("✅ Hand run {} completed", run.run_id);
+            print_run_details(&run);
+            Ok(())
+        }
+        crate::HandCommands::History { name, limit } => {
+            let hand = find_hand(config, &name)?;
+            let runs = store::list_runs(config, &hand.name, limit)?;
+            if runs.is_empty() {
+                println!("Hand {} has not run yet.", hand.name);
+                return Ok(());
+            }
+
+            println!("🕒 Recent runs of {} ({}):", hand.name, runs.len());
+            for run in runs {
+                let status = match &run.status {
+                    HandRunStatus::Running => "running".to_string(),
+                    HandRunStatus::Completed => "completed".to_string(),
+                    HandRunStatus::Failed { error } => format!("failed: {error}"),
+                };
+                println!(
+                    "- {} | {} | {}",
+                    run.run_id,
+                    run.started_at.to_rfc3339(),
+                    status
+                );
+                print_run_details(&run);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_run_details(run: &HandRun) {
+    for finding in &run.findings {
+        println!("    finding: {finding}");
+    }
+    for fact in &run.knowledge_added {
+        println!("    learned: {fact}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
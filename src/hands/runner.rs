@@ -0,0 +1,243 @@
+use crate::config::Config;
+use crate::hands::{store, Hand, HandContext, HandRun, HandRunStatus};
+use crate::security::SecurityPolicy;
+use chrono::Utc;
+use std::fmt::Write as _;
+
+/// Line prefix the agent uses to report a key result of the run.
+const FINDING_PREFIX: &str = "FINDING:";
+/// Line prefix the agent uses to report a fact worth keeping across runs.
+const LEARNED_PREFIX: &str = "LEARNED:";
+/// How many previous runs' findings are replayed into the prompt.
+const RECENT_RUNS_IN_PROMPT: usize = 3;
+/// Upper bound on the fallback finding taken from an unstructured reply.
+const MAX_FALLBACK_FINDING_CHARS: usize = 2_000;
+
+/// Execute a hand once through the agent and persist the outcome.
+///
+/// The run is recorded in the hands store whether it succeeds or fails;
+/// the returned [`HandRun`] mirrors what was stored.
+pub async fn run_hand(config: &Config, hand: &Hand) -> HandRun {
+    let started_at = Utc::now();
+    let run_id = uuid::Uuid::new_v4().to_string();
+
+    let outcome = Box::pin(execute(config, hand)).await;
+    let finished_at = Utc::now();
+    let duration_ms = u64::try_from((finished_at - started_at).num_milliseconds()).ok();
+
+    let run = match outcome {
+        Ok(response) => {
+            let (findings, knowledge_added) = parse_report(&response);
+            HandRun {
+                hand_name: hand.name.clone(),
+                run_id,
+                started_at,
+                finished_at: Some(finished_at),
+                status: HandRunStatus::Completed,
+                findings,
+                knowledge_added,
+                duration_ms,
+            }
+        }
+        Err(error) => HandRun {
+            hand_name: hand.name.clone(),
+            run_id,
+            started_at,
+            finished_at: Some(finished_at),
+            status: HandRunStatus::Failed { error },
+            findings: Vec::new(),
+            knowledge_added: Vec::new(),
+            duration_ms,
+        },
+    };
+
+    if let Err(e) = store::record_run(config, &run, hand.max_history) {
+        tracing::warn!(hand = %hand.name, "Failed to persist hand run: {e}");
+    }
+    run
+}
+
+async fn execute(config: &Config, hand: &Hand) -> Result<String, String> {
+    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+        .with_trust(crate::trust::runtime::init(config));
+    if !security.can_act() {
+        return Err("blocked by security policy: autonomy is read-only".to_string());
+    }
+    if security.is_rate_limited() {
+        return Err("blocked by security policy: rate limit exceeded".to_string());
+    }
+    if !security.record_action() {
+        return Err("blocked by security policy: action budget exhausted".to_string());
+    }
+
+    let context = store::load_context(config, hand).map_err(|e| e.to_string())?;
+    let prompt = build_prompt(hand, &context);
+
+    Box::pin(crate::agent::run(
+        config.clone(),
+        Some(prompt),
+        None,
+        hand.model.clone(),
+        config.default_temperature,
+        vec![],
+        false,
+        None,
+        hand.allowed_tools.clone(),
+    ))
+    .await
+    .map_err(|e| format!("hand run failed: {e}"))
+}
+
+/// Assemble the run prompt from the hand definition and its rolling context.
+pub(crate) fn build_prompt(hand: &Hand, context: &HandContext) -> String {
+    let mut prompt = format!(
+        "[hand:{}] {}\n\n{}\n",
+        hand.name, hand.description, hand.prompt
+    );
+
+    if !hand.knowledge.is_empty() {
+        prompt.push_str("\n## Domain knowledge\n");
+        for line in &hand.knowledge {
+            let _ = writeln!(prompt, "- {line}");
+        }
+    }
+
+    if !context.learned_facts.is_empty() {
+        prompt.push_str("\n## Learned in previous runs\n");
+        for fact in &context.learned_facts {
+            let _ = writeln!(prompt, "- {fact}");
+        }
+    }
+
+    let recent: Vec<&HandRun> = context
+        .history
+        .iter()
+        .filter(|run| run.status == HandRunStatus::Completed && !run.findings.is_empty())
+        .take(RECENT_RUNS_IN_PROMPT)
+        .collect();
+    if !recent.is_empty() {
+        prompt.push_str("\n## Findings from recent runs\n");
+        for run in recent {
+            let _ = writeln!(prompt, "### {}", run.started_at.to_rfc3339());
+            for finding in &run.findings {
+                let _ = writeln!(prompt, "- {finding}");
+            }
+        }
+    }
+
+    let _ = write!(
+        prompt,
+        "\n## Report format\n\
+         Finish with one `{FINDING_PREFIX} ...` line per key result of this run and one \
+         `{LEARNED_PREFIX} ...` line per new durable fact worth remembering for future runs. \
+         Do not repeat facts already listed above.\n"
+    );
+    prompt
+}
+
+/// Extract findings and newly learned facts from the agent's reply.
+///
+/// When the reply carries no `FINDING:` lines, the whole reply (truncated)
+/// is kept as a single finding so the run is never recorded empty-handed.
+pub(crate) fn parse_report(response: &str) -> (Vec<String>, Vec<String>) {
+    let mut findings = Vec::new();
+    let mut learned = Vec::new();
+    for line in response.lines() {
+        let line = line.trim().trim_start_matches(['-', '*']).trim_start();
+        if let Some(rest) = line.strip_prefix(FINDING_PREFIX) {
+            push_non_empty(&mut findings, rest);
+        } else if let Some(rest) = line.strip_prefix(LEARNED_PREFIX) {
+            push_non_empty(&mut learned, rest);
+        }
+    }
+
+    if findings.is_empty() {
+        let trimmed = response.trim();
+        if !trimmed.is_empty() {
+            findings.push(crate::util::truncate_with_ellipsis(
+                trimmed,
+                MAX_FALLBACK_FINDING_CHARS,
+            ));
+        }
+    }
+    (findings, learned)
+}
+
+fn push_non_empty(target: &mut Vec<String>, value: &str) {
+    let value = value.trim();
+    if !value.is_empty() && !target.iter().any(|existing| existing == value) {
+        target.push(value.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cron::Schedule;
+
+    fn sample_hand() -> Hand {
+        Hand {
+            name: "scanner".into(),
+            description: "Market scanner".into(),
+            schedule: Schedule::Every {
+                every_ms: 60_000,
+                jitter_ms: 0,
+            },
+            prompt: "Scan markets.".into(),
+            knowledge: vec!["Focus on tech.".into()],
+            allowed_tools: None,
+            model: None,
+            active: true,
+            max_history: 10,
+        }
+    }
+
+    #[test]
+    fn parse_report_extracts_tagged_lines() {
+        let reply = "Summary of the scan.\n\
+                     FINDING: NVDA up 4%\n\
+                     - FINDING: AMD flat\n\
+                     LEARNED: Earnings season starts in October\n\
+                     LEARNED:   \n";
+        let (findings, learned) = parse_report(reply);
+        assert_eq!(findings, vec!["NVDA up 4%", "AMD flat"]);
+        assert_eq!(learned, vec!["Earnings season starts in October"]);
+    }
+
+    #[test]
+    fn parse_report_falls_back_to_whole_reply() {
+        let (findings, learned) = parse_report("  Nothing notable today.  ");
+        assert_eq!(findings, vec!["Nothing notable today."]);
+        assert!(learned.is_empty());
+
+        let (findings, _) = parse_report("   ");
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn build_prompt_includes_rolling_context() {
+        let hand = sample_hand();
+        let mut context = HandContext::new("scanner");
+        context.learned_facts.push("Markets close at 4pm ET".into());
+        context.record_run(
+            HandRun {
+                hand_name: "scanner".into(),
+                run_id: "r1".into(),
+                started_at: Utc::now(),
+                finished_at: Some(Utc::now()),
+                status: HandRunStatus::Completed,
+                findings: vec!["TSLA down 2%".into()],
+                knowledge_added: vec![],
+                duration_ms: Some(10),
+            },
+            10,
+        );
+
+        let prompt = build_prompt(&hand, &context);
+        assert!(prompt.starts_with("[hand:scanner] Market scanner"));
+        assert!(prompt.contains("- Focus on tech."));
+        assert!(prompt.contains("- Markets close at 4pm ET"));
+        assert!(prompt.contains("- TSLA down 2%"));
+        assert!(prompt.contains(FINDING_PREFIX));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::config::Config;
+use crate::hands::{hands_dir, load_hands, runner, store, HandRunStatus};
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::{stream, StreamExt};
+use tokio::time::{self, Duration};
+
+const MIN_POLL_SECONDS: u64 = 5;
+const HANDS_COMPONENT: &str = "hands";
+
+/// Daemon loop: rediscover hand definitions on every tick and run the due ones.
+///
+/// Hand files are re-read each cycle so new or edited hands are picked up
+/// without restarting the daemon.
+pub async fn run(config: Config) -> Result<()> {
+    let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
+    let mut interval = time::interval(Duration::from_secs(poll_secs));
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+    let dir = hands_dir(&config);
+
+    crate::health::mark_component_ok(HANDS_COMPONENT);
+    tracing::info!(dir = %dir.display(), "Hands scheduler started");
+
+    loop {
+        interval.tick().await;
+        crate::health::mark_component_ok(HANDS_COMPONENT);
+
+        let hands = match load_hands(&dir) {
+            Ok(hands) => hands,
+            Err(e) => {
+                crate::health::mark_component_error(HANDS_COMPONENT, e.to_string());
+                tracing::warn!("Failed to load hands: {e}");
+                continue;
+            }
+        };
+        if hands.is_empty() {
+            continue;
+        }
+
+        let due = match store::due_hands(&config, &hands, Utc::now()) {
+            Ok(due) => due,
+            Err(e) => {
+                crate::health::mark_component_error(HANDS_COMPONENT, e.to_string());
+                tracing::warn!("Hands schedule query failed: {e}");
+                continue;
+            }
+        };
+
+        let max_concurrent = config.scheduler.max_concurrent.max(1);
+        let mut in_flight = stream::iter(due.into_iter().map(|hand| {
+            let config = config.clone();
+            async move {
+                let run = Box::pin(runner::run_hand(&config, &hand)).await;
+                if let Err(e) = store::reschedule_after_run(&config, &hand, Utc::now()) {
+                    tracing::warn!(hand = %hand.name, "Failed to reschedule hand: {e}");
+                }
+                run
+            }
+        }))
+        .buffer_unordered(max_concurrent);
+
+        while let Some(run) = in_flight.next().await {
+            match &run.status {
+                HandRunStatus::Failed { error } => {
+                    tracing::warn!(hand = %run.hand_name, run_id = %run.run_id, "Hand run failed: {error}");
+                }
+                _ => tracing::info!(
+                    hand = %run.hand_name,
+                    run_id = %run.run_id,
+                    findings = run.findings.len(),
+                    learned = run.knowledge_added.len(),
+                    "Hand run completed"
+                ),
+            }
+        }
+    }
+}
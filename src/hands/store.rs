@@ -0,0 +1,551 @@
+use crate::config::Config;
+use crate::cron::{next_run_for_schedule, Schedule};
+use crate::hands::{Hand, HandContext, HandRun, HandRunStatus};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+
+/// Scheduling and bookkeeping state for a single hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandState {
+    pub hand_name: String,
+    /// Next scheduled run (None once a one-shot hand has fired)
+    pub next_run: Option<DateTime<Utc>>,
+    pub last_run: Option<DateTime<Utc>>,
+    /// Total number of completed runs, including pruned history
+    pub total_runs: u64,
+}
+
+/// Record a finished run: append it to the history, merge any new
+/// knowledge and prune history beyond `max_history` and facts beyond
+/// `[hands] max_facts`.
+pub fn record_run(config: &Config, run: &HandRun, max_history: usize) -> Result<()> {
+    let (status, error) = match &run.status {
+        HandRunStatus::Running => ("running", None),
+        HandRunStatus::Completed => ("completed", None),
+        HandRunStatus::Failed { error } => ("failed", Some(error.as_str())),
+    };
+    let completed = run.status == HandRunStatus::Completed;
+    let findings = serde_json::to_string(&run.findings)?;
+    let knowledge = serde_json::to_string(&run.knowledge_added)?;
+    let duration_ms = run
+        .duration_ms
+        .map(i64::try_from)
+        .transpose()
+        .context("Hand run duration overflow")?;
+
+    with_connection(config, |conn| {
+        let tx = conn.unchecked_transaction()?;
+
+        tx.execute(
+            "INSERT INTO hand_runs
+                (run_id, hand_name, started_at, finished_at, status, error, findings, knowledge_added, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                run.run_id,
+                run.hand_name,
+                run.started_at.to_rfc3339(),
+                run.finished_at.map(|t| t.to_rfc3339()),
+                status,
+                error,
+                findings,
+                knowledge,
+                duration_ms,
+            ],
+        )
+        .context("Failed to insert hand run")?;
+
+        for fact in &run.knowledge_added {
+            tx.execute(
+                "INSERT OR IGNORE INTO hand_knowledge (hand_name, fact, run_id, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![run.hand_name, fact, run.run_id, run.started_at.to_rfc3339()],
+            )
+            .context("Failed to insert hand knowledge")?;
+        }
+
+        tx.execute(
+            "INSERT INTO hand_state (hand_name, total_runs) VALUES (?1, 0)
+             ON CONFLICT(hand_name) DO NOTHING",
+            params![run.hand_name],
+        )?;
+        if completed {
+            tx.execute(
+                "UPDATE hand_state
+                 SET total_runs = total_runs + 1, last_run = ?2
+                 WHERE hand_name = ?1",
+                params![run.hand_name, run.finished_at.map(|t| t.to_rfc3339())],
+            )?;
+        }
+
+        let keep = i64::try_from(max_history.max(1)).context("Hand history limit overflow")?;
+        tx.execute(
+            "DELETE FROM hand_runs
+             WHERE hand_name = ?1
+               AND run_id NOT IN (
+                 SELECT run_id FROM hand_runs
+                 WHERE hand_name = ?1
+                 ORDER BY started_at DESC, rowid DESC
+                 LIMIT ?2
+               )",
+            params![run.hand_name, keep],
+        )
+        .context("Failed to prune hand run history")?;
+
+        let keep_facts =
+            i64::try_from(config.hands.max_facts).context("Hand fact limit overflow")?;
+        tx.execute(
+            "DELETE FROM hand_knowledge
+             WHERE hand_name = ?1
+               AND id NOT IN (
+                 SELECT id FROM hand_knowledge
+                 WHERE hand_name = ?1
+                 ORDER BY id DESC
+                 LIMIT ?2
+               )",
+            params![run.hand_name, keep_facts],
+        )
+        .context("Failed to prune hand knowledge")?;
+
+        tx.commit().context("Failed to commit hand run")?;
+        Ok(())
+    })
+}
+
+/// List the most recent runs of a hand, newest first.
+pub fn list_runs(config: &Config, hand_name: &str, limit: usize) -> Result<Vec<HandRun>> {
+    with_connection(config, |conn| list_runs_on(conn, hand_name, limit))
+}
+
+fn list_runs_on(conn: &Connection, hand_name: &str, limit: usize) -> Result<Vec<HandRun>> {
+    let lim = i64::try_from(limit.max(1)).context("Hand history limit overflow")?;
+    let mut stmt = conn.prepare(
+        "SELECT run_id, hand_name, started_at, finished_at, status, error,
+                findings, knowledge_added, duration_ms
+         FROM hand_runs
+         WHERE hand_name = ?1
+         ORDER BY started_at DESC, rowid DESC
+         LIMIT ?2",
+    )?;
+
+    let rows = stmt.query_map(params![hand_name, lim], |row| {
+        let status: String = row.get(4)?;
+        let error: Option<String> = row.get(5)?;
+        let findings: String = row.get(6)?;
+        let knowledge: String = row.get(7)?;
+        let duration_ms: Option<i64> = row.get(8)?;
+        Ok(HandRun {
+            run_id: row.get(0)?,
+            hand_name: row.get(1)?,
+            started_at: parse_rfc3339(&row.get::<_, String>(2)?).map_err(sql_conversion_error)?,
+            finished_at: row
+                .get::<_, Option<String>>(3)?
+                .as_deref()
+                .map(parse_rfc3339)
+                .transpose()
+                .map_err(sql_conversion_error)?,
+            status: match status.as_str() {
+                "running" => HandRunStatus::Running,
+                "completed" => HandRunStatus::Completed,
+                _ => HandRunStatus::Failed {
+                    error: error.unwrap_or_default(),
+                },
+            },
+            findings: serde_json::from_str(&findings).unwrap_or_default(),
+            knowledge_added: serde_json::from_str(&knowledge).unwrap_or_default(),
+            duration_ms: duration_ms.and_then(|ms| u64::try_from(ms).ok()),
+        })
+    })?;
+
+    let mut runs = Vec::new();
+    for row in rows {
+        runs.push(row?);
+    }
+    Ok(runs)
+}
+
+/// Rebuild the rolling context of a hand from the store.
+pub fn load_context(config: &Config, hand: &Hand) -> Result<HandContext> {
+    with_connection(config, |conn| {
+        let mut context = HandContext::new(&hand.name);
+        context.history = list_runs_on(conn, &hand.name, hand.max_history)?;
+
+        let mut stmt =
+            conn.prepare("SELECT fact FROM hand_knowledge WHERE hand_name = ?1 ORDER BY id ASC")?;
+        let facts = stmt.query_map(params![hand.name], |row| row.get::<_, String>(0))?;
+        for fact in facts {
+            context.learned_facts.push(fact?);
+        }
+
+        if let Some(state) = get_state_on(conn, &hand.name)? {
+            context.last_run = state.last_run;
+            context.total_runs = state.total_runs;
+        }
+        Ok(context)
+    })
+}
+
+/// Fetch the scheduling state of a hand, if it has ever been scheduled or run.
+pub fn get_state(config: &Config, hand_name: &str) -> Result<Option<HandState>> {
+    with_connection(config, |conn| get_state_on(conn, hand_name))
+}
+
+fn get_state_on(conn: &Connection, hand_name: &str) -> Result<Option<HandState>> {
+    let row = conn
+        .query_row(
+            "SELECT hand_name, next_run, last_run, total_runs FROM hand_state WHERE hand_name = ?1",
+            params![hand_name],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            },
+        )
+        .optional()?;
+
+    row.map(|(hand_name, next_run, last_run, total_runs)| {
+        Ok(HandState {
+            hand_name,
+            next_run: next_run.as_deref().map(parse_rfc3339).transpose()?,
+            last_run: last_run.as_deref().map(parse_rfc3339).transpose()?,
+            total_runs: u64::try_from(total_runs).unwrap_or_default(),
+        })
+    })
+    .transpose()
+}
+
+/// Return the active hands whose next run is due at `now`.
+///
+/// Hands seen for the first time, or whose schedule changed since they were
+/// last planned, are scheduled from `now` instead of firing immediately.
+pub fn due_hands(config: &Config, hands: &[Hand], now: DateTime<Utc>) -> Result<Vec<Hand>> {
+    with_connection(config, |conn| {
+        let mut due = Vec::new();
+        for hand in hands.iter().filter(|hand| hand.active) {
+            let schedule = serde_json::to_string(&hand.schedule)?;
+            let planned: Option<(Option<String>, Option<String>)> = conn
+                .query_row(
+                    "SELECT schedule, next_run FROM hand_state WHERE hand_name = ?1",
+                    params![hand.name],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+
+            let next_run = match planned {
+                Some((Some(previous), next_run)) if previous == schedule => {
+                    next_run.as_deref().map(parse_rfc3339).transpose()?
+                }
+                _ => {
+                    let next_run = match next_run_for_schedule(&hand.schedule, now) {
+                        Ok(next_run) => next_run,
+                        Err(e) => {
+                            tracing::warn!(hand = %hand.name, "Skipping hand with invalid schedule: {e}");
+                            continue;
+                        }
+                    };
+                    set_next_run_on(conn, &hand.name, &schedule, Some(next_run))?;
+                    Some(next_run)
+                }
+            };
+
+            if next_run.is_some_and(|next_run| next_run <= now) {
+                due.push(hand.clone());
+            }
+        }
+        Ok(due)
+    })
+}
+
+/// Plan the next run of a hand after a scheduled run finished.
+///
+/// One-shot (`at`) hands are not rescheduled.
+pub fn reschedule_after_run(
+    config: &Config,
+    hand: &Hand,
+    finished_at: DateTime<Utc>,
+) -> Result<()> {
+    let schedule = serde_json::to_string(&hand.schedule)?;
+    let next_run = match hand.schedule {
+        Schedule::At { .. } => None,
+        _ => Some(next_run_for_schedule(&hand.schedule, finished_at)?),
+    };
+    with_connection(config, |conn| {
+        set_next_run_on(conn, &hand.name, &schedule, next_run)
+    })
+}
+
+fn set_next_run_on(
+    conn: &Connection,
+    hand_name: &str,
+    schedule: &str,
+    next_run: Option<DateTime<Utc>>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO hand_state (hand_name, schedule, next_run, total_runs) VALUES (?1, ?2, ?3, 0)
+         ON CONFLICT(hand_name) DO UPDATE SET schedule = excluded.schedule, next_run = excluded.next_run",
+        params![hand_name, schedule, next_run.map(|t| t.to_rfc3339())],
+    )
+    .context("Failed to update hand schedule")?;
+    Ok(())
+}
+
+fn parse_rfc3339(raw: &str) -> Result<DateTime<Utc>> {
+    let parsed = DateTime::parse_from_rfc3339(raw)
+        .with_context(|| format!("Invalid RFC3339 timestamp in hands DB: {raw}"))?;
+    Ok(parsed.with_timezone(&Utc))
+}
+
+fn sql_conversion_error(err: anyhow::Error) -> rusqlite::Error {
+    rusqlite::Error::ToSqlConversionFailure(err.into())
+}
+
+fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = config.workspace_dir.join("hands").join("hands.db");
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create hands directory: {}", parent.display()))?;
+    }
+
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("Failed to open hands DB: {}", db_path.display()))?;
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS hand_runs (
+            run_id          TEXT PRIMARY KEY,
+            hand_name       TEXT NOT NULL,
+            started_at      TEXT NOT NULL,
+            finished_at     TEXT,
+            status          TEXT NOT NULL,
+            error           TEXT,
+            findings        TEXT NOT NULL DEFAULT '[]',
+            knowledge_added TEXT NOT NULL DEFAULT '[]',
+            duration_ms     INTEGER
+        );
+        CREATE INDEX IF NOT EXISTS idx_hand_runs_hand_started ON hand_runs(hand_name, started_at);
+
+        CREATE TABLE IF NOT EXISTS hand_knowledge (
+            id         INTEGER PRIMARY KEY AUTOINCREMENT,
+            hand_name  TEXT NOT NULL,
+            fact       TEXT NOT NULL,
+            run_id     TEXT,
+            created_at TEXT NOT NULL,
+            UNIQUE (hand_name, fact)
+        );
+
+        CREATE TABLE IF NOT EXISTS hand_state (
+            hand_name  TEXT PRIMARY KEY,
+            schedule   TEXT,
+            next_run   TEXT,
+            last_run   TEXT,
+            total_runs INTEGER NOT NULL DEFAULT 0
+        );",
+    )
+    .context("Failed to initialize hands schema")?;
+
+    f(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        config
+    }
+
+    fn hand(name: &str, schedule: Schedule) -> Hand {
+        Hand {
+            name: name.into(),
+            description: "test hand".into(),
+            schedule,
+            prompt: "Do the thing.".into(),
+            knowledge: vec![],
+            allowed_tools: None,
+            model: None,
+            active: true,
+            max_history: 3,
+        }
+    }
+
+    fn run(name: &str, id: &str, status: HandRunStatus, fact: &str) -> HandRun {
+        let now = Utc::now();
+        HandRun {
+            hand_name: name.into(),
+            run_id: id.into(),
+            started_at: now,
+            finished_at: Some(now),
+            status,
+            findings: vec![format!("finding from {id}")],
+            knowledge_added: vec![fact.into()],
+            duration_ms: Some(42),
+        }
+    }
+
+    #[test]
+    fn record_run_builds_rolling_context() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let scanner = hand(
+            "scanner",
+            Schedule::Every {
+                every_ms: 60_000,
+                jitter_ms: 0,
+            },
+        );
+
+        for i in 0..5 {
+            let status = if i == 4 {
+                HandRunStatus::Failed {
+                    error: "boom".into(),
+                }
+            } else {
+                HandRunStatus::Completed
+            };
+            let fact = if i % 2 == 0 { "fact-even" } else { "fact-odd" };
+            record_run(
+                &config,
+                &run("scanner", &format!("run-{i}"), status, fact),
+                3,
+            )
+            .unwrap();
+        }
+
+        let ctx = load_context(&config, &scanner).unwrap();
+        assert_eq!(ctx.total_runs, 4, "failed runs are not counted");
+        assert_eq!(ctx.history.len(), 3, "history pruned to max_history");
+        assert_eq!(ctx.history[0].run_id, "run-4");
+        assert_eq!(
+            ctx.history[0].status,
+            HandRunStatus::Failed {
+                error: "boom".into()
+            }
+        );
+        assert_eq!(ctx.history[0].findings, vec!["finding from run-4"]);
+        assert_eq!(ctx.learned_facts, vec!["fact-even", "fact-odd"]);
+        assert!(ctx.last_run.is_some());
+    }
+
+    #[test]
+    fn record_run_keeps_newest_facts_up_to_the_cap() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config(&tmp);
+        config.hands.max_facts = 2;
+        let scanner = hand(
+            "scanner",
+            Schedule::Every {
+                every_ms: 60_000,
+                jitter_ms: 0,
+            },
+        );
+
+        for i in 0..4 {
+            record_run(
+                &config,
+                &run(
+                    "scanner",
+                    &format!("run-{i}"),
+                    HandRunStatus::Completed,
+                    &format!("fact-{i}"),
+                ),
+                3,
+            )
+            .unwrap();
+        }
+
+        let ctx = load_context(&config, &scanner).unwrap();
+        assert_eq!(ctx.learned_facts, vec!["fact-2", "fact-3"]);
+    }
+
+    #[test]
+    fn due_hands_schedules_new_hands_in_the_future() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let now = Utc::now();
+        let mut paused = hand(
+            "paused",
+            Schedule::Every {
+                every_ms: 1,
+                jitter_ms: 0,
+            },
+        );
+        paused.active = false;
+        let hands = vec![
+            hand(
+                "digest",
+                Schedule::Every {
+                    every_ms: 60_000,
+                    jitter_ms: 0,
+                },
+            ),
+            paused,
+        ];
+
+        assert!(due_hands(&config, &hands, now).unwrap().is_empty());
+        let state = get_state(&config, "digest").unwrap().unwrap();
+        assert_eq!(state.next_run, Some(now + ChronoDuration::minutes(1)));
+        assert!(get_state(&config, "paused").unwrap().is_none());
+
+        let due = due_hands(&config, &hands, now + ChronoDuration::minutes(2)).unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].name, "digest");
+    }
+
+    #[test]
+    fn due_hands_replans_when_schedule_changes() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let now = Utc::now();
+        due_hands(
+            &config,
+            &[hand(
+                "digest",
+                Schedule::Every {
+                    every_ms: 60_000,
+                    jitter_ms: 0,
+                },
+            )],
+            now,
+        )
+        .unwrap();
+
+        let changed = hand(
+            "digest",
+            Schedule::Every {
+                every_ms: 3_600_000,
+                jitter_ms: 0,
+            },
+        );
+        let later = now + ChronoDuration::minutes(5);
+        assert!(due_hands(&config, &[changed], later).unwrap().is_empty());
+        let state = get_state(&config, "digest").unwrap().unwrap();
+        assert_eq!(state.next_run, Some(later + ChronoDuration::hours(1)));
+    }
+
+    #[test]
+    fn reschedule_after_run_retires_one_shot_hands() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let now = Utc::now();
+        let once = hand("once", Schedule::At { at: now });
+
+        let due = due_hands(&config, std::slice::from_ref(&once), now).unwrap();
+        assert_eq!(due.len(), 1, "at-schedule in the past fires once");
+
+        reschedule_after_run(&config, &once, now).unwrap();
+        assert_eq!(get_state(&config, "once").unwrap().unwrap().next_run, None);
+        assert!(due_hands(&config, &[once], now + ChronoDuration::days(1))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -84,7 +84,9 @@ pub struct HandRun {
 
 /// Rolling context that accumulates across hand runs.
 ///
-/// Persisted as `~/.zeroclaw/hands/{name}/context.json`.
+/// Rebuilt from the hands SQLite store before each run (see
+/// [`crate::hands::store::load_context`]); can also be saved as
+/// `~/.zeroclaw/hands/{name}/context.json`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HandContext {
     /// Name of the hand this context belongs to
@@ -221,7 +223,8 @@ every_ms = 3600000
         assert!(matches!(
             hand.schedule,
             Schedule::Every {
-                every_ms: 3_600_000
+                every_ms: 3_600_000,
+                ..
             }
         ));
     }
@@ -295,6 +295,121 @@ pub enum SopCommands {
         /// SOP name
         name: String,
     },
+    /// List journaled SOP runs, or show one run in detail
+    Runs {
+        /// Run ID to show in detail (lists recent runs if omitted)
+        run_id: Option<String>,
+        /// Only list runs of this SOP
+        #[arg(long)]
+        sop: Option<String>,
+        /// Only list runs that have not finished
+        #[arg(long)]
+        active: bool,
+        /// Maximum number of runs to list
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+/// Tool policy subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PolicyCommands {
+    /// Show which `[[autonomy.rules]]` entry decides a tool call, and why
+    Explain {
+        /// Tool name (e.g. file_write, http_request)
+        tool: String,
+        /// Tool arguments as a JSON object
+        #[arg(default_value = "{}")]
+        args: String,
+    },
+}
+
+/// Hands (scheduled autonomous agents) subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HandCommands {
+    /// List hand definitions with their schedule and run state
+    List,
+    /// Run a hand immediately, outside its schedule
+    Run {
+        /// Hand name
+        name: String,
+    },
+    /// Show recent runs of a hand with their findings
+    History {
+        /// Hand name
+        name: String,
+        /// Maximum number of runs to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+}
+
+/// Routines (event-triggered automation) subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RoutineCommands {
+    /// List routines defined in the workspace routines.toml
+    List,
+    /// Dry-run an event against the routines without executing any action
+    Test {
+        /// Event source: channel, webhook, cron or system
+        source: String,
+        /// Event topic (channel name, webhook path, cron job name, system signal)
+        topic: String,
+        /// Optional event payload
+        #[arg(long)]
+        payload: Option<String>,
+    },
+    /// Show recent fires of a routine
+    History {
+        /// Routine name
+        name: String,
+        /// Maximum number of fires to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+/// Node (remote tool host) subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NodeCommands {
+    /// Connect to a gateway as a headless node and serve invocations
+    Connect {
+        /// Gateway node endpoint (ws:// or wss://, e.g. wss://host/ws/nodes)
+        #[arg(long)]
+        gateway: Option<String>,
+        /// Node ID to register under (defaults to the hostname)
+        #[arg(long)]
+        node_id: Option<String>,
+        /// Bearer token for the gateway (defaults to [nodes] auth_token)
+        #[arg(long)]
+        token: Option<String>,
+        /// Comma-separated tools to expose: shell, file, screenshot, hardware
+        #[arg(long, value_delimiter = ',')]
+        tools: Option<Vec<String>>,
+    },
+    /// Show the capabilities this machine would advertise
+    Capabilities {
+        /// Comma-separated tools to expose: shell, file, screenshot, hardware
+        #[arg(long, value_delimiter = ',')]
+        tools: Option<Vec<String>>,
+    },
+}
+
+/// MCP server subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum McpCommands {
+    /// Serve the configured tool registry and memory over MCP (stdio)
+    #[command(long_about = "\
+Serve ZeroClaw's tools and memory as an MCP server over stdio.
+
+Exposes the configured tool registry, filtered by the [mcp_serve] tools \
+allowlist, autonomy.non_cli_excluded_tools and the approval policy, \
+plus memory entries and recall as MCP resources. Logs go to stderr.
+
+Examples:
+  zeroclaw mcp serve
+  claude mcp add zeroclaw -- zeroclaw mcp serve")]
+    Serve,
 }
 
 /// Migration subcommands
@@ -40,7 +40,7 @@ use serde::{Deserialize, Serialize};
 use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 use tracing::{info, warn};
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, fmt::writer::BoxMakeWriter, EnvFilter};
 
 fn parse_temperature(s: &str) -> std::result::Result<f64, String> {
     let t: f64 = s.parse().map_err(|e| format!("{e}"))?;
@@ -86,6 +86,7 @@ mod cron;
 mod daemon;
 mod doctor;
 mod gateway;
+mod hands;
 mod hardware;
 mod health;
 mod heartbeat;
@@ -96,12 +97,16 @@ mod integrations;
 mod memory;
 mod migration;
 mod multimodal;
+mod nodes;
 mod observability;
 mod onboard;
 mod peripherals;
 #[cfg(feature = "plugins-wasm")]
 mod plugins;
 mod providers;
+// The binary does not use every re-export of the library API.
+#[allow(unused_imports)]
+mod routines;
 mod runtime;
 mod security;
 mod service;
@@ -118,8 +123,9 @@ use config::Config;
 
 // Re-export so binary modules can use crate::<CommandEnum> while keeping a single source of truth.
 pub use zeroclaw::{
-    ChannelCommands, CronCommands, GatewayCommands, HardwareCommands, IntegrationCommands,
-    MigrateCommands, PeripheralCommands, ServiceCommands, SkillCommands, SopCommands,
+    ChannelCommands, CronCommands, GatewayCommands, HandCommands, HardwareCommands,
+    IntegrationCommands, McpCommands, MigrateCommands, NodeCommands, PeripheralCommands,
+    PolicyCommands, RoutineCommands, ServiceCommands, SkillCommands, SopCommands,
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
@@ -251,7 +257,7 @@ Examples:
         gateway_command: Option<zeroclaw::GatewayCommands>,
     },
 
-    /// Start ACP (Agent Control Protocol) server over stdio
+    /// Start ACP (Agent Client Protocol) server over stdio
     #[command(long_about = "\
 Start the ACP server (JSON-RPC 2.0 over stdio).
 
@@ -274,6 +280,21 @@ Examples:
         session_timeout: Option<u64>,
     },
 
+    /// Run ZeroClaw as an MCP (Model Context Protocol) server
+    #[command(long_about = "\
+Run ZeroClaw as an MCP server.
+
+Lets editors and other agents reuse ZeroClaw's sandboxed tools and \
+memory. 'serve' speaks MCP over stdio; set [mcp_serve] gateway = true \
+to also expose streamable HTTP at POST /mcp on the gateway.
+
+Examples:
+  zeroclaw mcp serve                  # MCP over stdin/stdout")]
+    Mcp {
+        #[command(subcommand)]
+        mcp_command: McpCommands,
+    },
+
     /// Start long-running autonomous runtime (gateway + channels + heartbeat + scheduler)
     #[command(long_about = "\
 Start the long-running autonomous daemon.
@@ -466,12 +487,85 @@ Examples:
         peripheral_command: zeroclaw::PeripheralCommands,
     },
 
+    /// Inspect argument-aware tool rules (`[[autonomy.rules]]`)
+    #[command(long_about = "\
+Inspect argument-aware tool rules.
+
+Rules under [[autonomy.rules]] match a tool name plus argument predicates \
+(path globs, URL hosts, recipient domains, git subcommands) and allow, ask \
+or deny the call. `explain` walks the rules for one call and prints the \
+rule that decides it.
+
+Examples:
+  zeroclaw policy explain file_write '{\"path\": \".env\"}'
+  zeroclaw policy explain http_request '{\"url\": \"http://db.internal/\"}'
+  zeroclaw policy explain git_operations '{\"operation\": \"commit\"}'")]
+    Policy {
+        #[command(subcommand)]
+        policy_command: zeroclaw::PolicyCommands,
+    },
+
     /// Manage SOPs (standard operating procedures)
     Sop {
         #[command(subcommand)]
         sop_command: zeroclaw::SopCommands,
     },
 
+    /// Manage hands (scheduled autonomous agents)
+    #[command(long_about = "\
+Manage hands: autonomous agents defined in ~/.zeroclaw/hands/*.toml.
+
+The daemon runs active hands on their schedule alongside cron jobs. \
+Each run goes through the agent with the hand's allowed tools and model, \
+and its findings and learned facts are kept for future runs.
+
+Examples:
+  zeroclaw hands list
+  zeroclaw hands run market-scanner
+  zeroclaw hands history market-scanner --limit 5")]
+    Hands {
+        #[command(subcommand)]
+        hand_command: HandCommands,
+    },
+
+    /// Manage routines (event-triggered automation)
+    #[command(long_about = "\
+Manage routines: event-triggered automation rules in <workspace>/routines.toml.
+
+The daemon publishes channel messages, /webhook payloads, finished cron \
+jobs and system signals (estop.engaged, estop.resumed, provider.fallback, \
+health.<component>.<status>) into the routines engine and reloads \
+routines.toml when it changes.
+
+Examples:
+  zeroclaw routines list
+  zeroclaw routines test webhook /webhook --payload 'deploy finished'
+  zeroclaw routines test system estop.engaged
+  zeroclaw routines history deploy-notify")]
+    Routines {
+        #[command(subcommand)]
+        routine_command: RoutineCommands,
+    },
+
+    /// Run this machine as a remote node of a gateway
+    #[command(long_about = "\
+Run this machine as a headless node of a central gateway.
+
+`node connect` registers with the gateway's /ws/nodes endpoint and \
+advertises a subset of the local tools (shell, file, screenshot, \
+hardware). Invocations run under this machine's own [autonomy] policy. \
+The connection is kept alive with heartbeats and re-established with \
+backoff when it drops. Defaults come from [nodes.client].
+
+Examples:
+  zeroclaw node connect --gateway wss://hq.example.com/ws/nodes
+  zeroclaw node connect --gateway ws://10.0.0.5:42617/ws/nodes --tools shell,hardware
+  zeroclaw node capabilities --tools shell,file")]
+    Node {
+        #[command(subcommand)]
+        node_command: NodeCommands,
+    },
+
     /// Manage agent memory (list, get, stats, clear)
     #[command(long_about = "\
 Manage agent memory entries.
@@ -853,13 +947,33 @@ async fn main() -> Result<()> {
         return Ok(());
     }
 
+    // `mcp serve` speaks JSON-RPC on stdout, so its logs must go to stderr.
+    let log_writer = if matches!(
+        &cli.command,
+        Commands::Mcp {
+            mcp_command: McpCommands::Serve
+        }
+    ) {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+
     // Initialize logging - respects RUST_LOG env var, defaults to INFO
     let subscriber = fmt::Subscriber::builder()
         .with_env_filter(
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
         )
+        .with_writer(log_writer)
         .finish();
 
+    // Forwards logs to OTLP once an OTel observer is configured.
+    #[cfg(feature = "observability-otel")]
+    let subscriber = tracing_subscriber::layer::SubscriberExt::with(
+        subscriber,
+        observability::otel::OtelLogLayer,
+    );
+
     tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
 
     // Onboard auto-detects the environment: if stdin/stdout are a TTY and no
@@ -1052,6 +1166,13 @@ async fn main() -> Result<()> {
             server.run().await
         }
 
+        Commands::Mcp { mcp_command } => match mcp_command {
+            McpCommands::Serve => {
+                let server = tools::McpServer::from_config(&config)?;
+                server.serve_stdio().await
+            }
+        },
+
         Commands::Gateway { gateway_command } => {
             match gateway_command {
                 Some(zeroclaw::GatewayCommands::Restart { port, host }) => {
@@ -1290,6 +1411,27 @@ async fn main() -> Result<()> {
             }
             println!("  OTP enabled:       {}", config.security.otp.enabled);
             println!("  E-stop enabled:    {}", config.security.estop.enabled);
+            println!(
+                "  Adaptive trust:    {}",
+                if config.trust.enabled {
+                    "enabled"
+                } else {
+                    "disabled"
+                }
+            );
+            for score in trust::runtime::current_scores(&config) {
+                let regressed = score.score < config.trust.regression_threshold;
+                println!(
+                    "    {:16} {:.2}{}",
+                    score.domain,
+                    score.score,
+                    if regressed && config.trust.enabled {
+                        "  ⚠ autonomy reduced"
+                    } else {
+                        ""
+                    }
+                );
+            }
             println!();
             println!("Channels:");
             println!("  CLI:      ✅ always");
@@ -1425,8 +1567,24 @@ async fn main() -> Result<()> {
 
         Commands::Skills { skill_command } => skills::handle_command(skill_command, &config),
 
+        Commands::Policy { policy_command } => {
+            security::tool_rules::handle_command(policy_command, &config)
+        }
+
         Commands::Sop { sop_command } => sop::handle_command(sop_command, &config),
 
+        Commands::Hands { hand_command } => {
+            Box::pin(hands::handle_command(hand_command, &config)).await
+        }
+
+        Commands::Routines { routine_command } => {
+            routines::handle_command(routine_command, &config)
+        }
+
+        Commands::Node { node_command } => {
+            Box::pin(nodes::handle_command(node_command, &config)).await
+        }
+
         Commands::Migrate { migrate_command } => {
             migration::handle_command(migrate_command, &config).await
         }
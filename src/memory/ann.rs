@@ -0,0 +1,668 @@
+//! Approximate-nearest-neighbour index for SQLite memory embeddings.
+//!
+//! Inverted-file (IVF) index: embeddings are clustered with spherical k-means
+//! and each memory row is assigned to its nearest centroid. A query only scans
+//! the rows in the `nprobe` clusters closest to the query vector instead of
+//! every embedding blob in the table.
+//!
+//! The index lives inside brain.db so every process sharing the database sees
+//! the same state:
+//! - `ann_centroids` — one normalized centroid per cluster
+//! - `ann_assignments` — memory id → cluster (kept in sync by `store`, a
+//!   delete trigger on `memories`, and `rebuild`)
+//! - `ann_meta` — generation counter bumped on every rebuild, so processes
+//!   holding stale centroids reload them before searching, the corpus size
+//!   the centroids were trained on, and the current number of embedded
+//!   memories (kept by triggers on `memories`)
+//!
+//! Rows stored after training are assigned to the existing centroids. Once
+//! the corpus reaches [`ANN_RETRAIN_GROWTH`] times its trained size the index
+//! is retrained in the background so the clusters keep tracking the data.
+
+use super::vector;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::fmt::Write as _;
+
+/// Minimum number of embedded memories before an index is trained automatically.
+pub const ANN_MIN_ENTRIES: usize = 2048;
+/// Number of clusters scanned per query.
+pub const ANN_DEFAULT_NPROBE: usize = 8;
+/// Retrain once the embedded corpus is this many times the trained size.
+pub const ANN_RETRAIN_GROWTH: usize = 2;
+/// Upper bound on vectors sampled for k-means training.
+const ANN_TRAIN_SAMPLE: usize = 8192;
+/// k-means refinement passes.
+const ANN_TRAIN_ITERATIONS: usize = 8;
+const ANN_MIN_CLUSTERS: usize = 16;
+const ANN_MAX_CLUSTERS: usize = 1024;
+
+/// Trained IVF index: cluster centroids plus the generation they belong to.
+#[derive(Debug, Clone)]
+pub struct IvfIndex {
+    generation: i64,
+    centroids: Vec<Vec<f32>>,
+    /// Number of embedded memories when the centroids were trained.
+    trained_on: usize,
+}
+
+fn normalize(v: &[f32]) -> Option<Vec<f32>> {
+    let norm = v
+        .iter()
+        .map(|x| f64::from(*x) * f64::from(*x))
+        .sum::<f64>()
+        .sqrt();
+    if !norm.is_finite() || norm < f64::EPSILON {
+        return None;
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    Some(v.iter().map(|x| (f64::from(*x) / norm) as f32).collect())
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Cluster count for a corpus of `n` vectors: √n, clamped.
+fn cluster_count(n: usize) -> usize {
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    let root = (n as f64).sqrt() as usize;
+    root.clamp(ANN_MIN_CLUSTERS, ANN_MAX_CLUSTERS).min(n.max(1))
+}
+
+impl IvfIndex {
+    /// Train centroids with spherical k-means over (a sample of) `vectors`.
+    ///
+    /// Returns `None` when there are no usable (non-zero, equal-length) vectors.
+    pub fn train(vectors: &[Vec<f32>]) -> Option<Self> {
+        let dims = vectors.first()?.len();
+        let step = (vectors.len() / ANN_TRAIN_SAMPLE).max(1);
+        let sample: Vec<Vec<f32>> = vectors
+            .iter()
+            .step_by(step)
+            .filter(|v| v.len() == dims)
+            .filter_map(|v| normalize(v))
+            .collect();
+        if sample.is_empty() {
+            return None;
+        }
+
+        let k = cluster_count(sample.len());
+        // Deterministic init: evenly spaced samples.
+        let mut centroids: Vec<Vec<f32>> = (0..k)
+            .map(|i| sample[i * sample.len() / k].clone())
+            .collect();
+
+        let mut assignment = vec![0usize; sample.len()];
+        for _ in 0..ANN_TRAIN_ITERATIONS {
+            for (slot, v) in assignment.iter_mut().zip(&sample) {
+                *slot = Self::nearest(&centroids, v);
+            }
+
+            let mut sums = vec![vec![0.0_f32; dims]; k];
+            let mut counts = vec![0usize; k];
+            for (cluster, v) in assignment.iter().zip(&sample) {
+                counts[*cluster] += 1;
+                for (acc, x) in sums[*cluster].iter_mut().zip(v) {
+                    *acc += x;
+                }
+            }
+            for (cluster, sum) in sums.into_iter().enumerate() {
+                // Empty clusters keep their previous centroid.
+                if counts[cluster] > 0 {
+                    if let Some(c) = normalize(&sum) {
+                        centroids[cluster] = c;
+                    }
+                }
+            }
+        }
+
+        Some(Self {
+            generation: 0,
+            centroids,
+            trained_on: vectors.len(),
+        })
+    }
+
+    fn nearest(centroids: &[Vec<f32>], v: &[f32]) -> usize {
+        let mut best = 0;
+        let mut best_score = f32::NEG_INFINITY;
+        for (i, c) in centroids.iter().enumerate() {
+            let score = dot(c, v);
+            if score > best_score {
+                best = i;
+                best_score = score;
+            }
+        }
+        best
+    }
+
+    /// Number of clusters.
+    pub fn len(&self) -> usize {
+        self.centroids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.centroids.is_empty()
+    }
+
+    pub fn generation(&self) -> i64 {
+        self.generation
+    }
+
+    /// Whether a corpus of `embedded` vectors has outgrown these centroids.
+    pub fn needs_retrain(&self, embedded: usize) -> bool {
+        embedded >= self.trained_on.max(1).saturating_mul(ANN_RETRAIN_GROWTH)
+    }
+
+    /// Cluster for an embedding, or `None` for zero / mismatched vectors.
+    pub fn assign(&self, embedding: &[f32]) -> Option<usize> {
+        if self.centroids.first()?.len() != embedding.len() {
+            return None;
+        }
+        let v = normalize(embedding)?;
+        Some(Self::nearest(&self.centroids, &v))
+    }
+
+    /// The `nprobe` clusters closest to `query`, best first.
+    pub fn probe(&self, query: &[f32], nprobe: usize) -> Vec<usize> {
+        let Some(q) = normalize(query) else {
+            return Vec::new();
+        };
+        if self.centroids.first().map(Vec::len) != Some(q.len()) {
+            return Vec::new();
+        }
+        let mut scored: Vec<(usize, f32)> = self
+            .centroids
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i, dot(c, &q)))
+            .collect();
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored
+            .into_iter()
+            .take(nprobe.max(1))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // ── Persistence ─────────────────────────────────────────────
+
+    /// Create index tables and the delete trigger that keeps assignments in sync.
+    pub fn init_schema(conn: &Connection) -> anyhow::Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS ann_meta (
+                key   TEXT PRIMARY KEY,
+                value INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS ann_centroids (
+                cluster  INTEGER PRIMARY KEY,
+                centroid BLOB NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS ann_assignments (
+                id      TEXT PRIMARY KEY,
+                cluster INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_ann_assignments_cluster ON ann_assignments(cluster);
+            CREATE TRIGGER IF NOT EXISTS memories_ann_ad AFTER DELETE ON memories BEGIN
+                DELETE FROM ann_assignments WHERE id = old.id;
+            END;",
+        )?;
+
+        // Seed the embedded-row counter once, together with the triggers that
+        // keep it current, so stores never have to count the table.
+        let seeded = conn
+            .query_row("SELECT 1 FROM ann_meta WHERE key = 'embedded'", [], |_| {
+                Ok(())
+            })
+            .optional()?
+            .is_some();
+        if !seeded {
+            conn.execute(
+                "INSERT INTO ann_meta (key, value)
+                 SELECT 'embedded', COUNT(*) FROM memories WHERE embedding IS NOT NULL",
+                [],
+            )?;
+        }
+        conn.execute_batch(
+            "CREATE TRIGGER IF NOT EXISTS memories_ann_count_ai AFTER INSERT ON memories
+             WHEN new.embedding IS NOT NULL BEGIN
+                UPDATE ann_meta SET value = value + 1 WHERE key = 'embedded';
+            END;
+            CREATE TRIGGER IF NOT EXISTS memories_ann_count_ad AFTER DELETE ON memories
+             WHEN old.embedding IS NOT NULL BEGIN
+                UPDATE ann_meta SET value = value - 1 WHERE key = 'embedded';
+            END;
+            CREATE TRIGGER IF NOT EXISTS memories_ann_count_au AFTER UPDATE OF embedding ON memories
+             WHEN (old.embedding IS NULL) != (new.embedding IS NULL) BEGIN
+                UPDATE ann_meta
+                SET value = value + (CASE WHEN new.embedding IS NULL THEN -1 ELSE 1 END)
+                WHERE key = 'embedded';
+            END;",
+        )?;
+        Ok(())
+    }
+
+    /// Number of memories with an embedding, from the trigger-maintained counter.
+    pub fn embedded_count(conn: &Connection) -> anyhow::Result<usize> {
+        let count: i64 = conn
+            .query_row(
+                "SELECT value FROM ann_meta WHERE key = 'embedded'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0);
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Ok(count.max(0) as usize)
+    }
+
+    /// Whether the index in `slot` should be trained (or retrained) now.
+    pub fn train_due(conn: &Connection, slot: Option<&Self>) -> anyhow::Result<bool> {
+        let embedded = Self::embedded_count(conn)?;
+        Ok(match slot {
+            Some(index) => index.needs_retrain(embedded),
+            None => embedded >= ANN_MIN_ENTRIES,
+        })
+    }
+
+    /// Current index generation stored in the database (0 = never built).
+    pub fn stored_generation(conn: &Connection) -> anyhow::Result<i64> {
+        Ok(conn
+            .query_row(
+                "SELECT value FROM ann_meta WHERE key = 'generation'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0))
+    }
+
+    /// Load the persisted index, if one has been built.
+    pub fn load(conn: &Connection) -> anyhow::Result<Option<Self>> {
+        let generation = Self::stored_generation(conn)?;
+        if generation == 0 {
+            return Ok(None);
+        }
+        let mut stmt = conn.prepare("SELECT centroid FROM ann_centroids ORDER BY cluster")?;
+        let centroids = stmt
+            .query_map([], |row| row.get::<_, Vec<u8>>(0))?
+            .map(|blob| blob.map(|b| vector::bytes_to_vec(&b)))
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if centroids.is_empty() {
+            return Ok(None);
+        }
+        // Indexes built before the trained size was recorded fall back to
+        // the number of assigned rows.
+        let trained_on: i64 = match conn
+            .query_row(
+                "SELECT value FROM ann_meta WHERE key = 'trained_on'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+        {
+            Some(n) => n,
+            None => conn.query_row("SELECT COUNT(*) FROM ann_assignments", [], |row| row.get(0))?,
+        };
+        Ok(Some(Self {
+            generation,
+            centroids,
+            #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+            trained_on: trained_on as usize,
+        }))
+    }
+
+    /// Reload `slot` if another connection rebuilt the index since it was loaded.
+    pub fn refresh(conn: &Connection, slot: &mut Option<Self>) -> anyhow::Result<()> {
+        let stored = Self::stored_generation(conn)?;
+        let loaded = slot.as_ref().map_or(0, Self::generation);
+        if stored != loaded {
+            *slot = Self::load(conn)?;
+        }
+        Ok(())
+    }
+
+    /// Record (or replace) the cluster of the memory stored under `key`.
+    pub fn assign_key(
+        &self,
+        conn: &Connection,
+        key: &str,
+        embedding: &[f32],
+    ) -> anyhow::Result<()> {
+        if let Some(cluster) = self.assign(embedding) {
+            #[allow(clippy::cast_possible_wrap)]
+            conn.execute(
+                "INSERT OR REPLACE INTO ann_assignments (id, cluster)
+                 SELECT id, ?2 FROM memories WHERE key = ?1",
+                params![key, cluster as i64],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Retrain centroids from every stored embedding and reassign all rows.
+    ///
+    /// Runs in a single transaction; on failure the previous index is kept.
+    pub fn rebuild(conn: &Connection) -> anyhow::Result<Option<Self>> {
+        let rows: Vec<(String, Vec<f32>)> = {
+            let mut stmt =
+                conn.prepare("SELECT id, embedding FROM memories WHERE embedding IS NOT NULL")?;
+            let mapped = stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    vector::bytes_to_vec(&row.get::<_, Vec<u8>>(1)?),
+                ))
+            })?;
+            mapped.collect::<rusqlite::Result<_>>()?
+        };
+
+        let vectors: Vec<Vec<f32>> = rows.iter().map(|(_, v)| v.clone()).collect();
+        let Some(mut index) = Self::train(&vectors) else {
+            return Ok(None);
+        };
+        index.generation = Self::stored_generation(conn)? + 1;
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch("DELETE FROM ann_centroids; DELETE FROM ann_assignments;")?;
+        {
+            let mut insert_centroid =
+                tx.prepare("INSERT INTO ann_centroids (cluster, centroid) VALUES (?1, ?2)")?;
+            for (cluster, centroid) in index.centroids.iter().enumerate() {
+                #[allow(clippy::cast_possible_wrap)]
+                insert_centroid.execute(params![cluster as i64, vector::vec_to_bytes(centroid)])?;
+            }
+            let mut insert_assignment =
+                tx.prepare("INSERT INTO ann_assignments (id, cluster) VALUES (?1, ?2)")?;
+            for (id, embedding) in &rows {
+                if let Some(cluster) = index.assign(embedding) {
+                    #[allow(clippy::cast_possible_wrap)]
+                    insert_assignment.execute(params![id, cluster as i64])?;
+                }
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO ann_meta (key, value) VALUES ('generation', ?1)",
+            params![index.generation],
+        )?;
+        #[allow(clippy::cast_possible_wrap)]
+        tx.execute(
+            "INSERT OR REPLACE INTO ann_meta (key, value) VALUES ('trained_on', ?1)",
+            params![index.trained_on as i64],
+        )?;
+        tx.commit()?;
+
+        Ok(Some(index))
+    }
+
+    /// Cosine search restricted to the `nprobe` clusters nearest `query`.
+    ///
+    /// Same filters and result shape as [`SqliteMemory::vector_search`](super::SqliteMemory::vector_search).
+    pub fn search(
+        &self,
+        conn: &Connection,
+        query: &[f32],
+        limit: usize,
+        nprobe: usize,
+        category: Option<&str>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let clusters = self.probe(query, nprobe);
+        if clusters.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut sql = "SELECT m.id, m.embedding FROM ann_assignments a
+                       JOIN memories m ON m.id = a.id
+                       WHERE m.embedding IS NOT NULL AND a.cluster IN ("
+            .to_string();
+        let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+        for (i, cluster) in clusters.iter().enumerate() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+            let _ = write!(sql, "?{}", i + 1);
+            #[allow(clippy::cast_possible_wrap)]
+            param_values.push(Box::new(*cluster as i64));
+        }
+        sql.push(')');
+        let mut idx = clusters.len() + 1;
+
+        if let Some(cat) = category {
+            let _ = write!(sql, " AND m.category = ?{idx}");
+            param_values.push(Box::new(cat.to_string()));
+            idx += 1;
+        }
+        if let Some(sid) = session_id {
+            let _ = write!(sql, " AND m.session_id = ?{idx}");
+            param_values.push(Box::new(sid.to_string()));
+        }
+
+        let mut stmt = conn.prepare(&sql)?;
+        let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+            param_values.iter().map(AsRef::as_ref).collect();
+        let rows = stmt.query_map(params_ref.as_slice(), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+
+        let mut scored: Vec<(String, f32)> = Vec::new();
+        for row in rows {
+            let (id, blob) = row?;
+            let sim = vector::cosine_similarity(query, &vector::bytes_to_vec(&blob));
+            if sim > 0.0 {
+                scored.push((id, sim));
+            }
+        }
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(limit);
+        Ok(scored)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+
+    /// Points scattered around `n` well-separated axis directions.
+    fn clustered_vectors(groups: usize, per_group: usize, dims: usize) -> Vec<Vec<f32>> {
+        let mut out = Vec::new();
+        for g in 0..groups {
+            for j in 0..per_group {
+                let mut v = vec![0.01_f32; dims];
+                v[g % dims] = 1.0;
+                v[(g + j + 1) % dims] += 0.05 * (j % 3) as f32;
+                out.push(v);
+            }
+        }
+        out
+    }
+
+    fn memory_conn() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE memories (
+                id TEXT PRIMARY KEY, key TEXT NOT NULL UNIQUE, content TEXT NOT NULL,
+                category TEXT NOT NULL DEFAULT 'core', embedding BLOB, session_id TEXT
+            );",
+        )
+        .unwrap();
+        IvfIndex::init_schema(&conn).unwrap();
+        conn
+    }
+
+    fn insert(conn: &Connection, id: &str, embedding: &[f32]) {
+        conn.execute(
+            "INSERT INTO memories (id, key, content, embedding) VALUES (?1, ?1, ?1, ?2)",
+            params![id, vector::vec_to_bytes(embedding)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn train_empty_returns_none() {
+        assert!(IvfIndex::train(&[]).is_none());
+        assert!(IvfIndex::train(&[vec![0.0, 0.0]]).is_none());
+    }
+
+    #[test]
+    fn cluster_count_is_clamped() {
+        assert_eq!(cluster_count(4), 4);
+        assert_eq!(cluster_count(100), ANN_MIN_CLUSTERS);
+        assert_eq!(cluster_count(10_000), 100);
+        assert_eq!(cluster_count(10_000_000), ANN_MAX_CLUSTERS);
+    }
+
+    #[test]
+    fn similar_vectors_share_a_cluster() {
+        let vectors = clustered_vectors(32, 20, 64);
+        let index = IvfIndex::train(&vectors).unwrap();
+        assert!(!index.is_empty());
+
+        let a = index.assign(&vectors[0]).unwrap();
+        let b = index.assign(&vectors[1]).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(index.probe(&vectors[0], 1), vec![a]);
+    }
+
+    #[test]
+    fn assign_rejects_mismatched_dimensions() {
+        let index = IvfIndex::train(&clustered_vectors(4, 4, 8)).unwrap();
+        assert!(index.assign(&[1.0, 0.0]).is_none());
+        assert!(index.probe(&[1.0, 0.0], 4).is_empty());
+    }
+
+    #[test]
+    fn rebuild_persists_and_bumps_generation() {
+        let conn = memory_conn();
+        for (i, v) in clustered_vectors(20, 10, 32).iter().enumerate() {
+            insert(&conn, &format!("m{i}"), v);
+        }
+
+        assert!(IvfIndex::load(&conn).unwrap().is_none());
+        let built = IvfIndex::rebuild(&conn).unwrap().unwrap();
+        assert_eq!(built.generation(), 1);
+
+        let loaded = IvfIndex::load(&conn).unwrap().unwrap();
+        assert_eq!(loaded.len(), built.len());
+
+        let assigned: i64 = conn
+            .query_row("SELECT COUNT(*) FROM ann_assignments", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(assigned, 200);
+
+        let again = IvfIndex::rebuild(&conn).unwrap().unwrap();
+        assert_eq!(again.generation(), 2);
+    }
+
+    #[test]
+    fn retrain_is_due_once_corpus_doubles() {
+        let conn = memory_conn();
+        for (i, v) in clustered_vectors(20, 10, 32).iter().enumerate() {
+            insert(&conn, &format!("m{i}"), v);
+        }
+        IvfIndex::rebuild(&conn).unwrap().unwrap();
+        let loaded = IvfIndex::load(&conn).unwrap().unwrap();
+        assert!(!loaded.needs_retrain(399));
+        assert!(loaded.needs_retrain(400));
+    }
+
+    #[test]
+    fn embedded_counter_follows_inserts_updates_and_deletes() {
+        let conn = memory_conn();
+        insert(&conn, "a", &[1.0, 0.0]);
+        insert(&conn, "b", &[0.0, 1.0]);
+        conn.execute(
+            "INSERT INTO memories (id, key, content) VALUES ('c', 'c', 'c')",
+            [],
+        )
+        .unwrap();
+        assert_eq!(IvfIndex::embedded_count(&conn).unwrap(), 2);
+
+        conn.execute("UPDATE memories SET embedding = NULL WHERE id = 'a'", [])
+            .unwrap();
+        conn.execute(
+            "UPDATE memories SET embedding = ?1 WHERE id = 'c'",
+            params![vector::vec_to_bytes(&[1.0, 1.0])],
+        )
+        .unwrap();
+        conn.execute("DELETE FROM memories WHERE id = 'b'", [])
+            .unwrap();
+        assert_eq!(IvfIndex::embedded_count(&conn).unwrap(), 1);
+
+        // Re-running the schema setup must not reseed the counter.
+        IvfIndex::init_schema(&conn).unwrap();
+        assert_eq!(IvfIndex::embedded_count(&conn).unwrap(), 1);
+    }
+
+    #[test]
+    fn refresh_reloads_stale_index() {
+        let conn = memory_conn();
+        for (i, v) in clustered_vectors(20, 5, 16).iter().enumerate() {
+            insert(&conn, &format!("m{i}"), v);
+        }
+        let mut slot = None;
+        IvfIndex::refresh(&conn, &mut slot).unwrap();
+        assert!(slot.is_none());
+
+        IvfIndex::rebuild(&conn).unwrap();
+        IvfIndex::refresh(&conn, &mut slot).unwrap();
+        assert_eq!(slot.as_ref().map(IvfIndex::generation), Some(1));
+    }
+
+    #[test]
+    fn search_matches_brute_force_top_hit() {
+        let conn = memory_conn();
+        let vectors = clustered_vectors(40, 10, 64);
+        for (i, v) in vectors.iter().enumerate() {
+            insert(&conn, &format!("m{i}"), v);
+        }
+        let index = IvfIndex::rebuild(&conn).unwrap().unwrap();
+
+        let query = &vectors[123];
+        let ann = index
+            .search(&conn, query, 5, ANN_DEFAULT_NPROBE, None, None)
+            .unwrap();
+        let brute =
+            crate::memory::SqliteMemory::vector_search(&conn, query, 5, None, None).unwrap();
+        assert_eq!(ann.first().map(|r| r.1), brute.first().map(|r| r.1));
+    }
+
+    #[test]
+    fn delete_trigger_and_assign_key_keep_assignments_in_sync() {
+        let conn = memory_conn();
+        for (i, v) in clustered_vectors(20, 5, 16).iter().enumerate() {
+            insert(&conn, &format!("m{i}"), v);
+        }
+        let index = IvfIndex::rebuild(&conn).unwrap().unwrap();
+
+        conn.execute("DELETE FROM memories WHERE key = 'm0'", [])
+            .unwrap();
+        let orphan: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM ann_assignments WHERE id = 'm0'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(orphan, 0);
+
+        let v = vec![1.0_f32; 16];
+        insert(&conn, "fresh", &v);
+        index.assign_key(&conn, "fresh", &v).unwrap();
+        let cluster: i64 = conn
+            .query_row(
+                "SELECT cluster FROM ann_assignments WHERE id = 'fresh'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        #[allow(clippy::cast_possible_wrap)]
+        let expected = index.assign(&v).unwrap() as i64;
+        assert_eq!(cluster, expected);
+    }
+}
@@ -11,7 +11,8 @@
 use crate::memory::conflict;
 use crate::memory::importance;
 use crate::memory::traits::{Memory, MemoryCategory};
-use crate::providers::traits::Provider;
+use crate::providers::structured::{self, StructuredOutputError};
+use crate::providers::traits::{ChatMessage, Provider, ResponseFormat};
 
 /// Output of consolidation extraction.
 #[derive(Debug, serde::Deserialize)]
@@ -35,6 +36,22 @@ const CONSOLIDATION_SYSTEM_PROMPT: &str = r#"You are a memory consolidation engi
 Respond ONLY with valid JSON: {"history_entry": "...", "memory_update": "..." or null}
 Do not include any text outside the JSON object."#;
 
+/// Response schema mirroring [`ConsolidationResult`]'s required fields.
+fn consolidation_format() -> ResponseFormat {
+    ResponseFormat::json_schema(
+        "memory_consolidation",
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "history_entry": { "type": "string" },
+                "memory_update": { "type": ["string", "null"] }
+            },
+            "required": ["history_entry", "memory_update"],
+            "additionalProperties": false
+        }),
+    )
+}
+
 /// Run two-phase LLM-driven consolidation on a conversation turn.
 ///
 /// Phase 1: Write a history entry to the Daily memory category.
@@ -79,9 +96,18 @@ pub async fn consolidate_turn(
         turn_text.clone()
     };
 
-    let raw = provider
-        .chat_with_system(Some(CONSOLIDATION_SYSTEM_PROMPT), &truncated, model, 0.1)
-        .await?;
+    let messages = [
+        ChatMessage::system(CONSOLIDATION_SYSTEM_PROMPT),
+        ChatMessage::user(truncated),
+    ];
+    let raw =
+        match structured::chat_structured(provider, &messages, &consolidation_format(), model, 0.1)
+            .await
+        {
+            Ok(value) => value.to_string(),
+            Err(StructuredOutputError::Invalid { last_response, .. }) => last_response,
+            Err(StructuredOutputError::Provider(e)) => return Err(e),
+        };
 
     let result: ConsolidationResult = parse_consolidation_response(&raw, &turn_text);
 
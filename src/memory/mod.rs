@@ -1,3 +1,4 @@
+pub mod ann;
 pub mod audit;
 pub mod backend;
 pub mod chunker;
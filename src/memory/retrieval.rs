@@ -3,7 +3,9 @@
 //! Wraps a `Memory` trait object with staged retrieval:
 //! - **Stage 1 (Hot cache):** In-memory LRU of recent recall results.
 //! - **Stage 2 (FTS):** FTS5 keyword search with optional early-return.
-//! - **Stage 3 (Vector):** Vector similarity search + hybrid merge.
+//! - **Stage 3 (Vector):** Vector similarity search + hybrid merge. The SQLite
+//!   backend answers this through its IVF ANN index (`memory::ann`) and only
+//!   falls back to a full embedding scan when no index has been built.
 //!
 //! Configurable via `[memory]` settings: `retrieval_stages`, `fts_early_return_score`.
 
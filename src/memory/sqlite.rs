@@ -1,3 +1,4 @@
+use super::ann::{self, IvfIndex};
 use super::embeddings::EmbeddingProvider;
 use super::traits::{ExportFilter, Memory, MemoryCategory, MemoryEntry};
 use super::vector;
@@ -9,6 +10,7 @@ use parking_lot::Mutex;
 use rusqlite::{params, Connection};
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
@@ -22,6 +24,7 @@ const SQLITE_OPEN_TIMEOUT_CAP_SECS: u64 = 300;
 ///
 /// Full-stack search engine:
 /// - **Vector DB**: embeddings stored as BLOB, cosine similarity search
+/// - **ANN Index**: IVF clusters in brain.db, brute-force scan as fallback
 /// - **Keyword Search**: FTS5 virtual table with BM25 scoring
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
@@ -34,6 +37,9 @@ pub struct SqliteMemory {
     keyword_weight: f32,
     cache_max: usize,
     search_mode: SearchMode,
+    ann: Arc<Mutex<Option<IvfIndex>>>,
+    /// Set while a background ANN retrain is running.
+    ann_retraining: Arc<AtomicBool>,
 }
 
 impl SqliteMemory {
@@ -64,6 +70,7 @@ impl SqliteMemory {
              PRAGMA temp_store   = MEMORY;",
         )?;
         Self::init_schema(&conn)?;
+        let ann = IvfIndex::load(&conn)?;
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
             db_path,
@@ -72,6 +79,8 @@ impl SqliteMemory {
             keyword_weight: 0.3,
             cache_max: 10_000,
             search_mode: SearchMode::default(),
+            ann: Arc::new(Mutex::new(ann)),
+            ann_retraining: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -112,6 +121,7 @@ impl SqliteMemory {
         )?;
 
         Self::init_schema(&conn)?;
+        let ann = IvfIndex::load(&conn)?;
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
@@ -121,6 +131,8 @@ impl SqliteMemory {
             keyword_weight,
             cache_max,
             search_mode,
+            ann: Arc::new(Mutex::new(ann)),
+            ann_retraining: Arc::new(AtomicBool::new(false)),
         })
     }
 
@@ -232,6 +244,8 @@ impl SqliteMemory {
             conn.execute_batch("ALTER TABLE memories ADD COLUMN superseded_by TEXT;")?;
         }
 
+        IvfIndex::init_schema(conn)?;
+
         Ok(())
     }
 
@@ -430,6 +444,82 @@ impl SqliteMemory {
         Ok(scored)
     }
 
+    /// Vector search through the ANN index, falling back to the full scan in
+    /// [`vector_search`](Self::vector_search) when no index has been built or
+    /// the probed clusters yield fewer than `limit` candidates.
+    fn indexed_vector_search(
+        conn: &Connection,
+        ann: &Mutex<Option<IvfIndex>>,
+        query_embedding: &[f32],
+        limit: usize,
+        category: Option<&str>,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        let mut ann = ann.lock();
+        IvfIndex::refresh(conn, &mut ann)?;
+        if let Some(index) = ann.as_ref() {
+            let results = index.search(
+                conn,
+                query_embedding,
+                limit,
+                ann::ANN_DEFAULT_NPROBE,
+                category,
+                session_id,
+            )?;
+            if results.len() >= limit {
+                return Ok(results);
+            }
+        }
+        Self::vector_search(conn, query_embedding, limit, category, session_id)
+    }
+
+    /// Keep the ANN index in sync after a row under `key` was written.
+    ///
+    /// Assigns the row to its cluster and returns whether the index is due
+    /// for training: the first time the number of embedded memories reaches
+    /// [`ann::ANN_MIN_ENTRIES`], and again once the corpus has outgrown the
+    /// centroids. Training itself is left to [`Self::spawn_ann_retrain`].
+    fn sync_ann_after_store(
+        conn: &Connection,
+        ann: &Mutex<Option<IvfIndex>>,
+        key: &str,
+        embedding: &[f32],
+    ) -> anyhow::Result<bool> {
+        let mut ann = ann.lock();
+        IvfIndex::refresh(conn, &mut ann)?;
+        if let Some(index) = ann.as_ref() {
+            index.assign_key(conn, key, embedding)?;
+        }
+        IvfIndex::train_due(conn, ann.as_ref())
+    }
+
+    /// Retrain the ANN index on the blocking pool, one retrain at a time, so
+    /// the store that crossed the threshold does not wait for k-means.
+    fn spawn_ann_retrain(&self) {
+        if self.ann_retraining.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let conn = self.conn.clone();
+        let ann = self.ann.clone();
+        let retraining = self.ann_retraining.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = (|| -> anyhow::Result<()> {
+                let conn = conn.lock();
+                let mut ann = ann.lock();
+                // Another connection may have retrained in the meantime.
+                IvfIndex::refresh(&conn, &mut ann)?;
+                if IvfIndex::train_due(&conn, ann.as_ref())? {
+                    *ann = IvfIndex::rebuild(&conn)?;
+                }
+                Ok(())
+            })();
+            retraining.store(false, Ordering::Release);
+            if let Err(e) = result {
+                tracing::warn!("ANN index retrain failed: {e}");
+            }
+        });
+    }
+
     /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
     #[allow(dead_code)]
     pub async fn reindex(&self) -> anyhow::Result<usize> {
@@ -480,6 +570,19 @@ impl SqliteMemory {
             }
         }
 
+        // Step 3: Retrain the ANN index over the refreshed embeddings
+        let conn = self.conn.clone();
+        let ann = self.ann.clone();
+        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+            let conn = conn.lock();
+            let mut ann = ann.lock();
+            if ann.is_some() || IvfIndex::embedded_count(&conn)? >= ann::ANN_MIN_ENTRIES {
+                *ann = IvfIndex::rebuild(&conn)?;
+            }
+            Ok(())
+        })
+        .await??;
+
         Ok(count)
     }
 
@@ -569,17 +672,16 @@ impl Memory for SqliteMemory {
         session_id: Option<&str>,
     ) -> anyhow::Result<()> {
         // Compute embedding (async, before blocking work)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
+        let embedding = self.get_or_compute_embedding(content).await?;
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
 
         let conn = self.conn.clone();
+        let ann = self.ann.clone();
         let key = key.to_string();
         let content = content.to_string();
         let sid = session_id.map(String::from);
 
-        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+        let train_due = tokio::task::spawn_blocking(move || -> anyhow::Result<bool> {
             let conn = conn.lock();
             let now = Utc::now().to_rfc3339();
             let cat = Self::category_to_str(&category);
@@ -596,9 +698,16 @@ impl Memory for SqliteMemory {
                     session_id = excluded.session_id",
                 params![id, key, content, cat, embedding_bytes, now, now, sid],
             )?;
-            Ok(())
+            match embedding {
+                Some(ref emb) => Self::sync_ann_after_store(&conn, &ann, &key, emb),
+                None => Ok(false),
+            }
         })
-        .await?
+        .await??;
+        if train_due {
+            self.spawn_ann_retrain();
+        }
+        Ok(())
     }
 
     async fn recall(
@@ -631,6 +740,7 @@ impl Memory for SqliteMemory {
         let vector_weight = self.vector_weight;
         let keyword_weight = self.keyword_weight;
         let search_mode = self.search_mode.clone();
+        let ann = self.ann.clone();
 
         tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<MemoryEntry>> {
             let conn = conn.lock();
@@ -649,7 +759,8 @@ impl Memory for SqliteMemory {
             let vector_results = if search_mode == SearchMode::Bm25 {
                 Vec::new()
             } else if let Some(ref qe) = query_embedding {
-                Self::vector_search(&conn, qe, limit * 2, None, session_ref).unwrap_or_default()
+                Self::indexed_vector_search(&conn, &ann, qe, limit * 2, None, session_ref)
+                    .unwrap_or_default()
             } else {
                 Vec::new()
             };
@@ -1137,19 +1248,18 @@ impl Memory for SqliteMemory {
         namespace: Option<&str>,
         importance: Option<f64>,
     ) -> anyhow::Result<()> {
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
+        let embedding = self.get_or_compute_embedding(content).await?;
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
 
         let conn = self.conn.clone();
+        let ann = self.ann.clone();
         let key = key.to_string();
         let content = content.to_string();
         let sid = session_id.map(String::from);
         let ns = namespace.unwrap_or("default").to_string();
         let imp = importance.unwrap_or(0.5);
 
-        tokio::task::spawn_blocking(move || -> anyhow::Result<()> {
+        let train_due = tokio::task::spawn_blocking(move || -> anyhow::Result<bool> {
             let conn = conn.lock();
             let now = Utc::now().to_rfc3339();
             let cat = Self::category_to_str(&category);
@@ -1168,9 +1278,16 @@ impl Memory for SqliteMemory {
                     importance = excluded.importance",
                 params![id, key, content, cat, embedding_bytes, now, now, sid, ns, imp],
             )?;
-            Ok(())
+            match embedding {
+                Some(ref emb) => Self::sync_ann_after_store(&conn, &ann, &key, emb),
+                None => Ok(false),
+            }
         })
-        .await?
+        .await??;
+        if train_due {
+            self.spawn_ann_retrain();
+        }
+        Ok(())
     }
 }
 
@@ -1522,6 +1639,41 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[tokio::test]
+    async fn schema_has_ann_tables() {
+        let (_tmp, mem) = temp_sqlite();
+        let conn = mem.conn.lock();
+        for table in ["ann_meta", "ann_centroids", "ann_assignments"] {
+            let count: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=?1",
+                    params![table],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(count, 1, "missing table {table}");
+        }
+    }
+
+    #[tokio::test]
+    async fn indexed_vector_search_falls_back_without_index() {
+        let (_tmp, mem) = temp_sqlite();
+        let conn = mem.conn.lock();
+        conn.execute(
+            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at)
+             VALUES ('a', 'a', 'a', 'core', ?1, 'now', 'now')",
+            params![vector::vec_to_bytes(&[1.0, 0.0, 0.0])],
+        )
+        .unwrap();
+
+        let results =
+            SqliteMemory::indexed_vector_search(&conn, &mem.ann, &[1.0, 0.0, 0.0], 5, None, None)
+                .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, "a");
+        assert!(mem.ann.lock().is_none());
+    }
+
     #[tokio::test]
     async fn schema_memories_has_embedding_column() {
         let (_tmp, mem) = temp_sqlite();
@@ -0,0 +1,615 @@
+//! Headless node client behind `zeroclaw node connect`.
+//!
+//! Connects to a gateway's `/ws/nodes` endpoint (protocol documented in
+//! [`crate::gateway::nodes`]), advertises a subset of the local tools as
+//! capabilities and executes invocations under this machine's own
+//! [`SecurityPolicy`]. Heartbeats detect dead connections, and the client
+//! reconnects with exponential backoff until it is stopped.
+
+use crate::config::{Config, NodeClientConfig};
+use crate::gateway::nodes::{NodeCapability, WS_NODE_PROTOCOL};
+use crate::observability::trace_context::{SpanKind, TraceContext, TraceSpan};
+use crate::runtime::RuntimeAdapter;
+use crate::security::{create_sandbox, SecurityPolicy};
+use crate::tools::{
+    ContentSearchTool, FileEditTool, FileReadTool, FileWriteTool, GlobSearchTool, ScreenshotTool,
+    ShellTool, Tool,
+};
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header, HeaderValue};
+use tokio_tungstenite::tungstenite::Message;
+
+/// Tools that make up the `file` group.
+const FILE_TOOLS: &[&str] = &[
+    "file_read",
+    "file_write",
+    "file_edit",
+    "glob_search",
+    "content_search",
+];
+/// Invocation output is streamed to the gateway in chunks of at most this size.
+const MAX_CHUNK_BYTES: usize = 16 * 1024;
+/// Interval of keepalive chunks while an invocation is still running.
+const INVOCATION_KEEPALIVE_SECS: u64 = 10;
+/// Heartbeat intervals without any gateway traffic before reconnecting.
+const MAX_MISSED_HEARTBEATS: u32 = 3;
+/// First reconnect delay; doubled after each failed attempt.
+const INITIAL_BACKOFF_SECS: u64 = 1;
+
+/// Messages sent to the gateway.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Register {
+        node_id: String,
+        capabilities: Vec<NodeCapability>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        timestamp: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        nonce: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
+    },
+    Chunk {
+        call_id: String,
+        output: String,
+    },
+    Result {
+        call_id: String,
+        success: bool,
+        output: String,
+        error: Option<String>,
+    },
+    Heartbeat,
+}
+
+/// Messages received from the gateway.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Registered {
+        node_id: String,
+        capabilities_count: usize,
+    },
+    Error {
+        message: String,
+    },
+    Invoke {
+        call_id: String,
+        capability: String,
+        #[serde(default)]
+        args: serde_json::Value,
+        #[serde(default)]
+        traceparent: Option<String>,
+    },
+    HeartbeatAck,
+}
+
+/// Build the local tools a node exposes from a selection such as
+/// `["shell", "file", "hardware"]`.
+///
+/// Every tool shares one [`SecurityPolicy`] derived from this machine's
+/// `[autonomy]` config, so the node enforces its own limits regardless of
+/// what the central agent asks for.
+pub async fn build_tools(config: &Config, selection: &[String]) -> Result<Vec<Arc<dyn Tool>>> {
+    let security = Arc::new(
+        SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+            .with_trust(crate::trust::runtime::init(config)),
+    );
+    let mut tools: Vec<Arc<dyn Tool>> = Vec::new();
+
+    for entry in selection {
+        match entry.trim() {
+            "" => {}
+            "shell" => {
+                let runtime: Arc<dyn RuntimeAdapter> =
+                    Arc::from(crate::runtime::create_runtime(&config.runtime)?);
+                let sandbox =
+                    create_sandbox(&config.security, &config.http_request.allowed_domains);
+                tools.push(Arc::new(
+                    ShellTool::new_with_sandbox(security.clone(), runtime, sandbox)
+                        .with_timeout_secs(config.shell_tool.timeout_secs),
+                ));
+            }
+            "file" => {
+                for name in FILE_TOOLS {
+                    tools.push(file_tool(name, &security));
+                }
+            }
+            name if FILE_TOOLS.contains(&name) => tools.push(file_tool(name, &security)),
+            "screenshot" => tools.push(Arc::new(ScreenshotTool::new(security.clone()))),
+            "hardware" => {
+                let hardware = crate::peripherals::create_peripheral_tools(&config.peripherals)
+                    .await
+                    .context("Failed to initialize hardware tools")?;
+                if hardware.is_empty() {
+                    tracing::warn!("No hardware tools available; configure [peripherals] boards");
+                }
+                tools.extend(hardware.into_iter().map(Arc::from));
+            }
+            other => bail!(
+                "Tool '{other}' cannot be exposed on a node (supported: shell, file, {}, screenshot, hardware)",
+                FILE_TOOLS.join(", ")
+            ),
+        }
+    }
+
+    let mut seen = HashSet::new();
+    tools.retain(|tool| seen.insert(tool.name().to_string()));
+    Ok(tools)
+}
+
+fn file_tool(name: &str, security: &Arc<SecurityPolicy>) -> Arc<dyn Tool> {
+    match name {
+        "file_read" => Arc::new(FileReadTool::new(security.clone())),
+        "file_write" => Arc::new(FileWriteTool::new(security.clone())),
+        "file_edit" => Arc::new(FileEditTool::new(security.clone())),
+        "glob_search" => Arc::new(GlobSearchTool::new(security.clone())),
+        _ => Arc::new(ContentSearchTool::new(security.clone())),
+    }
+}
+
+/// Capability advertised for a local tool.
+pub fn capability_for(tool: &dyn Tool) -> NodeCapability {
+    NodeCapability {
+        name: tool.name().to_string(),
+        description: tool.description().to_string(),
+        parameters: tool.parameters_schema(),
+    }
+}
+
+/// A node connection to a single gateway.
+pub struct NodeClient {
+    gateway_url: String,
+    node_id: String,
+    token: Option<String>,
+    signing_secret: Option<String>,
+    heartbeat_interval: Duration,
+    max_backoff: Duration,
+    tools: Arc<HashMap<String, Arc<dyn Tool>>>,
+    capabilities: Vec<NodeCapability>,
+}
+
+impl NodeClient {
+    pub fn new(
+        gateway_url: String,
+        node_id: String,
+        token: Option<String>,
+        signing_secret: Option<String>,
+        tools: Vec<Arc<dyn Tool>>,
+        client_config: &NodeClientConfig,
+    ) -> Self {
+        let capabilities = tools
+            .iter()
+            .map(|tool| capability_for(tool.as_ref()))
+            .collect();
+        let tools = tools
+            .into_iter()
+            .map(|tool| (tool.name().to_string(), tool))
+            .collect();
+        Self {
+            gateway_url,
+            node_id,
+            token,
+            signing_secret,
+            heartbeat_interval: Duration::from_secs(client_config.heartbeat_interval_secs.max(1)),
+            max_backoff: Duration::from_secs(
+                client_config.max_backoff_secs.max(INITIAL_BACKOFF_SECS),
+            ),
+            tools: Arc::new(tools),
+            capabilities,
+        }
+    }
+
+    /// Capabilities advertised on registration.
+    pub fn capabilities(&self) -> &[NodeCapability] {
+        &self.capabilities
+    }
+
+    /// Stay connected to the gateway, reconnecting with exponential backoff.
+    ///
+    /// The backoff resets once a connection gets as far as a successful
+    /// registration.
+    pub async fn run(&self) -> Result<()> {
+        let mut backoff = Duration::from_secs(INITIAL_BACKOFF_SECS);
+        loop {
+            let mut registered = false;
+            match self.run_session(&mut registered).await {
+                Ok(()) => tracing::warn!("Gateway closed the node connection"),
+                Err(e) => tracing::warn!("Node connection failed: {e:#}"),
+            }
+            if registered {
+                backoff = Duration::from_secs(INITIAL_BACKOFF_SECS);
+            }
+            tracing::info!("Reconnecting to gateway in {}s", backoff.as_secs());
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+
+    /// One connection: register, then serve invocations until it drops.
+    async fn run_session(&self, registered: &mut bool) -> Result<()> {
+        let mut request = self
+            .gateway_url
+            .as_str()
+            .into_client_request()
+            .context("Invalid gateway URL")?;
+        let headers = request.headers_mut();
+        headers.insert(
+            header::SEC_WEBSOCKET_PROTOCOL,
+            HeaderValue::from_static(WS_NODE_PROTOCOL),
+        );
+        if let Some(token) = &self.token {
+            headers.insert(
+                header::AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {token}"))
+                    .context("Node token is not a valid header value")?,
+            );
+        }
+
+        let (socket, _) = tokio_tungstenite::connect_async(request)
+            .await
+            .with_context(|| format!("Failed to connect to {}", self.gateway_url))?;
+        let (mut sink, mut stream) = socket.split();
+
+        // Invocation tasks and the heartbeat share one writer.
+        let (out_tx, mut out_rx) = mpsc::channel::<ClientMessage>(64);
+        let writer = tokio::spawn(async move {
+            while let Some(msg) = out_rx.recv().await {
+                let Ok(json) = serde_json::to_string(&msg) else {
+                    continue;
+                };
+                if sink.send(Message::Text(json.into())).await.is_err() {
+                    break;
+                }
+            }
+        });
+        out_tx
+            .send(self.register_message()?)
+            .await
+            .map_err(|_| anyhow!("connection writer stopped"))?;
+
+        let mut heartbeat = tokio::time::interval(self.heartbeat_interval);
+        heartbeat.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        let dead_after = self.heartbeat_interval * MAX_MISSED_HEARTBEATS;
+        let mut last_seen = Instant::now();
+
+        let result = loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() > dead_after {
+                        break Err(anyhow!("gateway stopped responding to heartbeats"));
+                    }
+                    if out_tx.send(ClientMessage::Heartbeat).await.is_err() {
+                        break Err(anyhow!("connection writer stopped"));
+                    }
+                }
+                frame = stream.next() => {
+                    let text = match frame {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Close(_))) | None => break Ok(()),
+                        Some(Ok(_)) => {
+                            last_seen = Instant::now();
+                            continue;
+                        }
+                        Some(Err(e)) => break Err(e.into()),
+                    };
+                    last_seen = Instant::now();
+
+                    let Ok(message) = serde_json::from_str::<ServerMessage>(text.as_str()) else {
+                        continue;
+                    };
+                    match message {
+                        ServerMessage::Registered {
+                            node_id,
+                            capabilities_count,
+                        } => {
+                            *registered = true;
+                            tracing::info!(
+                                "Registered as node '{node_id}' with {capabilities_count} capabilities"
+                            );
+                        }
+                        ServerMessage::Error { message } => {
+                            if !*registered {
+                                break Err(anyhow!("gateway rejected registration: {message}"));
+                            }
+                            tracing::warn!("Gateway reported an error: {message}");
+                        }
+                        ServerMessage::Invoke {
+                            call_id,
+                            capability,
+                            args,
+                            traceparent,
+                        } => {
+                            let tool = self.tools.get(&capability).cloned();
+                            let parent = traceparent
+                                .as_deref()
+                                .and_then(TraceContext::from_traceparent);
+                            tokio::spawn(execute_invocation(
+                                tool,
+                                call_id,
+                                capability,
+                                args,
+                                parent,
+                                out_tx.clone(),
+                            ));
+                        }
+                        ServerMessage::HeartbeatAck => {}
+                    }
+                }
+            }
+        };
+
+        writer.abort();
+        result
+    }
+
+    fn register_message(&self) -> Result<ClientMessage> {
+        let (timestamp, nonce, signature) = match &self.signing_secret {
+            Some(secret) => {
+                let timestamp = Utc::now().timestamp();
+                let nonce = uuid::Uuid::new_v4().to_string();
+                let signature = super::transport::sign_request(
+                    secret,
+                    self.node_id.as_bytes(),
+                    timestamp,
+                    &nonce,
+                )?;
+                (Some(timestamp), Some(nonce), Some(signature))
+            }
+            None => (None, None, None),
+        };
+        Ok(ClientMessage::Register {
+            node_id: self.node_id.clone(),
+            capabilities: self.capabilities.clone(),
+            timestamp,
+            nonce,
+            signature,
+        })
+    }
+}
+
+/// Run one invocation and stream its output back to the gateway.
+///
+/// The call is traced as a child of the gateway-side span when the
+/// invocation carried a `traceparent`.
+async fn execute_invocation(
+    tool: Option<Arc<dyn Tool>>,
+    call_id: String,
+    capability: String,
+    args: serde_json::Value,
+    parent: Option<TraceContext>,
+    out_tx: mpsc::Sender<ClientMessage>,
+) {
+    let mut span = TraceSpan::start_with_parent(
+        format!("execute_tool {capability}"),
+        SpanKind::Server,
+        parent,
+    )
+    .with_attribute("gen_ai.operation.name", "execute_tool")
+    .with_attribute("gen_ai.tool.name", capability.as_str())
+    .with_attribute("gen_ai.tool.call.id", call_id.as_str());
+    let (success, output, error) = match tool {
+        None => (
+            false,
+            String::new(),
+            Some(format!(
+                "Capability '{capability}' is not exposed by this node"
+            )),
+        ),
+        Some(tool) => {
+            tracing::info!(call_id = %call_id, "Executing node invocation: {capability}");
+            let execution = span.scope(tool.execute(args));
+            tokio::pin!(execution);
+            let mut keepalive =
+                tokio::time::interval(Duration::from_secs(INVOCATION_KEEPALIVE_SECS));
+            keepalive.tick().await;
+
+            let outcome = loop {
+                tokio::select! {
+                    outcome = &mut execution => break outcome,
+                    _ = keepalive.tick() => {
+                        let _ = out_tx
+                            .send(ClientMessage::Chunk {
+                                call_id: call_id.clone(),
+                                output: String::new(),
+                            })
+                            .await;
+                    }
+                }
+            };
+            match outcome {
+                Ok(result) => (result.success, result.output, result.error),
+                Err(e) => (false, String::new(), Some(e.to_string())),
+            }
+        }
+    };
+    if !success {
+        span.set_error(error.clone().unwrap_or_default());
+    }
+    span.end();
+
+    let mut chunks = split_output(&output, MAX_CHUNK_BYTES);
+    let last = chunks.pop().unwrap_or_default();
+    for chunk in chunks {
+        let message = ClientMessage::Chunk {
+            call_id: call_id.clone(),
+            output: chunk,
+        };
+        if out_tx.send(message).await.is_err() {
+            return;
+        }
+    }
+    let _ = out_tx
+        .send(ClientMessage::Result {
+            call_id,
+            success,
+            output: last,
+            error,
+        })
+        .await;
+}
+
+/// Split `output` into pieces of at most `max_bytes`, on char boundaries.
+fn split_output(output: &str, max_bytes: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = output;
+    while rest.len() > max_bytes {
+        let mut cut = max_bytes;
+        while !rest.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        let (head, tail) = rest.split_at(cut);
+        pieces.push(head.to_string());
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        pieces.push(rest.to_string());
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_config(tmp: &TempDir) -> Config {
+        Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn split_output_respects_char_boundaries() {
+        let pieces = split_output("ab\u{e9}cd", 3);
+        assert_eq!(pieces, vec!["ab", "\u{e9}c", "d"]);
+        assert_eq!(pieces.concat(), "ab\u{e9}cd");
+        assert!(split_output("", 3).is_empty());
+    }
+
+    #[tokio::test]
+    async fn build_tools_expands_groups_and_dedups() {
+        let tmp = TempDir::new().unwrap();
+        let tools = build_tools(
+            &test_config(&tmp),
+            &["file".into(), "file_read".into(), "shell".into()],
+        )
+        .await
+        .unwrap();
+        let names: Vec<&str> = tools.iter().map(|tool| tool.name()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "file_read",
+                "file_write",
+                "file_edit",
+                "glob_search",
+                "content_search",
+                "shell"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn build_tools_rejects_unsupported_tools() {
+        let tmp = TempDir::new().unwrap();
+        let err = build_tools(&test_config(&tmp), &["memory_store".into()])
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("cannot be exposed"));
+    }
+
+    #[test]
+    fn register_message_is_signed_with_shared_secret() {
+        let client = NodeClient::new(
+            "ws://127.0.0.1:1/ws/nodes".into(),
+            "lab-1".into(),
+            None,
+            Some("secret".into()),
+            Vec::new(),
+            &NodeClientConfig::default(),
+        );
+        let ClientMessage::Register {
+            node_id,
+            timestamp,
+            nonce,
+            signature,
+            ..
+        } = client.register_message().unwrap()
+        else {
+            panic!("expected a register message");
+        };
+        assert!(super::super::transport::verify_request(
+            "secret",
+            node_id.as_bytes(),
+            timestamp.unwrap(),
+            &nonce.unwrap(),
+            &signature.unwrap(),
+            300,
+        )
+        .unwrap());
+    }
+
+    #[tokio::test]
+    async fn invocation_streams_large_output_before_result() {
+        struct BigOutput;
+
+        #[async_trait::async_trait]
+        impl Tool for BigOutput {
+            fn name(&self) -> &str {
+                "big"
+            }
+            fn description(&self) -> &str {
+                "Produces a lot of output"
+            }
+            fn parameters_schema(&self) -> serde_json::Value {
+                serde_json::json!({"type": "object"})
+            }
+            async fn execute(
+                &self,
+                _args: serde_json::Value,
+            ) -> anyhow::Result<crate::tools::ToolResult> {
+                Ok(crate::tools::ToolResult {
+                    success: true,
+                    output: "x".repeat(MAX_CHUNK_BYTES + 10),
+                    error: None,
+                })
+            }
+        }
+
+        let (out_tx, mut out_rx) = mpsc::channel(8);
+        execute_invocation(
+            Some(Arc::new(BigOutput)),
+            "c1".into(),
+            "big".into(),
+            serde_json::json!({}),
+            None,
+            out_tx,
+        )
+        .await;
+
+        let Some(ClientMessage::Chunk { output, .. }) = out_rx.recv().await else {
+            panic!("expected a chunk first");
+        };
+        assert_eq!(output.len(), MAX_CHUNK_BYTES);
+        let Some(ClientMessage::Result {
+            success, output, ..
+        }) = out_rx.recv().await
+        else {
+            panic!("expected the final result");
+        };
+        assert!(success);
+        assert_eq!(output.len(), 10);
+    }
+}
@@ -1,3 +1,73 @@
+pub mod client;
 pub mod transport;
 
+pub use client::NodeClient;
+#[allow(unused_imports)]
 pub use transport::NodeTransport;
+
+use crate::config::Config;
+use anyhow::{bail, Context, Result};
+
+/// Handle `zeroclaw node` subcommands.
+pub async fn handle_command(command: crate::NodeCommands, config: &Config) -> Result<()> {
+    let client_config = &config.nodes.client;
+    match command {
+        crate::NodeCommands::Connect {
+            gateway,
+            node_id,
+            token,
+            tools,
+        } => {
+            let gateway_url = gateway
+                .or_else(|| client_config.gateway_url.clone())
+                .context("No gateway URL: pass --gateway or set [nodes.client] gateway_url")?;
+            let node_id = node_id
+                .or_else(|| client_config.node_id.clone())
+                .unwrap_or_else(|| {
+                    hostname::get()
+                        .map_or_else(|_| "unknown".into(), |h| h.to_string_lossy().to_string())
+                });
+            let token = token.or_else(|| config.nodes.auth_token.clone());
+            let signing_secret =
+                transport::registration_secret(&config.node_transport).map(str::to_string);
+            if signing_secret.is_some()
+                && config.node_transport.require_https
+                && !gateway_url.starts_with("wss://")
+            {
+                bail!("[node_transport] require_https is set; use a wss:// gateway URL");
+            }
+
+            let tools =
+                client::build_tools(config, tools.as_deref().unwrap_or(&client_config.tools))
+                    .await?;
+            if tools.is_empty() {
+                bail!("No tools to expose; pass --tools or set [nodes.client] tools");
+            }
+
+            let client = NodeClient::new(
+                gateway_url.clone(),
+                node_id.clone(),
+                token,
+                signing_secret,
+                tools,
+                client_config,
+            );
+            println!(
+                "🔌 Connecting node '{node_id}' to {gateway_url} ({} capabilities)",
+                client.capabilities().len()
+            );
+            client.run().await
+        }
+        crate::NodeCommands::Capabilities { tools } => {
+            let tools =
+                client::build_tools(config, tools.as_deref().unwrap_or(&client_config.tools))
+                    .await?;
+            println!("Node capabilities ({}):", tools.len());
+            for tool in &tools {
+                let capability = client::capability_for(tool.as_ref());
+                println!("  {:<16} {}", capability.name, capability.description);
+            }
+            Ok(())
+        }
+    }
+}
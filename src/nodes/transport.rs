@@ -7,10 +7,15 @@
 use anyhow::{bail, Result};
 use chrono::Utc;
 use hmac::{Hmac, Mac};
+use parking_lot::Mutex;
 use sha2::Sha256;
+use std::collections::HashMap;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Upper bound on nonces remembered within one replay window.
+const MAX_TRACKED_NONCES: usize = 10_000;
+
 /// Signs a request payload with HMAC-SHA256.
 ///
 /// Uses `timestamp` + `nonce` alongside the payload to prevent replay attacks.
@@ -46,6 +51,39 @@ pub fn verify_request(
     Ok(constant_time_eq(expected.as_bytes(), signature.as_bytes()))
 }
 
+/// Nonces of recently verified requests, remembered for the replay window.
+#[derive(Debug, Default)]
+pub struct NonceCache {
+    seen: Mutex<HashMap<String, i64>>,
+}
+
+impl NonceCache {
+    /// Remember `nonce` from a request signed at `timestamp`.
+    ///
+    /// Returns `false` when the nonce was already used within
+    /// `max_age_secs`, or when too many nonces are outstanding to track
+    /// another one.
+    pub fn insert(&self, nonce: &str, timestamp: i64, max_age_secs: i64) -> bool {
+        let now = Utc::now().timestamp();
+        let mut seen = self.seen.lock();
+        // A nonce can be forgotten once its timestamp would fail verification.
+        seen.retain(|_, signed_at| (now - *signed_at).abs() <= max_age_secs);
+        if seen.contains_key(nonce) || seen.len() >= MAX_TRACKED_NONCES {
+            return false;
+        }
+        seen.insert(nonce.to_string(), timestamp);
+        true
+    }
+}
+
+/// Shared secret that node registrations must be signed with, if any.
+///
+/// Signing is active when `[node_transport]` is enabled with a non-empty
+/// `shared_secret`.
+pub fn registration_secret(config: &crate::config::NodeTransportConfig) -> Option<&str> {
+    (config.enabled && !config.shared_secret.is_empty()).then_some(config.shared_secret.as_str())
+}
+
 /// Constant-time comparison to prevent timing attacks.
 fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     if a.len() != b.len() {
@@ -71,6 +109,7 @@ pub struct NodeTransport {
     http: reqwest::Client,
     shared_secret: String,
     max_request_age_secs: i64,
+    seen_nonces: NonceCache,
 }
 
 impl NodeTransport {
@@ -82,6 +121,7 @@ impl NodeTransport {
                 .expect("HTTP client build"),
             shared_secret,
             max_request_age_secs: 300, // 5 min replay window
+            seen_nonces: NonceCache::default(),
         }
     }
 
@@ -120,7 +160,8 @@ impl NodeTransport {
         Ok(resp.json().await?)
     }
 
-    /// Verify an incoming request from a peer node.
+    /// Verify an incoming request from a peer node, rejecting a nonce that
+    /// was already used within the replay window.
     pub fn verify_incoming(
         &self,
         payload: &[u8],
@@ -131,14 +172,22 @@ impl NodeTransport {
         let timestamp: i64 = timestamp_header
             .parse()
             .map_err(|_| anyhow::anyhow!("Invalid timestamp header"))?;
-        verify_request(
+        let valid = verify_request(
             &self.shared_secret,
             payload,
             timestamp,
             nonce_header,
             signature_header,
             self.max_request_age_secs,
-        )
+        )?;
+        if valid
+            && !self
+                .seen_nonces
+                .insert(nonce_header, timestamp, self.max_request_age_secs)
+        {
+            bail!("Request nonce already used");
+        }
+        Ok(valid)
     }
 }
 
@@ -216,6 +265,29 @@ mod tests {
         assert!(ok, "Valid incoming request must pass verification");
     }
 
+    #[test]
+    fn node_transport_verify_incoming_rejects_replayed_nonce() {
+        let transport = NodeTransport::new(TEST_SECRET.into());
+        let now = Utc::now().timestamp();
+        let sig = sign_request(TEST_SECRET, b"body", now, "once").unwrap();
+
+        assert!(transport
+            .verify_incoming(b"body", &now.to_string(), "once", &sig)
+            .unwrap());
+        assert!(transport
+            .verify_incoming(b"body", &now.to_string(), "once", &sig)
+            .is_err());
+    }
+
+    #[test]
+    fn nonce_cache_forgets_nonces_outside_the_window() {
+        let cache = NonceCache::default();
+        let stale = Utc::now().timestamp() - 600;
+        assert!(cache.insert("n", stale, 300));
+        assert!(cache.insert("n", Utc::now().timestamp(), 300));
+        assert!(!cache.insert("n", Utc::now().timestamp(), 300));
+    }
+
     #[test]
     fn node_transport_verify_incoming_bad_timestamp_header() {
         let transport = NodeTransport::new(TEST_SECRET.into());
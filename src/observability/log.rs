@@ -124,6 +124,29 @@ impl Observer for LogObserver {
             ObserverEvent::RecoveryCompleted { deploy_id } => {
                 info!(deploy_id = %deploy_id, "recovery.completed");
             }
+            ObserverEvent::ResourceLimitExceeded {
+                tool,
+                resource,
+                detail,
+            } => {
+                info!(tool = %tool, resource = %resource, detail = %detail, "resource_limit.exceeded");
+            }
+            ObserverEvent::BudgetDowngrade {
+                requested_model,
+                routed_model,
+                period,
+                current_usd,
+                limit_usd,
+            } => {
+                info!(
+                    requested_model = %requested_model,
+                    routed_model = %routed_model,
+                    period = %period,
+                    current_usd = current_usd,
+                    limit_usd = limit_usd,
+                    "budget.downgrade"
+                );
+            }
         }
     }
 
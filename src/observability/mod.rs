@@ -7,6 +7,7 @@ pub mod otel;
 #[cfg(feature = "observability-prometheus")]
 pub mod prometheus;
 pub mod runtime_trace;
+pub mod trace_context;
 pub mod traits;
 pub mod verbose;
 
@@ -1,17 +1,32 @@
+use super::trace_context::{self, SpanRecord, SpanValue, TraceContext};
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use opentelemetry::logs::{AnyValue, LogRecord as _, Logger as _, LoggerProvider as _, Severity};
 use opentelemetry::metrics::{Counter, Gauge, Histogram};
-use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
-use opentelemetry::{global, KeyValue};
+use opentelemetry::trace::{
+    Span, SpanBuilder, SpanId, SpanKind, Status, TraceContextExt, TraceFlags, TraceId, TraceState,
+    Tracer, TracerProvider as _,
+};
+use opentelemetry::{global, Context, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::logs::{SdkLogger, SdkLoggerProvider};
 use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::any::Any;
+use std::sync::OnceLock;
 use std::time::SystemTime;
 
-/// OpenTelemetry-backed observer — exports traces and metrics via OTLP.
+/// Logger fed by [`OtelLogLayer`]; set once the first observer is created.
+static LOGGER: OnceLock<SdkLogger> = OnceLock::new();
+
+/// OpenTelemetry-backed observer — exports traces, metrics and logs via OTLP.
+///
+/// Spans follow the GenAI semantic conventions and form a hierarchy
+/// (channel message → agent turn → LLM call / tool call → delegated agent)
+/// built from [`trace_context`] spans; lifecycle events feed the metrics.
 pub struct OtelObserver {
     tracer_provider: SdkTracerProvider,
     meter_provider: SdkMeterProvider,
+    logger_provider: SdkLoggerProvider,
 
     // Metrics instruments
     agent_starts: Counter<u64>,
@@ -30,6 +45,8 @@ pub struct OtelObserver {
     hand_runs: Counter<u64>,
     hand_duration: Histogram<f64>,
     hand_findings: Counter<u64>,
+    genai_operation_duration: Histogram<f64>,
+    genai_token_usage: Histogram<u64>,
 }
 
 impl OtelObserver {
@@ -41,6 +58,7 @@ impl OtelObserver {
         let base_endpoint = endpoint.unwrap_or("http://localhost:4318");
         let traces_endpoint = format!("{}/v1/traces", base_endpoint.trim_end_matches('/'));
         let metrics_endpoint = format!("{}/v1/metrics", base_endpoint.trim_end_matches('/'));
+        let logs_endpoint = format!("{}/v1/logs", base_endpoint.trim_end_matches('/'));
         let service_name = service_name.unwrap_or("zeroclaw");
 
         // ── Trace exporter ──────────────────────────────────────
@@ -61,6 +79,26 @@ impl OtelObserver {
 
         global::set_tracer_provider(tracer_provider.clone());
 
+        let span_tracer = tracer_provider.tracer("zeroclaw");
+        trace_context::set_span_sink(move |record| export_span(&span_tracer, record));
+
+        // ── Log exporter ────────────────────────────────────────
+        let log_exporter = opentelemetry_otlp::LogExporter::builder()
+            .with_http()
+            .with_endpoint(&logs_endpoint)
+            .build()
+            .map_err(|e| format!("Failed to create OTLP log exporter: {e}"))?;
+
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_batch_exporter(log_exporter)
+            .with_resource(
+                opentelemetry_sdk::Resource::builder()
+                    .with_service_name(service_name.to_string())
+                    .build(),
+            )
+            .build();
+        let _ = LOGGER.set(logger_provider.logger("zeroclaw"));
+
         // ── Metric exporter ─────────────────────────────────────
         let metric_exporter = opentelemetry_otlp::MetricExporter::builder()
             .with_http()
@@ -171,9 +209,22 @@ impl OtelObserver {
             .with_description("Total findings produced by hand runs")
             .build();
 
+        let genai_operation_duration = meter
+            .f64_histogram("gen_ai.client.operation.duration")
+            .with_description("GenAI operation duration")
+            .with_unit("s")
+            .build();
+
+        let genai_token_usage = meter
+            .u64_histogram("gen_ai.client.token.usage")
+            .with_description("Number of input and output tokens used")
+            .with_unit("{token}")
+            .build();
+
         Ok(Self {
             tracer_provider,
             meter_provider: meter_provider_clone,
+            logger_provider,
             agent_starts,
             agent_duration,
             llm_calls,
@@ -190,13 +241,154 @@ impl OtelObserver {
             hand_runs,
             hand_duration,
             hand_findings,
+            genai_operation_duration,
+            genai_token_usage,
         })
     }
 }
 
+/// Build an OTel parent context from a `trace_context` span.
+fn parent_context(parent: Option<TraceContext>) -> Context {
+    match parent {
+        Some(parent) => {
+            Context::new().with_remote_span_context(opentelemetry::trace::SpanContext::new(
+                TraceId::from_bytes(parent.trace_id),
+                SpanId::from_bytes(parent.span_id),
+                TraceFlags::SAMPLED,
+                parent.remote,
+                TraceState::default(),
+            ))
+        }
+        None => Context::new(),
+    }
+}
+
+fn span_kind(kind: trace_context::SpanKind) -> SpanKind {
+    match kind {
+        trace_context::SpanKind::Internal => SpanKind::Internal,
+        trace_context::SpanKind::Server => SpanKind::Server,
+        trace_context::SpanKind::Client => SpanKind::Client,
+        trace_context::SpanKind::Consumer => SpanKind::Consumer,
+    }
+}
+
+fn key_value(key: &'static str, value: SpanValue) -> KeyValue {
+    match value {
+        SpanValue::Str(v) => KeyValue::new(key, v),
+        SpanValue::Int(v) => KeyValue::new(key, v),
+        SpanValue::Float(v) => KeyValue::new(key, v),
+        SpanValue::Bool(v) => KeyValue::new(key, v),
+    }
+}
+
+/// Export a finished `trace_context` span, keeping its ids so children
+/// recorded earlier link up with it.
+fn export_span(tracer: &opentelemetry_sdk::trace::SdkTracer, record: SpanRecord) {
+    let mut builder = SpanBuilder::from_name(record.name)
+        .with_kind(span_kind(record.kind))
+        .with_start_time(record.start)
+        .with_attributes(
+            record
+                .attributes
+                .into_iter()
+                .map(|(key, value)| key_value(key, value))
+                .collect::<Vec<_>>(),
+        );
+    builder.trace_id = Some(TraceId::from_bytes(record.context.trace_id));
+    builder.span_id = Some(SpanId::from_bytes(record.context.span_id));
+
+    let mut span = tracer.build_with_context(builder, &parent_context(record.parent));
+    match record.error {
+        Some(message) => {
+            span.set_attribute(KeyValue::new("error.type", "_OTHER"));
+            span.set_status(Status::error(message));
+        }
+        None => span.set_status(Status::Ok),
+    }
+    span.end_with_timestamp(record.end);
+}
+
+/// `tracing` layer that forwards log events to the OTLP log exporter,
+/// stamped with the current span's trace and span ids.
+///
+/// Installed unconditionally when built with `observability-otel`; it is
+/// a no-op until an [`OtelObserver`] has been created.
+pub struct OtelLogLayer;
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for OtelLogLayer {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let Some(logger) = LOGGER.get() else {
+            return;
+        };
+        let metadata = event.metadata();
+        // The exporter's own HTTP stack would otherwise log about every export.
+        if ["opentelemetry", "hyper", "reqwest", "h2", "tower", "rustls"]
+            .iter()
+            .any(|prefix| metadata.target().starts_with(prefix))
+        {
+            return;
+        }
+
+        let mut fields = LogFields::default();
+        event.record(&mut fields);
+
+        let mut record = logger.create_log_record();
+        record.set_timestamp(SystemTime::now());
+        record.set_severity_number(match *metadata.level() {
+            tracing::Level::ERROR => Severity::Error,
+            tracing::Level::WARN => Severity::Warn,
+            tracing::Level::INFO => Severity::Info,
+            tracing::Level::DEBUG => Severity::Debug,
+            tracing::Level::TRACE => Severity::Trace,
+        });
+        record.set_severity_text(metadata.level().as_str());
+        record.set_target(metadata.target().to_string());
+        record.set_body(AnyValue::from(fields.message));
+        record.add_attributes(fields.attributes);
+        if let Some(ctx) = trace_context::current() {
+            record.set_trace_context(
+                TraceId::from_bytes(ctx.trace_id),
+                SpanId::from_bytes(ctx.span_id),
+                Some(TraceFlags::SAMPLED),
+            );
+        }
+        logger.emit(record);
+    }
+}
+
+#[derive(Default)]
+struct LogFields {
+    message: String,
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl tracing::field::Visit for LogFields {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.attributes.push((field.name(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.attributes.push((field.name(), format!("{value:?}")));
+        }
+    }
+}
+
 impl Observer for OtelObserver {
     fn record_event(&self, event: &ObserverEvent) {
         let tracer = global::tracer("zeroclaw");
+        // Point-in-time spans hang off whatever span is current on this task.
+        let parent = parent_context(trace_context::current());
 
         match event {
             ObserverEvent::AgentStart { provider, model } => {
@@ -219,9 +411,11 @@ impl Observer for OtelObserver {
                 duration,
                 success,
                 error_message: _,
-                input_tokens: _,
-                output_tokens: _,
+                input_tokens,
+                output_tokens,
             } => {
+                // The `chat` span itself is emitted by the tool loop through
+                // `trace_context`; this event only feeds the metrics.
                 let secs = duration.as_secs_f64();
                 let attrs = [
                     KeyValue::new("provider", provider.clone()),
@@ -231,59 +425,32 @@ impl Observer for OtelObserver {
                 self.llm_calls.add(1, &attrs);
                 self.llm_duration.record(secs, &attrs);
 
-                // Create a completed span for visibility in trace backends.
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("llm.call")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("provider", provider.clone()),
-                            KeyValue::new("model", model.clone()),
-                            KeyValue::new("success", *success),
-                            KeyValue::new("duration_s", secs),
-                        ]),
-                );
-                if *success {
-                    span.set_status(Status::Ok);
-                } else {
-                    span.set_status(Status::error(""));
+                let mut genai_attrs = vec![
+                    KeyValue::new("gen_ai.operation.name", "chat"),
+                    KeyValue::new("gen_ai.provider.name", provider.clone()),
+                    KeyValue::new("gen_ai.request.model", model.clone()),
+                ];
+                if !success {
+                    genai_attrs.push(KeyValue::new("error.type", "_OTHER"));
+                }
+                self.genai_operation_duration.record(secs, &genai_attrs);
+                for (token_type, count) in [("input", input_tokens), ("output", output_tokens)] {
+                    if let Some(count) = count {
+                        let mut attrs = genai_attrs.clone();
+                        attrs.push(KeyValue::new("gen_ai.token.type", token_type));
+                        self.genai_token_usage.record(*count, &attrs);
+                    }
                 }
-                span.end();
             }
             ObserverEvent::AgentEnd {
                 provider,
                 model,
                 duration,
-                tokens_used,
-                cost_usd,
+                tokens_used: _,
+                cost_usd: _,
             } => {
+                // Turn spans come from `trace_context`; record duration only.
                 let secs = duration.as_secs_f64();
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-
-                // Create a completed span with correct timing
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("agent.invocation")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("provider", provider.clone()),
-                            KeyValue::new("model", model.clone()),
-                            KeyValue::new("duration_s", secs),
-                        ]),
-                );
-                if let Some(t) = tokens_used {
-                    span.set_attribute(KeyValue::new("tokens_used", *t as i64));
-                }
-                if let Some(c) = cost_usd {
-                    span.set_attribute(KeyValue::new("cost_usd", *c));
-                }
-                span.end();
-
                 self.agent_duration.record(
                     secs,
                     &[
@@ -299,30 +466,8 @@ impl Observer for OtelObserver {
                 duration,
                 success,
             } => {
+                // `execute_tool` spans come from `trace_context`.
                 let secs = duration.as_secs_f64();
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-
-                let status = if *success {
-                    Status::Ok
-                } else {
-                    Status::error("")
-                };
-
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("tool.call")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("tool.name", tool.clone()),
-                            KeyValue::new("tool.success", *success),
-                            KeyValue::new("duration_s", secs),
-                        ]),
-                );
-                span.set_status(status);
-                span.end();
-
                 let attrs = [
                     KeyValue::new("tool", tool.clone()),
                     KeyValue::new("success", success.to_string()),
@@ -345,13 +490,14 @@ impl Observer for OtelObserver {
             }
             ObserverEvent::Error { component, message } => {
                 // Create an error span for visibility in trace backends
-                let mut span = tracer.build(
+                let mut span = tracer.build_with_context(
                     opentelemetry::trace::SpanBuilder::from_name("error")
                         .with_kind(SpanKind::Internal)
                         .with_attributes(vec![
                             KeyValue::new("component", component.clone()),
                             KeyValue::new("error.message", message.clone()),
                         ]),
+                    &parent,
                 );
                 span.set_status(Status::error(message.clone()));
                 span.end();
@@ -371,7 +517,7 @@ impl Observer for OtelObserver {
                     .checked_sub(duration)
                     .unwrap_or(SystemTime::now());
 
-                let mut span = tracer.build(
+                let mut span = tracer.build_with_context(
                     opentelemetry::trace::SpanBuilder::from_name("hand.run")
                         .with_kind(SpanKind::Internal)
                         .with_start_time(start_time)
@@ -381,6 +527,7 @@ impl Observer for OtelObserver {
                             KeyValue::new("hand.findings", *findings_count as i64),
                             KeyValue::new("duration_s", secs),
                         ]),
+                    &parent,
                 );
                 span.set_status(Status::Ok);
                 span.end();
@@ -408,7 +555,7 @@ impl Observer for OtelObserver {
                     .checked_sub(duration)
                     .unwrap_or(SystemTime::now());
 
-                let mut span = tracer.build(
+                let mut span = tracer.build_with_context(
                     opentelemetry::trace::SpanBuilder::from_name("hand.run")
                         .with_kind(SpanKind::Internal)
                         .with_start_time(start_time)
@@ -418,6 +565,7 @@ impl Observer for OtelObserver {
                             KeyValue::new("error.message", error.clone()),
                             KeyValue::new("duration_s", secs),
                         ]),
+                    &parent,
                 );
                 span.set_status(Status::error(error.clone()));
                 span.end();
@@ -436,6 +584,46 @@ impl Observer for OtelObserver {
             | ObserverEvent::RecoveryCompleted { .. } => {
                 // DORA deployment events: OTel pass-through not yet implemented.
             }
+            ObserverEvent::ResourceLimitExceeded {
+                tool,
+                resource,
+                detail,
+            } => {
+                let mut span = tracer.build_with_context(
+                    opentelemetry::trace::SpanBuilder::from_name("resource_limit")
+                        .with_kind(SpanKind::Internal)
+                        .with_attributes(vec![
+                            KeyValue::new("tool.name", tool.clone()),
+                            KeyValue::new("resource", resource.clone()),
+                        ]),
+                    &parent,
+                );
+                span.set_status(Status::error(detail.clone()));
+                span.end();
+
+                self.errors.add(1, &[KeyValue::new("component", "runtime")]);
+            }
+            ObserverEvent::BudgetDowngrade {
+                requested_model,
+                routed_model,
+                period,
+                current_usd,
+                limit_usd,
+            } => {
+                let mut span = tracer.build_with_context(
+                    opentelemetry::trace::SpanBuilder::from_name("budget_downgrade")
+                        .with_kind(SpanKind::Internal)
+                        .with_attributes(vec![
+                            KeyValue::new("requested_model", requested_model.clone()),
+                            KeyValue::new("routed_model", routed_model.clone()),
+                            KeyValue::new("budget.period", period.clone()),
+                            KeyValue::new("budget.current_usd", *current_usd),
+                            KeyValue::new("budget.limit_usd", *limit_usd),
+                        ]),
+                    &parent,
+                );
+                span.end();
+            }
         }
     }
 
@@ -489,6 +677,9 @@ impl Observer for OtelObserver {
         if let Err(e) = self.meter_provider.force_flush() {
             tracing::warn!("OTel metric flush failed: {e}");
         }
+        if let Err(e) = self.logger_provider.force_flush() {
+            tracing::warn!("OTel log flush failed: {e}");
+        }
     }
 
     fn name(&self) -> &str {
@@ -681,6 +872,32 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn otel_exports_nested_trace_context_spans_without_panic() {
+        let obs = test_observer();
+        let mut turn = trace_context::TraceSpan::start(
+            "invoke_agent zeroclaw",
+            trace_context::SpanKind::Internal,
+        )
+        .with_attribute("gen_ai.operation.name", "invoke_agent");
+        turn.scope(async {
+            let mut chat =
+                trace_context::TraceSpan::start("chat gpt-4o", trace_context::SpanKind::Client)
+                    .with_attribute("gen_ai.usage.input_tokens", 12u64);
+            chat.set_error("timeout");
+            chat.end();
+            tracing::info!("logged inside a turn");
+            obs.record_event(&ObserverEvent::Error {
+                component: "provider".into(),
+                message: "timeout".into(),
+            });
+        })
+        .await;
+        turn.set_error("failed");
+        turn.end();
+        obs.flush();
+    }
+
     #[test]
     fn otel_observer_creation_with_valid_endpoint_succeeds() {
         // Even though endpoint is unreachable, creation should succeed
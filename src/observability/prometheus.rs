@@ -16,6 +16,8 @@ pub struct PrometheusObserver {
     channel_messages: IntCounterVec,
     heartbeat_ticks: prometheus::IntCounter,
     errors: IntCounterVec,
+    resource_limits: IntCounterVec,
+    budget_downgrades: IntCounterVec,
     cache_hits: IntCounterVec,
     cache_misses: IntCounterVec,
     cache_tokens_saved: IntCounterVec,
@@ -98,6 +100,24 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let resource_limits = IntCounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_resource_limit_exceeded_total",
+                "Tool subprocesses stopped or hampered by a cgroup limit",
+            ),
+            &["tool", "resource"],
+        )
+        .expect("valid metric");
+
+        let budget_downgrades = IntCounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_budget_downgrades_total",
+                "Requests rerouted to a cheaper model as spend neared a budget limit",
+            ),
+            &["requested_model", "routed_model", "period"],
+        )
+        .expect("valid metric");
+
         let cache_hits = IntCounterVec::new(
             prometheus::Opts::new("zeroclaw_cache_hits_total", "Total response cache hits"),
             &["cache_type"],
@@ -240,6 +260,8 @@ impl PrometheusObserver {
         registry.register(Box::new(channel_messages.clone())).ok();
         registry.register(Box::new(heartbeat_ticks.clone())).ok();
         registry.register(Box::new(errors.clone())).ok();
+        registry.register(Box::new(resource_limits.clone())).ok();
+        registry.register(Box::new(budget_downgrades.clone())).ok();
         registry.register(Box::new(cache_hits.clone())).ok();
         registry.register(Box::new(cache_misses.clone())).ok();
         registry.register(Box::new(cache_tokens_saved.clone())).ok();
@@ -272,6 +294,8 @@ impl PrometheusObserver {
             channel_messages,
             heartbeat_ticks,
             errors,
+            resource_limits,
+            budget_downgrades,
             cache_hits,
             cache_misses,
             cache_tokens_saved,
@@ -440,6 +464,25 @@ impl Observer for PrometheusObserver {
                     self.deployment_failure_rate.set(f as f64 / total as f64);
                 }
             }
+            ObserverEvent::ResourceLimitExceeded { tool, resource, .. } => {
+                self.resource_limits
+                    .with_label_values(&[tool.as_str(), resource.as_str()])
+                    .inc();
+            }
+            ObserverEvent::BudgetDowngrade {
+                requested_model,
+                routed_model,
+                period,
+                ..
+            } => {
+                self.budget_downgrades
+                    .with_label_values(&[
+                        requested_model.as_str(),
+                        routed_model.as_str(),
+                        period.as_str(),
+                    ])
+                    .inc();
+            }
             ObserverEvent::DeploymentFailed { .. } => {
                 self.deployments_total.with_label_values(&["failure"]).inc();
                 let f = self
@@ -0,0 +1,365 @@
+//! W3C trace context and span hierarchy for agent work.
+//!
+//! Spans (channel message → agent turn → LLM call / tool call → delegated
+//! sub-agent) are tracked with a task-local [`TraceContext`] so nested work
+//! picks up its parent without threading ids through every signature. The
+//! module has no OpenTelemetry dependency: finished spans are handed to an
+//! optional process-wide sink (installed by the OTel observer), and the
+//! current context can be injected into outgoing requests as a
+//! `traceparent` header whether or not a sink is installed.
+
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+/// Header name for W3C trace-context propagation.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// Identifies one span within a trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    /// Whether the span came from another process via `traceparent`.
+    pub remote: bool,
+}
+
+impl TraceContext {
+    /// Start a new trace.
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: non_zero_bytes(),
+            span_id: non_zero_bytes(),
+            remote: false,
+        }
+    }
+
+    /// A new span in the same trace.
+    pub fn new_child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id,
+            span_id: non_zero_bytes(),
+            remote: false,
+        }
+    }
+
+    /// Render as a W3C `traceparent` value (always sampled).
+    pub fn traceparent(&self) -> String {
+        format!(
+            "00-{}-{}-01",
+            hex::encode(self.trace_id),
+            hex::encode(self.span_id)
+        )
+    }
+
+    /// Parse a W3C `traceparent` value received from another process.
+    pub fn from_traceparent(value: &str) -> Option<Self> {
+        let mut parts = value.trim().split('-');
+        let version = parts.next()?;
+        let trace_id = parts.next()?;
+        let span_id = parts.next()?;
+        let flags = parts.next()?;
+        if version.len() != 2 || version == "ff" || flags.len() != 2 {
+            return None;
+        }
+        if version == "00" && parts.next().is_some() {
+            return None;
+        }
+        let mut ctx = Self {
+            trace_id: [0; 16],
+            span_id: [0; 8],
+            remote: true,
+        };
+        hex::decode_to_slice(trace_id, &mut ctx.trace_id).ok()?;
+        hex::decode_to_slice(span_id, &mut ctx.span_id).ok()?;
+        if ctx.trace_id == [0; 16] || ctx.span_id == [0; 8] {
+            return None;
+        }
+        Some(ctx)
+    }
+
+    pub fn trace_id_hex(&self) -> String {
+        hex::encode(self.trace_id)
+    }
+}
+
+fn non_zero_bytes<const N: usize>() -> [u8; N] {
+    loop {
+        let bytes: [u8; N] = rand::random();
+        if bytes != [0; N] {
+            return bytes;
+        }
+    }
+}
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+    static CONVERSATION: String;
+}
+
+/// The span context of the work currently running on this task, if any.
+pub fn current() -> Option<TraceContext> {
+    CURRENT.try_with(|ctx| *ctx).ok()
+}
+
+/// `traceparent` value for outgoing requests made from the current span.
+pub fn current_traceparent() -> Option<String> {
+    current().map(|ctx| ctx.traceparent())
+}
+
+/// Run `fut` with `ctx` as the current span context. Used to carry the
+/// caller's context into spawned tasks, which do not inherit task-locals.
+pub async fn scope_with<F: Future>(ctx: Option<TraceContext>, fut: F) -> F::Output {
+    match ctx {
+        Some(ctx) => CURRENT.scope(ctx, fut).await,
+        None => fut.await,
+    }
+}
+
+/// The conversation the current turn belongs to, if the entry point set one.
+pub fn current_conversation() -> Option<String> {
+    CONVERSATION.try_with(Clone::clone).ok()
+}
+
+/// Run `fut` with `conversation_id` reported as `gen_ai.conversation.id` on
+/// the agent spans it starts.
+pub async fn scope_conversation<F: Future>(conversation_id: String, fut: F) -> F::Output {
+    CONVERSATION.scope(conversation_id, fut).await
+}
+
+/// Role of a span relative to the process boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    Internal,
+    /// Handling a request that arrived from outside (e.g. a gateway webhook).
+    Server,
+    /// A call made to another service (e.g. an LLM provider).
+    Client,
+    /// Processing a message received from a channel.
+    Consumer,
+}
+
+/// Attribute value attached to a span.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpanValue {
+    Str(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+impl From<&str> for SpanValue {
+    fn from(value: &str) -> Self {
+        Self::Str(value.to_string())
+    }
+}
+
+impl From<String> for SpanValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl From<i64> for SpanValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<u64> for SpanValue {
+    fn from(value: u64) -> Self {
+        Self::Int(i64::try_from(value).unwrap_or(i64::MAX))
+    }
+}
+
+impl From<f64> for SpanValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<bool> for SpanValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// A finished span, as delivered to the sink.
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    pub name: String,
+    pub kind: SpanKind,
+    pub context: TraceContext,
+    pub parent: Option<TraceContext>,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub attributes: Vec<(&'static str, SpanValue)>,
+    /// Set when the span's work failed.
+    pub error: Option<String>,
+}
+
+type SpanSink = Box<dyn Fn(SpanRecord) + Send + Sync>;
+
+static SPAN_SINK: OnceLock<SpanSink> = OnceLock::new();
+
+/// Install the process-wide exporter for finished spans. Only the first
+/// call takes effect.
+pub fn set_span_sink(sink: impl Fn(SpanRecord) + Send + Sync + 'static) -> bool {
+    SPAN_SINK.set(Box::new(sink)).is_ok()
+}
+
+/// An in-progress span. It is exported when dropped, so early returns and
+/// cancelled futures still close it.
+#[derive(Debug)]
+pub struct TraceSpan {
+    record: Option<SpanRecord>,
+}
+
+impl TraceSpan {
+    /// Start a span as a child of the current context (or a new trace).
+    pub fn start(name: impl Into<String>, kind: SpanKind) -> Self {
+        Self::start_with_parent(name, kind, current())
+    }
+
+    /// Start a span under an explicit parent, e.g. one parsed from an
+    /// inbound `traceparent` header.
+    pub fn start_with_parent(
+        name: impl Into<String>,
+        kind: SpanKind,
+        parent: Option<TraceContext>,
+    ) -> Self {
+        let context = parent.map_or_else(TraceContext::new_root, |p| p.new_child());
+        Self {
+            record: Some(SpanRecord {
+                name: name.into(),
+                kind,
+                context,
+                parent,
+                start: SystemTime::now(),
+                end: SystemTime::now(),
+                attributes: Vec::new(),
+                error: None,
+            }),
+        }
+    }
+
+    pub fn context(&self) -> TraceContext {
+        self.record
+            .as_ref()
+            .map_or_else(TraceContext::new_root, |r| r.context)
+    }
+
+    #[must_use]
+    pub fn with_attribute(mut self, key: &'static str, value: impl Into<SpanValue>) -> Self {
+        self.set_attribute(key, value);
+        self
+    }
+
+    pub fn set_attribute(&mut self, key: &'static str, value: impl Into<SpanValue>) {
+        if let Some(record) = self.record.as_mut() {
+            record.attributes.push((key, value.into()));
+        }
+    }
+
+    /// Mark the span as failed.
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        if let Some(record) = self.record.as_mut() {
+            record.error = Some(message.into());
+        }
+    }
+
+    /// Run `fut` with this span as the current context.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        CURRENT.scope(self.context(), fut).await
+    }
+
+    /// Close the span now.
+    pub fn end(self) {}
+}
+
+impl Drop for TraceSpan {
+    fn drop(&mut self) {
+        if let (Some(mut record), Some(sink)) = (self.record.take(), SPAN_SINK.get()) {
+            record.end = SystemTime::now();
+            sink(record);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traceparent_round_trips() {
+        let ctx = TraceContext::new_root();
+        let header = ctx.traceparent();
+        assert_eq!(header.len(), 55);
+        let parsed = TraceContext::from_traceparent(&header).unwrap();
+        assert_eq!(parsed.trace_id, ctx.trace_id);
+        assert_eq!(parsed.span_id, ctx.span_id);
+        assert!(parsed.remote);
+    }
+
+    #[test]
+    fn rejects_malformed_traceparent() {
+        for value in [
+            "",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331",
+            "00-00000000000000000000000000000000-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01",
+            "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra",
+            "00-zzf7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+        ] {
+            assert!(TraceContext::from_traceparent(value).is_none(), "{value}");
+        }
+        assert!(TraceContext::from_traceparent(
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01"
+        )
+        .is_some());
+    }
+
+    #[tokio::test]
+    async fn nested_spans_share_trace_and_link_parents() {
+        assert!(current().is_none());
+        let outer = TraceSpan::start("outer", SpanKind::Consumer);
+        let outer_ctx = outer.context();
+        let (inner_ctx, seen) = outer
+            .scope(async {
+                let inner = TraceSpan::start("inner", SpanKind::Internal);
+                let inner_ctx = inner.context();
+                let seen = inner.scope(async { current() }).await;
+                (inner_ctx, seen)
+            })
+            .await;
+        assert_eq!(inner_ctx.trace_id, outer_ctx.trace_id);
+        assert_ne!(inner_ctx.span_id, outer_ctx.span_id);
+        assert_eq!(seen, Some(inner_ctx));
+        assert!(current().is_none());
+    }
+
+    #[tokio::test]
+    async fn conversation_is_scoped_to_the_turn() {
+        assert!(current_conversation().is_none());
+        let seen =
+            scope_conversation("telegram_alice".into(), async { current_conversation() }).await;
+        assert_eq!(seen.as_deref(), Some("telegram_alice"));
+        assert!(current_conversation().is_none());
+    }
+
+    #[tokio::test]
+    async fn scope_with_carries_context_into_spawned_tasks() {
+        let span = TraceSpan::start("parent", SpanKind::Internal);
+        let ctx = span.context();
+        let seen = span
+            .scope(async {
+                let captured = current();
+                tokio::spawn(scope_with(captured, async { current() }))
+                    .await
+                    .unwrap()
+            })
+            .await;
+        assert_eq!(seen, Some(ctx));
+    }
+}
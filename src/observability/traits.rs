@@ -113,6 +113,29 @@ pub enum ObserverEvent {
     },
     /// Recovery from a failed deployment has completed.
     RecoveryCompleted { deploy_id: String },
+    /// A tool subprocess hit a cgroup resource limit.
+    ResourceLimitExceeded {
+        /// Tool whose subprocess was limited (e.g., `"shell"`).
+        tool: String,
+        /// Limited resource: `"memory"` or `"pids"`.
+        resource: String,
+        /// Human-readable description of the limit event.
+        detail: String,
+    },
+    /// The model router moved a request to a cheaper model because spend
+    /// neared a configured budget limit.
+    BudgetDowngrade {
+        /// Model the request resolved to before the downgrade.
+        requested_model: String,
+        /// Cheaper model the request was sent to.
+        routed_model: String,
+        /// Budget period nearing its limit: `"daily"` or `"monthly"`.
+        period: String,
+        /// Spend in the period so far, in USD.
+        current_usd: f64,
+        /// Configured limit for the period, in USD.
+        limit_usd: f64,
+    },
 }
 
 /// Numeric metrics emitted by the agent runtime.
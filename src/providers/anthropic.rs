@@ -1,7 +1,8 @@
+use crate::providers::prompt_cache::PromptCacheStrategy;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ProviderCapabilities, StreamChunk, StreamError, StreamEvent, StreamOptions,
-    StreamResult, TokenUsage, ToolCall as ProviderToolCall,
+    Provider, ProviderCapabilities, ResponseFormat, StreamChunk, StreamError, StreamEvent,
+    StreamOptions, StreamResult, TokenUsage, ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
@@ -18,6 +19,10 @@ pub struct AnthropicProvider {
 
 const DEFAULT_ANTHROPIC_MAX_TOKENS: u32 = 4096;
 
+/// Description of the forced tool used to emulate `response_format`.
+const STRUCTURED_OUTPUT_TOOL_DESCRIPTION: &str =
+    "Return the final response. The input is the complete answer in the required format.";
+
 #[derive(Debug, Serialize)]
 struct ChatRequest {
     model: String,
@@ -253,12 +258,12 @@ impl AnthropicProvider {
 
     /// Cache system prompts larger than ~1024 tokens (3KB of text)
     fn should_cache_system(text: &str) -> bool {
-        text.len() > 3072
+        PromptCacheStrategy::DEFAULT.should_cache_system(text)
     }
 
     /// Cache conversations with more than 1 non-system message (i.e. after first exchange)
     fn should_cache_conversation(messages: &[ChatMessage]) -> bool {
-        messages.iter().filter(|m| m.role != "system").count() > 1
+        PromptCacheStrategy::DEFAULT.should_cache_conversation(messages)
     }
 
     /// Apply cache control to the last message content block
@@ -299,6 +304,49 @@ impl AnthropicProvider {
         Some(native_tools)
     }
 
+    /// Tool input schemas must describe an object, so non-object schemas are
+    /// wrapped in a single required `value` property.
+    fn structured_output_wraps_value(format: &ResponseFormat) -> bool {
+        format.schema().is_some_and(|schema| {
+            let declared = schema.get("type").and_then(serde_json::Value::as_str);
+            !(declared == Some("object")
+                || (declared.is_none() && schema.get("properties").is_some()))
+        })
+    }
+
+    fn structured_output_tool_schema(format: &ResponseFormat) -> serde_json::Value {
+        match format.schema() {
+            None => serde_json::json!({ "type": "object" }),
+            Some(schema) if Self::structured_output_wraps_value(format) => serde_json::json!({
+                "type": "object",
+                "properties": { "value": schema },
+                "required": ["value"],
+            }),
+            Some(schema) => schema.clone(),
+        }
+    }
+
+    /// Replace the forced structured-output tool call with its input as text.
+    fn unwrap_structured_output(response: &mut ProviderChatResponse, format: &ResponseFormat) {
+        let Some(pos) = response
+            .tool_calls
+            .iter()
+            .position(|call| call.name == format.name())
+        else {
+            return;
+        };
+        let call = response.tool_calls.remove(pos);
+        let text = if Self::structured_output_wraps_value(format) {
+            serde_json::from_str::<serde_json::Value>(&call.arguments)
+                .ok()
+                .and_then(|mut input| input.get_mut("value").map(serde_json::Value::take))
+                .map_or(call.arguments, |value| value.to_string())
+        } else {
+            call.arguments
+        };
+        response.text = Some(text);
+    }
+
     fn parse_assistant_tool_call_message(content: &str) -> Option<Vec<NativeContentOut>> {
         let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
         let tool_calls = value
@@ -517,8 +565,14 @@ impl AnthropicProvider {
         let mut text_parts = Vec::new();
         let mut tool_calls = Vec::new();
 
+        // Anthropic reports cache reads and writes separately from
+        // `input_tokens`; fold them in so cached tokens are a subset.
         let usage = response.usage.map(|u| TokenUsage {
-            input_tokens: u.input_tokens,
+            input_tokens: u.input_tokens.map(|tokens| {
+                tokens
+                    + u.cache_creation_input_tokens.unwrap_or(0)
+                    + u.cache_read_input_tokens.unwrap_or(0)
+            }),
             output_tokens: u.output_tokens,
             cached_input_tokens: u.cache_read_input_tokens,
         });
@@ -792,12 +846,32 @@ impl Provider for AnthropicProvider {
             .try_with(Clone::clone)
             .ok()
             .flatten();
-        let native_tools = Self::convert_tools(request.tools);
-        let tool_choice = if native_tools.is_some() {
-            tool_choice_override.map(|tc| serde_json::json!({ "type": tc }))
-        } else {
-            None
-        };
+        // Structured output is emulated with a single forced tool whose input
+        // schema is the requested schema; its input becomes the response text.
+        let structured_schema = request
+            .response_format
+            .map(Self::structured_output_tool_schema);
+        let (native_tools, tool_choice) =
+            match (request.response_format, structured_schema.as_ref()) {
+                (Some(format), Some(input_schema)) => (
+                    Some(vec![NativeToolSpec {
+                        name: format.name(),
+                        description: STRUCTURED_OUTPUT_TOOL_DESCRIPTION,
+                        input_schema,
+                        cache_control: None,
+                    }]),
+                    Some(serde_json::json!({ "type": "tool", "name": format.name() })),
+                ),
+                _ => {
+                    let native_tools = Self::convert_tools(request.tools);
+                    let tool_choice = if native_tools.is_some() {
+                        tool_choice_override.map(|tc| serde_json::json!({ "type": tc }))
+                    } else {
+                        None
+                    };
+                    (native_tools, tool_choice)
+                }
+            };
 
         // For OAuth tokens, prepend Claude Code identity to system prompt
         let system_prompt = if Self::is_setup_token(credential) {
@@ -829,7 +903,11 @@ impl Provider for AnthropicProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        Ok(Self::parse_native_response(native_response))
+        let mut result = Self::parse_native_response(native_response);
+        if let Some(format) = request.response_format {
+            Self::unwrap_structured_output(&mut result, format);
+        }
+        Ok(result)
     }
 
     fn capabilities(&self) -> ProviderCapabilities {
@@ -837,6 +915,7 @@ impl Provider for AnthropicProvider {
             native_tool_calling: true,
             vision: true,
             prompt_caching: true,
+            structured_output: true,
         }
     }
 
@@ -887,6 +966,7 @@ impl Provider for AnthropicProvider {
             } else {
                 Some(&tool_specs)
             },
+            response_format: None,
         };
         self.chat(request, model, temperature).await
     }
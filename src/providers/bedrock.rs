@@ -6,6 +6,7 @@
 //!   via environment variables or EC2 IMDSv2. SigV4 signing is implemented
 //!   manually using hmac/sha2 crates — no AWS SDK dependency.
 
+use crate::providers::prompt_cache::PromptCacheStrategy;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
     Provider, ProviderCapabilities, TokenUsage, ToolCall as ProviderToolCall, ToolsPayload,
@@ -23,6 +24,22 @@ const ENDPOINT_PREFIX: &str = "bedrock-runtime";
 const SIGNING_SERVICE: &str = "bedrock";
 const DEFAULT_REGION: &str = "us-east-1";
 const DEFAULT_MAX_TOKENS: u32 = 4096;
+/// Bedrock allows at most four cache points per request, so the history
+/// breakpoint waits until the conversation has a few turns to reuse.
+const CACHE_STRATEGY: PromptCacheStrategy = PromptCacheStrategy {
+    min_conversation_messages: 5,
+    ..PromptCacheStrategy::DEFAULT
+};
+/// Models whose Converse API accepts a `cachePoint` in `toolConfig`; others
+/// (e.g. Amazon Nova) reject the request. Matched anywhere in the model id so
+/// cross-region inference profiles (`us.anthropic...`) qualify.
+const TOOL_CACHE_MODELS: &[&str] = &[
+    "anthropic.claude-3-5-haiku",
+    "anthropic.claude-3-7-sonnet",
+    "anthropic.claude-sonnet-4",
+    "anthropic.claude-opus-4",
+    "anthropic.claude-haiku-4",
+];
 
 // ── Authentication ──────────────────────────────────────────────
 
@@ -381,7 +398,15 @@ struct InferenceConfig {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ToolConfig {
-    tools: Vec<ToolDefinition>,
+    tools: Vec<ToolEntry>,
+}
+
+/// Tool list entries: either `{"toolSpec": {...}}` or `{"cachePoint": {...}}`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum ToolEntry {
+    Tool(ToolDefinition),
+    CachePoint(CachePointWrapper),
 }
 
 #[derive(Debug, Serialize)]
@@ -424,6 +449,10 @@ struct BedrockUsage {
     input_tokens: Option<u64>,
     #[serde(default)]
     output_tokens: Option<u64>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    cache_write_input_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -575,16 +604,27 @@ impl BedrockProvider {
         Ok(BedrockAuth::SigV4(AwsCredentials::from_imds().await?))
     }
 
-    // ── Cache heuristics (same thresholds as AnthropicProvider) ──
+    // ── Cache heuristics ────────────────────────────────────────
 
     /// Cache system prompts larger than ~1024 tokens (3KB of text).
     fn should_cache_system(text: &str) -> bool {
-        text.len() > 3072
+        CACHE_STRATEGY.should_cache_system(text)
     }
 
     /// Cache conversations with more than 4 messages (excluding system).
     fn should_cache_conversation(messages: &[ChatMessage]) -> bool {
-        messages.iter().filter(|m| m.role != "system").count() > 4
+        CACHE_STRATEGY.should_cache_conversation(messages)
+    }
+
+    /// Cache the tool list when the plan asks for it and the model supports
+    /// tool cache points.
+    fn should_cache_tools(
+        model: &str,
+        messages: &[ChatMessage],
+        tools: Option<&[ToolSpec]>,
+    ) -> bool {
+        CACHE_STRATEGY.plan(messages, tools).tools
+            && TOOL_CACHE_MODELS.iter().any(|m| model.contains(m))
     }
 
     // ── Message conversion ──────────────────────────────────────
@@ -874,16 +914,18 @@ impl BedrockProvider {
         if items.is_empty() {
             return None;
         }
-        let tool_defs: Vec<ToolDefinition> = items
+        let tool_defs: Vec<ToolEntry> = items
             .iter()
-            .map(|tool| ToolDefinition {
-                tool_spec: ToolSpecDef {
-                    name: tool.name.clone(),
-                    description: tool.description.clone(),
-                    input_schema: InputSchema {
-                        json: tool.parameters.clone(),
+            .map(|tool| {
+                ToolEntry::Tool(ToolDefinition {
+                    tool_spec: ToolSpecDef {
+                        name: tool.name.clone(),
+                        description: tool.description.clone(),
+                        input_schema: InputSchema {
+                            json: tool.parameters.clone(),
+                        },
                     },
-                },
+                })
             })
             .collect();
         Some(ToolConfig { tools: tool_defs })
@@ -895,10 +937,16 @@ impl BedrockProvider {
         let mut text_parts = Vec::new();
         let mut tool_calls = Vec::new();
 
+        // `inputTokens` excludes cache reads and writes; fold them in so
+        // cached tokens are a subset of the input count.
         let usage = response.usage.map(|u| TokenUsage {
-            input_tokens: u.input_tokens,
+            input_tokens: u.input_tokens.map(|tokens| {
+                tokens
+                    + u.cache_read_input_tokens.unwrap_or(0)
+                    + u.cache_write_input_tokens.unwrap_or(0)
+            }),
             output_tokens: u.output_tokens,
-            cached_input_tokens: None,
+            cached_input_tokens: u.cache_read_input_tokens,
         });
 
         if let Some(output) = response.output {
@@ -1052,7 +1100,8 @@ impl Provider for BedrockProvider {
         ProviderCapabilities {
             native_tool_calling: true,
             vision: true,
-            prompt_caching: false,
+            prompt_caching: true,
+            structured_output: false,
         }
     }
 
@@ -1151,7 +1200,16 @@ impl Provider for BedrockProvider {
             }
         }
 
-        let tool_config = Self::convert_tools_to_converse(request.tools);
+        // Tools sit between the system prompt and messages in the cached
+        // prefix, so they get their own breakpoint where the model allows it.
+        let mut tool_config = Self::convert_tools_to_converse(request.tools);
+        if Self::should_cache_tools(model, request.messages, request.tools) {
+            if let Some(config) = tool_config.as_mut() {
+                config.tools.push(ToolEntry::CachePoint(CachePointWrapper {
+                    cache_point: CachePoint::default_cache(),
+                }));
+            }
+        }
 
         let converse_request = ConverseRequest {
             system,
@@ -1568,6 +1626,31 @@ mod tests {
         assert!(BedrockProvider::should_cache_system(&"a".repeat(3073)));
     }
 
+    #[test]
+    fn should_cache_tools_only_for_supporting_models() {
+        let messages = vec![ChatMessage::user("Hello")];
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        assert!(BedrockProvider::should_cache_tools(
+            "us.anthropic.claude-sonnet-4-20250514-v1:0",
+            &messages,
+            Some(&tools)
+        ));
+        assert!(!BedrockProvider::should_cache_tools(
+            "amazon.nova-pro-v1:0",
+            &messages,
+            Some(&tools)
+        ));
+        assert!(!BedrockProvider::should_cache_tools(
+            "anthropic.claude-sonnet-4-20250514-v1:0",
+            &messages,
+            Some(&[])
+        ));
+    }
+
     #[test]
     fn should_cache_conversation_short() {
         let messages = vec![
@@ -1603,7 +1686,7 @@ mod tests {
         assert!(config.is_some());
         let config = config.unwrap();
         assert_eq!(config.tools.len(), 1);
-        assert_eq!(config.tools[0].tool_spec.name, "shell");
+        assert!(matches!(&config.tools[0], ToolEntry::Tool(def) if def.tool_spec.name == "shell"));
     }
 
     #[test]
@@ -1751,6 +1834,32 @@ mod tests {
         };
         let caps = provider.capabilities();
         assert!(caps.native_tool_calling);
+        assert!(caps.prompt_caching);
+    }
+
+    #[test]
+    fn tool_config_serializes_cache_point_entry() {
+        let config = ToolConfig {
+            tools: vec![ToolEntry::CachePoint(CachePointWrapper {
+                cache_point: CachePoint::default_cache(),
+            })],
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(json, r#"{"tools":[{"cachePoint":{"type":"default"}}]}"#);
+    }
+
+    #[test]
+    fn converse_response_reports_cached_input_tokens() {
+        let json = r#"{
+            "output": {"message": {"role": "assistant", "content": [{"text": {"text": "Hi"}}]}},
+            "usage": {"inputTokens": 20, "outputTokens": 5, "cacheReadInputTokens": 3000, "cacheWriteInputTokens": 100}
+        }"#;
+        let resp: ConverseResponse = serde_json::from_str(json).unwrap();
+        let usage = BedrockProvider::parse_converse_response(resp)
+            .usage
+            .unwrap();
+        assert_eq!(usage.input_tokens, Some(3120));
+        assert_eq!(usage.cached_input_tokens, Some(3000));
     }
 
     #[test]
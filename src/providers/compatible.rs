@@ -4,9 +4,9 @@
 
 use crate::multimodal;
 use crate::providers::traits::{
-    ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamEvent, StreamOptions, StreamResult, TokenUsage,
-    ToolCall as ProviderToolCall,
+    append_system_instructions, ChatMessage, ChatRequest as ProviderChatRequest,
+    ChatResponse as ProviderChatResponse, Provider, ResponseFormat, StreamChunk, StreamError,
+    StreamEvent, StreamOptions, StreamResult, TokenUsage, ToolCall as ProviderToolCall,
 };
 use async_trait::async_trait;
 use futures_util::{stream, StreamExt};
@@ -633,6 +633,8 @@ struct NativeChatRequest {
     tool_choice: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
@@ -1555,6 +1557,24 @@ impl OpenAiCompatibleProvider {
     }
 }
 
+impl OpenAiCompatibleProvider {
+    /// `response_format` payload for endpoints that honour it; prompt-guided
+    /// endpoints reject the field, so they get instructions in `messages`.
+    fn response_format_payload(
+        &self,
+        messages: &mut Vec<ChatMessage>,
+        format: Option<&ResponseFormat>,
+    ) -> Option<serde_json::Value> {
+        let format = format?;
+        if self.supports_structured_output() {
+            Some(format.to_openai_payload())
+        } else {
+            append_system_instructions(messages, &format.instructions());
+            None
+        }
+    }
+}
+
 #[async_trait]
 impl Provider for OpenAiCompatibleProvider {
     fn capabilities(&self) -> crate::providers::traits::ProviderCapabilities {
@@ -1562,6 +1582,9 @@ impl Provider for OpenAiCompatibleProvider {
             native_tool_calling: self.native_tool_calling,
             vision: self.supports_vision,
             prompt_caching: false,
+            // Endpoints that accept OpenAI-style native tools also accept
+            // `response_format`; prompt-guided ones get instructions instead.
+            structured_output: self.native_tool_calling,
         }
     }
 
@@ -1941,10 +1964,12 @@ impl Provider for OpenAiCompatibleProvider {
         })?;
 
         let tools = Self::convert_tool_specs(request.tools);
+        let mut messages = request.messages.to_vec();
+        let response_format = self.response_format_payload(&mut messages, request.response_format);
         let effective_messages = if self.merge_system_into_user {
-            Self::flatten_system_messages(request.messages)
+            Self::flatten_system_messages(&messages)
         } else {
-            request.messages.to_vec()
+            messages
         };
         let native_request = NativeChatRequest {
             model: model.to_string(),
@@ -1960,6 +1985,7 @@ impl Provider for OpenAiCompatibleProvider {
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
             max_tokens: self.max_tokens,
+            response_format,
         };
 
         let url = self.chat_completions_url();
@@ -2113,6 +2139,7 @@ impl Provider for OpenAiCompatibleProvider {
                 tools: tools.clone(),
                 tool_choice: tools.as_ref().map(|_| "auto".to_string()),
                 max_tokens: self.max_tokens,
+                response_format: None,
             })
         } else {
             let messages = effective_messages
@@ -2433,6 +2460,24 @@ mod tests {
         OpenAiCompatibleProvider::new(name, url, key, AuthStyle::Bearer)
     }
 
+    #[test]
+    fn response_format_is_prompted_for_endpoints_without_structured_output() {
+        let format = ResponseFormat::JsonObject;
+        let native = make_provider("test", "https://example.com", Some("k"));
+        let mut messages = vec![ChatMessage::user("hi")];
+        assert!(native
+            .response_format_payload(&mut messages, Some(&format))
+            .is_some());
+        assert_eq!(messages.len(), 1);
+
+        let guided = make_provider("test", "https://example.com", Some("k")).without_native_tools();
+        let mut messages = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        assert!(guided
+            .response_format_payload(&mut messages, Some(&format))
+            .is_none());
+        assert!(messages[0].content.contains("## Response Format"));
+    }
+
     #[test]
     fn creates_with_key() {
         let p = make_provider(
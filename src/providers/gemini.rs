@@ -3,16 +3,26 @@
 //! - Gemini CLI OAuth tokens (reuse existing ~/.gemini/ authentication)
 //! - ZeroClaw auth-profiles OAuth tokens
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
+//!
+//! With API-key auth, the stable part of a long system instruction is moved
+//! into a `cachedContents` resource and referenced by name on later turns;
+//! the per-turn date/time tail is sent with the conversation instead.
 
 use crate::auth::AuthService;
-use crate::providers::traits::{ChatMessage, Provider, TokenUsage};
+use crate::providers::prompt_cache::{self, PromptCacheStrategy};
+use crate::providers::traits::{
+    append_system_instructions, build_tool_instructions_text, ChatMessage, ChatRequest,
+    ChatResponse, Provider, ResponseFormat, TokenUsage,
+};
 use async_trait::async_trait;
 use base64::Engine;
 use directories::UserDirs;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 /// Gemini provider supporting multiple authentication methods.
 pub struct GeminiProvider {
@@ -24,6 +34,51 @@ pub struct GeminiProvider {
     auth_service: Option<AuthService>,
     /// Override profile name for managed auth.
     auth_profile_override: Option<String>,
+    /// `cachedContents` resources keyed by [`cache_key`], shared by every
+    /// provider instance since callers rebuild providers per request.
+    cached_contents: Arc<tokio::sync::Mutex<HashMap<String, CachedPrefix>>>,
+}
+
+type CachedContents = Arc<tokio::sync::Mutex<HashMap<String, CachedPrefix>>>;
+
+/// The process-wide `cachedContents` registry.
+fn shared_cached_contents() -> CachedContents {
+    static CACHED_CONTENTS: OnceLock<CachedContents> = OnceLock::new();
+    CACHED_CONTENTS
+        .get_or_init(|| Arc::new(tokio::sync::Mutex::new(HashMap::new())))
+        .clone()
+}
+
+/// Registry key for a cached system prefix. Resources belong to the API key
+/// that created them, so the key is part of the hash.
+fn cache_key(auth: &GeminiAuth, model: &str, stable_prefix: &str) -> String {
+    prompt_cache::prefix_key(model, &[auth.api_key_credential(), stable_prefix])
+}
+
+/// A cached system prefix attached to a request.
+#[derive(Debug, PartialEq, Eq)]
+struct CacheAttachment {
+    key: String,
+    /// The volatile tail was inserted as the first entry of `contents`.
+    moved_tail: bool,
+}
+
+/// State of the server-side context cache for one system instruction.
+enum CachedPrefix {
+    /// Resource name to send as `cachedContent` until it expires.
+    Ready { name: String, expires_at: Instant },
+    /// Creation or use was rejected (e.g. the prefix is below the model's
+    /// minimum); send the instruction inline until `until`.
+    Unavailable { until: Instant },
+}
+
+impl CachedPrefix {
+    fn is_current(&self, now: Instant) -> bool {
+        match self {
+            CachedPrefix::Ready { expires_at, .. } => *expires_at > now,
+            CachedPrefix::Unavailable { until } => *until > now,
+        }
+    }
 }
 
 /// Mutable OAuth token state — supports runtime refresh for long-lived processes.
@@ -87,10 +142,27 @@ struct GenerateContentRequest {
     contents: Vec<Content>,
     #[serde(rename = "systemInstruction", skip_serializing_if = "Option::is_none")]
     system_instruction: Option<Content>,
+    /// `cachedContents/...` resource holding the system instruction.
+    #[serde(rename = "cachedContent", skip_serializing_if = "Option::is_none")]
+    cached_content: Option<String>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
 }
 
+/// Body for `POST /v1beta/cachedContents`.
+#[derive(Debug, Serialize)]
+struct CreateCachedContentRequest<'a> {
+    model: String,
+    #[serde(rename = "systemInstruction")]
+    system_instruction: &'a Content,
+    ttl: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedContentResponse {
+    name: String,
+}
+
 /// Request envelope for the internal cloudcode-pa API.
 /// OAuth tokens from Gemini CLI are scoped for this endpoint.
 ///
@@ -185,11 +257,54 @@ fn build_parts(content: &str) -> Vec<Part> {
     parts
 }
 
+/// Split a conversation into Gemini `contents` and a merged system instruction.
+fn convert_messages(messages: &[ChatMessage]) -> (Vec<Content>, Option<Content>) {
+    let mut system_parts: Vec<&str> = Vec::new();
+    let mut contents: Vec<Content> = Vec::new();
+
+    for msg in messages {
+        match msg.role.as_str() {
+            "system" => {
+                system_parts.push(&msg.content);
+            }
+            "user" => {
+                contents.push(Content {
+                    role: Some("user".to_string()),
+                    parts: build_parts(&msg.content),
+                });
+            }
+            "assistant" => {
+                // Gemini API uses "model" role instead of "assistant"
+                contents.push(Content {
+                    role: Some("model".to_string()),
+                    parts: vec![Part::text(&msg.content)],
+                });
+            }
+            _ => {}
+        }
+    }
+
+    let system_instruction = if system_parts.is_empty() {
+        None
+    } else {
+        Some(Content {
+            role: None,
+            parts: vec![Part::text(system_parts.join("\n\n"))],
+        })
+    };
+
+    (contents, system_instruction)
+}
+
 #[derive(Debug, Serialize, Clone)]
 struct GenerationConfig {
     temperature: f64,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: u32,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(rename = "responseJsonSchema", skip_serializing_if = "Option::is_none")]
+    response_json_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -208,6 +323,9 @@ struct GeminiUsageMetadata {
     prompt_token_count: Option<u64>,
     #[serde(default, rename = "candidatesTokenCount")]
     candidates_token_count: Option<u64>,
+    /// Part of `promptTokenCount` served from the context cache.
+    #[serde(default, rename = "cachedContentTokenCount")]
+    cached_content_token_count: Option<u64>,
 }
 
 /// Response envelope for the internal cloudcode-pa API.
@@ -330,6 +448,19 @@ const LOAD_CODE_ASSIST_ENDPOINT: &str =
 /// Public API endpoint for API key users.
 const PUBLIC_API_ENDPOINT: &str = "https://generativelanguage.googleapis.com/v1beta";
 
+/// Explicit context caching needs up to 4096 tokens of prefix depending on
+/// the model, so only clearly large system instructions are cached.
+const CACHE_STRATEGY: PromptCacheStrategy = PromptCacheStrategy {
+    min_system_chars: 16_384,
+    ..PromptCacheStrategy::DEFAULT
+};
+
+/// Lifetime requested for `cachedContents` resources.
+const CACHED_CONTENT_TTL_SECS: u64 = 3600;
+
+/// How long to send a system instruction inline after caching it failed.
+const CACHED_CONTENT_RETRY_SECS: u64 = 600;
+
 // ══════════════════════════════════════════════════════════════════════════════
 // TOKEN REFRESH
 // ══════════════════════════════════════════════════════════════════════════════
@@ -495,6 +626,7 @@ impl GeminiProvider {
             oauth_index: Arc::new(tokio::sync::Mutex::new(0)),
             auth_service: None,
             auth_profile_override: None,
+            cached_contents: shared_cached_contents(),
         }
     }
 
@@ -568,6 +700,7 @@ impl GeminiProvider {
                 None
             },
             auth_profile_override: profile_override,
+            cached_contents: shared_cached_contents(),
         }
     }
 
@@ -983,6 +1116,7 @@ impl GeminiProvider {
         system_instruction: Option<Content>,
         model: &str,
         temperature: f64,
+        response_format: Option<&ResponseFormat>,
     ) -> anyhow::Result<(String, Option<TokenUsage>)> {
         let auth = self.auth.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
@@ -1026,16 +1160,21 @@ impl GeminiProvider {
             _ => (None, None),
         };
 
-        let request = GenerateContentRequest {
+        let mut request = GenerateContentRequest {
             contents,
             system_instruction,
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature,
                 max_output_tokens: 8192,
+                response_mime_type: response_format.map(|_| "application/json".to_string()),
+                response_json_schema: response_format.and_then(ResponseFormat::schema).cloned(),
             },
         };
 
         let url = Self::build_generate_content_url(model, auth);
+        let inline_system_instruction = request.system_instruction.clone();
+        let cache_attachment = self.attach_cached_content(auth, model, &mut request).await;
 
         let mut response = self
             .build_generate_content_request(
@@ -1050,6 +1189,36 @@ impl GeminiProvider {
             .send()
             .await?;
 
+        // An expired or rejected cache must not fail the turn: fall back to
+        // the inline system instruction once.
+        if let Some(attachment) = cache_attachment.as_ref() {
+            let status = response.status();
+            if status.is_client_error() && status != reqwest::StatusCode::TOO_MANY_REQUESTS {
+                let error_text = response.text().await.unwrap_or_default();
+                tracing::warn!(
+                    "Gemini rejected cached content ({status}): {error_text}; retrying with inline system instruction"
+                );
+                self.mark_cache_unavailable(&attachment.key).await;
+                request.cached_content = None;
+                request.system_instruction = inline_system_instruction;
+                if attachment.moved_tail {
+                    request.contents.remove(0);
+                }
+                response = self
+                    .build_generate_content_request(
+                        auth,
+                        &url,
+                        &request,
+                        model,
+                        true,
+                        project.as_deref(),
+                        oauth_token.as_deref(),
+                    )
+                    .send()
+                    .await?;
+            }
+        }
+
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
@@ -1174,7 +1343,7 @@ impl GeminiProvider {
         let usage = result.usage_metadata.map(|u| TokenUsage {
             input_tokens: u.prompt_token_count,
             output_tokens: u.candidates_token_count,
-            cached_input_tokens: None,
+            cached_input_tokens: u.cached_content_token_count,
         });
 
         let text = result
@@ -1186,6 +1355,136 @@ impl GeminiProvider {
 
         Ok((text, usage))
     }
+
+    /// Move the stable prefix of a long system instruction into a
+    /// `cachedContents` resource and reference it from `request`. A request
+    /// using cached content cannot carry a system instruction, so the
+    /// volatile date/time tail goes first in `contents`. Only the public API
+    /// (API-key auth) supports explicit caching.
+    async fn attach_cached_content(
+        &self,
+        auth: &GeminiAuth,
+        model: &str,
+        request: &mut GenerateContentRequest,
+    ) -> Option<CacheAttachment> {
+        if !auth.is_api_key() {
+            return None;
+        }
+        let system = request.system_instruction.as_ref()?;
+        let text = content_text(system);
+        let (stable, tail) = prompt_cache::split_volatile_suffix(&text);
+        if !CACHE_STRATEGY.should_cache_system(stable) {
+            return None;
+        }
+        let key = cache_key(auth, model, stable);
+        let now = Instant::now();
+
+        let cached = match self.cached_contents.lock().await.get(&key) {
+            Some(CachedPrefix::Ready { name, expires_at }) if *expires_at > now => {
+                Some(Some(name.clone()))
+            }
+            Some(CachedPrefix::Unavailable { until }) if *until > now => Some(None),
+            _ => None,
+        };
+        let name = match cached {
+            Some(name) => name,
+            None => {
+                let stable_content = Content {
+                    role: system.role.clone(),
+                    parts: vec![Part::text(stable)],
+                };
+                let entry = match self
+                    .create_cached_content(auth, model, &stable_content)
+                    .await
+                {
+                    Ok(name) => CachedPrefix::Ready {
+                        name,
+                        // Leave headroom so a request never races the expiry.
+                        expires_at: now + Duration::from_secs(CACHED_CONTENT_TTL_SECS - 60),
+                    },
+                    Err(error) => {
+                        tracing::debug!("Gemini context cache unavailable for {model}: {error}");
+                        CachedPrefix::Unavailable {
+                            until: now + Duration::from_secs(CACHED_CONTENT_RETRY_SECS),
+                        }
+                    }
+                };
+                let name = match &entry {
+                    CachedPrefix::Ready { name, .. } => Some(name.clone()),
+                    CachedPrefix::Unavailable { .. } => None,
+                };
+                let mut entries = self.cached_contents.lock().await;
+                entries.retain(|_, entry| entry.is_current(now));
+                entries.insert(key.clone(), entry);
+                name
+            }
+        }?;
+
+        request.cached_content = Some(name);
+        request.system_instruction = None;
+        let moved_tail = !tail.trim().is_empty();
+        if moved_tail {
+            request.contents.insert(
+                0,
+                Content {
+                    role: Some("user".to_string()),
+                    parts: vec![Part::text(tail.trim())],
+                },
+            );
+        }
+        Some(CacheAttachment { key, moved_tail })
+    }
+
+    async fn create_cached_content(
+        &self,
+        auth: &GeminiAuth,
+        model: &str,
+        system_instruction: &Content,
+    ) -> anyhow::Result<String> {
+        let body = CreateCachedContentRequest {
+            model: Self::format_model_name(model),
+            system_instruction,
+            ttl: format!("{CACHED_CONTENT_TTL_SECS}s"),
+        };
+        let response = self
+            .http_client()
+            .post(format!(
+                "{PUBLIC_API_ENDPOINT}/cachedContents?key={}",
+                auth.api_key_credential()
+            ))
+            .json(&body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("cachedContents create failed ({status}): {error_text}");
+        }
+        let created: CachedContentResponse = response.json().await?;
+        Ok(created.name)
+    }
+
+    async fn mark_cache_unavailable(&self, key: &str) {
+        self.cached_contents.lock().await.insert(
+            key.to_string(),
+            CachedPrefix::Unavailable {
+                until: Instant::now() + Duration::from_secs(CACHED_CONTENT_RETRY_SECS),
+            },
+        );
+    }
+}
+
+/// Concatenated text parts of a content block.
+fn content_text(content: &Content) -> String {
+    content
+        .parts
+        .iter()
+        .filter_map(|part| match part {
+            Part::Text { text } => Some(text.as_str()),
+            Part::Inline { .. } => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 #[async_trait]
@@ -1194,7 +1493,8 @@ impl Provider for GeminiProvider {
         crate::providers::traits::ProviderCapabilities {
             vision: true,
             native_tool_calling: false,
-            prompt_caching: false,
+            prompt_caching: self.auth.as_ref().is_some_and(GeminiAuth::is_api_key),
+            structured_output: true,
         }
     }
 
@@ -1216,7 +1516,7 @@ impl Provider for GeminiProvider {
         }];
 
         let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+            .send_generate_content(contents, system_instruction, model, temperature, None)
             .await?;
         Ok(text)
     }
@@ -1227,44 +1527,48 @@ impl Provider for GeminiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let mut system_parts: Vec<&str> = Vec::new();
-        let mut contents: Vec<Content> = Vec::new();
+        let (contents, system_instruction) = convert_messages(messages);
+        let (text, _usage) = self
+            .send_generate_content(contents, system_instruction, model, temperature, None)
+            .await?;
+        Ok(text)
+    }
 
-        for msg in messages {
-            match msg.role.as_str() {
-                "system" => {
-                    system_parts.push(&msg.content);
-                }
-                "user" => {
-                    contents.push(Content {
-                        role: Some("user".to_string()),
-                        parts: build_parts(&msg.content),
-                    });
-                }
-                "assistant" => {
-                    // Gemini API uses "model" role instead of "assistant"
-                    contents.push(Content {
-                        role: Some("model".to_string()),
-                        parts: vec![Part::text(&msg.content)],
-                    });
-                }
-                _ => {}
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        // Tools stay prompt-guided; only the response format is sent natively.
+        let tool_instructions = request
+            .tools
+            .filter(|tools| !tools.is_empty())
+            .map(build_tool_instructions_text);
+        let (contents, system_instruction) = match tool_instructions {
+            Some(instructions) => {
+                let mut messages = request.messages.to_vec();
+                append_system_instructions(&mut messages, &instructions);
+                convert_messages(&messages)
             }
-        }
-
-        let system_instruction = if system_parts.is_empty() {
-            None
-        } else {
-            Some(Content {
-                role: None,
-                parts: vec![Part::text(system_parts.join("\n\n"))],
-            })
+            None => convert_messages(request.messages),
         };
 
-        let (text, _usage) = self
-            .send_generate_content(contents, system_instruction, model, temperature)
+        let (text, usage) = self
+            .send_generate_content(
+                contents,
+                system_instruction,
+                model,
+                temperature,
+                request.response_format,
+            )
             .await?;
-        Ok(text)
+        Ok(ChatResponse {
+            text: Some(text),
+            tool_calls: Vec::new(),
+            usage,
+            reasoning_content: None,
+        })
     }
 
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -1342,6 +1646,7 @@ mod tests {
             oauth_index: Arc::new(tokio::sync::Mutex::new(0)),
             auth_service: None,
             auth_profile_override: None,
+            cached_contents: shared_cached_contents(),
         }
     }
 
@@ -1539,9 +1844,12 @@ mod tests {
                 parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
             },
         };
 
@@ -1578,9 +1886,12 @@ mod tests {
                 parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
             },
         };
 
@@ -1620,9 +1931,12 @@ mod tests {
                 parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
             },
         };
 
@@ -1653,9 +1967,12 @@ mod tests {
                 role: None,
                 parts: vec![Part::text("You are helpful")],
             }),
+            cached_content: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
             },
         };
 
@@ -1683,6 +2000,8 @@ mod tests {
                 generation_config: Some(GenerationConfig {
                     temperature: 0.7,
                     max_output_tokens: 8192,
+                    response_mime_type: None,
+                    response_json_schema: None,
                 }),
             },
         };
@@ -2082,6 +2401,179 @@ mod tests {
         assert_eq!(usage.candidates_token_count, Some(40));
     }
 
+    #[test]
+    fn response_parses_cached_content_token_count() {
+        let json = r#"{
+            "candidates": [{"content": {"parts": [{"text": "Hello"}]}}],
+            "usageMetadata": {"promptTokenCount": 5000, "candidatesTokenCount": 40, "cachedContentTokenCount": 4800}
+        }"#;
+        let resp: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let usage = resp.usage_metadata.unwrap();
+        assert_eq!(usage.cached_content_token_count, Some(4800));
+    }
+
+    #[test]
+    fn cached_content_request_omits_system_instruction() {
+        let request = GenerateContentRequest {
+            contents: vec![Content {
+                role: Some("user".to_string()),
+                parts: vec![Part::text("Hello")],
+            }],
+            system_instruction: None,
+            cached_content: Some("cachedContents/abc123".to_string()),
+            generation_config: GenerationConfig {
+                temperature: 0.7,
+                max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
+            },
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["cachedContent"], "cachedContents/abc123");
+        assert!(json.get("systemInstruction").is_none());
+    }
+
+    #[tokio::test]
+    async fn attach_cached_content_skips_oauth_and_short_prompts() {
+        let mut request = GenerateContentRequest {
+            contents: Vec::new(),
+            system_instruction: Some(Content {
+                role: None,
+                parts: vec![Part::text("s".repeat(20_000))],
+            }),
+            cached_content: None,
+            generation_config: GenerationConfig {
+                temperature: 0.7,
+                max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
+            },
+        };
+        let oauth = test_oauth_auth("token");
+        let provider = test_provider(Some(test_oauth_auth("token")));
+        assert!(provider
+            .attach_cached_content(&oauth, "gemini-2.5-pro", &mut request)
+            .await
+            .is_none());
+        assert!(request.system_instruction.is_some());
+
+        let key = GeminiAuth::ExplicitKey("api-key".into());
+        request.system_instruction = Some(Content {
+            role: None,
+            parts: vec![Part::text("short")],
+        });
+        assert!(provider
+            .attach_cached_content(&key, "gemini-2.5-pro", &mut request)
+            .await
+            .is_none());
+        assert!(request.cached_content.is_none());
+    }
+
+    #[tokio::test]
+    async fn attach_cached_content_reuses_ready_entry() {
+        let auth = GeminiAuth::ExplicitKey("api-key".into());
+        let provider = test_provider(Some(GeminiAuth::ExplicitKey("api-key".into())));
+        let system = "s".repeat(20_000);
+        let key = cache_key(&auth, "gemini-2.5-pro", &system);
+        provider.cached_contents.lock().await.insert(
+            key.clone(),
+            CachedPrefix::Ready {
+                name: "cachedContents/abc123".into(),
+                expires_at: Instant::now() + Duration::from_secs(60),
+            },
+        );
+        let mut request = GenerateContentRequest {
+            contents: Vec::new(),
+            system_instruction: Some(Content {
+                role: None,
+                parts: vec![Part::text(system)],
+            }),
+            cached_content: None,
+            generation_config: GenerationConfig {
+                temperature: 0.7,
+                max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
+            },
+        };
+
+        let used = provider
+            .attach_cached_content(&auth, "gemini-2.5-pro", &mut request)
+            .await;
+        assert_eq!(
+            used,
+            Some(CacheAttachment {
+                key: key.clone(),
+                moved_tail: false
+            })
+        );
+        assert_eq!(
+            request.cached_content.as_deref(),
+            Some("cachedContents/abc123")
+        );
+        assert!(request.system_instruction.is_none());
+
+        provider.mark_cache_unavailable(&key).await;
+        let mut retry = GenerateContentRequest {
+            system_instruction: Some(Content {
+                role: None,
+                parts: vec![Part::text("s".repeat(20_000))],
+            }),
+            cached_content: None,
+            ..request
+        };
+        assert!(provider
+            .attach_cached_content(&auth, "gemini-2.5-pro", &mut retry)
+            .await
+            .is_none());
+        assert!(retry.system_instruction.is_some());
+    }
+
+    #[tokio::test]
+    async fn attach_cached_content_keys_on_prefix_and_moves_datetime_tail() {
+        let auth = GeminiAuth::ExplicitKey("api-key-tail".into());
+        let provider = test_provider(Some(GeminiAuth::ExplicitKey("api-key-tail".into())));
+        let stable = "s".repeat(20_000);
+        provider.cached_contents.lock().await.insert(
+            cache_key(&auth, "gemini-2.5-pro", &stable),
+            CachedPrefix::Ready {
+                name: "cachedContents/prefix".into(),
+                expires_at: Instant::now() + Duration::from_secs(60),
+            },
+        );
+        let mut request = GenerateContentRequest {
+            contents: vec![Content {
+                role: Some("user".into()),
+                parts: vec![Part::text("hi")],
+            }],
+            system_instruction: Some(Content {
+                role: None,
+                parts: vec![Part::text(format!(
+                    "{stable}## Current Date & Time\n\n2026-01-01 10:00:00 (UTC)\n"
+                ))],
+            }),
+            cached_content: None,
+            generation_config: GenerationConfig {
+                temperature: 0.7,
+                max_output_tokens: 8192,
+                response_mime_type: None,
+                response_json_schema: None,
+            },
+        };
+
+        let used = provider
+            .attach_cached_content(&auth, "gemini-2.5-pro", &mut request)
+            .await
+            .unwrap();
+        assert!(used.moved_tail);
+        assert_eq!(
+            request.cached_content.as_deref(),
+            Some("cachedContents/prefix")
+        );
+        assert_eq!(request.contents.len(), 2);
+        assert!(content_text(&request.contents[0]).starts_with("## Current Date & Time"));
+    }
+
     #[test]
     fn response_parses_without_usage_metadata() {
         let json = r#"{"candidates": [{"content": {"parts": [{"text": "Hello"}]}}]}"#;
@@ -2099,6 +2591,7 @@ mod tests {
             oauth_index: Arc::new(tokio::sync::Mutex::new(0)),
             auth_service: None, // Missing auth_service
             auth_profile_override: None,
+            cached_contents: shared_cached_contents(),
         };
 
         let result = provider.warmup().await;
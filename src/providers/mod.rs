@@ -29,15 +29,18 @@ pub mod ollama;
 pub mod openai;
 pub mod openai_codex;
 pub mod openrouter;
+pub mod prompt_cache;
 pub mod reliable;
+pub mod replay;
 pub mod router;
+pub mod structured;
 pub mod telnyx;
 pub mod traits;
 
 #[allow(unused_imports)]
 pub use traits::{
     ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ProviderCapabilityError,
-    ToolCall, ToolResultMessage,
+    ResponseFormat, ToolCall, ToolResultMessage,
 };
 
 use crate::auth::AuthService;
@@ -709,6 +712,12 @@ pub struct ProviderRuntimeOptions {
     /// Maximum output tokens for LLM provider API requests.
     /// `None` uses the provider's built-in default.
     pub provider_max_tokens: Option<u32>,
+    /// When set, record every provider call to this session trace file
+    /// (see [`replay::RecordingProvider`]).
+    pub provider_record_path: Option<PathBuf>,
+    /// Budget-aware downgrades for routed providers
+    /// (see [`router::BudgetRouting`]).
+    pub budget_routing: Option<router::BudgetRouting>,
 }
 
 impl Default for ProviderRuntimeOptions {
@@ -724,10 +733,25 @@ impl Default for ProviderRuntimeOptions {
             extra_headers: std::collections::HashMap::new(),
             api_path: None,
             provider_max_tokens: None,
+            provider_record_path: None,
+            budget_routing: None,
         }
     }
 }
 
+impl ProviderRuntimeOptions {
+    /// Report budget downgrades made by routed providers to `observer`.
+    pub fn with_budget_observer(
+        mut self,
+        observer: std::sync::Arc<dyn crate::observability::Observer>,
+    ) -> Self {
+        self.budget_routing = self
+            .budget_routing
+            .map(|budget| budget.with_observer(observer));
+        self
+    }
+}
+
 pub fn provider_runtime_options_from_config(
     config: &crate::config::Config,
 ) -> ProviderRuntimeOptions {
@@ -742,6 +766,8 @@ pub fn provider_runtime_options_from_config(
         extra_headers: config.extra_headers.clone(),
         api_path: config.api_path.clone(),
         provider_max_tokens: config.provider_max_tokens,
+        provider_record_path: config.provider_record_path.as_ref().map(PathBuf::from),
+        budget_routing: router::BudgetRouting::from_config(config),
     }
 }
 
@@ -1124,7 +1150,9 @@ fn create_provider_with_url_and_options(
 
     // Pre-flight: catch obvious API-key / provider mismatches early.
     if let Some(key_value) = key {
-        let is_custom = name.starts_with("custom:") || name.starts_with("anthropic-custom:");
+        let is_custom = name.starts_with("custom:")
+            || name.starts_with("anthropic-custom:")
+            || name.starts_with("replay:");
         let has_custom_url = api_url.map(str::trim).is_some_and(|u| !u.is_empty());
         if !is_custom && !has_custom_url {
             if let Some(likely_provider) = check_api_key_prefix(name, key_value) {
@@ -1589,6 +1617,18 @@ fn create_provider_with_url_and_options(
             )))
         }
 
+        // ── Recorded session replay ─────────────────────────
+        // Format: "replay:/path/to/session.json"
+        name if name.starts_with("replay:") => {
+            let path = name.strip_prefix("replay:").unwrap_or("").trim();
+            if path.is_empty() {
+                anyhow::bail!(
+                    "Replay provider requires a trace file path. Use \"replay:/path/to/session.json\"."
+                );
+            }
+            Ok(Box::new(replay::ReplayProvider::from_file(path)?))
+        }
+
         _ => anyhow::bail!(
             "Unknown provider: {name}. Check README for supported providers or run `zeroclaw onboard` to reconfigure.\n\
              Tip: Use \"custom:https://your-api.com\" for OpenAI-compatible endpoints.\n\
@@ -1602,9 +1642,9 @@ fn create_provider_with_url_and_options(
 /// Returns `(provider_name, Some(profile))` when the entry contains a colon-
 /// delimited profile, or `(original_str, None)` otherwise.  Entries starting
 /// with `custom:` or `anthropic-custom:` are left untouched because the colon
-/// is part of the URL scheme.
+/// is part of the URL scheme, as are `replay:` entries whose suffix is a path.
 fn parse_provider_profile(s: &str) -> (&str, Option<&str>) {
-    if s.starts_with("custom:") || s.starts_with("anthropic-custom:") {
+    if s.starts_with("custom:") || s.starts_with("anthropic-custom:") || s.starts_with("replay:") {
         return (s, None);
     }
     match s.split_once(':') {
@@ -1636,6 +1676,29 @@ pub fn create_resilient_provider_with_options(
     api_url: Option<&str>,
     reliability: &crate::config::ReliabilityConfig,
     options: &ProviderRuntimeOptions,
+) -> anyhow::Result<Box<dyn Provider>> {
+    let provider = build_resilient_provider(primary_name, api_key, api_url, reliability, options)?;
+    Ok(with_recording(provider, options))
+}
+
+/// Wrap `provider` in a [`replay::RecordingProvider`] when session recording
+/// is configured.
+fn with_recording(
+    provider: Box<dyn Provider>,
+    options: &ProviderRuntimeOptions,
+) -> Box<dyn Provider> {
+    match &options.provider_record_path {
+        Some(path) => Box::new(replay::RecordingProvider::new(provider, path)),
+        None => provider,
+    }
+}
+
+fn build_resilient_provider(
+    primary_name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    reliability: &crate::config::ReliabilityConfig,
+    options: &ProviderRuntimeOptions,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let mut providers: Vec<(String, Box<dyn Provider>)> = Vec::new();
 
@@ -1759,7 +1822,7 @@ pub fn create_routed_provider_with_options(
         let key = routed_credential.or(api_key);
         // Only use api_url for the primary provider
         let url = if name == primary_name { api_url } else { None };
-        match create_resilient_provider_with_options(name, key, url, reliability, options) {
+        match build_resilient_provider(name, key, url, reliability, options) {
             Ok(provider) => providers.push((name.clone(), provider)),
             Err(e) => {
                 if name == primary_name {
@@ -1782,16 +1845,15 @@ pub fn create_routed_provider_with_options(
                 router::Route {
                     provider_name: r.provider.clone(),
                     model: r.model.clone(),
+                    context_window: r.context_window,
                 },
             )
         })
         .collect();
 
-    Ok(Box::new(router::RouterProvider::new(
-        providers,
-        routes,
-        default_model.to_string(),
-    )))
+    let router = router::RouterProvider::new(providers, routes, default_model.to_string())
+        .with_budget(options.budget_routing.clone());
+    Ok(with_recording(Box::new(router), options))
 }
 
 /// Information about a supported provider for display purposes.
@@ -3064,6 +3126,74 @@ mod tests {
         }
     }
 
+    // ── Replay ───────────────────────────────────────────────
+
+    #[test]
+    fn factory_replay_loads_trace_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        replay::SessionTrace::default().save(&path).unwrap();
+        let p = create_provider(&format!("replay:{}", path.display()), Some("sk-ignored"));
+        assert!(p.is_ok());
+    }
+
+    #[test]
+    fn factory_replay_requires_path() {
+        match create_provider("replay:", None) {
+            Err(e) => assert!(
+                e.to_string().contains("requires a trace file path"),
+                "Expected missing path error, got: {e}"
+            ),
+            Ok(_) => panic!("Expected error for replay without a path"),
+        }
+    }
+
+    #[test]
+    fn factory_replay_missing_file_errors() {
+        let p = create_provider("replay:/nonexistent/zeroclaw-session.json", None);
+        assert!(p.is_err());
+    }
+
+    #[tokio::test]
+    async fn resilient_provider_records_when_record_path_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let trace_path = dir.path().join("session.json");
+        let mut trace = replay::SessionTrace::default();
+        trace.entries.push(replay::TraceEntry {
+            request: None,
+            response: replay::RecordedResponse {
+                text: Some("recorded".into()),
+                ..replay::RecordedResponse::default()
+            },
+        });
+        trace.save(&trace_path).unwrap();
+
+        let record_path = dir.path().join("copy.json");
+        let options = ProviderRuntimeOptions {
+            provider_record_path: Some(record_path.clone()),
+            ..ProviderRuntimeOptions::default()
+        };
+        let provider = create_resilient_provider_with_options(
+            &format!("replay:{}", trace_path.display()),
+            None,
+            None,
+            &crate::config::ReliabilityConfig::default(),
+            &options,
+        )
+        .unwrap();
+
+        let reply = provider.simple_chat("hi", "model", 0.0).await.unwrap();
+        assert_eq!(reply, "recorded");
+        replay::flush_trace(&record_path).await;
+
+        let recorded = replay::SessionTrace::load(&record_path).unwrap();
+        assert_eq!(recorded.entries.len(), 1);
+        assert_eq!(
+            recorded.entries[0].response.text.as_deref(),
+            Some("recorded")
+        );
+    }
+
     // ── Error cases ──────────────────────────────────────────
 
     #[test]
@@ -3498,6 +3628,14 @@ mod tests {
         assert_eq!(profile, Some("profile:extra"));
     }
 
+    #[test]
+    fn parse_provider_profile_replay_path_not_split() {
+        let input = "replay:/tmp/session.json";
+        let (name, profile) = parse_provider_profile(input);
+        assert_eq!(name, input);
+        assert_eq!(profile, None);
+    }
+
     // --- resilient fallback with profile syntax ---
 
     #[test]
@@ -25,6 +25,9 @@ struct ChatRequest {
     think: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<serde_json::Value>>,
+    /// `"json"` or a JSON schema constraining the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -301,6 +304,7 @@ impl OllamaProvider {
             options: Options { temperature },
             think,
             tools: tools.map(|t| t.to_vec()),
+            format: None,
         }
     }
 
@@ -439,10 +443,12 @@ impl OllamaProvider {
         temperature: f64,
         should_auth: bool,
         tools: Option<&[serde_json::Value]>,
+        format: Option<&serde_json::Value>,
         think: Option<bool>,
     ) -> anyhow::Result<ApiChatResponse> {
-        let request =
+        let mut request =
             self.build_chat_request_with_think(messages.to_vec(), model, temperature, tools, think);
+        request.format = format.cloned();
 
         let url = format!("{}/api/chat", self.base_url);
 
@@ -516,6 +522,7 @@ impl OllamaProvider {
         temperature: f64,
         should_auth: bool,
         tools: Option<&[serde_json::Value]>,
+        format: Option<&serde_json::Value>,
     ) -> anyhow::Result<ApiChatResponse> {
         let result = self
             .send_request_inner(
@@ -524,6 +531,7 @@ impl OllamaProvider {
                 temperature,
                 should_auth,
                 tools,
+                format,
                 self.reasoning_enabled,
             )
             .await;
@@ -538,18 +546,26 @@ impl OllamaProvider {
                      (model may not support it)"
                 );
                 // Retry with think omitted from the request entirely.
-                self.send_request_inner(&messages, model, temperature, should_auth, tools, None)
-                    .await
-                    .map_err(|retry_err| {
-                        // Both attempts failed — return the original error for clarity.
-                        tracing::error!(
-                            model = model,
-                            original_error = %first_err,
-                            retry_error = %retry_err,
-                            "Ollama request also failed without think; returning original error"
-                        );
-                        first_err
-                    })
+                self.send_request_inner(
+                    &messages,
+                    model,
+                    temperature,
+                    should_auth,
+                    tools,
+                    format,
+                    None,
+                )
+                .await
+                .map_err(|retry_err| {
+                    // Both attempts failed — return the original error for clarity.
+                    tracing::error!(
+                        model = model,
+                        original_error = %first_err,
+                        retry_error = %retry_err,
+                        "Ollama request also failed without think; returning original error"
+                    );
+                    first_err
+                })
             }
             Err(e) => Err(e),
         }
@@ -625,6 +641,97 @@ impl OllamaProvider {
         // Pattern 3: Normal tool call
         (name.clone(), args.clone())
     }
+
+    /// Native `/api/chat` call returning structured tool calls and usage.
+    /// `format` is forwarded as Ollama's `format` field when set.
+    async fn chat_native(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        format: Option<&serde_json::Value>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (normalized_model, should_auth) = self.resolve_request_details(model)?;
+
+        let api_messages = self.convert_messages(messages);
+
+        // Tools arrive pre-formatted in OpenAI/Ollama-compatible JSON from
+        // tools_to_openai_format() in loop_.rs — pass them through directly.
+        let tools_opt = if tools.is_empty() { None } else { Some(tools) };
+
+        let response = self
+            .send_request(
+                api_messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                tools_opt,
+                format,
+            )
+            .await?;
+
+        let usage = if response.prompt_eval_count.is_some() || response.eval_count.is_some() {
+            Some(TokenUsage {
+                input_tokens: response.prompt_eval_count,
+                output_tokens: response.eval_count,
+                cached_input_tokens: None,
+            })
+        } else {
+            None
+        };
+
+        // Native tool calls returned by the model.
+        if !response.message.tool_calls.is_empty() {
+            let tool_calls: Vec<ToolCall> = response
+                .message
+                .tool_calls
+                .iter()
+                .map(|tc| {
+                    let (name, args) = self.extract_tool_name_and_args(tc);
+                    ToolCall {
+                        id: tc
+                            .id
+                            .clone()
+                            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                        name,
+                        arguments: serde_json::to_string(&args)
+                            .unwrap_or_else(|_| "{}".to_string()),
+                    }
+                })
+                .collect();
+            let text = Self::normalize_response_text(response.message.content);
+            return Ok(ChatResponse {
+                text,
+                tool_calls,
+                usage,
+                reasoning_content: None,
+            });
+        }
+
+        // No native tool calls — use the effective content (content with
+        // `<think>` tags stripped, falling back to thinking field).
+        // The loop_.rs `parse_tool_calls` will extract any XML-style tool
+        // calls from the text, so preserve `<tool_call>` tags here.
+        let effective = Self::effective_content(
+            &response.message.content,
+            response.message.thinking.as_deref(),
+        );
+        let text = if let Some(content) = effective {
+            content
+        } else {
+            Self::fallback_text_for_empty_content(
+                &normalized_model,
+                response.message.thinking.as_deref(),
+            )
+        };
+        Ok(ChatResponse {
+            text: Some(text),
+            tool_calls: vec![],
+            usage,
+            reasoning_content: None,
+        })
+    }
 }
 
 #[async_trait]
@@ -634,6 +741,7 @@ impl Provider for OllamaProvider {
             native_tool_calling: false,
             vision: true,
             prompt_caching: false,
+            structured_output: true,
         }
     }
 
@@ -668,7 +776,14 @@ impl Provider for OllamaProvider {
         });
 
         let response = self
-            .send_request(messages, &normalized_model, temperature, should_auth, None)
+            .send_request(
+                messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                None,
+                None,
+            )
             .await?;
 
         // If model returned tool calls, format them for loop_.rs's parse_tool_calls
@@ -711,6 +826,7 @@ impl Provider for OllamaProvider {
                 temperature,
                 should_auth,
                 None,
+                None,
             )
             .await?;
 
@@ -744,84 +860,8 @@ impl Provider for OllamaProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (normalized_model, should_auth) = self.resolve_request_details(model)?;
-
-        let api_messages = self.convert_messages(messages);
-
-        // Tools arrive pre-formatted in OpenAI/Ollama-compatible JSON from
-        // tools_to_openai_format() in loop_.rs — pass them through directly.
-        let tools_opt = if tools.is_empty() { None } else { Some(tools) };
-
-        let response = self
-            .send_request(
-                api_messages,
-                &normalized_model,
-                temperature,
-                should_auth,
-                tools_opt,
-            )
-            .await?;
-
-        let usage = if response.prompt_eval_count.is_some() || response.eval_count.is_some() {
-            Some(TokenUsage {
-                input_tokens: response.prompt_eval_count,
-                output_tokens: response.eval_count,
-                cached_input_tokens: None,
-            })
-        } else {
-            None
-        };
-
-        // Native tool calls returned by the model.
-        if !response.message.tool_calls.is_empty() {
-            let tool_calls: Vec<ToolCall> = response
-                .message
-                .tool_calls
-                .iter()
-                .map(|tc| {
-                    let (name, args) = self.extract_tool_name_and_args(tc);
-                    ToolCall {
-                        id: tc
-                            .id
-                            .clone()
-                            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
-                        name,
-                        arguments: serde_json::to_string(&args)
-                            .unwrap_or_else(|_| "{}".to_string()),
-                    }
-                })
-                .collect();
-            let text = Self::normalize_response_text(response.message.content);
-            return Ok(ChatResponse {
-                text,
-                tool_calls,
-                usage,
-                reasoning_content: None,
-            });
-        }
-
-        // No native tool calls — use the effective content (content with
-        // `<think>` tags stripped, falling back to thinking field).
-        // The loop_.rs `parse_tool_calls` will extract any XML-style tool
-        // calls from the text, so preserve `<tool_call>` tags here.
-        let effective = Self::effective_content(
-            &response.message.content,
-            response.message.thinking.as_deref(),
-        );
-        let text = if let Some(content) = effective {
-            content
-        } else {
-            Self::fallback_text_for_empty_content(
-                &normalized_model,
-                response.message.thinking.as_deref(),
-            )
-        };
-        Ok(ChatResponse {
-            text: Some(text),
-            tool_calls: vec![],
-            usage,
-            reasoning_content: None,
-        })
+        self.chat_native(messages, tools, None, model, temperature)
+            .await
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -840,6 +880,13 @@ impl Provider for OllamaProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
+        let format = request.response_format.map(|format| {
+            format
+                .schema()
+                .cloned()
+                .unwrap_or_else(|| serde_json::Value::String("json".to_string()))
+        });
+
         // Convert ToolSpec to OpenAI-compatible JSON and delegate to chat_with_tools.
         if let Some(specs) = request.tools {
             if !specs.is_empty() {
@@ -857,11 +904,23 @@ impl Provider for OllamaProvider {
                     })
                     .collect();
                 return self
-                    .chat_with_tools(request.messages, &tools, model, temperature)
+                    .chat_native(
+                        request.messages,
+                        &tools,
+                        format.as_ref(),
+                        model,
+                        temperature,
+                    )
                     .await;
             }
         }
 
+        if format.is_some() {
+            return self
+                .chat_native(request.messages, &[], format.as_ref(), model, temperature)
+                .await;
+        }
+
         // No tools — fall back to plain text chat.
         let text = self
             .chat_with_history(request.messages, model, temperature)
@@ -718,6 +718,7 @@ impl Provider for OpenAiCodexProvider {
             native_tool_calling: false,
             vision: true,
             prompt_caching: false,
+            structured_output: false,
         }
     }
 
@@ -1162,6 +1163,8 @@ data: [DONE]
             extra_headers: std::collections::HashMap::new(),
             api_path: None,
             provider_max_tokens: None,
+            provider_record_path: None,
+            budget_routing: None,
         };
         let provider =
             OpenAiCodexProvider::new(&options, None).expect("provider should initialize");
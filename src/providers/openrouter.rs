@@ -1,4 +1,5 @@
 use crate::multimodal;
+use crate::providers::prompt_cache::{CachePlan, PromptCacheStrategy};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
     Provider, ProviderCapabilities, TokenUsage, ToolCall as ProviderToolCall,
@@ -43,8 +44,29 @@ enum MessageContent {
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 enum MessagePart {
-    Text { text: String },
-    ImageUrl { image_url: ImageUrlPart },
+    Text {
+        text: String,
+        /// Passed through to providers that need explicit breakpoints.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    ImageUrl {
+        image_url: ImageUrlPart,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    cache_type: String,
+}
+
+impl CacheControl {
+    fn ephemeral() -> Self {
+        Self {
+            cache_type: "ephemeral".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -137,6 +159,14 @@ struct UsageInfo {
     prompt_tokens: Option<u64>,
     #[serde(default)]
     completion_tokens: Option<u64>,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -292,6 +322,7 @@ impl OpenRouterProvider {
         if !trimmed_text.is_empty() {
             parts.push(MessagePart::Text {
                 text: trimmed_text.to_string(),
+                cache_control: None,
             });
         }
 
@@ -304,6 +335,56 @@ impl OpenRouterProvider {
         MessageContent::Parts(parts)
     }
 
+    /// OpenAI, DeepSeek and Grok models behind OpenRouter cache prefixes
+    /// automatically; Anthropic and Gemini models only cache up to an
+    /// explicit `cache_control` breakpoint, which OpenRouter passes through.
+    fn needs_cache_breakpoints(model: &str) -> bool {
+        model.starts_with("anthropic/") || model.starts_with("google/gemini")
+    }
+
+    /// Attach a `cache_control` breakpoint to the end of `content`.
+    fn mark_cache_breakpoint(content: &mut MessageContent) {
+        if let MessageContent::Text(text) = content {
+            *content = MessageContent::Parts(vec![MessagePart::Text {
+                text: std::mem::take(text),
+                cache_control: None,
+            }]);
+        }
+        if let MessageContent::Parts(parts) = content {
+            if let Some(MessagePart::Text { cache_control, .. }) = parts
+                .iter_mut()
+                .rev()
+                .find(|part| matches!(part, MessagePart::Text { .. }))
+            {
+                *cache_control = Some(CacheControl::ephemeral());
+            }
+        }
+    }
+
+    /// Map a [`CachePlan`] onto OpenRouter messages. The system breakpoint
+    /// also covers tool definitions, which precede it in the cached prefix.
+    fn apply_cache_plan(messages: &mut [NativeMessage], plan: CachePlan) {
+        if plan.system {
+            if let Some(content) = messages
+                .iter_mut()
+                .rev()
+                .find(|m| m.role == "system")
+                .and_then(|m| m.content.as_mut())
+            {
+                Self::mark_cache_breakpoint(content);
+            }
+        }
+        if plan.conversation {
+            if let Some(content) = messages
+                .last_mut()
+                .filter(|m| m.role == "user")
+                .and_then(|m| m.content.as_mut())
+            {
+                Self::mark_cache_breakpoint(content);
+            }
+        }
+    }
+
     fn parse_native_response(message: NativeResponseMessage) -> ProviderChatResponse {
         let reasoning_content = message.reasoning_content.clone();
         let tool_calls = message
@@ -372,7 +453,8 @@ impl Provider for OpenRouterProvider {
         ProviderCapabilities {
             native_tool_calling: true,
             vision: true,
-            prompt_caching: false,
+            prompt_caching: true,
+            structured_output: false,
         }
     }
 
@@ -403,9 +485,15 @@ impl Provider for OpenRouterProvider {
         let mut messages = Vec::new();
 
         if let Some(sys) = system_prompt {
+            let mut content = MessageContent::Text(sys.to_string());
+            if Self::needs_cache_breakpoints(model)
+                && PromptCacheStrategy::DEFAULT.should_cache_system(sys)
+            {
+                Self::mark_cache_breakpoint(&mut content);
+            }
             messages.push(Message {
                 role: "system".to_string(),
-                content: MessageContent::Text(sys.to_string()),
+                content,
             });
         }
 
@@ -456,11 +544,21 @@ impl Provider for OpenRouterProvider {
         let credential = self.credential.as_ref()
             .ok_or_else(|| anyhow::anyhow!("OpenRouter API key not set. Run `zeroclaw onboard` or set OPENROUTER_API_KEY env var."))?;
 
+        let cache_system = Self::needs_cache_breakpoints(model);
         let api_messages: Vec<Message> = messages
             .iter()
-            .map(|m| Message {
-                role: m.role.clone(),
-                content: Self::to_message_content(&m.role, &m.content),
+            .map(|m| {
+                let mut content = Self::to_message_content(&m.role, &m.content);
+                if cache_system
+                    && m.role == "system"
+                    && PromptCacheStrategy::DEFAULT.should_cache_system(&m.content)
+                {
+                    Self::mark_cache_breakpoint(&mut content);
+                }
+                Message {
+                    role: m.role.clone(),
+                    content,
+                }
             })
             .collect();
 
@@ -510,9 +608,14 @@ impl Provider for OpenRouterProvider {
         })?;
 
         let tools = Self::convert_tools(request.tools);
+        let mut messages = Self::convert_messages(request.messages);
+        if Self::needs_cache_breakpoints(model) {
+            let plan = PromptCacheStrategy::DEFAULT.plan(request.messages, request.tools);
+            Self::apply_cache_plan(&mut messages, plan);
+        }
         let native_request = NativeChatRequest {
             model: model.to_string(),
-            messages: Self::convert_messages(request.messages),
+            messages,
             temperature,
             tool_choice: tools.as_ref().map(|_| "auto".to_string()),
             tools,
@@ -539,7 +642,7 @@ impl Provider for OpenRouterProvider {
         let usage = native_response.usage.map(|u| TokenUsage {
             input_tokens: u.prompt_tokens,
             output_tokens: u.completion_tokens,
-            cached_input_tokens: None,
+            cached_input_tokens: u.prompt_tokens_details.and_then(|d| d.cached_tokens),
         });
         let message = native_response
             .choices
@@ -603,7 +706,11 @@ impl Provider for OpenRouterProvider {
 
         // Convert ChatMessage to NativeMessage, preserving structured assistant/tool entries
         // when history contains native tool-call metadata.
-        let native_messages = Self::convert_messages(messages);
+        let mut native_messages = Self::convert_messages(messages);
+        if Self::needs_cache_breakpoints(model) {
+            let plan = PromptCacheStrategy::DEFAULT.plan(messages, None);
+            Self::apply_cache_plan(&mut native_messages, plan);
+        }
 
         let native_request = NativeChatRequest {
             model: model.to_string(),
@@ -634,7 +741,7 @@ impl Provider for OpenRouterProvider {
         let usage = native_response.usage.map(|u| TokenUsage {
             input_tokens: u.prompt_tokens,
             output_tokens: u.completion_tokens,
-            cached_input_tokens: None,
+            cached_input_tokens: u.prompt_tokens_details.and_then(|d| d.cached_tokens),
         });
         let message = native_response
             .choices
@@ -669,6 +776,7 @@ mod tests {
         let caps = <OpenRouterProvider as Provider>::capabilities(&provider);
         assert!(caps.native_tool_calling);
         assert!(caps.vision);
+        assert!(caps.prompt_caching);
     }
 
     #[test]
@@ -1024,6 +1132,49 @@ mod tests {
         assert_eq!(usage.completion_tokens, Some(15));
     }
 
+    #[test]
+    fn native_response_parses_cached_tokens() {
+        let json = r#"{
+            "choices": [{"message": {"content": "Hi"}}],
+            "usage": {"prompt_tokens": 4000, "completion_tokens": 5, "prompt_tokens_details": {"cached_tokens": 3500}}
+        }"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let details = resp.usage.unwrap().prompt_tokens_details.unwrap();
+        assert_eq!(details.cached_tokens, Some(3500));
+    }
+
+    #[test]
+    fn cache_plan_marks_system_and_last_user_message() {
+        let history = vec![
+            ChatMessage::system("s".repeat(4000)),
+            ChatMessage::user("first"),
+            ChatMessage::assistant("reply"),
+            ChatMessage::user("second"),
+        ];
+        let mut messages = OpenRouterProvider::convert_messages(&history);
+        let plan = PromptCacheStrategy::DEFAULT.plan(&history, None);
+        OpenRouterProvider::apply_cache_plan(&mut messages, plan);
+
+        let json = serde_json::to_value(&messages).unwrap();
+        assert_eq!(json[0]["content"][0]["cache_control"]["type"], "ephemeral");
+        assert!(json[1]["content"].is_string());
+        assert_eq!(json[3]["content"][0]["cache_control"]["type"], "ephemeral");
+        assert_eq!(json[3]["content"][0]["text"], "second");
+    }
+
+    #[test]
+    fn cache_breakpoints_only_for_explicit_cache_models() {
+        assert!(OpenRouterProvider::needs_cache_breakpoints(
+            "anthropic/claude-sonnet-4"
+        ));
+        assert!(OpenRouterProvider::needs_cache_breakpoints(
+            "google/gemini-2.5-pro"
+        ));
+        assert!(!OpenRouterProvider::needs_cache_breakpoints(
+            "openai/gpt-4o"
+        ));
+    }
+
     #[test]
     fn native_response_parses_without_usage() {
         let json = r#"{"choices": [{"message": {"content": "Hello"}}]}"#;
@@ -0,0 +1,190 @@
+//! Provider-agnostic prompt caching strategy.
+//!
+//! Agent requests start with a long, stable prefix — the system prompt
+//! (identity, skills, SOUL.md), tool specs, and the earlier turns of the
+//! conversation — that would otherwise be re-billed as fresh input on every
+//! turn. [`PromptCacheStrategy`] decides which parts of a request are worth
+//! marking, and each provider maps the resulting [`CachePlan`] onto its own
+//! mechanism:
+//!
+//! | Provider    | Mechanism                                                  |
+//! |-------------|------------------------------------------------------------|
+//! | Anthropic   | `cache_control` on system, last tool and last message      |
+//! | Bedrock     | `cachePoint` blocks after system, tools and last message   |
+//! | OpenRouter  | `cache_control` passthrough for Anthropic / Gemini models  |
+//! | Gemini      | `cachedContents` resource referenced by `cachedContent`    |
+//!
+//! Providers report cache hits in [`TokenUsage::cached_input_tokens`]
+//! (always a subset of `input_tokens`) so the cost tracker can price them at
+//! the cached-input rate.
+//!
+//! [`TokenUsage::cached_input_tokens`]: crate::providers::traits::TokenUsage::cached_input_tokens
+
+use crate::providers::traits::ChatMessage;
+use crate::tools::ToolSpec;
+use sha2::{Digest, Sha256};
+
+/// Thresholds that decide which request prefixes get a cache breakpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptCacheStrategy {
+    /// System prompts longer than this many bytes are cached. Providers
+    /// ignore or reject breakpoints on prefixes shorter than ~1024 tokens.
+    pub min_system_chars: usize,
+    /// Cache the conversation once it has at least this many non-system
+    /// messages, so the next turn can reuse everything before it.
+    pub min_conversation_messages: usize,
+}
+
+impl Default for PromptCacheStrategy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl PromptCacheStrategy {
+    /// ~1024 tokens of system prompt; cache from the second message on.
+    pub const DEFAULT: Self = Self {
+        min_system_chars: 3072,
+        min_conversation_messages: 2,
+    };
+
+    pub fn should_cache_system(&self, text: &str) -> bool {
+        text.len() > self.min_system_chars
+    }
+
+    pub fn should_cache_conversation(&self, messages: &[ChatMessage]) -> bool {
+        messages.iter().filter(|m| m.role != "system").count() >= self.min_conversation_messages
+    }
+
+    /// Decide where the breakpoints of a request go.
+    pub fn plan(&self, messages: &[ChatMessage], tools: Option<&[ToolSpec]>) -> CachePlan {
+        CachePlan {
+            system: messages
+                .iter()
+                .any(|m| m.role == "system" && self.should_cache_system(&m.content)),
+            tools: tools.is_some_and(|tools| !tools.is_empty()),
+            conversation: self.should_cache_conversation(messages),
+        }
+    }
+}
+
+/// Which stable prefixes of a request should end in a cache breakpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CachePlan {
+    /// After the system prompt.
+    pub system: bool,
+    /// After the last tool definition.
+    pub tools: bool,
+    /// After the last message, covering the whole history so far.
+    pub conversation: bool,
+}
+
+impl CachePlan {
+    pub fn is_empty(self) -> bool {
+        !(self.system || self.tools || self.conversation)
+    }
+}
+
+/// Headings that open the per-turn date/time section of a system prompt.
+const VOLATILE_SECTION_HEADINGS: &[&str] = &[
+    "## Current Date & Time",
+    "## CRITICAL CONTEXT: CURRENT DATE & TIME",
+];
+
+/// Split a system prompt at its date/time section into the stable prefix and
+/// the per-turn tail, so a cache keyed on the prefix survives the clock
+/// ticking. The tail is empty when the prompt has no such section.
+pub fn split_volatile_suffix(system: &str) -> (&str, &str) {
+    VOLATILE_SECTION_HEADINGS
+        .iter()
+        .filter_map(|heading| system.find(heading))
+        .min()
+        .map_or((system, ""), |start| system.split_at(start))
+}
+
+/// Stable identifier for a cacheable prefix, used to reuse provider-side
+/// cache resources (e.g. Gemini `cachedContents`) across turns.
+pub fn prefix_key(model: &str, parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(model.as_bytes());
+    for part in parts {
+        hasher.update([0]);
+        hasher.update(part.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_volatile_suffix_separates_datetime_section() {
+        let prompt = "## Identity\n\nStable.\n\n## Current Date & Time\n\n2026-01-01 10:00:00 (UTC)\n\n## Runtime\n";
+        let (stable, tail) = split_volatile_suffix(prompt);
+        assert_eq!(stable, "## Identity\n\nStable.\n\n");
+        assert!(tail.starts_with("## Current Date & Time"));
+        assert!(tail.ends_with("## Runtime\n"));
+
+        assert_eq!(split_volatile_suffix("no clock"), ("no clock", ""));
+    }
+
+    fn tool() -> ToolSpec {
+        ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }
+    }
+
+    #[test]
+    fn plan_marks_large_system_tools_and_history() {
+        let strategy = PromptCacheStrategy::DEFAULT;
+        let messages = vec![
+            ChatMessage::system("a".repeat(4000)),
+            ChatMessage::user("hi"),
+            ChatMessage::assistant("hello"),
+        ];
+        let tools = [tool()];
+        let plan = strategy.plan(&messages, Some(&tools));
+        assert_eq!(
+            plan,
+            CachePlan {
+                system: true,
+                tools: true,
+                conversation: true,
+            }
+        );
+    }
+
+    #[test]
+    fn plan_skips_short_prefixes() {
+        let strategy = PromptCacheStrategy::DEFAULT;
+        let messages = vec![ChatMessage::system("short"), ChatMessage::user("hi")];
+        let plan = strategy.plan(&messages, Some(&[]));
+        assert!(plan.is_empty());
+    }
+
+    #[test]
+    fn conversation_threshold_is_configurable() {
+        let strategy = PromptCacheStrategy {
+            min_conversation_messages: 5,
+            ..PromptCacheStrategy::DEFAULT
+        };
+        let mut messages = vec![ChatMessage::system("s")];
+        for i in 0..4 {
+            messages.push(ChatMessage::user(format!("m{i}")));
+        }
+        assert!(!strategy.should_cache_conversation(&messages));
+        messages.push(ChatMessage::user("m4"));
+        assert!(strategy.should_cache_conversation(&messages));
+    }
+
+    #[test]
+    fn prefix_key_depends_on_model_and_parts() {
+        let a = prefix_key("gemini-2.5-pro", &["system"]);
+        assert_eq!(a, prefix_key("gemini-2.5-pro", &["system"]));
+        assert_ne!(a, prefix_key("gemini-2.5-flash", &["system"]));
+        assert_ne!(a, prefix_key("gemini-2.5-pro", &["sys", "tem"]));
+    }
+}
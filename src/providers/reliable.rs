@@ -56,6 +56,20 @@ fn record_provider_fallback(
     actual_provider: &str,
     actual_model: &str,
 ) {
+    crate::routines::bus::publish(crate::routines::RoutineEvent::new(
+        crate::routines::bus::SOURCE_SYSTEM,
+        "provider.fallback",
+        Some(
+            serde_json::json!({
+                "requested_provider": requested_provider,
+                "requested_model": requested_model,
+                "actual_provider": actual_provider,
+                "actual_model": actual_model,
+            })
+            .to_string(),
+        ),
+    ));
+
     let _ = PROVIDER_FALLBACK.try_with(|cell| {
         *cell.borrow_mut() = Some(ProviderFallbackInfo {
             requested_provider: requested_provider.to_string(),
@@ -736,6 +750,17 @@ impl Provider for ReliableProvider {
             .any(|(_, provider)| provider.supports_vision())
     }
 
+    /// Every provider in the chain may serve a request, so native support is
+    /// only reported when all of them honour `response_format`; each attempt
+    /// still falls back to prompt instructions inside the provider itself.
+    fn supports_structured_output(&self) -> bool {
+        !self.providers.is_empty()
+            && self
+                .providers
+                .iter()
+                .all(|(_, p)| p.supports_structured_output())
+    }
+
     async fn chat_with_tools(
         &self,
         messages: &[ChatMessage],
@@ -912,6 +937,7 @@ impl Provider for ReliableProvider {
                     let req = ChatRequest {
                         messages: &effective_messages,
                         tools: request.tools,
+                        response_format: request.response_format,
                     };
                     match provider.chat(req, current_model, temperature).await {
                         Ok(resp) => {
@@ -1098,6 +1124,7 @@ impl Provider for ReliableProvider {
             let req = ChatRequest {
                 messages: request.messages,
                 tools: request.tools,
+                response_format: request.response_format,
             };
             let stream = provider.stream_chat(req, &current_model, temperature, options);
             let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamEvent>>(100);
@@ -1330,6 +1357,52 @@ mod tests {
         }
     }
 
+    /// Mock with a configurable structured-output capability.
+    struct StructuredMock(bool);
+
+    #[async_trait]
+    impl Provider for StructuredMock {
+        fn capabilities(&self) -> crate::providers::traits::ProviderCapabilities {
+            crate::providers::traits::ProviderCapabilities {
+                structured_output: self.0,
+                ..Default::default()
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("ok".into())
+        }
+    }
+
+    #[test]
+    fn structured_output_requires_every_provider_in_chain() {
+        let chain = |flags: &[bool]| {
+            ReliableProvider::new(
+                flags
+                    .iter()
+                    .enumerate()
+                    .map(|(i, flag)| {
+                        (
+                            format!("p{i}"),
+                            Box::new(StructuredMock(*flag)) as Box<dyn Provider>,
+                        )
+                    })
+                    .collect(),
+                0,
+                1,
+            )
+        };
+        assert!(chain(&[true, true]).supports_structured_output());
+        assert!(!chain(&[true, false]).supports_structured_output());
+        assert!(!chain(&[]).supports_structured_output());
+    }
+
     // ── Existing tests (preserved) ──
 
     #[tokio::test]
@@ -2111,6 +2184,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -2147,6 +2221,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "test-model", 0.0).await.unwrap();
 
@@ -2218,6 +2293,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let err = provider
             .chat(request, "test", 0.0)
@@ -2334,6 +2410,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "claude-opus", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("ok from sonnet"));
@@ -2382,6 +2459,7 @@ mod tests {
         let request = ChatRequest {
             messages: &messages,
             tools: None,
+            response_format: None,
         };
         let result = provider.chat(request, "test", 0.0).await.unwrap();
         assert_eq!(result.text.as_deref(), Some("from fallback"));
@@ -2733,6 +2811,7 @@ mod tests {
             ChatRequest {
                 messages: &messages,
                 tools: Some(&tools),
+                response_format: None,
             },
             "model",
             0.0,
@@ -2774,6 +2853,7 @@ mod tests {
             ChatRequest {
                 messages: &messages,
                 tools: Some(&tools),
+                response_format: None,
             },
             "model",
             0.0,
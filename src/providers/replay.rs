@@ -0,0 +1,1200 @@
+//! Record/replay providers for deterministic agent sessions.
+//!
+//! [`RecordingProvider`] wraps a live provider and writes every successful
+//! request/response pair (messages, tool names, tool calls, usage) to a JSONL
+//! session trace: a header line followed by one line per call, appended off
+//! the async runtime. Every provider recording to the same path shares one
+//! recorder, so providers rebuilt per request extend the same trace. Traces
+//! hold full prompts and responses and are only readable by their owner.
+//! [`ReplayProvider`] serves a trace back in order and reports where live
+//! requests diverge from the recorded ones, so customer sessions can be
+//! reproduced and regression suites run offline against the real agent loop.
+//!
+//! Configuration:
+//! - `provider_record_path = "session.jsonl"` records whatever provider is
+//!   configured.
+//! - `default_provider = "replay:session.jsonl"` replays a recorded trace.
+//!
+//! Replay also accepts the `LlmTrace` fixture format used by the integration
+//! tests (`{"model_name", "turns": [{"user_input", "steps": [...]}]}`). Those
+//! fixtures carry no requests, so their responses are served unchecked.
+
+use crate::providers::traits::{
+    ChatMessage, ChatRequest, ChatResponse, Provider, ProviderCapabilities, StreamChunk,
+    StreamEvent, StreamOptions, StreamResult, TokenUsage, ToolCall, ToolsPayload,
+};
+use crate::tools::ToolSpec;
+use async_trait::async_trait;
+use futures_util::{stream, StreamExt};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// Current session trace format version.
+pub const TRACE_VERSION: u32 = 1;
+
+/// Longest excerpt of message content quoted in mismatch diagnostics.
+const DIAGNOSTIC_EXCERPT_CHARS: usize = 120;
+
+fn default_trace_version() -> u32 {
+    TRACE_VERSION
+}
+
+/// A recorded provider session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionTrace {
+    #[serde(default = "default_trace_version")]
+    pub version: u32,
+    /// Whether the recorded provider used native tool calling. Replay mirrors
+    /// it so the agent builds the same kind of requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub native_tool_calling: Option<bool>,
+    #[serde(default)]
+    pub entries: Vec<TraceEntry>,
+}
+
+/// One provider call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceEntry {
+    /// The request as sent; absent for response-only fixtures.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<RecordedRequest>,
+    pub response: RecordedResponse,
+}
+
+/// The parts of a request that identify a call during replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub model: String,
+    pub temperature: f64,
+    pub messages: Vec<ChatMessage>,
+    /// Names of the tools offered to the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// OpenAI-style `response_format` payload, when one was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tool_calls: Vec<RecordedToolCall>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<RecordedUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning_content: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedToolCall {
+    pub id: String,
+    pub name: String,
+    /// Raw JSON argument string, exactly as the provider returned it.
+    pub arguments: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecordedUsage {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cached_input_tokens: Option<u64>,
+}
+
+impl RecordedRequest {
+    fn new(
+        messages: &[ChatMessage],
+        tools: Vec<String>,
+        response_format: Option<serde_json::Value>,
+        model: &str,
+        temperature: f64,
+    ) -> Self {
+        Self {
+            model: model.to_string(),
+            temperature,
+            messages: messages.to_vec(),
+            tools,
+            response_format,
+        }
+    }
+
+    fn from_chat_request(request: &ChatRequest<'_>, model: &str, temperature: f64) -> Self {
+        Self::new(
+            request.messages,
+            request
+                .tools
+                .unwrap_or_default()
+                .iter()
+                .map(|tool| tool.name.clone())
+                .collect(),
+            request
+                .response_format
+                .map(|format| format.to_openai_payload()),
+            model,
+            temperature,
+        )
+    }
+
+    fn from_system_prompt(
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> Self {
+        let mut messages = Vec::new();
+        if let Some(system) = system_prompt {
+            messages.push(ChatMessage::system(system));
+        }
+        messages.push(ChatMessage::user(message));
+        Self::new(&messages, Vec::new(), None, model, temperature)
+    }
+}
+
+/// Names of OpenAI-format tool definitions (`{"function": {"name": ..}}`).
+fn openai_tool_names(tools: &[serde_json::Value]) -> Vec<String> {
+    tools
+        .iter()
+        .filter_map(|tool| {
+            tool.get("function")
+                .and_then(|f| f.get("name"))
+                .or_else(|| tool.get("name"))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string)
+        })
+        .collect()
+}
+
+impl From<&ChatResponse> for RecordedResponse {
+    fn from(response: &ChatResponse) -> Self {
+        Self {
+            text: response.text.clone(),
+            tool_calls: response
+                .tool_calls
+                .iter()
+                .map(|call| RecordedToolCall {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    arguments: call.arguments.clone(),
+                })
+                .collect(),
+            usage: response.usage.as_ref().map(|usage| RecordedUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cached_input_tokens: usage.cached_input_tokens,
+            }),
+            reasoning_content: response.reasoning_content.clone(),
+        }
+    }
+}
+
+impl RecordedResponse {
+    fn to_chat_response(&self) -> ChatResponse {
+        ChatResponse {
+            text: self.text.clone(),
+            tool_calls: self
+                .tool_calls
+                .iter()
+                .map(|call| ToolCall {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    arguments: call.arguments.clone(),
+                })
+                .collect(),
+            usage: self.usage.as_ref().map(|usage| TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cached_input_tokens: usage.cached_input_tokens,
+            }),
+            reasoning_content: self.reasoning_content.clone(),
+        }
+    }
+}
+
+// ── Integration-test fixture format ─────────────────────────────
+
+#[derive(Deserialize)]
+struct FixtureTrace {
+    turns: Vec<FixtureTurn>,
+}
+
+#[derive(Deserialize)]
+struct FixtureTurn {
+    steps: Vec<FixtureStep>,
+}
+
+#[derive(Deserialize)]
+struct FixtureStep {
+    response: FixtureResponse,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum FixtureResponse {
+    #[serde(rename = "text")]
+    Text {
+        content: String,
+        #[serde(default)]
+        input_tokens: u64,
+        #[serde(default)]
+        output_tokens: u64,
+    },
+    #[serde(rename = "tool_calls")]
+    ToolCalls {
+        tool_calls: Vec<FixtureToolCall>,
+        #[serde(default)]
+        input_tokens: u64,
+        #[serde(default)]
+        output_tokens: u64,
+    },
+}
+
+#[derive(Deserialize)]
+struct FixtureToolCall {
+    id: String,
+    name: String,
+    arguments: serde_json::Value,
+}
+
+impl From<FixtureTrace> for SessionTrace {
+    fn from(fixture: FixtureTrace) -> Self {
+        let usage = |input_tokens, output_tokens| {
+            Some(RecordedUsage {
+                input_tokens: Some(input_tokens),
+                output_tokens: Some(output_tokens),
+                cached_input_tokens: None,
+            })
+        };
+        let entries = fixture
+            .turns
+            .into_iter()
+            .flat_map(|turn| turn.steps)
+            .map(|step| {
+                let response = match step.response {
+                    FixtureResponse::Text {
+                        content,
+                        input_tokens,
+                        output_tokens,
+                    } => RecordedResponse {
+                        text: Some(content),
+                        usage: usage(input_tokens, output_tokens),
+                        ..RecordedResponse::default()
+                    },
+                    FixtureResponse::ToolCalls {
+                        tool_calls,
+                        input_tokens,
+                        output_tokens,
+                    } => RecordedResponse {
+                        tool_calls: tool_calls
+                            .into_iter()
+                            .map(|call| RecordedToolCall {
+                                id: call.id,
+                                name: call.name,
+                                arguments: call.arguments.to_string(),
+                            })
+                            .collect(),
+                        usage: usage(input_tokens, output_tokens),
+                        ..RecordedResponse::default()
+                    },
+                };
+                TraceEntry {
+                    request: None,
+                    response,
+                }
+            })
+            .collect();
+
+        Self {
+            version: TRACE_VERSION,
+            native_tool_calling: Some(true),
+            entries,
+        }
+    }
+}
+
+impl SessionTrace {
+    /// Load a session trace (or an integration-test fixture) from disk.
+    ///
+    /// Accepts the JSONL form written by [`RecordingProvider`] as well as a
+    /// single JSON document.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read replay trace {}: {e}", path.display()))?;
+        let trace = match serde_json::from_str::<serde_json::Value>(&content) {
+            Ok(value) if value.get("turns").is_some() => {
+                serde_json::from_value::<FixtureTrace>(value).map(Self::from)
+            }
+            Ok(value) => serde_json::from_value::<Self>(value),
+            Err(_) => Self::parse_jsonl(&content),
+        }
+        .map_err(|e| anyhow::anyhow!("Invalid replay trace {}: {e}", path.display()))?;
+
+        if trace.version > TRACE_VERSION {
+            anyhow::bail!(
+                "Replay trace {} has version {}, newer than supported version {TRACE_VERSION}",
+                path.display(),
+                trace.version
+            );
+        }
+        Ok(trace)
+    }
+
+    /// Parse a header line followed by one [`TraceEntry`] per line.
+    fn parse_jsonl(content: &str) -> serde_json::Result<Self> {
+        let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+        let mut trace: Self = match lines.next() {
+            Some(header) => serde_json::from_str(header)?,
+            None => Self::default(),
+        };
+        for line in lines {
+            trace.entries.push(serde_json::from_str(line)?);
+        }
+        Ok(trace)
+    }
+
+    /// Write the trace as pretty JSON, replacing `path` atomically.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+// ── Replay ──────────────────────────────────────────────────────
+
+/// A live request that did not match its recorded counterpart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayMismatch {
+    /// Zero-based trace entry index.
+    pub entry: usize,
+    /// Human-readable differences, one per line.
+    pub details: Vec<String>,
+}
+
+impl std::fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "replay entry {}: {}",
+            self.entry,
+            self.details.join("; ")
+        )
+    }
+}
+
+/// Serves recorded responses in order.
+///
+/// Mismatches between live and recorded requests are logged and collected
+/// (see [`ReplayProvider::mismatches`]); in strict mode they fail the call
+/// instead. Entries are only consumed when served, so retries of a failed
+/// strict call see the same entry again.
+pub struct ReplayProvider {
+    source: String,
+    trace: SessionTrace,
+    cursor: Mutex<usize>,
+    strict: bool,
+    mismatches: Mutex<Vec<ReplayMismatch>>,
+}
+
+impl ReplayProvider {
+    pub fn new(trace: SessionTrace, source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            trace,
+            cursor: Mutex::new(0),
+            strict: false,
+            mismatches: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Load a trace file; `path` is also used as the source label in errors.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        Ok(Self::new(
+            SessionTrace::load(path)?,
+            path.display().to_string(),
+        ))
+    }
+
+    /// Fail calls whose request differs from the recording.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Mismatches observed so far (non-strict mode).
+    pub fn mismatches(&self) -> Vec<ReplayMismatch> {
+        self.mismatches.lock().clone()
+    }
+
+    /// Number of recorded entries not yet served.
+    pub fn remaining(&self) -> usize {
+        self.trace.entries.len().saturating_sub(*self.cursor.lock())
+    }
+
+    fn serve(&self, request: &RecordedRequest) -> anyhow::Result<ChatResponse> {
+        let mut cursor = self.cursor.lock();
+        let index = *cursor;
+        let Some(entry) = self.trace.entries.get(index) else {
+            anyhow::bail!(
+                "Replay trace {} exhausted after {} entries; unexpected request with last message {}",
+                self.source,
+                self.trace.entries.len(),
+                request
+                    .messages
+                    .last()
+                    .map(describe_message)
+                    .unwrap_or_else(|| "(none)".to_string())
+            );
+        };
+
+        if let Some(expected) = &entry.request {
+            let details = diff_requests(expected, request);
+            if !details.is_empty() {
+                let mismatch = ReplayMismatch {
+                    entry: index,
+                    details,
+                };
+                if self.strict {
+                    anyhow::bail!("Replay mismatch in {}: {mismatch}", self.source);
+                }
+                tracing::warn!(source = %self.source, "Replay mismatch: {mismatch}");
+                self.mismatches.lock().push(mismatch);
+            }
+        }
+
+        *cursor += 1;
+        Ok(entry.response.to_chat_response())
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    let mut out: String = text.chars().take(DIAGNOSTIC_EXCERPT_CHARS).collect();
+    if text.chars().count() > DIAGNOSTIC_EXCERPT_CHARS {
+        out.push('…');
+    }
+    format!("{out:?}")
+}
+
+fn describe_message(message: &ChatMessage) -> String {
+    format!("{} {}", message.role, excerpt(&message.content))
+}
+
+/// Differences between a recorded and a live request. Temperature is ignored.
+fn diff_requests(expected: &RecordedRequest, actual: &RecordedRequest) -> Vec<String> {
+    let mut details = Vec::new();
+
+    if expected.model != actual.model {
+        details.push(format!(
+            "model: expected {:?}, got {:?}",
+            expected.model, actual.model
+        ));
+    }
+
+    if let Some((position, (want, got))) = expected
+        .messages
+        .iter()
+        .zip(&actual.messages)
+        .enumerate()
+        .find(|(_, (want, got))| want.role != got.role || want.content != got.content)
+    {
+        details.push(format!(
+            "message {position}: expected {}, got {}",
+            describe_message(want),
+            describe_message(got)
+        ));
+    }
+    if expected.messages.len() != actual.messages.len() {
+        details.push(format!(
+            "message count: expected {}, got {}",
+            expected.messages.len(),
+            actual.messages.len()
+        ));
+    }
+
+    if expected.tools != actual.tools {
+        let missing: Vec<_> = expected
+            .tools
+            .iter()
+            .filter(|name| !actual.tools.contains(name))
+            .cloned()
+            .collect();
+        let extra: Vec<_> = actual
+            .tools
+            .iter()
+            .filter(|name| !expected.tools.contains(name))
+            .cloned()
+            .collect();
+        if missing.is_empty() && extra.is_empty() {
+            details.push("tools: same set in a different order".to_string());
+        } else {
+            details.push(format!("tools: missing {missing:?}, unexpected {extra:?}"));
+        }
+    }
+
+    if expected.response_format != actual.response_format {
+        details.push("response_format differs".to_string());
+    }
+
+    details
+}
+
+#[async_trait]
+impl Provider for ReplayProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: self.trace.native_tool_calling.unwrap_or(true),
+            vision: true,
+            prompt_caching: false,
+            structured_output: true,
+        }
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request =
+            RecordedRequest::from_system_prompt(system_prompt, message, model, temperature);
+        Ok(self.serve(&request)?.text.unwrap_or_default())
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let request = RecordedRequest::new(messages, Vec::new(), None, model, temperature);
+        Ok(self.serve(&request)?.text.unwrap_or_default())
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        self.serve(&RecordedRequest::from_chat_request(
+            &request,
+            model,
+            temperature,
+        ))
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let request =
+            RecordedRequest::new(messages, openai_tool_names(tools), None, model, temperature);
+        self.serve(&request)
+    }
+}
+
+// ── Recording ───────────────────────────────────────────────────
+
+/// Appends trace lines to one file. Shared by every [`RecordingProvider`]
+/// recording to the same path.
+struct Recorder {
+    path: PathBuf,
+    /// Serialized lines waiting to be written, in call order.
+    pending: Mutex<Vec<String>>,
+    /// The open trace file; `None` until the first flush truncates it.
+    file: Mutex<Option<File>>,
+}
+
+/// Process-wide recorders keyed by trace path.
+fn recorders() -> &'static Mutex<HashMap<PathBuf, Arc<Recorder>>> {
+    static RECORDERS: OnceLock<Mutex<HashMap<PathBuf, Arc<Recorder>>>> = OnceLock::new();
+    RECORDERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+impl Recorder {
+    /// Return the recorder for `path`, creating it (and queueing the trace
+    /// header) on first use.
+    fn shared(path: PathBuf, native_tool_calling: bool) -> Arc<Self> {
+        let mut recorders = recorders().lock();
+        let recorder = recorders.entry(path.clone()).or_insert_with(|| {
+            let header = SessionTrace {
+                version: TRACE_VERSION,
+                native_tool_calling: Some(native_tool_calling),
+                entries: Vec::new(),
+            };
+            let header = serde_json::to_string(&header).unwrap_or_default();
+            Arc::new(Self {
+                path,
+                pending: Mutex::new(vec![header]),
+                file: Mutex::new(None),
+            })
+        });
+        Arc::clone(recorder)
+    }
+
+    fn record(self: &Arc<Self>, request: RecordedRequest, response: RecordedResponse) {
+        let entry = TraceEntry {
+            request: Some(request),
+            response,
+        };
+        match serde_json::to_string(&entry) {
+            Ok(line) => self.pending.lock().push(line),
+            Err(e) => {
+                tracing::warn!(path = %self.path.display(), "Failed to encode trace entry: {e}");
+                return;
+            }
+        }
+        // Flush after every call so a crashed session still leaves a trace.
+        let recorder = Arc::clone(self);
+        crate::util::run_blocking(move || recorder.flush());
+    }
+
+    /// Append every pending line. Holding the file lock while draining keeps
+    /// lines in call order across concurrent flushes.
+    fn flush(&self) {
+        let mut file = self.file.lock();
+        let lines = std::mem::take(&mut *self.pending.lock());
+        if lines.is_empty() {
+            return;
+        }
+        if let Err(e) = self.append(&mut file, &lines) {
+            tracing::warn!(path = %self.path.display(), "Failed to write session trace: {e}");
+        }
+    }
+
+    fn append(&self, file: &mut Option<File>, lines: &[String]) -> std::io::Result<()> {
+        if file.is_none() {
+            if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut options = OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            let opened = options.open(&self.path)?;
+            // `mode` only applies to new files; tighten a pre-existing trace.
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                opened.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+            }
+            *file = Some(opened);
+        }
+        let Some(file) = file.as_mut() else {
+            return Ok(());
+        };
+        let mut buf = String::new();
+        for line in lines {
+            buf.push_str(line);
+            buf.push('\n');
+        }
+        file.write_all(buf.as_bytes())?;
+        file.flush()
+    }
+}
+
+/// Wait until every call recorded so far to `path` has been written.
+pub async fn flush_trace(path: &Path) {
+    let Some(recorder) = recorders().lock().get(path).cloned() else {
+        return;
+    };
+    if let Err(e) = tokio::task::spawn_blocking(move || recorder.flush()).await {
+        tracing::warn!("Session trace flush task failed: {e}");
+    }
+}
+
+/// Wraps a provider and records every successful call to a session trace.
+///
+/// Streaming calls are recorded once the stream reaches its final event.
+/// Failed calls are not recorded.
+pub struct RecordingProvider {
+    inner: Box<dyn Provider>,
+    recorder: Arc<Recorder>,
+}
+
+impl RecordingProvider {
+    /// Record to `path`. The first recorder for a path in this process
+    /// replaces any existing file on first write; later ones append to it.
+    pub fn new(inner: Box<dyn Provider>, path: impl Into<PathBuf>) -> Self {
+        let recorder = Recorder::shared(path.into(), inner.supports_native_tools());
+        Self { inner, recorder }
+    }
+
+    /// Record a chunk stream, accumulating text until the final chunk.
+    fn record_chunks(
+        &self,
+        request: RecordedRequest,
+        chunks: stream::BoxStream<'static, StreamResult<StreamChunk>>,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let recorder = Arc::clone(&self.recorder);
+        let mut request = Some(request);
+        let mut text = String::new();
+        chunks
+            .map(move |chunk| {
+                if let Ok(chunk) = &chunk {
+                    text.push_str(&chunk.delta);
+                    if chunk.is_final {
+                        if let Some(request) = request.take() {
+                            let response = RecordedResponse {
+                                text: Some(std::mem::take(&mut text)),
+                                ..RecordedResponse::default()
+                            };
+                            recorder.record(request, response);
+                        }
+                    }
+                }
+                chunk
+            })
+            .boxed()
+    }
+}
+
+#[async_trait]
+impl Provider for RecordingProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        self.inner.capabilities()
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        self.inner.convert_tools(tools)
+    }
+
+    async fn chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let text = self
+            .inner
+            .chat_with_system(system_prompt, message, model, temperature)
+            .await?;
+        self.recorder.record(
+            RecordedRequest::from_system_prompt(system_prompt, message, model, temperature),
+            RecordedResponse {
+                text: Some(text.clone()),
+                ..RecordedResponse::default()
+            },
+        );
+        Ok(text)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let text = self
+            .inner
+            .chat_with_history(messages, model, temperature)
+            .await?;
+        self.recorder.record(
+            RecordedRequest::new(messages, Vec::new(), None, model, temperature),
+            RecordedResponse {
+                text: Some(text.clone()),
+                ..RecordedResponse::default()
+            },
+        );
+        Ok(text)
+    }
+
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let response = self.inner.chat(request, model, temperature).await?;
+        self.recorder.record(
+            RecordedRequest::from_chat_request(&request, model, temperature),
+            RecordedResponse::from(&response),
+        );
+        Ok(response)
+    }
+
+    fn supports_native_tools(&self) -> bool {
+        self.inner.supports_native_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        self.inner.supports_structured_output()
+    }
+
+    async fn warmup(&self) -> anyhow::Result<()> {
+        self.inner.warmup().await
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let response = self
+            .inner
+            .chat_with_tools(messages, tools, model, temperature)
+            .await?;
+        self.recorder.record(
+            RecordedRequest::new(messages, openai_tool_names(tools), None, model, temperature),
+            RecordedResponse::from(&response),
+        );
+        Ok(response)
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.inner.supports_streaming()
+    }
+
+    fn supports_streaming_tool_events(&self) -> bool {
+        self.inner.supports_streaming_tool_events()
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let request =
+            RecordedRequest::from_system_prompt(system_prompt, message, model, temperature);
+        let chunks =
+            self.inner
+                .stream_chat_with_system(system_prompt, message, model, temperature, options);
+        self.record_chunks(request, chunks)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let request = RecordedRequest::new(messages, Vec::new(), None, model, temperature);
+        let chunks = self
+            .inner
+            .stream_chat_with_history(messages, model, temperature, options);
+        self.record_chunks(request, chunks)
+    }
+
+    fn stream_chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamEvent>> {
+        let recorder = Arc::clone(&self.recorder);
+        let mut recorded = Some(RecordedRequest::from_chat_request(
+            &request,
+            model,
+            temperature,
+        ));
+        let mut response = RecordedResponse::default();
+        self.inner
+            .stream_chat(request, model, temperature, options)
+            .map(move |event| {
+                match &event {
+                    Ok(StreamEvent::TextDelta(chunk)) => {
+                        response
+                            .text
+                            .get_or_insert_with(String::new)
+                            .push_str(&chunk.delta);
+                        if let Some(reasoning) = &chunk.reasoning {
+                            response
+                                .reasoning_content
+                                .get_or_insert_with(String::new)
+                                .push_str(reasoning);
+                        }
+                    }
+                    Ok(StreamEvent::ToolCall(call)) => {
+                        response.tool_calls.push(RecordedToolCall {
+                            id: call.id.clone(),
+                            name: call.name.clone(),
+                            arguments: call.arguments.clone(),
+                        });
+                    }
+                    Ok(StreamEvent::Final) => {
+                        if let Some(request) = recorded.take() {
+                            recorder.record(request, std::mem::take(&mut response));
+                        }
+                    }
+                    _ => {}
+                }
+                event
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                native_tool_calling: true,
+                ..ProviderCapabilities::default()
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(format!("echo: {message}"))
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            let last = request.messages.last().map_or("", |m| m.content.as_str());
+            Ok(ChatResponse {
+                text: Some(format!("echo: {last}")),
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    arguments: r#"{"command":"ls"}"#.into(),
+                }],
+                usage: Some(TokenUsage {
+                    input_tokens: Some(12),
+                    output_tokens: Some(3),
+                    cached_input_tokens: Some(4),
+                }),
+                reasoning_content: None,
+            })
+        }
+    }
+
+    fn shell_tool() -> ToolSpec {
+        ToolSpec {
+            name: "shell".into(),
+            description: "Run a command".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }
+    }
+
+    #[tokio::test]
+    async fn recording_then_replay_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.jsonl");
+        let tools = vec![shell_tool()];
+        let messages = vec![ChatMessage::system("sys"), ChatMessage::user("hello")];
+        let request = ChatRequest {
+            messages: &messages,
+            tools: Some(&tools),
+            response_format: None,
+        };
+
+        let recorder = RecordingProvider::new(Box::new(EchoProvider), &path);
+        let live = recorder.chat(request, "model-a", 0.2).await.unwrap();
+        let live_text = recorder
+            .chat_with_system(None, "ping", "model-a", 0.2)
+            .await
+            .unwrap();
+        flush_trace(&path).await;
+
+        let trace = SessionTrace::load(&path).unwrap();
+        assert_eq!(trace.entries.len(), 2);
+        assert_eq!(trace.native_tool_calling, Some(true));
+        let first = &trace.entries[0];
+        assert_eq!(first.request.as_ref().unwrap().tools, vec!["shell"]);
+        assert_eq!(
+            first.response.usage.as_ref().unwrap().cached_input_tokens,
+            Some(4)
+        );
+
+        let replay = ReplayProvider::from_file(&path).unwrap().with_strict(true);
+        assert!(replay.supports_native_tools());
+        let replayed = replay.chat(request, "model-a", 0.9).await.unwrap();
+        assert_eq!(replayed.text, live.text);
+        assert_eq!(
+            replayed.tool_calls[0].arguments,
+            live.tool_calls[0].arguments
+        );
+        assert_eq!(replayed.usage.unwrap().input_tokens, Some(12));
+        assert_eq!(
+            replay
+                .chat_with_system(None, "ping", "model-a", 0.2)
+                .await
+                .unwrap(),
+            live_text
+        );
+        assert_eq!(replay.remaining(), 0);
+        assert!(replay.mismatches().is_empty());
+
+        let err = replay
+            .chat_with_system(None, "again", "model-a", 0.2)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exhausted after 2 entries"));
+    }
+
+    #[tokio::test]
+    async fn recorders_for_one_path_append_to_a_shared_trace() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shared.jsonl");
+
+        for message in ["first", "second"] {
+            let recorder = RecordingProvider::new(Box::new(EchoProvider), &path);
+            recorder
+                .chat_with_system(None, message, "model-a", 0.2)
+                .await
+                .unwrap();
+        }
+        flush_trace(&path).await;
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 3);
+        let trace = SessionTrace::load(&path).unwrap();
+        assert_eq!(trace.native_tool_calling, Some(true));
+        let messages: Vec<_> = trace
+            .entries
+            .iter()
+            .map(|entry| entry.request.as_ref().unwrap().messages[0].content.clone())
+            .collect();
+        assert_eq!(messages, vec!["first", "second"]);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn trace_files_are_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("private.jsonl");
+        std::fs::write(&path, "stale").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let recorder = RecordingProvider::new(Box::new(EchoProvider), &path);
+        recorder
+            .chat_with_system(None, "secret", "model-a", 0.2)
+            .await
+            .unwrap();
+        flush_trace(&path).await;
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    fn single_entry_trace() -> SessionTrace {
+        SessionTrace {
+            version: TRACE_VERSION,
+            native_tool_calling: Some(false),
+            entries: vec![TraceEntry {
+                request: Some(RecordedRequest::new(
+                    &[ChatMessage::user("hello")],
+                    vec!["shell".into()],
+                    None,
+                    "model-a",
+                    0.0,
+                )),
+                response: RecordedResponse {
+                    text: Some("hi".into()),
+                    ..RecordedResponse::default()
+                },
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_reports_mismatches_and_still_serves() {
+        let replay = ReplayProvider::new(single_entry_trace(), "inline");
+        assert!(!replay.supports_native_tools());
+
+        let text = replay
+            .chat_with_history(&[ChatMessage::user("goodbye")], "model-b", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(text, "hi");
+
+        let mismatches = replay.mismatches();
+        assert_eq!(mismatches.len(), 1);
+        let rendered = mismatches[0].to_string();
+        assert!(rendered.contains("model: expected \"model-a\", got \"model-b\""));
+        assert!(rendered.contains("message 0: expected user \"hello\", got user \"goodbye\""));
+        assert!(rendered.contains("tools: missing [\"shell\"]"));
+    }
+
+    #[tokio::test]
+    async fn strict_replay_rejects_mismatch_without_consuming_entry() {
+        let replay = ReplayProvider::new(single_entry_trace(), "inline").with_strict(true);
+        let err = replay
+            .chat_with_history(&[ChatMessage::user("goodbye")], "model-a", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Replay mismatch in inline"));
+        assert_eq!(replay.remaining(), 1);
+    }
+
+    #[test]
+    fn loads_integration_fixture_format() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixture.json");
+        std::fs::write(
+            &path,
+            r#"{
+                "model_name": "fixture",
+                "turns": [{
+                    "user_input": "hi",
+                    "steps": [
+                        {"response": {"type": "tool_calls", "tool_calls": [
+                            {"id": "c1", "name": "echo", "arguments": {"message": "x"}}
+                        ], "input_tokens": 5, "output_tokens": 2}},
+                        {"response": {"type": "text", "content": "done"}}
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let trace = SessionTrace::load(&path).unwrap();
+        assert_eq!(trace.entries.len(), 2);
+        assert!(trace.entries[0].request.is_none());
+        assert_eq!(trace.entries[0].response.tool_calls[0].name, "echo");
+        assert_eq!(
+            trace.entries[0].response.tool_calls[0].arguments,
+            r#"{"message":"x"}"#
+        );
+        assert_eq!(trace.entries[1].response.text.as_deref(), Some("done"));
+    }
+}
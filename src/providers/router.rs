@@ -1,17 +1,110 @@
+use super::traits::StreamError;
 use super::traits::{
     ChatMessage, ChatRequest, ChatResponse, StreamChunk, StreamEvent, StreamOptions, StreamResult,
 };
 use super::Provider;
 use crate::config::schema::ModelPricing;
+use crate::cost::{BudgetCheck, CostTracker, UsagePeriod};
+use crate::observability::{Observer, ObserverEvent};
 use async_trait::async_trait;
-use futures_util::stream::BoxStream;
+use futures_util::stream::{self, BoxStream};
+use futures_util::StreamExt;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// A single route: maps a task hint to a provider + model combo.
 #[derive(Debug, Clone)]
 pub struct Route {
     pub provider_name: String,
     pub model: String,
+    /// Context window of the model in tokens, if known.
+    pub context_window: Option<usize>,
+}
+
+/// Live budget state consulted by [`RouterProvider`] before every request.
+///
+/// Enabled by `[cost.enforcement] mode = "route_down"`. Once spend crosses
+/// `warn_at_percent` of the daily or monthly limit, requests move to a cheaper
+/// route that still satisfies them; at the limit itself they are rejected.
+#[derive(Clone)]
+pub struct BudgetRouting {
+    tracker: Arc<CostTracker>,
+    prices: HashMap<String, ModelPricing>,
+    route_down_hint: Option<String>,
+    observer: Option<Arc<dyn Observer>>,
+}
+
+impl std::fmt::Debug for BudgetRouting {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BudgetRouting")
+            .field("route_down_hint", &self.route_down_hint)
+            .field("priced_models", &self.prices.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl BudgetRouting {
+    /// Create budget routing backed by `tracker`, scoring routes with `prices`.
+    pub fn new(tracker: Arc<CostTracker>, prices: HashMap<String, ModelPricing>) -> Self {
+        Self {
+            tracker,
+            prices,
+            route_down_hint: None,
+            observer: None,
+        }
+    }
+
+    /// Build budget routing from `[cost]`, using the process-global tracker.
+    /// Returns `None` unless cost tracking is enabled with `mode = "route_down"`.
+    pub fn from_config(config: &crate::config::Config) -> Option<Self> {
+        let cost = &config.cost;
+        if !cost.enabled || cost.enforcement.mode != "route_down" {
+            return None;
+        }
+        let tracker = CostTracker::get_or_init_global(cost.clone(), &config.workspace_dir)?;
+        Some(
+            Self::new(tracker, cost.prices.clone())
+                .with_route_down_model(cost.enforcement.route_down_model.clone()),
+        )
+    }
+
+    /// Prefer this route (`"hint:fast"` or `"fast"`) when downgrading.
+    pub fn with_route_down_model(mut self, model: Option<String>) -> Self {
+        self.route_down_hint = model.map(|m| m.strip_prefix("hint:").unwrap_or(&m).to_string());
+        self
+    }
+
+    /// Report downgrades to `observer`.
+    pub fn with_observer(mut self, observer: Arc<dyn Observer>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+}
+
+/// What a request needs from any route it is downgraded to.
+#[derive(Debug, Clone, Copy, Default)]
+struct RouteNeeds {
+    vision: bool,
+    native_tools: bool,
+    structured_output: bool,
+    tokens: usize,
+}
+
+impl RouteNeeds {
+    fn for_messages(messages: &[ChatMessage]) -> Self {
+        Self {
+            vision: crate::multimodal::contains_image_markers(messages),
+            tokens: crate::agent::context_compressor::estimate_tokens(messages),
+            ..Self::default()
+        }
+    }
+
+    fn for_prompt(system_prompt: Option<&str>, message: &str) -> Self {
+        let mut messages: Vec<ChatMessage> =
+            system_prompt.map(ChatMessage::system).into_iter().collect();
+        messages.push(ChatMessage::user(message));
+        Self::for_messages(&messages)
+    }
 }
 
 /// Multi-model router — routes requests to different provider+model combos
@@ -24,9 +117,11 @@ pub struct Route {
 /// This wraps multiple pre-created providers and selects the right one per request.
 pub struct RouterProvider {
     routes: HashMap<String, (usize, String)>, // hint → (provider_index, model)
+    context_windows: HashMap<String, usize>,  // hint → context window tokens
     providers: Vec<(String, Box<dyn Provider>)>,
     default_index: usize,
     default_model: String,
+    budget: Option<BudgetRouting>,
 }
 
 impl RouterProvider {
@@ -46,6 +141,11 @@ impl RouterProvider {
             .map(|(i, (name, _))| (name.as_str(), i))
             .collect();
 
+        let context_windows: HashMap<String, usize> = routes
+            .iter()
+            .filter_map(|(hint, route)| Some((hint.clone(), route.context_window?)))
+            .collect();
+
         // Resolve routes to provider indices
         let resolved_routes: HashMap<String, (usize, String)> = routes
             .into_iter()
@@ -67,12 +167,20 @@ impl RouterProvider {
 
         Self {
             routes: resolved_routes,
+            context_windows,
             providers,
             default_index: 0,
             default_model,
+            budget: None,
         }
     }
 
+    /// Shift requests to cheaper routes as spend approaches the budget limits.
+    pub fn with_budget(mut self, budget: Option<BudgetRouting>) -> Self {
+        self.budget = budget;
+        self
+    }
+
     /// Resolve a model parameter to the cheapest qualifying route based on pricing.
     ///
     /// If the model starts with `"hint:cost-optimized"` or `"hint:cheapest"`, this
@@ -148,6 +256,136 @@ impl RouterProvider {
         // Not a hint or hint not found — use default provider with the model as-is
         (self.default_index, model.to_string())
     }
+
+    /// Apply budget routing to an already-resolved route.
+    ///
+    /// Returns the route unchanged while spend is below the warning threshold,
+    /// a cheaper qualifying route past it, and an error once a limit is reached.
+    fn budget_route(
+        &self,
+        resolved: (usize, String),
+        needs: impl FnOnce() -> RouteNeeds,
+    ) -> anyhow::Result<(usize, String)> {
+        let Some(budget) = &self.budget else {
+            return Ok(resolved);
+        };
+
+        let (current_usd, limit_usd, period) = match budget.tracker.check_budget(0.0) {
+            Ok(BudgetCheck::Allowed) => return Ok(resolved),
+            Ok(BudgetCheck::Warning {
+                current_usd,
+                limit_usd,
+                period,
+            }) => (current_usd, limit_usd, period),
+            Ok(BudgetCheck::Exceeded {
+                current_usd,
+                limit_usd,
+                period,
+            }) => anyhow::bail!(
+                "Budget exceeded: ${current_usd:.4} of ${limit_usd:.2} {period:?} limit. Cannot make further API calls until the budget resets."
+            ),
+            Err(e) => {
+                tracing::warn!("Budget check failed, routing without downgrade: {e}");
+                return Ok(resolved);
+            }
+        };
+
+        let Some(downgraded) = self.cheaper_route(budget, &resolved, &needs()) else {
+            return Ok(resolved);
+        };
+
+        let period = match period {
+            UsagePeriod::Session => "session",
+            UsagePeriod::Day => "daily",
+            UsagePeriod::Month => "monthly",
+        };
+        tracing::warn!(
+            requested_model = resolved.1.as_str(),
+            routed_model = downgraded.1.as_str(),
+            period,
+            current_usd,
+            limit_usd,
+            "Budget nearly spent, routing request to a cheaper model"
+        );
+        if let Some(observer) = &budget.observer {
+            observer.record_event(&ObserverEvent::BudgetDowngrade {
+                requested_model: resolved.1.clone(),
+                routed_model: downgraded.1.clone(),
+                period: period.to_string(),
+                current_usd,
+                limit_usd,
+            });
+        }
+        Ok(downgraded)
+    }
+
+    /// Pick the route to downgrade to: the configured `route_down_model` if it
+    /// satisfies the request, otherwise the cheapest priced route that is cheaper
+    /// than the requested model and satisfies it.
+    fn cheaper_route(
+        &self,
+        budget: &BudgetRouting,
+        (requested_idx, requested_model): &(usize, String),
+        needs: &RouteNeeds,
+    ) -> Option<(usize, String)> {
+        let requested = self.providers.get(*requested_idx).map(|(_, p)| p)?;
+        let satisfies = |hint: &str, idx: usize| {
+            let Some((_, provider)) = self.providers.get(idx) else {
+                return false;
+            };
+            if needs.vision && !provider.supports_vision() {
+                return false;
+            }
+            if needs.native_tools
+                && requested.supports_native_tools()
+                && !provider.supports_native_tools()
+            {
+                return false;
+            }
+            if needs.structured_output
+                && requested.supports_structured_output()
+                && !provider.supports_structured_output()
+            {
+                return false;
+            }
+            self.context_windows
+                .get(hint)
+                .is_none_or(|window| needs.tokens <= *window)
+        };
+
+        if let Some(hint) = &budget.route_down_hint {
+            if let Some((idx, model)) = self.routes.get(hint) {
+                if model != requested_model && satisfies(hint, *idx) {
+                    return Some((*idx, model.clone()));
+                }
+            }
+        }
+
+        let price_of = |model: &str| budget.prices.get(model).map(|p| p.input + p.output);
+        let requested_price = price_of(requested_model)?;
+        self.routes
+            .iter()
+            .filter(|(hint, (idx, model))| model != requested_model && satisfies(hint, *idx))
+            .filter_map(|(_, (idx, model))| {
+                let price = price_of(model)?;
+                (price < requested_price).then_some((*idx, model, price))
+            })
+            .min_by(|a, b| {
+                a.2.partial_cmp(&b.2)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.1.cmp(b.1))
+            })
+            .map(|(idx, model, _)| (idx, model.clone()))
+    }
+}
+
+fn budget_error_stream<T: Send + 'static>(
+    error: &anyhow::Error,
+) -> BoxStream<'static, crate::providers::traits::StreamResult<T>> {
+    stream::once(futures_util::future::ready(Err(StreamError::Provider(
+        error.to_string(),
+    ))))
+    .boxed()
 }
 
 /// A cost-optimized routing strategy that selects the cheapest qualifying
@@ -203,7 +441,9 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model) = self.budget_route(self.resolve(model), || {
+            RouteNeeds::for_prompt(system_prompt, message)
+        })?;
 
         let (provider_name, provider) = &self.providers[provider_idx];
         tracing::info!(
@@ -223,7 +463,8 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model) =
+            self.budget_route(self.resolve(model), || RouteNeeds::for_messages(messages))?;
         let (_, provider) = &self.providers[provider_idx];
         provider
             .chat_with_history(messages, &resolved_model, temperature)
@@ -236,7 +477,12 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model) =
+            self.budget_route(self.resolve(model), || RouteNeeds {
+                native_tools: request.tools.is_some_and(|t| !t.is_empty()),
+                structured_output: request.response_format.is_some(),
+                ..RouteNeeds::for_messages(request.messages)
+            })?;
         let (_, provider) = &self.providers[provider_idx];
         provider.chat(request, &resolved_model, temperature).await
     }
@@ -248,7 +494,11 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model) =
+            self.budget_route(self.resolve(model), || RouteNeeds {
+                native_tools: !tools.is_empty(),
+                ..RouteNeeds::for_messages(messages)
+            })?;
         let (_, provider) = &self.providers[provider_idx];
         provider
             .chat_with_tools(messages, tools, &resolved_model, temperature)
@@ -262,6 +512,13 @@ impl Provider for RouterProvider {
             .unwrap_or(false)
     }
 
+    fn supports_structured_output(&self) -> bool {
+        self.providers
+            .get(self.default_index)
+            .map(|(_, p)| p.supports_structured_output())
+            .unwrap_or(false)
+    }
+
     fn supports_streaming(&self) -> bool {
         self.providers
             .iter()
@@ -281,7 +538,11 @@ impl Provider for RouterProvider {
         temperature: f64,
         options: StreamOptions,
     ) -> BoxStream<'static, StreamResult<StreamChunk>> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model) =
+            match self.budget_route(self.resolve(model), || RouteNeeds::for_messages(messages)) {
+                Ok(route) => route,
+                Err(e) => return budget_error_stream(&e),
+            };
         let (_, provider) = &self.providers[provider_idx];
         provider.stream_chat_with_history(messages, &resolved_model, temperature, options)
     }
@@ -293,7 +554,15 @@ impl Provider for RouterProvider {
         temperature: f64,
         options: StreamOptions,
     ) -> BoxStream<'static, StreamResult<StreamEvent>> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let needs = || RouteNeeds {
+            native_tools: request.tools.is_some_and(|t| !t.is_empty()),
+            structured_output: request.response_format.is_some(),
+            ..RouteNeeds::for_messages(request.messages)
+        };
+        let (provider_idx, resolved_model) = match self.budget_route(self.resolve(model), needs) {
+            Ok(route) => route,
+            Err(e) => return budget_error_stream(&e),
+        };
         let (_, provider) = &self.providers[provider_idx];
         provider.stream_chat(request, &resolved_model, temperature, options)
     }
@@ -390,6 +659,7 @@ mod tests {
                     Route {
                         provider_name: (*provider_name).to_string(),
                         model: (*model).to_string(),
+                        context_window: None,
                     },
                 )
             })
@@ -805,6 +1075,7 @@ mod tests {
                 native_tool_calling: self.tools,
                 vision: self.vision,
                 prompt_caching: false,
+                structured_output: false,
             }
         }
 
@@ -822,7 +1093,16 @@ mod tests {
     fn make_pricing(entries: Vec<(&str, f64, f64)>) -> HashMap<String, ModelPricing> {
         entries
             .into_iter()
-            .map(|(model, input, output)| (model.to_string(), ModelPricing { input, output }))
+            .map(|(model, input, output)| {
+                (
+                    model.to_string(),
+                    ModelPricing {
+                        input,
+                        output,
+                        cached_input: None,
+                    },
+                )
+            })
             .collect()
     }
 
@@ -844,6 +1124,7 @@ mod tests {
                 Route {
                     provider_name: "expensive".into(),
                     model: "big-model".into(),
+                    context_window: None,
                 },
             ),
             (
@@ -851,6 +1132,7 @@ mod tests {
                 Route {
                     provider_name: "cheap".into(),
                     model: "small-model".into(),
+                    context_window: None,
                 },
             ),
         ];
@@ -882,6 +1164,7 @@ mod tests {
                 Route {
                     provider_name: "no-vision".into(),
                     model: "cheap-model".into(),
+                    context_window: None,
                 },
             ),
             (
@@ -889,6 +1172,7 @@ mod tests {
                 Route {
                     provider_name: "has-vision".into(),
                     model: "vision-model".into(),
+                    context_window: None,
                 },
             ),
         ];
@@ -922,6 +1206,7 @@ mod tests {
                 Route {
                     provider_name: "no-tools".into(),
                     model: "basic-model".into(),
+                    context_window: None,
                 },
             ),
             (
@@ -929,6 +1214,7 @@ mod tests {
                 Route {
                     provider_name: "has-tools".into(),
                     model: "tools-model".into(),
+                    context_window: None,
                 },
             ),
         ];
@@ -970,6 +1256,7 @@ mod tests {
             Route {
                 provider_name: "only".into(),
                 model: "the-model".into(),
+                context_window: None,
             },
         )];
         let router = RouterProvider::new(providers, routes, "default-model".into());
@@ -1003,6 +1290,7 @@ mod tests {
                 Route {
                     provider_name: "p1".into(),
                     model: "model-a".into(),
+                    context_window: None,
                 },
             ),
             (
@@ -1010,6 +1298,7 @@ mod tests {
                 Route {
                     provider_name: "p2".into(),
                     model: "model-b".into(),
+                    context_window: None,
                 },
             ),
             (
@@ -1017,6 +1306,7 @@ mod tests {
                 Route {
                     provider_name: "p3".into(),
                     model: "model-c".into(),
+                    context_window: None,
                 },
             ),
         ];
@@ -1044,6 +1334,189 @@ mod tests {
         assert!(strategy.score("unknown").is_none());
     }
 
+    // ── Budget-aware routing ─────────────────────────────────
+
+    struct RecordingObserver(parking_lot::Mutex<Vec<String>>);
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::BudgetDowngrade {
+                requested_model,
+                routed_model,
+                period,
+                ..
+            } = event
+            {
+                self.0
+                    .lock()
+                    .push(format!("{requested_model}->{routed_model} ({period})"));
+            }
+        }
+
+        fn record_metric(&self, _metric: &crate::observability::traits::ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    /// Tracker with `spent_usd` already recorded against a $10 daily limit (warn at 80%).
+    fn tracker_with_spend(tmp: &tempfile::TempDir, spent_usd: f64) -> Arc<CostTracker> {
+        let config = crate::config::CostConfig {
+            enabled: true,
+            daily_limit_usd: 10.0,
+            monthly_limit_usd: 100.0,
+            warn_at_percent: 80,
+            ..crate::config::CostConfig::default()
+        };
+        let tracker = CostTracker::new(config, tmp.path()).unwrap();
+        tracker
+            .record_usage(crate::cost::TokenUsage::new(
+                "big-model",
+                1_000_000,
+                0,
+                spent_usd,
+                0.0,
+            ))
+            .unwrap();
+        Arc::new(tracker)
+    }
+
+    fn budget_router(
+        tracker: Arc<CostTracker>,
+        observer: Arc<RecordingObserver>,
+        route_down: Option<&str>,
+    ) -> RouterProvider {
+        let providers: Vec<(String, Box<dyn Provider>)> = vec![
+            (
+                "premium".into(),
+                Box::new(CapableMockProvider::new("premium", true, true)),
+            ),
+            (
+                "text-only".into(),
+                Box::new(CapableMockProvider::new("text-only", false, true)),
+            ),
+            (
+                "small-vision".into(),
+                Box::new(CapableMockProvider::new("small-vision", true, true)),
+            ),
+        ];
+        let route = |provider: &str, model: &str, context_window: Option<usize>| Route {
+            provider_name: provider.into(),
+            model: model.into(),
+            context_window,
+        };
+        let routes = vec![
+            ("reasoning".to_string(), route("premium", "big-model", None)),
+            ("fast".to_string(), route("text-only", "tiny-model", None)),
+            (
+                "vision".to_string(),
+                route("small-vision", "mid-model", Some(1_000)),
+            ),
+        ];
+        let prices = make_pricing(vec![
+            ("big-model", 15.0, 75.0),
+            ("tiny-model", 0.1, 0.4),
+            ("mid-model", 1.0, 4.0),
+        ]);
+        let budget = BudgetRouting::new(tracker, prices)
+            .with_route_down_model(route_down.map(str::to_string))
+            .with_observer(observer);
+        RouterProvider::new(providers, routes, "big-model".into()).with_budget(Some(budget))
+    }
+
+    #[tokio::test]
+    async fn budget_below_warning_keeps_requested_route() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let observer = Arc::new(RecordingObserver(parking_lot::Mutex::new(Vec::new())));
+        let router = budget_router(tracker_with_spend(&tmp, 2.0), observer.clone(), None);
+
+        let result = router
+            .chat_with_system(None, "hi", "hint:reasoning", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(result, "premium");
+        assert!(observer.0.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn budget_warning_downgrades_to_cheapest_capable_route() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let observer = Arc::new(RecordingObserver(parking_lot::Mutex::new(Vec::new())));
+        let router = budget_router(tracker_with_spend(&tmp, 8.5), observer.clone(), None);
+
+        let text = router
+            .chat_with_system(None, "hi", "hint:reasoning", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(text, "text-only");
+
+        // Images rule out the text-only route
+        let vision = router
+            .chat_with_system(None, "look [IMAGE:/tmp/a.png]", "hint:reasoning", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(vision, "small-vision");
+
+        // Too long for the vision route's context window: stay on the requested model
+        let long = format!("[IMAGE:/tmp/a.png] {}", "x".repeat(8_000));
+        let premium = router
+            .chat_with_system(None, &long, "hint:reasoning", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(premium, "premium");
+
+        assert_eq!(
+            *observer.0.lock(),
+            vec![
+                "big-model->tiny-model (daily)".to_string(),
+                "big-model->mid-model (daily)".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn budget_warning_prefers_route_down_model() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let observer = Arc::new(RecordingObserver(parking_lot::Mutex::new(Vec::new())));
+        let router = budget_router(
+            tracker_with_spend(&tmp, 8.5),
+            observer.clone(),
+            Some("hint:vision"),
+        );
+
+        let result = router
+            .chat_with_system(None, "hi", "big-model", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(result, "small-vision");
+    }
+
+    #[tokio::test]
+    async fn budget_exceeded_rejects_requests() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let observer = Arc::new(RecordingObserver(parking_lot::Mutex::new(Vec::new())));
+        let router = budget_router(tracker_with_spend(&tmp, 10.5), observer, None);
+
+        let err = router
+            .chat_with_system(None, "hi", "hint:fast", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Budget exceeded"));
+
+        let mut stream = router.stream_chat_with_history(
+            &[ChatMessage::user("hi")],
+            "hint:fast",
+            0.0,
+            StreamOptions::new(true),
+        );
+        assert!(stream.next().await.unwrap().is_err());
+    }
+
     #[tokio::test]
     async fn supports_streaming_returns_true_when_any_provider_supports_it() {
         let streaming = Arc::new(StreamingMockProvider::new("stream"));
@@ -1063,6 +1536,7 @@ mod tests {
                 Route {
                     provider_name: "streaming".into(),
                     model: "claude-opus".into(),
+                    context_window: None,
                 },
             )],
             "model".into(),
@@ -1090,6 +1564,7 @@ mod tests {
                 Route {
                     provider_name: "streaming".into(),
                     model: "claude-opus".into(),
+                    context_window: None,
                 },
             )],
             "model".into(),
@@ -1133,6 +1608,7 @@ mod tests {
                 Route {
                     provider_name: "streaming".into(),
                     model: "claude-opus".into(),
+                    context_window: None,
                 },
             )],
             "model".into(),
@@ -1154,6 +1630,7 @@ mod tests {
             ChatRequest {
                 messages: &messages,
                 tools: Some(&tools),
+                response_format: None,
             },
             "hint:reasoning",
             0.0,
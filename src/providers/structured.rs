@@ -0,0 +1,565 @@
+//! Structured (JSON / JSON-schema) responses on top of [`Provider::chat`].
+//!
+//! Providers that declare `structured_output` receive the [`ResponseFormat`]
+//! on the request and constrain decoding natively. Everyone else gets the
+//! format as system prompt instructions. In both cases the reply is parsed
+//! and validated here, and invalid replies are sent back to the model with
+//! the validation error for up to [`MAX_REPAIR_ATTEMPTS`] repair rounds.
+//!
+//! Schema validation is intentionally lightweight (no full JSON Schema
+//! implementation): `type`, `enum`, `const`, `properties`, `required`,
+//! `additionalProperties: false`, `items`, `anyOf`/`oneOf`, and the common
+//! length / range bounds.
+
+use crate::providers::traits::{
+    append_system_instructions, ChatMessage, ChatRequest, Provider, ResponseFormat,
+};
+use serde_json::Value;
+
+/// Additional attempts after the first reply fails validation.
+pub const MAX_REPAIR_ATTEMPTS: usize = 2;
+
+/// Error returned by [`chat_structured`].
+#[derive(Debug, thiserror::Error)]
+pub enum StructuredOutputError {
+    /// The provider call itself failed.
+    #[error(transparent)]
+    Provider(#[from] anyhow::Error),
+    /// Every attempt produced a reply that did not satisfy the format.
+    #[error("response did not match the requested format after {attempts} attempt(s): {reason}")]
+    Invalid {
+        attempts: usize,
+        reason: String,
+        /// Raw text of the last rejected reply.
+        last_response: String,
+    },
+}
+
+/// Ask `provider` for a reply matching `format`, validating the result and
+/// retrying with repair feedback when it does not conform.
+pub async fn chat_structured(
+    provider: &dyn Provider,
+    messages: &[ChatMessage],
+    format: &ResponseFormat,
+    model: &str,
+    temperature: f64,
+) -> Result<Value, StructuredOutputError> {
+    let native = provider.supports_structured_output();
+    let mut conversation = messages.to_vec();
+    if !native {
+        append_system_instructions(&mut conversation, &format.instructions());
+    }
+
+    let attempts = MAX_REPAIR_ATTEMPTS + 1;
+    let mut reason = String::new();
+    let mut last_response = String::new();
+    for attempt in 1..=attempts {
+        let response = provider
+            .chat(
+                ChatRequest {
+                    messages: &conversation,
+                    tools: None,
+                    response_format: native.then_some(format),
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        let text = response.text.unwrap_or_default();
+
+        match parse_and_validate(&text, format) {
+            Ok(value) => return Ok(value),
+            Err(problem) => {
+                tracing::debug!(attempt, native, "Structured response rejected: {problem}");
+                if attempt < attempts {
+                    conversation.push(ChatMessage::assistant(text.clone()));
+                    conversation.push(ChatMessage::user(repair_prompt(&problem)));
+                }
+                reason = problem;
+                last_response = text;
+            }
+        }
+    }
+
+    Err(StructuredOutputError::Invalid {
+        attempts,
+        reason,
+        last_response,
+    })
+}
+
+/// Parse a model reply and check it against `format`.
+pub fn parse_and_validate(text: &str, format: &ResponseFormat) -> Result<Value, String> {
+    let value = parse_json_response(text)?;
+    match format.schema() {
+        Some(schema) => validate_against_schema(&value, schema)?,
+        None if !value.is_object() => {
+            return Err(format!(
+                "Response has wrong type: expected object, got {}",
+                json_type_name(&value)
+            ));
+        }
+        None => {}
+    }
+    Ok(value)
+}
+
+/// Extract a JSON value from a model reply, tolerating markdown code fences
+/// and prose around a single top-level object or array.
+pub fn parse_json_response(text: &str) -> Result<Value, String> {
+    let trimmed = text.trim();
+    let unfenced = if trimmed.starts_with("```") {
+        trimmed
+            .trim_start_matches("```json")
+            .trim_start_matches("```")
+            .trim_end_matches("```")
+            .trim()
+    } else {
+        trimmed
+    };
+
+    let first_error = match serde_json::from_str(unfenced) {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+
+    let start = unfenced.find(['{', '[']);
+    let end = unfenced.rfind(['}', ']']);
+    if let (Some(start), Some(end)) = (start, end) {
+        if start < end {
+            if let Ok(value) = serde_json::from_str(&unfenced[start..=end]) {
+                return Ok(value);
+            }
+        }
+    }
+
+    Err(format!("Invalid JSON: {first_error}"))
+}
+
+/// Validate `value` against the supported subset of JSON Schema.
+pub fn validate_against_schema(value: &Value, schema: &Value) -> Result<(), String> {
+    validate_at(value, schema, "")
+}
+
+fn validate_at(value: &Value, schema: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // `true` / `{}` / non-object schemas accept anything.
+        return Ok(());
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let matches = match expected {
+            Value::String(name) => type_matches(value, name),
+            Value::Array(names) => names
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|name| type_matches(value, name)),
+            _ => true,
+        };
+        if !matches {
+            let expected = match expected {
+                Value::Array(names) => names
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" | "),
+                other => other.as_str().unwrap_or_default().to_string(),
+            };
+            return Err(format!(
+                "{} has wrong type: expected {expected}, got {}",
+                describe(path),
+                json_type_name(value)
+            ));
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            return Err(format!(
+                "{} must be one of {}",
+                describe(path),
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            return Err(format!("{} must equal {expected}", describe(path)));
+        }
+    }
+
+    for key in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(key).and_then(Value::as_array) {
+            if !branches.is_empty()
+                && !branches
+                    .iter()
+                    .any(|branch| validate_at(value, branch, path).is_ok())
+            {
+                return Err(format!(
+                    "{} does not match any allowed schema",
+                    describe(path)
+                ));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(map) => {
+            if let Some(required) = schema.get("required").and_then(Value::as_array) {
+                for field in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(field) {
+                        return Err(format!(
+                            "Missing required field: {}",
+                            join_path(path, field)
+                        ));
+                    }
+                }
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (field, field_value) in map {
+                match properties.and_then(|p| p.get(field)) {
+                    Some(field_schema) => {
+                        validate_at(field_value, field_schema, &join_path(path, field))?;
+                    }
+                    None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                        return Err(format!("Unexpected field: {}", join_path(path, field)));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    return Err(format!(
+                        "{} must have at least {min} item(s)",
+                        describe(path)
+                    ));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if (items.len() as u64) > max {
+                    return Err(format!(
+                        "{} must have at most {max} item(s)",
+                        describe(path)
+                    ));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_at(item, item_schema, &format!("{path}[{index}]"))?;
+                }
+            }
+        }
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    return Err(format!(
+                        "{} must be at least {min} character(s)",
+                        describe(path)
+                    ));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    return Err(format!(
+                        "{} must be at most {max} character(s)",
+                        describe(path)
+                    ));
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    return Err(format!("{} must be >= {min}", describe(path)));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    return Err(format!("{} must be <= {max}", describe(path)));
+                }
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+
+    Ok(())
+}
+
+/// Check whether a JSON value matches an expected JSON Schema type string.
+pub fn type_matches(value: &Value, expected: &str) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true, // Unknown type — accept
+    }
+}
+
+/// Return a human-readable type name for a JSON value.
+pub fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn join_path(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{path}.{field}")
+    }
+}
+
+fn describe(path: &str) -> String {
+    if path.is_empty() {
+        "Response".to_string()
+    } else {
+        format!("Field '{path}'")
+    }
+}
+
+fn repair_prompt(problem: &str) -> String {
+    format!(
+        "Your previous reply was rejected: {problem}. Reply again with only the corrected \
+         JSON value, without any explanation or markdown."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::{ChatResponse, ProviderCapabilities};
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    struct ScriptedProvider {
+        native: bool,
+        replies: Mutex<Vec<&'static str>>,
+        requests: Mutex<Vec<(Vec<ChatMessage>, Option<ResponseFormat>)>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(native: bool, replies: &[&'static str]) -> Self {
+            Self {
+                native,
+                replies: Mutex::new(replies.iter().rev().copied().collect()),
+                requests: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn capabilities(&self) -> ProviderCapabilities {
+            ProviderCapabilities {
+                structured_output: self.native,
+                ..ProviderCapabilities::default()
+            }
+        }
+
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            unreachable!("chat() is overridden")
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.requests
+                .lock()
+                .unwrap()
+                .push((request.messages.to_vec(), request.response_format.cloned()));
+            let reply = self
+                .replies
+                .lock()
+                .unwrap()
+                .pop()
+                .ok_or_else(|| anyhow::anyhow!("no scripted reply left"))?;
+            Ok(ChatResponse {
+                text: Some(reply.to_string()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning_content: None,
+            })
+        }
+    }
+
+    fn person_format() -> ResponseFormat {
+        ResponseFormat::json_schema(
+            "person",
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "minLength": 1 },
+                    "age": { "type": "integer", "minimum": 0 },
+                    "tags": { "type": "array", "items": { "type": "string" } }
+                },
+                "required": ["name", "age"],
+                "additionalProperties": false
+            }),
+        )
+    }
+
+    #[test]
+    fn parse_json_response_handles_fences_and_prose() {
+        assert_eq!(
+            parse_json_response("```json\n{\"a\": 1}\n```").unwrap(),
+            json!({"a": 1})
+        );
+        assert_eq!(
+            parse_json_response("Here you go: {\"a\": [1, 2]} — done").unwrap(),
+            json!({"a": [1, 2]})
+        );
+        assert!(parse_json_response("no json here")
+            .unwrap_err()
+            .starts_with("Invalid JSON"));
+    }
+
+    #[test]
+    fn validation_reports_nested_paths() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "items": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": { "id": { "type": "integer" } },
+                        "required": ["id"]
+                    }
+                },
+                "mode": { "enum": ["fast", "slow"] }
+            }
+        });
+
+        assert!(validate_against_schema(&json!({"items": [{"id": 1}]}), &schema).is_ok());
+        assert_eq!(
+            validate_against_schema(&json!({"items": [{"id": "x"}]}), &schema).unwrap_err(),
+            "Field 'items[0].id' has wrong type: expected integer, got string"
+        );
+        assert_eq!(
+            validate_against_schema(&json!({"items": [{}]}), &schema).unwrap_err(),
+            "Missing required field: items[0].id"
+        );
+        assert!(validate_against_schema(&json!({"mode": "medium"}), &schema)
+            .unwrap_err()
+            .contains("must be one of"));
+    }
+
+    #[test]
+    fn validation_supports_type_unions_and_any_of() {
+        let nullable = json!({ "type": ["string", "null"] });
+        assert!(validate_against_schema(&json!(null), &nullable).is_ok());
+        assert!(validate_against_schema(&json!(3), &nullable).is_err());
+
+        let any_of = json!({ "anyOf": [{ "type": "integer" }, { "type": "boolean" }] });
+        assert!(validate_against_schema(&json!(true), &any_of).is_ok());
+        assert!(validate_against_schema(&json!("x"), &any_of).is_err());
+    }
+
+    #[test]
+    fn json_object_format_requires_object() {
+        assert!(parse_and_validate("{\"ok\": true}", &ResponseFormat::JsonObject).is_ok());
+        assert!(parse_and_validate("[1]", &ResponseFormat::JsonObject)
+            .unwrap_err()
+            .contains("expected object"));
+    }
+
+    #[tokio::test]
+    async fn native_provider_receives_format_without_instructions() {
+        let provider = ScriptedProvider::new(true, &[r#"{"name": "Ada", "age": 36}"#]);
+        let format = person_format();
+        let value = chat_structured(
+            &provider,
+            &[ChatMessage::user("who?")],
+            &format,
+            "model",
+            0.0,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(value["name"], "Ada");
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].1.as_ref(), Some(&format));
+        assert!(requests[0].0.iter().all(|m| m.role != "system"));
+    }
+
+    #[tokio::test]
+    async fn fallback_injects_instructions_and_repairs_invalid_reply() {
+        let provider = ScriptedProvider::new(
+            false,
+            &["Sure! {\"name\": \"Ada\"}", r#"{"name": "Ada", "age": 36}"#],
+        );
+        let value = chat_structured(
+            &provider,
+            &[ChatMessage::system("Be terse."), ChatMessage::user("who?")],
+            &person_format(),
+            "model",
+            0.0,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(value["age"], 36);
+        let requests = provider.requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].1.is_none());
+        assert!(requests[0].0[0].content.contains("## Response Format"));
+        let repair = requests[1].0.last().unwrap();
+        assert_eq!(repair.role, "user");
+        assert!(repair.content.contains("Missing required field: age"));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_repair_attempts() {
+        let provider =
+            ScriptedProvider::new(true, &["nope", "still nope", r#"{"name": 1, "age": 2}"#]);
+        let err = chat_structured(
+            &provider,
+            &[ChatMessage::user("who?")],
+            &person_format(),
+            "model",
+            0.0,
+        )
+        .await
+        .unwrap_err();
+
+        match err {
+            StructuredOutputError::Invalid {
+                attempts,
+                reason,
+                last_response,
+            } => {
+                assert_eq!(attempts, MAX_REPAIR_ATTEMPTS + 1);
+                assert!(reason.contains("wrong type"));
+                assert_eq!(last_response, r#"{"name": 1, "age": 2}"#);
+            }
+            other @ StructuredOutputError::Provider(_) => panic!("unexpected error: {other}"),
+        }
+    }
+}
@@ -55,7 +55,8 @@ pub struct TokenUsage {
     pub input_tokens: Option<u64>,
     pub output_tokens: Option<u64>,
     /// Tokens served from the provider's prompt cache (Anthropic `cache_read_input_tokens`,
-    /// OpenAI `prompt_tokens_details.cached_tokens`).
+    /// OpenAI `prompt_tokens_details.cached_tokens`, Bedrock `cacheReadInputTokens`,
+    /// Gemini `cachedContentTokenCount`). Always counted in `input_tokens` as well.
     pub cached_input_tokens: Option<u64>,
 }
 
@@ -87,11 +88,101 @@ impl ChatResponse {
     }
 }
 
+/// Requested shape of the final assistant message.
+///
+/// Providers with native support (OpenAI `response_format`, Anthropic forced
+/// tool, Gemini `responseJsonSchema`, Ollama `format`) constrain decoding;
+/// the rest receive prompt instructions. Use
+/// [`crate::providers::structured::chat_structured`] to also validate the
+/// result and retry with repair feedback.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResponseFormat {
+    /// Any syntactically valid JSON object.
+    JsonObject,
+    /// A JSON value conforming to `schema`.
+    JsonSchema {
+        /// Identifier sent to the provider (`[a-zA-Z0-9_-]`, max 64 chars).
+        name: String,
+        schema: serde_json::Value,
+        /// Ask the provider for strict schema adherence where supported.
+        strict: bool,
+    },
+}
+
+impl ResponseFormat {
+    /// Default name used for schema-less JSON requests.
+    pub const DEFAULT_NAME: &'static str = "json_response";
+
+    /// Build a schema-constrained format with strict adherence requested.
+    pub fn json_schema(name: impl Into<String>, schema: serde_json::Value) -> Self {
+        Self::JsonSchema {
+            name: name.into(),
+            schema,
+            strict: true,
+        }
+    }
+
+    /// Name of the format, used for forced-tool and `json_schema` payloads.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::JsonObject => Self::DEFAULT_NAME,
+            Self::JsonSchema { name, .. } => name,
+        }
+    }
+
+    /// The JSON schema the response must satisfy, if any.
+    pub fn schema(&self) -> Option<&serde_json::Value> {
+        match self {
+            Self::JsonObject => None,
+            Self::JsonSchema { schema, .. } => Some(schema),
+        }
+    }
+
+    /// OpenAI Chat Completions `response_format` payload.
+    pub fn to_openai_payload(&self) -> serde_json::Value {
+        match self {
+            Self::JsonObject => serde_json::json!({ "type": "json_object" }),
+            Self::JsonSchema {
+                name,
+                schema,
+                strict,
+            } => serde_json::json!({
+                "type": "json_schema",
+                "json_schema": {
+                    "name": name,
+                    "schema": schema,
+                    "strict": strict,
+                }
+            }),
+        }
+    }
+
+    /// System prompt instructions for providers without native support.
+    pub fn instructions(&self) -> String {
+        let mut instructions = String::from(
+            "## Response Format\n\nRespond with a single JSON value and nothing else: \
+             no prose, no markdown code fences.",
+        );
+        if let Some(schema) = self.schema() {
+            let schema = serde_json::to_string(schema).unwrap_or_else(|_| "{}".to_string());
+            let _ = write!(
+                instructions,
+                " The JSON must conform to this JSON Schema:\n\n{schema}"
+            );
+        } else {
+            instructions.push_str(" The top-level value must be a JSON object.");
+        }
+        instructions
+    }
+}
+
 /// Request payload for provider chat calls.
 #[derive(Debug, Clone, Copy)]
 pub struct ChatRequest<'a> {
     pub messages: &'a [ChatMessage],
     pub tools: Option<&'a [ToolSpec]>,
+    /// Constrain the final response to JSON (optionally schema-bound).
+    pub response_format: Option<&'a ResponseFormat>,
 }
 
 /// A tool result to feed back to the LLM.
@@ -270,6 +361,7 @@ pub struct ProviderCapabilityError {
 /// Describes what features a provider supports, enabling intelligent
 /// adaptation of tool calling modes and request formatting.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ProviderCapabilities {
     /// Whether the provider supports native tool calling via API primitives.
     ///
@@ -283,6 +375,9 @@ pub struct ProviderCapabilities {
     /// Whether the provider supports prompt caching (Anthropic cache_control,
     /// OpenAI automatic prompt caching).
     pub prompt_caching: bool,
+    /// Whether the provider can constrain responses to a JSON schema natively
+    /// (see [`ResponseFormat`]).
+    pub structured_output: bool,
 }
 
 /// Provider-specific tool payload formats.
@@ -378,6 +473,8 @@ pub trait Provider: Send + Sync {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
+        let mut modified_messages: Option<Vec<ChatMessage>> = None;
+
         // If tools are provided but provider doesn't support native tools,
         // inject tool instructions into system prompt as fallback.
         if let Some(tools) = request.tools {
@@ -390,35 +487,24 @@ pub trait Provider: Send + Sync {
                         )
                     }
                 };
-                let mut modified_messages = request.messages.to_vec();
-
-                // Inject tool instructions into an existing system message.
-                // If none exists, prepend one to the conversation.
-                if let Some(system_message) =
-                    modified_messages.iter_mut().find(|m| m.role == "system")
-                {
-                    if !system_message.content.is_empty() {
-                        system_message.content.push_str("\n\n");
-                    }
-                    system_message.content.push_str(&tool_instructions);
-                } else {
-                    modified_messages.insert(0, ChatMessage::system(tool_instructions));
-                }
-
-                let text = self
-                    .chat_with_history(&modified_messages, model, temperature)
-                    .await?;
-                return Ok(ChatResponse {
-                    text: Some(text),
-                    tool_calls: Vec::new(),
-                    usage: None,
-                    reasoning_content: None,
-                });
+                let messages = modified_messages.get_or_insert_with(|| request.messages.to_vec());
+                append_system_instructions(messages, &tool_instructions);
             }
         }
 
+        // The default implementation never constrains decoding, so a requested
+        // response format is always expressed as prompt instructions here.
+        if let Some(format) = request.response_format {
+            let messages = modified_messages.get_or_insert_with(|| request.messages.to_vec());
+            append_system_instructions(messages, &format.instructions());
+        }
+
         let text = self
-            .chat_with_history(request.messages, model, temperature)
+            .chat_with_history(
+                modified_messages.as_deref().unwrap_or(request.messages),
+                model,
+                temperature,
+            )
             .await?;
         Ok(ChatResponse {
             text: Some(text),
@@ -438,6 +524,11 @@ pub trait Provider: Send + Sync {
         self.capabilities().vision
     }
 
+    /// Whether provider honours `ChatRequest::response_format` natively.
+    fn supports_structured_output(&self) -> bool {
+        self.capabilities().structured_output
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -531,6 +622,19 @@ pub trait Provider: Send + Sync {
     }
 }
 
+/// Append `instructions` to the first system message, or prepend a new system
+/// message when the conversation has none.
+pub fn append_system_instructions(messages: &mut Vec<ChatMessage>, instructions: &str) {
+    if let Some(system_message) = messages.iter_mut().find(|m| m.role == "system") {
+        if !system_message.content.is_empty() {
+            system_message.content.push_str("\n\n");
+        }
+        system_message.content.push_str(instructions);
+    } else {
+        messages.insert(0, ChatMessage::system(instructions));
+    }
+}
+
 /// Build tool instructions text for prompt-guided tool calling.
 ///
 /// Generates a formatted text block describing available tools and how to
@@ -579,6 +683,7 @@ mod tests {
                 native_tool_calling: true,
                 vision: true,
                 prompt_caching: false,
+                structured_output: false,
             }
         }
 
@@ -696,16 +801,19 @@ mod tests {
             native_tool_calling: true,
             vision: false,
             prompt_caching: false,
+            structured_output: false,
         };
         let caps2 = ProviderCapabilities {
             native_tool_calling: true,
             vision: false,
             prompt_caching: false,
+            structured_output: false,
         };
         let caps3 = ProviderCapabilities {
             native_tool_calling: false,
             vision: false,
             prompt_caching: false,
+            structured_output: false,
         };
 
         assert_eq!(caps1, caps2);
@@ -865,6 +973,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -882,6 +991,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: None,
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -982,6 +1092,7 @@ mod tests {
                 ChatMessage::system("BASE_SYSTEM_PROMPT"),
             ],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -1004,6 +1115,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::system("BASE"), ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -1026,6 +1138,7 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            response_format: None,
         };
 
         let err = provider.chat(request, "model", 0.7).await.unwrap_err();
@@ -1112,6 +1225,7 @@ mod tests {
             ChatRequest {
                 messages: &[ChatMessage::user("hi")],
                 tools: None,
+                response_format: None,
             },
             "model",
             0.0,
@@ -0,0 +1,66 @@
+//! Process-wide event bus feeding the routines engine.
+//!
+//! Producers (channels, gateway webhooks, the cron scheduler, providers) call
+//! [`publish`] without knowing whether a routines worker is running; events
+//! published while nobody is subscribed are dropped.
+
+use std::sync::OnceLock;
+
+use tokio::sync::broadcast;
+
+use super::event_matcher::RoutineEvent;
+
+/// Event source for inbound channel messages (topic: channel name).
+pub const SOURCE_CHANNEL: &str = "channel";
+/// Event source for gateway webhook payloads (topic: request path).
+pub const SOURCE_WEBHOOK: &str = "webhook";
+/// Event source for finished cron jobs (topic: job name, or id when unnamed).
+pub const SOURCE_CRON: &str = "cron";
+/// Event source for runtime signals such as estop, provider fallback and
+/// component health changes.
+pub const SOURCE_SYSTEM: &str = "system";
+
+/// Events buffered per subscriber before the slowest one starts lagging.
+const BUS_CAPACITY: usize = 256;
+
+static BUS: OnceLock<broadcast::Sender<RoutineEvent>> = OnceLock::new();
+
+fn sender() -> &'static broadcast::Sender<RoutineEvent> {
+    BUS.get_or_init(|| broadcast::channel(BUS_CAPACITY).0)
+}
+
+/// Publish an event to every routines subscriber.
+pub fn publish(event: RoutineEvent) {
+    // A send error only means there is no subscriber right now.
+    let _ = sender().send(event);
+}
+
+/// Subscribe to events published after this call.
+pub fn subscribe() -> broadcast::Receiver<RoutineEvent> {
+    sender().subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn subscribers_receive_published_events() {
+        let mut rx = subscribe();
+        publish(RoutineEvent::new(
+            SOURCE_WEBHOOK,
+            "/bus-test",
+            Some("{}".into()),
+        ));
+
+        // Other tests may publish concurrently; skip unrelated events.
+        loop {
+            let event = rx.recv().await.unwrap();
+            if event.topic == "/bus-test" {
+                assert_eq!(event.source, SOURCE_WEBHOOK);
+                assert_eq!(event.payload.as_deref(), Some("{}"));
+                break;
+            }
+        }
+    }
+}
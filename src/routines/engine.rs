@@ -23,7 +23,16 @@ pub enum RoutineAction {
     /// Execute a shell command.
     Shell { command: String },
     /// Send a message to a channel.
-    Message { channel: String, text: String },
+    ///
+    /// `target` is the channel-specific recipient (chat id, channel id, room).
+    /// `text` may reference the triggering event as `{source}`, `{topic}` and
+    /// `{payload}`.
+    Message {
+        channel: String,
+        #[serde(default)]
+        target: Option<String>,
+        text: String,
+    },
     /// Run a cron job by name.
     CronJob { job_name: String },
 }
@@ -119,6 +128,15 @@ impl RoutinesEngine {
         self.routines.push(routine);
     }
 
+    /// Swap in a freshly loaded set of routines (hot reload).
+    ///
+    /// Cooldown state is kept for routines that survive the reload.
+    pub fn replace_routines(&mut self, routines: Vec<Routine>) {
+        self.cooldowns
+            .retain(|name, _| routines.iter().any(|r| &r.name == name));
+        self.routines = routines;
+    }
+
     /// Remove a routine by name. Returns `true` if removed.
     pub fn remove_routine(&mut self, name: &str) -> bool {
         let before = self.routines.len();
@@ -324,6 +342,28 @@ mod tests {
         assert!(matches!(results[0], RoutineDispatchResult::Fired { .. }));
     }
 
+    #[test]
+    fn replace_routines_keeps_cooldowns_of_surviving_routines() {
+        let mut kept = test_routine("kept", "webhook", "/deploy", MatchStrategy::Exact);
+        kept.cooldown_secs = 3600;
+        let mut dropped = test_routine("dropped", "webhook", "/deploy", MatchStrategy::Exact);
+        dropped.cooldown_secs = 3600;
+        let mut engine = RoutinesEngine::new(vec![kept.clone(), dropped.clone()]);
+        engine.dispatch(&test_event("webhook", "/deploy"));
+
+        engine.replace_routines(vec![kept]);
+        engine.add_routine(dropped);
+        let results = engine.dispatch(&test_event("webhook", "/deploy"));
+        assert!(matches!(
+            &results[0],
+            RoutineDispatchResult::Cooldown { routine_name, .. } if routine_name == "kept"
+        ));
+        assert!(matches!(
+            &results[1],
+            RoutineDispatchResult::Fired { routine_name, .. } if routine_name == "dropped"
+        ));
+    }
+
     #[test]
     fn add_and_remove_routine() {
         let mut engine = RoutinesEngine::empty();
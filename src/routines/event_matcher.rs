@@ -50,6 +50,18 @@ pub struct RoutineEvent {
     pub timestamp: String,
 }
 
+impl RoutineEvent {
+    /// Build an event stamped with the current time.
+    pub fn new(source: &str, topic: impl Into<String>, payload: Option<String>) -> Self {
+        Self {
+            source: source.to_string(),
+            topic: topic.into(),
+            payload,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
 /// Check whether an event matches a single pattern.
 pub fn matches(pattern: &EventPattern, event: &RoutineEvent) -> bool {
     if pattern.source != event.source {
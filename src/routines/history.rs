@@ -0,0 +1,168 @@
+//! Per-routine fire history, persisted in `<workspace>/routines/history.db`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+use super::engine::RoutineAction;
+use super::event_matcher::RoutineEvent;
+use crate::config::Config;
+
+/// Fires kept per routine; older entries are pruned on insert.
+const MAX_FIRES_PER_ROUTINE: i64 = 200;
+/// Upper bound on stored action output.
+const MAX_OUTPUT_BYTES: usize = 8 * 1024;
+
+/// A single recorded firing of a routine.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoutineFire {
+    pub id: i64,
+    pub routine_name: String,
+    pub fired_at: DateTime<Utc>,
+    pub source: String,
+    pub topic: String,
+    pub action: String,
+    pub success: bool,
+    pub output: String,
+}
+
+/// Record the outcome of a fired routine action.
+pub fn record_fire(
+    config: &Config,
+    routine_name: &str,
+    event: &RoutineEvent,
+    action: &RoutineAction,
+    success: bool,
+    output: &str,
+) -> Result<()> {
+    let action = serde_json::to_string(action)?;
+    let output = crate::util::truncate_with_ellipsis(output, MAX_OUTPUT_BYTES);
+    with_connection(config, |conn| {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO routine_fires (routine_name, fired_at, source, topic, action, success, output)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                routine_name,
+                Utc::now().to_rfc3339(),
+                event.source,
+                event.topic,
+                action,
+                success,
+                output,
+            ],
+        )
+        .context("Failed to insert routine fire")?;
+        tx.execute(
+            "DELETE FROM routine_fires
+             WHERE routine_name = ?1
+               AND id NOT IN (
+                 SELECT id FROM routine_fires
+                 WHERE routine_name = ?1
+                 ORDER BY id DESC
+                 LIMIT ?2
+               )",
+            params![routine_name, MAX_FIRES_PER_ROUTINE],
+        )
+        .context("Failed to prune routine fire history")?;
+        tx.commit().context("Failed to commit routine fire")?;
+        Ok(())
+    })
+}
+
+/// List the most recent fires of a routine, newest first.
+pub fn list_fires(config: &Config, routine_name: &str, limit: usize) -> Result<Vec<RoutineFire>> {
+    with_connection(config, |conn| {
+        let lim = i64::try_from(limit.max(1)).context("Fire history limit overflow")?;
+        let mut stmt = conn.prepare(
+            "SELECT id, routine_name, fired_at, source, topic, action, success, output
+             FROM routine_fires
+             WHERE routine_name = ?1
+             ORDER BY id DESC
+             LIMIT ?2",
+        )?;
+        let rows = stmt.query_map(params![routine_name, lim], |row| {
+            let fired_at: String = row.get(2)?;
+            Ok(RoutineFire {
+                id: row.get(0)?,
+                routine_name: row.get(1)?,
+                fired_at: DateTime::parse_from_rfc3339(&fired_at)
+                    .map(|t| t.with_timezone(&Utc))
+                    .map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))?,
+                source: row.get(3)?,
+                topic: row.get(4)?,
+                action: row.get(5)?,
+                success: row.get(6)?,
+                output: row.get(7)?,
+            })
+        })?;
+
+        let mut fires = Vec::new();
+        for row in rows {
+            fires.push(row?);
+        }
+        Ok(fires)
+    })
+}
+
+fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    let db_path = config.workspace_dir.join("routines").join("history.db");
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create routines directory: {}", parent.display())
+        })?;
+    }
+
+    let conn = Connection::open(&db_path)
+        .with_context(|| format!("Failed to open routines DB: {}", db_path.display()))?;
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS routine_fires (
+            id           INTEGER PRIMARY KEY AUTOINCREMENT,
+            routine_name TEXT NOT NULL,
+            fired_at     TEXT NOT NULL,
+            source       TEXT NOT NULL,
+            topic        TEXT NOT NULL,
+            action       TEXT NOT NULL,
+            success      INTEGER NOT NULL,
+            output       TEXT
+        );
+        CREATE INDEX IF NOT EXISTS idx_routine_fires_name ON routine_fires(routine_name, id);",
+    )
+    .context("Failed to initialize routines schema")?;
+
+    f(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn record_and_list_fires_newest_first() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let action = RoutineAction::Shell {
+            command: "echo hi".into(),
+        };
+
+        for i in 0..3 {
+            let event = RoutineEvent::new("webhook", format!("/hook/{i}"), None);
+            record_fire(&config, "notify", &event, &action, i != 1, "done").unwrap();
+        }
+        let other = RoutineEvent::new("cron", "nightly", None);
+        record_fire(&config, "other", &other, &action, true, "").unwrap();
+
+        let fires = list_fires(&config, "notify", 10).unwrap();
+        assert_eq!(fires.len(), 3);
+        assert_eq!(fires[0].topic, "/hook/2");
+        assert!(!fires[1].success);
+        assert!(fires[2].action.contains("\"type\":\"shell\""));
+
+        assert_eq!(list_fires(&config, "notify", 1).unwrap().len(), 1);
+    }
+}
@@ -23,15 +23,114 @@
 //!
 //! [routines.action]
 //! type = "message"
-//! channel = "slack-general"
-//! text = "Deploy triggered!"
+//! channel = "slack"
+//! target = "C0123456789"
+//! text = "Deploy triggered: {payload}"
 //! ```
+//!
+//! ## Runtime
+//!
+//! Inbound channel messages, `/webhook` payloads, finished cron jobs and
+//! system signals are published on the [`bus`]. The daemon's [`worker`]
+//! dispatches them through the engine, reloads `routines.toml` when it
+//! changes and records every firing in the [`history`] store.
 
+pub mod bus;
 pub mod engine;
 pub mod event_matcher;
+pub mod history;
+pub mod worker;
 
 pub use engine::{
     load_routines, load_routines_from_file, Routine, RoutineAction, RoutineDispatchResult,
     RoutinesEngine,
 };
 pub use event_matcher::{matches, matches_any, EventPattern, MatchStrategy, RoutineEvent};
+
+use crate::config::Config;
+use anyhow::Result;
+
+pub fn handle_command(command: crate::RoutineCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::RoutineCommands::List => {
+            let path = worker::routines_path(&config.workspace_dir);
+            let routines = load_routines_from_file(&path);
+            if routines.is_empty() {
+                println!("No routines defined in {}.", path.display());
+                return Ok(());
+            }
+
+            println!("⚡ Routines ({}):", routines.len());
+            for routine in routines {
+                println!(
+                    "- {}{} | cooldown={}s | action={}",
+                    routine.name,
+                    if routine.enabled { "" } else { " (disabled)" },
+                    routine.cooldown_secs,
+                    serde_json::to_string(&routine.action)?,
+                );
+                for pattern in &routine.patterns {
+                    println!(
+                        "    on {} {:?} '{}'",
+                        pattern.source, pattern.strategy, pattern.pattern
+                    );
+                }
+            }
+            Ok(())
+        }
+        crate::RoutineCommands::Test {
+            source,
+            topic,
+            payload,
+        } => {
+            let event = RoutineEvent::new(&source, topic, payload);
+            let mut engine = RoutinesEngine::new(load_routines(&config.workspace_dir));
+            println!(
+                "🧪 Dry run for {} event '{}' (no actions executed):",
+                event.source, event.topic
+            );
+            for result in engine.dispatch(&event) {
+                match result {
+                    RoutineDispatchResult::Fired {
+                        routine_name,
+                        action,
+                    } => println!(
+                        "- {routine_name}: would fire {}",
+                        serde_json::to_string(&action)?
+                    ),
+                    RoutineDispatchResult::Disabled { routine_name } => {
+                        println!("- {routine_name}: matches but is disabled");
+                    }
+                    RoutineDispatchResult::Cooldown {
+                        routine_name,
+                        remaining_secs,
+                    } => println!("- {routine_name}: in cooldown for {remaining_secs}s"),
+                    RoutineDispatchResult::NoMatch => println!("- no routine matches"),
+                }
+            }
+            Ok(())
+        }
+        crate::RoutineCommands::History { name, limit } => {
+            let fires = history::list_fires(config, &name, limit)?;
+            if fires.is_empty() {
+                println!("Routine {name} has not fired yet.");
+                return Ok(());
+            }
+
+            println!("🕒 Recent fires of {name} ({}):", fires.len());
+            for fire in fires {
+                println!(
+                    "- {} | {} '{}' | {}",
+                    fire.fired_at.to_rfc3339(),
+                    fire.source,
+                    fire.topic,
+                    if fire.success { "ok" } else { "error" },
+                );
+                if !fire.output.is_empty() {
+                    println!("    {}", fire.output);
+                }
+            }
+            Ok(())
+        }
+    }
+}
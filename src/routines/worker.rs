@@ -0,0 +1,410 @@
+//! Daemon worker: feeds bus events through the routines engine and executes
+//! the actions of fired routines.
+//!
+//! Besides bus events, the worker polls for system signals that originate
+//! outside this process or have no natural publish point (estop state changes
+//! made by `zeroclaw estop`, component health transitions) and hot-reloads
+//! `routines.toml` when it changes on disk.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use anyhow::Result;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{self, Duration};
+use tracing::{debug, info, warn};
+
+use super::bus::{self, SOURCE_SYSTEM};
+use super::engine::{
+    load_routines_from_file, RoutineAction, RoutineDispatchResult, RoutinesEngine,
+};
+use super::event_matcher::RoutineEvent;
+use super::history;
+use crate::config::Config;
+use crate::security::SecurityPolicy;
+use crate::sop::dispatch::{process_headless_results, start_sop_by_name, DispatchResult};
+use crate::sop::{SopAuditLogger, SopEngine, SopEvent, SopTriggerSource};
+
+const ROUTINES_COMPONENT: &str = "routines";
+/// How often `routines.toml` and system signals are polled.
+const POLL_SECS: u64 = 5;
+
+/// Path of the routines manifest inside the workspace.
+pub fn routines_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("routines.toml")
+}
+
+pub async fn run(config: Config) -> Result<()> {
+    let mut events = bus::subscribe();
+    let path = routines_path(&config.workspace_dir);
+    let mut engine = RoutinesEngine::new(load_routines_from_file(&path));
+    let mut loaded_stamp = file_stamp(&path);
+    let runner = Arc::new(ActionRunner::new(&config));
+    let mut signals = SystemSignals::new(&config);
+
+    let mut interval = time::interval(Duration::from_secs(POLL_SECS));
+    interval.set_missed_tick_behavior(time::MissedTickBehavior::Skip);
+
+    crate::health::mark_component_ok(ROUTINES_COMPONENT);
+    info!(
+        count = engine.len(),
+        path = %path.display(),
+        "Routines worker started"
+    );
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                crate::health::mark_component_ok(ROUTINES_COMPONENT);
+
+                let stamp = file_stamp(&path);
+                if stamp != loaded_stamp {
+                    loaded_stamp = stamp;
+                    engine.replace_routines(load_routines_from_file(&path));
+                    info!(count = engine.len(), "Reloaded routines from {}", path.display());
+                }
+
+                for event in signals.poll() {
+                    dispatch(&mut engine, &runner, event);
+                }
+            }
+            received = events.recv() => match received {
+                Ok(event) => dispatch(&mut engine, &runner, event),
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Routines worker fell behind; {skipped} events dropped");
+                }
+                Err(RecvError::Closed) => anyhow::bail!("routines event bus closed"),
+            },
+        }
+    }
+}
+
+/// Modification marker used to detect edits to `routines.toml`.
+fn file_stamp(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn dispatch(engine: &mut RoutinesEngine, runner: &Arc<ActionRunner>, event: RoutineEvent) {
+    for result in engine.dispatch(&event) {
+        let RoutineDispatchResult::Fired {
+            routine_name,
+            action,
+        } = result
+        else {
+            continue;
+        };
+
+        let runner = Arc::clone(runner);
+        let event = event.clone();
+        tokio::spawn(async move {
+            let (success, output) = Box::pin(runner.execute(&action, &event)).await;
+            if !success {
+                warn!(routine = %routine_name, "Routine action failed: {output}");
+            }
+            if let Err(e) = history::record_fire(
+                &runner.config,
+                &routine_name,
+                &event,
+                &action,
+                success,
+                &output,
+            ) {
+                warn!(routine = %routine_name, "Failed to record routine fire: {e}");
+            }
+        });
+    }
+}
+
+// ── Action execution ────────────────────────────────────────────
+
+struct ActionRunner {
+    config: Config,
+    security: SecurityPolicy,
+    sop: Option<(Arc<Mutex<SopEngine>>, SopAuditLogger)>,
+}
+
+impl ActionRunner {
+    fn new(config: &Config) -> Self {
+        let sop =
+            crate::sop::shared_sop_engine(&config.sop, &config.workspace_dir).and_then(|engine| {
+                match crate::memory::create_memory(
+                    &config.memory,
+                    &config.workspace_dir,
+                    config.api_key.as_deref(),
+                ) {
+                    Ok(memory) => Some((engine, SopAuditLogger::new(Arc::from(memory)))),
+                    Err(e) => {
+                        warn!("Routines: SOP actions unavailable, memory init failed: {e}");
+                        None
+                    }
+                }
+            });
+
+        Self {
+            config: config.clone(),
+            security: SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+                .with_trust(crate::trust::runtime::init(config)),
+            sop,
+        }
+    }
+
+    async fn execute(&self, action: &RoutineAction, event: &RoutineEvent) -> (bool, String) {
+        match action {
+            RoutineAction::Sop { name } => {
+                let Some((engine, audit)) = &self.sop else {
+                    return (
+                        false,
+                        "SOP engine is disabled ([sop] enabled = false)".into(),
+                    );
+                };
+                let sop_event = SopEvent {
+                    source: SopTriggerSource::Manual,
+                    topic: Some(format!("{}:{}", event.source, event.topic)),
+                    payload: event.payload.clone(),
+                    timestamp: event.timestamp.clone(),
+                    idempotency_key: None,
+                };
+                let result = start_sop_by_name(engine, audit, name, sop_event).await;
+                process_headless_results(engine, std::slice::from_ref(&result));
+                match result {
+                    DispatchResult::Started { run_id, .. } => {
+                        (true, format!("started SOP '{name}' run {run_id}"))
+                    }
+                    DispatchResult::Skipped { reason, .. } => (false, reason),
+                    DispatchResult::NoMatch => (false, format!("SOP '{name}' not found")),
+                }
+            }
+            RoutineAction::Shell { command } => {
+                crate::cron::scheduler::run_shell_command(
+                    &self.config,
+                    &self.security,
+                    command,
+                    Duration::from_secs(crate::cron::scheduler::SHELL_JOB_TIMEOUT_SECS),
+                )
+                .await
+            }
+            RoutineAction::Message {
+                channel,
+                target,
+                text,
+            } => {
+                let Some(target) = target.as_deref() else {
+                    return (
+                        false,
+                        format!("message action for channel '{channel}' has no target"),
+                    );
+                };
+                let text = render_text(text, event);
+                match crate::cron::scheduler::deliver_announcement(
+                    &self.config,
+                    channel,
+                    target,
+                    &text,
+                )
+                .await
+                {
+                    Ok(()) => (true, format!("delivered to {channel}:{target}")),
+                    Err(e) => (false, format!("delivery to {channel}:{target} failed: {e}")),
+                }
+            }
+            RoutineAction::CronJob { job_name } => Box::pin(self.run_cron_job(job_name)).await,
+        }
+    }
+
+    async fn run_cron_job(&self, job_name: &str) -> (bool, String) {
+        let job = match crate::cron::list_jobs(&self.config) {
+            Ok(jobs) => jobs
+                .into_iter()
+                .find(|job| job.name.as_deref() == Some(job_name) || job.id == job_name),
+            Err(e) => return (false, format!("failed to list cron jobs: {e}")),
+        };
+        let Some(job) = job else {
+            return (false, format!("cron job '{job_name}' not found"));
+        };
+
+        let started_at = chrono::Utc::now();
+        let (status, output) =
+            Box::pin(crate::cron::scheduler::execute_job_now(&self.config, &job)).await;
+        let finished_at = chrono::Utc::now();
+        let success = status.is_success();
+        let _ = crate::cron::record_run(
+            &self.config,
+            &job.id,
+            started_at,
+            finished_at,
+            status.as_str(),
+            "routine",
+            Some(&output),
+            (finished_at - started_at).num_milliseconds(),
+        );
+        if !matches!(
+            status,
+            crate::cron::RunStatus::Skipped | crate::cron::RunStatus::Replaced
+        ) {
+            let _ =
+                crate::cron::record_last_run(&self.config, &job.id, finished_at, success, &output);
+        }
+        (success, output)
+    }
+}
+
+/// Substitute `{source}`, `{topic}` and `{payload}` in a message template.
+fn render_text(template: &str, event: &RoutineEvent) -> String {
+    template
+        .replace("{source}", &event.source)
+        .replace("{topic}", &event.topic)
+        .replace("{payload}", event.payload.as_deref().unwrap_or_default())
+}
+
+// ── System signals ──────────────────────────────────────────────
+
+/// Turns polled runtime state into `system` events on transitions.
+///
+/// The first poll only records a baseline so a daemon restart does not
+/// replay the current state as fresh events.
+struct SystemSignals {
+    estop: Option<(crate::config::EstopConfig, PathBuf)>,
+    estop_engaged: Option<bool>,
+    health: Option<BTreeMap<String, String>>,
+}
+
+impl SystemSignals {
+    fn new(config: &Config) -> Self {
+        let estop = config
+            .security
+            .estop
+            .enabled
+            .then(|| {
+                config
+                    .config_path
+                    .parent()
+                    .map(|dir| (config.security.estop.clone(), dir.to_path_buf()))
+            })
+            .flatten();
+        Self {
+            estop,
+            estop_engaged: None,
+            health: None,
+        }
+    }
+
+    fn poll(&mut self) -> Vec<RoutineEvent> {
+        let mut events = Vec::new();
+
+        if let Some((estop_config, config_dir)) = &self.estop {
+            match crate::security::EstopManager::load(estop_config, config_dir) {
+                Ok(manager) => {
+                    let state = manager.status();
+                    let engaged = state.is_engaged();
+                    if self
+                        .estop_engaged
+                        .is_some_and(|previous| previous != engaged)
+                    {
+                        let topic = if engaged {
+                            "estop.engaged"
+                        } else {
+                            "estop.resumed"
+                        };
+                        events.push(RoutineEvent::new(
+                            SOURCE_SYSTEM,
+                            topic,
+                            serde_json::to_string(&state).ok(),
+                        ));
+                    }
+                    self.estop_engaged = Some(engaged);
+                }
+                Err(e) => debug!("Routines: failed to read estop state: {e}"),
+            }
+        }
+
+        let current: BTreeMap<String, String> = crate::health::snapshot()
+            .components
+            .into_iter()
+            .map(|(name, component)| (name, component.status))
+            .collect();
+        if let Some(previous) = &self.health {
+            events.extend(health_transitions(previous, &current));
+        }
+        self.health = Some(current);
+
+        events
+    }
+}
+
+/// `system` events for components whose health status changed.
+fn health_transitions(
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Vec<RoutineEvent> {
+    current
+        .iter()
+        .filter(|(name, status)| {
+            previous
+                .get(*name)
+                .is_some_and(|previous| previous != *status)
+        })
+        .map(|(name, status)| {
+            let last_error = crate::health::snapshot()
+                .components
+                .get(name)
+                .and_then(|component| component.last_error.clone());
+            RoutineEvent::new(SOURCE_SYSTEM, format!("health.{name}.{status}"), last_error)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_text_substitutes_event_fields() {
+        let event = RoutineEvent::new("webhook", "/webhook", Some("build 42 failed".into()));
+        assert_eq!(
+            render_text("[{source}] {topic}: {payload}", &event),
+            "[webhook] /webhook: build 42 failed"
+        );
+    }
+
+    #[test]
+    fn health_transitions_only_reports_changed_components() {
+        let previous = BTreeMap::from([
+            ("gateway".to_string(), "ok".to_string()),
+            ("channels".to_string(), "ok".to_string()),
+        ]);
+        let current = BTreeMap::from([
+            ("gateway".to_string(), "ok".to_string()),
+            ("channels".to_string(), "error".to_string()),
+            ("scheduler".to_string(), "ok".to_string()),
+        ]);
+
+        let topics: Vec<String> = health_transitions(&previous, &current)
+            .into_iter()
+            .map(|event| event.topic)
+            .collect();
+        assert_eq!(topics, vec!["health.channels.error"]);
+    }
+
+    #[tokio::test]
+    async fn message_action_without_target_fails_cleanly() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        let runner = ActionRunner::new(&config);
+        let action = RoutineAction::Message {
+            channel: "telegram".into(),
+            target: None,
+            text: "hi".into(),
+        };
+
+        let (success, output) = runner
+            .execute(&action, &RoutineEvent::new("system", "estop.engaged", None))
+            .await;
+        assert!(!success);
+        assert!(output.contains("no target"));
+    }
+}
@@ -0,0 +1,518 @@
+//! cgroup v2 resource limits for native runtime commands.
+//!
+//! Each limited command runs in its own transient cgroup created under a
+//! delegated parent (the daemon's own cgroup unless `[runtime.cgroup] parent`
+//! is set). No systemd is involved: the parent only has to be writable by the
+//! daemon user. cgroup v2 forbids processes in a cgroup that distributes
+//! controllers to children, so when the daemon lives in the parent it first
+//! moves itself into a `zeroclaw-daemon` leaf.
+
+use super::traits::RuntimeAdapter;
+use crate::config::{CgroupConfig, CgroupLimitsConfig};
+use crate::observability::{Observer, ObserverEvent};
+use anyhow::{Context, Result};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Mount point of the unified cgroup v2 hierarchy.
+const CGROUP_MOUNT: &str = "/sys/fs/cgroup";
+/// Leaf the daemon moves itself into when it lives in the parent cgroup.
+const DAEMON_LEAF: &str = "zeroclaw-daemon";
+/// `cpu.max` period in microseconds.
+const CPU_PERIOD_US: u64 = 100_000;
+/// Smallest `cpu.max` quota the kernel accepts.
+const CPU_MIN_QUOTA_US: u64 = 1_000;
+
+/// Creates transient cgroups for tool subprocesses under a delegated parent.
+pub struct CgroupManager {
+    parent: PathBuf,
+    config: CgroupConfig,
+    observer: Option<Arc<dyn Observer>>,
+    next_id: AtomicU64,
+}
+
+impl CgroupManager {
+    /// Prepare the delegated parent: enable the controllers the configured
+    /// limits need. Fails when cgroup v2 is not mounted or the parent is not
+    /// delegated to this user, so callers can fall back to running unlimited.
+    pub fn new(config: &CgroupConfig, observer: Option<Arc<dyn Observer>>) -> Result<Self> {
+        let mount = Path::new(CGROUP_MOUNT);
+        if !mount.join("cgroup.controllers").is_file() {
+            anyhow::bail!("cgroup v2 is not mounted at {CGROUP_MOUNT}");
+        }
+        let mut own = mount.join(own_cgroup_path()?.trim_start_matches('/'));
+        // An earlier manager in this process already moved the daemon into
+        // its leaf; the delegated parent is the level above.
+        let in_leaf = own.ends_with(DAEMON_LEAF);
+        if in_leaf {
+            own.pop();
+        }
+        let parent = match config.parent.as_deref().map(str::trim) {
+            Some(parent) if !parent.is_empty() => mount.join(parent.trim_start_matches('/')),
+            _ => own.clone(),
+        };
+        if parent == own && !in_leaf {
+            let leaf = parent.join(DAEMON_LEAF);
+            fs::create_dir_all(&leaf)
+                .with_context(|| format!("failed to create {}", leaf.display()))?;
+            write_interface(&leaf, "cgroup.procs", &std::process::id().to_string())?;
+        }
+        Self::with_parent(parent, config, observer)
+    }
+
+    fn with_parent(
+        parent: PathBuf,
+        config: &CgroupConfig,
+        observer: Option<Arc<dyn Observer>>,
+    ) -> Result<Self> {
+        enable_controllers(&parent, &required_controllers(config))?;
+        Ok(Self {
+            parent,
+            config: config.clone(),
+            observer,
+            next_id: AtomicU64::new(0),
+        })
+    }
+
+    /// Delegated parent the transient cgroups are created under.
+    pub fn parent(&self) -> &Path {
+        &self.parent
+    }
+
+    /// Effective limits for `tool` (defaults merged with per-tool overrides).
+    pub fn limits_for(&self, tool: &str) -> CgroupLimitsConfig {
+        self.config.limits_for(tool)
+    }
+
+    /// Create a transient cgroup for `tool` and arrange for `cmd` to join it
+    /// between fork and exec, so the limits cover the whole process tree.
+    pub fn attach(&self, tool: &str, cmd: &mut std::process::Command) -> Result<CgroupLease> {
+        let lease = self.create(tool)?;
+        lease.place(cmd)?;
+        Ok(lease)
+    }
+
+    fn create(&self, tool: &str) -> Result<CgroupLease> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let name: String = tool
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        let path = self
+            .parent
+            .join(format!("zeroclaw-{name}-{}-{id}", std::process::id()));
+        fs::create_dir(&path)
+            .with_context(|| format!("failed to create cgroup {}", path.display()))?;
+
+        // Constructed before the limits are written so Drop removes the
+        // cgroup if any write fails.
+        let lease = CgroupLease {
+            path,
+            tool: tool.to_string(),
+            limits: self.limits_for(tool),
+            observer: self.observer.clone(),
+        };
+        lease.write_limits()?;
+        Ok(lease)
+    }
+}
+
+/// A transient cgroup holding one tool subprocess tree.
+///
+/// Dropping the lease kills anything still running in the cgroup and
+/// removes it.
+pub struct CgroupLease {
+    path: PathBuf,
+    tool: String,
+    limits: CgroupLimitsConfig,
+    observer: Option<Arc<dyn Observer>>,
+}
+
+impl CgroupLease {
+    fn write_limits(&self) -> Result<()> {
+        let limits = &self.limits;
+        if let Some(mb) = limits.memory_max_mb {
+            let bytes = mb.saturating_mul(1024 * 1024);
+            write_interface(&self.path, "memory.max", &bytes.to_string())?;
+        }
+        if let Some(cpus) = limits.cpu_limit {
+            write_interface(&self.path, "cpu.max", &cpu_max_value(cpus))?;
+        }
+        if let Some(pids) = limits.pids_max {
+            write_interface(&self.path, "pids.max", &pids.to_string())?;
+        }
+        if let Some(weight) = limits.io_weight {
+            write_interface(&self.path, "io.weight", &format!("default {weight}"))?;
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    fn place(&self, cmd: &mut std::process::Command) -> Result<()> {
+        use std::os::unix::io::AsRawFd;
+        use std::os::unix::process::CommandExt;
+
+        let procs_path = self.path.join("cgroup.procs");
+        let procs = fs::OpenOptions::new()
+            .write(true)
+            .open(&procs_path)
+            .with_context(|| format!("failed to open {}", procs_path.display()))?;
+        // SAFETY: the hook only calls write(2) on a descriptor opened before
+        // fork, which is async-signal-safe.
+        unsafe {
+            cmd.pre_exec(move || {
+                // Writing "0" moves the writing process: the forked child.
+                if libc::write(procs.as_raw_fd(), b"0".as_ptr().cast(), 1) < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    fn place(&self, _cmd: &mut std::process::Command) -> Result<()> {
+        anyhow::bail!("cgroup limits are only supported on Linux")
+    }
+
+    /// Path of the transient cgroup.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Limit events recorded by the kernel so far.
+    pub fn events(&self) -> LimitEvents {
+        let memory = read_keyed(&self.path.join("memory.events"));
+        let pids = read_keyed(&self.path.join("pids.events"));
+        LimitEvents {
+            oom_kills: keyed_value(&memory, "oom_kill"),
+            memory_max: keyed_value(&memory, "max"),
+            pids_max: keyed_value(&pids, "max"),
+        }
+    }
+
+    /// Check for limit violations once the process has exited, reporting
+    /// them to the observer. Releases the cgroup.
+    pub fn finish(self) -> Option<ResourceLimitViolation> {
+        let violation = self.events().violation(&self.tool, &self.limits)?;
+        tracing::warn!("{violation}");
+        if let Some(observer) = &self.observer {
+            observer.record_event(&ObserverEvent::ResourceLimitExceeded {
+                tool: violation.tool.clone(),
+                resource: violation.resource.to_string(),
+                detail: violation.detail.clone(),
+            });
+        }
+        Some(violation)
+    }
+}
+
+impl Drop for CgroupLease {
+    fn drop(&mut self) {
+        let path = std::mem::take(&mut self.path);
+        // Removal may wait for killed processes to exit.
+        crate::util::run_blocking(move || remove_cgroup(&path));
+    }
+}
+
+/// Kill anything left in the cgroup at `path` and remove it.
+fn remove_cgroup(path: &Path) {
+    // cgroup.kill (5.14+) takes out stragglers such as backgrounded
+    // children so the cgroup can be removed.
+    let _ = fs::write(path.join("cgroup.kill"), "1");
+    for _ in 0..20 {
+        match fs::remove_dir(path) {
+            Ok(()) => return,
+            Err(e) if e.kind() == std::io::ErrorKind::ResourceBusy => {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            Err(e) => {
+                tracing::debug!("failed to remove cgroup {}: {e}", path.display());
+                return;
+            }
+        }
+    }
+    tracing::debug!("cgroup {} still busy; leaving it", path.display());
+}
+
+/// Output of a command run by [`output_with_limits`], or the elapsed timeout.
+pub type TimedOutput = Result<std::io::Result<std::process::Output>, tokio::time::error::Elapsed>;
+
+/// Run `cmd` to completion within `timeout` under `runtime`'s resource
+/// limits for `tool`.
+///
+/// # Errors
+///
+/// Returns a message for the tool result when the limits cannot be applied
+/// or the process exceeded them.
+pub async fn output_with_limits(
+    runtime: Option<&dyn RuntimeAdapter>,
+    tool: &str,
+    cmd: &mut tokio::process::Command,
+    timeout: Duration,
+) -> std::result::Result<TimedOutput, String> {
+    let lease = match runtime {
+        Some(runtime) => runtime
+            .apply_resource_limits(tool, cmd.as_std_mut())
+            .map_err(|e| format!("Failed to apply resource limits: {e:#}"))?,
+        None => None,
+    };
+    let result = tokio::time::timeout(timeout, cmd.output()).await;
+    if let Some(violation) = lease.and_then(CgroupLease::finish) {
+        return Err(violation.to_string());
+    }
+    Ok(result)
+}
+
+/// Counters from `memory.events` and `pids.events`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LimitEvents {
+    /// Processes killed by the OOM killer inside the cgroup.
+    pub oom_kills: u64,
+    /// Times memory usage hit `memory.max` (reclaim, not necessarily fatal).
+    pub memory_max: u64,
+    /// Times fork/clone failed because of `pids.max`.
+    pub pids_max: u64,
+}
+
+impl LimitEvents {
+    fn violation(&self, tool: &str, limits: &CgroupLimitsConfig) -> Option<ResourceLimitViolation> {
+        let (resource, detail) = if self.oom_kills > 0 {
+            let limit = limits
+                .memory_max_mb
+                .map_or_else(String::new, |mb| format!(" at the {mb} MiB limit"));
+            (
+                "memory",
+                format!(
+                    "{} process(es) OOM-killed{limit} ({} time(s) at memory.max)",
+                    self.oom_kills, self.memory_max
+                ),
+            )
+        } else if self.pids_max > 0 {
+            let limit = limits
+                .pids_max
+                .map_or_else(String::new, |pids| format!(" at the {pids} process limit"));
+            ("pids", format!("{} fork(s) refused{limit}", self.pids_max))
+        } else {
+            return None;
+        };
+        Some(ResourceLimitViolation {
+            tool: tool.to_string(),
+            resource,
+            detail,
+        })
+    }
+}
+
+/// A command was stopped or hampered by a cgroup limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceLimitViolation {
+    pub tool: String,
+    /// `"memory"` or `"pids"`.
+    pub resource: &'static str,
+    pub detail: String,
+}
+
+impl fmt::Display for ResourceLimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Resource limit exceeded ({}) in `{}`: {}",
+            self.resource, self.tool, self.detail
+        )
+    }
+}
+
+/// `cpu.max` value for a limit expressed in CPUs (e.g. `0.5` = half a core).
+fn cpu_max_value(cpus: f64) -> String {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let quota = (cpus * CPU_PERIOD_US as f64).round().max(0.0) as u64;
+    format!("{} {CPU_PERIOD_US}", quota.max(CPU_MIN_QUOTA_US))
+}
+
+/// Controllers the configured limits depend on.
+fn required_controllers(config: &CgroupConfig) -> Vec<&'static str> {
+    let all: Vec<CgroupLimitsConfig> = std::iter::once(config.limits_for(""))
+        .chain(config.tools.keys().map(|tool| config.limits_for(tool)))
+        .collect();
+    let mut controllers = Vec::new();
+    if all.iter().any(|l| l.memory_max_mb.is_some()) {
+        controllers.push("memory");
+    }
+    if all.iter().any(|l| l.cpu_limit.is_some()) {
+        controllers.push("cpu");
+    }
+    if all.iter().any(|l| l.pids_max.is_some()) {
+        controllers.push("pids");
+    }
+    if all.iter().any(|l| l.io_weight.is_some()) {
+        controllers.push("io");
+    }
+    controllers
+}
+
+/// Enable `controllers` for the children of `parent`.
+fn enable_controllers(parent: &Path, controllers: &[&str]) -> Result<()> {
+    let available = fs::read_to_string(parent.join("cgroup.controllers"))
+        .with_context(|| format!("{} is not a cgroup v2 directory", parent.display()))?;
+    let enabled = fs::read_to_string(parent.join("cgroup.subtree_control")).unwrap_or_default();
+    for controller in controllers {
+        if enabled.split_whitespace().any(|c| c == *controller) {
+            continue;
+        }
+        if !available.split_whitespace().any(|c| c == *controller) {
+            anyhow::bail!(
+                "cgroup controller `{controller}` is not delegated to {}",
+                parent.display()
+            );
+        }
+        write_interface(parent, "cgroup.subtree_control", &format!("+{controller}"))?;
+    }
+    Ok(())
+}
+
+fn write_interface(dir: &Path, file: &str, value: &str) -> Result<()> {
+    let path = dir.join(file);
+    fs::write(&path, value)
+        .with_context(|| format!("failed to write {value:?} to {}", path.display()))
+}
+
+/// Parse a flat-keyed cgroup file (`key value` per line).
+fn read_keyed(path: &Path) -> Vec<(String, u64)> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn keyed_value(entries: &[(String, u64)], key: &str) -> u64 {
+    entries
+        .iter()
+        .find(|(k, _)| k == key)
+        .map_or(0, |(_, v)| *v)
+}
+
+/// The daemon's cgroup v2 path from `/proc/self/cgroup` (`0::/path`).
+fn own_cgroup_path() -> Result<String> {
+    let content =
+        fs::read_to_string("/proc/self/cgroup").context("failed to read /proc/self/cgroup")?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("0::"))
+        .map(str::to_string)
+        .context("no cgroup v2 entry in /proc/self/cgroup")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fake delegated parent: a plain directory with the interface files
+    /// the manager reads.
+    fn fake_parent(controllers: &str) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("cgroup.controllers"), controllers).unwrap();
+        fs::write(dir.path().join("cgroup.subtree_control"), "").unwrap();
+        dir
+    }
+
+    fn config() -> CgroupConfig {
+        let mut config = CgroupConfig {
+            enabled: true,
+            memory_max_mb: Some(64),
+            pids_max: Some(32),
+            ..CgroupConfig::default()
+        };
+        config.tools.insert(
+            "shell".into(),
+            CgroupLimitsConfig {
+                cpu_limit: Some(0.5),
+                ..CgroupLimitsConfig::default()
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn cpu_limit_maps_to_quota_and_period() {
+        assert_eq!(cpu_max_value(0.5), "50000 100000");
+        assert_eq!(cpu_max_value(2.0), "200000 100000");
+        assert_eq!(cpu_max_value(0.0001), "1000 100000");
+    }
+
+    #[test]
+    fn required_controllers_follow_configured_limits() {
+        assert_eq!(
+            required_controllers(&config()),
+            vec!["memory", "cpu", "pids"]
+        );
+    }
+
+    #[test]
+    fn missing_controller_is_rejected() {
+        let parent = fake_parent("memory pids");
+        let err = CgroupManager::with_parent(parent.path().to_path_buf(), &config(), None)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("`cpu` is not delegated"));
+    }
+
+    #[test]
+    fn transient_cgroup_gets_tool_limits() {
+        let parent = fake_parent("cpu io memory pids");
+        let manager =
+            CgroupManager::with_parent(parent.path().to_path_buf(), &config(), None).unwrap();
+        let lease = manager.create("shell").unwrap();
+        assert!(lease.path().starts_with(parent.path()));
+
+        let read = |file: &str| fs::read_to_string(lease.path().join(file)).unwrap();
+        assert_eq!(read("memory.max"), (64 * 1024 * 1024).to_string());
+        assert_eq!(read("cpu.max"), "50000 100000");
+        assert_eq!(read("pids.max"), "32");
+        assert!(!lease.path().join("io.weight").exists());
+    }
+
+    #[test]
+    fn oom_kill_is_reported_as_memory_violation() {
+        let parent = fake_parent("cpu io memory pids");
+        let manager =
+            CgroupManager::with_parent(parent.path().to_path_buf(), &config(), None).unwrap();
+        let lease = manager.create("shell").unwrap();
+        fs::write(
+            lease.path().join("memory.events"),
+            "low 0\nhigh 0\nmax 7\noom 1\noom_kill 1\n",
+        )
+        .unwrap();
+
+        let violation = lease.finish().unwrap();
+        assert_eq!(violation.resource, "memory");
+        assert!(violation.detail.contains("64 MiB"));
+        assert!(violation.to_string().contains("`shell`"));
+    }
+
+    #[test]
+    fn pids_limit_and_clean_exit_are_distinguished() {
+        let parent = fake_parent("cpu io memory pids");
+        let manager =
+            CgroupManager::with_parent(parent.path().to_path_buf(), &config(), None).unwrap();
+
+        let clean = manager.create("shell").unwrap();
+        fs::write(clean.path().join("memory.events"), "max 3\noom_kill 0\n").unwrap();
+        assert!(clean.finish().is_none());
+
+        let forked = manager.create("claude_code").unwrap();
+        fs::write(forked.path().join("pids.events"), "max 4\n").unwrap();
+        let violation = forked.finish().unwrap();
+        assert_eq!(violation.resource, "pids");
+        assert!(violation.detail.contains("32 process limit"));
+    }
+}
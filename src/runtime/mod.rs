@@ -1,16 +1,33 @@
+pub mod cgroup;
 pub mod docker;
 pub mod native;
 pub mod traits;
 
+pub use cgroup::{output_with_limits, CgroupLease};
 pub use docker::DockerRuntime;
 pub use native::NativeRuntime;
 pub use traits::RuntimeAdapter;
 
 use crate::config::RuntimeConfig;
+use crate::observability::Observer;
+use std::sync::Arc;
 
 /// Factory: create the right runtime from config
 pub fn create_runtime(config: &RuntimeConfig) -> anyhow::Result<Box<dyn RuntimeAdapter>> {
+    create_runtime_with_observer(config, None)
+}
+
+/// Factory: create the right runtime from config, reporting resource limit
+/// events to `observer`
+pub fn create_runtime_with_observer(
+    config: &RuntimeConfig,
+    observer: Option<Arc<dyn Observer>>,
+) -> anyhow::Result<Box<dyn RuntimeAdapter>> {
     match config.kind.as_str() {
+        "native" if config.cgroup.enabled => Ok(Box::new(NativeRuntime::with_cgroups(
+            &config.cgroup,
+            observer,
+        ))),
         "native" => Ok(Box::new(NativeRuntime::new())),
         "docker" => Ok(Box::new(DockerRuntime::new(config.docker.clone()))),
         "cloudflare" => anyhow::bail!(
@@ -1,12 +1,35 @@
+use super::cgroup::{CgroupLease, CgroupManager};
 use super::traits::RuntimeAdapter;
+use crate::config::CgroupConfig;
+use crate::observability::Observer;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Native runtime — full access, runs on Mac/Linux/Windows/Docker/Raspberry Pi
-pub struct NativeRuntime;
+pub struct NativeRuntime {
+    cgroups: Option<CgroupManager>,
+}
 
 impl NativeRuntime {
     pub fn new() -> Self {
-        Self
+        Self { cgroups: None }
+    }
+
+    /// Native runtime that runs tool subprocesses in transient cgroup v2
+    /// groups. Falls back to no limits (with a warning) when the host has no
+    /// delegated cgroup v2 hierarchy.
+    pub fn with_cgroups(config: &CgroupConfig, observer: Option<Arc<dyn Observer>>) -> Self {
+        let cgroups = match CgroupManager::new(config, observer) {
+            Ok(manager) => {
+                tracing::info!("cgroup limits enabled under {}", manager.parent().display());
+                Some(manager)
+            }
+            Err(e) => {
+                tracing::warn!("cgroup limits unavailable, running tools unlimited: {e:#}");
+                None
+            }
+        };
+        Self { cgroups }
     }
 }
 
@@ -34,6 +57,13 @@ impl RuntimeAdapter for NativeRuntime {
         true
     }
 
+    fn memory_budget(&self) -> u64 {
+        self.cgroups
+            .as_ref()
+            .and_then(|cgroups| cgroups.limits_for("shell").memory_max_mb)
+            .map_or(0, |mb| mb.saturating_mul(1024 * 1024))
+    }
+
     fn build_shell_command(
         &self,
         command: &str,
@@ -53,6 +83,17 @@ impl RuntimeAdapter for NativeRuntime {
             Ok(process)
         }
     }
+
+    fn apply_resource_limits(
+        &self,
+        tool: &str,
+        cmd: &mut std::process::Command,
+    ) -> anyhow::Result<Option<CgroupLease>> {
+        self.cgroups
+            .as_ref()
+            .map(|cgroups| cgroups.attach(tool, cmd))
+            .transpose()
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use super::cgroup::CgroupLease;
 use std::path::{Path, PathBuf};
 
 /// Runtime adapter that abstracts platform differences for the agent.
@@ -68,6 +69,24 @@ pub trait RuntimeAdapter: Send + Sync {
         command: &str,
         workspace_dir: &Path,
     ) -> anyhow::Result<tokio::process::Command>;
+
+    /// Place a host subprocess spawned by `tool` under this runtime's
+    /// resource limits.
+    ///
+    /// Call after `cmd` is fully configured and before it is spawned. The
+    /// returned lease reports limit violations once the process has exited
+    /// and releases the limits when dropped. The default enforces no limits.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if limits are configured but cannot be applied.
+    fn apply_resource_limits(
+        &self,
+        _tool: &str,
+        _cmd: &mut std::process::Command,
+    ) -> anyhow::Result<Option<CgroupLease>> {
+        Ok(None)
+    }
 }
 
 #[cfg(test)]
@@ -115,6 +134,15 @@ mod tests {
         assert_eq!(runtime.memory_budget(), 0);
     }
 
+    #[test]
+    fn default_resource_limits_are_none() {
+        let mut cmd = std::process::Command::new("echo");
+        assert!(DummyRuntime
+            .apply_resource_limits("shell", &mut cmd)
+            .unwrap()
+            .is_none());
+    }
+
     #[test]
     fn runtime_reports_capabilities() {
         let runtime = DummyRuntime;
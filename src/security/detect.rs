@@ -1,11 +1,14 @@
 //! Auto-detection of available security features
 
-use crate::config::{SandboxBackend, SecurityConfig};
+use crate::config::{SandboxBackend, SandboxConfig, SecurityConfig};
 use crate::security::traits::Sandbox;
 use std::sync::Arc;
 
-/// Create a sandbox based on auto-detection or explicit config
-pub fn create_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
+/// Create a sandbox based on auto-detection or explicit config.
+///
+/// `network_allowlist` is the domain allowlist that `restrict_network`
+/// mirrors for Landlock (normally `[http_request] allowed_domains`).
+pub fn create_sandbox(config: &SecurityConfig, network_allowlist: &[String]) -> Arc<dyn Sandbox> {
     let backend = &config.sandbox.backend;
 
     // If explicitly disabled, return noop
@@ -13,6 +16,69 @@ pub fn create_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
         return Arc::new(super::traits::NoopSandbox);
     }
 
+    let primary = select_backend(&config.sandbox, network_allowlist);
+    if config.sandbox.restrict_network && primary.name() != "landlock" {
+        tracing::warn!(
+            "sandbox.restrict_network requires the Landlock backend; network is not restricted"
+        );
+    }
+    stack_seccomp(primary, &config.sandbox)
+}
+
+/// Layer seccomp-bpf on top of the selected backend when enabled.
+///
+/// Only Landlock (or no backend) is stacked: Firejail, Bubblewrap and Docker
+/// wrap the command in another binary that installs its own filters and may
+/// need privileges `PR_SET_NO_NEW_PRIVS` would take away.
+fn stack_seccomp(primary: Arc<dyn Sandbox>, sandbox: &SandboxConfig) -> Arc<dyn Sandbox> {
+    if !sandbox.seccomp.enabled || matches!(sandbox.backend, SandboxBackend::Seccomp) {
+        return primary;
+    }
+    match primary.name() {
+        "landlock" | "none" => {}
+        other => {
+            tracing::warn!("seccomp filter is not stacked on the {other} backend; skipping");
+            return primary;
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        match super::seccomp::SeccompSandbox::new(&sandbox.seccomp) {
+            Ok(seccomp) if primary.name() == "none" => return Arc::new(seccomp),
+            Ok(seccomp) => {
+                return Arc::new(super::traits::StackedSandbox::new(vec![
+                    primary,
+                    Arc::new(seccomp),
+                ]));
+            }
+            Err(e) => tracing::warn!("seccomp filter unavailable: {e}"),
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    tracing::warn!("seccomp filter is only supported on Linux; skipping");
+
+    primary
+}
+
+#[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
+fn landlock_sandbox(
+    sandbox: &SandboxConfig,
+    network_allowlist: &[String],
+) -> std::io::Result<super::landlock::LandlockSandbox> {
+    use super::landlock::{LandlockNetworkPolicy, LandlockSandbox};
+
+    let network = if sandbox.restrict_network {
+        LandlockNetworkPolicy::from_allowlist(network_allowlist, &sandbox.allowed_ports)
+    } else {
+        LandlockNetworkPolicy::Unrestricted
+    };
+    LandlockSandbox::with_network(None, network)
+}
+
+fn select_backend(sandbox: &SandboxConfig, network_allowlist: &[String]) -> Arc<dyn Sandbox> {
+    let backend = &sandbox.backend;
+
     // If specific backend requested, try that
     match backend {
         SandboxBackend::Landlock => {
@@ -20,7 +86,7 @@ pub fn create_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
             {
                 #[cfg(target_os = "linux")]
                 {
-                    if let Ok(sandbox) = super::landlock::LandlockSandbox::new() {
+                    if let Ok(sandbox) = landlock_sandbox(sandbox, network_allowlist) {
                         return Arc::new(sandbox);
                     }
                 }
@@ -76,21 +142,37 @@ pub fn create_sandbox(config: &SecurityConfig) -> Arc<dyn Sandbox> {
             );
             Arc::new(super::traits::NoopSandbox)
         }
+        SandboxBackend::Seccomp => {
+            #[cfg(target_os = "linux")]
+            {
+                if let Ok(sandbox) = super::seccomp::SeccompSandbox::new(&sandbox.seccomp) {
+                    return Arc::new(sandbox);
+                }
+            }
+            tracing::warn!(
+                "seccomp requested but not available, falling back to application-layer"
+            );
+            Arc::new(super::traits::NoopSandbox)
+        }
         SandboxBackend::Auto | SandboxBackend::None => {
             // Auto-detect best available
-            detect_best_sandbox()
+            detect_best_sandbox(sandbox, network_allowlist)
         }
     }
 }
 
 /// Auto-detect the best available sandbox
-fn detect_best_sandbox() -> Arc<dyn Sandbox> {
+fn detect_best_sandbox(sandbox: &SandboxConfig, network_allowlist: &[String]) -> Arc<dyn Sandbox> {
+    // Only the Landlock backend reads the sandbox settings.
+    #[cfg(not(all(feature = "sandbox-landlock", target_os = "linux")))]
+    let _ = (sandbox, network_allowlist);
+
     #[cfg(target_os = "linux")]
     {
         // Try Landlock first (native, no dependencies)
         #[cfg(feature = "sandbox-landlock")]
         {
-            if let Ok(sandbox) = super::landlock::LandlockSandbox::probe() {
+            if let Ok(sandbox) = landlock_sandbox(sandbox, network_allowlist) {
                 tracing::info!("Landlock sandbox enabled (Linux kernel 5.13+)");
                 return Arc::new(sandbox);
             }
@@ -135,11 +217,11 @@ fn detect_best_sandbox() -> Arc<dyn Sandbox> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{SandboxConfig, SecurityConfig};
+    use crate::config::{SeccompConfig, SecurityConfig};
 
     #[test]
     fn detect_best_sandbox_returns_something() {
-        let sandbox = detect_best_sandbox();
+        let sandbox = detect_best_sandbox(&SandboxConfig::default(), &[]);
         // Should always return at least NoopSandbox
         assert!(sandbox.is_available());
     }
@@ -151,10 +233,11 @@ mod tests {
                 enabled: Some(false),
                 backend: SandboxBackend::None,
                 firejail_args: Vec::new(),
+                ..SandboxConfig::default()
             },
             ..Default::default()
         };
-        let sandbox = create_sandbox(&config);
+        let sandbox = create_sandbox(&config, &[]);
         assert_eq!(sandbox.name(), "none");
     }
 
@@ -165,11 +248,58 @@ mod tests {
                 enabled: None, // Auto-detect
                 backend: SandboxBackend::Auto,
                 firejail_args: Vec::new(),
+                ..SandboxConfig::default()
             },
             ..Default::default()
         };
-        let sandbox = create_sandbox(&config);
+        let sandbox = create_sandbox(&config, &[]);
         // Should return some sandbox (at least NoopSandbox)
         assert!(sandbox.is_available());
     }
+
+    #[cfg(all(
+        target_os = "linux",
+        any(target_arch = "x86_64", target_arch = "aarch64")
+    ))]
+    #[test]
+    fn explicit_seccomp_backend_selects_seccomp() {
+        let config = SecurityConfig {
+            sandbox: SandboxConfig {
+                backend: SandboxBackend::Seccomp,
+                ..SandboxConfig::default()
+            },
+            ..Default::default()
+        };
+        let sandbox = create_sandbox(&config, &[]);
+        assert!(matches!(sandbox.name(), "seccomp" | "none"));
+    }
+
+    #[test]
+    fn seccomp_is_not_stacked_on_wrapper_backends() {
+        struct Wrapper;
+        impl Sandbox for Wrapper {
+            fn wrap_command(&self, _cmd: &mut std::process::Command) -> std::io::Result<()> {
+                Ok(())
+            }
+            fn is_available(&self) -> bool {
+                true
+            }
+            fn name(&self) -> &str {
+                "firejail"
+            }
+            fn description(&self) -> &str {
+                "test"
+            }
+        }
+
+        let sandbox = SandboxConfig {
+            seccomp: SeccompConfig {
+                enabled: true,
+                ..SeccompConfig::default()
+            },
+            ..SandboxConfig::default()
+        };
+        let stacked = stack_seccomp(Arc::new(Wrapper), &sandbox);
+        assert_eq!(stacked.name(), "firejail");
+    }
 }
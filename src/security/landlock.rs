@@ -1,20 +1,58 @@
 //! Landlock sandbox (Linux kernel 5.13+ LSM)
 //!
 //! Landlock provides unprivileged sandboxing through the Linux kernel.
-//! This module uses the pure-Rust `landlock` crate for filesystem access control.
+//! This module uses the pure-Rust `landlock` crate for filesystem access control
+//! and, on kernels with Landlock ABI v4+ (6.7), TCP bind/connect restriction.
+//! Restrictions are applied to each sandboxed child just before `exec`, never
+//! to the ZeroClaw process itself.
 
 #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
-use landlock::{AccessFs, PathBeneath, PathFd, Ruleset, RulesetAttr, RulesetCreatedAttr};
+use landlock::{
+    AccessFs, AccessNet, NetPort, PathBeneath, PathFd, Ruleset, RulesetAttr, RulesetCreated,
+    RulesetCreatedAttr,
+};
 #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
 use std::path::Path;
 
 use crate::security::traits::Sandbox;
 
+/// TCP policy enforced by Landlock for sandboxed commands.
+///
+/// Landlock filters by port, not host, so a domain allowlist is mapped
+/// conservatively: `"*"` leaves TCP unrestricted, any narrower list denies
+/// `bind` and limits `connect` to the configured ports, and an empty list
+/// denies all TCP.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum LandlockNetworkPolicy {
+    /// TCP is not handled by the ruleset.
+    #[default]
+    Unrestricted,
+    /// Deny TCP bind; allow TCP connect only to these ports.
+    ConnectPorts(Vec<u16>),
+}
+
+impl LandlockNetworkPolicy {
+    /// Derive the policy from a domain allowlist (`[http_request] allowed_domains`).
+    pub fn from_allowlist(allowed_domains: &[String], allowed_ports: &[u16]) -> Self {
+        if allowed_domains.iter().any(|d| d.trim() == "*") {
+            Self::Unrestricted
+        } else if allowed_domains.iter().all(|d| d.trim().is_empty()) {
+            Self::ConnectPorts(Vec::new())
+        } else {
+            let mut ports = allowed_ports.to_vec();
+            ports.sort_unstable();
+            ports.dedup();
+            Self::ConnectPorts(ports)
+        }
+    }
+}
+
 /// Landlock sandbox backend for Linux
 #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
 #[derive(Debug)]
 pub struct LandlockSandbox {
     workspace_dir: Option<std::path::PathBuf>,
+    network: LandlockNetworkPolicy,
 }
 
 #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
@@ -26,13 +64,24 @@ impl LandlockSandbox {
 
     /// Create a Landlock sandbox with a specific workspace directory
     pub fn with_workspace(workspace_dir: Option<std::path::PathBuf>) -> std::io::Result<Self> {
+        Self::with_network(workspace_dir, LandlockNetworkPolicy::Unrestricted)
+    }
+
+    /// Create a Landlock sandbox that also enforces a TCP policy
+    pub fn with_network(
+        workspace_dir: Option<std::path::PathBuf>,
+        network: LandlockNetworkPolicy,
+    ) -> std::io::Result<Self> {
         // Test if Landlock is available by trying to create a minimal ruleset
         let test_ruleset = Ruleset::default()
             .handle_access(AccessFs::ReadFile | AccessFs::WriteFile)
             .and_then(|ruleset| ruleset.create());
 
         match test_ruleset {
-            Ok(_) => Ok(Self { workspace_dir }),
+            Ok(_) => Ok(Self {
+                workspace_dir,
+                network,
+            }),
             Err(e) => {
                 tracing::debug!("Landlock not available: {}", e);
                 Err(std::io::Error::new(
@@ -48,8 +97,10 @@ impl LandlockSandbox {
         Self::new()
     }
 
-    /// Apply Landlock restrictions to the current process
-    fn apply_restrictions(&self) -> std::io::Result<()> {
+    /// Build the ruleset for a sandboxed child. Network rules are best-effort:
+    /// kernels without Landlock ABI v4 silently ignore them.
+    fn build_ruleset(&self) -> std::io::Result<RulesetCreated> {
+        let restrict_network = matches!(self.network, LandlockNetworkPolicy::ConnectPorts(_));
         let mut ruleset = Ruleset::default()
             .handle_access(
                 AccessFs::ReadFile
@@ -64,6 +115,13 @@ impl LandlockSandbox {
                     | AccessFs::MakeReg
                     | AccessFs::MakeSym,
             )
+            .and_then(|ruleset| {
+                if restrict_network {
+                    ruleset.handle_access(AccessNet::BindTcp | AccessNet::ConnectTcp)
+                } else {
+                    Ok(ruleset)
+                }
+            })
             .and_then(|ruleset| ruleset.create())
             .map_err(|e| std::io::Error::other(e.to_string()))?;
 
@@ -110,27 +168,42 @@ impl LandlockSandbox {
             ))
             .map_err(|e| std::io::Error::other(e.to_string()))?;
 
-        // Apply the ruleset
-        match ruleset.restrict_self() {
-            Ok(_) => {
-                tracing::debug!("Landlock restrictions applied successfully");
-                Ok(())
-            }
-            Err(e) => {
-                tracing::warn!("Failed to apply Landlock restrictions: {}", e);
-                Err(std::io::Error::other(e.to_string()))
+        // Allow outbound TCP to the permitted ports only
+        if let LandlockNetworkPolicy::ConnectPorts(ref ports) = self.network {
+            for &port in ports {
+                ruleset = ruleset
+                    .add_rule(NetPort::new(port, AccessNet::ConnectTcp))
+                    .map_err(|e| std::io::Error::other(e.to_string()))?;
             }
         }
+
+        Ok(ruleset)
     }
 }
 
 #[cfg(all(feature = "sandbox-landlock", target_os = "linux"))]
 impl Sandbox for LandlockSandbox {
-    fn wrap_command(&self, _cmd: &mut std::process::Command) -> std::io::Result<()> {
-        // Apply Landlock restrictions before executing the command
-        // Note: This affects the current process, not the child process
-        // Child processes inherit the Landlock restrictions
-        self.apply_restrictions()
+    fn wrap_command(&self, cmd: &mut std::process::Command) -> std::io::Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        // Open the rule fds in the parent; only restrict_self runs after fork,
+        // so the restriction lands on the child and never on this process.
+        let mut ruleset = Some(self.build_ruleset()?);
+        // SAFETY: the hook only issues the prctl/landlock_restrict_self
+        // syscalls on a ruleset prepared before fork.
+        unsafe {
+            cmd.pre_exec(move || {
+                let Some(ruleset) = ruleset.take() else {
+                    return Ok(());
+                };
+                ruleset
+                    .restrict_self()
+                    .map(|_| ())
+                    .map_err(|_| std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+            });
+        }
+        tracing::debug!("Landlock restrictions armed for sandboxed command");
+        Ok(())
     }
 
     fn is_available(&self) -> bool {
@@ -146,7 +219,7 @@ impl Sandbox for LandlockSandbox {
     }
 
     fn description(&self) -> &str {
-        "Linux kernel LSM sandboxing (filesystem access control)"
+        "Linux kernel LSM sandboxing (filesystem and TCP access control)"
     }
 }
 
@@ -170,6 +243,16 @@ impl LandlockSandbox {
         ))
     }
 
+    pub fn with_network(
+        _workspace_dir: Option<std::path::PathBuf>,
+        _network: LandlockNetworkPolicy,
+    ) -> std::io::Result<Self> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "Landlock is only supported on Linux",
+        ))
+    }
+
     pub fn probe() -> std::io::Result<Self> {
         Err(std::io::Error::new(
             std::io::ErrorKind::Unsupported,
@@ -233,6 +316,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn network_policy_follows_domain_allowlist() {
+        let ports = [443, 80, 443];
+        assert_eq!(
+            LandlockNetworkPolicy::from_allowlist(&["*".into()], &ports),
+            LandlockNetworkPolicy::Unrestricted
+        );
+        assert_eq!(
+            LandlockNetworkPolicy::from_allowlist(&["api.example.com".into()], &ports),
+            LandlockNetworkPolicy::ConnectPorts(vec![80, 443])
+        );
+        assert_eq!(
+            LandlockNetworkPolicy::from_allowlist(&[], &ports),
+            LandlockNetworkPolicy::ConnectPorts(Vec::new())
+        );
+    }
+
     // ── §1.1 Landlock stub tests ──────────────────────────────
 
     #[cfg(not(all(feature = "sandbox-landlock", target_os = "linux")))]
@@ -8,8 +8,8 @@
 //!
 //! OS-level isolation is provided through the [`Sandbox`] trait defined in
 //! [`traits`], with pluggable backends including Docker, Firejail, Bubblewrap,
-//! and Landlock. The [`create_sandbox`] function selects the best available
-//! backend at runtime. An [`AuditLogger`] records security-relevant events for
+//! Landlock, and seccomp-bpf. The [`create_sandbox`] function selects the best
+//! available backend at runtime. An [`AuditLogger`] records security-relevant events for
 //! forensic review.
 //!
 //! # Extension
@@ -41,7 +41,10 @@ pub mod policy;
 pub mod prompt_guard;
 #[cfg(target_os = "macos")]
 pub mod seatbelt;
+#[cfg(target_os = "linux")]
+pub mod seccomp;
 pub mod secrets;
+pub mod tool_rules;
 pub mod traits;
 pub mod vulnerability;
 #[cfg(feature = "webauthn")]
@@ -1,7 +1,10 @@
+use super::tool_rules::ToolPolicy;
+use crate::trust::runtime::PersistentTrust;
 use parking_lot::Mutex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Instant;
 
 /// How much autonomy the agent has
@@ -92,6 +95,10 @@ pub struct SecurityPolicy {
     pub block_high_risk_commands: bool,
     pub shell_env_passthrough: Vec<String>,
     pub tracker: ActionTracker,
+    /// Trust tracker that tightens autonomy for regressed domains.
+    pub trust: Option<Arc<PersistentTrust>>,
+    /// `[[autonomy.rules]]`, compiled against `workspace_dir`.
+    pub tool_rules: Arc<ToolPolicy>,
 }
 
 /// Default allowed commands for Unix platforms.
@@ -224,6 +231,8 @@ impl Default for SecurityPolicy {
             block_high_risk_commands: true,
             shell_env_passthrough: vec![],
             tracker: ActionTracker::new(),
+            trust: None,
+            tool_rules: Arc::default(),
         }
     }
 }
@@ -848,6 +857,15 @@ impl SecurityPolicy {
             return Err(format!("Command not allowed by security policy: {command}"));
         }
 
+        let autonomy = self.autonomy_for_command(command);
+        if autonomy == AutonomyLevel::ReadOnly {
+            return Err(
+                "Command blocked: trust for this command's domain is below the regression \
+                 threshold, autonomy is read-only until it recovers"
+                    .into(),
+            );
+        }
+
         let risk = self.command_risk_level(command);
 
         // When the operator has set `allowed_commands = ["*"]` AND explicitly
@@ -863,7 +881,7 @@ impl SecurityPolicy {
             if self.block_high_risk_commands && !self.is_command_explicitly_allowed(command) {
                 return Err("Command blocked: high-risk command is disallowed by policy".into());
             }
-            if self.autonomy == AutonomyLevel::Supervised && !approved {
+            if autonomy == AutonomyLevel::Supervised && !approved {
                 return Err(
                     "Command requires explicit approval (approved=true): high-risk operation"
                         .into(),
@@ -872,7 +890,7 @@ impl SecurityPolicy {
         }
 
         if risk == CommandRiskLevel::Medium
-            && self.autonomy == AutonomyLevel::Supervised
+            && autonomy == AutonomyLevel::Supervised
             && self.require_approval_for_medium_risk
             && !approved
         {
@@ -1278,6 +1296,31 @@ impl SecurityPolicy {
         self.autonomy != AutonomyLevel::ReadOnly
     }
 
+    /// Autonomy level for a tool after trust-based tightening.
+    ///
+    /// With a trust tracker and the tool's domain in regression, this is one
+    /// level below the configured autonomy.
+    pub fn autonomy_for_tool(&self, tool_name: &str) -> AutonomyLevel {
+        self.trust.as_ref().map_or(self.autonomy, |trust| {
+            trust.effective_autonomy(tool_name, self.autonomy)
+        })
+    }
+
+    /// Trust-adjusted autonomy for a shell command: `git` invocations are
+    /// judged by the `git` domain, everything else by `shell`.
+    fn autonomy_for_command(&self, command: &str) -> AutonomyLevel {
+        let executable = skip_env_assignments(command.trim())
+            .split_whitespace()
+            .next()
+            .map(|word| command_basename(strip_wrapping_quotes(word)).to_ascii_lowercase())
+            .unwrap_or_default();
+        if strip_windows_exe_suffix(&executable) == "git" {
+            self.autonomy_for_tool("git")
+        } else {
+            self.autonomy_for_tool("shell")
+        }
+    }
+
     // ── Tool Operation Gating ──────────────────────────────────────────────
     // Read operations bypass autonomy and rate checks because they have
     // no side effects. Act operations must pass both the autonomy gate
@@ -1301,6 +1344,13 @@ impl SecurityPolicy {
                     ));
                 }
 
+                if self.autonomy_for_tool(operation_name) == AutonomyLevel::ReadOnly {
+                    return Err(format!(
+                        "Security policy: trust for '{operation_name}' is below the regression \
+                         threshold, read-only until it recovers"
+                    ));
+                }
+
                 if !self.record_action() {
                     return Err("Rate limit exceeded: action budget exhausted".to_string());
                 }
@@ -1390,9 +1440,20 @@ impl SecurityPolicy {
             block_high_risk_commands: autonomy_config.block_high_risk_commands,
             shell_env_passthrough: autonomy_config.shell_env_passthrough.clone(),
             tracker: ActionTracker::new(),
+            trust: None,
+            tool_rules: Arc::new(
+                ToolPolicy::compile(&autonomy_config.rules).with_workspace_dir(workspace_dir),
+            ),
         }
     }
 
+    /// Tighten per-domain autonomy from `trust` (see [`Self::autonomy_for_tool`]).
+    #[must_use]
+    pub fn with_trust(mut self, trust: Option<Arc<PersistentTrust>>) -> Self {
+        self.trust = trust;
+        self
+    }
+
     /// Render a human-readable summary of the active security constraints
     /// suitable for injection into the LLM system prompt.
     ///
@@ -1984,6 +2045,31 @@ mod tests {
         assert_eq!(policy.workspace_dir, PathBuf::from("/tmp/test-workspace"));
     }
 
+    #[test]
+    fn from_config_compiles_tool_rules_against_workspace() {
+        let autonomy_config = crate::config::AutonomyConfig {
+            rules: vec![crate::config::ToolRuleConfig {
+                tool: "file_write".into(),
+                action: crate::config::ToolRuleAction::Deny,
+                reason: None,
+                paths: vec!["secrets/**".into()],
+                hosts: Vec::new(),
+                recipient_domains: Vec::new(),
+                git_subcommands: Vec::new(),
+            }],
+            ..crate::config::AutonomyConfig::default()
+        };
+        let workspace = PathBuf::from("/tmp/test-workspace");
+        let policy = SecurityPolicy::from_config(&autonomy_config, &workspace);
+
+        let absolute = serde_json::json!({"path": "/tmp/test-workspace/secrets/key.pem"});
+        assert!(policy
+            .tool_rules
+            .evaluate("file_write", &absolute)
+            .is_some());
+        assert!(SecurityPolicy::default().tool_rules.is_empty());
+    }
+
     #[test]
     fn from_config_normalizes_allowed_roots() {
         let autonomy_config = crate::config::AutonomyConfig {
@@ -0,0 +1,442 @@
+//! seccomp-bpf sandbox (Linux)
+//!
+//! Installs a syscall filter in each sandboxed child between `fork` and
+//! `exec`. Denied syscalls fail with `EPERM` instead of killing the process,
+//! so tools see an ordinary error. Packet sockets and raw IP sockets are
+//! always denied; raw netlink sockets (used by `ip`, `ss`) stay allowed.
+//! Only x86_64 and aarch64 are supported; on other architectures the backend
+//! reports itself unavailable.
+
+use crate::config::{SeccompConfig, SeccompProfile};
+use crate::security::traits::Sandbox;
+use std::process::Command;
+
+/// Syscalls denied by [`SeccompProfile::Default`]: debugging other processes,
+/// mounting, loading kernels/modules, and kernel-facing escape hatches.
+const DEFAULT_DENIED: &[&str] = &[
+    "ptrace",
+    "process_vm_readv",
+    "process_vm_writev",
+    "mount",
+    "umount2",
+    "pivot_root",
+    "kexec_load",
+    "kexec_file_load",
+    "init_module",
+    "finit_module",
+    "delete_module",
+    "reboot",
+    "swapon",
+    "swapoff",
+    "bpf",
+    "perf_event_open",
+    "keyctl",
+    "add_key",
+    "request_key",
+    "setns",
+    "acct",
+    "open_by_handle_at",
+    "userfaultfd",
+];
+
+/// Additional syscalls denied by [`SeccompProfile::Strict`].
+const STRICT_DENIED: &[&str] = &[
+    "unshare",
+    "chroot",
+    "personality",
+    "syslog",
+    "settimeofday",
+    "clock_settime",
+    "adjtimex",
+    "quotactl",
+    "vhangup",
+    "name_to_handle_at",
+    "iopl",
+    "ioperm",
+];
+
+/// Resolve a syscall name to its number on the build architecture.
+pub fn syscall_number(name: &str) -> Option<i64> {
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    {
+        let nr = match name {
+            "ptrace" => libc::SYS_ptrace,
+            "process_vm_readv" => libc::SYS_process_vm_readv,
+            "process_vm_writev" => libc::SYS_process_vm_writev,
+            "mount" => libc::SYS_mount,
+            "umount2" => libc::SYS_umount2,
+            "pivot_root" => libc::SYS_pivot_root,
+            "kexec_load" => libc::SYS_kexec_load,
+            "kexec_file_load" => libc::SYS_kexec_file_load,
+            "init_module" => libc::SYS_init_module,
+            "finit_module" => libc::SYS_finit_module,
+            "delete_module" => libc::SYS_delete_module,
+            "reboot" => libc::SYS_reboot,
+            "swapon" => libc::SYS_swapon,
+            "swapoff" => libc::SYS_swapoff,
+            "bpf" => libc::SYS_bpf,
+            "perf_event_open" => libc::SYS_perf_event_open,
+            "keyctl" => libc::SYS_keyctl,
+            "add_key" => libc::SYS_add_key,
+            "request_key" => libc::SYS_request_key,
+            "setns" => libc::SYS_setns,
+            "acct" => libc::SYS_acct,
+            "open_by_handle_at" => libc::SYS_open_by_handle_at,
+            "name_to_handle_at" => libc::SYS_name_to_handle_at,
+            "userfaultfd" => libc::SYS_userfaultfd,
+            "unshare" => libc::SYS_unshare,
+            "chroot" => libc::SYS_chroot,
+            "personality" => libc::SYS_personality,
+            "syslog" => libc::SYS_syslog,
+            "settimeofday" => libc::SYS_settimeofday,
+            "clock_settime" => libc::SYS_clock_settime,
+            "adjtimex" => libc::SYS_adjtimex,
+            "quotactl" => libc::SYS_quotactl,
+            "vhangup" => libc::SYS_vhangup,
+            "socket" => libc::SYS_socket,
+            "clone3" => libc::SYS_clone3,
+            "io_uring_setup" => libc::SYS_io_uring_setup,
+            #[cfg(target_arch = "x86_64")]
+            "iopl" => libc::SYS_iopl,
+            #[cfg(target_arch = "x86_64")]
+            "ioperm" => libc::SYS_ioperm,
+            _ => return None,
+        };
+        Some(nr)
+    }
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        let _ = name;
+        None
+    }
+}
+
+/// Names in `names` that are not known syscalls on this architecture.
+pub fn unknown_syscalls(names: &[String]) -> Vec<String> {
+    names
+        .iter()
+        .filter(|name| syscall_number(name).is_none())
+        .cloned()
+        .collect()
+}
+
+/// Resolve the denylist for a profile plus config overrides. Names that do
+/// not exist on this architecture (e.g. `iopl` on aarch64) are skipped.
+pub fn denied_syscalls(config: &SeccompConfig) -> Vec<i64> {
+    let mut names: Vec<&str> = DEFAULT_DENIED.to_vec();
+    if config.profile == SeccompProfile::Strict {
+        names.extend_from_slice(STRICT_DENIED);
+    }
+    names.extend(config.deny_syscalls.iter().map(String::as_str));
+    names.retain(|name| !config.allow_syscalls.iter().any(|allowed| allowed == name));
+
+    let mut numbers: Vec<i64> = names.into_iter().filter_map(syscall_number).collect();
+    numbers.sort_unstable();
+    numbers.dedup();
+    numbers
+}
+
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+mod bpf {
+    use libc::sock_filter;
+
+    #[cfg(target_arch = "x86_64")]
+    pub const AUDIT_ARCH: u32 = 0xC000_003E;
+    #[cfg(target_arch = "aarch64")]
+    pub const AUDIT_ARCH: u32 = 0xC000_00B7;
+
+    /// Syscall numbers at or above this are the x32 ABI on x86_64.
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    // Offsets into `struct seccomp_data` (little-endian; low word of args).
+    const OFFSET_NR: u32 = 0;
+    const OFFSET_ARCH: u32 = 4;
+    const OFFSET_ARG0: u32 = 16;
+    const OFFSET_ARG1: u32 = 24;
+
+    const SOCK_TYPE_MASK: u32 = 0xf;
+
+    const RET_ALLOW: u32 = libc::SECCOMP_RET_ALLOW;
+    const RET_KILL: u32 = libc::SECCOMP_RET_KILL_PROCESS;
+    #[allow(clippy::cast_sign_loss)]
+    const RET_EPERM: u32 = libc::SECCOMP_RET_ERRNO | (libc::EPERM as u32 & 0xffff);
+
+    fn stmt(code: u32, k: u32) -> sock_filter {
+        sock_filter {
+            #[allow(clippy::cast_possible_truncation)]
+            code: code as u16,
+            jt: 0,
+            jf: 0,
+            k,
+        }
+    }
+
+    fn jump(code: u32, k: u32, jt: u8, jf: u8) -> sock_filter {
+        sock_filter {
+            #[allow(clippy::cast_possible_truncation)]
+            code: code as u16,
+            jt,
+            jf,
+            k,
+        }
+    }
+
+    fn load(offset: u32) -> sock_filter {
+        stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, offset)
+    }
+
+    fn ret(action: u32) -> sock_filter {
+        stmt(libc::BPF_RET | libc::BPF_K, action)
+    }
+
+    fn jeq(k: u32, jt: u8, jf: u8) -> sock_filter {
+        jump(libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K, k, jt, jf)
+    }
+
+    /// Build the filter program: wrong-arch → kill, x32 → EPERM, each denied
+    /// syscall → EPERM, `socket(AF_PACKET, ..)` and `socket(AF_INET/AF_INET6,
+    /// SOCK_RAW, ..)` → EPERM, everything else allowed.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn build_filter(denied: &[i64]) -> Vec<sock_filter> {
+        let mut prog = vec![
+            load(OFFSET_ARCH),
+            jeq(AUDIT_ARCH, 1, 0),
+            ret(RET_KILL),
+            load(OFFSET_NR),
+            jump(
+                libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+                X32_SYSCALL_BIT,
+                0,
+                1,
+            ),
+            ret(RET_EPERM),
+        ];
+        for &nr in denied {
+            prog.push(jeq(nr as u32, 0, 1));
+            prog.push(ret(RET_EPERM));
+        }
+        // Raw/packet socket block; jump offsets count instructions skipped.
+        prog.extend([
+            jeq(libc::SYS_socket as u32, 0, 8),
+            load(OFFSET_ARG0),
+            jeq(libc::AF_PACKET as u32, 5, 0),
+            jeq(libc::AF_INET as u32, 1, 0),
+            jeq(libc::AF_INET6 as u32, 0, 4),
+            load(OFFSET_ARG1),
+            stmt(libc::BPF_ALU | libc::BPF_AND | libc::BPF_K, SOCK_TYPE_MASK),
+            jeq(libc::SOCK_RAW as u32, 0, 1),
+            ret(RET_EPERM),
+            ret(RET_ALLOW),
+        ]);
+        prog
+    }
+}
+
+/// seccomp-bpf sandbox backend for Linux
+#[derive(Debug, Clone)]
+pub struct SeccompSandbox {
+    denied: Vec<i64>,
+}
+
+impl SeccompSandbox {
+    /// Create a seccomp sandbox for the configured profile
+    pub fn new(config: &SeccompConfig) -> std::io::Result<Self> {
+        if !Self::is_supported() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "seccomp-bpf is only supported on Linux x86_64/aarch64",
+            ));
+        }
+        Ok(Self {
+            denied: denied_syscalls(config),
+        })
+    }
+
+    /// Probe if seccomp is available (for auto-detection)
+    pub fn probe(config: &SeccompConfig) -> std::io::Result<Self> {
+        Self::new(config)
+    }
+
+    fn is_supported() -> bool {
+        if !cfg!(any(target_arch = "x86_64", target_arch = "aarch64")) {
+            return false;
+        }
+        // PR_GET_SECCOMP fails with EINVAL when the kernel lacks seccomp.
+        // SAFETY: prctl with PR_GET_SECCOMP takes no pointers.
+        unsafe { libc::prctl(libc::PR_GET_SECCOMP, 0, 0, 0, 0) >= 0 }
+    }
+
+    /// Syscall numbers this sandbox denies.
+    pub fn denied(&self) -> &[i64] {
+        &self.denied
+    }
+}
+
+impl Sandbox for SeccompSandbox {
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    fn wrap_command(&self, cmd: &mut Command) -> std::io::Result<()> {
+        use std::os::unix::process::CommandExt;
+
+        // Build the program before fork: the pre_exec hook must not allocate.
+        let filter = bpf::build_filter(&self.denied);
+        // SAFETY: the hook only issues prctl syscalls on memory owned by the
+        // closure, which is async-signal-safe.
+        unsafe {
+            cmd.pre_exec(move || {
+                let prog = libc::sock_fprog {
+                    #[allow(clippy::cast_possible_truncation)]
+                    len: filter.len() as u16,
+                    filter: filter.as_ptr().cast_mut(),
+                };
+                if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                if libc::prctl(
+                    libc::PR_SET_SECCOMP,
+                    libc::SECCOMP_MODE_FILTER,
+                    &raw const prog,
+                ) != 0
+                {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+    fn wrap_command(&self, _cmd: &mut Command) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "seccomp-bpf is only supported on Linux x86_64/aarch64",
+        ))
+    }
+
+    fn is_available(&self) -> bool {
+        Self::is_supported()
+    }
+
+    fn name(&self) -> &str {
+        "seccomp"
+    }
+
+    fn description(&self) -> &str {
+        "Linux seccomp-bpf syscall filtering (ptrace, mount, kexec, raw sockets)"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(profile: SeccompProfile) -> SeccompConfig {
+        SeccompConfig {
+            profile,
+            ..SeccompConfig::default()
+        }
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    fn default_profile_denies_core_syscalls() {
+        let denied = denied_syscalls(&config(SeccompProfile::Default));
+        for name in ["ptrace", "mount", "kexec_load", "bpf"] {
+            assert!(denied.contains(&syscall_number(name).unwrap()), "{name}");
+        }
+        assert!(!denied.contains(&libc::SYS_unshare));
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    fn strict_profile_and_overrides_adjust_denylist() {
+        let mut cfg = config(SeccompProfile::Strict);
+        cfg.allow_syscalls = vec!["ptrace".into()];
+        cfg.deny_syscalls = vec!["io_uring_setup".into()];
+        let denied = denied_syscalls(&cfg);
+        assert!(denied.contains(&libc::SYS_unshare));
+        assert!(denied.contains(&libc::SYS_io_uring_setup));
+        assert!(!denied.contains(&libc::SYS_ptrace));
+    }
+
+    #[test]
+    fn unknown_syscall_names_are_reported() {
+        let unknown = unknown_syscalls(&["ptrace".into(), "not_a_syscall".into()]);
+        assert!(unknown.contains(&"not_a_syscall".to_string()));
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    fn filter_ends_with_socket_block_and_allow() {
+        let prog = bpf::build_filter(&[libc::SYS_ptrace]);
+        // 6 header + 2 per denied syscall + 10 socket block.
+        assert_eq!(prog.len(), 6 + 2 + 10);
+        assert_eq!(prog.last().unwrap().k, libc::SECCOMP_RET_ALLOW);
+    }
+
+    /// Run the filter over one `socket(family, kind)` call.
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn socket_verdict(family: i32, kind: i32) -> u32 {
+        let prog = bpf::build_filter(&[]);
+        let load = |offset: u32| match offset {
+            0 => libc::SYS_socket as u32,
+            4 => bpf::AUDIT_ARCH,
+            16 => family as u32,
+            24 => kind as u32,
+            _ => 0,
+        };
+        let (mut pc, mut acc) = (0usize, 0u32);
+        loop {
+            let insn = prog[pc];
+            let code = u32::from(insn.code);
+            pc += 1;
+            if code == libc::BPF_RET | libc::BPF_K {
+                return insn.k;
+            } else if code == libc::BPF_LD | libc::BPF_W | libc::BPF_ABS {
+                acc = load(insn.k);
+            } else if code == libc::BPF_ALU | libc::BPF_AND | libc::BPF_K {
+                acc &= insn.k;
+            } else {
+                let taken = if (code & libc::BPF_JGE) == libc::BPF_JGE {
+                    acc >= insn.k
+                } else {
+                    acc == insn.k
+                };
+                pc += usize::from(if taken { insn.jt } else { insn.jf });
+            }
+        }
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    #[allow(clippy::cast_sign_loss)]
+    fn socket_block_matches_family_before_raw_type() {
+        let eperm = libc::SECCOMP_RET_ERRNO | libc::EPERM as u32;
+        assert_eq!(socket_verdict(libc::AF_INET, libc::SOCK_RAW), eperm);
+        assert_eq!(socket_verdict(libc::AF_INET6, libc::SOCK_RAW), eperm);
+        assert_eq!(socket_verdict(libc::AF_PACKET, libc::SOCK_DGRAM), eperm);
+        assert_eq!(
+            socket_verdict(libc::AF_NETLINK, libc::SOCK_RAW),
+            libc::SECCOMP_RET_ALLOW
+        );
+        assert_eq!(
+            socket_verdict(libc::AF_INET, libc::SOCK_STREAM),
+            libc::SECCOMP_RET_ALLOW
+        );
+    }
+
+    #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+    #[test]
+    fn sandboxed_child_runs_normally() {
+        let Ok(sandbox) = SeccompSandbox::new(&SeccompConfig::default()) else {
+            return; // seccomp unavailable in this environment
+        };
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", "echo ok"]);
+        sandbox.wrap_command(&mut cmd).unwrap();
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "ok");
+    }
+}
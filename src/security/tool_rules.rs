@@ -0,0 +1,663 @@
+//! Argument-aware tool rules (`[[autonomy.rules]]`).
+//!
+//! `auto_approve` / `always_ask` decide by tool name alone. Rules add
+//! predicates over the call's arguments — path globs, URL hosts, email
+//! recipient domains, git subcommands — and yield `allow`, `ask` or `deny`
+//! with a reason. Rules are evaluated in order and the first match wins;
+//! a call no rule matches keeps the name-based behavior.
+//!
+//! Each [`SecurityPolicy`](super::SecurityPolicy) compiles the rules once
+//! against its workspace. The agent's tool executor enforces `deny` for
+//! every tool call through that instance, and the
+//! [`ApprovalManager`](crate::approval::ApprovalManager) shares it for
+//! `allow` and `ask`. `zeroclaw policy explain` walks the same rules through
+//! [`ToolPolicy::explain`].
+
+use super::DomainMatcher;
+use crate::config::{Config, ToolRuleAction, ToolRuleConfig};
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Argument keys holding a filesystem path.
+const PATH_KEYS: &[&str] = &["path", "file_path"];
+/// Argument keys holding a URL.
+const URL_KEYS: &[&str] = &["url"];
+/// Argument keys holding email recipients.
+const RECIPIENT_KEYS: &[&str] = &["to", "cc", "bcc", "recipients"];
+/// Argument key holding the `git_operations` subcommand.
+const GIT_SUBCOMMAND_KEY: &str = "operation";
+
+/// `*` stays within one path segment; `**` crosses directories.
+const PATH_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// The decision of the first matching rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolRuleDecision {
+    pub action: ToolRuleAction,
+    /// Index into `[[autonomy.rules]]`.
+    pub rule: usize,
+    pub reason: String,
+}
+
+/// One rule as walked by [`ToolPolicy::explain`].
+#[derive(Debug, Clone)]
+pub struct RuleCheck {
+    /// Index into `[[autonomy.rules]]`.
+    pub rule: usize,
+    pub tool: String,
+    pub action: ToolRuleAction,
+    /// Why the rule did not match; `None` when it matched.
+    pub mismatch: Option<String>,
+}
+
+/// Compiled `[[autonomy.rules]]`.
+#[derive(Debug, Default)]
+pub struct ToolPolicy {
+    rules: Vec<CompiledRule>,
+    /// Absolute path arguments under this directory are matched in their
+    /// workspace-relative form.
+    workspace_dir: Option<PathBuf>,
+}
+
+#[derive(Debug)]
+struct CompiledRule {
+    index: usize,
+    tool: Pattern,
+    action: ToolRuleAction,
+    reason: Option<String>,
+    paths: Vec<Pattern>,
+    hosts: Option<DomainMatcher>,
+    recipient_domains: Option<DomainMatcher>,
+    git_subcommands: Vec<String>,
+}
+
+impl ToolPolicy {
+    /// Compile rules, failing on the first invalid glob or domain pattern.
+    pub fn from_rules(rules: &[ToolRuleConfig]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| CompiledRule::new(index, rule))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            rules,
+            workspace_dir: None,
+        })
+    }
+
+    /// Match path globs relative to `workspace_dir`.
+    #[must_use]
+    pub fn with_workspace_dir(mut self, workspace_dir: &Path) -> Self {
+        self.workspace_dir = Some(workspace_dir.to_path_buf());
+        self
+    }
+
+    /// The workspace path globs are matched relative to, if set.
+    pub fn workspace_dir(&self) -> Option<&Path> {
+        self.workspace_dir.as_deref()
+    }
+
+    /// Compile rules, dropping all of them with a warning when any is invalid.
+    /// Config validation rejects invalid rules, so this only guards callers
+    /// that build a policy from an unvalidated config.
+    pub fn compile(rules: &[ToolRuleConfig]) -> Self {
+        Self::from_rules(rules).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid [[autonomy.rules]]: {e:#}");
+            Self::default()
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The decision of the first rule matching this call, if any.
+    pub fn evaluate(&self, tool_name: &str, args: &Value) -> Option<ToolRuleDecision> {
+        self.rules
+            .iter()
+            .find(|rule| {
+                rule.mismatch(tool_name, args, self.workspace_dir())
+                    .is_none()
+            })
+            .map(CompiledRule::decision)
+    }
+
+    /// Walk the rules in order for this call, stopping at the first match.
+    pub fn explain(&self, tool_name: &str, args: &Value) -> Vec<RuleCheck> {
+        let mut checks = Vec::new();
+        for rule in &self.rules {
+            let mismatch = rule.mismatch(tool_name, args, self.workspace_dir());
+            let matched = mismatch.is_none();
+            checks.push(RuleCheck {
+                rule: rule.index,
+                tool: rule.tool.as_str().to_string(),
+                action: rule.action,
+                mismatch,
+            });
+            if matched {
+                break;
+            }
+        }
+        checks
+    }
+}
+
+impl CompiledRule {
+    fn new(index: usize, rule: &ToolRuleConfig) -> Result<Self> {
+        let field = |name: &str| format!("autonomy.rules[{index}].{name}");
+
+        let tool = Pattern::new(rule.tool.trim())
+            .with_context(|| format!("{} is not a valid glob", field("tool")))?;
+        let paths = rule
+            .paths
+            .iter()
+            .map(|p| {
+                Pattern::new(p.trim())
+                    .with_context(|| format!("{} entry '{p}' is not a valid glob", field("paths")))
+            })
+            .collect::<Result<_>>()?;
+        let domains = |patterns: &[String], name: &str| -> Result<Option<DomainMatcher>> {
+            if patterns.is_empty() {
+                return Ok(None);
+            }
+            DomainMatcher::new(patterns, &[])
+                .map(Some)
+                .with_context(|| format!("{} is invalid", field(name)))
+        };
+
+        Ok(Self {
+            index,
+            tool,
+            action: rule.action,
+            reason: rule
+                .reason
+                .as_deref()
+                .map(str::trim)
+                .filter(|r| !r.is_empty())
+                .map(str::to_string),
+            paths,
+            hosts: domains(&rule.hosts, "hosts")?,
+            recipient_domains: domains(&rule.recipient_domains, "recipient_domains")?,
+            git_subcommands: rule
+                .git_subcommands
+                .iter()
+                .map(|s| s.trim().to_ascii_lowercase())
+                .collect(),
+        })
+    }
+
+    fn decision(&self) -> ToolRuleDecision {
+        ToolRuleDecision {
+            action: self.action,
+            rule: self.index,
+            reason: self.reason.clone().unwrap_or_else(|| {
+                format!(
+                    "autonomy.rules[{}] ({} {})",
+                    self.index,
+                    self.action.as_str(),
+                    self.tool.as_str()
+                )
+            }),
+        }
+    }
+
+    /// `None` when the rule matches, otherwise why it does not.
+    fn mismatch(
+        &self,
+        tool_name: &str,
+        args: &Value,
+        workspace_dir: Option<&Path>,
+    ) -> Option<String> {
+        if !self.tool.matches(tool_name) {
+            return Some(format!("tool does not match '{}'", self.tool.as_str()));
+        }
+
+        if !self.paths.is_empty() {
+            let paths = string_args(args, PATH_KEYS);
+            if paths.is_empty() {
+                return Some("no path argument".into());
+            }
+            let matched = paths.iter().any(|path| {
+                path_forms(path, workspace_dir).iter().any(|form| {
+                    self.paths
+                        .iter()
+                        .any(|p| p.matches_with(form, PATH_MATCH_OPTIONS))
+                })
+            });
+            if !matched {
+                return Some(format!("path '{}' matches no paths glob", paths[0]));
+            }
+        }
+
+        if let Some(hosts) = &self.hosts {
+            let urls = string_args(args, URL_KEYS);
+            if urls.is_empty() {
+                return Some("no url argument".into());
+            }
+            if !urls.iter().any(|url| hosts.is_gated(url)) {
+                return Some(format!("host of '{}' matches no hosts pattern", urls[0]));
+            }
+        }
+
+        if let Some(domains) = &self.recipient_domains {
+            let recipients: Vec<String> = string_args(args, RECIPIENT_KEYS)
+                .iter()
+                .flat_map(|r| split_list(r))
+                .collect();
+            if recipients.is_empty() {
+                return Some("no recipients".into());
+            }
+            // An allow rule must cover every recipient; deny/ask trigger on any.
+            let matched = match self.action {
+                ToolRuleAction::Allow => recipients.iter().all(|r| domains.is_gated(r)),
+                _ => recipients.iter().any(|r| domains.is_gated(r)),
+            };
+            if !matched {
+                let shown = recipients
+                    .iter()
+                    .find(|r| !domains.is_gated(r))
+                    .unwrap_or(&recipients[0]);
+                return Some(format!(
+                    "recipient '{shown}' matches no recipient_domains pattern"
+                ));
+            }
+        }
+
+        if !self.git_subcommands.is_empty() {
+            let Some(op) = args.get(GIT_SUBCOMMAND_KEY).and_then(Value::as_str) else {
+                return Some("no git operation argument".into());
+            };
+            let op = op.trim().to_ascii_lowercase();
+            if !self.git_subcommands.contains(&op) {
+                return Some(format!("git operation '{op}' is not listed"));
+            }
+        }
+
+        None
+    }
+}
+
+/// Collect string values under `keys`; arrays contribute each string element.
+fn string_args(args: &Value, keys: &[&str]) -> Vec<String> {
+    let mut values = Vec::new();
+    for key in keys {
+        match args.get(key) {
+            Some(Value::String(s)) => values.push(s.clone()),
+            Some(Value::Array(items)) => {
+                values.extend(items.iter().filter_map(Value::as_str).map(str::to_string));
+            }
+            _ => {}
+        }
+    }
+    values
+}
+
+/// The forms a path argument is matched in: lexically normalised (`.` and
+/// empty segments dropped, `..` resolved), plus the workspace-relative form
+/// when it is an absolute path inside `workspace_dir`.
+fn path_forms(path: &str, workspace_dir: Option<&Path>) -> Vec<String> {
+    let (absolute, segments) = normalize_segments(path);
+    let joined = segments.join("/");
+    let mut forms = Vec::with_capacity(2);
+    if absolute {
+        let workspace = workspace_dir
+            .and_then(Path::to_str)
+            .map(normalize_segments)
+            .filter(|(abs, _)| *abs);
+        if let Some((_, root)) = workspace {
+            if segments.len() > root.len() && segments.starts_with(&root) {
+                forms.push(segments[root.len()..].join("/"));
+            }
+        }
+        forms.push(format!("/{joined}"));
+    } else {
+        forms.push(joined);
+    }
+    forms
+}
+
+fn normalize_segments(path: &str) -> (bool, Vec<&str>) {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|s| *s != "..") => {
+                segments.pop();
+            }
+            // `..` above the root stays at the root
+            ".." if path.starts_with('/') => {}
+            other => segments.push(other),
+        }
+    }
+    (path.starts_with('/'), segments)
+}
+
+/// Split a comma- or semicolon-separated recipient list.
+fn split_list(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split([',', ';'])
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Handle `zeroclaw policy` subcommands.
+pub fn handle_command(command: crate::PolicyCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::PolicyCommands::Explain { tool, args } => {
+            let args: Value =
+                serde_json::from_str(&args).context("Tool arguments must be valid JSON")?;
+            if !args.is_object() {
+                anyhow::bail!("Tool arguments must be a JSON object");
+            }
+            let policy = Arc::new(
+                ToolPolicy::from_rules(&config.autonomy.rules)?
+                    .with_workspace_dir(&config.workspace_dir),
+            );
+            let approval = crate::approval::ApprovalManager::from_config(&config.autonomy)
+                .with_rules(Arc::clone(&policy));
+            print!("{}", explain_report(&policy, &approval, &tool, &args));
+            Ok(())
+        }
+    }
+}
+
+/// Human-readable walk of the rules for one call, ending in the decision.
+fn explain_report(
+    policy: &ToolPolicy,
+    approval: &crate::approval::ApprovalManager,
+    tool_name: &str,
+    args: &Value,
+) -> String {
+    use std::fmt::Write;
+
+    let mut out = String::new();
+    let _ = writeln!(out, "Tool:      {tool_name}");
+    let _ = writeln!(out, "Arguments: {args}");
+    let _ = writeln!(out);
+
+    let checks = policy.explain(tool_name, args);
+    if checks.is_empty() {
+        let _ = writeln!(out, "  (no [[autonomy.rules]] configured)");
+    }
+    for check in &checks {
+        let outcome = check
+            .mismatch
+            .as_deref()
+            .map_or_else(|| "matched".to_string(), |m| format!("skipped: {m}"));
+        let _ = writeln!(
+            out,
+            "  rules[{}] {} {} — {outcome}",
+            check.rule,
+            check.action.as_str(),
+            check.tool
+        );
+    }
+    let _ = writeln!(out);
+
+    match policy.evaluate(tool_name, args) {
+        Some(decision) if decision.action == ToolRuleAction::Deny => {
+            let _ = writeln!(out, "Decision: deny — {}", decision.reason);
+        }
+        Some(decision) => {
+            let prompt = if approval.needs_approval_for_call(tool_name, args) {
+                "approval required"
+            } else {
+                "runs without approval"
+            };
+            let _ = writeln!(
+                out,
+                "Decision: {} ({prompt}) — {}",
+                decision.action.as_str(),
+                decision.reason
+            );
+        }
+        None => {
+            let prompt = if approval.needs_approval(tool_name) {
+                "approval required"
+            } else {
+                "runs without approval"
+            };
+            let _ = writeln!(
+                out,
+                "Decision: no rule matched; auto_approve / always_ask apply ({prompt})"
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule(tool: &str, action: ToolRuleAction) -> ToolRuleConfig {
+        ToolRuleConfig {
+            tool: tool.into(),
+            action,
+            reason: None,
+            paths: Vec::new(),
+            hosts: Vec::new(),
+            recipient_domains: Vec::new(),
+            git_subcommands: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let policy = ToolPolicy::from_rules(&[
+            ToolRuleConfig {
+                paths: vec!["**/.env".into(), "/etc/**".into()],
+                reason: Some("secrets stay put".into()),
+                ..rule("file_*", ToolRuleAction::Deny)
+            },
+            rule("file_write", ToolRuleAction::Allow),
+        ])
+        .unwrap();
+
+        let denied = policy
+            .evaluate("file_write", &json!({"path": "config/.env"}))
+            .unwrap();
+        assert_eq!(denied.action, ToolRuleAction::Deny);
+        assert_eq!(denied.reason, "secrets stay put");
+        let root = policy
+            .evaluate("file_edit", &json!({"path": "./.env"}))
+            .unwrap();
+        assert_eq!(root.action, ToolRuleAction::Deny);
+
+        let allowed = policy
+            .evaluate("file_write", &json!({"path": "src/main.rs"}))
+            .unwrap();
+        assert_eq!(allowed.action, ToolRuleAction::Allow);
+        assert_eq!(allowed.rule, 1);
+        assert!(policy.evaluate("shell", &json!({})).is_none());
+    }
+
+    #[test]
+    fn single_star_stays_within_a_directory() {
+        let policy = ToolPolicy::from_rules(&[ToolRuleConfig {
+            paths: vec!["docs/*.md".into()],
+            ..rule("file_write", ToolRuleAction::Allow)
+        }])
+        .unwrap();
+
+        assert!(policy
+            .evaluate("file_write", &json!({"path": "docs/a.md"}))
+            .is_some());
+        assert!(policy
+            .evaluate("file_write", &json!({"path": "docs/sub/a.md"}))
+            .is_none());
+        assert!(policy.evaluate("file_write", &json!({})).is_none());
+    }
+
+    #[test]
+    fn absolute_and_unnormalised_paths_match_workspace_globs() {
+        let policy = ToolPolicy::from_rules(&[ToolRuleConfig {
+            paths: vec!["secrets/**".into()],
+            ..rule("file_*", ToolRuleAction::Deny)
+        }])
+        .unwrap()
+        .with_workspace_dir(Path::new("/home/user/ws"));
+
+        for path in [
+            "/home/user/ws/secrets/key.pem",
+            "/home/user/ws//secrets/./key.pem",
+            "./secrets//key.pem",
+            "docs/../secrets/key.pem",
+        ] {
+            assert!(
+                policy
+                    .evaluate("file_read", &json!({ "path": path }))
+                    .is_some(),
+                "{path} should be denied"
+            );
+        }
+        assert!(policy
+            .evaluate(
+                "file_read",
+                &json!({"path": "/home/user/wsx/secrets/key.pem"})
+            )
+            .is_none());
+        assert!(policy
+            .evaluate("file_read", &json!({"path": "/other/secrets/key.pem"}))
+            .is_none());
+    }
+
+    #[test]
+    fn hosts_match_url_argument() {
+        let policy = ToolPolicy::from_rules(&[ToolRuleConfig {
+            hosts: vec!["*.internal".into(), "169.254.169.254".into()],
+            ..rule("http_request", ToolRuleAction::Deny)
+        }])
+        .unwrap();
+
+        for url in [
+            "http://api.internal/v1",
+            "https://user@db.internal:8443/x",
+            "http://169.254.169.254/latest/meta-data",
+        ] {
+            assert!(policy
+                .evaluate("http_request", &json!({ "url": url }))
+                .is_some());
+        }
+        assert!(policy
+            .evaluate("http_request", &json!({"url": "https://example.com"}))
+            .is_none());
+    }
+
+    #[test]
+    fn recipient_domains_use_any_for_deny_and_all_for_allow() {
+        let deny = ToolPolicy::from_rules(&[ToolRuleConfig {
+            recipient_domains: vec!["competitor.com".into()],
+            ..rule("*", ToolRuleAction::Deny)
+        }])
+        .unwrap();
+        let args = json!({"action": "mail_send", "to": ["a@corp.com", "b@competitor.com"]});
+        assert!(deny.evaluate("microsoft365", &args).is_some());
+
+        let allow = ToolPolicy::from_rules(&[ToolRuleConfig {
+            recipient_domains: vec!["corp.com".into()],
+            ..rule("microsoft365", ToolRuleAction::Allow)
+        }])
+        .unwrap();
+        assert!(allow.evaluate("microsoft365", &args).is_none());
+        assert!(allow
+            .evaluate("microsoft365", &json!({"to": "a@corp.com; b@CORP.com"}))
+            .is_some());
+    }
+
+    #[test]
+    fn git_subcommands_match_operation() {
+        let policy = ToolPolicy::from_rules(&[ToolRuleConfig {
+            git_subcommands: vec!["commit".into(), "checkout".into()],
+            ..rule("git_operations", ToolRuleAction::Ask)
+        }])
+        .unwrap();
+
+        assert!(policy
+            .evaluate("git_operations", &json!({"operation": "commit"}))
+            .is_some());
+        assert!(policy
+            .evaluate("git_operations", &json!({"operation": "status"}))
+            .is_none());
+    }
+
+    #[test]
+    fn explain_reports_mismatches_up_to_first_match() {
+        let policy = ToolPolicy::from_rules(&[
+            rule("shell", ToolRuleAction::Deny),
+            ToolRuleConfig {
+                git_subcommands: vec!["commit".into()],
+                ..rule("git_operations", ToolRuleAction::Ask)
+            },
+            rule("git_*", ToolRuleAction::Allow),
+            rule("*", ToolRuleAction::Deny),
+        ])
+        .unwrap();
+
+        let checks = policy.explain("git_operations", &json!({"operation": "diff"}));
+        assert_eq!(checks.len(), 3);
+        assert!(checks[0].mismatch.as_deref().unwrap().contains("tool"));
+        assert!(checks[1].mismatch.as_deref().unwrap().contains("'diff'"));
+        assert!(checks[2].mismatch.is_none());
+        assert_eq!(checks[2].action, ToolRuleAction::Allow);
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        let bad_glob = ToolRuleConfig {
+            paths: vec!["[".into()],
+            ..rule("file_write", ToolRuleAction::Deny)
+        };
+        let err = ToolPolicy::from_rules(&[bad_glob]).unwrap_err();
+        assert!(err.to_string().contains("autonomy.rules[0].paths"));
+
+        let bad_host = ToolRuleConfig {
+            hosts: vec!["bad host".into()],
+            ..rule("http_request", ToolRuleAction::Deny)
+        };
+        assert!(ToolPolicy::from_rules(&[bad_host]).is_err());
+        assert!(ToolPolicy::compile(&[ToolRuleConfig {
+            tool: "[".into(),
+            ..rule("x", ToolRuleAction::Deny)
+        }])
+        .is_empty());
+    }
+
+    #[test]
+    fn explain_report_names_deciding_rule() {
+        let autonomy = crate::config::AutonomyConfig {
+            rules: vec![ToolRuleConfig {
+                hosts: vec!["*.internal".into()],
+                reason: Some("internal network is off limits".into()),
+                ..rule("http_request", ToolRuleAction::Deny)
+            }],
+            ..crate::config::AutonomyConfig::default()
+        };
+        let policy = Arc::new(ToolPolicy::from_rules(&autonomy.rules).unwrap());
+        let approval = crate::approval::ApprovalManager::from_config(&autonomy)
+            .with_rules(Arc::clone(&policy));
+
+        let report = explain_report(
+            &policy,
+            &approval,
+            "http_request",
+            &json!({"url": "http://db.internal/"}),
+        );
+        assert!(report.contains("rules[0] deny http_request — matched"));
+        assert!(report.contains("Decision: deny — internal network is off limits"));
+
+        let report = explain_report(&policy, &approval, "file_read", &json!({}));
+        assert!(report.contains("skipped: tool does not match"));
+        assert!(report.contains("no rule matched"));
+        assert!(report.contains("runs without approval"));
+    }
+}
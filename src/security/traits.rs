@@ -79,9 +79,71 @@ impl Sandbox for NoopSandbox {
     }
 }
 
+/// Applies several sandboxes to the same command, in order.
+///
+/// Used to layer complementary mechanisms that both act in the child's
+/// `pre_exec` hook (e.g. Landlock plus seccomp-bpf). Wrapper-binary backends
+/// such as Firejail should not be stacked: each would wrap the other.
+pub struct StackedSandbox {
+    layers: Vec<std::sync::Arc<dyn Sandbox>>,
+    name: String,
+    description: String,
+}
+
+impl StackedSandbox {
+    pub fn new(layers: Vec<std::sync::Arc<dyn Sandbox>>) -> Self {
+        let name = layers
+            .iter()
+            .map(|layer| layer.name())
+            .collect::<Vec<_>>()
+            .join("+");
+        let description = layers
+            .iter()
+            .map(|layer| layer.description())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Self {
+            layers,
+            name,
+            description,
+        }
+    }
+}
+
+impl Sandbox for StackedSandbox {
+    fn wrap_command(&self, cmd: &mut Command) -> std::io::Result<()> {
+        for layer in &self.layers {
+            layer.wrap_command(cmd)?;
+        }
+        Ok(())
+    }
+
+    fn is_available(&self) -> bool {
+        self.layers.iter().all(|layer| layer.is_available())
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn stacked_sandbox_joins_layer_names_and_wraps_each() {
+        let sandbox = StackedSandbox::new(vec![Arc::new(NoopSandbox), Arc::new(NoopSandbox)]);
+        assert_eq!(sandbox.name(), "none+none");
+        assert!(sandbox.is_available());
+        let mut cmd = Command::new("echo");
+        assert!(sandbox.wrap_command(&mut cmd).is_ok());
+    }
 
     #[test]
     fn noop_sandbox_name() {
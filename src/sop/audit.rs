@@ -120,7 +120,7 @@ fn category() -> MemoryCategory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sop::types::{SopEvent, SopRunStatus, SopStepStatus, SopTriggerSource};
+    use crate::sop::types::{SopEvent, SopRunFlow, SopRunStatus, SopStepStatus, SopTriggerSource};
 
     fn test_run() -> SopRun {
         SopRun {
@@ -131,6 +131,7 @@ mod tests {
                 topic: None,
                 payload: None,
                 timestamp: "2026-02-19T12:00:00Z".into(),
+                idempotency_key: None,
             },
             status: SopRunStatus::Running,
             current_step: 1,
@@ -140,6 +141,7 @@ mod tests {
             step_results: Vec::new(),
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         }
     }
 
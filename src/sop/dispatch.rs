@@ -5,6 +5,7 @@
 //! happen in exactly one place.
 
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use tracing::{debug, info, warn};
 
@@ -41,6 +42,9 @@ fn extract_run_id_from_action(action: &SopRunAction) -> &str {
         | SopRunAction::WaitApproval { run_id, .. }
         | SopRunAction::DeterministicStep { run_id, .. }
         | SopRunAction::CheckpointWait { run_id, .. }
+        | SopRunAction::FanOut { run_id, .. }
+        | SopRunAction::Retry { run_id, .. }
+        | SopRunAction::AwaitParallel { run_id, .. }
         | SopRunAction::Completed { run_id, .. }
         | SopRunAction::Failed { run_id, .. } => run_id,
     }
@@ -53,6 +57,9 @@ fn action_label(action: &SopRunAction) -> &'static str {
         SopRunAction::WaitApproval { .. } => "WaitApproval",
         SopRunAction::DeterministicStep { .. } => "DeterministicStep",
         SopRunAction::CheckpointWait { .. } => "CheckpointWait",
+        SopRunAction::FanOut { .. } => "FanOut",
+        SopRunAction::Retry { .. } => "Retry",
+        SopRunAction::AwaitParallel { .. } => "AwaitParallel",
         SopRunAction::Completed { .. } => "Completed",
         SopRunAction::Failed { .. } => "Failed",
     }
@@ -152,64 +159,89 @@ pub async fn dispatch_sop_event(
     results
 }
 
+/// Start a named SOP directly, bypassing trigger matching.
+///
+/// Used by callers that already decided which SOP should run (routines).
+/// Locking and audit follow the same rules as [`dispatch_sop_event`].
+pub async fn start_sop_by_name(
+    engine: &Arc<Mutex<SopEngine>>,
+    audit: &SopAuditLogger,
+    sop_name: &str,
+    event: SopEvent,
+) -> DispatchResult {
+    let (result, started_run) = {
+        let mut eng = match engine.lock() {
+            Ok(e) => e,
+            Err(e) => {
+                crate::health::mark_component_error("sop_dispatch", format!("lock poisoned: {e}"));
+                warn!("SOP dispatch: engine lock poisoned while starting '{sop_name}': {e}");
+                return DispatchResult::Skipped {
+                    sop_name: sop_name.to_string(),
+                    reason: "SOP engine unavailable".to_string(),
+                };
+            }
+        };
+
+        match eng.start_run(sop_name, event) {
+            Ok(action) => {
+                let run_id = extract_run_id_from_action(&action).to_string();
+                let run = eng.active_runs().get(&run_id).cloned();
+                info!(
+                    "SOP dispatch: started '{}' run {run_id} (action: {})",
+                    sop_name,
+                    action_label(&action),
+                );
+                (
+                    DispatchResult::Started {
+                        run_id,
+                        sop_name: sop_name.to_string(),
+                        action: Box::new(action),
+                    },
+                    run,
+                )
+            }
+            Err(e) => {
+                info!("SOP dispatch: skipped '{}': {e}", sop_name);
+                (
+                    DispatchResult::Skipped {
+                        sop_name: sop_name.to_string(),
+                        reason: e.to_string(),
+                    },
+                    None,
+                )
+            }
+        }
+    }; // lock dropped
+
+    if let Some(run) = started_run {
+        if let Err(e) = audit.log_run_start(&run).await {
+            warn!("SOP dispatch: audit log failed for run {}: {e}", run.run_id);
+        }
+    }
+
+    crate::health::mark_component_ok("sop_dispatch");
+    result
+}
+
 // ── Headless result processing ──────────────────────────────────
 
 /// Process dispatch results in headless (non-agent-loop) callers.
 ///
 /// This handles audit and logging for fan-in callers (MQTT, webhook, cron)
 /// that cannot execute SOP steps interactively. For `WaitApproval` actions,
-/// approval timeout polling in the scheduler handles progression.
+/// the engine tick's approval timeout handles progression.
 /// For `ExecuteStep` actions, the run is started in the engine but steps
 /// cannot be executed without an agent loop — this is logged as a warning.
-pub fn process_headless_results(results: &[DispatchResult]) {
+/// A parallel `FanOut` is rejected: the run is failed rather than left
+/// holding concurrency slots for steps nobody will run.
+pub fn process_headless_results(engine: &Arc<Mutex<SopEngine>>, results: &[DispatchResult]) {
     for result in results {
         match result {
             DispatchResult::Started {
                 run_id,
                 sop_name,
                 action,
-            } => match action.as_ref() {
-                SopRunAction::ExecuteStep { step, .. } => {
-                    warn!(
-                        "SOP headless dispatch: run {run_id} ('{sop_name}') ready for step {} \
-                         '{}' but no agent loop available to execute",
-                        step.number, step.title,
-                    );
-                }
-                SopRunAction::WaitApproval { step, .. } => {
-                    info!(
-                        "SOP headless dispatch: run {run_id} ('{sop_name}') waiting for approval \
-                         on step {} '{}'. Timeout polling will handle progression",
-                        step.number, step.title,
-                    );
-                }
-                SopRunAction::DeterministicStep { step, .. } => {
-                    info!(
-                        "SOP headless dispatch: run {run_id} ('{sop_name}') deterministic step {} \
-                         '{}'",
-                        step.number, step.title,
-                    );
-                }
-                SopRunAction::CheckpointWait {
-                    step, state_file, ..
-                } => {
-                    info!(
-                        "SOP headless dispatch: run {run_id} ('{sop_name}') checkpoint at step {} \
-                         '{}', state persisted to {}",
-                        step.number,
-                        step.title,
-                        state_file.display(),
-                    );
-                }
-                SopRunAction::Completed { .. } => {
-                    info!(
-                        "SOP headless dispatch: run {run_id} ('{sop_name}') completed immediately"
-                    );
-                }
-                SopRunAction::Failed { reason, .. } => {
-                    warn!("SOP headless dispatch: run {run_id} ('{sop_name}') failed: {reason}");
-                }
-            },
+            } => process_headless_action(engine, run_id, sop_name, action),
             DispatchResult::Skipped { sop_name, reason } => {
                 info!("SOP headless dispatch: skipped '{sop_name}': {reason}");
             }
@@ -218,6 +250,148 @@ pub fn process_headless_results(results: &[DispatchResult]) {
     }
 }
 
+fn process_headless_action(
+    engine: &Arc<Mutex<SopEngine>>,
+    run_id: &str,
+    sop_name: &str,
+    action: &SopRunAction,
+) {
+    match action {
+        SopRunAction::ExecuteStep { step, .. } => {
+            warn!(
+                "SOP headless dispatch: run {run_id} ('{sop_name}') ready for step {} \
+                 '{}' but no agent loop available to execute",
+                step.number, step.title,
+            );
+        }
+        SopRunAction::WaitApproval { step, .. } => {
+            info!(
+                "SOP headless dispatch: run {run_id} ('{sop_name}') waiting for approval \
+                 on step {} '{}'. Timeout polling will handle progression",
+                step.number, step.title,
+            );
+        }
+        SopRunAction::DeterministicStep { step, .. } => {
+            info!(
+                "SOP headless dispatch: run {run_id} ('{sop_name}') deterministic step {} \
+                 '{}'",
+                step.number, step.title,
+            );
+        }
+        SopRunAction::CheckpointWait {
+            step, state_file, ..
+        } => {
+            info!(
+                "SOP headless dispatch: run {run_id} ('{sop_name}') checkpoint at step {} \
+                 '{}', state persisted to {}",
+                step.number,
+                step.title,
+                state_file.display(),
+            );
+        }
+        SopRunAction::FanOut { actions, .. } => {
+            let reason = format!(
+                "parallel fan-out into {} steps needs an agent loop; headless dispatch \
+                 cannot run it",
+                actions.len()
+            );
+            let aborted = match engine.lock() {
+                Ok(mut engine) => engine.abort_run(run_id, reason),
+                Err(e) => Err(anyhow::anyhow!("engine lock poisoned: {e}")),
+            };
+            if let Err(e) = aborted {
+                warn!("SOP headless dispatch: run {run_id} ('{sop_name}') fan-out rejected: {e}");
+            }
+        }
+        SopRunAction::Retry {
+            attempt,
+            delay_secs,
+            ..
+        } => {
+            info!(
+                "SOP headless dispatch: run {run_id} ('{sop_name}') retrying (attempt \
+                 {attempt}) in {delay_secs}s"
+            );
+        }
+        SopRunAction::AwaitParallel { pending, .. } => {
+            info!(
+                "SOP headless dispatch: run {run_id} ('{sop_name}') waiting on parallel \
+                 steps {pending:?}"
+            );
+        }
+        SopRunAction::Completed { .. } => {
+            info!("SOP headless dispatch: run {run_id} ('{sop_name}') completed");
+        }
+        SopRunAction::Failed { reason, .. } => {
+            warn!("SOP headless dispatch: run {run_id} ('{sop_name}') failed: {reason}");
+        }
+    }
+}
+
+// ── Engine tick ─────────────────────────────────────────────────
+
+/// How often the engine tick checks timeouts and due retries.
+const ENGINE_TICK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Run one engine tick and process the resulting actions headlessly.
+pub fn tick_engine(engine: &Arc<Mutex<SopEngine>>) {
+    let actions: Vec<(String, SopRunAction)> = match engine.lock() {
+        Ok(mut eng) => {
+            let actions = eng.tick();
+            actions
+                .into_iter()
+                .map(|action| {
+                    let run_id = extract_run_id_from_action(&action);
+                    let sop_name = eng
+                        .get_run(run_id)
+                        .map(|run| run.sop_name.clone())
+                        .unwrap_or_default();
+                    (sop_name, action)
+                })
+                .collect()
+        }
+        Err(e) => {
+            warn!("SOP engine tick: engine lock poisoned: {e}");
+            return;
+        }
+    };
+    for (sop_name, action) in actions {
+        debug!(
+            "SOP engine tick: run {} produced {}",
+            extract_run_id_from_action(&action),
+            action_label(&action)
+        );
+        process_headless_action(
+            engine,
+            extract_run_id_from_action(&action),
+            &sop_name,
+            &action,
+        );
+    }
+}
+
+/// Tick `engine` periodically until it is dropped. Without this, step
+/// timeouts, retry backoffs and approval timeouts only advance when someone
+/// happens to call `sop_status`.
+pub fn spawn_engine_tick(engine: &Arc<Mutex<SopEngine>>) {
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let weak = Arc::downgrade(engine);
+    handle.spawn(async move {
+        let mut interval = tokio::time::interval(ENGINE_TICK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let Some(engine) = weak.upgrade() else {
+                break;
+            };
+            tick_engine(&engine);
+        }
+    });
+}
+
 // ── Peripheral signal helper ────────────────────────────────────
 
 /// Convenience wrapper for peripheral hardware callbacks.
@@ -236,6 +410,7 @@ pub async fn dispatch_peripheral_signal(
         topic: Some(format!("{board}/{signal}")),
         payload: payload.map(String::from),
         timestamp: now_iso8601(),
+        idempotency_key: None,
     };
     dispatch_sop_event(engine, audit, event).await
 }
@@ -334,6 +509,8 @@ pub async fn check_sop_cron_triggers(
                     topic: Some(expression.clone()),
                     payload: None,
                     timestamp: now_iso8601(),
+                    // One run per scheduled tick, even if the window is re-checked
+                    idempotency_key: Some(format!("cron:{expression}@{}", next.to_rfc3339())),
                 };
                 let results = dispatch_sop_event(engine, audit, event).await;
                 all_results.extend(results);
@@ -354,6 +531,7 @@ mod tests {
     use crate::memory::traits::Memory;
     use crate::sop::types::{
         Sop, SopExecutionMode, SopPriority, SopRunAction, SopStep, SopTrigger, SopTriggerSource,
+        StepFlow,
     };
 
     fn test_sop(name: &str, triggers: Vec<SopTrigger>) -> Sop {
@@ -372,6 +550,7 @@ mod tests {
                 requires_confirmation: false,
                 kind: crate::sop::SopStepKind::default(),
                 schema: None,
+                flow: StepFlow::default(),
             }],
             cooldown_secs: 0,
             max_concurrent: 2,
@@ -399,6 +578,41 @@ mod tests {
         SopAuditLogger::new(memory)
     }
 
+    #[tokio::test]
+    async fn headless_fan_out_fails_the_run() {
+        let mut sop = test_sop("fan-sop", vec![SopTrigger::Manual]);
+        let parallel = StepFlow {
+            parallel: Some("diag".into()),
+            ..StepFlow::default()
+        };
+        sop.steps[0].flow = parallel.clone();
+        let mut second = sop.steps[0].clone();
+        second.number = 2;
+        second.flow = parallel;
+        sop.steps.push(second);
+        let engine = test_engine(vec![sop]);
+        let audit = test_audit();
+
+        let event = SopEvent {
+            source: SopTriggerSource::Manual,
+            topic: None,
+            payload: None,
+            timestamp: now_iso8601(),
+            idempotency_key: None,
+        };
+        let result = start_sop_by_name(&engine, &audit, "fan-sop", event).await;
+        let DispatchResult::Started { run_id, action, .. } = &result else {
+            panic!("expected Started, got {result:?}");
+        };
+        assert!(matches!(action.as_ref(), SopRunAction::FanOut { .. }));
+
+        process_headless_results(&engine, std::slice::from_ref(&result));
+        let eng = engine.lock().unwrap();
+        let run = eng.get_run(run_id).unwrap();
+        assert_eq!(run.status, crate::sop::SopRunStatus::Failed);
+        assert!(eng.active_runs().is_empty());
+    }
+
     #[tokio::test]
     async fn dispatch_starts_matching_sop() {
         let engine = test_engine(vec![test_sop(
@@ -415,6 +629,7 @@ mod tests {
             topic: Some("sensors/temp".into()),
             payload: Some(r#"{"value": 42}"#.into()),
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
 
         let results = dispatch_sop_event(&engine, &audit, event).await;
@@ -443,6 +658,7 @@ mod tests {
                         topic: None,
                         payload: None,
                         timestamp: now_iso8601(),
+                        idempotency_key: None,
                     },
                 )
                 .unwrap();
@@ -467,6 +683,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         let results = dispatch_sop_event(&engine, &audit, event).await;
         assert_eq!(results.len(), 1);
@@ -475,6 +692,36 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn start_sop_by_name_ignores_triggers_and_reports_unknown_sops() {
+        let engine = test_engine(vec![test_sop(
+            "mqtt-only",
+            vec![SopTrigger::Mqtt {
+                topic: "sensors/temp".into(),
+                condition: None,
+            }],
+        )]);
+        let audit = test_audit();
+        let event = SopEvent {
+            source: SopTriggerSource::Manual,
+            topic: Some("routine:escalate".into()),
+            payload: None,
+            timestamp: now_iso8601(),
+            idempotency_key: None,
+        };
+
+        let started = start_sop_by_name(&engine, &audit, "mqtt-only", event.clone()).await;
+        assert!(
+            matches!(&started, DispatchResult::Started { sop_name, .. } if sop_name == "mqtt-only")
+        );
+        assert_eq!(engine.lock().unwrap().active_runs().len(), 1);
+
+        let missing = start_sop_by_name(&engine, &audit, "missing", event).await;
+        assert!(
+            matches!(&missing, DispatchResult::Skipped { sop_name, .. } if sop_name == "missing")
+        );
+    }
+
     #[tokio::test]
     async fn dispatch_returns_no_match_for_unknown_event() {
         let engine = test_engine(vec![test_sop("manual-sop", vec![SopTrigger::Manual])]);
@@ -486,6 +733,7 @@ mod tests {
             topic: Some("some/topic".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         let results = dispatch_sop_event(&engine, &audit, event).await;
         assert_eq!(results.len(), 1);
@@ -514,6 +762,7 @@ mod tests {
             topic: Some("/api/deploy".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
 
         let results = dispatch_sop_event(&engine, &audit, event).await;
@@ -545,6 +794,7 @@ mod tests {
             topic: Some("alert".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
 
         let results = dispatch_sop_event(&engine, &audit, event).await;
@@ -578,6 +828,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
 
         let results = dispatch_sop_event(&engine, &audit, event).await;
@@ -1,18 +1,24 @@
 use std::collections::HashMap;
 use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{bail, Result};
 use tracing::{info, warn};
 
 use super::condition::evaluate_condition;
 use super::load_sops;
+use super::store;
 use super::types::{
-    DeterministicRunState, DeterministicSavings, Sop, SopEvent, SopExecutionMode, SopPriority,
-    SopRun, SopRunAction, SopRunStatus, SopStep, SopStepKind, SopStepResult, SopStepStatus,
-    SopTrigger, SopTriggerSource,
+    DeterministicRunState, DeterministicSavings, InFlightStep, Sop, SopEvent, SopExecutionMode,
+    SopPriority, SopRun, SopRunAction, SopRunFlow, SopRunStatus, SopStep, SopStepKind,
+    SopStepResult, SopStepStatus, SopTrigger, SopTriggerSource,
 };
 use crate::config::SopConfig;
+use crate::providers::structured;
+
+/// Upper bound on recorded step results per run, guarding against branch loops.
+const MAX_STEP_RESULTS: usize = 1000;
 
 /// Central SOP orchestrator: loads SOPs, matches triggers, manages run lifecycle.
 pub struct SopEngine {
@@ -24,6 +30,8 @@ pub struct SopEngine {
     run_counter: u64,
     /// Cumulative savings from deterministic execution.
     deterministic_savings: DeterministicSavings,
+    /// Run journal every state change is written to.
+    journal: Option<Arc<store::RunJournal>>,
 }
 
 impl SopEngine {
@@ -36,7 +44,34 @@ impl SopEngine {
             config,
             run_counter: 0,
             deterministic_savings: DeterministicSavings::default(),
+            journal: None,
+        }
+    }
+
+    /// Journal runs to the SQLite store under `workspace_dir` and restore the
+    /// runs it holds: unfinished runs become active again (with their pending
+    /// approvals, in-flight steps and timeout bases) and recent finished runs
+    /// restore cooldown and status history. Returns the number of resumed runs.
+    pub fn enable_journal(&mut self, workspace_dir: &Path) -> Result<usize> {
+        let keep = match self.config.max_finished_runs {
+            0 => usize::MAX,
+            max => max,
+        };
+        let journal = store::RunJournal::open(workspace_dir)?;
+        let finished = journal.load_finished_runs(keep)?;
+        let active = journal.load_active_runs()?;
+
+        self.finished_runs = finished;
+        let resumed = active.len();
+        for run in active {
+            info!(
+                "SOP run {} resumed for '{}' ({})",
+                run.run_id, run.sop_name, run.status
+            );
+            self.active_runs.insert(run.run_id.clone(), run);
         }
+        self.journal = Some(Arc::new(journal));
+        Ok(resumed)
     }
 
     /// Load/reload SOPs from the configured directory.
@@ -136,6 +171,8 @@ impl SopEngine {
             .ok_or_else(|| anyhow::anyhow!("SOP not found: {sop_name}"))?
             .clone();
 
+        self.check_duplicate(sop_name, &event)?;
+
         if !self.can_start(sop_name) {
             bail!(
                 "Cannot start SOP '{}': cooldown or concurrency limit reached",
@@ -143,9 +180,9 @@ impl SopEngine {
             );
         }
 
-        if sop.steps.is_empty() {
+        let Some(first_step) = first_step_number(&sop) else {
             bail!("SOP '{}' has no steps defined", sop_name);
-        }
+        };
 
         self.run_counter += 1;
         let dur = std::time::SystemTime::now()
@@ -160,41 +197,32 @@ impl SopEngine {
             sop_name: sop_name.to_string(),
             trigger_event: event,
             status: SopRunStatus::Running,
-            current_step: 1,
+            current_step: first_step,
             total_steps: u32::try_from(sop.steps.len()).unwrap_or(u32::MAX),
             started_at: now,
             completed_at: None,
             step_results: Vec::new(),
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
 
         self.active_runs.insert(run_id.clone(), run);
 
         info!("SOP run {} started for '{}'", run_id, sop_name);
 
-        // Determine first action based on execution mode
-        let step = sop.steps[0].clone();
-        let context = format_step_context(&sop, &self.active_runs[&run_id], &step);
-        let action = resolve_step_action(&sop, &step, run_id.clone(), context);
-
-        // If the action is WaitApproval, update run status and record timestamp
-        if matches!(action, SopRunAction::WaitApproval { .. }) {
-            if let Some(run) = self.active_runs.get_mut(&run_id) {
-                run.status = SopRunStatus::WaitingApproval;
-                run.waiting_since = Some(now_iso8601());
-            }
-        }
-
-        Ok(action)
+        let action = self.enter_step(&sop, &run_id, first_step, serde_json::Value::Null, false);
+        self.journal(&run_id);
+        action
     }
 
-    /// Report the result of the current step and advance the run.
-    /// Returns the next action to take.
+    /// Report the result of a step and advance the run.
+    /// `result.step_number` selects the step, which matters during a parallel
+    /// fan-out where several steps are in flight. Returns the next action to take.
     pub fn advance_step(&mut self, run_id: &str, result: SopStepResult) -> Result<SopRunAction> {
         let run = self
             .active_runs
-            .get_mut(run_id)
+            .get(run_id)
             .ok_or_else(|| anyhow::anyhow!("Active run not found: {run_id}"))?;
 
         let sop = self
@@ -204,43 +232,36 @@ impl SopEngine {
             .ok_or_else(|| anyhow::anyhow!("SOP '{}' no longer loaded", run.sop_name))?
             .clone();
 
-        // Record step result
-        run.step_results.push(result.clone());
-
-        // Check if step failed
-        if result.status == SopStepStatus::Failed {
-            let reason = format!("Step {} failed: {}", result.step_number, result.output);
-            warn!("SOP run {run_id}: {reason}");
-            return Ok(self.finish_run(run_id, SopRunStatus::Failed, Some(reason)));
-        }
-
-        // Advance to next step
-        let next_step_num = run.current_step + 1;
-        if next_step_num > run.total_steps {
-            // All steps completed
-            info!("SOP run {run_id} completed successfully");
-            return Ok(self.finish_run(run_id, SopRunStatus::Completed, None));
-        }
-
-        // Update run state
-        let run = self.active_runs.get_mut(run_id).unwrap();
-        run.current_step = next_step_num;
-
-        let step_idx = (next_step_num - 1) as usize;
-        let step = sop.steps[step_idx].clone();
-        let context = format_step_context(&sop, run, &step);
-        let run_id_str = run_id.to_string();
-        let action = resolve_step_action(&sop, &step, run_id_str.clone(), context);
-
-        // If the action is WaitApproval, update run status and record timestamp
-        if matches!(action, SopRunAction::WaitApproval { .. }) {
-            if let Some(run) = self.active_runs.get_mut(&run_id_str) {
-                run.status = SopRunStatus::WaitingApproval;
-                run.waiting_since = Some(now_iso8601());
+        // Reject output that violates the step's output schema without
+        // recording it, so the agent can correct it and report again.
+        if result.status == SopStepStatus::Completed {
+            if let Some(schema) = step_output_schema(&sop, result.step_number) {
+                if let Err(reason) = validate_step_output(&result.output, schema) {
+                    record_sop_deviation(
+                        &sop.name,
+                        &format!("step {} output violated its schema", result.step_number),
+                    );
+                    bail!(
+                        "Step {} output does not match its output schema: {reason}",
+                        result.step_number
+                    );
+                }
             }
         }
 
-        Ok(action)
+        let value = structured::parse_json_response(&result.output)
+            .unwrap_or_else(|_| serde_json::Value::String(result.output.clone()));
+        let action = self.record_outcome(
+            &sop,
+            run_id,
+            result.step_number,
+            result.status,
+            result.output,
+            value,
+            None,
+        );
+        self.journal(run_id);
+        action
     }
 
     /// Cancel an active run.
@@ -249,6 +270,7 @@ impl SopEngine {
             bail!("Active run not found: {run_id}");
         }
         self.finish_run(run_id, SopRunStatus::Cancelled, None);
+        self.journal(run_id);
         info!("SOP run {run_id} cancelled");
         Ok(())
     }
@@ -277,15 +299,10 @@ impl SopEngine {
             .ok_or_else(|| anyhow::anyhow!("SOP '{}' no longer loaded", run.sop_name))?
             .clone();
 
-        let step_idx = (run.current_step - 1) as usize;
-        let step = sop.steps[step_idx].clone();
-        let context = format_step_context(&sop, run, &step);
-
-        Ok(SopRunAction::ExecuteStep {
-            run_id: run_id.to_string(),
-            step,
-            context,
-        })
+        let step_number = run.current_step;
+        let action = self.enter_step(&sop, run_id, step_number, serde_json::Value::Null, true);
+        self.journal(run_id);
+        action
     }
 
     /// List finished runs, optionally filtered by SOP name.
@@ -323,6 +340,8 @@ impl SopEngine {
             );
         }
 
+        self.check_duplicate(sop_name, &event)?;
+
         if !self.can_start(sop_name) {
             bail!(
                 "Cannot start SOP '{}': cooldown or concurrency limit reached",
@@ -330,9 +349,9 @@ impl SopEngine {
             );
         }
 
-        if sop.steps.is_empty() {
+        let Some(first_step) = first_step_number(&sop) else {
             bail!("SOP '{}' has no steps defined", sop_name);
-        }
+        };
 
         self.run_counter += 1;
         let dur = std::time::SystemTime::now()
@@ -348,13 +367,14 @@ impl SopEngine {
             sop_name: sop_name.to_string(),
             trigger_event: event,
             status: SopRunStatus::Running,
-            current_step: 1,
+            current_step: first_step,
             total_steps,
             started_at: now,
             completed_at: None,
             step_results: Vec::new(),
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
 
         self.active_runs.insert(run_id.clone(), run);
@@ -363,18 +383,42 @@ impl SopEngine {
             run_id, sop_name
         );
 
-        // Produce first step action
-        let step = sop.steps[0].clone();
-        let input = serde_json::Value::Null;
-        self.resolve_deterministic_action(&sop, &run_id, &step, input)
+        let action = self.enter_step(&sop, &run_id, first_step, serde_json::Value::Null, false);
+        self.journal(&run_id);
+        action
     }
 
     /// Advance a deterministic run with the output of the current step.
-    /// The output is piped as input to the next step.
+    /// The output is piped as input to the next step. During a parallel
+    /// fan-out use [`Self::report_deterministic_step`] instead.
     pub fn advance_deterministic_step(
         &mut self,
         run_id: &str,
         step_output: serde_json::Value,
+    ) -> Result<SopRunAction> {
+        let run = self
+            .active_runs
+            .get(run_id)
+            .ok_or_else(|| anyhow::anyhow!("Active run not found: {run_id}"))?;
+
+        let step_number = match run.flow.in_flight.as_slice() {
+            [] => run.current_step,
+            [only] => only.step_number,
+            many => bail!(
+                "Run {run_id} has {} parallel steps in flight; report each by step number",
+                many.len()
+            ),
+        };
+        self.report_deterministic_step(run_id, step_number, step_output)
+    }
+
+    /// Report the output of a specific deterministic step (any member of a
+    /// parallel fan-out). Returns the next action once the step is recorded.
+    pub fn report_deterministic_step(
+        &mut self,
+        run_id: &str,
+        step_number: u32,
+        step_output: serde_json::Value,
     ) -> Result<SopRunAction> {
         let run = self
             .active_runs
@@ -388,44 +432,38 @@ impl SopEngine {
             .ok_or_else(|| anyhow::anyhow!("SOP '{}' no longer loaded", run.sop_name))?
             .clone();
 
-        // Record step result
-        let now = now_iso8601();
-        let step_result = SopStepResult {
-            step_number: run.current_step,
-            status: SopStepStatus::Completed,
-            output: step_output.to_string(),
-            started_at: run.started_at.clone(),
-            completed_at: Some(now),
-        };
-        run.step_results.push(step_result);
+        let schema_error = step_output_schema(&sop, step_number)
+            .and_then(|schema| structured::validate_against_schema(&step_output, schema).err())
+            .map(|reason| {
+                format!("Step {step_number} output does not match its output schema: {reason}")
+            });
 
         // Each deterministic step saves one LLM call
-        run.llm_calls_saved += 1;
-
-        // Advance to next step
-        let next_step_num = run.current_step + 1;
-        if next_step_num > run.total_steps {
-            info!(
-                "Deterministic SOP run {run_id} completed ({} LLM calls saved)",
-                run.llm_calls_saved
-            );
-            let saved = run.llm_calls_saved;
-            self.deterministic_savings.total_llm_calls_saved += saved;
-            self.deterministic_savings.total_runs += 1;
-            return Ok(self.finish_run(run_id, SopRunStatus::Completed, None));
+        if schema_error.is_none() {
+            run.llm_calls_saved += 1;
         }
 
-        let run = self.active_runs.get_mut(run_id).unwrap();
-        run.current_step = next_step_num;
-
-        let step_idx = (next_step_num - 1) as usize;
-        let step = sop.steps[step_idx].clone();
-        let run_id_owned = run_id.to_string();
-
-        self.resolve_deterministic_action(&sop, &run_id_owned, &step, step_output)
+        let status = if schema_error.is_some() {
+            SopStepStatus::Failed
+        } else {
+            SopStepStatus::Completed
+        };
+        let output = step_output.to_string();
+        let action = self.record_outcome(
+            &sop,
+            run_id,
+            step_number,
+            status,
+            output,
+            step_output,
+            schema_error,
+        );
+        self.journal(run_id);
+        action
     }
 
-    /// Resume a deterministic run from persisted state.
+    /// Resume a deterministic run from persisted state. The checkpoint the
+    /// run paused at counts as approved and the run continues after it.
     pub fn resume_deterministic_run(
         &mut self,
         state: DeterministicRunState,
@@ -450,29 +488,25 @@ impl SopEngine {
             .ok_or_else(|| anyhow::anyhow!("SOP '{}' no longer loaded", run.sop_name))?
             .clone();
 
+        let paused_step = state
+            .paused_step
+            .unwrap_or(state.last_completed_step.saturating_add(1));
+        let Some(checkpoint) = find_step(&sop, paused_step).cloned() else {
+            bail!("SOP '{}' has no step {paused_step}", sop.name);
+        };
+
+        let now = now_iso8601();
+        run.step_results.push(SopStepResult {
+            step_number: paused_step,
+            status: SopStepStatus::Completed,
+            output: "checkpoint approved".into(),
+            started_at: run.waiting_since.take().unwrap_or_else(|| now.clone()),
+            completed_at: Some(now),
+        });
         run.status = SopRunStatus::Running;
-        run.waiting_since = None;
         run.llm_calls_saved = state.llm_calls_saved;
 
-        // Resume from the step after the last completed one
-        let next_step_num = state.last_completed_step + 1;
-        if next_step_num > state.total_steps {
-            info!(
-                "Deterministic SOP run {} completed on resume ({} LLM calls saved)",
-                state.run_id, state.llm_calls_saved
-            );
-            self.deterministic_savings.total_llm_calls_saved += state.llm_calls_saved;
-            self.deterministic_savings.total_runs += 1;
-            return Ok(self.finish_run(&state.run_id, SopRunStatus::Completed, None));
-        }
-
-        let run = self.active_runs.get_mut(&state.run_id).unwrap();
-        run.current_step = next_step_num;
-
-        let step_idx = (next_step_num - 1) as usize;
-        let step = sop.steps[step_idx].clone();
-
-        // Use last step's output as input, or Null
+        // Use the output of the last step before the checkpoint as input
         let last_output = state
             .step_outputs
             .get(&state.last_completed_step)
@@ -480,7 +514,18 @@ impl SopEngine {
             .unwrap_or(serde_json::Value::Null);
 
         let run_id = state.run_id.clone();
-        self.resolve_deterministic_action(&sop, &run_id, &step, last_output)
+        let action = match successor(&sop, &checkpoint, None) {
+            (Some(next), _) => self.enter_step(&sop, &run_id, next, last_output, false),
+            (None, _) => {
+                info!(
+                    "Deterministic SOP run {} completed on resume ({} LLM calls saved)",
+                    run_id, state.llm_calls_saved
+                );
+                Ok(self.complete_run(&sop, &run_id))
+            }
+        };
+        self.journal(&run_id);
+        action
     }
 
     /// Resolve the action for a deterministic step (execute or checkpoint).
@@ -539,12 +584,13 @@ impl SopEngine {
         let state = DeterministicRunState {
             run_id: run_id.to_string(),
             sop_name: run.sop_name.clone(),
-            last_completed_step: run.current_step.saturating_sub(1),
+            last_completed_step: run.step_results.last().map_or(0, |r| r.step_number),
             total_steps: run.total_steps,
             step_outputs,
             persisted_at: now_iso8601(),
             llm_calls_saved: run.llm_calls_saved,
             paused_at_checkpoint: run.status == SopRunStatus::PausedCheckpoint,
+            paused_step: (run.status == SopRunStatus::PausedCheckpoint).then_some(run.current_step),
         };
 
         // Write to SOP location directory, or system temp dir
@@ -567,6 +613,435 @@ impl SopEngine {
         Ok(state)
     }
 
+    // ── Control flow ──────────────────────────────────────────────
+
+    /// Move a run to step `step_number`, fanning out its parallel group.
+    /// Unless `approved`, LLM-driven runs pause first when the step (or any
+    /// step of its group) needs approval.
+    fn enter_step(
+        &mut self,
+        sop: &Sop,
+        run_id: &str,
+        step_number: u32,
+        input: serde_json::Value,
+        approved: bool,
+    ) -> Result<SopRunAction> {
+        let Some(step) = find_step(sop, step_number).cloned() else {
+            let reason = format!("Step {step_number} does not exist");
+            record_sop_deviation(&sop.name, &reason);
+            return Ok(self.fail_run(sop, run_id, reason));
+        };
+        let group: Vec<SopStep> = parallel_group(sop, step_number)
+            .into_iter()
+            .cloned()
+            .collect();
+        let deterministic = sop.execution_mode == SopExecutionMode::Deterministic;
+
+        let run = self
+            .active_runs
+            .get_mut(run_id)
+            .ok_or_else(|| anyhow::anyhow!("Active run not found: {run_id}"))?;
+        run.current_step = step_number;
+
+        if deterministic && group.len() == 1 && step.kind == SopStepKind::Checkpoint {
+            return self.resolve_deterministic_action(sop, run_id, &step, input);
+        }
+
+        if !deterministic && !approved && group.iter().any(|s| step_needs_approval(sop, s)) {
+            run.status = SopRunStatus::WaitingApproval;
+            run.waiting_since = Some(now_iso8601());
+            let context = format_step_context(sop, run, &step);
+            return Ok(SopRunAction::WaitApproval {
+                run_id: run_id.to_string(),
+                step,
+                context,
+            });
+        }
+
+        let now = now_iso8601();
+        for member in &group {
+            run.flow.in_flight.push(InFlightStep {
+                step_number: member.number,
+                attempt: 1,
+                started_at: now.clone(),
+                input: input.clone(),
+                retry_pending: false,
+            });
+        }
+        let mut actions: Vec<SopRunAction> = group
+            .iter()
+            .map(|member| dispatch_action(sop, run, member, &input))
+            .collect();
+
+        if actions.len() == 1 {
+            return Ok(actions.remove(0));
+        }
+        run.flow.fan_outs += 1;
+        info!(
+            "SOP run {run_id}: fanning out {} parallel steps from step {step_number}",
+            actions.len()
+        );
+        Ok(SopRunAction::FanOut {
+            run_id: run_id.to_string(),
+            actions,
+        })
+    }
+
+    /// Record the outcome of an in-flight step and decide what comes next:
+    /// a retry, waiting on parallel siblings, the next (or branched-to)
+    /// step, compensation, or the end of the run.
+    #[allow(clippy::too_many_arguments)]
+    fn record_outcome(
+        &mut self,
+        sop: &Sop,
+        run_id: &str,
+        step_number: u32,
+        status: SopStepStatus,
+        output: String,
+        value: serde_json::Value,
+        error: Option<String>,
+    ) -> Result<SopRunAction> {
+        let step = find_step(sop, step_number)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("SOP '{}' has no step {step_number}", sop.name))?;
+        let run = self
+            .active_runs
+            .get_mut(run_id)
+            .ok_or_else(|| anyhow::anyhow!("Active run not found: {run_id}"))?;
+
+        let now = now_iso8601();
+        let in_flight = match run
+            .flow
+            .in_flight
+            .iter()
+            .position(|f| f.step_number == step_number)
+        {
+            Some(idx) => run.flow.in_flight.remove(idx),
+            // Steps reported without being dispatched (e.g. while waiting
+            // for approval) are accepted for the current step only.
+            None if run.flow.in_flight.is_empty() && step_number == run.current_step => {
+                InFlightStep {
+                    step_number,
+                    attempt: 1,
+                    started_at: now.clone(),
+                    input: serde_json::Value::Null,
+                    retry_pending: false,
+                }
+            }
+            None => bail!("Step {step_number} is not awaiting a result in run {run_id}"),
+        };
+        if run.status == SopRunStatus::WaitingApproval {
+            run.status = SopRunStatus::Running;
+            run.waiting_since = None;
+        }
+
+        // A result that arrives after the step's time limit counts as a timeout.
+        let (status, error) = match step.flow.timeout_secs {
+            Some(secs)
+                if status != SopStepStatus::Failed
+                    && cooldown_elapsed(&in_flight.started_at, secs) =>
+            {
+                run.flow.timeouts += 1;
+                (
+                    SopStepStatus::Failed,
+                    Some(format!("Step {step_number} timed out after {secs}s")),
+                )
+            }
+            _ => (status, error),
+        };
+
+        run.step_results.push(SopStepResult {
+            step_number,
+            status,
+            output: output.clone(),
+            started_at: in_flight.started_at.clone(),
+            completed_at: Some(now),
+        });
+
+        if status == SopStepStatus::Failed {
+            let reason = error.unwrap_or_else(|| format!("Step {step_number} failed: {output}"));
+            if let Some(retry) = step
+                .flow
+                .retry
+                .filter(|retry| in_flight.attempt <= retry.max_retries)
+            {
+                let delay_secs = retry.backoff_for(in_flight.attempt);
+                let attempt = in_flight.attempt + 1;
+                warn!("SOP run {run_id}: {reason}; retry {attempt} in {delay_secs}s");
+                run.flow.retries += 1;
+                run.flow.in_flight.push(InFlightStep {
+                    step_number,
+                    attempt,
+                    started_at: iso8601_in(delay_secs),
+                    input: in_flight.input.clone(),
+                    retry_pending: true,
+                });
+                let action = dispatch_action(sop, run, &step, &in_flight.input);
+                return Ok(SopRunAction::Retry {
+                    run_id: run_id.to_string(),
+                    attempt,
+                    delay_secs,
+                    action: Box::new(action),
+                });
+            }
+
+            warn!("SOP run {run_id}: {reason}");
+            record_sop_deviation(&sop.name, &reason);
+            if run.status == SopRunStatus::Compensating {
+                let original = run.flow.failure_reason.clone().unwrap_or_default();
+                let reason = format!("{original}; compensation failed: {reason}");
+                return Ok(self.finish_run(run_id, SopRunStatus::Failed, Some(reason)));
+            }
+            return Ok(self.fail_run(sop, run_id, reason));
+        }
+
+        if run.status == SopRunStatus::Compensating {
+            run.flow.compensations_run += 1;
+            return Ok(self.next_compensation(sop, run_id));
+        }
+
+        if status == SopStepStatus::Skipped {
+            record_sop_deviation(&sop.name, &format!("step {step_number} skipped"));
+        } else if step.flow.compensate_with.is_some() {
+            run.flow.compensable.push(step_number);
+        }
+
+        if !run.flow.in_flight.is_empty() {
+            return Ok(SopRunAction::AwaitParallel {
+                run_id: run_id.to_string(),
+                pending: run.flow.in_flight.iter().map(|f| f.step_number).collect(),
+            });
+        }
+
+        let branch_output = (status == SopStepStatus::Completed).then_some(output.as_str());
+        let (next, branched) = successor(sop, &step, branch_output);
+        if branched {
+            run.flow.branches_taken += 1;
+        }
+        if let Some(target) = next {
+            let anchor = parallel_group(sop, step_number)
+                .last()
+                .map_or(step_number, |s| s.number);
+            run.flow
+                .bypassed
+                .extend(steps_between(sop, natural_successor(sop, anchor), target));
+        }
+
+        match next {
+            None => {
+                info!("SOP run {run_id} completed successfully");
+                Ok(self.complete_run(sop, run_id))
+            }
+            Some(_) if run.step_results.len() >= MAX_STEP_RESULTS => {
+                let reason = format!("Run exceeded {MAX_STEP_RESULTS} step executions");
+                record_sop_deviation(&sop.name, &reason);
+                Ok(self.fail_run(sop, run_id, reason))
+            }
+            Some(target) => self.enter_step(sop, run_id, target, value, false),
+        }
+    }
+
+    /// Fail a run, first running the compensation steps of completed steps
+    /// (most recent first) when any are declared.
+    fn fail_run(&mut self, sop: &Sop, run_id: &str, reason: String) -> SopRunAction {
+        let Some(run) = self.active_runs.get_mut(run_id) else {
+            return SopRunAction::Failed {
+                run_id: run_id.to_string(),
+                sop_name: sop.name.clone(),
+                reason,
+            };
+        };
+        run.flow.in_flight.clear();
+        run.flow.compensation_queue = run
+            .flow
+            .compensable
+            .iter()
+            .rev()
+            .filter_map(|n| find_step(sop, *n).and_then(|s| s.flow.compensate_with))
+            .collect();
+
+        if run.flow.compensation_queue.is_empty() {
+            return self.finish_run(run_id, SopRunStatus::Failed, Some(reason));
+        }
+        info!(
+            "SOP run {run_id}: running {} compensation step(s)",
+            run.flow.compensation_queue.len()
+        );
+        run.status = SopRunStatus::Compensating;
+        run.flow.failure_reason = Some(reason);
+        self.next_compensation(sop, run_id)
+    }
+
+    /// Dispatch the next queued compensation step, or end the run as failed.
+    fn next_compensation(&mut self, sop: &Sop, run_id: &str) -> SopRunAction {
+        let run = self.active_runs.get_mut(run_id).unwrap();
+        let reason = run.flow.failure_reason.clone().unwrap_or_default();
+        if run.flow.compensation_queue.is_empty() {
+            return self.finish_run(
+                run_id,
+                SopRunStatus::Failed,
+                Some(format!("{reason} (compensated)")),
+            );
+        }
+        let number = run.flow.compensation_queue.remove(0);
+        let Some(step) = find_step(sop, number).cloned() else {
+            let reason = format!("{reason}; compensation step {number} does not exist");
+            return self.finish_run(run_id, SopRunStatus::Failed, Some(reason));
+        };
+        run.current_step = number;
+        run.flow.in_flight.push(InFlightStep {
+            step_number: number,
+            attempt: 1,
+            started_at: now_iso8601(),
+            input: serde_json::Value::Null,
+            retry_pending: false,
+        });
+        dispatch_action(sop, run, &step, &serde_json::Value::Null)
+    }
+
+    /// Finish a run successfully, crediting deterministic savings.
+    fn complete_run(&mut self, sop: &Sop, run_id: &str) -> SopRunAction {
+        if sop.execution_mode == SopExecutionMode::Deterministic {
+            let saved = self
+                .active_runs
+                .get(run_id)
+                .map_or(0, |r| r.llm_calls_saved);
+            self.deterministic_savings.total_llm_calls_saved += saved;
+            self.deterministic_savings.total_runs += 1;
+        }
+        self.finish_run(run_id, SopRunStatus::Completed, None)
+    }
+
+    // ── Step timeouts ─────────────────────────────────────────────
+
+    /// Fail every in-flight step that has exceeded its `timeout_secs`,
+    /// retrying or compensating per the step's settings. Returns the
+    /// resulting actions.
+    pub fn check_step_timeouts(&mut self) -> Vec<SopRunAction> {
+        let expired: Vec<(String, u32, u64)> = self
+            .active_runs
+            .values()
+            .filter(|r| matches!(r.status, SopRunStatus::Running | SopRunStatus::Compensating))
+            .flat_map(|run| {
+                let sop = self.sops.iter().find(|s| s.name == run.sop_name);
+                run.flow.in_flight.iter().filter_map(move |f| {
+                    let secs = find_step(sop?, f.step_number)?.flow.timeout_secs?;
+                    cooldown_elapsed(&f.started_at, secs)
+                        .then(|| (run.run_id.clone(), f.step_number, secs))
+                })
+            })
+            .collect();
+
+        let mut actions = Vec::new();
+        for (run_id, step_number, secs) in expired {
+            // An earlier timeout in the same run may already have failed it
+            let Some(run) = self.active_runs.get_mut(&run_id) else {
+                continue;
+            };
+            if !run
+                .flow
+                .in_flight
+                .iter()
+                .any(|f| f.step_number == step_number)
+            {
+                continue;
+            }
+            run.flow.timeouts += 1;
+            let Some(sop) = self.sops.iter().find(|s| s.name == run.sop_name).cloned() else {
+                continue;
+            };
+            info!("SOP run {run_id}: step {step_number} timed out after {secs}s");
+            match self.record_outcome(
+                &sop,
+                &run_id,
+                step_number,
+                SopStepStatus::Failed,
+                "timed out".into(),
+                serde_json::Value::Null,
+                Some(format!("Step {step_number} timed out after {secs}s")),
+            ) {
+                Ok(action) => actions.push(action),
+                Err(e) => warn!("SOP run {run_id}: timeout handling failed: {e}"),
+            }
+            self.journal(&run_id);
+        }
+        actions
+    }
+
+    /// Re-dispatch deterministic retries whose backoff has elapsed. The
+    /// `Retry` action only announces the delay; this hands the step back out
+    /// once it is due. Agent-driven retries wait for
+    /// [`Self::take_due_agent_retries`], since only the owning agent loop can
+    /// execute them.
+    pub fn check_due_retries(&mut self) -> Vec<SopRunAction> {
+        self.redispatch_due_retries(true)
+    }
+
+    /// Hand out agent-driven retries whose backoff has elapsed, as
+    /// `ExecuteStep` actions for the agent that asked (`sop_status`).
+    pub fn take_due_agent_retries(&mut self) -> Vec<SopRunAction> {
+        self.redispatch_due_retries(false)
+    }
+
+    fn redispatch_due_retries(&mut self, deterministic: bool) -> Vec<SopRunAction> {
+        let mut actions = Vec::new();
+        let mut touched = Vec::new();
+        for run in self.active_runs.values_mut() {
+            let Some(sop) = self.sops.iter().find(|s| {
+                s.name == run.sop_name
+                    && (s.execution_mode == SopExecutionMode::Deterministic) == deterministic
+            }) else {
+                continue;
+            };
+            let due: Vec<(u32, serde_json::Value)> = run
+                .flow
+                .in_flight
+                .iter_mut()
+                .filter(|f| f.retry_pending && cooldown_elapsed(&f.started_at, 0))
+                .map(|f| {
+                    f.retry_pending = false;
+                    (f.step_number, f.input.clone())
+                })
+                .collect();
+            if due.is_empty() {
+                continue;
+            }
+            for (step_number, input) in due {
+                if let Some(step) = find_step(sop, step_number) {
+                    info!("SOP run {}: retry of step {step_number} is due", run.run_id);
+                    actions.push(dispatch_action(sop, run, step, &input));
+                }
+            }
+            touched.push(run.run_id.clone());
+        }
+        for run_id in touched {
+            self.journal(&run_id);
+        }
+        actions
+    }
+
+    /// Periodic housekeeping: step timeouts, due deterministic retries and
+    /// approval timeouts, in that order. Returns every resulting action.
+    pub fn tick(&mut self) -> Vec<SopRunAction> {
+        let mut actions = self.check_step_timeouts();
+        actions.extend(self.check_due_retries());
+        actions.extend(self.check_approval_timeouts());
+        actions
+    }
+
+    /// Fail an active run without compensation, e.g. when the caller cannot
+    /// execute the step it was handed.
+    pub fn abort_run(&mut self, run_id: &str, reason: String) -> Result<SopRunAction> {
+        let Some(run) = self.active_runs.get(run_id) else {
+            bail!("Active run not found: {run_id}");
+        };
+        record_sop_deviation(&run.sop_name, &reason);
+        warn!("SOP run {run_id} aborted: {reason}");
+        let action = self.finish_run(run_id, SopRunStatus::Failed, Some(reason));
+        self.journal(run_id);
+        Ok(action)
+    }
+
     // ── Approval timeout ──────────────────────────────────────────
 
     /// Check all WaitingApproval runs for timeout. For Critical/High-priority SOPs,
@@ -630,6 +1105,49 @@ impl SopEngine {
 
     // ── Internal helpers ────────────────────────────────────────
 
+    /// Reject an event whose idempotency key already started a run of this SOP.
+    fn check_duplicate(&self, sop_name: &str, event: &SopEvent) -> Result<()> {
+        let Some(key) = event.dedup_key() else {
+            return Ok(());
+        };
+        let in_memory = self
+            .active_runs
+            .values()
+            .chain(&self.finished_runs)
+            .find(|r| r.sop_name == sop_name && r.trigger_event.dedup_key().as_ref() == Some(&key))
+            .map(|r| r.run_id.clone());
+        let existing = match (in_memory, &self.journal) {
+            (Some(run_id), _) => Some(run_id),
+            (None, Some(journal)) => journal
+                .find_by_idempotency_key(sop_name, &key)
+                .unwrap_or_else(|e| {
+                    warn!("SOP run journal lookup failed: {e}");
+                    None
+                }),
+            (None, None) => None,
+        };
+        if let Some(run_id) = existing {
+            bail!("Duplicate trigger for SOP '{sop_name}': idempotency key '{key}' already started run {run_id}");
+        }
+        Ok(())
+    }
+
+    /// Queue the current state of a run for the journal, if enabled. The
+    /// write happens off the caller's thread; failures are logged and the
+    /// in-memory run stays authoritative.
+    fn journal(&self, run_id: &str) {
+        let Some(ref journal) = self.journal else {
+            return;
+        };
+        let run = self
+            .active_runs
+            .get(run_id)
+            .or_else(|| self.finished_runs.iter().rev().find(|r| r.run_id == run_id));
+        if let Some(run) = run {
+            journal.submit(run.clone(), self.config.max_finished_runs);
+        }
+    }
+
     fn last_finished_run(&self, sop_name: &str) -> Option<&SopRun> {
         self.finished_runs
             .iter()
@@ -646,6 +1164,15 @@ impl SopEngine {
         let mut run = self.active_runs.remove(run_id).unwrap();
         run.status = status;
         run.completed_at = Some(now_iso8601());
+        run.flow.in_flight.clear();
+        // Unused compensation steps were never on this run's path
+        if let Some(sop) = self.sops.iter().find(|s| s.name == run.sop_name) {
+            let unused = sop.steps.iter().filter(|s| {
+                s.kind == SopStepKind::Compensation
+                    && !run.step_results.iter().any(|r| r.step_number == s.number)
+            });
+            run.flow.bypassed.extend(unused.map(|s| s.number));
+        }
         let sop_name = run.sop_name.clone();
         let run_id_owned = run.run_id.clone();
         self.finished_runs.push(run);
@@ -759,18 +1286,14 @@ fn mqtt_topic_matches(pattern: &str, topic: &str) -> bool {
 
 // ── Execution mode resolution ───────────────────────────────────
 
-/// Determine the action for a step based on SOP execution mode.
-fn resolve_step_action(sop: &Sop, step: &SopStep, run_id: String, context: String) -> SopRunAction {
+/// Whether a step must wait for approval under the SOP's execution mode.
+fn step_needs_approval(sop: &Sop, step: &SopStep) -> bool {
     // Steps with requires_confirmation always need approval
     if step.requires_confirmation {
-        return SopRunAction::WaitApproval {
-            run_id,
-            step: step.clone(),
-            context,
-        };
+        return true;
     }
 
-    let needs_approval = match sop.execution_mode {
+    match sop.execution_mode {
         // Deterministic mode is handled via start_deterministic_run;
         // if we reach here via the standard path, treat as Auto.
         SopExecutionMode::Auto | SopExecutionMode::Deterministic => false,
@@ -786,23 +1309,122 @@ fn resolve_step_action(sop: &Sop, step: &SopStep, run_id: String, context: Strin
                 step.number == 1
             }
         },
-    };
+    }
+}
 
-    if needs_approval {
-        SopRunAction::WaitApproval {
-            run_id,
+/// Build the action that hands a dispatched step to its executor: the
+/// agent for LLM-driven runs, the deterministic runner otherwise.
+fn dispatch_action(
+    sop: &Sop,
+    run: &SopRun,
+    step: &SopStep,
+    input: &serde_json::Value,
+) -> SopRunAction {
+    if sop.execution_mode == SopExecutionMode::Deterministic {
+        SopRunAction::DeterministicStep {
+            run_id: run.run_id.clone(),
             step: step.clone(),
-            context,
+            input: input.clone(),
         }
     } else {
         SopRunAction::ExecuteStep {
-            run_id,
+            run_id: run.run_id.clone(),
             step: step.clone(),
-            context,
+            context: format_step_context(sop, run, step),
         }
     }
 }
 
+// ── Step sequencing ─────────────────────────────────────────────
+
+fn find_step(sop: &Sop, number: u32) -> Option<&SopStep> {
+    sop.steps.iter().find(|s| s.number == number)
+}
+
+/// First step on the normal path (compensation steps are skipped).
+fn first_step_number(sop: &Sop) -> Option<u32> {
+    sop.steps
+        .iter()
+        .find(|s| s.kind != SopStepKind::Compensation)
+        .map(|s| s.number)
+}
+
+/// The consecutive steps sharing `number`'s parallel group, in order.
+/// A step outside any group forms a group of one.
+pub(crate) fn parallel_group(sop: &Sop, number: u32) -> Vec<&SopStep> {
+    let Some(idx) = sop.steps.iter().position(|s| s.number == number) else {
+        return Vec::new();
+    };
+    let Some(group) = sop.steps[idx].flow.parallel.as_deref() else {
+        return vec![&sop.steps[idx]];
+    };
+    let in_group = |s: &&SopStep| s.flow.parallel.as_deref() == Some(group);
+    let start = sop.steps[..idx]
+        .iter()
+        .rposition(|s| !in_group(&s))
+        .map_or(0, |i| i + 1);
+    sop.steps[start..].iter().take_while(in_group).collect()
+}
+
+/// Next step on the normal path after `number` (compensation steps are skipped).
+fn natural_successor(sop: &Sop, number: u32) -> Option<u32> {
+    let idx = sop.steps.iter().position(|s| s.number == number)?;
+    sop.steps[idx + 1..]
+        .iter()
+        .find(|s| s.kind != SopStepKind::Compensation)
+        .map(|s| s.number)
+}
+
+/// Where a run continues after `step`: the first branch whose condition
+/// matches `output`, then the step's `next`, then the following step.
+/// Parallel groups continue after their last step and do not branch.
+/// Returns the target (`None` = end of run) and whether a branch was taken.
+fn successor(sop: &Sop, step: &SopStep, output: Option<&str>) -> (Option<u32>, bool) {
+    let group = parallel_group(sop, step.number);
+    let anchor = group.last().copied().unwrap_or(step);
+    if group.len() <= 1 {
+        if let Some(output) = output {
+            let payload = condition_payload(output);
+            if let Some(branch) = step
+                .flow
+                .branches
+                .iter()
+                .find(|b| evaluate_condition(&b.when, Some(&payload)))
+            {
+                return (Some(branch.goto), true);
+            }
+        }
+    }
+    match anchor.flow.next {
+        Some(next) => (Some(next), false),
+        None => (natural_successor(sop, anchor.number), false),
+    }
+}
+
+/// Normal-path steps skipped by jumping from `natural` forward to `target`.
+fn steps_between(sop: &Sop, natural: Option<u32>, target: u32) -> Vec<u32> {
+    let Some(natural) = natural else {
+        return Vec::new();
+    };
+    let pos = |n: u32| sop.steps.iter().position(|s| s.number == n);
+    let (Some(from), Some(to)) = (pos(natural), pos(target)) else {
+        return Vec::new();
+    };
+    sop.steps
+        .get(from..to)
+        .unwrap_or_default()
+        .iter()
+        .filter(|s| s.kind != SopStepKind::Compensation)
+        .map(|s| s.number)
+        .collect()
+}
+
+/// Step output as a JSON payload for branch conditions. Agent reports may
+/// wrap JSON in prose or code fences; plain text is passed through.
+fn condition_payload(output: &str) -> String {
+    structured::parse_json_response(output).map_or_else(|_| output.to_string(), |v| v.to_string())
+}
+
 // ── Step context formatting ─────────────────────────────────────
 
 /// Build the structured context message that gets injected into the agent.
@@ -823,6 +1445,31 @@ fn format_step_context(sop: &Sop, run: &SopRun, step: &SopStep) -> String {
         let _ = writeln!(ctx, "Payload: {payload}");
     }
 
+    if run.status == SopRunStatus::Compensating {
+        let _ = writeln!(
+            ctx,
+            "Compensating: the run failed ({}). Undo the work of the completed step.",
+            run.flow
+                .failure_reason
+                .as_deref()
+                .unwrap_or("unknown reason")
+        );
+    }
+
+    if let Some(attempt) = run
+        .flow
+        .in_flight
+        .iter()
+        .find(|f| f.step_number == step.number && f.attempt > 1)
+    {
+        let max = step.flow.retry.map_or(0, |r| r.max_retries) + 1;
+        let _ = writeln!(
+            ctx,
+            "Attempt: {} of {max} (the previous attempt failed)",
+            attempt.attempt
+        );
+    }
+
     // Previous step summary
     if let Some(prev) = run.step_results.last() {
         let _ = writeln!(
@@ -842,20 +1489,73 @@ fn format_step_context(sop: &Sop, run: &SopRun, step: &SopStep) -> String {
         );
     }
 
+    if let Some(schema) = step.schema.as_ref().and_then(|s| s.output.as_ref()) {
+        let _ = write!(
+            ctx,
+            "\nReport the step output as a single JSON value matching this schema:\n{schema}\n"
+        );
+    }
+
+    let siblings: Vec<String> = parallel_group(sop, step.number)
+        .iter()
+        .filter(|s| s.number != step.number)
+        .map(|s| s.number.to_string())
+        .collect();
+    if !siblings.is_empty() {
+        let _ = write!(
+            ctx,
+            "\nRuns in parallel with step(s) {}. Report this step's result with step {}.\n",
+            siblings.join(", "),
+            step.number
+        );
+    }
+
+    if !step.flow.branches.is_empty() {
+        ctx.push_str("\nThe next step depends on this step's JSON output:\n");
+        for branch in &step.flow.branches {
+            let _ = writeln!(ctx, "- `{}` → step {}", branch.when, branch.goto);
+        }
+    }
+
+    if let Some(secs) = step.flow.timeout_secs {
+        let _ = writeln!(ctx, "\nTime limit: {secs}s");
+    }
+
     ctx.push_str("\nWhen done, report your result.\n");
 
     ctx
 }
 
+/// Output schema declared for step `step_number` (1-based), if any.
+fn step_output_schema(sop: &Sop, step_number: u32) -> Option<&serde_json::Value> {
+    sop.steps
+        .get(step_number.checked_sub(1)? as usize)?
+        .schema
+        .as_ref()?
+        .output
+        .as_ref()
+}
+
+/// Parse reported step output as JSON and validate it against `schema`.
+fn validate_step_output(output: &str, schema: &serde_json::Value) -> Result<(), String> {
+    let value = structured::parse_json_response(output)?;
+    structured::validate_against_schema(&value, schema)
+}
+
 // ── Utilities ───────────────────────────────────────────────────
 
 pub(crate) fn now_iso8601() -> String {
+    iso8601_in(0)
+}
+
+/// ISO-8601 timestamp `delay_secs` from now.
+fn iso8601_in(delay_secs: u64) -> String {
     // Use chrono if available, otherwise fallback to SystemTime
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap_or_default();
     // Simple UTC timestamp without chrono dependency
-    let secs = now.as_secs();
+    let secs = now.as_secs().saturating_add(delay_secs);
     let days = secs / 86400;
     let time_secs = secs % 86400;
     let hours = time_secs / 3600;
@@ -926,10 +1626,17 @@ fn parse_iso8601_secs(input: &str) -> Option<u64> {
     Some(days * 86400 + hour * 3600 + min * 60 + sec)
 }
 
+/// Feed an SOP deviation into the trust tracker, when one is enabled.
+fn record_sop_deviation(sop_name: &str, description: &str) {
+    if let Some(trust) = crate::trust::runtime::global() {
+        trust.record_sop_deviation(sop_name, description);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sop::types::SopExecutionMode;
+    use crate::sop::types::{SopExecutionMode, StepBranch, StepFlow, StepRetry};
 
     fn manual_event() -> SopEvent {
         SopEvent {
@@ -937,6 +1644,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         }
     }
 
@@ -946,6 +1654,7 @@ mod tests {
             topic: Some(topic.into()),
             payload: Some(payload.into()),
             timestamp: now_iso8601(),
+            idempotency_key: None,
         }
     }
 
@@ -966,6 +1675,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::default(),
                     schema: None,
+                    flow: StepFlow::default(),
                 },
                 SopStep {
                     number: 2,
@@ -975,6 +1685,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::default(),
                     schema: None,
+                    flow: StepFlow::default(),
                 },
             ],
             cooldown_secs: 0,
@@ -998,7 +1709,10 @@ mod tests {
             | SopRunAction::DeterministicStep { run_id, .. }
             | SopRunAction::CheckpointWait { run_id, .. }
             | SopRunAction::Completed { run_id, .. }
-            | SopRunAction::Failed { run_id, .. } => run_id,
+            | SopRunAction::Failed { run_id, .. }
+            | SopRunAction::FanOut { run_id, .. }
+            | SopRunAction::Retry { run_id, .. }
+            | SopRunAction::AwaitParallel { run_id, .. } => run_id,
         }
     }
 
@@ -1132,6 +1846,7 @@ mod tests {
             topic: Some("/webhook".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert_eq!(engine.match_trigger(&event).len(), 1);
     }
@@ -1152,6 +1867,7 @@ mod tests {
             topic: Some("/webhook".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert!(engine.match_trigger(&event).is_empty());
 
@@ -1161,6 +1877,7 @@ mod tests {
             topic: Some("/sop/deploy".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert_eq!(engine.match_trigger(&event).len(), 1);
     }
@@ -1183,6 +1900,7 @@ mod tests {
             topic: Some("0 */5 * * *".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert_eq!(engine.match_trigger(&event).len(), 1);
 
@@ -1192,6 +1910,7 @@ mod tests {
             topic: Some("0 */10 * * *".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert!(engine.match_trigger(&event).is_empty());
 
@@ -1201,6 +1920,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert!(engine.match_trigger(&event).is_empty());
     }
@@ -1259,6 +1979,7 @@ mod tests {
             topic: Some("sensors/temp".into()),
             payload: None,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert!(engine.match_trigger(&event).is_empty());
     }
@@ -1281,6 +2002,7 @@ mod tests {
             topic: Some("nucleo/pin_3".into()),
             payload: Some("1".into()),
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert_eq!(engine.match_trigger(&event).len(), 1);
 
@@ -1290,6 +2012,7 @@ mod tests {
             topic: Some("nucleo/pin_3".into()),
             payload: Some("0".into()),
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert!(engine.match_trigger(&event).is_empty());
     }
@@ -1311,6 +2034,7 @@ mod tests {
             topic: Some("rpi/gpio_5".into()),
             payload: Some("0".into()),
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
         assert_eq!(engine.match_trigger(&event).len(), 1);
     }
@@ -1383,6 +2107,47 @@ mod tests {
         assert_eq!(engine.finished_runs(None).len(), 1);
     }
 
+    #[test]
+    fn advance_step_rejects_output_violating_schema() {
+        let mut sop = test_sop("s1", SopExecutionMode::Auto, SopPriority::Normal);
+        sop.steps[0].schema = Some(crate::sop::types::StepSchema {
+            input: None,
+            output: Some(serde_json::json!({
+                "type": "object",
+                "properties": { "count": { "type": "integer" } },
+                "required": ["count"]
+            })),
+        });
+        let mut engine = engine_with_sops(vec![sop]);
+        let action = engine.start_run("s1", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+        match &action {
+            SopRunAction::ExecuteStep { context, .. } => {
+                assert!(context.contains("matching this schema"));
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+
+        let result = |output: &str| SopStepResult {
+            step_number: 1,
+            status: SopStepStatus::Completed,
+            output: output.into(),
+            started_at: now_iso8601(),
+            completed_at: Some(now_iso8601()),
+        };
+
+        let err = engine
+            .advance_step(&run_id, result(r#"{"count": "three"}"#))
+            .unwrap_err();
+        assert!(err.to_string().contains("does not match its output schema"));
+        assert!(engine.get_run(&run_id).unwrap().step_results.is_empty());
+
+        let action = engine
+            .advance_step(&run_id, result(r#"{"count": 3}"#))
+            .unwrap();
+        assert!(matches!(action, SopRunAction::ExecuteStep { .. }));
+    }
+
     #[test]
     fn step_failure_ends_run() {
         let mut engine = engine_with_sops(vec![test_sop(
@@ -1651,6 +2416,7 @@ mod tests {
             step_results: Vec::new(),
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
         let ctx = format_step_context(&sop, &run, &sop.steps[0]);
         assert!(ctx.contains("pump-shutdown"));
@@ -1940,6 +2706,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::Execute,
                     schema: None,
+                    flow: StepFlow::default(),
                 },
                 SopStep {
                     number: 2,
@@ -1949,6 +2716,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::Checkpoint,
                     schema: None,
+                    flow: StepFlow::default(),
                 },
                 SopStep {
                     number: 3,
@@ -1958,6 +2726,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::Execute,
                     schema: None,
+                    flow: StepFlow::default(),
                 },
             ],
             cooldown_secs: 0,
@@ -2039,6 +2808,7 @@ mod tests {
                 requires_confirmation: false,
                 kind: SopStepKind::Execute,
                 schema: None,
+                flow: StepFlow::default(),
             },
             SopStep {
                 number: 2,
@@ -2048,6 +2818,7 @@ mod tests {
                 requires_confirmation: false,
                 kind: SopStepKind::Execute,
                 schema: None,
+                flow: StepFlow::default(),
             },
         ];
         let mut engine = engine_with_sops(vec![sop]);
@@ -2073,6 +2844,28 @@ mod tests {
         assert_eq!(savings.total_llm_calls_saved, 2);
     }
 
+    #[test]
+    fn deterministic_output_violating_schema_fails_run() {
+        let mut sop = deterministic_sop("det-sop");
+        sop.steps[0].schema = Some(crate::sop::types::StepSchema {
+            input: None,
+            output: Some(serde_json::json!({ "type": "array" })),
+        });
+        let mut engine = engine_with_sops(vec![sop]);
+        let action = engine.start_run("det-sop", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+
+        let action = engine
+            .advance_deterministic_step(&run_id, serde_json::json!({"not": "an array"}))
+            .unwrap();
+        match action {
+            SopRunAction::Failed { reason, .. } => {
+                assert!(reason.contains("does not match its output schema"));
+            }
+            other => panic!("unexpected action: {other:?}"),
+        }
+    }
+
     #[test]
     fn deterministic_non_deterministic_sop_rejected() {
         let mut engine = engine_with_sops(vec![test_sop(
@@ -2087,4 +2880,454 @@ mod tests {
             .to_string()
             .contains("not in deterministic mode"));
     }
+
+    // ── Control flow ────────────────────────────────────
+
+    fn flow_step(number: u32, flow: StepFlow) -> SopStep {
+        SopStep {
+            number,
+            title: format!("Step {number}"),
+            body: String::new(),
+            suggested_tools: vec![],
+            requires_confirmation: false,
+            kind: SopStepKind::Execute,
+            schema: None,
+            flow,
+        }
+    }
+
+    fn flow_sop(mode: SopExecutionMode, steps: Vec<SopStep>) -> Sop {
+        let mut sop = test_sop("flow", mode, SopPriority::Normal);
+        sop.steps = steps;
+        sop
+    }
+
+    fn report(
+        engine: &mut SopEngine,
+        run_id: &str,
+        step: u32,
+        status: SopStepStatus,
+        output: &str,
+    ) -> SopRunAction {
+        engine
+            .advance_step(
+                run_id,
+                SopStepResult {
+                    step_number: step,
+                    status,
+                    output: output.into(),
+                    started_at: now_iso8601(),
+                    completed_at: Some(now_iso8601()),
+                },
+            )
+            .unwrap()
+    }
+
+    fn step_number_of(action: &SopRunAction) -> Option<u32> {
+        match action {
+            SopRunAction::ExecuteStep { step, .. }
+            | SopRunAction::WaitApproval { step, .. }
+            | SopRunAction::DeterministicStep { step, .. }
+            | SopRunAction::CheckpointWait { step, .. } => Some(step.number),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn branch_on_output_jumps_and_bypasses_steps() {
+        let branching = StepFlow {
+            branches: vec![StepBranch {
+                when: r#"$.severity == "critical""#.into(),
+                goto: 3,
+            }],
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(
+            SopExecutionMode::Auto,
+            vec![
+                flow_step(1, branching),
+                flow_step(2, StepFlow::default()),
+                flow_step(3, StepFlow::default()),
+            ],
+        );
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+
+        let action = report(
+            &mut engine,
+            &run_id,
+            1,
+            SopStepStatus::Completed,
+            "Triage done: ```json\n{\"severity\": \"critical\"}\n```",
+        );
+        assert_eq!(step_number_of(&action), Some(3));
+
+        let action = report(&mut engine, &run_id, 3, SopStepStatus::Completed, "done");
+        assert!(matches!(action, SopRunAction::Completed { .. }));
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.flow.branches_taken, 1);
+        assert_eq!(run.flow.bypassed, vec![2]);
+    }
+
+    #[test]
+    fn unmatched_branch_falls_through_to_next() {
+        let flow = StepFlow {
+            branches: vec![StepBranch {
+                when: "$.value > 10".into(),
+                goto: 3,
+            }],
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(
+            SopExecutionMode::Auto,
+            vec![
+                flow_step(1, flow),
+                flow_step(2, StepFlow::default()),
+                flow_step(3, StepFlow::default()),
+            ],
+        );
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+
+        let action = report(
+            &mut engine,
+            &run_id,
+            1,
+            SopStepStatus::Completed,
+            r#"{"value": 3}"#,
+        );
+        assert_eq!(step_number_of(&action), Some(2));
+    }
+
+    #[test]
+    fn parallel_group_fans_out_and_joins() {
+        let diag = || StepFlow {
+            parallel: Some("diag".into()),
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(
+            SopExecutionMode::Auto,
+            vec![
+                flow_step(1, StepFlow::default()),
+                flow_step(2, diag()),
+                flow_step(3, diag()),
+                flow_step(4, StepFlow::default()),
+            ],
+        );
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+
+        let action = report(&mut engine, &run_id, 1, SopStepStatus::Completed, "ok");
+        match &action {
+            SopRunAction::FanOut { actions, .. } => {
+                let numbers: Vec<_> = actions.iter().filter_map(step_number_of).collect();
+                assert_eq!(numbers, vec![2, 3]);
+                assert!(action
+                    .step_context()
+                    .contains("Runs in parallel with step(s) 3"));
+            }
+            other => panic!("expected fan-out, got {other:?}"),
+        }
+
+        let action = report(&mut engine, &run_id, 3, SopStepStatus::Completed, "ok");
+        assert!(
+            matches!(action, SopRunAction::AwaitParallel { ref pending, .. } if pending == &[2])
+        );
+
+        let action = report(&mut engine, &run_id, 2, SopStepStatus::Completed, "ok");
+        assert_eq!(step_number_of(&action), Some(4));
+        assert_eq!(engine.get_run(&run_id).unwrap().flow.fan_outs, 1);
+    }
+
+    #[test]
+    fn failed_step_retries_with_exponential_backoff() {
+        let flow = StepFlow {
+            retry: Some(StepRetry {
+                max_retries: 2,
+                backoff_secs: 5,
+            }),
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(
+            SopExecutionMode::Auto,
+            vec![flow_step(1, flow), flow_step(2, StepFlow::default())],
+        );
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+
+        let action = report(&mut engine, &run_id, 1, SopStepStatus::Failed, "503");
+        assert!(matches!(
+            action,
+            SopRunAction::Retry {
+                attempt: 2,
+                delay_secs: 5,
+                ..
+            }
+        ));
+        let action = report(&mut engine, &run_id, 1, SopStepStatus::Failed, "503");
+        match action {
+            SopRunAction::Retry {
+                attempt: 3,
+                delay_secs: 10,
+                action,
+                ..
+            } => assert!(action.step_context().contains("Attempt: 3 of 3")),
+            other => panic!("expected retry, got {other:?}"),
+        }
+
+        let action = report(&mut engine, &run_id, 1, SopStepStatus::Failed, "503");
+        assert!(
+            matches!(action, SopRunAction::Failed { ref reason, .. } if reason.contains("503"))
+        );
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.flow.retries, 2);
+        assert_eq!(run.step_results.len(), 3);
+    }
+
+    #[test]
+    fn failure_runs_compensation_in_reverse_order() {
+        let compensated_by = |n| StepFlow {
+            compensate_with: Some(n),
+            ..StepFlow::default()
+        };
+        let mut undo_two = flow_step(4, StepFlow::default());
+        undo_two.kind = SopStepKind::Compensation;
+        let mut undo_one = flow_step(5, StepFlow::default());
+        undo_one.kind = SopStepKind::Compensation;
+        let sop = flow_sop(
+            SopExecutionMode::Auto,
+            vec![
+                flow_step(1, compensated_by(5)),
+                flow_step(2, compensated_by(4)),
+                flow_step(3, StepFlow::default()),
+                undo_two,
+                undo_one,
+            ],
+        );
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+
+        report(&mut engine, &run_id, 1, SopStepStatus::Completed, "ok");
+        let action = report(&mut engine, &run_id, 2, SopStepStatus::Completed, "ok");
+        // Compensation steps are not on the normal path
+        assert_eq!(step_number_of(&action), Some(3));
+
+        let action = report(&mut engine, &run_id, 3, SopStepStatus::Failed, "boom");
+        assert_eq!(step_number_of(&action), Some(4));
+        assert!(action.step_context().contains("Compensating"));
+        assert_eq!(
+            engine.get_run(&run_id).unwrap().status,
+            SopRunStatus::Compensating
+        );
+
+        let action = report(&mut engine, &run_id, 4, SopStepStatus::Completed, "undone");
+        assert_eq!(step_number_of(&action), Some(5));
+        let action = report(&mut engine, &run_id, 5, SopStepStatus::Completed, "undone");
+        match action {
+            SopRunAction::Failed { reason, .. } => {
+                assert!(reason.contains("boom"));
+                assert!(reason.contains("(compensated)"));
+            }
+            other => panic!("expected failure, got {other:?}"),
+        }
+        let run = engine.get_run(&run_id).unwrap();
+        assert_eq!(run.status, SopRunStatus::Failed);
+        assert_eq!(run.flow.compensations_run, 2);
+    }
+
+    #[test]
+    fn step_timeout_fails_in_flight_step() {
+        let flow = StepFlow {
+            timeout_secs: Some(0),
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(SopExecutionMode::Auto, vec![flow_step(1, flow)]);
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+
+        let actions = engine.check_step_timeouts();
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(
+            &actions[0],
+            SopRunAction::Failed { reason, .. } if reason.contains("timed out after 0s")
+        ));
+        assert_eq!(engine.get_run(&run_id).unwrap().flow.timeouts, 1);
+        assert!(engine.check_step_timeouts().is_empty());
+    }
+
+    #[test]
+    fn tick_redispatches_retry_once_backoff_elapses() {
+        let flow = StepFlow {
+            retry: Some(StepRetry {
+                max_retries: 1,
+                backoff_secs: 0,
+            }),
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(SopExecutionMode::Deterministic, vec![flow_step(1, flow)]);
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+
+        let action = report(&mut engine, &run_id, 1, SopStepStatus::Failed, "503");
+        assert!(matches!(action, SopRunAction::Retry { attempt: 2, .. }));
+
+        let actions = engine.tick();
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(actions[0], SopRunAction::DeterministicStep { .. }));
+        assert_eq!(step_number_of(&actions[0]), Some(1));
+        assert!(!engine.get_run(&run_id).unwrap().flow.in_flight[0].retry_pending);
+        assert!(engine.tick().is_empty());
+    }
+
+    #[test]
+    fn agent_retry_waits_for_the_agent_instead_of_the_tick() {
+        let flow = StepFlow {
+            retry: Some(StepRetry {
+                max_retries: 1,
+                backoff_secs: 0,
+            }),
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(SopExecutionMode::Auto, vec![flow_step(1, flow)]);
+        let mut engine = engine_with_sops(vec![sop]);
+        let run_id = extract_run_id(&engine.start_run("flow", manual_event()).unwrap()).to_string();
+        report(&mut engine, &run_id, 1, SopStepStatus::Failed, "503");
+
+        assert!(engine.tick().is_empty());
+        assert!(engine.get_run(&run_id).unwrap().flow.in_flight[0].retry_pending);
+
+        let actions = engine.take_due_agent_retries();
+        assert_eq!(actions.len(), 1);
+        assert!(matches!(actions[0], SopRunAction::ExecuteStep { .. }));
+        assert!(engine.take_due_agent_retries().is_empty());
+    }
+
+    #[test]
+    fn deterministic_parallel_steps_report_by_number() {
+        let pair = || StepFlow {
+            parallel: Some("collect".into()),
+            ..StepFlow::default()
+        };
+        let sop = flow_sop(
+            SopExecutionMode::Deterministic,
+            vec![
+                flow_step(1, pair()),
+                flow_step(2, pair()),
+                flow_step(3, StepFlow::default()),
+            ],
+        );
+        let mut engine = engine_with_sops(vec![sop]);
+        let action = engine.start_run("flow", manual_event()).unwrap();
+        assert!(matches!(action, SopRunAction::FanOut { ref actions, .. } if actions.len() == 2));
+        let run_id = extract_run_id(&action).to_string();
+
+        assert!(engine
+            .advance_deterministic_step(&run_id, serde_json::json!(1))
+            .is_err());
+        let action = engine
+            .report_deterministic_step(&run_id, 2, serde_json::json!("b"))
+            .unwrap();
+        assert!(matches!(action, SopRunAction::AwaitParallel { .. }));
+        let action = engine
+            .report_deterministic_step(&run_id, 1, serde_json::json!("a"))
+            .unwrap();
+        assert!(matches!(
+            action,
+            SopRunAction::DeterministicStep { ref step, ref input, .. }
+                if step.number == 3 && input == &serde_json::json!("a")
+        ));
+    }
+
+    #[test]
+    fn deterministic_resume_continues_after_checkpoint() {
+        let mut engine = engine_with_sops(vec![deterministic_sop("det-sop")]);
+        let action = engine.start_run("det-sop", manual_event()).unwrap();
+        let run_id = extract_run_id(&action).to_string();
+
+        let action = engine
+            .advance_deterministic_step(&run_id, serde_json::json!({"ok": true}))
+            .unwrap();
+        let SopRunAction::CheckpointWait { state_file, .. } = action else {
+            panic!("expected checkpoint, got {action:?}");
+        };
+        let state = SopEngine::load_deterministic_state(&state_file).unwrap();
+        let _ = std::fs::remove_file(&state_file);
+        assert_eq!(state.paused_step, Some(2));
+
+        let action = engine.resume_deterministic_run(state).unwrap();
+        assert!(matches!(
+            action,
+            SopRunAction::DeterministicStep { ref step, ref input, .. }
+                if step.number == 3 && input == &serde_json::json!({"ok": true})
+        ));
+    }
+
+    fn journaled_engine(dir: &Path) -> SopEngine {
+        let mut engine = engine_with_sops(vec![test_sop(
+            "journaled",
+            SopExecutionMode::Supervised,
+            SopPriority::Normal,
+        )]);
+        engine.enable_journal(dir).unwrap();
+        engine
+    }
+
+    #[test]
+    fn journaled_run_resumes_after_restart() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let run_id = {
+            let mut engine = journaled_engine(tmp.path());
+            let action = engine.start_run("journaled", manual_event()).unwrap();
+            assert!(matches!(action, SopRunAction::WaitApproval { .. }));
+            first_active_run_id(&engine)
+        };
+
+        // A fresh engine picks the pending approval back up
+        let mut engine = journaled_engine(tmp.path());
+        let run = engine.get_run(&run_id).expect("run resumed");
+        assert_eq!(run.status, SopRunStatus::WaitingApproval);
+        assert!(run.waiting_since.is_some());
+        assert!(!engine.can_start("journaled"), "resumed run counts");
+
+        let action = engine.approve_step(&run_id).unwrap();
+        assert_eq!(step_number_of(&action), Some(1));
+        report(&mut engine, &run_id, 1, SopStepStatus::Completed, "done");
+        let action = report(&mut engine, &run_id, 2, SopStepStatus::Completed, "done");
+        assert!(matches!(action, SopRunAction::Completed { .. }));
+
+        let engine = journaled_engine(tmp.path());
+        assert!(engine.active_runs().is_empty());
+        let finished = engine.finished_runs(Some("journaled"));
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].step_results.len(), 2);
+    }
+
+    #[test]
+    fn replayed_event_with_idempotency_key_is_rejected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut engine = journaled_engine(tmp.path());
+        let keyed = SopEvent {
+            idempotency_key: Some("evt-1".into()),
+            ..manual_event()
+        };
+        engine.start_run("journaled", keyed.clone()).unwrap();
+        let run_id = first_active_run_id(&engine);
+        engine.cancel_run(&run_id).unwrap();
+
+        let err = engine.start_run("journaled", keyed.clone()).unwrap_err();
+        assert!(err.to_string().contains("idempotency key 'evt-1'"));
+
+        // Still rejected after a restart, and keys can come from the payload
+        let mut engine = journaled_engine(tmp.path());
+        assert!(engine.start_run("journaled", keyed).is_err());
+        let from_payload = SopEvent {
+            payload: Some(r#"{"idempotency_key": "evt-2"}"#.into()),
+            ..manual_event()
+        };
+        engine.start_run("journaled", from_payload.clone()).unwrap();
+        let run_id = first_active_run_id(&engine);
+        engine.cancel_run(&run_id).unwrap();
+        assert!(engine.start_run("journaled", from_payload).is_err());
+        assert!(engine.start_run("journaled", manual_event()).is_ok());
+    }
 }
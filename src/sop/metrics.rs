@@ -33,6 +33,12 @@ struct MetricCounters {
     steps_skipped: u64,
     human_approvals: u64,
     timeout_auto_approvals: u64,
+    steps_retried: u64,
+    steps_timed_out: u64,
+    branches_taken: u64,
+    parallel_fan_outs: u64,
+    compensations_run: u64,
+    runs_compensated: u64,
 }
 
 // ── RunSnapshot ────────────────────────────────────────────────
@@ -51,6 +57,11 @@ struct RunSnapshot {
     steps_skipped: u64,
     human_approval_count: u64,
     timeout_approval_count: u64,
+    steps_retried: u64,
+    steps_timed_out: u64,
+    branches_taken: u64,
+    parallel_fan_outs: u64,
+    compensations_run: u64,
 }
 
 // ── SopCounters ────────────────────────────────────────────────
@@ -434,15 +445,32 @@ fn build_snapshot(run: &SopRun, human_count: u64, timeout_count: u64) -> RunSnap
         .filter(|s| s.status == SopStepStatus::Skipped)
         .count() as u64;
 
+    // Steps a branch jumped over (or unused compensation steps) were never
+    // on this run's path, so they do not count against protocol adherence.
+    let mut off_path: Vec<u32> = run
+        .flow
+        .bypassed
+        .iter()
+        .copied()
+        .filter(|n| !run.step_results.iter().any(|r| r.step_number == *n))
+        .collect();
+    off_path.sort_unstable();
+    off_path.dedup();
+
     RunSnapshot {
         completed_at,
         terminal_status: run.status,
         steps_executed,
-        steps_defined: u64::from(run.total_steps),
+        steps_defined: u64::from(run.total_steps).saturating_sub(off_path.len() as u64),
         steps_failed,
         steps_skipped,
         human_approval_count: human_count,
         timeout_approval_count: timeout_count,
+        steps_retried: u64::from(run.flow.retries),
+        steps_timed_out: u64::from(run.flow.timeouts),
+        branches_taken: u64::from(run.flow.branches_taken),
+        parallel_fan_outs: u64::from(run.flow.fan_outs),
+        compensations_run: u64::from(run.flow.compensations_run),
     }
 }
 
@@ -458,6 +486,7 @@ fn apply_run(sop: &mut SopCounters, snap: &RunSnapshot) {
     c.steps_defined += snap.steps_defined;
     c.steps_failed += snap.steps_failed;
     c.steps_skipped += snap.steps_skipped;
+    apply_flow(c, snap);
 
     sop.recent_runs.push_back(snap.clone());
     if sop.recent_runs.len() > MAX_RECENT_RUNS {
@@ -465,6 +494,18 @@ fn apply_run(sop: &mut SopCounters, snap: &RunSnapshot) {
     }
 }
 
+/// Add a run's control-flow counts (retries, branches, compensation) to `c`.
+fn apply_flow(c: &mut MetricCounters, snap: &RunSnapshot) {
+    c.steps_retried += snap.steps_retried;
+    c.steps_timed_out += snap.steps_timed_out;
+    c.branches_taken += snap.branches_taken;
+    c.parallel_fan_outs += snap.parallel_fan_outs;
+    c.compensations_run += snap.compensations_run;
+    if snap.compensations_run > 0 {
+        c.runs_compensated += 1;
+    }
+}
+
 fn parse_completed_at(ts: &str) -> Option<DateTime<Utc>> {
     // Primary: RFC 3339
     if let Ok(dt) = DateTime::parse_from_rfc3339(ts) {
@@ -499,6 +540,7 @@ fn aggregate_windowed(
             wc.steps_skipped += snap.steps_skipped;
             wc.human_approvals += snap.human_approval_count;
             wc.timeout_auto_approvals += snap.timeout_approval_count;
+            apply_flow(&mut wc, snap);
         }
     }
     wc
@@ -566,6 +608,19 @@ fn resolve_from_counters(c: &MetricCounters, metric: &str) -> Option<serde_json:
             let total = c.runs_completed + c.runs_failed + c.runs_cancelled;
             Some(json!(c.runs_completed as f64 / total.max(1) as f64))
         }
+        "steps_retried" => Some(json!(c.steps_retried)),
+        "steps_timed_out" => Some(json!(c.steps_timed_out)),
+        "branches_taken" => Some(json!(c.branches_taken)),
+        "parallel_fan_outs" => Some(json!(c.parallel_fan_outs)),
+        "compensations_run" => Some(json!(c.compensations_run)),
+        "runs_compensated" => Some(json!(c.runs_compensated)),
+        "retry_rate" => {
+            if c.steps_executed == 0 {
+                Some(json!(0.0))
+            } else {
+                Some(json!(c.steps_retried as f64 / c.steps_executed as f64))
+            }
+        }
         _ => None,
     }
 }
@@ -582,6 +637,12 @@ fn counters_to_json(sop: &SopCounters) -> serde_json::Value {
         "steps_skipped": c.steps_skipped,
         "human_approvals": c.human_approvals,
         "timeout_auto_approvals": c.timeout_auto_approvals,
+        "steps_retried": c.steps_retried,
+        "steps_timed_out": c.steps_timed_out,
+        "branches_taken": c.branches_taken,
+        "parallel_fan_outs": c.parallel_fan_outs,
+        "compensations_run": c.compensations_run,
+        "runs_compensated": c.runs_compensated,
         "recent_runs_depth": sop.recent_runs.len(),
     })
 }
@@ -591,7 +652,7 @@ fn counters_to_json(sop: &SopCounters) -> serde_json::Value {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sop::types::{SopEvent, SopStepResult, SopTriggerSource};
+    use crate::sop::types::{SopEvent, SopRunFlow, SopStepResult, SopTriggerSource};
 
     fn recent_timestamp(offset: chrono::Duration) -> String {
         (Utc::now() - offset).to_rfc3339()
@@ -603,6 +664,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: recent_timestamp(chrono::Duration::minutes(5)),
+            idempotency_key: None,
         }
     }
 
@@ -628,6 +690,7 @@ mod tests {
             step_results,
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         }
     }
 
@@ -782,6 +845,43 @@ mod tests {
         assert!((val - 1.0 / 3.0).abs() < 1e-10);
     }
 
+    #[test]
+    fn flow_counters_and_bypassed_steps() {
+        let c = SopMetricsCollector::new();
+        let mut run = make_run(
+            "r1",
+            "test-sop",
+            SopRunStatus::Completed,
+            4,
+            vec![
+                make_step(1, SopStepStatus::Failed),
+                make_step(1, SopStepStatus::Completed),
+                make_step(4, SopStepStatus::Completed),
+            ],
+        );
+        run.flow.bypassed = vec![2, 3];
+        run.flow.retries = 1;
+        run.flow.branches_taken = 1;
+        c.record_run_complete(&run);
+
+        assert_eq!(c.get_metric_value("sop.steps_retried"), Some(json!(1u64)));
+        assert_eq!(c.get_metric_value("sop.branches_taken"), Some(json!(1u64)));
+        assert_eq!(
+            c.get_metric_value("sop.runs_compensated"),
+            Some(json!(0u64))
+        );
+        assert_eq!(c.get_metric_value("sop.retry_rate"), Some(json!(1.0 / 3.0)));
+
+        // Branched-over steps leave 2 steps on the path:
+        // adherence = (3 - 1 - 0) / 2 = 1
+        let val = c
+            .get_metric_value("sop.protocol_adherence_rate")
+            .unwrap()
+            .as_f64()
+            .unwrap();
+        assert!((val - 1.0).abs() < 1e-10);
+    }
+
     #[test]
     fn derived_rate_metrics() {
         let c = SopMetricsCollector::new();
@@ -1076,6 +1176,11 @@ mod tests {
                 steps_skipped: 0,
                 human_approval_count: 0,
                 timeout_approval_count: 0,
+                steps_retried: 0,
+                steps_timed_out: 0,
+                branches_taken: 0,
+                parallel_fan_outs: 0,
+                compensations_run: 0,
             };
             state.global.counters.runs_completed += 1;
             state.global.counters.steps_executed += 1;
@@ -1189,6 +1294,7 @@ mod tests {
             step_results: vec![],
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
         audit.log_run_start(&run).await.unwrap();
 
@@ -1287,6 +1393,7 @@ mod tests {
             step_results: vec![],
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
         audit.log_run_start(&running_run).await.unwrap();
         audit.log_approval(&running_run, 1).await.unwrap();
@@ -1380,6 +1487,11 @@ mod tests {
                 steps_skipped: 0,
                 human_approval_count: 0,
                 timeout_approval_count: 0,
+                steps_retried: 0,
+                steps_timed_out: 0,
+                branches_taken: 0,
+                parallel_fan_outs: 0,
+                compensations_run: 0,
             };
             state.global.recent_runs.push_back(old_snap);
         }
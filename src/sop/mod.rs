@@ -3,6 +3,7 @@ pub mod condition;
 pub mod dispatch;
 pub mod engine;
 pub mod metrics;
+pub mod store;
 pub mod types;
 
 pub use audit::SopAuditLogger;
@@ -10,30 +11,59 @@ pub use engine::SopEngine;
 pub use metrics::SopMetricsCollector;
 #[allow(unused_imports)]
 pub use types::{
-    DeterministicRunState, DeterministicSavings, Sop, SopEvent, SopExecutionMode, SopPriority,
-    SopRun, SopRunAction, SopRunStatus, SopStep, SopStepKind, SopStepResult, SopStepStatus,
-    SopTrigger, SopTriggerSource, StepSchema,
+    DeterministicRunState, DeterministicSavings, InFlightStep, Sop, SopEvent, SopExecutionMode,
+    SopPriority, SopRun, SopRunAction, SopRunFlow, SopRunStatus, SopStep, SopStepKind,
+    SopStepResult, SopStepStatus, SopTrigger, SopTriggerSource, StepBranch, StepFlow, StepRetry,
+    StepSchema,
 };
 
 use anyhow::Result;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex};
-use tracing::warn;
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+use tracing::{info, warn};
+
+/// Live engines keyed by workspace, so every entry point in the process
+/// drives the same runs and journal.
+fn engines() -> &'static parking_lot::Mutex<HashMap<PathBuf, Weak<Mutex<SopEngine>>>> {
+    static ENGINES: OnceLock<parking_lot::Mutex<HashMap<PathBuf, Weak<Mutex<SopEngine>>>>> =
+        OnceLock::new();
+    ENGINES.get_or_init(|| parking_lot::Mutex::new(HashMap::new()))
+}
 
-/// Create a shared SOP engine from config, returning `None` when SOP is disabled.
-pub fn create_sop_engine(
+/// Return the process's SOP engine for `workspace_dir`, creating it from
+/// config when none is alive. Returns `None` when SOP is disabled.
+///
+/// Gateway, channels and agents share one engine per workspace, so a
+/// journaled run is resumed and ticked by a single owner. Inside a Tokio
+/// runtime the engine is ticked in the background for as long as it is
+/// alive, so timeouts and retries advance without a status query.
+pub fn shared_sop_engine(
     config: &crate::config::SopConfig,
     workspace_dir: &Path,
 ) -> Option<Arc<Mutex<SopEngine>>> {
     if !config.enabled {
         return None;
     }
+    let mut engines = engines().lock();
+    if let Some(engine) = engines.get(workspace_dir).and_then(Weak::upgrade) {
+        return Some(engine);
+    }
     let mut engine = SopEngine::new(config.clone());
     engine.reload(workspace_dir);
-    Some(Arc::new(Mutex::new(engine)))
+    match engine.enable_journal(workspace_dir) {
+        Ok(0) => {}
+        Ok(resumed) => info!("Resumed {resumed} SOP run(s) from the run journal"),
+        Err(e) => warn!("SOP run journal unavailable, runs will not survive a restart: {e}"),
+    }
+    let engine = Arc::new(Mutex::new(engine));
+    dispatch::spawn_engine_tick(&engine);
+    engines.retain(|_, engine| engine.strong_count() > 0);
+    engines.insert(workspace_dir.to_path_buf(), Arc::downgrade(&engine));
+    Some(engine)
 }
 
-use types::{SopManifest, SopMeta};
+use types::{SopManifest, SopMeta, StepFlowOverride};
 
 /// Parse an execution mode string into `SopExecutionMode`, falling back to
 /// `Supervised` for unknown values.
@@ -121,12 +151,13 @@ fn load_sop(sop_dir: &Path, default_execution_mode: SopExecutionMode) -> Result<
     let manifest: SopManifest = toml::from_str(&toml_content)?;
 
     let md_path = sop_dir.join("SOP.md");
-    let steps = if md_path.exists() {
+    let mut steps = if md_path.exists() {
         let md_content = std::fs::read_to_string(&md_path)?;
         parse_steps(&md_content)
     } else {
         Vec::new()
     };
+    apply_step_overrides(&mut steps, manifest.steps, &manifest.sop.name);
 
     let SopMeta {
         name,
@@ -161,13 +192,40 @@ fn load_sop(sop_dir: &Path, default_execution_mode: SopExecutionMode) -> Result<
     })
 }
 
+/// Overlay `[[steps]]` control flow from SOP.toml onto the parsed steps.
+/// Fields set in SOP.toml win over the same fields from SOP.md.
+fn apply_step_overrides(steps: &mut [SopStep], overrides: Vec<StepFlowOverride>, sop_name: &str) {
+    for o in overrides {
+        let Some(step) = steps.iter_mut().find(|s| s.number == o.number) else {
+            warn!(
+                "SOP '{sop_name}': SOP.toml configures step {} which SOP.md does not define",
+                o.number
+            );
+            continue;
+        };
+        if let Some(kind) = o.kind {
+            step.kind = kind;
+        }
+        let flow = &mut step.flow;
+        if !o.flow.branches.is_empty() {
+            flow.branches = o.flow.branches;
+        }
+        flow.next = o.flow.next.or(flow.next);
+        flow.parallel = o.flow.parallel.or(flow.parallel.take());
+        flow.retry = o.flow.retry.or(flow.retry);
+        flow.timeout_secs = o.flow.timeout_secs.or(flow.timeout_secs);
+        flow.compensate_with = o.flow.compensate_with.or(flow.compensate_with);
+    }
+}
+
 // ── Markdown step parser ────────────────────────────────────────
 
 /// Parse procedure steps from SOP.md content.
 ///
 /// Expects a `## Steps` heading followed by numbered items (`1.`, `2.`, …).
 /// Each item's first bold text (`**...**`) is the step title; the rest is body.
-/// Sub-bullets `- tools:` and `- requires_confirmation: true` are parsed.
+/// Sub-bullets `- tools:`, `- requires_confirmation: true`, `- kind:` and the
+/// control-flow bullets handled by [`parse_flow_bullet`] are parsed.
 pub fn parse_steps(md: &str) -> Vec<SopStep> {
     let mut steps = Vec::new();
     let mut in_steps_section = false;
@@ -177,6 +235,7 @@ pub fn parse_steps(md: &str) -> Vec<SopStep> {
     let mut current_tools: Vec<String> = Vec::new();
     let mut current_requires_confirmation = false;
     let mut current_kind = SopStepKind::Execute;
+    let mut current_flow = StepFlow::default();
 
     for line in md.lines() {
         let trimmed = line.trim();
@@ -199,6 +258,7 @@ pub fn parse_steps(md: &str) -> Vec<SopStep> {
                     &mut current_tools,
                     &mut current_requires_confirmation,
                     &mut current_kind,
+                    &mut current_flow,
                 );
                 in_steps_section = false;
             }
@@ -220,6 +280,7 @@ pub fn parse_steps(md: &str) -> Vec<SopStep> {
                 &mut current_tools,
                 &mut current_requires_confirmation,
                 &mut current_kind,
+                &mut current_flow,
             );
 
             let step_num = u32::try_from(steps.len())
@@ -258,10 +319,14 @@ pub fn parse_steps(md: &str) -> Vec<SopStep> {
                     let val = val.trim();
                     if val.eq_ignore_ascii_case("checkpoint") {
                         current_kind = SopStepKind::Checkpoint;
+                    } else if val.eq_ignore_ascii_case("compensation") {
+                        current_kind = SopStepKind::Compensation;
                     } else {
                         current_kind = SopStepKind::Execute;
                     }
                 }
+            } else if parse_flow_bullet(bullet, &mut current_flow) {
+                // Control-flow setting consumed
             } else {
                 // Continuation body line
                 if !current_body.is_empty() {
@@ -290,6 +355,7 @@ pub fn parse_steps(md: &str) -> Vec<SopStep> {
         &mut current_tools,
         &mut current_requires_confirmation,
         &mut current_kind,
+        &mut current_flow,
     );
 
     steps
@@ -304,6 +370,7 @@ fn flush_step(
     tools: &mut Vec<String>,
     requires_confirmation: &mut bool,
     kind: &mut SopStepKind,
+    flow: &mut StepFlow,
 ) {
     if let Some(n) = number.take() {
         steps.push(SopStep {
@@ -314,6 +381,7 @@ fn flush_step(
             requires_confirmation: *requires_confirmation,
             kind: *kind,
             schema: None,
+            flow: std::mem::take(flow),
         });
         *body = String::new();
         *requires_confirmation = false;
@@ -321,6 +389,59 @@ fn flush_step(
     }
 }
 
+/// Parse a control-flow sub-bullet into `flow`. Returns `false` when the
+/// bullet is not a control-flow setting (so it stays part of the body).
+///
+/// - `branch: <condition> -> <step>` — jump when the output matches
+/// - `next: <step>` — continue here when no branch matches
+/// - `parallel: <group>` — run with adjacent steps of the same group
+/// - `retry: <n>` / `backoff_secs: <n>` — retries and initial backoff
+/// - `timeout_secs: <n>` — time limit per attempt
+/// - `compensate_with: <step>` — compensation step for this step
+fn parse_flow_bullet(bullet: &str, flow: &mut StepFlow) -> bool {
+    let Some((key, value)) = bullet.split_once(':') else {
+        return false;
+    };
+    let value = value.trim();
+    let number = || value.parse::<u32>().ok();
+    match key.trim() {
+        "branch" => {
+            if let Some((when, goto)) = value.rsplit_once("->") {
+                if let Ok(goto) = goto.trim().parse() {
+                    flow.branches.push(StepBranch {
+                        when: when.trim().to_string(),
+                        goto,
+                    });
+                }
+            }
+        }
+        "next" => flow.next = number(),
+        "parallel" => flow.parallel = Some(value.to_string()).filter(|g| !g.is_empty()),
+        "retry" => {
+            if let Some(max_retries) = number() {
+                let backoff_secs = flow.retry.map_or(0, |r| r.backoff_secs);
+                flow.retry = Some(StepRetry {
+                    max_retries,
+                    backoff_secs,
+                });
+            }
+        }
+        "backoff_secs" => {
+            if let Ok(backoff_secs) = value.parse() {
+                let max_retries = flow.retry.map_or(0, |r| r.max_retries);
+                flow.retry = Some(StepRetry {
+                    max_retries,
+                    backoff_secs,
+                });
+            }
+        }
+        "timeout_secs" => flow.timeout_secs = value.parse().ok(),
+        "compensate_with" => flow.compensate_with = number(),
+        _ => return false,
+    }
+    true
+}
+
 /// Try to parse `N. rest` from a line, returning `rest` if successful.
 fn parse_numbered_item(line: &str) -> Option<&str> {
     let dot_pos = line.find(". ")?;
@@ -383,11 +504,86 @@ pub fn validate_sop(sop: &Sop) -> Vec<String> {
         if step.title.is_empty() {
             warnings.push(format!("Step {} has an empty title", step.number));
         }
+        warnings.extend(validate_step_flow(sop, step));
+    }
+
+    // Parallel groups must be contiguous
+    let mut seen_groups: Vec<&str> = Vec::new();
+    let mut previous: Option<&str> = None;
+    for step in &sop.steps {
+        let group = step.flow.parallel.as_deref();
+        if let Some(group) = group {
+            if previous != Some(group) {
+                if seen_groups.contains(&group) {
+                    warnings.push(format!(
+                        "Parallel group '{group}' is split; its steps must be adjacent"
+                    ));
+                }
+                seen_groups.push(group);
+            }
+        }
+        previous = group;
     }
 
     warnings
 }
 
+/// Check a step's control-flow references and combinations.
+fn validate_step_flow(sop: &Sop, step: &SopStep) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let n = step.number;
+    let target = |number: u32| sop.steps.iter().find(|s| s.number == number);
+
+    for branch in &step.flow.branches {
+        match target(branch.goto) {
+            None => warnings.push(format!(
+                "Step {n} branches to step {} which does not exist",
+                branch.goto
+            )),
+            Some(t) if t.kind == SopStepKind::Compensation => warnings.push(format!(
+                "Step {n} branches to compensation step {}",
+                branch.goto
+            )),
+            Some(_) => {}
+        }
+    }
+    if let Some(next) = step.flow.next {
+        if target(next).is_none() {
+            warnings.push(format!(
+                "Step {n} continues at step {next} which does not exist"
+            ));
+        }
+    }
+    if let Some(comp) = step.flow.compensate_with {
+        match target(comp) {
+            Some(t) if t.kind == SopStepKind::Compensation => {}
+            Some(_) => warnings.push(format!(
+                "Step {n} is compensated by step {comp}, which is not `kind: compensation`"
+            )),
+            None => warnings.push(format!(
+                "Step {n} is compensated by step {comp} which does not exist"
+            )),
+        }
+    }
+    if step.flow.parallel.is_some() {
+        if step.kind != SopStepKind::Execute {
+            warnings.push(format!(
+                "Step {n} is a {} step and cannot run in a parallel group",
+                step.kind
+            ));
+        }
+        if !step.flow.branches.is_empty() {
+            warnings.push(format!(
+                "Step {n} is in a parallel group; its branches are ignored"
+            ));
+        }
+    }
+    if step.flow.timeout_secs == Some(0) {
+        warnings.push(format!("Step {n} has a zero timeout"));
+    }
+    warnings
+}
+
 // ── CLI handler ─────────────────────────────────────────────────
 
 /// Handle the `sop` CLI subcommand.
@@ -526,10 +722,28 @@ pub fn handle_command(command: crate::SopCommands, config: &crate::config::Confi
                 for step in &sop.steps {
                     let mut tags = Vec::new();
                     if step.requires_confirmation {
-                        tags.push("requires confirmation");
+                        tags.push("requires confirmation".to_string());
                     }
                     if step.kind == SopStepKind::Checkpoint {
-                        tags.push("checkpoint");
+                        tags.push("checkpoint".to_string());
+                    }
+                    if step.kind == SopStepKind::Compensation {
+                        tags.push("compensation".to_string());
+                    }
+                    if let Some(ref group) = step.flow.parallel {
+                        tags.push(format!("parallel: {group}"));
+                    }
+                    if let Some(retry) = step.flow.retry {
+                        tags.push(format!(
+                            "retry ×{}, backoff {}s",
+                            retry.max_retries, retry.backoff_secs
+                        ));
+                    }
+                    if let Some(secs) = step.flow.timeout_secs {
+                        tags.push(format!("timeout {secs}s"));
+                    }
+                    if let Some(comp) = step.flow.compensate_with {
+                        tags.push(format!("compensated by {comp}"));
                     }
                     let tag_str = if tags.is_empty() {
                         String::new()
@@ -550,12 +764,102 @@ pub fn handle_command(command: crate::SopCommands, config: &crate::config::Confi
                     if !step.suggested_tools.is_empty() {
                         println!("     Tools: {}", step.suggested_tools.join(", "));
                     }
+                    for branch in &step.flow.branches {
+                        println!("     Branch: {} → step {}", branch.when, branch.goto);
+                    }
+                    if let Some(next) = step.flow.next {
+                        println!("     Next: step {next}");
+                    }
                 }
             }
             println!();
             Ok(())
         }
+
+        crate::SopCommands::Runs {
+            run_id: Some(run_id),
+            ..
+        } => {
+            let run = store::get_run(&config.workspace_dir, &run_id)?
+                .ok_or_else(|| anyhow::anyhow!("SOP run not found: {run_id}"))?;
+            print_run_detail(&run);
+            Ok(())
+        }
+
+        crate::SopCommands::Runs {
+            run_id: None,
+            sop,
+            active,
+            limit,
+        } => {
+            let filter = store::RunFilter {
+                sop_name: sop,
+                active_only: active,
+            };
+            let runs = store::list_runs(&config.workspace_dir, &filter, limit)?;
+            if runs.is_empty() {
+                println!("No SOP runs recorded.");
+                return Ok(());
+            }
+
+            println!("SOP runs ({}):", runs.len());
+            println!();
+            for run in &runs {
+                println!(
+                    "  {} {} [{}] — step {}/{}, started {}",
+                    console::style(&run.run_id).white().bold(),
+                    run.sop_name,
+                    console::style(run.status).cyan(),
+                    run.current_step,
+                    run.total_steps,
+                    run.started_at
+                );
+            }
+            println!();
+            Ok(())
+        }
+    }
+}
+
+fn print_run_detail(run: &SopRun) {
+    println!(
+        "{} — {}",
+        console::style(&run.run_id).white().bold(),
+        run.sop_name
+    );
+    println!("Status:       {}", run.status);
+    println!("Current step: {}/{}", run.current_step, run.total_steps);
+    println!("Trigger:      {}", run.trigger_event.source);
+    if let Some(key) = run.trigger_event.dedup_key() {
+        println!("Idempotency:  {key}");
+    }
+    println!("Started:      {}", run.started_at);
+    if let Some(ref completed) = run.completed_at {
+        println!("Completed:    {completed}");
+    }
+    if let Some(ref since) = run.waiting_since {
+        println!("Waiting since: {since}");
+    }
+    if let Some(ref reason) = run.flow.failure_reason {
+        println!("Failure:      {reason}");
+    }
+    for step in &run.flow.in_flight {
+        println!(
+            "In flight:    step {} (attempt {}, since {})",
+            step.step_number, step.attempt, step.started_at
+        );
+    }
+    if !run.step_results.is_empty() {
+        println!();
+        println!("Steps:");
+        for result in &run.step_results {
+            println!(
+                "  {}. [{}] {}",
+                result.step_number, result.status, result.output
+            );
+        }
     }
+    println!();
 }
 
 #[cfg(test)]
@@ -789,6 +1093,7 @@ type = "manual"
                 requires_confirmation: false,
                 kind: SopStepKind::default(),
                 schema: None,
+                flow: StepFlow::default(),
             }],
             cooldown_secs: 0,
             max_concurrent: 1,
@@ -945,4 +1250,162 @@ type = "manual"
         // Default kind should be Execute
         assert_eq!(steps[2].kind, SopStepKind::Execute);
     }
+
+    #[test]
+    fn parse_steps_with_control_flow() {
+        let md = r#"## Steps
+
+1. **Triage** — Classify the alert.
+   - branch: $.severity == "critical" -> 4
+   - retry: 2
+   - backoff_secs: 10
+   - timeout_secs: 120
+
+2. **Collect logs** — Gather logs.
+   - parallel: diagnostics
+   - compensate_with: 5
+
+3. **Collect metrics** — Gather metrics.
+   - parallel: diagnostics
+   - next: 6
+
+4. **Page on-call** — Escalate.
+
+5. **Delete log bundle** — Undo step 2.
+   - kind: compensation
+"#;
+        let steps = parse_steps(md);
+        assert_eq!(steps.len(), 5);
+
+        let triage = &steps[0].flow;
+        assert_eq!(
+            triage.branches,
+            vec![StepBranch {
+                when: r#"$.severity == "critical""#.into(),
+                goto: 4,
+            }]
+        );
+        assert_eq!(
+            triage.retry,
+            Some(StepRetry {
+                max_retries: 2,
+                backoff_secs: 10,
+            })
+        );
+        assert_eq!(triage.timeout_secs, Some(120));
+        assert!(steps[0].body.starts_with("Classify"));
+        assert!(!steps[0].body.contains("branch"));
+
+        assert_eq!(steps[1].flow.parallel.as_deref(), Some("diagnostics"));
+        assert_eq!(steps[1].flow.compensate_with, Some(5));
+        assert_eq!(steps[2].flow.next, Some(6));
+        assert!(steps[3].flow.is_default());
+        assert_eq!(steps[4].kind, SopStepKind::Compensation);
+    }
+
+    #[test]
+    fn sop_toml_steps_override_markdown_flow() {
+        let dir = tempfile::tempdir().unwrap();
+        let sop_dir = dir.path().join("incident");
+        fs::create_dir_all(&sop_dir).unwrap();
+        fs::write(
+            sop_dir.join("SOP.toml"),
+            r#"
+[sop]
+name = "incident"
+description = "Incident response"
+
+[[triggers]]
+type = "manual"
+
+[[steps]]
+number = 1
+timeout_secs = 30
+branches = [{ when = "$.healthy == true", goto = 3 }]
+
+[[steps]]
+number = 2
+retry = { max_retries = 3, backoff_secs = 5 }
+compensate_with = 3
+
+[[steps]]
+number = 3
+kind = "compensation"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            sop_dir.join("SOP.md"),
+            "## Steps\n\n1. **Check** — Probe.\n   - timeout_secs: 300\n\n2. **Restart** — Restart.\n\n3. **Rollback** — Undo.\n",
+        )
+        .unwrap();
+
+        let sops = load_sops_from_directory(dir.path(), SopExecutionMode::Auto);
+        let steps = &sops[0].steps;
+        assert_eq!(steps[0].flow.timeout_secs, Some(30));
+        assert_eq!(steps[0].flow.branches[0].goto, 3);
+        assert_eq!(steps[1].flow.retry.unwrap().max_retries, 3);
+        assert_eq!(steps[1].flow.compensate_with, Some(3));
+        assert_eq!(steps[2].kind, SopStepKind::Compensation);
+    }
+
+    #[test]
+    fn validate_sop_flags_bad_flow_references() {
+        let md = r#"## Steps
+
+1. **A** — a.
+   - branch: $.x == 1 -> 9
+   - compensate_with: 2
+
+2. **B** — b.
+   - parallel: g
+
+3. **C** — c.
+
+4. **D** — d.
+   - parallel: g
+"#;
+        let sop = Sop {
+            name: "flow".into(),
+            description: "Flow".into(),
+            version: "1.0.0".into(),
+            priority: SopPriority::Normal,
+            execution_mode: SopExecutionMode::Auto,
+            triggers: vec![SopTrigger::Manual],
+            steps: parse_steps(md),
+            cooldown_secs: 0,
+            max_concurrent: 1,
+            location: None,
+            deterministic: false,
+        };
+        let warnings = validate_sop(&sop);
+        assert!(warnings.iter().any(|w| w.contains("branches to step 9")));
+        assert!(warnings
+            .iter()
+            .any(|w| w.contains("not `kind: compensation`")));
+        assert!(warnings.iter().any(|w| w.contains("'g' is split")));
+    }
+
+    #[test]
+    fn engines_are_shared_per_workspace_while_alive() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = crate::config::SopConfig {
+            enabled: true,
+            ..crate::config::SopConfig::default()
+        };
+
+        let first = shared_sop_engine(&config, dir.path()).unwrap();
+        let second = shared_sop_engine(&config, dir.path()).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let other = tempfile::tempdir().unwrap();
+        let elsewhere = shared_sop_engine(&config, other.path()).unwrap();
+        assert!(!Arc::ptr_eq(&first, &elsewhere));
+
+        let stale = Arc::downgrade(&first);
+        drop((first, second));
+        let fresh = shared_sop_engine(&config, dir.path()).unwrap();
+        assert!(stale.upgrade().is_none());
+        assert_eq!(Arc::strong_count(&fresh), 1);
+    }
 }
@@ -0,0 +1,447 @@
+//! SQLite journal of SOP runs.
+//!
+//! The engine writes a run through on every state change, so active runs,
+//! pending approvals and step results survive a daemon restart. The engine
+//! holds one [`RunJournal`] connection for its lifetime and queues writes to
+//! it; the free functions below open a fresh
+//! connection per call for one-off readers (CLI, gateway). Finished runs are
+//! pruned to a configurable limit, while the idempotency keys that started
+//! them live in their own table so a pruned run's trigger stays rejected.
+
+use super::types::SopRun;
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// How long an idempotency key keeps rejecting replays of its trigger.
+const IDEMPOTENCY_KEY_RETENTION_DAYS: i64 = 30;
+
+/// Filter for [`list_runs`].
+#[derive(Debug, Clone, Default)]
+pub struct RunFilter {
+    /// Only runs of this SOP.
+    pub sop_name: Option<String>,
+    /// Only runs that have not reached a terminal status.
+    pub active_only: bool,
+}
+
+/// Long-lived journal connection owned by an engine.
+pub struct RunJournal {
+    conn: Mutex<Connection>,
+    /// Snapshots waiting to be written, in the order they were taken.
+    pending: Mutex<Vec<(SopRun, usize)>>,
+}
+
+impl RunJournal {
+    /// Open (creating if needed) the journal under `workspace_dir`.
+    pub fn open(workspace_dir: &Path) -> Result<Self> {
+        Ok(Self {
+            conn: Mutex::new(open_connection(workspace_dir)?),
+            pending: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Queue a snapshot of `run` and write it in the background. Failures
+    /// are logged.
+    pub fn submit(self: &Arc<Self>, run: SopRun, keep_finished: usize) {
+        self.pending.lock().push((run, keep_finished));
+        let journal = Arc::clone(self);
+        crate::util::run_blocking(move || journal.flush());
+    }
+
+    /// Write every queued snapshot. Draining under the connection lock keeps
+    /// writes in submission order across concurrent flushes.
+    pub fn flush(&self) {
+        let conn = self.conn.lock();
+        let queued = std::mem::take(&mut *self.pending.lock());
+        for (run, keep_finished) in queued {
+            if let Err(e) = write_run(&conn, &run, keep_finished) {
+                tracing::warn!("SOP run {}: failed to journal run state: {e}", run.run_id);
+            }
+        }
+    }
+
+    /// Load every run that has not reached a terminal status, oldest first.
+    pub fn load_active_runs(&self) -> Result<Vec<SopRun>> {
+        select_active_runs(&self.conn.lock())
+    }
+
+    /// Load the most recent `limit` finished runs, oldest first.
+    pub fn load_finished_runs(&self, limit: usize) -> Result<Vec<SopRun>> {
+        select_finished_runs(&self.conn.lock(), limit)
+    }
+
+    /// Return the id of the run of `sop_name` started by a trigger carrying
+    /// `key`, if one is recorded.
+    pub fn find_by_idempotency_key(&self, sop_name: &str, key: &str) -> Result<Option<String>> {
+        select_by_idempotency_key(&self.conn.lock(), sop_name, key)
+    }
+}
+
+/// Insert or update a run. When the run is finished, finished runs beyond
+/// `keep_finished` are pruned (0 keeps everything).
+pub fn save_run(workspace_dir: &Path, run: &SopRun, keep_finished: usize) -> Result<()> {
+    with_connection(workspace_dir, |conn| write_run(conn, run, keep_finished))
+}
+
+fn write_run(conn: &Connection, run: &SopRun, keep_finished: usize) -> Result<()> {
+    let json = serde_json::to_string(run).context("Failed to serialize SOP run")?;
+    let finished = run.status.is_terminal();
+    let now = chrono::Utc::now();
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute(
+        "INSERT INTO sop_runs
+            (run_id, sop_name, status, finished, idempotency_key, started_at, completed_at, updated_at, run_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT(run_id) DO UPDATE SET
+            status = excluded.status,
+            finished = excluded.finished,
+            completed_at = excluded.completed_at,
+            updated_at = excluded.updated_at,
+            run_json = excluded.run_json",
+        params![
+            run.run_id,
+            run.sop_name,
+            run.status.to_string(),
+            finished,
+            run.trigger_event.dedup_key(),
+            run.started_at,
+            run.completed_at,
+            now.to_rfc3339(),
+            json,
+        ],
+    )
+    .context("Failed to journal SOP run")?;
+
+    if let Some(key) = run.trigger_event.dedup_key() {
+        tx.execute(
+            "INSERT OR IGNORE INTO sop_idempotency_keys (sop_name, idempotency_key, run_id, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![run.sop_name, key, run.run_id, now.to_rfc3339()],
+        )
+        .context("Failed to record SOP idempotency key")?;
+    }
+
+    if finished && keep_finished > 0 {
+        let keep = i64::try_from(keep_finished).context("SOP run history limit overflow")?;
+        tx.execute(
+            "DELETE FROM sop_runs
+             WHERE finished = 1
+               AND run_id NOT IN (
+                 SELECT run_id FROM sop_runs
+                 WHERE finished = 1
+                 ORDER BY completed_at DESC, rowid DESC
+                 LIMIT ?1
+               )",
+            params![keep],
+        )
+        .context("Failed to prune SOP run history")?;
+        let cutoff = now - chrono::Duration::days(IDEMPOTENCY_KEY_RETENTION_DAYS);
+        tx.execute(
+            "DELETE FROM sop_idempotency_keys WHERE created_at < ?1",
+            params![cutoff.to_rfc3339()],
+        )
+        .context("Failed to prune SOP idempotency keys")?;
+    }
+
+    tx.commit().context("Failed to commit SOP run")?;
+    Ok(())
+}
+
+/// Load every run that has not reached a terminal status, oldest first.
+pub fn load_active_runs(workspace_dir: &Path) -> Result<Vec<SopRun>> {
+    with_connection(workspace_dir, select_active_runs)
+}
+
+fn select_active_runs(conn: &Connection) -> Result<Vec<SopRun>> {
+    query_runs(
+        conn,
+        "SELECT run_json FROM sop_runs WHERE finished = 0 ORDER BY started_at ASC, rowid ASC",
+        params![],
+    )
+}
+
+/// Load the most recent `limit` finished runs, oldest first (the order the
+/// engine keeps them in).
+pub fn load_finished_runs(workspace_dir: &Path, limit: usize) -> Result<Vec<SopRun>> {
+    with_connection(workspace_dir, |conn| select_finished_runs(conn, limit))
+}
+
+fn select_finished_runs(conn: &Connection, limit: usize) -> Result<Vec<SopRun>> {
+    let lim = limit_param(limit)?;
+    let mut runs = query_runs(
+        conn,
+        "SELECT run_json FROM sop_runs WHERE finished = 1
+         ORDER BY completed_at DESC, rowid DESC
+         LIMIT ?1",
+        params![lim],
+    )?;
+    runs.reverse();
+    Ok(runs)
+}
+
+/// List runs matching `filter`, newest first.
+pub fn list_runs(workspace_dir: &Path, filter: &RunFilter, limit: usize) -> Result<Vec<SopRun>> {
+    let lim = limit_param(limit)?;
+    with_connection(workspace_dir, |conn| {
+        query_runs(
+            conn,
+            "SELECT run_json FROM sop_runs
+             WHERE (?1 IS NULL OR sop_name = ?1)
+               AND (?2 = 0 OR finished = 0)
+             ORDER BY started_at DESC, rowid DESC
+             LIMIT ?3",
+            params![filter.sop_name, filter.active_only, lim],
+        )
+    })
+}
+
+/// Fetch a single run by id.
+pub fn get_run(workspace_dir: &Path, run_id: &str) -> Result<Option<SopRun>> {
+    with_connection(workspace_dir, |conn| {
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT run_json FROM sop_runs WHERE run_id = ?1",
+                params![run_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        json.map(|j| parse_run(&j)).transpose()
+    })
+}
+
+/// Return the id of the run of `sop_name` that was started by a trigger event
+/// carrying `key`, if one is recorded. Keys outlive pruned run history.
+pub fn find_by_idempotency_key(
+    workspace_dir: &Path,
+    sop_name: &str,
+    key: &str,
+) -> Result<Option<String>> {
+    with_connection(workspace_dir, |conn| {
+        select_by_idempotency_key(conn, sop_name, key)
+    })
+}
+
+fn select_by_idempotency_key(
+    conn: &Connection,
+    sop_name: &str,
+    key: &str,
+) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT run_id FROM sop_idempotency_keys WHERE sop_name = ?1 AND idempotency_key = ?2",
+            params![sop_name, key],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn query_runs(conn: &Connection, sql: &str, params: impl rusqlite::Params) -> Result<Vec<SopRun>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params, |row| row.get::<_, String>(0))?;
+
+    let mut runs = Vec::new();
+    for row in rows {
+        match parse_run(&row?) {
+            Ok(run) => runs.push(run),
+            Err(e) => tracing::warn!("Skipping unreadable journaled SOP run: {e}"),
+        }
+    }
+    Ok(runs)
+}
+
+fn parse_run(json: &str) -> Result<SopRun> {
+    serde_json::from_str(json).context("Invalid SOP run in journal")
+}
+
+fn limit_param(limit: usize) -> Result<i64> {
+    i64::try_from(limit.max(1)).context("SOP run limit overflow")
+}
+
+fn db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("sop").join("runs.db")
+}
+
+fn with_connection<T>(workspace_dir: &Path, f: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+    f(&open_connection(workspace_dir)?)
+}
+
+fn open_connection(workspace_dir: &Path) -> Result<Connection> {
+    let path = db_path(workspace_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create SOP directory: {}", parent.display()))?;
+    }
+
+    let conn = Connection::open(&path)
+        .with_context(|| format!("Failed to open SOP run journal: {}", path.display()))?;
+
+    conn.execute_batch(
+        "PRAGMA journal_mode = WAL;
+         PRAGMA synchronous = NORMAL;
+         PRAGMA temp_store = MEMORY;
+
+         CREATE TABLE IF NOT EXISTS sop_runs (
+            run_id           TEXT PRIMARY KEY,
+            sop_name         TEXT NOT NULL,
+            status           TEXT NOT NULL,
+            finished         INTEGER NOT NULL DEFAULT 0,
+            idempotency_key  TEXT,
+            started_at       TEXT NOT NULL,
+            completed_at     TEXT,
+            updated_at       TEXT NOT NULL,
+            run_json         TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_sop_runs_finished ON sop_runs(finished, completed_at);
+         CREATE INDEX IF NOT EXISTS idx_sop_runs_sop ON sop_runs(sop_name, started_at);
+         CREATE INDEX IF NOT EXISTS idx_sop_runs_key ON sop_runs(sop_name, idempotency_key);
+
+         CREATE TABLE IF NOT EXISTS sop_idempotency_keys (
+            sop_name         TEXT NOT NULL,
+            idempotency_key  TEXT NOT NULL,
+            run_id           TEXT NOT NULL,
+            created_at       TEXT NOT NULL,
+            PRIMARY KEY (sop_name, idempotency_key)
+         );
+         CREATE INDEX IF NOT EXISTS idx_sop_idempotency_created ON sop_idempotency_keys(created_at);",
+    )
+    .context("Failed to initialize SOP run journal schema")?;
+
+    // Journals written before the key table existed carry keys on the runs
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 1 {
+        conn.execute(
+            "INSERT OR IGNORE INTO sop_idempotency_keys (sop_name, idempotency_key, run_id, created_at)
+             SELECT sop_name, idempotency_key, run_id, ?1 FROM sop_runs
+             WHERE idempotency_key IS NOT NULL",
+            params![chrono::Utc::now().to_rfc3339()],
+        )
+        .context("Failed to backfill SOP idempotency keys")?;
+        conn.execute_batch("PRAGMA user_version = 1")?;
+    }
+
+    Ok(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sop::types::{SopEvent, SopRunFlow, SopRunStatus, SopTriggerSource};
+    use tempfile::TempDir;
+
+    fn run(run_id: &str, status: SopRunStatus, key: Option<&str>) -> SopRun {
+        SopRun {
+            run_id: run_id.into(),
+            sop_name: "deploy".into(),
+            trigger_event: SopEvent {
+                source: SopTriggerSource::Manual,
+                topic: None,
+                payload: None,
+                timestamp: "2026-01-01T00:00:00Z".into(),
+                idempotency_key: key.map(String::from),
+            },
+            status,
+            current_step: 1,
+            total_steps: 2,
+            started_at: format!("2026-01-01T00:00:0{}Z", &run_id[run_id.len() - 1..]),
+            completed_at: status
+                .is_terminal()
+                .then(|| format!("2026-01-01T00:01:0{}Z", &run_id[run_id.len() - 1..])),
+            step_results: Vec::new(),
+            waiting_since: None,
+            llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
+        }
+    }
+
+    #[test]
+    fn save_updates_and_splits_active_from_finished() {
+        let tmp = TempDir::new().unwrap();
+        let mut r1 = run("r1", SopRunStatus::WaitingApproval, None);
+        save_run(tmp.path(), &r1, 0).unwrap();
+        save_run(tmp.path(), &run("r2", SopRunStatus::Running, None), 0).unwrap();
+
+        assert_eq!(load_active_runs(tmp.path()).unwrap().len(), 2);
+
+        r1.status = SopRunStatus::Completed;
+        r1.completed_at = Some("2026-01-01T00:05:00Z".into());
+        save_run(tmp.path(), &r1, 0).unwrap();
+
+        let active = load_active_runs(tmp.path()).unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].run_id, "r2");
+        let finished = load_finished_runs(tmp.path(), 10).unwrap();
+        assert_eq!(finished[0].status, SopRunStatus::Completed);
+        assert_eq!(
+            get_run(tmp.path(), "r1").unwrap().unwrap().status,
+            SopRunStatus::Completed
+        );
+        assert!(get_run(tmp.path(), "missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn prunes_finished_runs_only() {
+        let tmp = TempDir::new().unwrap();
+        save_run(tmp.path(), &run("r0", SopRunStatus::Running, None), 2).unwrap();
+        for i in 1..=4 {
+            let r = run(&format!("r{i}"), SopRunStatus::Completed, None);
+            save_run(tmp.path(), &r, 2).unwrap();
+        }
+
+        let finished = load_finished_runs(tmp.path(), 10).unwrap();
+        let ids: Vec<&str> = finished.iter().map(|r| r.run_id.as_str()).collect();
+        assert_eq!(ids, vec!["r3", "r4"]);
+        assert_eq!(load_active_runs(tmp.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn list_filters_and_idempotency_lookup() {
+        let tmp = TempDir::new().unwrap();
+        save_run(
+            tmp.path(),
+            &run("r1", SopRunStatus::Failed, Some("evt-1")),
+            0,
+        )
+        .unwrap();
+        save_run(tmp.path(), &run("r2", SopRunStatus::Running, None), 0).unwrap();
+
+        let all = list_runs(tmp.path(), &RunFilter::default(), 10).unwrap();
+        assert_eq!(all[0].run_id, "r2", "newest first");
+        let active = RunFilter {
+            active_only: true,
+            ..RunFilter::default()
+        };
+        assert_eq!(list_runs(tmp.path(), &active, 10).unwrap().len(), 1);
+        let other = RunFilter {
+            sop_name: Some("other".into()),
+            ..RunFilter::default()
+        };
+        assert!(list_runs(tmp.path(), &other, 10).unwrap().is_empty());
+
+        assert_eq!(
+            find_by_idempotency_key(tmp.path(), "deploy", "evt-1").unwrap(),
+            Some("r1".into())
+        );
+        assert!(find_by_idempotency_key(tmp.path(), "other", "evt-1")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn idempotency_keys_outlive_pruned_runs() {
+        let tmp = TempDir::new().unwrap();
+        let journal = Arc::new(RunJournal::open(tmp.path()).unwrap());
+        journal.submit(run("r1", SopRunStatus::Completed, Some("evt-1")), 1);
+        journal.submit(run("r2", SopRunStatus::Completed, None), 1);
+        journal.flush();
+
+        assert!(get_run(tmp.path(), "r1").unwrap().is_none(), "r1 pruned");
+        assert_eq!(
+            journal.find_by_idempotency_key("deploy", "evt-1").unwrap(),
+            Some("r1".into())
+        );
+    }
+}
@@ -110,6 +110,9 @@ pub enum SopStepKind {
     Execute,
     /// Checkpoint step — pauses execution and waits for human approval.
     Checkpoint,
+    /// Compensation step — skipped in normal flow, run only to undo a
+    /// completed step after the run fails.
+    Compensation,
 }
 
 impl fmt::Display for SopStepKind {
@@ -117,6 +120,7 @@ impl fmt::Display for SopStepKind {
         match self {
             Self::Execute => write!(f, "execute"),
             Self::Checkpoint => write!(f, "checkpoint"),
+            Self::Compensation => write!(f, "compensation"),
         }
     }
 }
@@ -137,6 +141,66 @@ pub struct StepSchema {
     pub output: Option<serde_json::Value>,
 }
 
+// ── Step control flow ────────────────────────────────────────────
+
+/// Conditional jump evaluated against a step's output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepBranch {
+    /// Condition in `sop/condition.rs` syntax, e.g. `$.severity == "critical"`.
+    pub when: String,
+    /// Step number to continue with when the condition matches.
+    pub goto: u32,
+}
+
+/// Retry policy for a failing step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepRetry {
+    /// Retries allowed after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on every further retry.
+    #[serde(default)]
+    pub backoff_secs: u64,
+}
+
+impl StepRetry {
+    /// Backoff before retry number `retry` (1-based), capped at one hour.
+    pub fn backoff_for(&self, retry: u32) -> u64 {
+        let factor = 1u64 << retry.saturating_sub(1).min(16);
+        self.backoff_secs.saturating_mul(factor).min(3600)
+    }
+}
+
+/// Control-flow settings of a step. All fields are optional; a step with
+/// the default flow simply continues with the next step.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepFlow {
+    /// Conditional jumps, checked in order after the step completes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<StepBranch>,
+    /// Step to continue with when no branch matches (default: the next step).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<u32>,
+    /// Parallel group name. Consecutive steps sharing a group are fanned out
+    /// together and the run continues once all of them have completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel: Option<String>,
+    /// Retry policy applied when the step fails or times out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<StepRetry>,
+    /// Maximum seconds the step may run before it counts as failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Compensation step that undoes this step if the run later fails.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compensate_with: Option<u32>,
+}
+
+impl StepFlow {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 // ── Step ────────────────────────────────────────────────────────
 
 /// A single step in an SOP procedure, parsed from SOP.md.
@@ -155,6 +219,9 @@ pub struct SopStep {
     /// Typed input/output schemas for deterministic data flow validation.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub schema: Option<StepSchema>,
+    /// Branching, parallel fan-out, retry, timeout and compensation settings.
+    #[serde(default, skip_serializing_if = "StepFlow::is_default")]
+    pub flow: StepFlow,
 }
 
 // ── SOP ─────────────────────────────────────────────────────────
@@ -197,6 +264,19 @@ pub(crate) struct SopManifest {
     pub sop: SopMeta,
     #[serde(default)]
     pub triggers: Vec<SopTrigger>,
+    /// Per-step control flow overlaid on the steps parsed from SOP.md.
+    #[serde(default)]
+    pub steps: Vec<StepFlowOverride>,
+}
+
+/// A `[[steps]]` entry in SOP.toml, keyed by step number.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct StepFlowOverride {
+    pub number: u32,
+    #[serde(default)]
+    pub kind: Option<SopStepKind>,
+    #[serde(flatten)]
+    pub flow: StepFlow,
 }
 
 /// The `[sop]` table in SOP.toml.
@@ -260,6 +340,27 @@ pub struct SopEvent {
     pub payload: Option<String>,
     /// When the event occurred (ISO-8601).
     pub timestamp: String,
+    /// Caller-supplied key identifying this event. A replayed event with the
+    /// same key does not start a second run of the same SOP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+}
+
+impl SopEvent {
+    /// Key used to deduplicate replayed events: the explicit
+    /// `idempotency_key`, else a top-level `idempotency_key` string in a JSON
+    /// payload.
+    pub fn dedup_key(&self) -> Option<String> {
+        if let Some(ref key) = self.idempotency_key {
+            return Some(key.clone());
+        }
+        let payload: serde_json::Value = serde_json::from_str(self.payload.as_deref()?).ok()?;
+        payload
+            .get("idempotency_key")?
+            .as_str()
+            .filter(|k| !k.is_empty())
+            .map(String::from)
+    }
 }
 
 // ── Run state ────────────────────────────────────────────────────
@@ -273,11 +374,20 @@ pub enum SopRunStatus {
     WaitingApproval,
     /// Paused at a checkpoint in a deterministic workflow.
     PausedCheckpoint,
+    /// A step failed; compensation steps are undoing completed work.
+    Compensating,
     Completed,
     Failed,
     Cancelled,
 }
 
+impl SopRunStatus {
+    /// Whether the run has ended (completed, failed or cancelled).
+    pub fn is_terminal(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
 impl fmt::Display for SopRunStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -285,6 +395,7 @@ impl fmt::Display for SopRunStatus {
             Self::Running => write!(f, "running"),
             Self::WaitingApproval => write!(f, "waiting_approval"),
             Self::PausedCheckpoint => write!(f, "paused_checkpoint"),
+            Self::Compensating => write!(f, "compensating"),
             Self::Completed => write!(f, "completed"),
             Self::Failed => write!(f, "failed"),
             Self::Cancelled => write!(f, "cancelled"),
@@ -339,6 +450,61 @@ pub struct SopRun {
     /// Number of LLM calls saved by deterministic execution in this run.
     #[serde(default)]
     pub llm_calls_saved: u64,
+    /// Branch, fan-out, retry and compensation state.
+    #[serde(default)]
+    pub flow: SopRunFlow,
+}
+
+/// A step that has been dispatched and is awaiting its result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InFlightStep {
+    pub step_number: u32,
+    /// 1-based attempt number.
+    pub attempt: u32,
+    /// ISO-8601 timestamp when the attempt was dispatched (timeout base).
+    pub started_at: String,
+    /// Input piped to the step, reused when a deterministic step is retried.
+    #[serde(default)]
+    pub input: serde_json::Value,
+    /// A retry still waiting out its backoff; cleared once the engine tick
+    /// re-dispatches it.
+    #[serde(default)]
+    pub retry_pending: bool,
+}
+
+/// Control-flow bookkeeping for a run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SopRunFlow {
+    /// Steps awaiting a result; more than one during a parallel fan-out.
+    #[serde(default)]
+    pub in_flight: Vec<InFlightStep>,
+    /// Steps that completed and declare a compensation step, in completion order.
+    #[serde(default)]
+    pub compensable: Vec<u32>,
+    /// Compensation steps still to run after a failure, next one first.
+    #[serde(default)]
+    pub compensation_queue: Vec<u32>,
+    /// Reason the run failed, kept while compensation runs.
+    #[serde(default)]
+    pub failure_reason: Option<String>,
+    /// Steps jumped over by a branch or `next`.
+    #[serde(default)]
+    pub bypassed: Vec<u32>,
+    /// Retries issued across all steps.
+    #[serde(default)]
+    pub retries: u32,
+    /// Step attempts that hit their timeout.
+    #[serde(default)]
+    pub timeouts: u32,
+    /// Conditional branches taken.
+    #[serde(default)]
+    pub branches_taken: u32,
+    /// Parallel groups fanned out.
+    #[serde(default)]
+    pub fan_outs: u32,
+    /// Compensation steps executed.
+    #[serde(default)]
+    pub compensations_run: u32,
 }
 
 // ── Deterministic workflow state (persistence + resume) ──────────
@@ -363,6 +529,10 @@ pub struct DeterministicRunState {
     pub llm_calls_saved: u64,
     /// Whether the run is paused at a checkpoint awaiting approval.
     pub paused_at_checkpoint: bool,
+    /// Step the run is paused at. Resume continues after this step; older
+    /// state files without it resume at `last_completed_step + 1`.
+    #[serde(default)]
+    pub paused_step: Option<u32>,
 }
 
 // ── Cost savings metric ──────────────────────────────────────────
@@ -398,6 +568,21 @@ pub enum SopRunAction {
         step: SopStep,
         input: serde_json::Value,
     },
+    /// Run every action concurrently (a parallel group) and report each
+    /// step's result separately. The run continues once all have completed.
+    FanOut {
+        run_id: String,
+        actions: Vec<SopRunAction>,
+    },
+    /// The step failed and will be retried: wait `delay_secs`, then perform `action`.
+    Retry {
+        run_id: String,
+        attempt: u32,
+        delay_secs: u64,
+        action: Box<SopRunAction>,
+    },
+    /// A parallel step was recorded; the listed steps are still running.
+    AwaitParallel { run_id: String, pending: Vec<u32> },
     /// Deterministic workflow hit a checkpoint — pause for human approval.
     /// Workflow state has been persisted so it can resume after approval.
     CheckpointWait {
@@ -415,6 +600,30 @@ pub enum SopRunAction {
     },
 }
 
+impl SopRunAction {
+    /// Agent-facing description of the step(s) this action asks for.
+    /// Fan-outs join their branches; terminal actions yield an empty string.
+    pub fn step_context(&self) -> String {
+        match self {
+            Self::ExecuteStep { context, .. } | Self::WaitApproval { context, .. } => {
+                context.clone()
+            }
+            Self::DeterministicStep { step, .. } | Self::CheckpointWait { step, .. } => {
+                format!("Step {}: {}", step.number, step.title)
+            }
+            Self::FanOut { actions, .. } => actions
+                .iter()
+                .map(Self::step_context)
+                .collect::<Vec<_>>()
+                .join("\n---\n"),
+            Self::Retry { action, .. } => action.step_context(),
+            Self::AwaitParallel { .. } | Self::Completed { .. } | Self::Failed { .. } => {
+                String::new()
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -528,6 +737,7 @@ condition = "$.value > 85"
             persisted_at: "2026-03-01T00:00:00Z".into(),
             llm_calls_saved: 2,
             paused_at_checkpoint: true,
+            paused_step: Some(3),
         };
         let json = serde_json::to_string(&state).unwrap();
         let parsed: DeterministicRunState = serde_json::from_str(&json).unwrap();
@@ -596,6 +806,7 @@ path = "/sop/test"
             topic: Some("sensors/pressure".into()),
             payload: Some(r#"{"value": 87.3}"#.into()),
             timestamp: "2026-02-19T12:00:00Z".into(),
+            idempotency_key: None,
         };
         let json = serde_json::to_string(&event).unwrap();
         let parsed: SopEvent = serde_json::from_str(&json).unwrap();
@@ -613,6 +824,7 @@ path = "/sop/test"
                 topic: None,
                 payload: None,
                 timestamp: "2026-02-19T12:00:00Z".into(),
+                idempotency_key: None,
             },
             status: SopRunStatus::Running,
             current_step: 2,
@@ -628,6 +840,7 @@ path = "/sop/test"
             }],
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
         let json = serde_json::to_string(&run).unwrap();
         let parsed: SopRun = serde_json::from_str(&json).unwrap();
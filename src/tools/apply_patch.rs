@@ -0,0 +1,1359 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::workspace::WorkspaceManager;
+use crate::config::ToolRuleAction;
+use crate::security::{BoundaryVerdict, SecurityPolicy, WorkspaceBoundary};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Apply a multi-file patch atomically.
+///
+/// Accepts either standard unified diffs (`---`/`+++`/`@@`, including git
+/// rename headers) or the `*** Begin Patch` envelope with `Add File`,
+/// `Delete File`, `Update File` and `Move to` sections. Hunks are located
+/// exactly first, then with trailing-whitespace and whitespace-insensitive
+/// matching. Every path passes the same checks as
+/// [`super::file_edit::FileEditTool`] plus the active [`WorkspaceBoundary`],
+/// and `deny` rules in `[[autonomy.rules]]` are checked against every path,
+/// not only the one the executor sees in the call arguments.
+/// Nothing is written unless every hunk applies, and a failed write restores
+/// all files already touched.
+pub struct ApplyPatchTool {
+    security: Arc<SecurityPolicy>,
+    workspaces: Option<Arc<RwLock<WorkspaceManager>>>,
+}
+
+impl ApplyPatchTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            security,
+            workspaces: None,
+        }
+    }
+
+    /// Why a `deny` rule in `security.tool_rules` refuses writing `path`
+    /// (resolved to `target`), if any.
+    fn policy_denial(&self, path: &str, target: &Path) -> Option<String> {
+        let policy = &self.security.tool_rules;
+        let target = target.to_string_lossy();
+        let denial = [path, target.as_ref()].into_iter().find_map(|candidate| {
+            let decision = policy.evaluate(self.name(), &json!({ "path": candidate }))?;
+            (decision.action == ToolRuleAction::Deny)
+                .then(|| format!("Denied by policy for {path}: {}", decision.reason))
+        });
+        denial
+    }
+
+    /// Enforce the boundary of whichever workspace is active at call time.
+    pub fn with_workspaces(mut self, manager: Arc<RwLock<WorkspaceManager>>) -> Self {
+        self.workspaces = Some(manager);
+        self
+    }
+
+    async fn boundary(&self) -> (WorkspaceBoundary, PathBuf) {
+        let Some(manager) = &self.workspaces else {
+            return (WorkspaceBoundary::inactive(), PathBuf::new());
+        };
+        let manager = manager.read().await;
+        let base = manager.workspaces_dir().to_path_buf();
+        let base = tokio::fs::canonicalize(&base).await.unwrap_or(base);
+        // Patching writes, so the read-only cross-workspace exception never applies.
+        (
+            WorkspaceBoundary::new(manager.active_profile().cloned(), false),
+            base,
+        )
+    }
+
+    /// Run the per-path security checks and return the resolved target.
+    async fn resolve_target(
+        &self,
+        path: &str,
+        boundary: &WorkspaceBoundary,
+        workspaces_base: &Path,
+    ) -> Result<PathBuf, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+
+        let full_path = self.security.resolve_tool_path(path);
+        let file_name = full_path
+            .file_name()
+            .ok_or_else(|| format!("Invalid path: missing file name: {path}"))?
+            .to_owned();
+        let mut existing = full_path
+            .parent()
+            .ok_or_else(|| format!("Invalid path: missing parent directory: {path}"))?
+            .to_path_buf();
+
+        // New files may live in directories that do not exist yet: resolve the
+        // nearest existing ancestor and re-attach the missing components.
+        let mut missing = Vec::new();
+        let resolved_parent = loop {
+            match tokio::fs::canonicalize(&existing).await {
+                Ok(resolved) => break resolved,
+                Err(e) => {
+                    let Some(name) = existing.file_name().map(ToOwned::to_owned) else {
+                        return Err(format!("Failed to resolve file path {path}: {e}"));
+                    };
+                    missing.push(name);
+                    existing.pop();
+                }
+            }
+        };
+
+        if !self.security.is_resolved_path_allowed(&resolved_parent) {
+            return Err(self
+                .security
+                .resolved_path_violation_message(&resolved_parent));
+        }
+
+        let mut target = resolved_parent;
+        target.extend(missing.iter().rev());
+        target.push(file_name);
+
+        if self.security.is_runtime_config_path(&target) {
+            return Err(self.security.runtime_config_violation_message(&target));
+        }
+
+        if let Ok(meta) = tokio::fs::symlink_metadata(&target).await {
+            if meta.file_type().is_symlink() {
+                return Err(format!(
+                    "Refusing to patch through symlink: {}",
+                    target.display()
+                ));
+            }
+        }
+
+        if let BoundaryVerdict::Deny(reason) = boundary.check_path_access(&target, workspaces_base)
+        {
+            return Err(format!("Workspace boundary: {reason}"));
+        }
+
+        Ok(target)
+    }
+}
+
+#[async_trait]
+impl Tool for ApplyPatchTool {
+    fn name(&self) -> &str {
+        "apply_patch"
+    }
+
+    fn description(&self) -> &str {
+        "Apply a multi-file patch (unified diff or *** Begin Patch envelope) atomically; \
+         all files are left untouched if any hunk fails"
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "patch": {
+                    "type": "string",
+                    "description": "Unified diff (---/+++/@@ hunks) or a '*** Begin Patch' envelope using '*** Add File:', '*** Delete File:', '*** Update File:' and '*** Move to:' sections"
+                },
+                "dry_run": {
+                    "type": "boolean",
+                    "description": "Check that every hunk applies and report, without writing any file",
+                    "default": false
+                }
+            },
+            "required": ["patch"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let patch = args
+            .get("patch")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'patch' parameter"))?;
+        let dry_run = args
+            .get("dry_run")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+
+        if !self.security.can_act() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Action blocked: autonomy is read-only".into()),
+            });
+        }
+
+        if self.security.is_rate_limited() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+            });
+        }
+
+        let ops = match parse_patch(patch) {
+            Ok(ops) => ops,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Invalid patch: {e}")),
+                });
+            }
+        };
+
+        // ── Resolve and authorize every path before touching anything ──
+        let (boundary, workspaces_base) = self.boundary().await;
+        let mut seen = HashSet::new();
+        let mut resolved = Vec::with_capacity(ops.len());
+        for op in &ops {
+            let mut targets = Vec::with_capacity(2);
+            for path in op.paths() {
+                let target = match self.resolve_target(path, &boundary, &workspaces_base).await {
+                    Ok(target) => target,
+                    Err(e) => {
+                        return Ok(ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(e),
+                        });
+                    }
+                };
+                if let Some(denial) = self.policy_denial(path, &target) {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(denial),
+                    });
+                }
+                if !seen.insert(target.clone()) {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(format!("Path appears more than once in patch: {path}")),
+                    });
+                }
+                targets.push(target);
+            }
+            resolved.push(targets);
+        }
+
+        if !self.security.record_action() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some("Rate limit exceeded: action budget exhausted".into()),
+            });
+        }
+
+        // ── Stage every change in memory ──
+        let mut report = String::new();
+        let mut writes: Vec<(PathBuf, Option<String>)> = Vec::new();
+        let mut failures = 0usize;
+        for (op, targets) in ops.iter().zip(&resolved) {
+            match stage(op, targets, &mut report).await {
+                Ok(staged) => writes.extend(staged),
+                Err(()) => failures += 1,
+            }
+        }
+
+        if failures > 0 {
+            return Ok(ToolResult {
+                success: false,
+                output: report,
+                error: Some(format!(
+                    "Patch failed: {failures} of {} file(s) could not be patched; no files were modified",
+                    ops.len()
+                )),
+            });
+        }
+
+        if dry_run {
+            return Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Dry run: patch applies cleanly to {} file(s); nothing written\n{report}",
+                    ops.len()
+                ),
+                error: None,
+            });
+        }
+
+        match commit(&writes).await {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!("Applied patch to {} file(s)\n{report}", ops.len()),
+                error: None,
+            }),
+            Err(e) => Ok(ToolResult {
+                success: false,
+                output: report,
+                error: Some(format!("Failed to write {e}; all changes were rolled back")),
+            }),
+        }
+    }
+}
+
+// ── Patch model ────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatchLine {
+    Context(String),
+    Remove(String),
+    Add(String),
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Hunk {
+    /// 1-based start line from a `@@ -a,b +c,d @@` header, when present.
+    old_start: Option<usize>,
+    /// Free text after `@@` used to narrow the search (envelope format).
+    anchor: Option<String>,
+    /// Set by `*** End of File`: the hunk must match at the end of the file.
+    at_eof: bool,
+    lines: Vec<PatchLine>,
+}
+
+impl Hunk {
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| match line {
+                PatchLine::Context(text) | PatchLine::Remove(text) => Some(text.as_str()),
+                PatchLine::Add(_) => None,
+            })
+            .collect()
+    }
+
+    fn added_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().filter_map(|line| match line {
+            PatchLine::Add(text) => Some(text.as_str()),
+            _ => None,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FileOp {
+    Add {
+        path: String,
+        lines: Vec<String>,
+    },
+    Delete {
+        path: String,
+    },
+    Update {
+        path: String,
+        move_to: Option<String>,
+        hunks: Vec<Hunk>,
+    },
+}
+
+impl FileOp {
+    fn paths(&self) -> Vec<&str> {
+        match self {
+            Self::Add { path, .. } | Self::Delete { path } => vec![path],
+            Self::Update { path, move_to, .. } => std::iter::once(path.as_str())
+                .chain(move_to.as_deref())
+                .collect(),
+        }
+    }
+}
+
+// ── Parsing ────────────────────────────────────────────────────
+
+fn parse_patch(patch: &str) -> Result<Vec<FileOp>, String> {
+    let lines: Vec<&str> = patch
+        .lines()
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+    let ops = match lines.iter().position(|l| l.trim() == "*** Begin Patch") {
+        Some(begin) => parse_envelope(&lines[begin + 1..])?,
+        None => parse_unified(&lines)?,
+    };
+    if ops.is_empty() {
+        return Err("no file changes found".into());
+    }
+    Ok(ops)
+}
+
+/// Collects hunk body lines, dropping blank lines that trail a hunk (they
+/// are usually separators, not context).
+#[derive(Default)]
+struct HunkBody {
+    lines: Vec<PatchLine>,
+    pending_blanks: usize,
+}
+
+impl HunkBody {
+    /// Returns `false` when `raw` is not a hunk body line.
+    fn push(&mut self, raw: &str) -> bool {
+        let line = if raw.is_empty() {
+            self.pending_blanks += 1;
+            return true;
+        } else if raw.starts_with('\\') {
+            // "\ No newline at end of file": the original file ending is kept.
+            return true;
+        } else if let Some(text) = raw.strip_prefix(' ') {
+            PatchLine::Context(text.to_string())
+        } else if let Some(text) = raw.strip_prefix('-') {
+            PatchLine::Remove(text.to_string())
+        } else if let Some(text) = raw.strip_prefix('+') {
+            PatchLine::Add(text.to_string())
+        } else {
+            return false;
+        };
+        self.lines.extend(
+            std::iter::repeat_with(|| PatchLine::Context(String::new()))
+                .take(std::mem::take(&mut self.pending_blanks)),
+        );
+        self.lines.push(line);
+        true
+    }
+
+    fn finish(self) -> Vec<PatchLine> {
+        self.lines
+    }
+}
+
+fn parse_envelope(lines: &[&str]) -> Result<Vec<FileOp>, String> {
+    let mut ops = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        i += 1;
+        if line.trim() == "*** End Patch" {
+            return Ok(ops);
+        }
+        if let Some(path) = line.strip_prefix("*** Add File: ") {
+            let mut content = Vec::new();
+            while i < lines.len() && !lines[i].starts_with("*** ") {
+                match lines[i].strip_prefix('+') {
+                    Some(text) => content.push(text.to_string()),
+                    None if lines[i].is_empty() => content.push(String::new()),
+                    None => {
+                        return Err(format!(
+                            "added file {path}: expected '+' prefix, found {:?}",
+                            lines[i]
+                        ));
+                    }
+                }
+                i += 1;
+            }
+            ops.push(FileOp::Add {
+                path: path.trim().to_string(),
+                lines: content,
+            });
+        } else if let Some(path) = line.strip_prefix("*** Delete File: ") {
+            ops.push(FileOp::Delete {
+                path: path.trim().to_string(),
+            });
+        } else if let Some(path) = line.strip_prefix("*** Update File: ") {
+            let mut move_to = None;
+            if let Some(dest) = lines.get(i).and_then(|l| l.strip_prefix("*** Move to: ")) {
+                move_to = Some(dest.trim().to_string());
+                i += 1;
+            }
+            let hunks = parse_envelope_hunks(lines, &mut i)
+                .map_err(|e| format!("updated file {}: {e}", path.trim()))?;
+            if hunks.is_empty() && move_to.is_none() {
+                return Err(format!("updated file {} has no hunks", path.trim()));
+            }
+            ops.push(FileOp::Update {
+                path: path.trim().to_string(),
+                move_to,
+                hunks,
+            });
+        } else if !line.trim().is_empty() {
+            return Err(format!("unexpected line in patch envelope: {line:?}"));
+        }
+    }
+    Err("missing '*** End Patch'".into())
+}
+
+fn parse_envelope_hunks(lines: &[&str], i: &mut usize) -> Result<Vec<Hunk>, String> {
+    let mut hunks = Vec::new();
+    let mut current: Option<(Hunk, HunkBody)> = None;
+    while *i < lines.len() {
+        let line = lines[*i];
+        if line.trim() == "*** End of File" {
+            let (hunk, _) = current.get_or_insert_with(Default::default);
+            hunk.at_eof = true;
+            *i += 1;
+            continue;
+        }
+        if line.starts_with("*** ") {
+            break;
+        }
+        if let Some(header) = line.strip_prefix("@@") {
+            if let Some((hunk, body)) = current.take() {
+                hunks.push(Hunk {
+                    lines: body.finish(),
+                    ..hunk
+                });
+            }
+            current = Some((parse_hunk_header(header), HunkBody::default()));
+        } else {
+            let (_, body) = current.get_or_insert_with(Default::default);
+            if !body.push(line) {
+                return Err(format!("unexpected line in hunk: {line:?}"));
+            }
+        }
+        *i += 1;
+    }
+    if let Some((hunk, body)) = current {
+        hunks.push(Hunk {
+            lines: body.finish(),
+            ..hunk
+        });
+    }
+    hunks.retain(|h| !h.lines.is_empty());
+    Ok(hunks)
+}
+
+/// Parse the text after `@@`: either `-a,b +c,d @@ trailer` or a bare anchor.
+fn parse_hunk_header(header: &str) -> Hunk {
+    let header = header.trim();
+    let ranges = header
+        .strip_prefix('-')
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|old| old.split(',').next())
+        .and_then(|start| start.parse::<usize>().ok());
+    match ranges {
+        Some(old_start) => Hunk {
+            old_start: Some(old_start),
+            ..Hunk::default()
+        },
+        None => Hunk {
+            anchor: (!header.is_empty() && header != "@@").then(|| header.to_string()),
+            ..Hunk::default()
+        },
+    }
+}
+
+fn parse_unified(lines: &[&str]) -> Result<Vec<FileOp>, String> {
+    let mut ops = Vec::new();
+    let mut rename: (Option<String>, Option<String>) = (None, None);
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if line.starts_with("diff --git ") {
+            flush_rename(&mut rename, &mut ops);
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            rename.0 = Some(from.trim().to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            rename.1 = Some(to.trim().to_string());
+        } else if is_file_header(lines, i) {
+            let old = header_path(&line[4..]);
+            let new = header_path(&lines[i + 1][4..]);
+            rename = (None, None);
+            i += 2;
+            let hunks = parse_unified_hunks(lines, &mut i);
+            ops.push(match (old, new) {
+                (None, None) => return Err("both sides of a file header are /dev/null".into()),
+                (None, Some(path)) => FileOp::Add {
+                    lines: hunks
+                        .iter()
+                        .flat_map(Hunk::added_lines)
+                        .map(str::to_string)
+                        .collect(),
+                    path,
+                },
+                (Some(path), None) => FileOp::Delete { path },
+                (Some(path), Some(new)) => {
+                    if hunks.is_empty() {
+                        return Err(format!("file {path} has no hunks"));
+                    }
+                    FileOp::Update {
+                        move_to: (new != path).then_some(new),
+                        path,
+                        hunks,
+                    }
+                }
+            });
+            continue;
+        }
+        i += 1;
+    }
+    flush_rename(&mut rename, &mut ops);
+    Ok(ops)
+}
+
+/// A git rename with no content change has no `---`/`+++` header.
+fn flush_rename(rename: &mut (Option<String>, Option<String>), ops: &mut Vec<FileOp>) {
+    if let (Some(from), Some(to)) = std::mem::take(rename) {
+        ops.push(FileOp::Update {
+            path: from,
+            move_to: Some(to),
+            hunks: Vec::new(),
+        });
+    }
+}
+
+fn is_file_header(lines: &[&str], i: usize) -> bool {
+    lines[i].starts_with("--- ") && lines.get(i + 1).is_some_and(|l| l.starts_with("+++ "))
+}
+
+fn header_path(raw: &str) -> Option<String> {
+    let path = raw.split('\t').next().unwrap_or_default().trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+fn parse_unified_hunks(lines: &[&str], i: &mut usize) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut current: Option<(Hunk, HunkBody)> = None;
+    while *i < lines.len() {
+        let line = lines[*i];
+        if line.starts_with("diff --git ") || is_file_header(lines, *i) {
+            break;
+        }
+        if let Some(header) = line.strip_prefix("@@") {
+            if let Some((hunk, body)) = current.take() {
+                hunks.push(Hunk {
+                    lines: body.finish(),
+                    ..hunk
+                });
+            }
+            current = Some((parse_hunk_header(header), HunkBody::default()));
+        } else if !current.as_mut().is_some_and(|(_, body)| body.push(line)) {
+            break;
+        }
+        *i += 1;
+    }
+    if let Some((hunk, body)) = current {
+        hunks.push(Hunk {
+            lines: body.finish(),
+            ..hunk
+        });
+    }
+    hunks.retain(|h| !h.lines.is_empty());
+    hunks
+}
+
+// ── Applying ───────────────────────────────────────────────────
+
+/// How loosely a hunk's old lines were matched against the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fuzz {
+    Exact,
+    TrailingWhitespace,
+    Whitespace,
+}
+
+impl Fuzz {
+    const LEVELS: [Self; 3] = [Self::Exact, Self::TrailingWhitespace, Self::Whitespace];
+
+    fn matches(self, file_line: &str, patch_line: &str) -> bool {
+        match self {
+            Self::Exact => file_line == patch_line,
+            Self::TrailingWhitespace => file_line.trim_end() == patch_line.trim_end(),
+            Self::Whitespace => file_line
+                .split_whitespace()
+                .eq(patch_line.split_whitespace()),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Exact => "exact",
+            Self::TrailingWhitespace => "ignoring trailing whitespace",
+            Self::Whitespace => "ignoring whitespace",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum HunkOutcome {
+    Applied { line: usize, fuzz: Fuzz },
+    Failed(String),
+}
+
+/// File content split into lines, remembering its line ending style.
+struct FileText {
+    lines: Vec<String>,
+    crlf: bool,
+    trailing_newline: bool,
+}
+
+impl FileText {
+    fn parse(content: &str) -> Self {
+        let crlf = content.contains("\r\n");
+        let trailing_newline = content.ends_with('\n');
+        let body = content.strip_suffix('\n').unwrap_or(content);
+        let lines = if content.is_empty() {
+            Vec::new()
+        } else {
+            body.split('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+                .collect()
+        };
+        Self {
+            lines,
+            crlf,
+            trailing_newline,
+        }
+    }
+
+    fn render(&self, lines: &[String]) -> String {
+        if lines.is_empty() {
+            return String::new();
+        }
+        let eol = if self.crlf { "\r\n" } else { "\n" };
+        let mut out = lines.join(eol);
+        if self.trailing_newline || self.lines.is_empty() {
+            out.push_str(eol);
+        }
+        out
+    }
+}
+
+/// Locate every hunk in `lines` and return the patched lines if all of them
+/// applied, along with one outcome per hunk.
+fn apply_hunks(lines: &[String], hunks: &[Hunk]) -> (Option<Vec<String>>, Vec<HunkOutcome>) {
+    let mut outcomes = Vec::with_capacity(hunks.len());
+    let mut splices: Vec<(usize, usize, Vec<String>)> = Vec::new();
+    let mut cursor = 0;
+
+    for hunk in hunks {
+        let old = hunk.old_lines();
+        let mut start = cursor;
+        let mut anchor_line = None;
+        if let Some(anchor) = &hunk.anchor {
+            let found = (cursor..lines.len())
+                .find(|&idx| lines[idx].trim() == anchor.trim())
+                .or_else(|| (cursor..lines.len()).find(|&idx| lines[idx].contains(anchor.trim())));
+            match found {
+                Some(idx) => {
+                    start = idx;
+                    anchor_line = Some(idx);
+                }
+                None => {
+                    outcomes.push(HunkOutcome::Failed(format!(
+                        "anchor {anchor:?} not found after line {cursor}"
+                    )));
+                    continue;
+                }
+            }
+        }
+
+        let expected = hunk.old_start.map(|line| {
+            if old.is_empty() {
+                line
+            } else {
+                line.saturating_sub(1)
+            }
+        });
+
+        if old.is_empty() {
+            // Pure insertion: no context to match against.
+            let pos = if hunk.at_eof {
+                lines.len()
+            } else if let Some(idx) = anchor_line {
+                idx + 1
+            } else {
+                expected.unwrap_or(lines.len()).clamp(cursor, lines.len())
+            };
+            splices.push((pos, 0, hunk.added_lines().map(str::to_string).collect()));
+            outcomes.push(HunkOutcome::Applied {
+                line: pos + 1,
+                fuzz: Fuzz::Exact,
+            });
+            cursor = pos;
+            continue;
+        }
+
+        match locate(lines, &old, start, expected, hunk.at_eof) {
+            Some((pos, fuzz)) => {
+                let mut replacement = Vec::new();
+                let mut offset = 0;
+                for line in &hunk.lines {
+                    match line {
+                        // Keep the file's own text for fuzzily matched context.
+                        PatchLine::Context(_) => {
+                            replacement.push(lines[pos + offset].clone());
+                            offset += 1;
+                        }
+                        PatchLine::Remove(_) => offset += 1,
+                        PatchLine::Add(text) => replacement.push(text.clone()),
+                    }
+                }
+                splices.push((pos, old.len(), replacement));
+                outcomes.push(HunkOutcome::Applied {
+                    line: pos + 1,
+                    fuzz,
+                });
+                cursor = pos + old.len();
+            }
+            None => outcomes.push(HunkOutcome::Failed(diagnose(lines, &old, expected))),
+        }
+    }
+
+    if outcomes.iter().any(|o| matches!(o, HunkOutcome::Failed(_))) {
+        return (None, outcomes);
+    }
+
+    let mut patched = lines.to_vec();
+    for (pos, len, replacement) in splices.into_iter().rev() {
+        patched.splice(pos..pos + len, replacement);
+    }
+    (Some(patched), outcomes)
+}
+
+/// Find where `old` matches at or after `start`, trying each fuzz level in
+/// turn and preferring the candidate closest to the expected line.
+fn locate(
+    lines: &[String],
+    old: &[&str],
+    start: usize,
+    expected: Option<usize>,
+    at_eof: bool,
+) -> Option<(usize, Fuzz)> {
+    let last = lines.len().checked_sub(old.len())?;
+    if start > last {
+        return None;
+    }
+    let range = if at_eof { last..=last } else { start..=last };
+    for fuzz in Fuzz::LEVELS {
+        let mut candidates = range.clone().filter(|&pos| {
+            old.iter()
+                .enumerate()
+                .all(|(k, line)| fuzz.matches(&lines[pos + k], line))
+        });
+        let best = match expected {
+            Some(expected) => candidates.min_by_key(|pos| pos.abs_diff(expected)),
+            None => candidates.next(),
+        };
+        if let Some(pos) = best {
+            return Some((pos, fuzz));
+        }
+    }
+    None
+}
+
+/// Explain why a hunk did not match by showing the closest candidate.
+fn diagnose(lines: &[String], old: &[&str], expected: Option<usize>) -> String {
+    let near = expected.map_or(String::new(), |line| format!(" near line {}", line + 1));
+    let Some(last) = lines.len().checked_sub(old.len()) else {
+        return format!(
+            "hunk needs {} line(s) but the file has only {}",
+            old.len(),
+            lines.len()
+        );
+    };
+    let score = |pos: usize| {
+        old.iter()
+            .enumerate()
+            .filter(|(k, line)| Fuzz::Whitespace.matches(&lines[pos + k], line))
+            .count()
+    };
+    let best = (0..=last)
+        .max_by_key(|&pos| {
+            (
+                score(pos),
+                std::cmp::Reverse(pos.abs_diff(expected.unwrap_or(0))),
+            )
+        })
+        .unwrap_or(0);
+    match old
+        .iter()
+        .enumerate()
+        .find(|(k, line)| !Fuzz::Whitespace.matches(&lines[best + k], line))
+    {
+        Some((k, line)) => format!(
+            "context not found{near}; closest match at line {} differs at line {}: expected {:?}, found {:?}",
+            best + 1,
+            best + k + 1,
+            line,
+            lines[best + k]
+        ),
+        None => format!("context not found{near}; it only matches before an earlier hunk"),
+    }
+}
+
+/// Validate one file operation and stage its writes, appending to `report`.
+/// Returns `Err(())` once the failure has been reported.
+async fn stage(
+    op: &FileOp,
+    targets: &[PathBuf],
+    report: &mut String,
+) -> Result<Vec<(PathBuf, Option<String>)>, ()> {
+    match op {
+        FileOp::Add { path, lines } => {
+            if tokio::fs::symlink_metadata(&targets[0]).await.is_ok() {
+                let _ = writeln!(report, "A {path}: FAILED (file already exists)");
+                return Err(());
+            }
+            let _ = writeln!(report, "A {path} ({} lines)", lines.len());
+            let mut content = lines.join("\n");
+            if !lines.is_empty() {
+                content.push('\n');
+            }
+            Ok(vec![(targets[0].clone(), Some(content))])
+        }
+        FileOp::Delete { path } => match tokio::fs::metadata(&targets[0]).await {
+            Ok(meta) if meta.is_file() => {
+                let _ = writeln!(report, "D {path}");
+                Ok(vec![(targets[0].clone(), None)])
+            }
+            Ok(_) => {
+                let _ = writeln!(report, "D {path}: FAILED (not a regular file)");
+                Err(())
+            }
+            Err(e) => {
+                let _ = writeln!(report, "D {path}: FAILED ({e})");
+                Err(())
+            }
+        },
+        FileOp::Update {
+            path,
+            move_to,
+            hunks,
+        } => {
+            let label = match move_to {
+                Some(dest) => format!("R {path} -> {dest}"),
+                None => format!("M {path}"),
+            };
+            let content = match tokio::fs::read_to_string(&targets[0]).await {
+                Ok(content) => content,
+                Err(e) => {
+                    let _ = writeln!(report, "{label}: FAILED (cannot read: {e})");
+                    return Err(());
+                }
+            };
+            if let Some(dest) = targets.get(1) {
+                if tokio::fs::symlink_metadata(dest).await.is_ok() {
+                    let _ = writeln!(report, "{label}: FAILED (destination already exists)");
+                    return Err(());
+                }
+            }
+
+            let text = FileText::parse(&content);
+            let (patched, outcomes) = apply_hunks(&text.lines, hunks);
+            let _ = writeln!(
+                report,
+                "{label}{}",
+                if patched.is_some() { "" } else { ": FAILED" }
+            );
+            for (idx, outcome) in outcomes.iter().enumerate() {
+                let _ = match outcome {
+                    HunkOutcome::Applied { line, fuzz } if *fuzz == Fuzz::Exact => {
+                        writeln!(report, "  hunk {}: applied at line {line}", idx + 1)
+                    }
+                    HunkOutcome::Applied { line, fuzz } => writeln!(
+                        report,
+                        "  hunk {}: applied at line {line} ({})",
+                        idx + 1,
+                        fuzz.label()
+                    ),
+                    HunkOutcome::Failed(reason) => {
+                        writeln!(report, "  hunk {}: FAILED: {reason}", idx + 1)
+                    }
+                };
+            }
+            let patched = patched.ok_or(())?;
+            let rendered = text.render(&patched);
+            Ok(match targets.get(1) {
+                Some(dest) => vec![(dest.clone(), Some(rendered)), (targets[0].clone(), None)],
+                None => vec![(targets[0].clone(), Some(rendered))],
+            })
+        }
+    }
+}
+
+/// Write staged changes in order; on the first error restore every file
+/// already touched and remove directories created along the way.
+async fn commit(writes: &[(PathBuf, Option<String>)]) -> Result<(), String> {
+    let mut snapshots = Vec::with_capacity(writes.len());
+    let mut created_dirs = Vec::new();
+
+    for (path, content) in writes {
+        snapshots.push((path.clone(), tokio::fs::read(path).await.ok()));
+        let result = match content {
+            Some(text) => match create_parents(path, &mut created_dirs).await {
+                Ok(()) => tokio::fs::write(path, text).await,
+                Err(e) => Err(e),
+            },
+            None => tokio::fs::remove_file(path).await,
+        };
+        if let Err(e) = result {
+            rollback(&snapshots, &created_dirs).await;
+            return Err(format!("{}: {e}", path.display()));
+        }
+    }
+    Ok(())
+}
+
+async fn create_parents(path: &Path, created: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    let mut missing = Vec::new();
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if tokio::fs::symlink_metadata(current).await.is_ok() {
+            break;
+        }
+        missing.push(current.to_path_buf());
+        dir = current.parent();
+    }
+    for dir in missing.into_iter().rev() {
+        tokio::fs::create_dir(&dir).await?;
+        created.push(dir);
+    }
+    Ok(())
+}
+
+async fn rollback(snapshots: &[(PathBuf, Option<Vec<u8>>)], created_dirs: &[PathBuf]) {
+    for (path, original) in snapshots.iter().rev() {
+        let result = match original {
+            Some(bytes) => tokio::fs::write(path, bytes).await,
+            None => match tokio::fs::remove_file(path).await {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                other => other,
+            },
+        };
+        if let Err(e) = result {
+            tracing::warn!("apply_patch rollback failed for {}: {e}", path.display());
+        }
+    }
+    for dir in created_dirs.iter().rev() {
+        let _ = tokio::fs::remove_dir(dir).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::tool_rules::ToolPolicy;
+    use crate::security::AutonomyLevel;
+
+    fn test_security(workspace: PathBuf) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: workspace,
+            ..SecurityPolicy::default()
+        })
+    }
+
+    async fn run(dir: &Path, patch: &str) -> ToolResult {
+        ApplyPatchTool::new(test_security(dir.to_path_buf()))
+            .execute(json!({ "patch": patch }))
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn parses_envelope_operations() {
+        let ops = parse_patch(
+            "*** Begin Patch\n\
+             *** Add File: new.txt\n\
+             +hello\n\
+             *** Delete File: old.txt\n\
+             *** Update File: src/a.rs\n\
+             *** Move to: src/b.rs\n\
+             @@ fn main\n\
+             -let x = 1;\n\
+             +let x = 2;\n\
+             *** End of File\n\
+             *** End Patch\n",
+        )
+        .unwrap();
+        assert_eq!(ops.len(), 3);
+        assert_eq!(
+            ops[0],
+            FileOp::Add {
+                path: "new.txt".into(),
+                lines: vec!["hello".into()],
+            }
+        );
+        let FileOp::Update { move_to, hunks, .. } = &ops[2] else {
+            panic!("expected update");
+        };
+        assert_eq!(move_to.as_deref(), Some("src/b.rs"));
+        assert_eq!(hunks[0].anchor.as_deref(), Some("fn main"));
+        assert!(hunks[0].at_eof);
+    }
+
+    #[test]
+    fn parses_unified_diff_with_git_headers() {
+        let ops = parse_patch(
+            "diff --git a/x.txt b/x.txt\n\
+             index 123..456 100644\n\
+             --- a/x.txt\n\
+             +++ b/x.txt\n\
+             @@ -2,3 +2,3 @@ header\n \
+             b\n\
+             -c\n\
+             +C\n \
+             d\n\
+             \n\
+             diff --git a/old.txt b/new.txt\n\
+             similarity index 100%\n\
+             rename from old.txt\n\
+             rename to new.txt\n",
+        )
+        .unwrap();
+        assert_eq!(ops.len(), 2);
+        let FileOp::Update { path, hunks, .. } = &ops[0] else {
+            panic!("expected update");
+        };
+        assert_eq!(path, "x.txt");
+        assert_eq!(hunks[0].old_start, Some(2));
+        // The blank separator line is not treated as trailing context.
+        assert_eq!(hunks[0].lines.len(), 4);
+        assert!(matches!(&ops[1], FileOp::Update { move_to: Some(dest), .. } if dest == "new.txt"));
+    }
+
+    #[test]
+    fn fuzzy_matching_prefers_exact_and_reports_level() {
+        let lines: Vec<String> = ["fn a() {", "    x();  ", "}"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        let hunk = Hunk {
+            lines: vec![
+                PatchLine::Context("fn a() {".into()),
+                PatchLine::Remove("    x();".into()),
+                PatchLine::Add("    y();".into()),
+            ],
+            ..Hunk::default()
+        };
+        let (patched, outcomes) = apply_hunks(&lines, &[hunk]);
+        assert_eq!(patched.unwrap()[1], "    y();");
+        assert_eq!(
+            outcomes[0],
+            HunkOutcome::Applied {
+                line: 1,
+                fuzz: Fuzz::TrailingWhitespace
+            }
+        );
+    }
+
+    #[test]
+    fn failed_hunk_reports_closest_mismatch() {
+        let lines: Vec<String> = ["a", "b", "c"].iter().map(ToString::to_string).collect();
+        let hunk = Hunk {
+            old_start: Some(1),
+            lines: vec![
+                PatchLine::Context("a".into()),
+                PatchLine::Remove("x".into()),
+            ],
+            ..Hunk::default()
+        };
+        let (patched, outcomes) = apply_hunks(&lines, &[hunk]);
+        assert!(patched.is_none());
+        let HunkOutcome::Failed(reason) = &outcomes[0] else {
+            panic!("expected failure");
+        };
+        assert!(reason.contains("line 2"), "{reason}");
+        assert!(reason.contains("\"x\""), "{reason}");
+    }
+
+    #[tokio::test]
+    async fn applies_multi_file_patch() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("a.txt"), "one\ntwo\nthree\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("gone.txt"), "bye\n")
+            .await
+            .unwrap();
+
+        let result = run(
+            dir.path(),
+            "*** Begin Patch\n\
+             *** Update File: a.txt\n\
+             @@\n \
+             one\n\
+             -two\n\
+             +TWO\n\
+             *** Add File: nested/dir/new.txt\n\
+             +fresh\n\
+             *** Delete File: gone.txt\n\
+             *** End Patch",
+        )
+        .await;
+
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("hunk 1: applied at line 1"));
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("a.txt"))
+                .await
+                .unwrap(),
+            "one\nTWO\nthree\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("nested/dir/new.txt"))
+                .await
+                .unwrap(),
+            "fresh\n"
+        );
+        assert!(!dir.path().join("gone.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn deny_rule_on_any_path_refuses_the_whole_patch() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("a.txt"), "one\n")
+            .await
+            .unwrap();
+        let policy = ToolPolicy::from_rules(&[crate::config::ToolRuleConfig {
+            tool: "apply_patch".into(),
+            action: ToolRuleAction::Deny,
+            reason: Some("secrets stay put".into()),
+            paths: vec!["secrets/**".into()],
+            hosts: Vec::new(),
+            recipient_domains: Vec::new(),
+            git_subcommands: Vec::new(),
+        }])
+        .unwrap()
+        .with_workspace_dir(dir.path());
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            workspace_dir: dir.path().to_path_buf(),
+            tool_rules: Arc::new(policy),
+            ..SecurityPolicy::default()
+        });
+
+        let result = ApplyPatchTool::new(security)
+            .execute(json!({
+                "patch": "*** Begin Patch\n\
+                          *** Update File: a.txt\n\
+                          @@\n\
+                          -one\n\
+                          +ONE\n\
+                          *** Add File: secrets/key.pem\n\
+                          +leaked\n\
+                          *** End Patch"
+            }))
+            .await
+            .unwrap();
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("secrets stay put"));
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("a.txt"))
+                .await
+                .unwrap(),
+            "one\n"
+        );
+        assert!(!dir.path().join("secrets").exists());
+    }
+
+    #[tokio::test]
+    async fn failing_hunk_leaves_every_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("a.txt"), "one\ntwo\n")
+            .await
+            .unwrap();
+        tokio::fs::write(dir.path().join("b.txt"), "alpha\nbeta\n")
+            .await
+            .unwrap();
+
+        let result = run(
+            dir.path(),
+            "--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n\
+             --- a/b.txt\n+++ b/b.txt\n@@ -1,2 +1,2 @@\n alpha\n-gamma\n+delta\n",
+        )
+        .await;
+
+        assert!(!result.success);
+        assert!(result.output.contains("M a.txt\n  hunk 1: applied"));
+        assert!(result.output.contains("M b.txt: FAILED"));
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("a.txt"))
+                .await
+                .unwrap(),
+            "one\ntwo\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn write_error_rolls_back_earlier_files() {
+        let dir = tempfile::tempdir().unwrap();
+        tokio::fs::write(dir.path().join("a.txt"), "one\r\ntwo\r\n")
+            .await
+            .unwrap();
+        // A regular file where a directory is needed makes the second write fail.
+        tokio::fs::write(dir.path().join("blocker"), "")
+            .await
+            .unwrap();
+
+        let result = run(
+            dir.path(),
+            "*** Begin Patch\n\
+             *** Update File: a.txt\n\
+             -two\n\
+             +2\n\
+             *** Add File: blocker/new.txt\n\
+             +x\n\
+             *** End Patch",
+        )
+        .await;
+
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("rolled back"));
+        assert_eq!(
+            tokio::fs::read_to_string(dir.path().join("a.txt"))
+                .await
+                .unwrap(),
+            "one\r\ntwo\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_workspace_and_read_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = run(
+            dir.path(),
+            "*** Begin Patch\n*** Add File: /etc/evil\n+x\n*** End Patch",
+        )
+        .await;
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+
+        let tool = ApplyPatchTool::new(Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::ReadOnly,
+            workspace_dir: dir.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        }));
+        let result = tool
+            .execute(json!({ "patch": "*** Begin Patch\n*** Add File: a\n+x\n*** End Patch" }))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("read-only"));
+    }
+
+    #[tokio::test]
+    async fn workspace_boundary_blocks_other_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("workspaces");
+        for name in ["alpha", "beta"] {
+            tokio::fs::create_dir_all(base.join(name)).await.unwrap();
+            tokio::fs::write(
+                base.join(name).join("profile.toml"),
+                format!("name = \"{name}\"\n"),
+            )
+            .await
+            .unwrap();
+        }
+        let mut manager = WorkspaceManager::new(base.clone());
+        manager.load_profiles().await.unwrap();
+        manager.switch("alpha").unwrap();
+
+        let tool = ApplyPatchTool::new(test_security(dir.path().to_path_buf()))
+            .with_workspaces(Arc::new(RwLock::new(manager)));
+        let patch = |path: &str| json!({ "patch": format!("*** Begin Patch\n*** Add File: {path}\n+x\n*** End Patch") });
+
+        let denied = tool.execute(patch("workspaces/beta/x.txt")).await.unwrap();
+        assert!(denied.error.unwrap().contains("Workspace boundary"));
+
+        let allowed = tool.execute(patch("workspaces/alpha/x.txt")).await.unwrap();
+        assert!(allowed.success, "{:?}", allowed.error);
+    }
+}
@@ -1,7 +1,8 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
 use crate::cron::{
-    self, deserialize_maybe_stringified, DeliveryConfig, JobType, Schedule, SessionTarget,
+    self, deserialize_maybe_stringified, CronJobPatch, DeliveryConfig, JobType, Schedule,
+    SessionTarget,
 };
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -102,7 +103,8 @@ impl Tool for CronAddTool {
                             "description": "Repeating interval schedule in milliseconds. Example: {\"kind\":\"every\",\"every_ms\":3600000} runs every hour.",
                             "properties": {
                                 "kind": { "type": "string", "enum": ["every"] },
-                                "every_ms": { "type": "integer", "description": "Interval in milliseconds, e.g. 3600000 for every hour" }
+                                "every_ms": { "type": "integer", "description": "Interval in milliseconds, e.g. 3600000 for every hour" },
+                                "jitter_ms": { "type": "integer", "description": "Optional random delay of up to this many milliseconds added to each run; must be less than every_ms" }
                             },
                             "required": ["kind", "every_ms"]
                         }
@@ -163,6 +165,30 @@ impl Tool for CronAddTool {
                     "type": "boolean",
                     "description": "If true, the job is automatically deleted after its first successful run. Defaults to true for 'at' schedules."
                 },
+                "depends_on": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Job IDs whose last run must have succeeded before this job runs on schedule; otherwise the run is recorded as skipped"
+                },
+                "on_success": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Job IDs to run as soon as this job succeeds"
+                },
+                "on_failure": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Job IDs to run as soon as this job fails or times out"
+                },
+                "concurrency_policy": {
+                    "type": "string",
+                    "enum": ["allow", "forbid", "replace"],
+                    "description": "When triggered while a previous run is still going: 'allow' overlaps, 'forbid' skips the new run, 'replace' kills the old run"
+                },
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Kill a run that takes longer than this many seconds. Shell jobs default to 120; agent jobs are unbounded unless set."
+                },
                 "approved": {
                     "type": "boolean",
                     "description": "Set true to explicitly approve medium/high-risk shell commands in supervised mode",
@@ -248,6 +274,29 @@ impl Tool for CronAddTool {
             },
             None => None,
         };
+        let options = match serde_json::from_value::<CronJobPatch>(json!({
+            "depends_on": args.get("depends_on"),
+            "on_success": args.get("on_success"),
+            "on_failure": args.get("on_failure"),
+            "concurrency_policy": args.get("concurrency_policy"),
+            "timeout_secs": args.get("timeout_secs"),
+        })) {
+            Ok(options) => options,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Invalid job options: {e}")),
+                });
+            }
+        };
+        if let Err(e) = cron::validate_job_references(&self.config, &options) {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(e.to_string()),
+            });
+        }
 
         let result = match job_type {
             JobType::Shell => {
@@ -350,6 +399,7 @@ impl Tool for CronAddTool {
                 )
             }
         };
+        let result = result.and_then(|job| cron::apply_job_options(&self.config, job, options));
 
         match result {
             Ok(job) => Ok(ToolResult {
@@ -361,7 +411,12 @@ impl Tool for CronAddTool {
                     "schedule": job.schedule,
                     "next_run": job.next_run,
                     "enabled": job.enabled,
-                    "allowed_tools": job.allowed_tools
+                    "allowed_tools": job.allowed_tools,
+                    "depends_on": job.depends_on,
+                    "on_success": job.on_success,
+                    "on_failure": job.on_failure,
+                    "concurrency_policy": job.concurrency_policy,
+                    "timeout_secs": job.timeout_secs
                 }))?,
                 error: None,
             }),
@@ -700,6 +755,50 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn persists_chaining_concurrency_and_timeout() {
+        let tmp = TempDir::new().unwrap();
+        let cfg = test_config(&tmp).await;
+        let tool = CronAddTool::new(cfg.clone(), test_security(&cfg));
+        let upstream = cron::add_job(&cfg, "*/5 * * * *", "echo upstream").unwrap();
+
+        let result = tool
+            .execute(json!({
+                "schedule": { "kind": "every", "every_ms": 60000, "jitter_ms": 5000 },
+                "command": "echo downstream",
+                "depends_on": [upstream.id],
+                "concurrency_policy": "forbid",
+                "timeout_secs": 30
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let output: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        let job = cron::get_job(&cfg, output["id"].as_str().unwrap()).unwrap();
+        assert_eq!(job.depends_on, vec![upstream.id]);
+        assert_eq!(job.concurrency_policy, cron::ConcurrencyPolicy::Forbid);
+        assert_eq!(job.timeout_secs, Some(30));
+        assert!(matches!(
+            job.schedule,
+            Schedule::Every {
+                jitter_ms: 5000,
+                ..
+            }
+        ));
+
+        let missing = tool
+            .execute(json!({
+                "schedule": { "kind": "cron", "expr": "*/5 * * * *" },
+                "command": "echo orphan",
+                "on_success": ["no-such-job"]
+            }))
+            .await
+            .unwrap();
+        assert!(!missing.success);
+        assert_eq!(cron::list_jobs(&cfg).unwrap().len(), 2);
+    }
+
     #[tokio::test]
     async fn empty_allowed_tools_stored_as_none() {
         let tmp = TempDir::new().unwrap();
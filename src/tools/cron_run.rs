@@ -1,6 +1,6 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::Config;
-use crate::cron::{self, JobType};
+use crate::cron::{self, JobType, RunStatus};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use chrono::Utc;
@@ -116,11 +116,12 @@ impl Tool for CronRunTool {
         }
 
         let started_at = Utc::now();
-        let (success, output) =
+        let (run_status, output) =
             Box::pin(cron::scheduler::execute_job_now(&self.config, &job)).await;
         let finished_at = Utc::now();
         let duration_ms = (finished_at - started_at).num_milliseconds();
-        let status = if success { "ok" } else { "error" };
+        let success = run_status.is_success();
+        let status = run_status.as_str();
 
         let _ = cron::record_run(
             &self.config,
@@ -128,10 +129,13 @@ impl Tool for CronRunTool {
             started_at,
             finished_at,
             status,
+            "manual",
             Some(&output),
             duration_ms,
         );
-        let _ = cron::record_last_run(&self.config, &job.id, finished_at, success, &output);
+        if !matches!(run_status, RunStatus::Skipped | RunStatus::Replaced) {
+            let _ = cron::record_last_run(&self.config, &job.id, finished_at, success, &output);
+        }
 
         Ok(ToolResult {
             success,
@@ -27,6 +27,7 @@ struct RunView {
     status: String,
     output: Option<String>,
     duration_ms: Option<i64>,
+    trigger: String,
 }
 
 #[async_trait]
@@ -87,6 +88,7 @@ impl Tool for CronRunsTool {
                         status: run.status,
                         output: run.output.map(|out| truncate(&out, MAX_RUN_OUTPUT_CHARS)),
                         duration_ms: run.duration_ms,
+                        trigger: run.trigger,
                     })
                     .collect();
 
@@ -147,6 +149,7 @@ mod tests {
             now,
             now + ChronoDuration::milliseconds(1),
             "ok",
+            "schedule",
             Some(&long_output),
             1,
         )
@@ -54,7 +54,7 @@ impl Tool for CronUpdateTool {
     }
 
     fn description(&self) -> &str {
-        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, etc.)"
+        "Patch an existing cron job (schedule, command, prompt, enabled, delivery, model, chaining, concurrency policy, timeout, etc.)"
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -103,6 +103,30 @@ impl Tool for CronUpdateTool {
                             "type": "boolean",
                             "description": "If true, delete the job automatically after its first successful run"
                         },
+                        "depends_on": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Job IDs whose last run must have succeeded before this job runs on schedule; otherwise the run is recorded as skipped"
+                        },
+                        "on_success": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Job IDs to run as soon as this job succeeds"
+                        },
+                        "on_failure": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Job IDs to run as soon as this job fails or times out"
+                        },
+                        "concurrency_policy": {
+                            "type": "string",
+                            "enum": ["allow", "forbid", "replace"],
+                            "description": "When triggered while a previous run is still going: 'allow' overlaps, 'forbid' skips the new run, 'replace' kills the old run"
+                        },
+                        "timeout_secs": {
+                            "type": "integer",
+                            "description": "Kill a run that takes longer than this many seconds. Shell jobs default to 120; agent jobs are unbounded unless set. 0 clears it."
+                        },
                         // NOTE: oneOf is correct for OpenAI-compatible APIs (including OpenRouter).
                         // Gemini does not support oneOf in tool schemas; if Gemini native tool calling
                         // is ever wired up, SchemaCleanr::clean_for_gemini must be applied before
@@ -134,7 +158,8 @@ impl Tool for CronUpdateTool {
                                     "description": "Repeating interval schedule in milliseconds. Example: {\"kind\":\"every\",\"every_ms\":3600000} runs every hour.",
                                     "properties": {
                                         "kind": { "type": "string", "enum": ["every"] },
-                                        "every_ms": { "type": "integer", "description": "Interval in milliseconds, e.g. 3600000 for every hour" }
+                                        "every_ms": { "type": "integer", "description": "Interval in milliseconds, e.g. 3600000 for every hour" },
+                                        "jitter_ms": { "type": "integer", "description": "Optional random delay of up to this many milliseconds added to each run; must be less than every_ms" }
                                     },
                                     "required": ["kind", "every_ms"]
                                 }
@@ -413,6 +438,11 @@ mod tests {
             "delete_after_run",
             "schedule",
             "delivery",
+            "depends_on",
+            "on_success",
+            "on_failure",
+            "concurrency_policy",
+            "timeout_secs",
         ] {
             assert!(
                 patch_props.contains_key(*field),
@@ -2,6 +2,7 @@ use super::traits::{Tool, ToolResult};
 use crate::agent::loop_::run_tool_call_loop;
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
 use crate::config::{DelegateAgentConfig, DelegateToolConfig};
+use crate::observability::trace_context::{self, SpanKind, TraceSpan};
 use crate::observability::traits::{Observer, ObserverEvent, ObserverMetric};
 use crate::providers::{self, ChatMessage, Provider};
 use crate::security::policy::ToolOperation;
@@ -347,12 +348,40 @@ impl Tool for DelegateTool {
 }
 
 impl DelegateTool {
-    /// Original synchronous delegation path (extracted for reuse).
+    /// Synchronous delegation, traced as an `invoke_agent` span so the
+    /// sub-agent's turn nests under the calling tool.
     async fn execute_sync(
         &self,
         agent_name: &str,
         prompt: &str,
         args: &serde_json::Value,
+    ) -> anyhow::Result<ToolResult> {
+        let mut span = TraceSpan::start(format!("invoke_agent {agent_name}"), SpanKind::Internal)
+            .with_attribute("gen_ai.operation.name", "invoke_agent")
+            .with_attribute("gen_ai.agent.name", agent_name);
+        if let Some(agent_config) = self.agents.get(agent_name) {
+            span.set_attribute("gen_ai.provider.name", agent_config.provider.as_str());
+            span.set_attribute("gen_ai.request.model", agent_config.model.as_str());
+        }
+        let result = span
+            .scope(self.delegate_to_agent(agent_name, prompt, args))
+            .await;
+        match &result {
+            Ok(tool_result) if !tool_result.success => {
+                span.set_error(tool_result.error.clone().unwrap_or_default());
+            }
+            Err(e) => span.set_error(e.to_string()),
+            Ok(_) => {}
+        }
+        result
+    }
+
+    /// Original synchronous delegation path (extracted for reuse).
+    async fn delegate_to_agent(
+        &self,
+        agent_name: &str,
+        prompt: &str,
+        args: &serde_json::Value,
     ) -> anyhow::Result<ToolResult> {
         let context = args
             .get("context")
@@ -612,8 +641,9 @@ impl DelegateTool {
         let workspace_dir = self.workspace_dir.clone();
         let child_token = self.cancellation_token.child_token();
         let task_id_clone = task_id.clone();
+        let trace_parent = trace_context::current();
 
-        tokio::spawn(async move {
+        tokio::spawn(trace_context::scope_with(trace_parent, async move {
             // Build an inner DelegateTool for the spawned context
             let inner = DelegateTool {
                 agents,
@@ -685,7 +715,7 @@ impl DelegateTool {
             if let Ok(bytes) = serde_json::to_vec_pretty(&final_result) {
                 let _ = tokio::fs::write(&result_path, &bytes).await;
             }
-        });
+        }));
 
         Ok(ToolResult {
             success: true,
@@ -770,23 +800,28 @@ impl DelegateTool {
             let agent_name = agent_name.clone();
             let prompt = prompt.to_string();
             let args_clone = args.clone();
-
-            handles.push(tokio::spawn(async move {
-                let inner = DelegateTool {
-                    agents,
-                    security,
-                    fallback_credential,
-                    provider_runtime_options,
-                    depth,
-                    parent_tools,
-                    multimodal_config,
-                    delegate_config,
-                    workspace_dir,
-                    cancellation_token,
-                };
-                let result = Box::pin(inner.execute_sync(&agent_name, &prompt, &args_clone)).await;
-                (agent_name, result)
-            }));
+            let trace_parent = trace_context::current();
+
+            handles.push(tokio::spawn(trace_context::scope_with(
+                trace_parent,
+                async move {
+                    let inner = DelegateTool {
+                        agents,
+                        security,
+                        fallback_credential,
+                        provider_runtime_options,
+                        depth,
+                        parent_tools,
+                        multimodal_config,
+                        delegate_config,
+                        workspace_dir,
+                        cancellation_token,
+                    };
+                    let result =
+                        Box::pin(inner.execute_sync(&agent_name, &prompt, &args_clone)).await;
+                    (agent_name, result)
+                },
+            )));
         }
 
         // Collect all results
@@ -1131,6 +1166,7 @@ impl DelegateTool {
                 temperature,
                 true,
                 None,
+                Some(&self.security.tool_rules),
                 "delegate",
                 None,
                 &self.multimodal_config,
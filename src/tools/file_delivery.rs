@@ -0,0 +1,253 @@
+//! Delivery of tool-generated files to messaging channels.
+//!
+//! File-producing tools (`image_gen`, `screenshot`, `report_template`) accept
+//! `deliver`, `channel` and `recipient` arguments. When a tool runs inside a
+//! channel conversation the agent loop fills in `channel` and `recipient`, so
+//! `deliver: true` sends the file straight back to the chat it came from.
+//! Any other destination is off-conversation: the agent loop routes it
+//! through approval unless an `allow` rule in `[[autonomy.rules]]` matches
+//! the call, and refuses it when no operator can approve. Each delivery
+//! counts against the hourly action budget. Delivery shares the late-bound
+//! channel map used by the poll tool.
+
+use super::ChannelMapHandle;
+use crate::channels::traits::{Channel, OutboundAttachment, SendMessage};
+use crate::config::ToolRuleAction;
+use crate::security::tool_rules::ToolPolicy;
+use crate::security::SecurityPolicy;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Tools whose files can be delivered to a channel.
+pub(crate) const FILE_DELIVERY_TOOLS: &[&str] = &["image_gen", "screenshot", "report_template"];
+
+/// JSON schema properties shared by file-delivering tools.
+pub(crate) fn delivery_schema_properties() -> serde_json::Map<String, serde_json::Value> {
+    let mut properties = serde_json::Map::new();
+    properties.insert(
+        "deliver".into(),
+        json!({
+            "type": "boolean",
+            "description": "Send the file to the user as an attachment (default: false). In a chat conversation it goes back to the same chat."
+        }),
+    );
+    properties.insert(
+        "channel".into(),
+        json!({
+            "type": "string",
+            "description": "Channel to deliver to. Defaults to the current conversation's channel; other destinations need approval."
+        }),
+    );
+    properties.insert(
+        "recipient".into(),
+        json!({
+            "type": "string",
+            "description": "Recipient or chat ID on the channel. Defaults to the current conversation."
+        }),
+    );
+    properties
+}
+
+/// Whether the caller asked for the file to be delivered.
+pub(crate) fn delivery_requested(args: &serde_json::Value) -> bool {
+    args.get("deliver")
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn target_arg(args: &serde_json::Value, key: &str) -> Option<String> {
+    args.get(key)
+        .and_then(serde_json::Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// The `channel:recipient` a delivery call targets outside the current
+/// conversation, unless an `allow` rule matches the call.
+///
+/// `None` also covers calls that deliver nothing or name no channel.
+pub(crate) fn off_conversation_target(
+    rules: Option<&ToolPolicy>,
+    tool_name: &str,
+    args: &serde_json::Value,
+    channel_name: &str,
+    reply_target: Option<&str>,
+) -> Option<String> {
+    if !FILE_DELIVERY_TOOLS.contains(&tool_name) || !delivery_requested(args) {
+        return None;
+    }
+    let channel = target_arg(args, "channel")?;
+    let recipient = target_arg(args, "recipient").unwrap_or_default();
+    if channel == channel_name && Some(recipient.as_str()) == reply_target.map(str::trim) {
+        return None;
+    }
+    let allowlisted = rules
+        .and_then(|rules| rules.evaluate(tool_name, args))
+        .is_some_and(|decision| decision.action == ToolRuleAction::Allow);
+    (!allowlisted).then(|| format!("{channel}:{recipient}"))
+}
+
+/// Deliver `attachment` to the channel and recipient named in `args`.
+///
+/// Returns a line for the tool output. Delivery failures are reported rather
+/// than failing the tool, since the file itself was produced.
+pub(crate) async fn deliver_attachment(
+    security: &SecurityPolicy,
+    channels: &ChannelMapHandle,
+    args: &serde_json::Value,
+    caption: &str,
+    attachment: OutboundAttachment,
+) -> String {
+    let Some(channel_name) = target_arg(args, "channel") else {
+        return "Not delivered: no channel in this context (pass `channel` and `recipient`)."
+            .to_string();
+    };
+    let Some(recipient) = target_arg(args, "recipient") else {
+        return format!("Not delivered: no recipient for channel '{channel_name}'.");
+    };
+    if !security.record_action() {
+        return "Not delivered: rate limit exceeded (too many actions in the last hour)."
+            .to_string();
+    }
+
+    // Clone out of the map so the RwLock guard is dropped before the send.
+    let channel: Option<Arc<dyn Channel>> = channels.read().get(&channel_name).cloned();
+    let Some(channel) = channel else {
+        return format!("Not delivered: channel '{channel_name}' is not running.");
+    };
+
+    let file_name = attachment.file_name.clone();
+    let message = SendMessage::new(caption, recipient).with_attachments(vec![attachment]);
+    match channel.send_with_attachments(&message).await {
+        Ok(()) if channel.supports_attachments() => {
+            format!("Delivered {file_name} to {channel_name}.")
+        }
+        Ok(()) => format!(
+            "Sent a link to {file_name} on {channel_name} (the channel does not support file uploads)."
+        ),
+        Err(e) => {
+            tracing::warn!(channel = %channel_name, "File delivery failed: {e}");
+            format!("Not delivered: sending {file_name} to {channel_name} failed: {e}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::ChannelMessage;
+    use async_trait::async_trait;
+    use parking_lot::RwLock;
+    use std::collections::HashMap;
+
+    struct UploadChannel(parking_lot::Mutex<Vec<SendMessage>>);
+
+    #[async_trait]
+    impl Channel for UploadChannel {
+        fn name(&self) -> &str {
+            "upload"
+        }
+
+        fn supports_attachments(&self) -> bool {
+            true
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.0.lock().push(message.clone());
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn deliver_attachment_sends_to_named_channel() {
+        let channel = Arc::new(UploadChannel(parking_lot::Mutex::new(Vec::new())));
+        let mut map: HashMap<String, Arc<dyn Channel>> = HashMap::new();
+        map.insert("upload".into(), channel.clone());
+        let handle: ChannelMapHandle = Arc::new(RwLock::new(map));
+
+        let args = json!({ "deliver": true, "channel": "upload", "recipient": "chat-1" });
+        assert!(delivery_requested(&args));
+        let note = deliver_attachment(
+            &SecurityPolicy::default(),
+            &handle,
+            &args,
+            "Your chart",
+            OutboundAttachment::new("chart.png", vec![1, 2, 3]),
+        )
+        .await;
+
+        assert_eq!(note, "Delivered chart.png to upload.");
+        let sent = channel.0.lock();
+        assert_eq!(sent[0].recipient, "chat-1");
+        assert_eq!(sent[0].attachments[0].file_name, "chart.png");
+    }
+
+    #[tokio::test]
+    async fn deliver_attachment_reports_missing_context() {
+        let security = SecurityPolicy::default();
+        let handle: ChannelMapHandle = Arc::new(RwLock::new(HashMap::new()));
+        let attachment = || OutboundAttachment::new("chart.png", vec![]);
+
+        let args = json!({ "deliver": true });
+        let note = deliver_attachment(&security, &handle, &args, "", attachment()).await;
+        assert!(note.starts_with("Not delivered: no channel"));
+
+        let args = json!({ "channel": "telegram", "recipient": "42" });
+        assert!(!delivery_requested(&args));
+        let note = deliver_attachment(&security, &handle, &args, "", attachment()).await;
+        assert_eq!(note, "Not delivered: channel 'telegram' is not running.");
+
+        let limited = SecurityPolicy {
+            max_actions_per_hour: 0,
+            ..SecurityPolicy::default()
+        };
+        let note = deliver_attachment(&limited, &handle, &args, "", attachment()).await;
+        assert!(note.starts_with("Not delivered: rate limit exceeded"));
+    }
+
+    #[test]
+    fn other_conversations_are_off_conversation_unless_allowed() {
+        let to = |channel: &str, recipient: &str| json!({ "deliver": true, "channel": channel, "recipient": recipient });
+        let current = |args: &serde_json::Value| {
+            off_conversation_target(None, "screenshot", args, "telegram", Some("42"))
+        };
+
+        assert_eq!(current(&to("telegram", "42")), None);
+        assert_eq!(
+            current(&json!({ "channel": "slack", "recipient": "x" })),
+            None
+        );
+        assert_eq!(
+            current(&to("telegram", "43")).as_deref(),
+            Some("telegram:43")
+        );
+        assert_eq!(current(&to("slack", "42")).as_deref(), Some("slack:42"));
+
+        let rules = ToolPolicy::compile(&[crate::config::ToolRuleConfig {
+            tool: "screenshot".into(),
+            action: ToolRuleAction::Allow,
+            reason: None,
+            paths: Vec::new(),
+            hosts: Vec::new(),
+            recipient_domains: Vec::new(),
+            git_subcommands: Vec::new(),
+        }]);
+        let args = to("slack", "42");
+        assert_eq!(
+            off_conversation_target(Some(&rules), "screenshot", &args, "cli", None),
+            None
+        );
+        assert_eq!(
+            off_conversation_target(Some(&rules), "image_gen", &args, "cli", None).as_deref(),
+            Some("slack:42")
+        );
+    }
+}
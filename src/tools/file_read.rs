@@ -4,7 +4,7 @@ use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
 
-const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
+pub(crate) const MAX_FILE_SIZE_BYTES: u64 = 10 * 1024 * 1024;
 
 /// Read file contents with path sandboxing
 pub struct FileReadTool {
@@ -621,7 +621,7 @@ impl Tool for GitOperationsTool {
                 });
             }
 
-            match self.security.autonomy {
+            match self.security.autonomy_for_tool(self.name()) {
                 AutonomyLevel::ReadOnly => {
                     return Ok(ToolResult {
                         success: false,
@@ -1,9 +1,14 @@
+use super::file_delivery;
 use super::traits::{Tool, ToolResult};
+use super::ChannelMapHandle;
+use crate::channels::traits::OutboundAttachment;
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use anyhow::Context;
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use serde_json::json;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -11,12 +16,14 @@ use std::sync::Arc;
 ///
 /// Reads the API key from an environment variable (default: `FAL_API_KEY`),
 /// calls the fal.ai synchronous endpoint, downloads the resulting image,
-/// and saves it to `{workspace}/images/{filename}.png`.
+/// and saves it to `{workspace}/images/{filename}.png`. With `deliver: true`
+/// the image is also sent to the conversation's channel.
 pub struct ImageGenTool {
     security: Arc<SecurityPolicy>,
     workspace_dir: PathBuf,
     default_model: String,
     api_key_env: String,
+    channels: ChannelMapHandle,
 }
 
 impl ImageGenTool {
@@ -31,9 +38,16 @@ impl ImageGenTool {
             workspace_dir,
             default_model,
             api_key_env,
+            channels: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Share the live channel map used for `deliver: true`.
+    pub fn with_channel_map(mut self, channels: ChannelMapHandle) -> Self {
+        self.channels = channels;
+        self
+    }
+
     /// Build a reusable HTTP client with reasonable timeouts.
     fn http_client() -> reqwest::Client {
         reqwest::Client::builder()
@@ -213,19 +227,35 @@ impl ImageGenTool {
 
         let size_kb = bytes.len() / 1024;
 
+        let mut output = format!(
+            "Image generated successfully.\n\
+             File: {}\n\
+             Size: {} KB\n\
+             Model: {}\n\
+             Prompt: {}",
+            output_path.display(),
+            size_kb,
+            model,
+            prompt,
+        );
+        if file_delivery::delivery_requested(&args) {
+            let attachment = OutboundAttachment::new(format!("{safe_name}.png"), bytes.to_vec())
+                .with_link(output_path.display().to_string());
+            let note = file_delivery::deliver_attachment(
+                &self.security,
+                &self.channels,
+                &args,
+                "",
+                attachment,
+            )
+            .await;
+            output.push('\n');
+            output.push_str(&note);
+        }
+
         Ok(ToolResult {
             success: true,
-            output: format!(
-                "Image generated successfully.\n\
-                 File: {}\n\
-                 Size: {} KB\n\
-                 Model: {}\n\
-                 Prompt: {}",
-                output_path.display(),
-                size_kb,
-                model,
-                prompt,
-            ),
+            output,
             error: None,
         })
     }
@@ -243,7 +273,7 @@ impl Tool for ImageGenTool {
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
-        json!({
+        let mut schema = json!({
             "type": "object",
             "required": ["prompt"],
             "properties": {
@@ -265,7 +295,11 @@ impl Tool for ImageGenTool {
                     "description": "fal.ai model identifier (default: 'fal-ai/flux/schnell')."
                 }
             }
-        })
+        });
+        if let Some(properties) = schema["properties"].as_object_mut() {
+            properties.extend(file_delivery::delivery_schema_properties());
+        }
+        schema
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
@@ -1,11 +1,14 @@
 //! Lightweight LLM task tool for structured JSON-only sub-calls.
 //!
 //! Runs a single prompt through an LLM provider with no tool access and
-//! optionally validates the response against a caller-supplied JSON Schema.
+//! optionally constrains the response to a caller-supplied JSON Schema, using
+//! native structured output where available and validate-and-repair retries
+//! otherwise (see [`crate::providers::structured`]).
 //! Ideal for structured data extraction in workflows.
 
 use super::traits::{Tool, ToolResult};
-use crate::providers::{self, Provider};
+use crate::providers::structured::{self, StructuredOutputError};
+use crate::providers::{self, ChatMessage, Provider, ResponseFormat};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -126,21 +129,6 @@ impl Tool for LlmTaskTool {
             .and_then(|v| v.as_f64())
             .unwrap_or(self.default_temperature);
 
-        // Build the effective prompt, adding JSON schema instructions when needed
-        let effective_prompt = if let Some(schema_obj) = schema {
-            let schema_json =
-                serde_json::to_string_pretty(&serde_json::Value::Object(schema_obj.clone()))
-                    .unwrap_or_else(|_| "{}".to_string());
-            format!(
-                "{prompt}\n\n\
-                 IMPORTANT: You MUST respond with valid JSON that conforms to this schema:\n\
-                 ```json\n{schema_json}\n```\n\
-                 Respond ONLY with the JSON object, no explanation or markdown."
-            )
-        } else {
-            prompt.to_string()
-        };
-
         // Create provider
         let api_key_ref = self.api_key.as_deref();
         let provider: Box<dyn Provider> = match providers::create_provider_with_options(
@@ -158,130 +146,65 @@ impl Tool for LlmTaskTool {
             }
         };
 
-        // Make the LLM call (no tools, no agent loop)
-        let response = match provider
-            .simple_chat(&effective_prompt, model, temperature)
-            .await
-        {
-            Ok(text) => text,
-            Err(e) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!("LLM call failed: {e}")),
-                });
-            }
-        };
-
-        // If schema was provided, validate the response
-        if let Some(schema_obj) = schema {
-            let schema_value = serde_json::Value::Object(schema_obj.clone());
-            match validate_json_response(&response, &schema_value) {
-                Ok(validated_json) => Ok(ToolResult {
+        // Without a schema this is a plain text call (no tools, no agent loop).
+        let Some(schema_obj) = schema else {
+            return match provider.simple_chat(prompt, model, temperature).await {
+                Ok(text) => Ok(ToolResult {
                     success: true,
-                    output: validated_json,
+                    output: text,
                     error: None,
                 }),
-                Err(validation_error) => Ok(ToolResult {
+                Err(e) => Ok(ToolResult {
                     success: false,
-                    output: response,
-                    error: Some(format!("Schema validation failed: {validation_error}")),
+                    output: String::new(),
+                    error: Some(format!("LLM call failed: {e}")),
                 }),
-            }
-        } else {
-            Ok(ToolResult {
+            };
+        };
+
+        // With a schema, use native structured output where the provider has
+        // it and validate-and-repair otherwise.
+        let format =
+            ResponseFormat::json_schema("llm_task", serde_json::Value::Object(schema_obj.clone()));
+        let messages = [ChatMessage::user(prompt)];
+        match structured::chat_structured(provider.as_ref(), &messages, &format, model, temperature)
+            .await
+        {
+            Ok(value) => Ok(ToolResult {
                 success: true,
-                output: response,
+                output: value.to_string(),
                 error: None,
-            })
-        }
-    }
-}
-
-/// Validate a JSON response string against a JSON Schema value.
-///
-/// Performs lightweight validation: parses the response as JSON, checks that
-/// required fields exist, and verifies basic type constraints (string, number,
-/// integer, boolean, array, object) for each declared property.
-fn validate_json_response(response: &str, schema: &serde_json::Value) -> Result<String, String> {
-    // Strip markdown code fences if the LLM wrapped the response
-    let trimmed = response.trim();
-    let json_str = if trimmed.starts_with("```") {
-        let inner = trimmed
-            .trim_start_matches("```json")
-            .trim_start_matches("```")
-            .trim_end_matches("```")
-            .trim();
-        inner
-    } else {
-        trimmed
-    };
-
-    // Parse as JSON
-    let parsed: serde_json::Value =
-        serde_json::from_str(json_str).map_err(|e| format!("Invalid JSON: {e}"))?;
-
-    // Check required fields
-    if let Some(required) = schema.get("required").and_then(|v| v.as_array()) {
-        for req in required {
-            if let Some(field_name) = req.as_str() {
-                if parsed.get(field_name).is_none() {
-                    return Err(format!("Missing required field: {field_name}"));
-                }
-            }
-        }
-    }
-
-    // Check property types
-    if let Some(properties) = schema.get("properties").and_then(|v| v.as_object()) {
-        for (prop_name, prop_schema) in properties {
-            if let Some(value) = parsed.get(prop_name) {
-                if let Some(expected_type) = prop_schema.get("type").and_then(|t| t.as_str()) {
-                    if !type_matches(value, expected_type) {
-                        return Err(format!(
-                            "Field '{prop_name}' has wrong type: expected {expected_type}, \
-                             got {}",
-                            json_type_name(value)
-                        ));
-                    }
-                }
-            }
+            }),
+            Err(StructuredOutputError::Invalid {
+                reason,
+                last_response,
+                ..
+            }) => Ok(ToolResult {
+                success: false,
+                output: last_response,
+                error: Some(format!("Schema validation failed: {reason}")),
+            }),
+            Err(StructuredOutputError::Provider(e)) => Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(format!("LLM call failed: {e}")),
+            }),
         }
     }
-
-    // Return the cleaned, re-serialized JSON
-    serde_json::to_string(&parsed).map_err(|e| format!("JSON serialization error: {e}"))
-}
-
-/// Check whether a JSON value matches an expected JSON Schema type string.
-fn type_matches(value: &serde_json::Value, expected: &str) -> bool {
-    match expected {
-        "string" => value.is_string(),
-        "number" => value.is_number(),
-        "integer" => value.is_i64() || value.is_u64(),
-        "boolean" => value.is_boolean(),
-        "array" => value.is_array(),
-        "object" => value.is_object(),
-        "null" => value.is_null(),
-        _ => true, // Unknown type — accept
-    }
-}
-
-/// Return a human-readable type name for a JSON value.
-fn json_type_name(value: &serde_json::Value) -> &'static str {
-    match value {
-        serde_json::Value::Null => "null",
-        serde_json::Value::Bool(_) => "boolean",
-        serde_json::Value::Number(_) => "number",
-        serde_json::Value::String(_) => "string",
-        serde_json::Value::Array(_) => "array",
-        serde_json::Value::Object(_) => "object",
-    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::structured::type_matches;
+
+    fn validate_json_response(
+        response: &str,
+        schema: &serde_json::Value,
+    ) -> Result<String, String> {
+        let format = ResponseFormat::json_schema("llm_task", schema.clone());
+        structured::parse_and_validate(response, &format).map(|value| value.to_string())
+    }
 
     // ── Schema validation tests ──────────────────────────────────────
 
@@ -2,7 +2,8 @@
 //!
 //! Supports multiple transports: stdio (spawn local process), HTTP, and SSE.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
 #[cfg(not(target_has_atomic = "64"))]
 use std::sync::atomic::AtomicU32;
 #[cfg(target_has_atomic = "64")]
@@ -16,9 +17,13 @@ use tokio::sync::Mutex;
 use tokio::time::{timeout, Duration};
 
 use crate::config::schema::McpServerConfig;
+use crate::config::Config;
 use crate::tools::mcp_protocol::{
-    JsonRpcRequest, McpToolDef, McpToolsListResult, MCP_PROTOCOL_VERSION,
+    JsonRpcRequest, McpGetPromptResult, McpPromptDef, McpPromptsListResult, McpReadResourceResult,
+    McpResourceContents, McpResourceDef, McpResourcesListResult, McpToolDef, McpToolsListResult,
+    MCP_PROTOCOL_VERSION,
 };
+use crate::tools::mcp_sampling::{McpClientHandler, McpSampler};
 use crate::tools::mcp_transport::{create_transport, McpTransportConn};
 
 /// Timeout for receiving a response from an MCP server during init/list.
@@ -31,6 +36,9 @@ const DEFAULT_TOOL_TIMEOUT_SECS: u64 = 180;
 /// Maximum allowed tool call timeout (seconds) — hard safety ceiling.
 const MAX_TOOL_TIMEOUT_SECS: u64 = 600;
 
+/// Per-resource character cap for `context_resources` injected into the prompt.
+const MAX_CONTEXT_RESOURCE_CHARS: usize = 8_000;
+
 // ── Internal server state ──────────────────────────────────────────────────
 
 struct McpServerInner {
@@ -41,6 +49,66 @@ struct McpServerInner {
     #[cfg(not(target_has_atomic = "64"))]
     next_id: AtomicU32,
     tools: Vec<McpToolDef>,
+    resources: Vec<McpResourceDef>,
+    prompts: Vec<McpPromptDef>,
+    /// `capabilities` object from the server's initialize result.
+    capabilities: serde_json::Value,
+}
+
+impl McpServerInner {
+    fn next_id(&self) -> u64 {
+        self.next_id.fetch_add(1, Ordering::Relaxed) as u64
+    }
+
+    /// Send a request and return its result, bounded by [`RECV_TIMEOUT_SECS`].
+    async fn request(
+        &mut self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let req = JsonRpcRequest::new(self.next_id(), method, params);
+        let resp = timeout(
+            Duration::from_secs(RECV_TIMEOUT_SECS),
+            self.transport.send_and_recv(&req),
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "MCP server `{}` timed out after {}s waiting for {method} response",
+                self.config.name, RECV_TIMEOUT_SECS
+            )
+        })??;
+        if let Some(err) = resp.error {
+            bail!(
+                "MCP server `{}` {method} error {}: {}",
+                self.config.name,
+                err.code,
+                err.message
+            );
+        }
+        Ok(resp.result.unwrap_or(serde_json::Value::Null))
+    }
+
+    async fn refresh_resources(&mut self) -> Result<()> {
+        let result = self.request("resources/list", json!({})).await?;
+        let list: McpResourcesListResult = serde_json::from_value(result).with_context(|| {
+            format!("failed to parse resources/list from `{}`", self.config.name)
+        })?;
+        self.resources = list.resources;
+        Ok(())
+    }
+
+    async fn refresh_prompts(&mut self) -> Result<()> {
+        let result = self.request("prompts/list", json!({})).await?;
+        let list: McpPromptsListResult = serde_json::from_value(result)
+            .with_context(|| format!("failed to parse prompts/list from `{}`", self.config.name))?;
+        self.prompts = list.prompts;
+        Ok(())
+    }
+
+    fn has_capability(&self, name: &str) -> bool {
+        self.capabilities.get(name).is_some()
+    }
 }
 
 // ── McpServer ──────────────────────────────────────────────────────────────
@@ -49,11 +117,23 @@ struct McpServerInner {
 #[derive(Clone)]
 pub struct McpServer {
     inner: Arc<Mutex<McpServerInner>>,
+    /// URIs the server reported as changed via `notifications/resources/updated`.
+    updated_resources: Arc<std::sync::Mutex<HashSet<String>>>,
 }
 
 impl McpServer {
     /// Connect to the server, perform the initialize handshake, and fetch the tool list.
     pub async fn connect(config: McpServerConfig) -> Result<Self> {
+        Self::connect_with_sampler(config, None).await
+    }
+
+    /// Like [`connect`](Self::connect), additionally fetching resources and
+    /// prompts when advertised. `sampler` services `sampling/createMessage`
+    /// if the server's sampling budget is enabled.
+    pub async fn connect_with_sampler(
+        config: McpServerConfig,
+        sampler: Option<Arc<McpSampler>>,
+    ) -> Result<Self> {
         // Create transport based on config
         let mut transport = create_transport(&config).with_context(|| {
             format!(
@@ -62,6 +142,20 @@ impl McpServer {
             )
         })?;
 
+        let updated_resources = Arc::new(std::sync::Mutex::new(HashSet::new()));
+        let handler = McpClientHandler::new(
+            config.name.clone(),
+            &config.sampling,
+            sampler,
+            Arc::clone(&updated_resources),
+        );
+        let client_capabilities = if handler.supports_sampling() {
+            json!({ "sampling": {} })
+        } else {
+            json!({})
+        };
+        transport.set_message_handler(Arc::new(handler));
+
         // Initialize handshake
         let id = 1u64;
         let init_req = JsonRpcRequest::new(
@@ -69,7 +163,7 @@ impl McpServer {
             "initialize",
             json!({
                 "protocolVersion": MCP_PROTOCOL_VERSION,
-                "capabilities": {},
+                "capabilities": client_capabilities,
                 "clientInfo": {
                     "name": "zeroclaw",
                     "version": env!("CARGO_PKG_VERSION")
@@ -96,6 +190,12 @@ impl McpServer {
                 init_resp.error
             );
         }
+        let capabilities = init_resp
+            .result
+            .as_ref()
+            .and_then(|r| r.get("capabilities"))
+            .cloned()
+            .unwrap_or_else(|| json!({}));
 
         // Notify server that client is initialized (no response expected for notifications)
         // For notifications, we send but don't wait for response
@@ -127,7 +227,7 @@ impl McpServer {
 
         let tool_count = tool_list.tools.len();
 
-        let inner = McpServerInner {
+        let mut inner = McpServerInner {
             config,
             transport,
             #[cfg(target_has_atomic = "64")]
@@ -135,16 +235,35 @@ impl McpServer {
             #[cfg(not(target_has_atomic = "64"))]
             next_id: AtomicU32::new(3), // Start at 3 since we used 1 and 2
             tools: tool_list.tools,
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            capabilities,
         };
 
+        // Resources and prompts are optional extras — a failure here must not
+        // cost us the server's tools.
+        if inner.has_capability("resources") {
+            if let Err(e) = inner.refresh_resources().await {
+                tracing::warn!("MCP server `{}`: {e:#}", inner.config.name);
+            }
+        }
+        if inner.has_capability("prompts") {
+            if let Err(e) = inner.refresh_prompts().await {
+                tracing::warn!("MCP server `{}`: {e:#}", inner.config.name);
+            }
+        }
+
         tracing::info!(
-            "MCP server `{}` connected — {} tool(s) available",
+            "MCP server `{}` connected — {} tool(s), {} resource(s), {} prompt(s) available",
             inner.config.name,
-            tool_count
+            tool_count,
+            inner.resources.len(),
+            inner.prompts.len()
         );
 
         Ok(Self {
             inner: Arc::new(Mutex::new(inner)),
+            updated_resources,
         })
     }
 
@@ -158,6 +277,87 @@ impl McpServer {
         self.inner.lock().await.config.name.clone()
     }
 
+    /// Whether the server advertised the `resources` capability.
+    pub async fn supports_resources(&self) -> bool {
+        self.inner.lock().await.has_capability("resources")
+    }
+
+    /// Prompt templates advertised by this server.
+    pub async fn prompts(&self) -> Vec<McpPromptDef> {
+        self.inner.lock().await.prompts.clone()
+    }
+
+    /// Re-fetch and return the server's resource list.
+    pub async fn list_resources(&self) -> Result<Vec<McpResourceDef>> {
+        let mut inner = self.inner.lock().await;
+        inner.refresh_resources().await?;
+        Ok(inner.resources.clone())
+    }
+
+    /// Read a resource by URI. Clears its "updated" flag.
+    pub async fn read_resource(&self, uri: &str) -> Result<Vec<McpResourceContents>> {
+        let result = self
+            .inner
+            .lock()
+            .await
+            .request("resources/read", json!({ "uri": uri }))
+            .await?;
+        let read: McpReadResourceResult = serde_json::from_value(result)
+            .with_context(|| format!("failed to parse resources/read for `{uri}`"))?;
+        if let Ok(mut updated) = self.updated_resources.lock() {
+            updated.remove(uri);
+        }
+        Ok(read.contents)
+    }
+
+    /// Subscribe to change notifications for a resource.
+    pub async fn subscribe_resource(&self, uri: &str) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        let supported = inner
+            .capabilities
+            .get("resources")
+            .and_then(|r| r.get("subscribe"))
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        if !supported {
+            bail!(
+                "MCP server `{}` does not support resource subscriptions",
+                inner.config.name
+            );
+        }
+        inner
+            .request("resources/subscribe", json!({ "uri": uri }))
+            .await?;
+        Ok(())
+    }
+
+    /// Whether the server reported `uri` as changed since it was last read.
+    pub fn is_resource_updated(&self, uri: &str) -> bool {
+        self.updated_resources
+            .lock()
+            .map(|updated| updated.contains(uri))
+            .unwrap_or(false)
+    }
+
+    /// Expand a prompt template with the given arguments.
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<McpGetPromptResult> {
+        let result = self
+            .inner
+            .lock()
+            .await
+            .request(
+                "prompts/get",
+                json!({ "name": name, "arguments": arguments }),
+            )
+            .await?;
+        serde_json::from_value(result)
+            .with_context(|| format!("failed to parse prompts/get for `{name}`"))
+    }
+
     /// Call a tool on this server. Returns the raw JSON result.
     pub async fn call_tool(
         &self,
@@ -165,7 +365,7 @@ impl McpServer {
         arguments: serde_json::Value,
     ) -> Result<serde_json::Value> {
         let mut inner = self.inner.lock().await;
-        let id = inner.next_id.fetch_add(1, Ordering::Relaxed) as u64;
+        let id = inner.next_id();
         let req = JsonRpcRequest::new(
             id,
             "tools/call",
@@ -211,6 +411,8 @@ impl McpServer {
 /// Registry of all connected MCP servers, with a flat tool index.
 pub struct McpRegistry {
     servers: Vec<McpServer>,
+    /// Server configs, parallel to `servers`.
+    configs: Vec<McpServerConfig>,
     /// prefixed_name → (server_index, original_tool_name)
     tool_index: HashMap<String, (usize, String)>,
 }
@@ -218,11 +420,31 @@ pub struct McpRegistry {
 impl McpRegistry {
     /// Connect to all configured servers. Non-fatal: failures are logged and skipped.
     pub async fn connect_all(configs: &[McpServerConfig]) -> Result<Self> {
+        Self::connect_all_with_sampler(configs, None).await
+    }
+
+    /// Connect to `config.mcp.servers`, servicing sampling requests with the
+    /// configured default provider.
+    pub async fn connect_from_config(config: &Config) -> Result<Self> {
+        let sampler = McpSampler::from_config(config).unwrap_or_else(|e| {
+            tracing::warn!("MCP sampling disabled — provider unavailable: {e:#}");
+            None
+        });
+        Self::connect_all_with_sampler(&config.mcp.servers, sampler).await
+    }
+
+    /// Connect to all configured servers, sharing `sampler` across the
+    /// servers that enable sampling.
+    pub async fn connect_all_with_sampler(
+        configs: &[McpServerConfig],
+        sampler: Option<Arc<McpSampler>>,
+    ) -> Result<Self> {
         let mut servers = Vec::new();
+        let mut connected_configs = Vec::new();
         let mut tool_index = HashMap::new();
 
         for config in configs {
-            match McpServer::connect(config.clone()).await {
+            match McpServer::connect_with_sampler(config.clone(), sampler.clone()).await {
                 Ok(server) => {
                     let server_idx = servers.len();
                     // Collect tools while holding the lock once, then release
@@ -233,6 +455,7 @@ impl McpRegistry {
                         tool_index.insert(prefixed, (server_idx, tool.name.clone()));
                     }
                     servers.push(server);
+                    connected_configs.push(config.clone());
                 }
                 // Non-fatal — log and continue with remaining servers
                 Err(e) => {
@@ -243,10 +466,160 @@ impl McpRegistry {
 
         Ok(Self {
             servers,
+            configs: connected_configs,
             tool_index,
         })
     }
 
+    fn server(&self, name: &str) -> Result<&McpServer> {
+        self.configs
+            .iter()
+            .position(|c| c.name == name)
+            .map(|idx| &self.servers[idx])
+            .ok_or_else(|| anyhow!("unknown MCP server `{name}`"))
+    }
+
+    /// Names of connected servers that advertise resources.
+    pub async fn resource_servers(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for (server, config) in self.servers.iter().zip(&self.configs) {
+            if server.supports_resources().await {
+                names.push(config.name.clone());
+            }
+        }
+        names
+    }
+
+    /// List resources of one server.
+    pub async fn list_resources(&self, server: &str) -> Result<Vec<McpResourceDef>> {
+        self.server(server)?.list_resources().await
+    }
+
+    /// Read a resource from one server.
+    pub async fn read_resource(&self, server: &str, uri: &str) -> Result<Vec<McpResourceContents>> {
+        self.server(server)?.read_resource(uri).await
+    }
+
+    /// Subscribe to updates of a resource on one server.
+    pub async fn subscribe_resource(&self, server: &str, uri: &str) -> Result<()> {
+        self.server(server)?.subscribe_resource(uri).await
+    }
+
+    /// Whether `server` reported `uri` as changed since it was last read.
+    pub fn is_resource_updated(&self, server: &str, uri: &str) -> bool {
+        self.server(server)
+            .is_ok_and(|server| server.is_resource_updated(uri))
+    }
+
+    /// System-prompt section with each server's `context_resources`.
+    /// Returns an empty string when none are configured or readable.
+    pub async fn resource_context_section(&self) -> String {
+        let mut section = String::new();
+        for (server, config) in self.servers.iter().zip(&self.configs) {
+            for uri in &config.context_resources {
+                let contents = match server.read_resource(uri).await {
+                    Ok(contents) => contents,
+                    Err(e) => {
+                        tracing::warn!("MCP context resource `{uri}` unavailable: {e:#}");
+                        continue;
+                    }
+                };
+                let text = render_resource_contents(&contents);
+                let text = crate::util::truncate_with_ellipsis(&text, MAX_CONTEXT_RESOURCE_CHARS);
+                let _ = write!(section, "\n### {}: {uri}\n\n{text}\n", config.name);
+            }
+        }
+        if section.is_empty() {
+            return section;
+        }
+        format!("\n## MCP Resources\n{section}")
+    }
+
+    /// Slash commands (`/<server>:<prompt>`) for every advertised prompt,
+    /// paired with their descriptions.
+    pub async fn prompt_commands(&self) -> Vec<(String, String)> {
+        let mut commands = Vec::new();
+        for (server, config) in self.servers.iter().zip(&self.configs) {
+            for prompt in server.prompts().await {
+                let args: Vec<String> = prompt
+                    .arguments
+                    .iter()
+                    .map(|a| {
+                        if a.required {
+                            format!("{}=<..>", a.name)
+                        } else {
+                            format!("[{}=<..>]", a.name)
+                        }
+                    })
+                    .collect();
+                let usage = if args.is_empty() {
+                    format!("/{}:{}", config.name, prompt.name)
+                } else {
+                    format!("/{}:{} {}", config.name, prompt.name, args.join(" "))
+                };
+                commands.push((usage, prompt.description.unwrap_or_default()));
+            }
+        }
+        commands
+    }
+
+    /// Expand `/<server>:<prompt> [key=value ...]` into the prompt's text.
+    ///
+    /// Returns `None` when `input` does not name a known server prompt, so
+    /// callers can fall through to their own command handling. A prompt with
+    /// a single argument also accepts the bare remainder as its value.
+    pub async fn expand_prompt_command(&self, input: &str) -> Option<Result<String>> {
+        let (command, rest) = input
+            .strip_prefix('/')?
+            .split_once(char::is_whitespace)
+            .map_or((input[1..].trim(), ""), |(c, r)| (c, r.trim()));
+        let (server_name, prompt_name) = command.split_once(':')?;
+        let server = self.server(server_name).ok()?;
+        let prompt = server
+            .prompts()
+            .await
+            .into_iter()
+            .find(|p| p.name == prompt_name)?;
+
+        let mut arguments = HashMap::new();
+        if prompt.arguments.len() == 1 && !rest.is_empty() && !rest.contains('=') {
+            arguments.insert(prompt.arguments[0].name.clone(), rest.to_string());
+        } else {
+            for pair in rest.split_whitespace() {
+                match pair.split_once('=') {
+                    Some((key, value)) => {
+                        arguments.insert(key.to_string(), value.to_string());
+                    }
+                    None => return Some(Err(anyhow!("expected key=value, got `{pair}`"))),
+                }
+            }
+        }
+        if let Some(missing) = prompt
+            .arguments
+            .iter()
+            .find(|a| a.required && !arguments.contains_key(&a.name))
+        {
+            return Some(Err(anyhow!(
+                "prompt `{prompt_name}` requires argument `{}`",
+                missing.name
+            )));
+        }
+
+        Some(
+            server
+                .get_prompt(prompt_name, arguments)
+                .await
+                .map(|result| {
+                    result
+                        .messages
+                        .iter()
+                        .filter_map(|m| m.text())
+                        .collect::<Vec<_>>()
+                        .join("\n\n")
+                }),
+        )
+    }
+
     /// All prefixed tool names across all connected servers.
     pub fn tool_names(&self) -> Vec<String> {
         self.tool_index.keys().cloned().collect()
@@ -293,6 +666,24 @@ impl McpRegistry {
     }
 }
 
+/// Flatten `resources/read` contents to text; binary blobs are summarized.
+pub fn render_resource_contents(contents: &[McpResourceContents]) -> String {
+    contents
+        .iter()
+        .map(|c| match (&c.text, &c.blob) {
+            (Some(text), _) => text.clone(),
+            (None, Some(blob)) => format!(
+                "[binary resource {} ({}), {} base64 bytes]",
+                c.uri,
+                c.mime_type.as_deref().unwrap_or("application/octet-stream"),
+                blob.len()
+            ),
+            (None, None) => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -316,6 +707,7 @@ mod tests {
             transport: McpTransport::Stdio,
             url: None,
             headers: std::collections::HashMap::default(),
+            ..Default::default()
         };
         let result = McpServer::connect(config).await;
         assert!(result.is_err());
@@ -335,6 +727,7 @@ mod tests {
             transport: McpTransport::Stdio,
             url: None,
             headers: std::collections::HashMap::default(),
+            ..Default::default()
         }];
         let registry = McpRegistry::connect_all(&configs)
             .await
@@ -416,4 +809,89 @@ mod tests {
         assert_eq!(registry.tool_count(), 0);
         assert!(registry.is_empty());
     }
+
+    #[test]
+    fn render_resource_contents_summarizes_blobs() {
+        let contents = vec![
+            McpResourceContents {
+                uri: "file:///a.txt".into(),
+                mime_type: Some("text/plain".into()),
+                text: Some("alpha".into()),
+                blob: None,
+            },
+            McpResourceContents {
+                uri: "file:///b.png".into(),
+                mime_type: Some("image/png".into()),
+                text: None,
+                blob: Some("AAAA".into()),
+            },
+        ];
+        let rendered = render_resource_contents(&contents);
+        assert!(rendered.starts_with("alpha\n"));
+        assert!(rendered.contains("binary resource file:///b.png (image/png), 4 base64 bytes"));
+    }
+
+    /// A scripted stdio server advertising one prompt with a required argument.
+    fn prompt_server() -> McpServerConfig {
+        let script = r#"read l
+printf '%s\n' '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{"tools":{},"prompts":{}}}}'
+read l
+read l
+printf '%s\n' '{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}'
+read l
+printf '%s\n' '{"jsonrpc":"2.0","id":3,"result":{"prompts":[{"name":"review","description":"Review a file","arguments":[{"name":"path","required":true}]}]}}'
+read l
+case "$l" in
+  *'"path":"src/main.rs"'*) text='Review src/main.rs' ;;
+  *) text='wrong arguments' ;;
+esac
+printf '{"jsonrpc":"2.0","id":4,"result":{"messages":[{"role":"user","content":{"type":"text","text":"%s"}}]}}\n' "$text"
+"#;
+        McpServerConfig {
+            name: "fake".into(),
+            command: "sh".into(),
+            args: vec!["-c".into(), script.into()],
+            ..Default::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn prompt_commands_expand_via_prompts_get() {
+        let registry = McpRegistry::connect_all(&[prompt_server()])
+            .await
+            .expect("connect_all should succeed");
+        assert_eq!(registry.server_count(), 1);
+
+        let commands = registry.prompt_commands().await;
+        assert_eq!(
+            commands,
+            vec![(
+                "/fake:review path=<..>".to_string(),
+                "Review a file".to_string()
+            )]
+        );
+
+        assert!(registry
+            .expand_prompt_command("/other:review")
+            .await
+            .is_none());
+        assert!(registry
+            .expand_prompt_command("/fake:missing")
+            .await
+            .is_none());
+        let err = registry
+            .expand_prompt_command("/fake:review")
+            .await
+            .unwrap()
+            .expect_err("missing required argument");
+        assert!(err.to_string().contains("requires argument `path`"));
+
+        let text = registry
+            .expand_prompt_command("/fake:review src/main.rs")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(text, "Review src/main.rs");
+    }
 }
@@ -87,6 +87,107 @@ pub struct McpToolsListResult {
     pub tools: Vec<McpToolDef>,
 }
 
+/// A resource advertised by an MCP server (from `resources/list` response).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpResourceDef {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Expected shape of the `resources/list` result payload.
+#[derive(Debug, Deserialize)]
+pub struct McpResourcesListResult {
+    pub resources: Vec<McpResourceDef>,
+}
+
+/// One content block of a `resources/read` result. Binary resources carry
+/// base64 in `blob` instead of `text`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpResourceContents {
+    pub uri: String,
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+/// Expected shape of the `resources/read` result payload.
+#[derive(Debug, Deserialize)]
+pub struct McpReadResourceResult {
+    pub contents: Vec<McpResourceContents>,
+}
+
+/// A prompt template advertised by an MCP server (from `prompts/list` response).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPromptDef {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/// A named argument accepted by a prompt template.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpPromptArgument {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Expected shape of the `prompts/list` result payload.
+#[derive(Debug, Deserialize)]
+pub struct McpPromptsListResult {
+    pub prompts: Vec<McpPromptDef>,
+}
+
+/// A role-tagged message, as used by `prompts/get` results and
+/// `sampling/createMessage` requests. `content` is a single content block
+/// (`{"type":"text","text":...}`, image, or embedded resource).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpMessage {
+    pub role: String,
+    pub content: serde_json::Value,
+}
+
+impl McpMessage {
+    /// Text of the content block, if it is a text (or embedded text resource) block.
+    pub fn text(&self) -> Option<&str> {
+        self.content
+            .get("text")
+            .or_else(|| self.content.get("resource").and_then(|r| r.get("text")))
+            .and_then(serde_json::Value::as_str)
+    }
+}
+
+/// Expected shape of the `prompts/get` result payload.
+#[derive(Debug, Deserialize)]
+pub struct McpGetPromptResult {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub messages: Vec<McpMessage>,
+}
+
+/// Params of a server-initiated `sampling/createMessage` request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpCreateMessageParams {
+    pub messages: Vec<McpMessage>,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    pub max_tokens: u32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -228,4 +329,53 @@ mod tests {
         let result: McpToolsListResult = serde_json::from_str(json).unwrap();
         assert_eq!(result.tools.len(), 0);
     }
+
+    #[test]
+    fn resource_defs_deserialize_mime_type() {
+        let json =
+            r#"{"resources":[{"uri":"file:///a.md","name":"a.md","mimeType":"text/markdown"}]}"#;
+        let result: McpResourcesListResult = serde_json::from_str(json).unwrap();
+        assert_eq!(result.resources[0].uri, "file:///a.md");
+        assert_eq!(
+            result.resources[0].mime_type.as_deref(),
+            Some("text/markdown")
+        );
+        assert!(result.resources[0].description.is_none());
+    }
+
+    #[test]
+    fn prompt_def_arguments_default_to_empty() {
+        let json = r#"{"prompts":[{"name":"review"},{"name":"summarize","arguments":[{"name":"topic","required":true}]}]}"#;
+        let result: McpPromptsListResult = serde_json::from_str(json).unwrap();
+        assert!(result.prompts[0].arguments.is_empty());
+        assert!(result.prompts[1].arguments[0].required);
+    }
+
+    #[test]
+    fn message_text_reads_text_and_embedded_resources() {
+        let text = McpMessage {
+            role: "user".into(),
+            content: serde_json::json!({"type":"text","text":"hi"}),
+        };
+        let embedded = McpMessage {
+            role: "user".into(),
+            content: serde_json::json!({"type":"resource","resource":{"uri":"x://y","text":"body"}}),
+        };
+        let image = McpMessage {
+            role: "user".into(),
+            content: serde_json::json!({"type":"image","data":"AAAA","mimeType":"image/png"}),
+        };
+        assert_eq!(text.text(), Some("hi"));
+        assert_eq!(embedded.text(), Some("body"));
+        assert!(image.text().is_none());
+    }
+
+    #[test]
+    fn create_message_params_deserialize_camel_case() {
+        let json = r#"{"messages":[{"role":"user","content":{"type":"text","text":"q"}}],"systemPrompt":"be brief","maxTokens":64}"#;
+        let params: McpCreateMessageParams = serde_json::from_str(json).unwrap();
+        assert_eq!(params.max_tokens, 64);
+        assert_eq!(params.system_prompt.as_deref(), Some("be brief"));
+        assert!(params.temperature.is_none());
+    }
 }
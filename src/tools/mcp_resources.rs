@@ -0,0 +1,190 @@
+//! Exposes MCP server resources to the agent as a single `mcp_resources` tool.
+
+use super::traits::{Tool, ToolResult};
+use crate::tools::mcp_client::{render_resource_contents, McpRegistry};
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// List, read and subscribe to resources on connected MCP servers
+pub struct McpResourcesTool {
+    registry: Arc<McpRegistry>,
+    /// Servers that advertised the `resources` capability at connect time.
+    servers: Vec<String>,
+}
+
+impl McpResourcesTool {
+    pub fn new(registry: Arc<McpRegistry>, servers: Vec<String>) -> Self {
+        Self { registry, servers }
+    }
+
+    fn fail(message: impl Into<String>) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(message.into()),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for McpResourcesTool {
+    fn name(&self) -> &str {
+        "mcp_resources"
+    }
+
+    fn description(&self) -> &str {
+        "Access resources (files, records, documents) published by connected MCP servers. Use action 'list' to discover resource URIs, 'read' to fetch a resource's contents, and 'subscribe' to be told when a resource changes (changed resources are marked in 'list')."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "read", "subscribe"],
+                    "description": "Operation to perform"
+                },
+                "server": {
+                    "type": "string",
+                    "enum": self.servers,
+                    "description": "MCP server name (optional for 'list': defaults to all servers)"
+                },
+                "uri": {
+                    "type": "string",
+                    "description": "Resource URI (required for 'read' and 'subscribe')"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args.get("action").and_then(|v| v.as_str()).unwrap_or("");
+        let server = args.get("server").and_then(|v| v.as_str());
+        let uri = args.get("uri").and_then(|v| v.as_str());
+
+        if let Some(server) = server {
+            if !self.servers.iter().any(|s| s == server) {
+                return Ok(Self::fail(format!(
+                    "Unknown resource server '{server}'. Available: {}",
+                    self.servers.join(", ")
+                )));
+            }
+        }
+
+        match action {
+            "list" => {
+                let servers: Vec<&str> = match server {
+                    Some(server) => vec![server],
+                    None => self.servers.iter().map(String::as_str).collect(),
+                };
+                let mut output = String::new();
+                for server in servers {
+                    match self.registry.list_resources(server).await {
+                        Ok(resources) => {
+                            let _ = writeln!(output, "[{server}] {} resource(s)", resources.len());
+                            for resource in resources {
+                                let updated =
+                                    if self.registry.is_resource_updated(server, &resource.uri) {
+                                        " (updated)"
+                                    } else {
+                                        ""
+                                    };
+                                let _ = write!(output, "- {} — {}", resource.uri, resource.name);
+                                if let Some(mime) = &resource.mime_type {
+                                    let _ = write!(output, " [{mime}]");
+                                }
+                                let _ = writeln!(output, "{updated}");
+                                if let Some(description) = &resource.description {
+                                    let _ = writeln!(output, "  {description}");
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            let _ = writeln!(output, "[{server}] error: {e}");
+                        }
+                    }
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
+            "read" | "subscribe" => {
+                let (Some(server), Some(uri)) = (server, uri) else {
+                    return Ok(Self::fail(format!(
+                        "'{action}' requires both 'server' and 'uri'"
+                    )));
+                };
+                let result = if action == "read" {
+                    self.registry
+                        .read_resource(server, uri)
+                        .await
+                        .map(|contents| render_resource_contents(&contents))
+                } else {
+                    self.registry
+                        .subscribe_resource(server, uri)
+                        .await
+                        .map(|()| format!("Subscribed to {uri} on '{server}'."))
+                };
+                Ok(match result {
+                    Ok(output) => ToolResult {
+                        success: true,
+                        output,
+                        error: None,
+                    },
+                    Err(e) => Self::fail(e.to_string()),
+                })
+            }
+            other => Ok(Self::fail(format!(
+                "Unknown action '{other}'. Use 'list', 'read' or 'subscribe'."
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn tool() -> McpResourcesTool {
+        let registry = Arc::new(McpRegistry::connect_all(&[]).await.unwrap());
+        McpResourcesTool::new(registry, vec!["docs".to_string()])
+    }
+
+    #[tokio::test]
+    async fn schema_enumerates_resource_servers() {
+        let tool = tool().await;
+        let schema = tool.parameters_schema();
+        assert_eq!(schema["properties"]["server"]["enum"], json!(["docs"]));
+        assert_eq!(schema["required"], json!(["action"]));
+    }
+
+    #[tokio::test]
+    async fn read_requires_server_and_uri() {
+        let tool = tool().await;
+        let result = tool
+            .execute(json!({ "action": "read", "server": "docs" }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("requires both"));
+    }
+
+    #[tokio::test]
+    async fn unknown_server_and_action_fail() {
+        let tool = tool().await;
+        let result = tool
+            .execute(json!({ "action": "list", "server": "nope" }))
+            .await
+            .unwrap();
+        assert!(result.error.unwrap().contains("Unknown resource server"));
+
+        let result = tool.execute(json!({ "action": "delete" })).await.unwrap();
+        assert!(result.error.unwrap().contains("Unknown action"));
+    }
+}
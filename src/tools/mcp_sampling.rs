@@ -0,0 +1,391 @@
+//! Client-side handling of server-initiated MCP messages.
+//!
+//! MCP servers may ask the client for an LLM completion
+//! (`sampling/createMessage`). ZeroClaw services those with its configured
+//! provider, but only for servers that opt in via `[mcp.servers.sampling]`
+//! and only within that server's request/token budget. Resource update
+//! notifications are recorded so the `mcp_resources` tool can flag stale
+//! subscriptions.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use crate::config::schema::McpSamplingConfig;
+use crate::config::Config;
+use crate::providers::{self, ChatMessage, ChatRequest, Provider};
+use crate::tools::mcp_protocol::{
+    JsonRpcError, McpCreateMessageParams, INTERNAL_ERROR, INVALID_PARAMS, METHOD_NOT_FOUND,
+};
+use crate::tools::mcp_transport::McpServerMessageHandler;
+
+/// Error code MCP clients use to decline a sampling request.
+pub const SAMPLING_REJECTED: i32 = -1;
+
+/// Rough characters-per-token ratio used when a provider reports no usage.
+const CHARS_PER_TOKEN: u64 = 4;
+
+/// Provider handle used to answer `sampling/createMessage` requests.
+pub struct McpSampler {
+    provider: Box<dyn Provider>,
+    model: String,
+    temperature: f64,
+}
+
+impl McpSampler {
+    pub fn new(provider: Box<dyn Provider>, model: impl Into<String>, temperature: f64) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            temperature,
+        }
+    }
+
+    /// Build a sampler from the default provider and model. Returns `None`
+    /// when no configured server has sampling enabled.
+    pub fn from_config(config: &Config) -> anyhow::Result<Option<Arc<Self>>> {
+        if !config.mcp.servers.iter().any(|s| s.sampling.enabled) {
+            return Ok(None);
+        }
+        let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
+        let model = config
+            .default_model
+            .as_deref()
+            .unwrap_or("anthropic/claude-sonnet-4")
+            .to_string();
+        let provider = providers::create_routed_provider_with_options(
+            provider_name,
+            config.api_key.as_deref(),
+            config.api_url.as_deref(),
+            &config.reliability,
+            &config.model_routes,
+            &model,
+            &providers::provider_runtime_options_from_config(config),
+        )?;
+        Ok(Some(Arc::new(Self::new(
+            provider,
+            model,
+            config.default_temperature,
+        ))))
+    }
+}
+
+/// Remaining sampling allowance for one server connection.
+struct SamplingBudget {
+    max_requests: u32,
+    max_tokens: u64,
+    used_requests: AtomicU32,
+    used_tokens: AtomicU64,
+}
+
+impl SamplingBudget {
+    fn new(config: &McpSamplingConfig) -> Self {
+        Self {
+            max_requests: config.max_requests,
+            max_tokens: config.max_tokens,
+            used_requests: AtomicU32::new(0),
+            used_tokens: AtomicU64::new(0),
+        }
+    }
+
+    /// Reserve one request and `tokens` up front; fails when either limit
+    /// would be exceeded.
+    fn reserve(&self, tokens: u64) -> Result<(), String> {
+        self.used_requests
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |requests| {
+                (requests < self.max_requests).then_some(requests + 1)
+            })
+            .map_err(|_| {
+                format!(
+                    "sampling request budget exhausted ({} requests)",
+                    self.max_requests
+                )
+            })?;
+        let reserved =
+            self.used_tokens
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |spent| {
+                    spent
+                        .checked_add(tokens)
+                        .filter(|total| *total <= self.max_tokens)
+                });
+        if let Err(spent) = reserved {
+            // Hand back the request slot taken above.
+            self.used_requests.fetch_sub(1, Ordering::AcqRel);
+            return Err(format!(
+                "sampling token budget exhausted ({spent}/{} tokens used, {tokens} requested)",
+                self.max_tokens
+            ));
+        }
+        Ok(())
+    }
+
+    /// Replace an up-front reservation with the tokens actually spent.
+    fn settle(&self, reserved: u64, actual: u64) {
+        self.used_tokens.fetch_sub(reserved, Ordering::Relaxed);
+        self.used_tokens.fetch_add(actual, Ordering::Relaxed);
+    }
+}
+
+/// Per-connection handler for server-initiated requests and notifications.
+pub struct McpClientHandler {
+    server_name: String,
+    sampler: Option<Arc<McpSampler>>,
+    budget: SamplingBudget,
+    updated_resources: Arc<Mutex<HashSet<String>>>,
+}
+
+impl McpClientHandler {
+    /// `sampler` is only used when `sampling.enabled` is set.
+    pub fn new(
+        server_name: impl Into<String>,
+        sampling: &McpSamplingConfig,
+        sampler: Option<Arc<McpSampler>>,
+        updated_resources: Arc<Mutex<HashSet<String>>>,
+    ) -> Self {
+        Self {
+            server_name: server_name.into(),
+            sampler: sampler.filter(|_| sampling.enabled),
+            budget: SamplingBudget::new(sampling),
+            updated_resources,
+        }
+    }
+
+    /// Whether the sampling capability should be advertised.
+    pub fn supports_sampling(&self) -> bool {
+        self.sampler.is_some()
+    }
+
+    async fn create_message(&self, params: Value) -> Result<Value, JsonRpcError> {
+        let Some(sampler) = &self.sampler else {
+            return Err(rpc_error(METHOD_NOT_FOUND, "Sampling is not enabled"));
+        };
+        let params: McpCreateMessageParams = serde_json::from_value(params)
+            .map_err(|e| rpc_error(INVALID_PARAMS, format!("Invalid sampling params: {e}")))?;
+
+        let mut messages = Vec::with_capacity(params.messages.len() + 1);
+        if let Some(system) = params.system_prompt.as_deref().filter(|s| !s.is_empty()) {
+            messages.push(ChatMessage::system(system));
+        }
+        for message in &params.messages {
+            let text = message.text().ok_or_else(|| {
+                rpc_error(INVALID_PARAMS, "Only text sampling content is supported")
+            })?;
+            messages.push(match message.role.as_str() {
+                "assistant" => ChatMessage::assistant(text),
+                _ => ChatMessage::user(text),
+            });
+        }
+
+        let prompt_chars: usize = messages.iter().map(|m| m.content.len()).sum();
+        let reserved = estimate_tokens(prompt_chars) + u64::from(params.max_tokens);
+        self.budget
+            .reserve(reserved)
+            .map_err(|e| rpc_error(SAMPLING_REJECTED, e))?;
+
+        tracing::info!(
+            "MCP server `{}` sampling request ({} message(s), maxTokens {})",
+            self.server_name,
+            params.messages.len(),
+            params.max_tokens
+        );
+
+        let response = sampler
+            .provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                    response_format: None,
+                },
+                &sampler.model,
+                params.temperature.unwrap_or(sampler.temperature),
+            )
+            .await;
+        let response = match response {
+            Ok(response) => response,
+            Err(e) => {
+                self.budget.settle(reserved, 0);
+                return Err(rpc_error(INTERNAL_ERROR, format!("Sampling failed: {e}")));
+            }
+        };
+
+        let text = response.text.unwrap_or_default();
+        let actual = response
+            .usage
+            .as_ref()
+            .and_then(|u| Some(u.input_tokens? + u.output_tokens?))
+            .unwrap_or_else(|| estimate_tokens(prompt_chars + text.len()));
+        self.budget.settle(reserved, actual);
+
+        Ok(json!({
+            "role": "assistant",
+            "content": { "type": "text", "text": text },
+            "model": sampler.model,
+            "stopReason": "endTurn",
+        }))
+    }
+}
+
+#[async_trait::async_trait]
+impl McpServerMessageHandler for McpClientHandler {
+    async fn handle_request(&self, method: &str, params: Value) -> Result<Value, JsonRpcError> {
+        match method {
+            "sampling/createMessage" => self.create_message(params).await,
+            "ping" => Ok(json!({})),
+            other => Err(rpc_error(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {other}"),
+            )),
+        }
+    }
+
+    fn handle_notification(&self, method: &str, params: &Value) {
+        if method == "notifications/resources/updated" {
+            if let Some(uri) = params.get("uri").and_then(Value::as_str) {
+                if let Ok(mut updated) = self.updated_resources.lock() {
+                    updated.insert(uri.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn estimate_tokens(chars: usize) -> u64 {
+    (chars as u64).div_ceil(CHARS_PER_TOKEN)
+}
+
+fn rpc_error(code: i32, message: impl Into<String>) -> JsonRpcError {
+    JsonRpcError {
+        code,
+        message: message.into(),
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    struct FixedProvider;
+
+    #[async_trait]
+    impl Provider for FixedProvider {
+        async fn chat_with_system(
+            &self,
+            system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(format!("{}|{message}", system_prompt.unwrap_or("")))
+        }
+    }
+
+    fn handler(sampling: McpSamplingConfig) -> McpClientHandler {
+        McpClientHandler::new(
+            "test",
+            &sampling,
+            Some(Arc::new(McpSampler::new(Box::new(FixedProvider), "m", 0.7))),
+            Arc::new(Mutex::new(HashSet::new())),
+        )
+    }
+
+    fn enabled(max_requests: u32, max_tokens: u64) -> McpSamplingConfig {
+        McpSamplingConfig {
+            enabled: true,
+            max_requests,
+            max_tokens,
+        }
+    }
+
+    fn create_params(max_tokens: u32) -> Value {
+        json!({
+            "messages": [{ "role": "user", "content": { "type": "text", "text": "hello" } }],
+            "systemPrompt": "sys",
+            "maxTokens": max_tokens,
+        })
+    }
+
+    #[tokio::test]
+    async fn create_message_uses_provider() {
+        let handler = handler(enabled(5, 1_000));
+        assert!(handler.supports_sampling());
+        let result = handler
+            .handle_request("sampling/createMessage", create_params(16))
+            .await
+            .unwrap();
+        assert_eq!(result["role"], "assistant");
+        assert_eq!(result["content"]["text"], "sys|hello");
+        assert_eq!(result["model"], "m");
+    }
+
+    #[tokio::test]
+    async fn sampling_disabled_is_not_advertised_or_serviced() {
+        let handler = handler(McpSamplingConfig::default());
+        assert!(!handler.supports_sampling());
+        let err = handler
+            .handle_request("sampling/createMessage", create_params(16))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn request_budget_is_enforced() {
+        let handler = handler(enabled(1, 1_000));
+        handler
+            .handle_request("sampling/createMessage", create_params(16))
+            .await
+            .unwrap();
+        let err = handler
+            .handle_request("sampling/createMessage", create_params(16))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, SAMPLING_REJECTED);
+        assert!(err.message.contains("request budget"));
+    }
+
+    #[tokio::test]
+    async fn token_budget_rejects_oversized_max_tokens() {
+        let handler = handler(enabled(10, 100));
+        let err = handler
+            .handle_request("sampling/createMessage", create_params(500))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, SAMPLING_REJECTED);
+        assert!(err.message.contains("token budget"));
+    }
+
+    #[tokio::test]
+    async fn non_text_content_is_rejected() {
+        let handler = handler(enabled(5, 1_000));
+        let params = json!({
+            "messages": [{ "role": "user", "content": { "type": "image", "data": "AA==", "mimeType": "image/png" } }],
+            "maxTokens": 16,
+        });
+        let err = handler
+            .handle_request("sampling/createMessage", params)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, INVALID_PARAMS);
+    }
+
+    #[test]
+    fn resource_update_notifications_are_recorded() {
+        let updated = Arc::new(Mutex::new(HashSet::new()));
+        let handler = McpClientHandler::new(
+            "test",
+            &McpSamplingConfig::default(),
+            None,
+            Arc::clone(&updated),
+        );
+        handler.handle_notification(
+            "notifications/resources/updated",
+            &json!({ "uri": "file:///notes.md" }),
+        );
+        assert!(updated.lock().unwrap().contains("file:///notes.md"));
+    }
+}
@@ -0,0 +1,711 @@
+//! MCP server mode — exposes ZeroClaw's tool registry and memory over MCP.
+//!
+//! [`McpServer`] is transport-agnostic: [`McpServer::handle_request`] maps one
+//! JSON-RPC request to an optional response. `zeroclaw mcp serve` drives it
+//! over stdio ([`McpServer::serve_stdio`]) and the gateway mounts it as
+//! streamable HTTP at `POST /mcp` when `[mcp_serve] gateway = true`.
+//!
+//! Only tools that pass every filter are listed or callable:
+//! - the `[mcp_serve] tools` allowlist, when non-empty
+//! - `autonomy.non_cli_excluded_tools`
+//! - the non-interactive [`ApprovalManager`]: tools that would need operator
+//!   approval are withheld, since an MCP client cannot answer the prompt
+//!
+//! Exposed tools still enforce `SecurityPolicy` on every call, exactly as they
+//! do inside the agent loop. Tool output is credential-scrubbed before it
+//! leaves the process.
+//!
+//! When memory resources are enabled, entries are readable as
+//! `memory://entry/{key}` and keyword recall as `memory://recall/{query}`.
+
+use crate::agent::loop_::scrub_credentials;
+use crate::approval::{ApprovalManager, ApprovalResponse};
+use crate::config::{AutonomyConfig, Config, McpServeConfig, ToolRuleAction};
+use crate::memory::Memory;
+use crate::tools::mcp_protocol::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST,
+    JSONRPC_VERSION, MCP_PROTOCOL_VERSION, METHOD_NOT_FOUND, PARSE_ERROR,
+};
+use crate::tools::Tool;
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tracing::{info, warn};
+
+/// MCP protocol revisions this server can speak. The client's requested
+/// version is echoed back when supported; otherwise we offer our default.
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", MCP_PROTOCOL_VERSION];
+
+/// MCP error code for `resources/read` on an unknown URI.
+const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// Maximum entries returned by `resources/list`.
+const MAX_LISTED_RESOURCES: usize = 100;
+
+/// Maximum entries returned by a `memory://recall/{query}` read.
+const RECALL_LIMIT: usize = 10;
+
+/// Approval audit channel name for MCP-originated tool calls.
+const APPROVAL_CHANNEL: &str = "mcp";
+
+const ENTRY_URI_PREFIX: &str = "memory://entry/";
+const RECALL_URI_PREFIX: &str = "memory://recall/";
+
+/// Serves a tool registry (and optionally memory) to MCP clients.
+pub struct McpServer {
+    tools: Vec<Box<dyn Tool>>,
+    approval: ApprovalManager,
+    allowlist: HashSet<String>,
+    excluded: HashSet<String>,
+    memory: Option<Arc<dyn Memory>>,
+}
+
+impl McpServer {
+    /// Build a server over an existing tool registry.
+    ///
+    /// `memory` is only exposed when `serve.memory_resources` is set.
+    pub fn new(
+        tools: Vec<Box<dyn Tool>>,
+        autonomy: &AutonomyConfig,
+        serve: &McpServeConfig,
+        memory: Option<Arc<dyn Memory>>,
+    ) -> Self {
+        Self {
+            tools,
+            approval: ApprovalManager::for_non_interactive(autonomy),
+            allowlist: serve.tools.iter().cloned().collect(),
+            excluded: autonomy.non_cli_excluded_tools.iter().cloned().collect(),
+            memory: memory.filter(|_| serve.memory_resources),
+        }
+    }
+
+    /// Build the full tool registry and memory backend from config, the same
+    /// way the agent does. MCP client tools are not re-exported.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let runtime: Arc<dyn crate::runtime::RuntimeAdapter> =
+            Arc::from(crate::runtime::create_runtime(&config.runtime)?);
+        let trust = crate::trust::runtime::init(config);
+        let security = Arc::new(
+            crate::security::SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir)
+                .with_trust(trust.clone()),
+        );
+        let memory: Arc<dyn Memory> =
+            Arc::from(crate::memory::create_memory_with_storage_and_routes(
+                &config.memory,
+                &config.embedding_routes,
+                Some(&config.storage.provider.config),
+                &config.workspace_dir,
+                config.api_key.as_deref(),
+            )?);
+
+        let (composio_key, composio_entity_id) = if config.composio.enabled {
+            (
+                config.composio.api_key.as_deref(),
+                Some(config.composio.entity_id.as_str()),
+            )
+        } else {
+            (None, None)
+        };
+
+        let sop_engine = crate::sop::shared_sop_engine(&config.sop, &config.workspace_dir);
+        let (tools, ..) = crate::tools::all_tools_with_runtime(
+            Arc::new(config.clone()),
+            &security,
+            runtime,
+            Arc::clone(&memory),
+            composio_key,
+            composio_entity_id,
+            &config.browser,
+            &config.http_request,
+            &config.web_fetch,
+            &config.workspace_dir,
+            &config.agents,
+            config.api_key.as_deref(),
+            config,
+            sop_engine,
+            None,
+        );
+
+        Ok(Self {
+            approval: ApprovalManager::for_non_interactive(&config.autonomy)
+                .with_trust(trust)
+                .with_rules(Arc::clone(&security.tool_rules)),
+            ..Self::new(tools, &config.autonomy, &config.mcp_serve, Some(memory))
+        })
+    }
+
+    /// Whether `name` passes the allowlist, exclusion and approval filters.
+    fn is_exposed(&self, name: &str) -> bool {
+        (self.allowlist.is_empty() || self.allowlist.contains(name))
+            && !self.excluded.contains(name)
+            && !self.approval.needs_approval(name)
+    }
+
+    /// Names of the tools currently exposed to clients.
+    pub fn exposed_tool_names(&self) -> Vec<String> {
+        self.tools
+            .iter()
+            .map(|tool| tool.name())
+            .filter(|name| self.is_exposed(name))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Run the server over newline-delimited JSON-RPC on stdin/stdout until
+    /// stdin closes.
+    pub async fn serve_stdio(&self) -> Result<()> {
+        info!(
+            "MCP server listening on stdio ({} tool(s) exposed)",
+            self.exposed_tool_names().len()
+        );
+
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdout = tokio::io::stdout();
+
+        while let Some(line) = lines.next_line().await? {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(trimmed).await {
+                let mut out = serde_json::to_string(&response)?;
+                out.push('\n');
+                stdout.write_all(out.as_bytes()).await?;
+                stdout.flush().await?;
+            }
+        }
+
+        info!("MCP server: stdin closed, shutting down");
+        Ok(())
+    }
+
+    /// Parse and handle one raw JSON-RPC message.
+    pub async fn handle_message(&self, raw: &str) -> Option<JsonRpcResponse> {
+        match serde_json::from_str::<JsonRpcRequest>(raw) {
+            Ok(request) => self.handle_request(request).await,
+            Err(e) => {
+                warn!("MCP server: failed to parse JSON-RPC message: {e}");
+                Some(error_response(
+                    Value::Null,
+                    PARSE_ERROR,
+                    format!("Parse error: {e}"),
+                ))
+            }
+        }
+    }
+
+    /// Handle one request. Returns `None` for notifications.
+    pub async fn handle_request(&self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+        let Some(id) = request.id.clone() else {
+            // Notifications (`notifications/initialized`, cancellations, …)
+            // need no reply.
+            return None;
+        };
+
+        if request.jsonrpc != JSONRPC_VERSION {
+            return Some(error_response(
+                id,
+                INVALID_REQUEST,
+                "Invalid JSON-RPC version",
+            ));
+        }
+
+        let params = request.params.unwrap_or(Value::Null);
+        let result = match request.method.as_str() {
+            "initialize" => Ok(self.initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(&params).await,
+            "resources/list" if self.memory.is_some() => self.list_resources().await,
+            "resources/templates/list" if self.memory.is_some() => {
+                Ok(json!({ "resourceTemplates": resource_templates() }))
+            }
+            "resources/read" if self.memory.is_some() => self.read_resource(&params).await,
+            other => Err((METHOD_NOT_FOUND, format!("Method not found: {other}"))),
+        };
+
+        Some(match result {
+            Ok(result) => JsonRpcResponse {
+                jsonrpc: JSONRPC_VERSION.to_string(),
+                id: Some(id),
+                result: Some(result),
+                error: None,
+            },
+            Err((code, message)) => error_response(id, code, message),
+        })
+    }
+
+    fn initialize(&self, params: &Value) -> Value {
+        let requested = params
+            .get("protocolVersion")
+            .and_then(Value::as_str)
+            .unwrap_or(MCP_PROTOCOL_VERSION);
+        let protocol_version = if SUPPORTED_PROTOCOL_VERSIONS.contains(&requested) {
+            requested
+        } else {
+            MCP_PROTOCOL_VERSION
+        };
+
+        let mut capabilities = json!({ "tools": { "listChanged": false } });
+        if self.memory.is_some() {
+            capabilities["resources"] = json!({ "subscribe": false, "listChanged": false });
+        }
+
+        json!({
+            "protocolVersion": protocol_version,
+            "capabilities": capabilities,
+            "serverInfo": {
+                "name": "zeroclaw",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })
+    }
+
+    fn list_tools(&self) -> Value {
+        let tools: Vec<Value> = self
+            .tools
+            .iter()
+            .filter(|tool| self.is_exposed(tool.name()))
+            .map(|tool| {
+                json!({
+                    "name": tool.name(),
+                    "description": tool.description(),
+                    "inputSchema": tool.parameters_schema(),
+                })
+            })
+            .collect();
+        json!({ "tools": tools })
+    }
+
+    async fn call_tool(&self, params: &Value) -> Result<Value, (i32, String)> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+
+        let tool = self
+            .tools
+            .iter()
+            .find(|tool| tool.name() == name)
+            .filter(|_| {
+                (self.allowlist.is_empty() || self.allowlist.contains(name))
+                    && !self.excluded.contains(name)
+            })
+            .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool: {name}")))?;
+
+        if let Some(decision) = self
+            .approval
+            .rule_decision(name, &arguments)
+            .filter(|d| d.action == ToolRuleAction::Deny)
+        {
+            self.approval
+                .record_decision(name, &arguments, ApprovalResponse::No, APPROVAL_CHANNEL);
+            return Ok(tool_result(
+                &format!("Denied by policy: {}", decision.reason),
+                true,
+            ));
+        }
+
+        if self.approval.needs_approval_for_call(name, &arguments) {
+            self.approval
+                .record_decision(name, &arguments, ApprovalResponse::No, APPROVAL_CHANNEL);
+            return Ok(tool_result(
+                &format!(
+                    "Denied: '{name}' requires operator approval, which is unavailable over MCP."
+                ),
+                true,
+            ));
+        }
+
+        Ok(match tool.execute(arguments).await {
+            Ok(result) if result.success => tool_result(&result.output, false),
+            Ok(result) => tool_result(&result.error.unwrap_or(result.output), true),
+            Err(e) => tool_result(&format!("Error executing {name}: {e}"), true),
+        })
+    }
+
+    async fn list_resources(&self) -> Result<Value, (i32, String)> {
+        let memory = self.memory.as_ref().expect("checked by caller");
+        let entries = memory
+            .list(None, None)
+            .await
+            .map_err(|e| (INTERNAL_ERROR, format!("Memory list failed: {e}")))?;
+
+        let resources: Vec<Value> = entries
+            .iter()
+            .take(MAX_LISTED_RESOURCES)
+            .map(|entry| {
+                json!({
+                    "uri": entry_uri(&entry.key),
+                    "name": entry.key,
+                    "description": format!("{} memory ({})", entry.category, entry.timestamp),
+                    "mimeType": "text/plain",
+                })
+            })
+            .collect();
+        Ok(json!({ "resources": resources }))
+    }
+
+    async fn read_resource(&self, params: &Value) -> Result<Value, (i32, String)> {
+        let memory = self.memory.as_ref().expect("checked by caller");
+        let uri = params
+            .get("uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, "Missing resource uri".to_string()))?;
+
+        if let Some(key) = uri.strip_prefix(ENTRY_URI_PREFIX) {
+            let key = decode_uri_component(key)?;
+            let entry = memory
+                .get(&key)
+                .await
+                .map_err(|e| (INTERNAL_ERROR, format!("Memory read failed: {e}")))?
+                .ok_or_else(|| (RESOURCE_NOT_FOUND, format!("Resource not found: {uri}")))?;
+            return Ok(json!({
+                "contents": [{
+                    "uri": uri,
+                    "mimeType": "text/plain",
+                    "text": entry.content,
+                }]
+            }));
+        }
+
+        if let Some(query) = uri.strip_prefix(RECALL_URI_PREFIX) {
+            let query = decode_uri_component(query)?;
+            let entries = memory
+                .recall(&query, RECALL_LIMIT, None, None, None)
+                .await
+                .map_err(|e| (INTERNAL_ERROR, format!("Memory recall failed: {e}")))?;
+            let matches: Vec<Value> = entries
+                .iter()
+                .map(|entry| {
+                    json!({
+                        "key": entry.key,
+                        "content": entry.content,
+                        "category": entry.category.to_string(),
+                        "timestamp": entry.timestamp,
+                        "score": entry.score,
+                    })
+                })
+                .collect();
+            return Ok(json!({
+                "contents": [{
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": Value::Array(matches).to_string(),
+                }]
+            }));
+        }
+
+        Err((RESOURCE_NOT_FOUND, format!("Resource not found: {uri}")))
+    }
+}
+
+fn resource_templates() -> Value {
+    json!([
+        {
+            "uriTemplate": format!("{ENTRY_URI_PREFIX}{{key}}"),
+            "name": "Memory entry",
+            "description": "A stored memory entry by key",
+            "mimeType": "text/plain",
+        },
+        {
+            "uriTemplate": format!("{RECALL_URI_PREFIX}{{query}}"),
+            "name": "Memory recall",
+            "description": "Memory entries matching a keyword query",
+            "mimeType": "application/json",
+        },
+    ])
+}
+
+fn entry_uri(key: &str) -> String {
+    format!("{ENTRY_URI_PREFIX}{}", urlencoding::encode(key))
+}
+
+fn decode_uri_component(raw: &str) -> Result<String, (i32, String)> {
+    urlencoding::decode(raw)
+        .map(|decoded| decoded.into_owned())
+        .map_err(|e| (INVALID_PARAMS, format!("Invalid resource uri: {e}")))
+}
+
+fn tool_result(text: &str, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": scrub_credentials(text) }],
+        "isError": is_error,
+    })
+}
+
+fn error_response(id: Value, code: i32, message: impl Into<String>) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: JSONRPC_VERSION.to_string(),
+        id: Some(id),
+        result: None,
+        error: Some(JsonRpcError {
+            code,
+            message: message.into(),
+            data: None,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MemoryCategory, NoneMemory};
+    use crate::security::AutonomyLevel;
+    use crate::tools::ToolResult;
+    use async_trait::async_trait;
+
+    struct EchoTool(&'static str);
+
+    #[async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "Echo the message argument"
+        }
+
+        fn parameters_schema(&self) -> Value {
+            json!({
+                "type": "object",
+                "properties": { "message": { "type": "string" } },
+            })
+        }
+
+        async fn execute(&self, args: Value) -> anyhow::Result<ToolResult> {
+            Ok(ToolResult {
+                success: true,
+                output: args["message"].as_str().unwrap_or_default().to_string(),
+                error: None,
+            })
+        }
+    }
+
+    fn full_autonomy() -> AutonomyConfig {
+        AutonomyConfig {
+            level: AutonomyLevel::Full,
+            ..AutonomyConfig::default()
+        }
+    }
+
+    fn server(autonomy: &AutonomyConfig, serve: &McpServeConfig) -> McpServer {
+        McpServer::new(
+            vec![Box::new(EchoTool("echo")), Box::new(EchoTool("shout"))],
+            autonomy,
+            serve,
+            None,
+        )
+    }
+
+    fn request(method: &str, params: Value) -> JsonRpcRequest {
+        JsonRpcRequest::new(1, method, params)
+    }
+
+    #[tokio::test]
+    async fn initialize_negotiates_protocol_version() {
+        let server = server(&full_autonomy(), &McpServeConfig::default());
+
+        let resp = server
+            .handle_request(request(
+                "initialize",
+                json!({ "protocolVersion": "2025-03-26" }),
+            ))
+            .await
+            .unwrap();
+        let result = resp.result.unwrap();
+        assert_eq!(result["protocolVersion"], "2025-03-26");
+        assert_eq!(result["serverInfo"]["name"], "zeroclaw");
+        assert!(result["capabilities"].get("resources").is_none());
+
+        let resp = server
+            .handle_request(request(
+                "initialize",
+                json!({ "protocolVersion": "1999-01-01" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            resp.result.unwrap()["protocolVersion"],
+            MCP_PROTOCOL_VERSION
+        );
+    }
+
+    #[tokio::test]
+    async fn notifications_get_no_response() {
+        let server = server(&full_autonomy(), &McpServeConfig::default());
+        let notification = JsonRpcRequest::notification("notifications/initialized", json!({}));
+        assert!(server.handle_request(notification).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn tools_list_respects_allowlist_and_exclusions() {
+        let mut autonomy = full_autonomy();
+        autonomy.non_cli_excluded_tools = vec!["shout".into()];
+        let server = server(&autonomy, &McpServeConfig::default());
+        assert_eq!(server.exposed_tool_names(), vec!["echo".to_string()]);
+
+        let serve = McpServeConfig {
+            tools: vec!["shout".into()],
+            ..McpServeConfig::default()
+        };
+        let server = self::server(&full_autonomy(), &serve);
+        let resp = server
+            .handle_request(request("tools/list", json!({})))
+            .await
+            .unwrap();
+        let tools = resp.result.unwrap()["tools"].clone();
+        assert_eq!(tools.as_array().unwrap().len(), 1);
+        assert_eq!(tools[0]["name"], "shout");
+        assert!(tools[0]["inputSchema"].is_object());
+    }
+
+    #[tokio::test]
+    async fn tools_call_executes_exposed_tool() {
+        let server = server(&full_autonomy(), &McpServeConfig::default());
+        let resp = server
+            .handle_request(request(
+                "tools/call",
+                json!({ "name": "echo", "arguments": { "message": "hello" } }),
+            ))
+            .await
+            .unwrap();
+        let result = resp.result.unwrap();
+        assert_eq!(result["isError"], false);
+        assert_eq!(result["content"][0]["text"], "hello");
+    }
+
+    #[tokio::test]
+    async fn tools_needing_approval_are_hidden_and_denied() {
+        let autonomy = AutonomyConfig {
+            level: AutonomyLevel::Supervised,
+            auto_approve: vec!["echo".into()],
+            always_ask: vec![],
+            ..AutonomyConfig::default()
+        };
+        let server = server(&autonomy, &McpServeConfig::default());
+        assert_eq!(server.exposed_tool_names(), vec!["echo".to_string()]);
+
+        let resp = server
+            .handle_request(request(
+                "tools/call",
+                json!({ "name": "shout", "arguments": { "message": "hi" } }),
+            ))
+            .await
+            .unwrap();
+        let result = resp.result.unwrap();
+        assert_eq!(result["isError"], true);
+        assert!(result["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("requires operator approval"));
+        assert_eq!(server.approval.audit_log()[0].channel, APPROVAL_CHANNEL);
+    }
+
+    #[tokio::test]
+    async fn unknown_tool_and_method_are_errors() {
+        let server = server(&full_autonomy(), &McpServeConfig::default());
+
+        let resp = server
+            .handle_request(request("tools/call", json!({ "name": "missing" })))
+            .await
+            .unwrap();
+        assert_eq!(resp.error.unwrap().code, INVALID_PARAMS);
+
+        let resp = server
+            .handle_request(request("resources/list", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(resp.error.unwrap().code, METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn malformed_message_returns_parse_error() {
+        let server = server(&full_autonomy(), &McpServeConfig::default());
+        let resp = server.handle_message("{not json").await.unwrap();
+        assert_eq!(resp.error.unwrap().code, PARSE_ERROR);
+        assert_eq!(resp.id, Some(Value::Null));
+    }
+
+    #[tokio::test]
+    async fn memory_entries_are_readable_resources() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let memory: Arc<dyn Memory> =
+            Arc::new(crate::memory::SqliteMemory::new(tmp.path()).unwrap());
+        memory
+            .store("user/lang", "Prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let server = McpServer::new(
+            Vec::new(),
+            &full_autonomy(),
+            &McpServeConfig::default(),
+            Some(memory),
+        );
+
+        let resp = server
+            .handle_request(request("resources/list", json!({})))
+            .await
+            .unwrap();
+        let resources = resp.result.unwrap()["resources"].clone();
+        assert_eq!(resources[0]["uri"], "memory://entry/user%2Flang");
+
+        let resp = server
+            .handle_request(request(
+                "resources/read",
+                json!({ "uri": "memory://entry/user%2Flang" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.result.unwrap()["contents"][0]["text"], "Prefers Rust");
+
+        let resp = server
+            .handle_request(request(
+                "resources/read",
+                json!({ "uri": "memory://recall/Rust" }),
+            ))
+            .await
+            .unwrap();
+        let text = resp.result.unwrap()["contents"][0]["text"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(text.contains("Prefers Rust"));
+
+        let resp = server
+            .handle_request(request(
+                "resources/read",
+                json!({ "uri": "memory://entry/missing" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.error.unwrap().code, RESOURCE_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn memory_resources_can_be_disabled() {
+        let serve = McpServeConfig {
+            memory_resources: false,
+            ..McpServeConfig::default()
+        };
+        let server = McpServer::new(
+            Vec::new(),
+            &full_autonomy(),
+            &serve,
+            Some(Arc::new(NoneMemory::new())),
+        );
+        let resp = server
+            .handle_request(request("resources/list", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(resp.error.unwrap().code, METHOD_NOT_FOUND);
+    }
+}
@@ -1,6 +1,7 @@
 //! MCP transport abstraction — supports stdio, SSE, and HTTP transports.
 
 use std::borrow::Cow;
+use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Context, Result};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -10,7 +11,11 @@ use tokio::time::{timeout, Duration};
 use tokio_stream::StreamExt;
 
 use crate::config::schema::{McpServerConfig, McpTransport};
-use crate::tools::mcp_protocol::{JsonRpcError, JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR};
+use crate::observability::trace_context;
+use crate::tools::mcp_protocol::{
+    JsonRpcError, JsonRpcRequest, JsonRpcResponse, INTERNAL_ERROR, JSONRPC_VERSION,
+    METHOD_NOT_FOUND,
+};
 
 /// Maximum bytes for a single JSON-RPC response.
 const MAX_LINE_BYTES: usize = 4 * 1024 * 1024; // 4 MB
@@ -36,6 +41,26 @@ pub trait McpTransportConn: Send + Sync {
 
     /// Close the connection.
     async fn close(&mut self) -> Result<()>;
+
+    /// Install a handler for server-initiated requests and notifications.
+    /// Transports that cannot reply to the server keep the default no-op and
+    /// ignore such messages.
+    fn set_message_handler(&mut self, _handler: Arc<dyn McpServerMessageHandler>) {}
+}
+
+/// Services messages an MCP server sends on its own initiative, such as
+/// `sampling/createMessage` requests and `notifications/resources/updated`.
+#[async_trait::async_trait]
+pub trait McpServerMessageHandler: Send + Sync {
+    /// Handle a server → client request and produce its result.
+    async fn handle_request(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> std::result::Result<serde_json::Value, JsonRpcError>;
+
+    /// Observe a server → client notification.
+    fn handle_notification(&self, _method: &str, _params: &serde_json::Value) {}
 }
 
 // ── Stdio Transport ──────────────────────────────────────────────────────
@@ -45,6 +70,7 @@ pub struct StdioTransport {
     _child: Child,
     stdin: tokio::process::ChildStdin,
     stdout_lines: tokio::io::Lines<BufReader<tokio::process::ChildStdout>>,
+    handler: Option<Arc<dyn McpServerMessageHandler>>,
 }
 
 impl StdioTransport {
@@ -73,6 +99,7 @@ impl StdioTransport {
             _child: child,
             stdin,
             stdout_lines,
+            handler: None,
         })
     }
 
@@ -100,6 +127,34 @@ impl StdioTransport {
         }
         Ok(line)
     }
+
+    /// Answer a server-initiated request on stdin.
+    async fn reply_to_server_request(
+        &mut self,
+        id: serde_json::Value,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<()> {
+        let outcome = match &self.handler {
+            Some(handler) => handler.handle_request(method, params).await,
+            None => Err(JsonRpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Client does not support {method}"),
+                data: None,
+            }),
+        };
+        let (result, error) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        let response = JsonRpcResponse {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id),
+            result,
+            error,
+        };
+        self.send_raw(&serde_json::to_string(&response)?).await
+    }
 }
 
 #[async_trait::async_trait]
@@ -115,7 +170,7 @@ impl McpTransportConn for StdioTransport {
                 error: None,
             });
         }
-        let deadline = std::time::Instant::now() + Duration::from_secs(RECV_TIMEOUT_SECS);
+        let mut deadline = std::time::Instant::now() + Duration::from_secs(RECV_TIMEOUT_SECS);
         loop {
             let remaining = deadline.saturating_duration_since(std::time::Instant::now());
             if remaining.is_zero() {
@@ -124,7 +179,28 @@ impl McpTransportConn for StdioTransport {
             let resp_line = timeout(remaining, self.recv_raw())
                 .await
                 .context("timeout waiting for MCP response")??;
-            let resp: JsonRpcResponse = serde_json::from_str(&resp_line)
+            let value: serde_json::Value = serde_json::from_str(&resp_line)
+                .with_context(|| format!("invalid JSON-RPC response: {}", resp_line))?;
+            if let Some(method) = value.get("method").and_then(|m| m.as_str()) {
+                // Server-initiated message. Requests are answered in-band; the
+                // deadline restarts afterwards since servicing them (e.g.
+                // sampling) is time the server was not responding to us.
+                let params = value.get("params").cloned().unwrap_or_default();
+                match value.get("id").cloned() {
+                    Some(id) => {
+                        self.reply_to_server_request(id, method, params).await?;
+                        deadline =
+                            std::time::Instant::now() + Duration::from_secs(RECV_TIMEOUT_SECS);
+                    }
+                    None => {
+                        if let Some(handler) = &self.handler {
+                            handler.handle_notification(method, &params);
+                        }
+                    }
+                }
+                continue;
+            }
+            let resp: JsonRpcResponse = serde_json::from_value(value)
                 .with_context(|| format!("invalid JSON-RPC response: {}", resp_line))?;
             if resp.id.is_none() {
                 // Server-sent notification (e.g. `notifications/initialized`) — skip and
@@ -142,6 +218,10 @@ impl McpTransportConn for StdioTransport {
         let _ = self.stdin.shutdown().await;
         Ok(())
     }
+
+    fn set_message_handler(&mut self, handler: Arc<dyn McpServerMessageHandler>) {
+        self.handler = Some(handler);
+    }
 }
 
 // ── HTTP Transport ───────────────────────────────────────────────────────
@@ -217,6 +297,7 @@ impl McpTransportConn for HttpTransport {
             req = req.header(key, value);
         }
         req = self.apply_session_header(req);
+        req = apply_trace_header(req);
         if !has_accept {
             req = req.header("Accept", MCP_STREAMABLE_ACCEPT);
         }
@@ -487,6 +568,14 @@ struct SseSharedState {
     pending: std::collections::HashMap<u64, oneshot::Sender<JsonRpcResponse>>,
 }
 
+/// Propagate the caller's span to the MCP server as a W3C `traceparent`.
+fn apply_trace_header(req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match trace_context::current_traceparent() {
+        Some(traceparent) => req.header(trace_context::TRACEPARENT_HEADER, traceparent),
+        None => req,
+    }
+}
+
 fn derive_message_url(sse_url: &str, message_path: &str) -> Option<String> {
     let url = reqwest::Url::parse(sse_url).ok()?;
     let mut segments: Vec<&str> = url.path_segments()?.collect();
@@ -542,8 +631,17 @@ async fn handle_sse_event(
         return;
     };
 
-    let Ok(resp) = serde_json::from_value::<JsonRpcResponse>(value.clone()) else {
-        let _ = serde_json::from_value::<JsonRpcRequest>(value);
+    if value.get("method").is_some() {
+        // Server-initiated requests/notifications are not serviced over SSE;
+        // never mistake one for the response to a pending request.
+        tracing::debug!(
+            "MCP SSE `{}`: ignoring server-initiated message",
+            server_name
+        );
+        return;
+    }
+
+    let Ok(resp) = serde_json::from_value::<JsonRpcResponse>(value) else {
         return;
     };
 
@@ -686,7 +784,14 @@ async fn read_first_jsonrpc_from_sse_response(
                 continue;
             }
             let json_str = extract_json_from_sse_text(trimmed);
-            if let Ok(resp) = serde_json::from_str::<JsonRpcResponse>(json_str.as_ref()) {
+            let Ok(value) = serde_json::from_str::<serde_json::Value>(json_str.as_ref()) else {
+                continue;
+            };
+            if value.get("method").is_some() {
+                // Server-initiated message on the response stream — not a reply.
+                continue;
+            }
+            if let Ok(resp) = serde_json::from_value::<JsonRpcResponse>(value) {
                 return Ok(Some(resp));
             }
             continue;
@@ -783,6 +888,7 @@ impl McpTransportConn for SseTransport {
             for (key, value) in &self.headers {
                 req = req.header(key, value);
             }
+            req = apply_trace_header(req);
             if !has_accept {
                 req = req.header("Accept", MCP_STREAMABLE_ACCEPT);
             }
@@ -1279,4 +1385,62 @@ mod tests {
             .expect("build request");
         assert!(req.headers().get(MCP_SESSION_ID_HEADER).is_none());
     }
+
+    struct FixedHandler;
+
+    #[async_trait::async_trait]
+    impl McpServerMessageHandler for FixedHandler {
+        async fn handle_request(
+            &self,
+            method: &str,
+            _params: serde_json::Value,
+        ) -> std::result::Result<serde_json::Value, JsonRpcError> {
+            Ok(serde_json::json!({ "handled": method }))
+        }
+    }
+
+    /// A fake server that issues a sampling request before answering, and
+    /// reports whether our in-band reply carried the handler's result.
+    fn server_request_script() -> McpServerConfig {
+        let script = r#"read req
+printf '%s\n' '{"jsonrpc":"2.0","method":"notifications/message","params":{}}'
+printf '%s\n' '{"jsonrpc":"2.0","id":"srv-1","method":"sampling/createMessage","params":{}}'
+read reply
+case "$reply" in
+  *'"id":"srv-1"'*'"handled":"sampling/createMessage"'*) ok=true ;;
+  *) ok=false ;;
+esac
+printf '{"jsonrpc":"2.0","id":1,"result":{"replied":%s}}\n' "$ok"
+"#;
+        McpServerConfig {
+            name: "fake".into(),
+            command: "sh".into(),
+            args: vec!["-c".into(), script.into()],
+            ..Default::default()
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_transport_answers_server_requests_in_band() {
+        let mut transport = StdioTransport::new(&server_request_script()).unwrap();
+        transport.set_message_handler(Arc::new(FixedHandler));
+        let resp = transport
+            .send_and_recv(&JsonRpcRequest::new(1, "tools/call", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(resp.id, Some(serde_json::json!(1)));
+        assert_eq!(resp.result.unwrap()["replied"], true);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stdio_transport_without_handler_rejects_server_requests() {
+        let mut transport = StdioTransport::new(&server_request_script()).unwrap();
+        let resp = transport
+            .send_and_recv(&JsonRpcRequest::new(1, "tools/call", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert_eq!(resp.result.unwrap()["replied"], false);
+    }
 }
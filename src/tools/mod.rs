@@ -15,6 +15,7 @@
 //! To add a new tool, implement [`Tool`] in a new submodule and register it in
 //! [`all_tools_with_runtime`]. See `AGENTS.md` §7.3 for the full change playbook.
 
+pub mod apply_patch;
 pub mod ask_user;
 pub mod backup_tool;
 pub mod browser;
@@ -40,6 +41,7 @@ pub mod data_management;
 pub mod delegate;
 pub mod discord_search;
 pub mod escalate;
+pub mod file_delivery;
 pub mod file_edit;
 pub mod file_read;
 pub mod file_write;
@@ -64,6 +66,9 @@ pub mod llm_task;
 pub mod mcp_client;
 pub mod mcp_deferred;
 pub mod mcp_protocol;
+pub mod mcp_resources;
+pub mod mcp_sampling;
+pub mod mcp_server;
 pub mod mcp_tool;
 pub mod mcp_transport;
 pub mod memory_export;
@@ -76,11 +81,13 @@ pub mod model_routing_config;
 pub mod model_switch;
 pub mod node_capabilities;
 pub mod node_tool;
+pub mod nodes;
 pub mod notion_tool;
 pub mod opencode_cli;
 pub mod pdf_read;
 pub mod pipeline;
 pub mod poll;
+pub mod process;
 pub mod project_intel;
 pub mod proxy_config;
 pub mod pushover;
@@ -112,6 +119,7 @@ mod web_search_provider_routing;
 pub mod web_search_tool;
 pub mod workspace_tool;
 
+pub use apply_patch::ApplyPatchTool;
 pub use ask_user::AskUserTool;
 pub use backup_tool::BackupTool;
 pub use browser::{BrowserTool, ComputerUseConfig};
@@ -162,6 +170,8 @@ pub use linkedin::LinkedInTool;
 pub use llm_task::LlmTaskTool;
 pub use mcp_client::McpRegistry;
 pub use mcp_deferred::{ActivatedToolSet, DeferredMcpToolSet};
+pub use mcp_resources::McpResourcesTool;
+pub use mcp_server::McpServer;
 pub use mcp_tool::McpToolWrapper;
 pub use memory_export::MemoryExportTool;
 pub use memory_forget::MemoryForgetTool;
@@ -173,10 +183,12 @@ pub use model_routing_config::ModelRoutingConfigTool;
 pub use model_switch::ModelSwitchTool;
 #[allow(unused_imports)]
 pub use node_tool::NodeTool;
+pub use nodes::NodesTool;
 pub use notion_tool::NotionTool;
 pub use opencode_cli::OpenCodeCliTool;
 pub use pdf_read::PdfReadTool;
 pub use poll::{ChannelMapHandle, PollTool};
+pub use process::ProcessTool;
 pub use project_intel::ProjectIntelTool;
 pub use proxy_config::ProxyConfigTool;
 pub use pushover::PushoverTool;
@@ -406,15 +418,44 @@ pub fn all_tools_with_runtime(
     Option<ChannelMapHandle>,
 ) {
     let has_shell_access = runtime.has_shell_access();
-    let sandbox = create_sandbox(&root_config.security);
+    // Shared by the workspace tool and the boundary checks of file-mutating tools.
+    let workspace_manager = root_config.workspace.enabled.then(|| {
+        let workspaces_dir = if root_config.workspace.workspaces_dir.starts_with("~/") {
+            let home = directories::UserDirs::new()
+                .map(|u| u.home_dir().to_path_buf())
+                .unwrap_or_else(|| std::path::PathBuf::from("."));
+            home.join(&root_config.workspace.workspaces_dir[2..])
+        } else {
+            std::path::PathBuf::from(&root_config.workspace.workspaces_dir)
+        };
+        Arc::new(tokio::sync::RwLock::new(
+            crate::config::workspace::WorkspaceManager::new(workspaces_dir),
+        ))
+    });
+    let apply_patch = match &workspace_manager {
+        Some(manager) => ApplyPatchTool::new(security.clone()).with_workspaces(manager.clone()),
+        None => ApplyPatchTool::new(security.clone()),
+    };
+    let sandbox = create_sandbox(
+        &root_config.security,
+        &root_config.http_request.allowed_domains,
+    );
     let mut tool_arcs: Vec<Arc<dyn Tool>> = vec![
         Arc::new(
-            ShellTool::new_with_sandbox(security.clone(), runtime, sandbox)
+            ShellTool::new_with_sandbox(security.clone(), runtime.clone(), sandbox.clone())
                 .with_timeout_secs(root_config.shell_tool.timeout_secs),
         ),
+        Arc::new(ProcessTool::new(
+            security.clone(),
+            runtime.clone(),
+            sandbox,
+            root_config.shell_tool.max_background_processes,
+            root_config.shell_tool.background_output_bytes,
+        )),
         Arc::new(FileReadTool::new(security.clone())),
         Arc::new(FileWriteTool::new(security.clone())),
         Arc::new(FileEditTool::new(security.clone())),
+        Arc::new(apply_patch),
         Arc::new(GlobSearchTool::new(security.clone())),
         Arc::new(ContentSearchTool::new(security.clone())),
         Arc::new(CronAddTool::new(config.clone(), security.clone())),
@@ -484,6 +525,8 @@ pub fn all_tools_with_runtime(
             extra_headers: root_config.extra_headers.clone(),
             api_path: root_config.api_path.clone(),
             provider_max_tokens: root_config.provider_max_tokens,
+            provider_record_path: None,
+            budget_routing: None,
         };
         tool_arcs.push(Arc::new(LlmTaskTool::new(
             security.clone(),
@@ -591,6 +634,11 @@ pub fn all_tools_with_runtime(
         )));
     }
 
+    // Remote nodes connected to this process's gateway (config-gated)
+    if root_config.nodes.enabled {
+        tool_arcs.push(Arc::new(NodesTool::new(security.clone())));
+    }
+
     // Notion API tool (conditionally registered)
     if root_config.notion.enabled {
         let notion_api_key = if root_config.notion.api_key.trim().is_empty() {
@@ -642,8 +690,16 @@ pub fn all_tools_with_runtime(
         )));
     }
 
+    // Late-bound channel map shared by the poll tool and the tools that can
+    // deliver generated files to a channel; populated by start_channels.
+    let channel_map_handle: ChannelMapHandle = Arc::new(RwLock::new(HashMap::new()));
+
     // Report template tool — standalone, does not require project_intel
-    tool_arcs.push(Arc::new(ReportTemplateTool::new()));
+    tool_arcs.push(Arc::new(
+        ReportTemplateTool::new()
+            .with_channel_map(Arc::clone(&channel_map_handle))
+            .with_security(security.clone()),
+    ));
 
     // MCSS Security Operations
     if root_config.security_ops.enabled {
@@ -695,10 +751,10 @@ pub fn all_tools_with_runtime(
 
     // Claude Code delegation tool
     if root_config.claude_code.enabled {
-        tool_arcs.push(Arc::new(ClaudeCodeTool::new(
-            security.clone(),
-            root_config.claude_code.clone(),
-        )));
+        tool_arcs.push(Arc::new(
+            ClaudeCodeTool::new(security.clone(), root_config.claude_code.clone())
+                .with_runtime(runtime.clone()),
+        ));
     }
 
     // Claude Code task runner with Slack progress and SSH handoff
@@ -716,33 +772,35 @@ pub fn all_tools_with_runtime(
 
     // Codex CLI delegation tool
     if root_config.codex_cli.enabled {
-        tool_arcs.push(Arc::new(CodexCliTool::new(
-            security.clone(),
-            root_config.codex_cli.clone(),
-        )));
+        tool_arcs.push(Arc::new(
+            CodexCliTool::new(security.clone(), root_config.codex_cli.clone())
+                .with_runtime(runtime.clone()),
+        ));
     }
 
     // Gemini CLI delegation tool
     if root_config.gemini_cli.enabled {
-        tool_arcs.push(Arc::new(GeminiCliTool::new(
-            security.clone(),
-            root_config.gemini_cli.clone(),
-        )));
+        tool_arcs.push(Arc::new(
+            GeminiCliTool::new(security.clone(), root_config.gemini_cli.clone())
+                .with_runtime(runtime.clone()),
+        ));
     }
 
     // OpenCode CLI delegation tool
     if root_config.opencode_cli.enabled {
-        tool_arcs.push(Arc::new(OpenCodeCliTool::new(
-            security.clone(),
-            root_config.opencode_cli.clone(),
-        )));
+        tool_arcs.push(Arc::new(
+            OpenCodeCliTool::new(security.clone(), root_config.opencode_cli.clone())
+                .with_runtime(runtime.clone()),
+        ));
     }
 
     // PDF extraction (feature-gated at compile time via rag-pdf)
     tool_arcs.push(Arc::new(PdfReadTool::new(security.clone())));
 
     // Vision tools are always available
-    tool_arcs.push(Arc::new(ScreenshotTool::new(security.clone())));
+    tool_arcs.push(Arc::new(
+        ScreenshotTool::new(security.clone()).with_channel_map(Arc::clone(&channel_map_handle)),
+    ));
     tool_arcs.push(Arc::new(ImageInfoTool::new(security.clone())));
 
     // Session-to-session messaging tools (always available when sessions dir exists)
@@ -770,16 +828,18 @@ pub fn all_tools_with_runtime(
 
     // Standalone image generation tool (config-gated)
     if root_config.image_gen.enabled {
-        tool_arcs.push(Arc::new(ImageGenTool::new(
-            security.clone(),
-            workspace_dir.to_path_buf(),
-            root_config.image_gen.default_model.clone(),
-            root_config.image_gen.api_key_env.clone(),
-        )));
+        tool_arcs.push(Arc::new(
+            ImageGenTool::new(
+                security.clone(),
+                workspace_dir.to_path_buf(),
+                root_config.image_gen.default_model.clone(),
+                root_config.image_gen.api_key_env.clone(),
+            )
+            .with_channel_map(Arc::clone(&channel_map_handle)),
+        ));
     }
 
     // Poll tool — always registered; uses late-bound channel map handle
-    let channel_map_handle: ChannelMapHandle = Arc::new(RwLock::new(HashMap::new()));
     tool_arcs.push(Arc::new(PollTool::new(
         security.clone(),
         Arc::clone(&channel_map_handle),
@@ -925,6 +985,8 @@ pub fn all_tools_with_runtime(
         provider_max_tokens: root_config.provider_max_tokens,
         extra_headers: root_config.extra_headers.clone(),
         api_path: root_config.api_path.clone(),
+        provider_record_path: None,
+        budget_routing: None,
     };
 
     let delegate_handle: Option<DelegateParentToolsHandle> = if agents.is_empty() {
@@ -988,20 +1050,8 @@ pub fn all_tools_with_runtime(
     }
 
     // Workspace management tool (conditionally registered when workspace isolation is enabled)
-    if root_config.workspace.enabled {
-        let workspaces_dir = if root_config.workspace.workspaces_dir.starts_with("~/") {
-            let home = directories::UserDirs::new()
-                .map(|u| u.home_dir().to_path_buf())
-                .unwrap_or_else(|| std::path::PathBuf::from("."));
-            home.join(&root_config.workspace.workspaces_dir[2..])
-        } else {
-            std::path::PathBuf::from(&root_config.workspace.workspaces_dir)
-        };
-        let ws_manager = crate::config::workspace::WorkspaceManager::new(workspaces_dir);
-        tool_arcs.push(Arc::new(WorkspaceTool::new(
-            Arc::new(tokio::sync::RwLock::new(ws_manager)),
-            security.clone(),
-        )));
+    if let Some(manager) = workspace_manager {
+        tool_arcs.push(Arc::new(WorkspaceTool::new(manager, security.clone())));
     }
 
     // Verifiable Intent tool (opt-in via config)
@@ -12,7 +12,8 @@ use crate::gateway::nodes::{NodeInvocation, NodeRegistry};
 use crate::tools::node_capabilities::requires_approval;
 use crate::tools::traits::{Tool, ToolResult};
 
-/// Default timeout for node invocations (30 seconds).
+/// Idle timeout for node invocations (30 seconds), reset whenever the node
+/// streams output for the call.
 const NODE_INVOKE_TIMEOUT_SECS: u64 = 30;
 
 /// A zeroclaw [`Tool`] backed by a node capability.
@@ -115,13 +116,16 @@ impl Tool for NodeTool {
             };
 
         let call_id = uuid::Uuid::new_v4().to_string();
-        let (response_tx, response_rx) = tokio::sync::oneshot::channel();
+        let (response_tx, mut response_rx) = tokio::sync::oneshot::channel();
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::channel(8);
 
         let invocation = NodeInvocation {
             call_id,
             capability: self.capability_name.clone(),
             args,
+            traceparent: crate::observability::trace_context::current_traceparent(),
             response_tx,
+            progress_tx: Some(progress_tx),
         };
 
         if invoke_tx.send(invocation).await.is_err() {
@@ -135,15 +139,24 @@ impl Tool for NodeTool {
             });
         }
 
-        // Wait for response with timeout
-        match tokio::time::timeout(Duration::from_secs(NODE_INVOKE_TIMEOUT_SECS), response_rx).await
-        {
-            Ok(Ok(result)) => Ok(ToolResult {
+        // Wait for the response; streamed output keeps the call alive
+        let response = loop {
+            tokio::select! {
+                response = &mut response_rx => break Some(response),
+                Some(()) = progress_rx.recv() => {}
+                () = tokio::time::sleep(Duration::from_secs(NODE_INVOKE_TIMEOUT_SECS)) => {
+                    break None;
+                }
+            }
+        };
+
+        match response {
+            Some(Ok(result)) => Ok(ToolResult {
                 success: result.success,
                 output: result.output,
                 error: result.error,
             }),
-            Ok(Err(_)) => Ok(ToolResult {
+            Some(Err(_)) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!(
@@ -151,11 +164,11 @@ impl Tool for NodeTool {
                     self.node_id
                 )),
             }),
-            Err(_) => Ok(ToolResult {
+            None => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!(
-                    "Node '{}' invocation timed out after {NODE_INVOKE_TIMEOUT_SECS}s",
+                    "Node '{}' invocation timed out after {NODE_INVOKE_TIMEOUT_SECS}s without progress",
                     self.node_id
                 )),
             }),
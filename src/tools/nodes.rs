@@ -0,0 +1,263 @@
+//! Tool for operating remote nodes connected to this process's gateway.
+//!
+//! Nodes come and go at runtime, so instead of registering one tool per
+//! capability when the tool list is built, this tool resolves the live
+//! [`NodeRegistry`] on every call: `list` shows connected nodes and their
+//! capabilities, `invoke` runs a capability on a node.
+
+use super::node_tool::NodeTool;
+use super::traits::{Tool, ToolResult};
+use crate::gateway::nodes::{global_registry, NodeRegistry};
+use crate::security::policy::ToolOperation;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Agent-callable tool for listing and invoking node capabilities.
+pub struct NodesTool {
+    security: Arc<SecurityPolicy>,
+    /// Fixed registry for tests; `None` resolves the gateway's registry.
+    registry: Option<Arc<NodeRegistry>>,
+}
+
+impl NodesTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self {
+            security,
+            registry: None,
+        }
+    }
+
+    fn registry(&self) -> Option<Arc<NodeRegistry>> {
+        self.registry.clone().or_else(global_registry)
+    }
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+    }
+}
+
+#[async_trait]
+impl Tool for NodesTool {
+    fn name(&self) -> &str {
+        "nodes"
+    }
+
+    fn description(&self) -> &str {
+        "Operate remote nodes connected to the gateway. Actions: list (connected nodes and their capabilities), invoke (run a capability on a node with the given args)."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "invoke"],
+                    "description": "Node action to perform"
+                },
+                "node_id": {
+                    "type": "string",
+                    "description": "Target node (required for invoke)"
+                },
+                "capability": {
+                    "type": "string",
+                    "description": "Capability name as shown by list (required for invoke)"
+                },
+                "args": {
+                    "type": "object",
+                    "description": "Arguments for the capability, matching its parameter schema"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        let Some(registry) = self.registry() else {
+            return Ok(failure(
+                "No node gateway is running in this process; start the gateway with [nodes] enabled = true",
+            ));
+        };
+
+        match action {
+            "list" => {
+                let mut by_node: BTreeMap<String, Vec<String>> = registry
+                    .node_ids()
+                    .into_iter()
+                    .map(|id| (id, Vec::new()))
+                    .collect();
+                for (node_id, name, cap) in registry.all_capabilities() {
+                    by_node
+                        .entry(node_id)
+                        .or_default()
+                        .push(format!("{name}: {}", cap.description));
+                }
+
+                if by_node.is_empty() {
+                    return Ok(ToolResult {
+                        success: true,
+                        output: "No nodes connected.".to_string(),
+                        error: None,
+                    });
+                }
+
+                let mut output = format!("Connected nodes ({}):\n", by_node.len());
+                for (node_id, mut caps) in by_node {
+                    caps.sort();
+                    let _ = writeln!(output, "- {node_id}");
+                    for cap in caps {
+                        let _ = writeln!(output, "    {cap}");
+                    }
+                }
+                Ok(ToolResult {
+                    success: true,
+                    output,
+                    error: None,
+                })
+            }
+
+            "invoke" => {
+                if let Err(error) = self
+                    .security
+                    .enforce_tool_operation(ToolOperation::Act, "nodes")
+                {
+                    return Ok(failure(error));
+                }
+
+                let node_id = args
+                    .get("node_id")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("'node_id' parameter is required for invoke"))?;
+                let capability =
+                    args.get("capability")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("'capability' parameter is required for invoke")
+                        })?;
+
+                let Some((_, _, cap)) = registry
+                    .all_capabilities()
+                    .into_iter()
+                    .find(|(id, name, _)| id == node_id && name == capability)
+                else {
+                    return Ok(failure(format!(
+                        "Node '{node_id}' is not connected or has no capability '{capability}'"
+                    )));
+                };
+
+                let tool = NodeTool::new(
+                    node_id.to_string(),
+                    cap.name,
+                    cap.description,
+                    cap.parameters,
+                    registry,
+                );
+                tool.execute(args.get("args").cloned().unwrap_or_else(|| json!({})))
+                    .await
+            }
+
+            other => Ok(failure(format!(
+                "Unknown action '{other}'. Valid actions: list, invoke"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::nodes::{NodeCapability, NodeInfo, NodeInvocationResult};
+
+    fn tool_with(registry: Arc<NodeRegistry>) -> NodesTool {
+        NodesTool {
+            security: Arc::new(SecurityPolicy::default()),
+            registry: Some(registry),
+        }
+    }
+
+    fn register_echo_node(
+        registry: &NodeRegistry,
+    ) -> tokio::sync::mpsc::Receiver<crate::gateway::nodes::NodeInvocation> {
+        let (invoke_tx, invoke_rx) = tokio::sync::mpsc::channel(4);
+        registry.register(NodeInfo {
+            node_id: "lab-1".into(),
+            capabilities: vec![NodeCapability {
+                name: "shell".into(),
+                description: "Run a command".into(),
+                parameters: json!({"type": "object", "properties": {}}),
+            }],
+            invoke_tx,
+        });
+        invoke_rx
+    }
+
+    #[tokio::test]
+    async fn list_shows_nodes_and_capabilities() {
+        let registry = Arc::new(NodeRegistry::new(4));
+        let _rx = register_echo_node(&registry);
+
+        let result = tool_with(registry)
+            .execute(json!({"action": "list"}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("- lab-1"));
+        assert!(result.output.contains("shell: Run a command"));
+    }
+
+    #[tokio::test]
+    async fn invoke_routes_args_to_node() {
+        let registry = Arc::new(NodeRegistry::new(4));
+        let mut rx = register_echo_node(&registry);
+        tokio::spawn(async move {
+            let invocation = rx.recv().await.unwrap();
+            let _ = invocation.response_tx.send(NodeInvocationResult {
+                success: true,
+                output: format!("ran {}", invocation.args["command"]),
+                error: None,
+            });
+        });
+
+        let result = tool_with(registry)
+            .execute(json!({
+                "action": "invoke",
+                "node_id": "lab-1",
+                "capability": "shell",
+                "args": {"command": "uptime"}
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "ran \"uptime\"");
+    }
+
+    #[tokio::test]
+    async fn invoke_unknown_capability_fails() {
+        let registry = Arc::new(NodeRegistry::new(4));
+        let _rx = register_echo_node(&registry);
+
+        let result = tool_with(registry)
+            .execute(json!({
+                "action": "invoke",
+                "node_id": "lab-1",
+                "capability": "camera.snap"
+            }))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("no capability"));
+    }
+}
@@ -1,5 +1,6 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::OpenCodeCliConfig;
+use crate::runtime::{output_with_limits, RuntimeAdapter};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -24,11 +25,22 @@ const SAFE_ENV_VARS: &[&str] = &[
 pub struct OpenCodeCliTool {
     security: Arc<SecurityPolicy>,
     config: OpenCodeCliConfig,
+    runtime: Option<Arc<dyn RuntimeAdapter>>,
 }
 
 impl OpenCodeCliTool {
     pub fn new(security: Arc<SecurityPolicy>, config: OpenCodeCliConfig) -> Self {
-        Self { security, config }
+        Self {
+            security,
+            config,
+            runtime: None,
+        }
+    }
+
+    /// Run `opencode` under the limits `runtime` enforces for this tool.
+    pub fn with_runtime(mut self, runtime: Arc<dyn RuntimeAdapter>) -> Self {
+        self.runtime = Some(runtime);
+        self
     }
 }
 
@@ -172,7 +184,18 @@ impl Tool for OpenCodeCliTool {
         let timeout = Duration::from_secs(self.config.timeout_secs);
         cmd.kill_on_drop(true);
 
-        let result = tokio::time::timeout(timeout, cmd.output()).await;
+        let result =
+            match output_with_limits(self.runtime.as_deref(), self.name(), &mut cmd, timeout).await
+            {
+                Ok(result) => result,
+                Err(error) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(error),
+                    });
+                }
+            };
 
         match result {
             Ok(Ok(output)) => {
@@ -0,0 +1,992 @@
+//! Background and interactive shell processes.
+//!
+//! `shell` runs a command to completion under a timeout. `process` starts it
+//! on a pseudo-terminal and returns right away, so the agent can run a dev
+//! server, tail a log or drive a REPL: poll incremental output, write to its
+//! stdin, send signals, list and kill. Commands pass the same policy gate and
+//! sandbox/environment wrapping as `shell`.
+//!
+//! Processes belong to the session that started them ([`PROCESS_SESSION`]):
+//! channels scope it per conversation, an [`Agent`](crate::agent::Agent) per
+//! instance (one per WebSocket connection, ACP session or `/v1` request), and
+//! CLI runs per invocation. Terminal output goes to a bounded ring buffer per
+//! process. Exited processes whose output has been read are forgotten when
+//! the session starts another one, and at most `max_processes` exited ones
+//! are kept. Everything a session owns is killed when the session ends
+//! ([`end_session`]) or when the tool registry is dropped.
+
+use super::shell::{authorize_command, build_sandboxed_command};
+use super::traits::{Tool, ToolResult};
+use crate::runtime::{CgroupLease, RuntimeAdapter};
+use crate::security::traits::Sandbox;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde_json::json;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::sync::{Arc, OnceLock, Weak};
+use std::time::Duration;
+
+/// Default cap on running processes per session.
+pub const DEFAULT_MAX_PROCESSES: usize = 8;
+/// Default per-process output ring buffer size in bytes.
+pub const DEFAULT_OUTPUT_BUFFER_BYTES: usize = 256 * 1024;
+/// Most output returned by a single `poll`.
+const MAX_POLL_BYTES: usize = 64 * 1024;
+/// Longest a `poll` may wait for new output.
+const MAX_WAIT_MS: u64 = 30_000;
+/// Interval at which a waiting `poll` checks for output.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Longest a `write` may block on a process that is not reading its input.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Session used when no [`PROCESS_SESSION`] is in scope.
+const DEFAULT_SESSION: &str = "default";
+/// Signals the agent may send.
+const SIGNALS: &[&str] = &[
+    "SIGINT", "SIGTERM", "SIGHUP", "SIGQUIT", "SIGKILL", "SIGUSR1", "SIGUSR2",
+];
+
+tokio::task_local! {
+    /// Session that owns processes started during the current turn.
+    pub static PROCESS_SESSION: String;
+}
+
+fn current_session() -> String {
+    PROCESS_SESSION
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| DEFAULT_SESSION.to_string())
+}
+
+/// Live process managers, so [`end_session`] can reach every registry.
+static MANAGERS: OnceLock<Mutex<Vec<Weak<ProcessManager>>>> = OnceLock::new();
+
+/// Kill every process owned by `session`, in every tool registry.
+pub fn end_session(session: &str) {
+    let Some(managers) = MANAGERS.get() else {
+        return;
+    };
+    let live: Vec<Arc<ProcessManager>> = {
+        let mut managers = managers.lock();
+        managers.retain(|m| m.strong_count() > 0);
+        managers.iter().filter_map(Weak::upgrade).collect()
+    };
+    for manager in live {
+        let killed = manager.kill_session(session);
+        if killed > 0 {
+            tracing::info!("Killed {killed} background process(es) of ended session {session}");
+        }
+    }
+}
+
+// ── Output buffer ────────────────────────────────────────────────
+
+/// Bounded terminal output addressed by absolute byte offsets, so readers
+/// can resume where they stopped and learn how much was evicted meanwhile.
+struct OutputBuffer {
+    data: VecDeque<u8>,
+    capacity: usize,
+    /// Bytes ever written.
+    total: u64,
+    /// The terminal reached end of file.
+    closed: bool,
+}
+
+/// A slice of output read from an [`OutputBuffer`].
+struct OutputChunk {
+    text: String,
+    /// Offset to resume reading from.
+    next: u64,
+    /// Bytes evicted before they were read.
+    dropped: u64,
+}
+
+impl OutputBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            data: VecDeque::new(),
+            capacity: capacity.max(1),
+            total: 0,
+            closed: false,
+        }
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.total += bytes.len() as u64;
+        self.data.extend(bytes);
+        let excess = self.data.len().saturating_sub(self.capacity);
+        self.data.drain(..excess);
+    }
+
+    /// Offset of the oldest retained byte.
+    fn start(&self) -> u64 {
+        self.total - self.data.len() as u64
+    }
+
+    /// Up to `max` bytes from `cursor` on. A trailing incomplete UTF-8
+    /// sequence is held back until the rest arrives or the terminal closes.
+    fn read_from(&self, cursor: u64, max: usize) -> OutputChunk {
+        let start = self.start();
+        let dropped = start.saturating_sub(cursor);
+        let from = cursor.max(start);
+        let skip = usize::try_from(from - start).unwrap_or(usize::MAX);
+        let mut bytes: Vec<u8> = self.data.iter().skip(skip).take(max).copied().collect();
+        let at_end = from + bytes.len() as u64 == self.total;
+        if !(self.closed && at_end) {
+            let tail = incomplete_utf8_tail(&bytes);
+            bytes.truncate(bytes.len() - tail);
+        }
+        OutputChunk {
+            text: String::from_utf8_lossy(&bytes).into_owned(),
+            next: from + bytes.len() as u64,
+            dropped,
+        }
+    }
+}
+
+/// Length of an incomplete UTF-8 sequence at the end of `bytes`.
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let needed = match byte {
+            0xF0.. => 4,
+            0xE0.. => 3,
+            0xC0.. => 2,
+            _ => 1,
+        };
+        return if needed > back { back } else { 0 };
+    }
+    0
+}
+
+// ── Process table ────────────────────────────────────────────────
+
+struct ManagedProcess {
+    session: String,
+    command: String,
+    started_at: DateTime<Utc>,
+    pid: Option<u32>,
+    child: tokio::process::Child,
+    /// Write side of the pseudo-terminal. Writes happen off the table lock;
+    /// this lock only keeps concurrent writes from interleaving.
+    terminal: Arc<Mutex<File>>,
+    output: Arc<Mutex<OutputBuffer>>,
+    /// Offset the next `poll` resumes from.
+    cursor: u64,
+    exit: Option<String>,
+    lease: Option<CgroupLease>,
+}
+
+impl ManagedProcess {
+    /// Record the exit status once the process has finished.
+    fn refresh(&mut self) {
+        if self.exit.is_some() {
+            return;
+        }
+        if let Ok(Some(status)) = self.child.try_wait() {
+            let mut exit = describe_exit(status);
+            if let Some(violation) = self.lease.take().and_then(CgroupLease::finish) {
+                exit = format!("{exit} ({violation})");
+            }
+            self.exit = Some(exit);
+        }
+    }
+
+    fn is_running(&self) -> bool {
+        self.exit.is_none()
+    }
+
+    /// Exited with all of its output read.
+    fn is_drained(&self) -> bool {
+        let output = self.output.lock();
+        !self.is_running() && output.closed && output.total <= self.cursor
+    }
+
+    fn kill(&mut self) {
+        if self.is_running() {
+            if let Some(pid) = self.pid {
+                let _ = signal_group(pid, "SIGKILL");
+            }
+            let _ = self.child.start_kill();
+        }
+    }
+
+    fn summary(&self, id: &str) -> serde_json::Value {
+        let unread = self.output.lock().total.saturating_sub(self.cursor);
+        json!({
+            "id": id,
+            "command": self.command,
+            "pid": self.pid,
+            "started_at": self.started_at.to_rfc3339(),
+            "status": self.exit.as_deref().unwrap_or("running"),
+            "unread_bytes": unread,
+        })
+    }
+}
+
+fn describe_exit(status: std::process::ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exited with code {code}");
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("killed by signal {signal}");
+        }
+    }
+    "exited".to_string()
+}
+
+/// Processes started by one tool registry.
+pub struct ProcessManager {
+    processes: Mutex<HashMap<String, ManagedProcess>>,
+    next_id: Mutex<u64>,
+    max_processes: usize,
+    buffer_bytes: usize,
+}
+
+impl ProcessManager {
+    pub fn new(max_processes: usize, buffer_bytes: usize) -> Arc<Self> {
+        let manager = Arc::new(Self {
+            processes: Mutex::new(HashMap::new()),
+            next_id: Mutex::new(0),
+            max_processes: max_processes.max(1),
+            buffer_bytes,
+        });
+        MANAGERS
+            .get_or_init(|| Mutex::new(Vec::new()))
+            .lock()
+            .push(Arc::downgrade(&manager));
+        manager
+    }
+
+    /// Kill and forget every process owned by `session`; returns how many.
+    pub fn kill_session(&self, session: &str) -> usize {
+        let mut processes = self.processes.lock();
+        let ids: Vec<String> = processes
+            .iter()
+            .filter(|(_, p)| p.session == session)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &ids {
+            if let Some(mut process) = processes.remove(id) {
+                process.kill();
+            }
+        }
+        ids.len()
+    }
+}
+
+/// Forget `session`'s exited processes whose output has been read, then the
+/// oldest exited ones beyond `keep`, releasing their output buffers.
+fn prune_exited(processes: &mut HashMap<String, ManagedProcess>, session: &str, keep: usize) {
+    processes.retain(|_, p| p.session != session || !p.is_drained());
+    let mut exited: Vec<(DateTime<Utc>, String)> = processes
+        .iter()
+        .filter(|(_, p)| p.session == session && !p.is_running())
+        .map(|(id, p)| (p.started_at, id.clone()))
+        .collect();
+    if exited.len() > keep {
+        exited.sort();
+        for (_, id) in &exited[..exited.len() - keep] {
+            processes.remove(id);
+        }
+    }
+}
+
+impl Drop for ProcessManager {
+    fn drop(&mut self) {
+        for process in self.processes.get_mut().values_mut() {
+            process.kill();
+        }
+    }
+}
+
+// ── Pseudo-terminal ──────────────────────────────────────────────
+
+/// Spawn `cmd` as a session leader with a new pseudo-terminal as its
+/// controlling terminal and stdio. Returns the child and the terminal's
+/// master side.
+#[cfg(unix)]
+fn spawn_on_pty(
+    cmd: &mut tokio::process::Command,
+) -> std::io::Result<(tokio::process::Child, File)> {
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+    use std::os::unix::process::CommandExt;
+    use std::process::Stdio;
+
+    let mut master = -1;
+    let mut slave = -1;
+    let mut size = libc::winsize {
+        ws_row: 40,
+        ws_col: 160,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+    // SAFETY: openpty writes two descriptors into the provided integers and
+    // reads the window size; name and termios are optional (null).
+    let rc = unsafe {
+        libc::openpty(
+            &raw mut master,
+            &raw mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut::<libc::termios>(),
+            &raw mut size,
+        )
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: openpty succeeded, so both descriptors are open and owned here.
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+    for fd in [master.as_raw_fd(), slave.as_raw_fd()] {
+        // SAFETY: fcntl on descriptors owned above; keeps them out of
+        // unrelated children spawned concurrently.
+        if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    cmd.stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave))
+        .kill_on_drop(true);
+    // SAFETY: the hook only calls setsid(2) and ioctl(2), which are
+    // async-signal-safe.
+    unsafe {
+        cmd.as_std_mut().pre_exec(|| {
+            if libc::setsid() < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let child = cmd.spawn()?;
+    Ok((child, File::from(master)))
+}
+
+#[cfg(not(unix))]
+fn spawn_on_pty(
+    _cmd: &mut tokio::process::Command,
+) -> std::io::Result<(tokio::process::Child, File)> {
+    Err(std::io::Error::other(
+        "background processes need a Unix pseudo-terminal",
+    ))
+}
+
+/// Send a named signal to the process group led by `pid`.
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: &str) -> std::io::Result<()> {
+    let signal = match signal {
+        "SIGINT" => libc::SIGINT,
+        "SIGTERM" => libc::SIGTERM,
+        "SIGHUP" => libc::SIGHUP,
+        "SIGQUIT" => libc::SIGQUIT,
+        "SIGKILL" => libc::SIGKILL,
+        "SIGUSR1" => libc::SIGUSR1,
+        "SIGUSR2" => libc::SIGUSR2,
+        other => {
+            return Err(std::io::Error::other(format!("unsupported signal {other}")));
+        }
+    };
+    let pgid = libc::pid_t::try_from(pid).map_err(std::io::Error::other)?;
+    // SAFETY: kill(2) with a negative pid signals the process group the
+    // child leads (it called setsid before exec).
+    if unsafe { libc::kill(-pgid, signal) } < 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn signal_group(_pid: u32, _signal: &str) -> std::io::Result<()> {
+    Err(std::io::Error::other("signals are only supported on Unix"))
+}
+
+/// Copy terminal output into `output` until the terminal closes.
+fn spawn_reader(mut terminal: File, output: Arc<Mutex<OutputBuffer>>) {
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            match terminal.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => output.lock().push(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                // Linux reports EIO once every slave descriptor is closed.
+                Err(_) => break,
+            }
+        }
+        output.lock().closed = true;
+    });
+}
+
+// ── Tool ─────────────────────────────────────────────────────────
+
+/// Manage background and interactive shell processes.
+pub struct ProcessTool {
+    security: Arc<SecurityPolicy>,
+    runtime: Arc<dyn RuntimeAdapter>,
+    sandbox: Arc<dyn Sandbox>,
+    manager: Arc<ProcessManager>,
+}
+
+impl ProcessTool {
+    pub fn new(
+        security: Arc<SecurityPolicy>,
+        runtime: Arc<dyn RuntimeAdapter>,
+        sandbox: Arc<dyn Sandbox>,
+        max_processes: usize,
+        buffer_bytes: usize,
+    ) -> Self {
+        Self {
+            security,
+            runtime,
+            sandbox,
+            manager: ProcessManager::new(max_processes, buffer_bytes),
+        }
+    }
+
+    fn start(&self, args: &serde_json::Value) -> Result<String, String> {
+        let command = args
+            .get("command")
+            .and_then(serde_json::Value::as_str)
+            .filter(|c| !c.trim().is_empty())
+            .ok_or("Missing 'command' parameter")?;
+        let approved = args
+            .get("approved")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
+        let session = current_session();
+
+        {
+            let mut processes = self.manager.processes.lock();
+            let running = processes
+                .values_mut()
+                .filter(|p| p.session == session)
+                .map(|p| {
+                    p.refresh();
+                    p.is_running()
+                })
+                .filter(|running| *running)
+                .count();
+            prune_exited(&mut processes, &session, self.manager.max_processes);
+            if running >= self.manager.max_processes {
+                return Err(format!(
+                    "Too many background processes ({running} running); kill one first"
+                ));
+            }
+        }
+
+        authorize_command(&self.security, command, approved)?;
+        let mut cmd = build_sandboxed_command(
+            self.runtime.as_ref(),
+            self.sandbox.as_ref(),
+            &self.security,
+            command,
+        )?;
+        let lease = self
+            .runtime
+            .apply_resource_limits(self.name(), cmd.as_std_mut())
+            .map_err(|e| format!("Failed to apply resource limits: {e:#}"))?;
+        let (child, terminal) = spawn_on_pty(&mut cmd)
+            .map_err(|e| format!("Failed to start background process: {e}"))?;
+        drop(cmd);
+
+        let output = Arc::new(Mutex::new(OutputBuffer::new(self.manager.buffer_bytes)));
+        let reader = terminal
+            .try_clone()
+            .map_err(|e| format!("Failed to read process terminal: {e}"))?;
+        spawn_reader(reader, output.clone());
+
+        let id = {
+            let mut next = self.manager.next_id.lock();
+            *next += 1;
+            format!("p{next}")
+        };
+        let pid = child.id();
+        self.manager.processes.lock().insert(
+            id.clone(),
+            ManagedProcess {
+                session,
+                command: command.to_string(),
+                started_at: Utc::now(),
+                pid,
+                child,
+                terminal: Arc::new(Mutex::new(terminal)),
+                output,
+                cursor: 0,
+                exit: None,
+                lease,
+            },
+        );
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "id": id,
+            "pid": pid,
+            "status": "running",
+        }))
+        .unwrap_or_default())
+    }
+
+    /// Run `f` on a process owned by the current session.
+    fn with_process<T>(
+        &self,
+        args: &serde_json::Value,
+        f: impl FnOnce(&str, &mut ManagedProcess) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let id = args
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .ok_or("Missing 'id' parameter")?;
+        let session = current_session();
+        let mut processes = self.manager.processes.lock();
+        match processes.get_mut(id) {
+            Some(process) if process.session == session => {
+                process.refresh();
+                f(id, process)
+            }
+            _ => Err(format!("Unknown process: {id}")),
+        }
+    }
+
+    async fn poll(&self, args: &serde_json::Value) -> Result<String, String> {
+        let wait_ms = args
+            .get("wait_ms")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(0)
+            .min(MAX_WAIT_MS);
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(wait_ms);
+
+        loop {
+            let ready = self.with_process(args, |_, p| {
+                let output = p.output.lock();
+                Ok(output.total > p.cursor || !p.is_running() || output.closed)
+            })?;
+            if ready || tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        self.with_process(args, |id, p| {
+            let chunk = p.output.lock().read_from(p.cursor, MAX_POLL_BYTES);
+            p.cursor = chunk.next;
+            let mut report = p.summary(id);
+            report["output"] = json!(chunk.text);
+            if chunk.dropped > 0 {
+                report["dropped_bytes"] = json!(chunk.dropped);
+            }
+            Ok(serde_json::to_string_pretty(&report).unwrap_or_default())
+        })
+    }
+
+    async fn write(&self, args: &serde_json::Value) -> Result<String, String> {
+        let input = args
+            .get("input")
+            .and_then(serde_json::Value::as_str)
+            .ok_or("Missing 'input' parameter")?;
+        let enter = args
+            .get("enter")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(true);
+        let (id, terminal) = self.with_process(args, |id, p| {
+            if !p.is_running() {
+                return Err(format!("Process {id} is no longer running"));
+            }
+            Ok((id.to_string(), p.terminal.clone()))
+        })?;
+        let mut bytes = input.as_bytes().to_vec();
+        if enter {
+            bytes.push(b'\n');
+        }
+        let len = bytes.len();
+
+        // A process that stops reading fills the terminal and blocks the
+        // write, so it runs on the blocking pool without the table lock.
+        let write = tokio::task::spawn_blocking(move || {
+            let Some(mut terminal) = terminal.try_lock() else {
+                return Err("a previous write is still pending".to_string());
+            };
+            terminal
+                .write_all(&bytes)
+                .and_then(|()| terminal.flush())
+                .map_err(|e| e.to_string())
+        });
+        match tokio::time::timeout(WRITE_TIMEOUT, write).await {
+            Ok(Ok(Ok(()))) => Ok(format!("Wrote {len} bytes to {id}")),
+            Ok(Ok(Err(e))) => Err(format!("Failed to write to process {id}: {e}")),
+            Ok(Err(e)) => Err(format!("Failed to write to process {id}: {e}")),
+            Err(_) => Err(format!(
+                "Write to process {id} timed out after {}s; it is not reading its input",
+                WRITE_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    fn signal(&self, args: &serde_json::Value) -> Result<String, String> {
+        let signal = args
+            .get("signal")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("SIGINT")
+            .trim()
+            .to_ascii_uppercase();
+        let signal = if signal.starts_with("SIG") {
+            signal
+        } else {
+            format!("SIG{signal}")
+        };
+        if !SIGNALS.contains(&signal.as_str()) {
+            return Err(format!(
+                "Unsupported signal {signal}; use one of {}",
+                SIGNALS.join(", ")
+            ));
+        }
+        self.with_process(args, |id, p| {
+            if !p.is_running() {
+                return Err(format!("Process {id} is no longer running"));
+            }
+            let pid = p.pid.ok_or_else(|| format!("Process {id} has no pid"))?;
+            signal_group(pid, &signal)
+                .map_err(|e| format!("Failed to signal process {id}: {e}"))?;
+            Ok(format!("Sent {signal} to {id}"))
+        })
+    }
+
+    fn list(&self) -> String {
+        let session = current_session();
+        let mut processes = self.manager.processes.lock();
+        let mut entries: Vec<(&String, &mut ManagedProcess)> = processes
+            .iter_mut()
+            .filter(|(_, p)| p.session == session)
+            .collect();
+        entries.sort_by_key(|(_, p)| p.started_at);
+        let list: Vec<serde_json::Value> = entries
+            .into_iter()
+            .map(|(id, p)| {
+                p.refresh();
+                p.summary(id)
+            })
+            .collect();
+        if list.is_empty() {
+            return "No background processes.".to_string();
+        }
+        serde_json::to_string_pretty(&list).unwrap_or_default()
+    }
+
+    fn kill(&self, args: &serde_json::Value) -> Result<String, String> {
+        let id = self.with_process(args, |id, _| Ok(id.to_string()))?;
+        let Some(mut process) = self.manager.processes.lock().remove(&id) else {
+            return Err(format!("Unknown process: {id}"));
+        };
+        let summary = if process.is_running() {
+            process.kill();
+            format!("Killed {id}")
+        } else {
+            format!(
+                "Removed {id} ({})",
+                process.exit.as_deref().unwrap_or("exited")
+            )
+        };
+        let unread = process
+            .output
+            .lock()
+            .read_from(process.cursor, MAX_POLL_BYTES)
+            .text;
+        Ok(if unread.is_empty() {
+            summary
+        } else {
+            format!("{summary}. Unread output:\n{unread}")
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for ProcessTool {
+    fn name(&self) -> &str {
+        "process"
+    }
+
+    fn description(&self) -> &str {
+        "Run shell commands in the background on a pseudo-terminal: start a dev server, \
+         tail a log or drive an interactive program. Actions: start (returns an id), \
+         poll (new output since the last poll; wait_ms waits for output), write (send \
+         input to stdin), signal, list and kill. Use `shell` for commands that finish."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["start", "poll", "write", "signal", "list", "kill"],
+                    "description": "Operation to perform"
+                },
+                "command": {
+                    "type": "string",
+                    "description": "Shell command to start (start)"
+                },
+                "approved": {
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode (start)",
+                    "default": false
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Process id returned by start (poll, write, signal, kill)"
+                },
+                "wait_ms": {
+                    "type": "integer",
+                    "description": "Wait up to this long for new output, max 30000 (poll)",
+                    "default": 0
+                },
+                "input": {
+                    "type": "string",
+                    "description": "Text to send to the process's stdin (write)"
+                },
+                "enter": {
+                    "type": "boolean",
+                    "description": "Append a newline to input (write)",
+                    "default": true
+                },
+                "signal": {
+                    "type": "string",
+                    "enum": SIGNALS,
+                    "description": "Signal to send to the process group (signal)",
+                    "default": "SIGINT"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = args
+            .get("action")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        let result = match action {
+            "start" => self.start(&args),
+            "poll" => self.poll(&args).await,
+            "write" => self.write(&args).await,
+            "signal" => self.signal(&args),
+            "list" => Ok(self.list()),
+            "kill" => self.kill(&args),
+            other => Err(format!("Unknown action: {other}")),
+        };
+
+        Ok(match result {
+            Ok(output) => ToolResult {
+                success: true,
+                output,
+                error: None,
+            },
+            Err(reason) => ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(reason),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+    use crate::security::{AutonomyLevel, NoopSandbox};
+
+    fn tool() -> ProcessTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: vec!["echo".into(), "cat".into(), "sleep".into()],
+            ..SecurityPolicy::default()
+        });
+        ProcessTool::new(
+            security,
+            Arc::new(NativeRuntime::new()),
+            Arc::new(NoopSandbox),
+            2,
+            DEFAULT_OUTPUT_BUFFER_BYTES,
+        )
+    }
+
+    async fn start(tool: &ProcessTool, command: &str) -> String {
+        let result = tool
+            .execute(json!({"action": "start", "command": command}))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let started: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+        started["id"].as_str().unwrap().to_string()
+    }
+
+    async fn poll_until(tool: &ProcessTool, id: &str, needle: &str) -> String {
+        let mut seen = String::new();
+        for _ in 0..50 {
+            let result = tool
+                .execute(json!({"action": "poll", "id": id, "wait_ms": 200}))
+                .await
+                .unwrap();
+            let report: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+            seen.push_str(report["output"].as_str().unwrap());
+            if seen.contains(needle) {
+                break;
+            }
+        }
+        seen
+    }
+
+    #[test]
+    fn output_buffer_reads_incrementally_and_reports_eviction() {
+        let mut buf = OutputBuffer::new(8);
+        buf.push(b"hello ");
+        let first = buf.read_from(0, 100);
+        assert_eq!(first.text, "hello ");
+        assert_eq!(first.dropped, 0);
+
+        buf.push(b"world!");
+        let second = buf.read_from(first.next, 100);
+        assert_eq!(second.text, "world!");
+        assert_eq!(second.dropped, 0);
+
+        let stale = buf.read_from(0, 100);
+        assert_eq!(stale.dropped, 4);
+        assert_eq!(stale.next, 12);
+    }
+
+    #[test]
+    fn output_buffer_holds_back_split_utf8() {
+        let mut buf = OutputBuffer::new(64);
+        let snowman = "☃".as_bytes();
+        buf.push(b"a");
+        buf.push(&snowman[..2]);
+        let chunk = buf.read_from(0, 100);
+        assert_eq!(chunk.text, "a");
+        assert_eq!(chunk.next, 1);
+
+        buf.push(&snowman[2..]);
+        assert_eq!(buf.read_from(chunk.next, 100).text, "☃");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn start_poll_and_exit() {
+        let tool = tool();
+        let id = start(&tool, "echo background-ok").await;
+        let output = poll_until(&tool, &id, "background-ok").await;
+        assert!(output.contains("background-ok"));
+
+        let list = tool.execute(json!({"action": "list"})).await.unwrap();
+        assert!(list.output.contains(&id));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn write_reaches_interactive_process() {
+        let tool = tool();
+        let id = start(&tool, "cat").await;
+        let wrote = tool
+            .execute(json!({"action": "write", "id": id, "input": "ping-from-agent"}))
+            .await
+            .unwrap();
+        assert!(wrote.success, "{:?}", wrote.error);
+        let output = poll_until(&tool, &id, "ping-from-agent").await;
+        assert!(output.contains("ping-from-agent"));
+
+        let killed = tool
+            .execute(json!({"action": "kill", "id": id}))
+            .await
+            .unwrap();
+        assert!(killed.output.starts_with("Killed"));
+        let gone = tool
+            .execute(json!({"action": "poll", "id": id}))
+            .await
+            .unwrap();
+        assert!(!gone.success);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn sessions_own_their_processes() {
+        let tool = tool();
+        let id = PROCESS_SESSION
+            .scope("chat-a".into(), start(&tool, "sleep 30"))
+            .await;
+
+        let other = PROCESS_SESSION
+            .scope(
+                "chat-b".into(),
+                tool.execute(json!({"action": "poll", "id": id})),
+            )
+            .await
+            .unwrap();
+        assert!(other.error.unwrap().contains("Unknown process"));
+
+        end_session("chat-a");
+        assert!(tool.manager.processes.lock().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn enforces_policy_and_process_limit() {
+        let tool = tool();
+        let blocked = tool
+            .execute(json!({"action": "start", "command": "curl http://example.com"}))
+            .await
+            .unwrap();
+        assert!(!blocked.success);
+
+        start(&tool, "sleep 30").await;
+        start(&tool, "sleep 30").await;
+        let third = tool
+            .execute(json!({"action": "start", "command": "sleep 30"}))
+            .await
+            .unwrap();
+        assert!(third
+            .error
+            .unwrap()
+            .contains("Too many background processes"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn read_exited_processes_are_pruned_on_next_start() {
+        let tool = tool();
+        let first = start(&tool, "echo first-done").await;
+        poll_until(&tool, &first, "first-done").await;
+        for _ in 0..50 {
+            let done = tool.with_process(&json!({"id": first}), |_, p| Ok(p.is_drained()));
+            if done.unwrap() {
+                break;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        let second = start(&tool, "sleep 30").await;
+        let processes = tool.manager.processes.lock();
+        assert!(!processes.contains_key(&first));
+        assert!(processes.contains_key(&second));
+    }
+
+    #[test]
+    fn rejects_unknown_signal() {
+        let tool = tool();
+        let err = tool
+            .signal(&json!({"id": "p1", "signal": "SIGSEGV"}))
+            .unwrap_err();
+        assert!(err.contains("Unsupported signal"));
+    }
+}
@@ -3,22 +3,47 @@
 //! Exposes the report template engine directly so agents can render
 //! templates with custom variable maps without going through ProjectIntelTool.
 
+use super::file_delivery;
 use super::report_templates;
 use super::traits::{Tool, ToolResult};
+use super::ChannelMapHandle;
+use crate::channels::traits::OutboundAttachment;
+use crate::security::SecurityPolicy;
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use serde_json::json;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Standalone report template tool.
 ///
 /// Provides direct access to the template engine for rendering
 /// weekly_status, sprint_review, risk_register, and milestone_report
-/// templates in en/de/fr/it.
-pub struct ReportTemplateTool;
+/// templates in en/de/fr/it. With `deliver: true` the rendered report is
+/// also sent to the conversation's channel as a Markdown file.
+pub struct ReportTemplateTool {
+    channels: ChannelMapHandle,
+    security: Arc<SecurityPolicy>,
+}
 
 impl ReportTemplateTool {
     pub fn new() -> Self {
-        Self
+        Self {
+            channels: Arc::new(RwLock::new(HashMap::new())),
+            security: Arc::new(SecurityPolicy::default()),
+        }
+    }
+
+    /// Share the live channel map used for `deliver: true`.
+    pub fn with_channel_map(mut self, channels: ChannelMapHandle) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    /// Share the policy whose action budget `deliver: true` counts against.
+    pub fn with_security(mut self, security: Arc<SecurityPolicy>) -> Self {
+        self.security = security;
+        self
     }
 }
 
@@ -39,7 +64,7 @@ impl Tool for ReportTemplateTool {
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
-        json!({
+        let mut schema = json!({
             "type": "object",
             "properties": {
                 "template": {
@@ -59,7 +84,11 @@ impl Tool for ReportTemplateTool {
                 }
             },
             "required": ["template", "variables"]
-        })
+        });
+        if let Some(properties) = schema["properties"].as_object_mut() {
+            properties.extend(file_delivery::delivery_schema_properties());
+        }
+        schema
     }
 
     async fn execute(&self, params: serde_json::Value) -> anyhow::Result<ToolResult> {
@@ -97,9 +126,25 @@ impl Tool for ReportTemplateTool {
 
         let rendered = report_templates::render_template(template, language, &var_map)?;
 
+        let mut output = rendered.clone();
+        if file_delivery::delivery_requested(&params) {
+            let attachment =
+                OutboundAttachment::new(format!("{template}_{language}.md"), rendered.into_bytes());
+            let note = file_delivery::deliver_attachment(
+                &self.security,
+                &self.channels,
+                &params,
+                "",
+                attachment,
+            )
+            .await;
+            output.push_str("\n\n");
+            output.push_str(&note);
+        }
+
         Ok(ToolResult {
             success: true,
-            output: rendered,
+            output,
             error: None,
         })
     }
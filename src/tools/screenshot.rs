@@ -1,7 +1,12 @@
+use super::file_delivery;
 use super::traits::{Tool, ToolResult};
+use super::ChannelMapHandle;
+use crate::channels::traits::OutboundAttachment;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
+use parking_lot::RwLock;
 use serde_json::json;
+use std::collections::HashMap;
 use std::fmt::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -18,11 +23,21 @@ const MAX_BASE64_BYTES: usize = 2_097_152;
 /// Linux: tries `gnome-screenshot`, `scrot`, `import` (`ImageMagick`) in order.
 pub struct ScreenshotTool {
     security: Arc<SecurityPolicy>,
+    channels: ChannelMapHandle,
 }
 
 impl ScreenshotTool {
     pub fn new(security: Arc<SecurityPolicy>) -> Self {
-        Self { security }
+        Self {
+            security,
+            channels: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Share the live channel map used for `deliver: true`.
+    pub fn with_channel_map(mut self, channels: ChannelMapHandle) -> Self {
+        self.channels = channels;
+        self
     }
 
     /// Determine the screenshot command for the current platform.
@@ -132,7 +147,24 @@ impl ScreenshotTool {
                     });
                 }
 
-                Self::read_and_encode(&output_path).await
+                let mut result = Self::read_and_encode(&output_path).await?;
+                if result.success && file_delivery::delivery_requested(&args) {
+                    let note = match OutboundAttachment::from_path(&output_path).await {
+                        Ok(attachment) => {
+                            file_delivery::deliver_attachment(
+                                &self.security,
+                                &self.channels,
+                                &args,
+                                "",
+                                attachment,
+                            )
+                            .await
+                        }
+                        Err(e) => format!("Not delivered: {e}"),
+                    };
+                    result.output = format!("{note}\n{}", result.output);
+                }
+                Ok(result)
             }
             Ok(Err(e)) => Ok(ToolResult {
                 success: false,
@@ -226,7 +258,7 @@ impl Tool for ScreenshotTool {
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
-        json!({
+        let mut schema = json!({
             "type": "object",
             "properties": {
                 "filename": {
@@ -238,7 +270,11 @@ impl Tool for ScreenshotTool {
                     "description": "Optional region for macOS: 'selection' for interactive crop, 'window' for front window. Ignored on Linux."
                 }
             }
-        })
+        });
+        if let Some(properties) = schema["properties"].as_object_mut() {
+            properties.extend(file_delivery::delivery_schema_properties());
+        }
+        schema
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::runtime::RuntimeAdapter;
+use crate::runtime::{output_with_limits, RuntimeAdapter};
 use crate::security::traits::Sandbox;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
@@ -107,6 +107,65 @@ fn collect_allowed_shell_env_vars(security: &SecurityPolicy) -> Vec<String> {
     out
 }
 
+/// Policy gate shared by `shell` and `process`: rate limit, command
+/// allowlist and risk level, forbidden path arguments, then the action budget.
+pub(crate) fn authorize_command(
+    security: &SecurityPolicy,
+    command: &str,
+    approved: bool,
+) -> Result<(), String> {
+    if security.is_rate_limited() {
+        return Err("Rate limit exceeded: too many actions in the last hour".into());
+    }
+
+    security.validate_command_execution(command, approved)?;
+
+    if let Some(path) = security.forbidden_path_argument(command) {
+        return Err(format!("Path blocked by security policy: {path}"));
+    }
+
+    if !security.record_action() {
+        return Err("Rate limit exceeded: action budget exhausted".into());
+    }
+    Ok(())
+}
+
+/// Build `command` through the runtime, apply sandbox wrapping and replace the
+/// environment with the safe baseline plus `shell_env_passthrough`.
+pub(crate) fn build_sandboxed_command(
+    runtime: &dyn RuntimeAdapter,
+    sandbox: &dyn Sandbox,
+    security: &SecurityPolicy,
+    command: &str,
+) -> Result<tokio::process::Command, String> {
+    let mut cmd = runtime
+        .build_shell_command(command, &security.workspace_dir)
+        .map_err(|e| format!("Failed to build runtime command: {e}"))?;
+
+    // The Sandbox trait operates on std::process::Command, so use as_std_mut()
+    // to get a mutable reference to the underlying command.
+    sandbox
+        .wrap_command(cmd.as_std_mut())
+        .map_err(|e| format!("Sandbox error: {e}"))?;
+
+    // Clear the environment to prevent leaking API keys and other secrets
+    // (CWE-200), then re-add only safe, functional variables.
+    cmd.env_clear();
+    cmd.envs(allowed_shell_env(security));
+    Ok(cmd)
+}
+
+/// The safe baseline plus `shell_env_passthrough`, with their current values.
+pub(crate) fn allowed_shell_env(security: &SecurityPolicy) -> Vec<(String, String)> {
+    collect_allowed_shell_env_vars(security)
+        .into_iter()
+        .filter_map(|var| {
+            let val = std::env::var(&var).ok()?;
+            Some((var, val))
+        })
+        .collect()
+}
+
 #[async_trait]
 impl Tool for ShellTool {
     fn name(&self) -> &str {
@@ -145,16 +204,21 @@ impl Tool for ShellTool {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
-        if self.security.is_rate_limited() {
+        if let Err(reason) = authorize_command(&self.security, command, approved) {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                error: Some(reason),
             });
         }
 
-        match self.security.validate_command_execution(command, approved) {
-            Ok(_) => {}
+        let mut cmd = match build_sandboxed_command(
+            self.runtime.as_ref(),
+            self.sandbox.as_ref(),
+            &self.security,
+            command,
+        ) {
+            Ok(cmd) => cmd,
             Err(reason) => {
                 return Ok(ToolResult {
                     success: false,
@@ -162,58 +226,24 @@ impl Tool for ShellTool {
                     error: Some(reason),
                 });
             }
-        }
-
-        if let Some(path) = self.security.forbidden_path_argument(command) {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("Path blocked by security policy: {path}")),
-            });
-        }
-
-        if !self.security.record_action() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Rate limit exceeded: action budget exhausted".into()),
-            });
-        }
-
-        // Execute with timeout to prevent hanging commands.
-        // Clear the environment to prevent leaking API keys and other secrets
-        // (CWE-200), then re-add only safe, functional variables.
-        let mut cmd = match self
-            .runtime
-            .build_shell_command(command, &self.security.workspace_dir)
-        {
-            Ok(cmd) => cmd,
-            Err(e) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!("Failed to build runtime command: {e}")),
-                });
-            }
         };
 
-        // Apply sandbox wrapping before execution.
-        // The Sandbox trait operates on std::process::Command, so use as_std_mut()
-        // to get a mutable reference to the underlying command.
-        self.sandbox
-            .wrap_command(cmd.as_std_mut())
-            .map_err(|e| anyhow::anyhow!("Sandbox error: {}", e))?;
-
-        cmd.env_clear();
-
-        for var in collect_allowed_shell_env_vars(&self.security) {
-            if let Ok(val) = std::env::var(&var) {
-                cmd.env(&var, val);
-            }
-        }
-
+        // Execute with timeout to prevent hanging commands.
         let timeout_secs = self.timeout_secs;
-        let result = tokio::time::timeout(Duration::from_secs(timeout_secs), cmd.output()).await;
+        let timeout = Duration::from_secs(timeout_secs);
+        let result =
+            match output_with_limits(Some(self.runtime.as_ref()), self.name(), &mut cmd, timeout)
+                .await
+            {
+                Ok(result) => result,
+                Err(error) => {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: String::new(),
+                        error: Some(error),
+                    });
+                }
+            };
 
         match result {
             Ok(Ok(output)) => {
@@ -60,7 +60,11 @@ impl Tool for SopAdvanceTool {
                 },
                 "output": {
                     "type": "string",
-                    "description": "Brief summary of what happened in this step"
+                    "description": "Brief summary of what happened in this step, or a JSON value when the step declares an output schema or branches on its output"
+                },
+                "step": {
+                    "type": "integer",
+                    "description": "Step number being reported. Required for parallel steps; defaults to the step in progress"
                 }
             },
             "required": ["run_id", "status", "output"]
@@ -105,14 +109,19 @@ impl Tool for SopAdvanceTool {
                 .lock()
                 .map_err(|e| anyhow::anyhow!("Engine lock poisoned: {e}"))?;
 
-            let current_step = engine
+            let run = engine
                 .get_run(run_id)
-                .map(|r| r.current_step)
                 .ok_or_else(|| anyhow::anyhow!("Run not found: {run_id}"))?;
+            let step_number = args
+                .get("step")
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|n| u32::try_from(n).ok())
+                .or_else(|| run.flow.in_flight.first().map(|f| f.step_number))
+                .unwrap_or(run.current_step);
 
             let now = now_iso8601();
             let step_result = SopStepResult {
-                step_number: current_step,
+                step_number,
                 status: step_status,
                 output: output.to_string(),
                 started_at: now.clone(),
@@ -193,6 +202,35 @@ impl Tool for SopAdvanceTool {
                             step.title
                         )
                     }
+                    SopRunAction::FanOut { run_id, actions } => {
+                        let contexts: Vec<String> =
+                            actions.iter().map(SopRunAction::step_context).collect();
+                        format!(
+                            "Step recorded. Run {run_id} continues with {} parallel steps; \
+                             report each with its step number:\n\n{}",
+                            actions.len(),
+                            contexts.join("\n---\n")
+                        )
+                    }
+                    SopRunAction::Retry {
+                        run_id,
+                        attempt,
+                        delay_secs,
+                        action,
+                    } => {
+                        format!(
+                            "Step failed. Retry {attempt} for run {run_id} after {delay_secs}s:\n\n{}",
+                            action.step_context()
+                        )
+                    }
+                    SopRunAction::AwaitParallel { run_id, pending } => {
+                        let pending: Vec<String> =
+                            pending.iter().map(ToString::to_string).collect();
+                        format!(
+                            "Step recorded. Run {run_id} is waiting on parallel step(s) {}.",
+                            pending.join(", ")
+                        )
+                    }
                 };
                 Ok(ToolResult {
                     success: true,
@@ -236,6 +274,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::default(),
                     schema: None,
+                    flow: StepFlow::default(),
                 },
                 SopStep {
                     number: 2,
@@ -245,6 +284,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::default(),
                     schema: None,
+                    flow: StepFlow::default(),
                 },
             ],
             cooldown_secs: 0,
@@ -262,6 +302,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: "2026-02-19T12:00:00Z".into(),
+            idempotency_key: None,
         };
         engine.start_run("test-sop", event).unwrap();
         let run_id = engine
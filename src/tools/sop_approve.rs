@@ -104,6 +104,11 @@ impl Tool for SopApproveTool {
                     } => {
                         format!("Approved. Proceeding with run {run_id}.\n\n{context}")
                     }
+                    SopRunAction::FanOut { ref run_id, .. } => format!(
+                        "Approved. Run {run_id} continues with parallel steps; report each \
+                         with its step number:\n\n{}",
+                        action.step_context()
+                    ),
                     other => format!("Approved. Action: {other:?}"),
                 };
                 Ok(ToolResult {
@@ -145,6 +150,7 @@ mod tests {
                 requires_confirmation: false,
                 kind: SopStepKind::default(),
                 schema: None,
+                flow: StepFlow::default(),
             }],
             cooldown_secs: 0,
             max_concurrent: 1,
@@ -161,6 +167,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: "2026-02-19T12:00:00Z".into(),
+            idempotency_key: None,
         };
         // Start run — Supervised mode → WaitApproval
         engine.start_run("test-sop", event).unwrap();
@@ -71,6 +71,7 @@ impl Tool for SopExecuteTool {
             topic: None,
             payload,
             timestamp: now_iso8601(),
+            idempotency_key: None,
         };
 
         // Lock engine, start run, snapshot run for audit, then drop lock
@@ -130,6 +131,18 @@ impl Tool for SopExecuteTool {
                             step.title
                         )
                     }
+                    action @ SopRunAction::FanOut { .. } => {
+                        format!(
+                            "SOP run started: {} — these steps run in parallel; report each \
+                             with its step number:\n\n{}",
+                            action_run_id(&action).unwrap_or_default(),
+                            action.step_context()
+                        )
+                    }
+                    SopRunAction::Retry { run_id, .. }
+                    | SopRunAction::AwaitParallel { run_id, .. } => {
+                        format!("SOP run started: {run_id}")
+                    }
                 };
                 Ok(ToolResult {
                     success: true,
@@ -154,7 +167,10 @@ fn action_run_id(action: &SopRunAction) -> Option<&str> {
         | SopRunAction::Completed { run_id, .. }
         | SopRunAction::Failed { run_id, .. }
         | SopRunAction::DeterministicStep { run_id, .. }
-        | SopRunAction::CheckpointWait { run_id, .. } => Some(run_id),
+        | SopRunAction::CheckpointWait { run_id, .. }
+        | SopRunAction::FanOut { run_id, .. }
+        | SopRunAction::Retry { run_id, .. }
+        | SopRunAction::AwaitParallel { run_id, .. } => Some(run_id),
     }
 }
 
@@ -184,6 +200,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::default(),
                     schema: None,
+                    flow: StepFlow::default(),
                 },
                 SopStep {
                     number: 2,
@@ -193,6 +210,7 @@ mod tests {
                     requires_confirmation: false,
                     kind: SopStepKind::default(),
                     schema: None,
+                    flow: StepFlow::default(),
                 },
             ],
             cooldown_secs: 0,
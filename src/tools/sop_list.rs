@@ -139,6 +139,7 @@ mod tests {
                 requires_confirmation: false,
                 kind: SopStepKind::default(),
                 schema: None,
+                flow: StepFlow::default(),
             }],
             cooldown_secs: 0,
             max_concurrent: 1,
@@ -5,7 +5,7 @@ use async_trait::async_trait;
 use serde_json::json;
 
 use super::traits::{Tool, ToolResult};
-use crate::sop::{SopEngine, SopMetricsCollector};
+use crate::sop::{SopEngine, SopMetricsCollector, SopRunAction};
 
 /// Query SOP execution status — active runs, finished runs, or a specific run by ID.
 pub struct SopStatusTool {
@@ -82,17 +82,36 @@ impl Tool for SopStatusTool {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
-        let engine = self
+        let mut engine = self
             .engine
             .lock()
             .map_err(|e| anyhow::anyhow!("Engine lock poisoned: {e}"))?;
 
+        // Steps past their time limit are failed (and retried or compensated)
+        // before reporting, so status reflects the timeout.
+        let timed_out = engine.check_step_timeouts().len();
+        let mut timeout_note = if timed_out > 0 {
+            format!("{timed_out} step(s) timed out and were retried or failed.\n\n")
+        } else {
+            String::new()
+        };
+        // The engine tick only re-dispatches deterministic retries; agent-driven
+        // ones are handed to the agent here once their backoff has elapsed.
+        for retry in engine.take_due_agent_retries() {
+            if let SopRunAction::ExecuteStep {
+                run_id, context, ..
+            } = retry
+            {
+                let _ = write!(timeout_note, "Retry due for run {run_id}:\n\n{context}\n\n");
+            }
+        }
+
         // Query specific run
         if let Some(run_id) = run_id {
             return match engine.get_run(run_id) {
                 Some(run) => {
                     let mut output = format!(
-                        "Run: {}\nSOP: {}\nStatus: {}\nStep: {} of {}\nStarted: {}\n",
+                        "{timeout_note}Run: {}\nSOP: {}\nStatus: {}\nStep: {} of {}\nStarted: {}\n",
                         run.run_id,
                         run.sop_name,
                         run.status,
@@ -103,6 +122,18 @@ impl Tool for SopStatusTool {
                     if let Some(ref completed) = run.completed_at {
                         let _ = writeln!(output, "Completed: {completed}");
                     }
+                    if !run.flow.in_flight.is_empty() {
+                        let steps: Vec<String> = run
+                            .flow
+                            .in_flight
+                            .iter()
+                            .map(|f| format!("{} (attempt {})", f.step_number, f.attempt))
+                            .collect();
+                        let _ = writeln!(output, "In flight: {}", steps.join(", "));
+                    }
+                    if let Some(ref reason) = run.flow.failure_reason {
+                        let _ = writeln!(output, "Failure: {reason}");
+                    }
                     if !run.step_results.is_empty() {
                         let _ = writeln!(output, "\nStep results:");
                         for step in &run.step_results {
@@ -129,7 +160,7 @@ impl Tool for SopStatusTool {
         }
 
         // List runs for a specific SOP or all active runs
-        let mut output = String::new();
+        let mut output = timeout_note;
 
         // Active runs
         let active: Vec<_> = engine
@@ -209,6 +240,13 @@ const METRIC_SUFFIXES: &[&str] = &[
     "human_intervention_rate",
     "timeout_auto_approvals",
     "timeout_approval_rate",
+    "steps_retried",
+    "retry_rate",
+    "steps_timed_out",
+    "branches_taken",
+    "parallel_fan_outs",
+    "compensations_run",
+    "runs_compensated",
     "completion_rate_7d",
     "deviation_rate_7d",
     "completion_rate_30d",
@@ -257,6 +295,7 @@ mod tests {
                 requires_confirmation: false,
                 kind: SopStepKind::default(),
                 schema: None,
+                flow: StepFlow::default(),
             }],
             cooldown_secs: 0,
             max_concurrent: 2,
@@ -277,6 +316,7 @@ mod tests {
             topic: None,
             payload: None,
             timestamp: "2026-02-19T12:00:00Z".into(),
+            idempotency_key: None,
         }
     }
 
@@ -319,6 +359,39 @@ mod tests {
         assert!(result.output.contains("Status: running"));
     }
 
+    #[tokio::test]
+    async fn status_hands_out_due_retry() {
+        let mut sop = test_sop("s1");
+        sop.steps[0].flow.retry = Some(StepRetry {
+            max_retries: 1,
+            backoff_secs: 0,
+        });
+        let engine = engine_with_sops(vec![sop]);
+        let run_id = {
+            let mut e = engine.lock().unwrap();
+            e.start_run("s1", manual_event()).unwrap();
+            let run_id = e.active_runs().keys().next().unwrap().clone();
+            let result = SopStepResult {
+                step_number: 1,
+                status: SopStepStatus::Failed,
+                output: "503".into(),
+                started_at: "2026-02-19T12:00:00Z".into(),
+                completed_at: None,
+            };
+            e.advance_step(&run_id, result).unwrap();
+            run_id
+        };
+        let tool = SopStatusTool::new(engine);
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(result
+            .output
+            .contains(&format!("Retry due for run {run_id}")));
+        assert!(result.output.contains("Do it"));
+
+        let result = tool.execute(json!({})).await.unwrap();
+        assert!(!result.output.contains("Retry due"));
+    }
+
     #[tokio::test]
     async fn status_unknown_run() {
         let engine = engine_with_sops(vec![]);
@@ -381,6 +454,7 @@ mod tests {
             }],
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
         collector.record_run_complete(&run);
 
@@ -417,6 +491,7 @@ mod tests {
             }],
             waiting_since: None,
             llm_calls_saved: 0,
+            flow: SopRunFlow::default(),
         };
         collector.record_run_complete(&run);
 
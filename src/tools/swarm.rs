@@ -1,5 +1,6 @@
 use super::traits::{Tool, ToolResult};
 use crate::config::{DelegateAgentConfig, SwarmConfig, SwarmStrategy};
+use crate::observability::trace_context::{SpanKind, TraceSpan};
 use crate::providers::{self, Provider};
 use crate::security::policy::ToolOperation;
 use crate::security::SecurityPolicy;
@@ -76,6 +77,12 @@ impl SwarmTool {
             .map_err(|r| r.error.unwrap_or_default())?;
 
         let temperature = agent_config.temperature.unwrap_or(0.7);
+        let mut span = TraceSpan::start(format!("invoke_agent {agent_name}"), SpanKind::Client)
+            .with_attribute("gen_ai.operation.name", "invoke_agent")
+            .with_attribute("gen_ai.agent.name", agent_name)
+            .with_attribute("gen_ai.provider.name", agent_config.provider.as_str())
+            .with_attribute("gen_ai.request.model", agent_config.model.as_str())
+            .with_attribute("gen_ai.request.temperature", temperature);
 
         let result = tokio::time::timeout(
             Duration::from_secs(timeout_secs),
@@ -88,7 +95,7 @@ impl SwarmTool {
         )
         .await;
 
-        match result {
+        let outcome = match result {
             Ok(Ok(response)) => {
                 if response.trim().is_empty() {
                     Ok("[Empty response]".to_string())
@@ -100,7 +107,11 @@ impl SwarmTool {
             Err(_) => Err(format!(
                 "Agent '{agent_name}' timed out after {timeout_secs}s"
             )),
+        };
+        if let Err(e) = &outcome {
+            span.set_error(e.as_str());
         }
+        outcome
     }
 
     async fn execute_sequential(
@@ -0,0 +1,479 @@
+//! Process-wide, persisted trust tracking.
+//!
+//! When `[trust] enabled = true`, a single [`TrustTracker`] is shared by every
+//! agent loop in the process and persisted to `<workspace>/state/trust.json`.
+//! Tool outcomes, denied approvals, "undo that" follow-ups and SOP deviations
+//! feed it. Approval managers and security policies built with the tracker
+//! (see [`init`]) consult it to reduce autonomy for a tool domain while that
+//! domain's score is below the regression threshold, and restore it once the
+//! score recovers.
+
+use super::types::{CorrectionType, TrustConfig, TrustScore, TrustState, TrustTracker};
+use crate::config::Config;
+use crate::security::AutonomyLevel;
+use chrono::Utc;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+
+/// Correction events kept on disk; older ones are dropped on save.
+const MAX_PERSISTED_CORRECTIONS: usize = 500;
+/// Conversations whose latest-turn domains are remembered; the least
+/// recently active one is forgotten first.
+const MAX_TRACKED_CONVERSATIONS: usize = 1024;
+/// Follow-up messages longer than this are not treated as corrections.
+const MAX_CORRECTION_MESSAGE_CHARS: usize = 280;
+/// Phrases that mark a user message as a correction of the previous turn.
+const CORRECTION_PHRASES: &[&str] = &[
+    "undo that",
+    "undo it",
+    "undo this",
+    "revert that",
+    "revert it",
+    "revert this",
+    "roll that back",
+    "roll it back",
+    "that was wrong",
+    "that's wrong",
+    "that is wrong",
+    "that was a mistake",
+    "don't do that",
+    "do not do that",
+    "you shouldn't have",
+    "you should not have",
+];
+
+static GLOBAL_TRUST: OnceLock<Arc<PersistentTrust>> = OnceLock::new();
+
+/// The process-wide tracker for `config`, opened on first use.
+///
+/// Returns `None` when trust tracking is disabled. Entry points pass the
+/// handle to the approval managers and security policies they build.
+pub fn init(config: &Config) -> Option<Arc<PersistentTrust>> {
+    if !config.trust.enabled {
+        return None;
+    }
+    Some(Arc::clone(GLOBAL_TRUST.get_or_init(|| {
+        Arc::new(PersistentTrust::open(
+            config.trust.clone(),
+            &config.workspace_dir,
+        ))
+    })))
+}
+
+/// The process-wide tracker, if [`init`] opened one. Used to feed outcomes
+/// from code that does not hold a handle.
+pub fn global() -> Option<&'static PersistentTrust> {
+    GLOBAL_TRUST.get().map(Arc::as_ref)
+}
+
+/// Current scores for display, sorted by domain.
+///
+/// Uses the live tracker when this process has one, otherwise reads the
+/// persisted state so `zeroclaw status` can report on a running daemon.
+pub fn current_scores(config: &Config) -> Vec<TrustScore> {
+    match global() {
+        Some(trust) => trust.scores(),
+        None => PersistentTrust::open(config.trust.clone(), &config.workspace_dir).scores(),
+    }
+}
+
+/// Map a tool name to the trust domain it acts in.
+///
+/// Related tools share a domain so that, for example, a failing
+/// `microsoft365.mail_send` and a denied Gmail action both count against
+/// `email`. Unrecognized tools form their own domain.
+pub fn tool_domain(tool_name: &str) -> String {
+    let name = tool_name.to_ascii_lowercase();
+    let domain = if name == "shell" || name == "process" {
+        "shell"
+    } else if name.starts_with("git") {
+        "git"
+    } else if name.contains("mail") {
+        "email"
+    } else if name.starts_with("file_") || name == "apply_patch" {
+        "file"
+    } else if name.starts_with("browser") || name.starts_with("web_") || name == "http_request" {
+        "web"
+    } else if name.starts_with("memory_") {
+        "memory"
+    } else if name.starts_with("cron_") || name == "schedule" {
+        "cron"
+    } else if name.starts_with("sop_") {
+        "sop"
+    } else {
+        return name.split('.').next().unwrap_or_default().to_string();
+    };
+    domain.to_string()
+}
+
+/// Whether a user message reads as "undo what you just did".
+pub fn is_correction_message(message: &str) -> bool {
+    let message = message.trim();
+    if message.is_empty() || message.chars().count() > MAX_CORRECTION_MESSAGE_CHARS {
+        return false;
+    }
+    let normalized = message.to_lowercase().replace('\u{2019}', "'");
+    CORRECTION_PHRASES
+        .iter()
+        .any(|phrase| normalized.contains(phrase))
+}
+
+fn level_name(level: AutonomyLevel) -> &'static str {
+    match level {
+        AutonomyLevel::ReadOnly => "read_only",
+        AutonomyLevel::Supervised => "supervised",
+        AutonomyLevel::Full => "full",
+    }
+}
+
+fn level_from_name(name: &str) -> AutonomyLevel {
+    match name {
+        "read_only" => AutonomyLevel::ReadOnly,
+        "full" => AutonomyLevel::Full,
+        _ => AutonomyLevel::Supervised,
+    }
+}
+
+/// Domains touched by the latest turn of a conversation.
+struct RecentTurn {
+    domains: Vec<String>,
+    touched: Instant,
+}
+
+/// Writes trust snapshots to disk, newest first.
+struct TrustWriter {
+    path: PathBuf,
+    /// Latest snapshot not yet written; superseded snapshots are dropped.
+    pending: Mutex<Option<TrustState>>,
+    /// Held for the whole write so concurrent flushes never interleave.
+    write_lock: Mutex<()>,
+}
+
+impl TrustWriter {
+    fn submit(self: &Arc<Self>, state: TrustState) {
+        *self.pending.lock() = Some(state);
+        let writer = Arc::clone(self);
+        crate::util::run_blocking(move || writer.flush());
+    }
+
+    fn flush(&self) {
+        let _guard = self.write_lock.lock();
+        let Some(state) = self.pending.lock().take() else {
+            return;
+        };
+        if let Err(e) = self.write(&state) {
+            tracing::warn!("Failed to persist trust state: {e}");
+        }
+    }
+
+    fn write(&self, state: &TrustState) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self
+            .path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        std::fs::write(&tmp, serde_json::to_vec_pretty(state)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// A [`TrustTracker`] backed by a JSON file.
+pub struct PersistentTrust {
+    tracker: Mutex<TrustTracker>,
+    writer: Arc<TrustWriter>,
+    /// Domains touched by the latest turn of each conversation, used to
+    /// attribute an "undo that" follow-up to what was actually done.
+    recent_domains: Mutex<HashMap<String, RecentTurn>>,
+}
+
+impl std::fmt::Debug for PersistentTrust {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistentTrust")
+            .field("path", &self.writer.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PersistentTrust {
+    /// Load persisted state from the workspace, starting fresh if it is
+    /// missing or unreadable.
+    pub fn open(config: TrustConfig, workspace_dir: &Path) -> Self {
+        let path = workspace_dir.join("state").join("trust.json");
+        let state = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str::<TrustState>(&raw).unwrap_or_else(|e| {
+                tracing::warn!("Ignoring unreadable trust state {}: {e}", path.display());
+                TrustState::default()
+            }),
+            Err(_) => TrustState::default(),
+        };
+        Self {
+            tracker: Mutex::new(TrustTracker::from_state(config, state)),
+            writer: Arc::new(TrustWriter {
+                path,
+                pending: Mutex::new(None),
+                write_lock: Mutex::new(()),
+            }),
+            recent_domains: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Record the outcome of a tool call made in `conversation`.
+    pub fn record_tool_outcome(
+        &self,
+        conversation: &str,
+        tool_name: &str,
+        success: bool,
+        detail: Option<&str>,
+    ) {
+        let domain = tool_domain(tool_name);
+        {
+            let mut recent = self.recent_domains.lock();
+            if !recent.contains_key(conversation) && recent.len() >= MAX_TRACKED_CONVERSATIONS {
+                let stalest = recent
+                    .iter()
+                    .min_by_key(|(_, turn)| turn.touched)
+                    .map(|(key, _)| key.clone());
+                if let Some(stalest) = stalest {
+                    recent.remove(&stalest);
+                }
+            }
+            let turn = recent
+                .entry(conversation.to_string())
+                .or_insert_with(|| RecentTurn {
+                    domains: Vec::new(),
+                    touched: Instant::now(),
+                });
+            turn.touched = Instant::now();
+            if !turn.domains.contains(&domain) {
+                turn.domains.push(domain.clone());
+            }
+        }
+
+        if success {
+            self.update(&domain, |tracker| tracker.record_success(&domain));
+        } else {
+            let description = format!("{tool_name} failed: {}", detail.unwrap_or("no details"));
+            self.update(&domain, |tracker| {
+                tracker.record_correction(&domain, CorrectionType::QualityFailure, &description);
+            });
+        }
+    }
+
+    /// Record that the user overrode the agent on a tool (e.g. denied approval).
+    pub fn record_user_override(&self, tool_name: &str, description: &str) {
+        let domain = tool_domain(tool_name);
+        let description = format!("{tool_name}: {description}");
+        self.update(&domain, |tracker| {
+            tracker.record_correction(&domain, CorrectionType::UserOverride, &description);
+        });
+    }
+
+    /// Record that an SOP run deviated from its procedure.
+    pub fn record_sop_deviation(&self, sop_name: &str, description: &str) {
+        let domain = tool_domain("sop_execute");
+        let description = format!("{sop_name}: {description}");
+        self.update(&domain, |tracker| {
+            tracker.record_correction(&domain, CorrectionType::SopDeviation, &description);
+        });
+    }
+
+    /// Inspect the message that starts a new turn in `conversation`.
+    ///
+    /// A correction ("undo that", "that was wrong", ...) counts as a user
+    /// override against every domain the previous turn acted in. Either way
+    /// the previous turn's domains are forgotten, so a correction is only
+    /// attributed once.
+    pub fn observe_user_message(&self, conversation: &str, message: &str) {
+        let Some(RecentTurn { domains, .. }) = self.recent_domains.lock().remove(conversation)
+        else {
+            return;
+        };
+        if !is_correction_message(message) {
+            return;
+        }
+        let description = format!(
+            "user correction: {}",
+            crate::util::truncate_with_ellipsis(message.trim(), 120)
+        );
+        for domain in domains {
+            self.update(&domain, |tracker| {
+                tracker.record_correction(&domain, CorrectionType::UserOverride, &description);
+            });
+        }
+    }
+
+    /// Autonomy to apply to `tool_name` given the configured `base` level.
+    ///
+    /// Drops one level while the tool's domain is in regression. Domains
+    /// without any recorded events keep the base level.
+    pub fn effective_autonomy(&self, tool_name: &str, base: AutonomyLevel) -> AutonomyLevel {
+        let domain = tool_domain(tool_name);
+        let mut tracker = self.tracker.lock();
+        if tracker.score_if_tracked(&domain).is_none() {
+            return base;
+        }
+        tracker.apply_decay(Utc::now());
+        level_from_name(&tracker.get_effective_autonomy(&domain, level_name(base)))
+    }
+
+    /// All tracked scores with decay applied, sorted by domain.
+    pub fn scores(&self) -> Vec<TrustScore> {
+        let mut tracker = self.tracker.lock();
+        tracker.apply_decay(Utc::now());
+        let mut scores: Vec<TrustScore> = tracker.snapshot().into_values().collect();
+        scores.sort_by(|a, b| a.domain.cmp(&b.domain));
+        scores
+    }
+
+    /// The configured regression threshold.
+    pub fn regression_threshold(&self) -> f64 {
+        self.tracker.lock().config().regression_threshold
+    }
+
+    /// Apply a mutation, log regression transitions and persist the result.
+    fn update(&self, domain: &str, mutate: impl FnOnce(&mut TrustTracker)) {
+        let mut tracker = self.tracker.lock();
+        tracker.apply_decay(Utc::now());
+        let was_regressed = tracker.score_if_tracked(domain).is_some()
+            && tracker.check_regression(domain).is_some();
+        mutate(&mut tracker);
+        match (was_regressed, tracker.check_regression(domain)) {
+            (false, Some(alert)) => tracing::warn!(
+                domain,
+                score = alert.current_score,
+                threshold = alert.threshold,
+                "Trust regression detected; autonomy reduced for this domain"
+            ),
+            (true, None) => {
+                tracing::info!(domain, "Trust recovered; autonomy restored for this domain");
+            }
+            _ => {}
+        }
+        // Snapshot under the tracker lock so the writer never sees an older
+        // state after a newer one.
+        let state = tracker.to_state(MAX_PERSISTED_CORRECTIONS);
+        self.writer.submit(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn strict_config() -> TrustConfig {
+        TrustConfig {
+            enabled: true,
+            initial_score: 0.6,
+            correction_penalty: 0.2,
+            success_boost: 0.2,
+            ..TrustConfig::default()
+        }
+    }
+
+    #[test]
+    fn tool_domain_groups_related_tools() {
+        assert_eq!(tool_domain("shell"), "shell");
+        assert_eq!(tool_domain("git_operations"), "git");
+        assert_eq!(tool_domain("microsoft365.mail_send"), "email");
+        assert_eq!(tool_domain("file_write"), "file");
+        assert_eq!(tool_domain("sop_advance"), "sop");
+        assert_eq!(tool_domain("jira"), "jira");
+        assert_eq!(tool_domain("notion.pages"), "notion");
+    }
+
+    #[test]
+    fn correction_messages_are_short_and_explicit() {
+        assert!(is_correction_message("Undo that, please"));
+        assert!(is_correction_message("no — that\u{2019}s wrong"));
+        assert!(!is_correction_message("run the tests again"));
+        assert!(!is_correction_message(&format!(
+            "undo that {}",
+            "x".repeat(MAX_CORRECTION_MESSAGE_CHARS)
+        )));
+    }
+
+    #[test]
+    fn regression_tightens_and_recovery_relaxes_domain_autonomy() {
+        let tmp = TempDir::new().unwrap();
+        let trust = PersistentTrust::open(strict_config(), tmp.path());
+
+        // Untracked domains keep the configured level.
+        assert_eq!(
+            trust.effective_autonomy("git_operations", AutonomyLevel::Full),
+            AutonomyLevel::Full
+        );
+
+        trust.record_user_override("git_operations", "approval denied");
+        assert_eq!(
+            trust.effective_autonomy("git_operations", AutonomyLevel::Full),
+            AutonomyLevel::Supervised
+        );
+        assert_eq!(
+            trust.effective_autonomy("git_operations", AutonomyLevel::Supervised),
+            AutonomyLevel::ReadOnly
+        );
+        // Other domains are unaffected.
+        assert_eq!(
+            trust.effective_autonomy("shell", AutonomyLevel::Full),
+            AutonomyLevel::Full
+        );
+
+        trust.record_tool_outcome("cli:", "git_operations", true, None);
+        assert_eq!(
+            trust.effective_autonomy("git_operations", AutonomyLevel::Full),
+            AutonomyLevel::Full
+        );
+    }
+
+    #[test]
+    fn undo_follow_up_penalizes_previous_turn_domains_once() {
+        let tmp = TempDir::new().unwrap();
+        let trust = PersistentTrust::open(strict_config(), tmp.path());
+
+        trust.record_tool_outcome("telegram:42", "shell", true, None);
+        trust.observe_user_message("telegram:42", "undo that");
+        trust.observe_user_message("telegram:42", "undo that");
+
+        let scores = trust.scores();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].domain, "shell");
+        // +0.2 for the success, -0.2 for the single attributed correction.
+        assert!((scores[0].score - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn recent_domains_forget_the_stalest_conversation_at_the_cap() {
+        let tmp = TempDir::new().unwrap();
+        let trust = PersistentTrust::open(strict_config(), tmp.path());
+
+        for i in 0..=MAX_TRACKED_CONVERSATIONS {
+            trust.record_tool_outcome(&format!("chat:{i}"), "shell", true, None);
+        }
+
+        let recent = trust.recent_domains.lock();
+        assert_eq!(recent.len(), MAX_TRACKED_CONVERSATIONS);
+        assert!(!recent.contains_key("chat:0"));
+        assert!(recent.contains_key(&format!("chat:{MAX_TRACKED_CONVERSATIONS}")));
+    }
+
+    #[test]
+    fn state_persists_across_reopen() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let trust = PersistentTrust::open(strict_config(), tmp.path());
+            trust.record_sop_deviation("deploy", "step 2 skipped");
+        }
+
+        let reopened = PersistentTrust::open(strict_config(), tmp.path());
+        let scores = reopened.scores();
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores[0].domain, "sop");
+        assert!(scores[0].score < reopened.regression_threshold());
+    }
+}
@@ -11,11 +11,13 @@ fn trust_config_defaults() {
     assert_eq!(config.regression_threshold, 0.5);
     assert_eq!(config.correction_penalty, 0.05);
     assert_eq!(config.success_boost, 0.01);
+    assert!(!config.enabled);
 }
 
 #[test]
 fn trust_config_serde_roundtrip() {
     let config = TrustConfig {
+        enabled: true,
         initial_score: 0.9,
         decay_half_life_days: 45.0,
         regression_threshold: 0.6,
@@ -6,6 +6,10 @@ use std::collections::HashMap;
 /// Configuration for trust scoring
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TrustConfig {
+    /// Feed the tracker from tool outcomes, denied approvals and SOP
+    /// deviations, and tighten autonomy for regressed domains (default false)
+    #[serde(default)]
+    pub enabled: bool,
     /// Initial trust score for new domains (default 0.8)
     #[serde(default = "default_initial_score")]
     pub initial_score: f64,
@@ -42,6 +46,7 @@ fn default_success_boost() -> f64 {
 impl Default for TrustConfig {
     fn default() -> Self {
         Self {
+            enabled: false,
             initial_score: default_initial_score(),
             decay_half_life_days: default_decay_half_life(),
             regression_threshold: default_regression_threshold(),
@@ -87,6 +92,15 @@ pub struct RegressionAlert {
     pub detected_at: DateTime<Utc>,
 }
 
+/// Persisted form of a [`TrustTracker`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrustState {
+    #[serde(default)]
+    pub scores: HashMap<String, TrustScore>,
+    #[serde(default)]
+    pub correction_log: Vec<CorrectionEvent>,
+}
+
 /// Main trust tracker
 pub struct TrustTracker {
     config: TrustConfig,
@@ -103,12 +117,35 @@ impl TrustTracker {
         }
     }
 
+    /// Restore a tracker from previously persisted state
+    pub fn from_state(config: TrustConfig, state: TrustState) -> Self {
+        Self {
+            config,
+            scores: state.scores,
+            correction_log: state.correction_log,
+        }
+    }
+
+    /// Export persistable state, keeping only the newest `max_corrections` events
+    pub fn to_state(&self, max_corrections: usize) -> TrustState {
+        let skip = self.correction_log.len().saturating_sub(max_corrections);
+        TrustState {
+            scores: self.scores.clone(),
+            correction_log: self.correction_log[skip..].to_vec(),
+        }
+    }
+
     /// Get current trust score for domain (initializes if missing)
     pub fn get_score(&mut self, domain: &str) -> f64 {
         self.ensure_domain(domain);
         self.scores[domain].score
     }
 
+    /// Current score for a domain without starting to track it
+    pub fn score_if_tracked(&self, domain: &str) -> Option<f64> {
+        self.scores.get(domain).map(|s| s.score)
+    }
+
     /// Record a correction event — reduces trust
     pub fn record_correction(
         &mut self,
@@ -63,6 +63,21 @@ pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
     }
 }
 
+/// Run blocking work (file or database writes, cgroup teardown) on Tokio's
+/// blocking pool when a runtime is running, otherwise inline.
+///
+/// For callers that may sit on an async worker but cannot `.await`, such as
+/// `Drop` impls and synchronous engine methods. The work is detached; callers
+/// that need ordering queue their data and drain it inside `work`.
+pub fn run_blocking(work: impl FnOnce() + Send + 'static) {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => {
+            handle.spawn_blocking(work);
+        }
+        Err(_) => work(),
+    }
+}
+
 /// Utility enum for handling optional values.
 pub enum MaybeSet<T> {
     Set(T),
@@ -86,6 +86,7 @@ async fn provider_vision_support() -> Result<()> {
     let request = ChatRequest {
         messages: &messages,
         tools: None,
+        response_format: None,
     };
 
     // Send request to provider
@@ -154,8 +155,10 @@ async fn openai_codex_second_vision_support() -> Result<()> {
         reasoning_effort: None,
         provider_timeout_secs: None,
         provider_max_tokens: None,
+        provider_record_path: None,
         extra_headers: std::collections::HashMap::new(),
         api_path: None,
+        budget_routing: None,
     };
 
     let provider = zeroclaw::providers::create_provider_with_options("openai-codex", None, &opts)?;
@@ -213,6 +216,7 @@ async fn openai_codex_second_vision_support() -> Result<()> {
     let request = ChatRequest {
         messages: &messages,
         tools: None,
+        response_format: None,
     };
 
     // Send request to provider
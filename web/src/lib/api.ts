@@ -12,6 +12,8 @@ import type {
   Session,
   ChannelDetail,
   SessionMessagesResponse,
+  SessionBranchesResponse,
+  SessionCheckoutResponse,
 } from '../types/api';
 import { clearToken, getToken, setToken } from './auth';
 import { apiOrigin, basePath } from './basePath';
@@ -322,6 +324,26 @@ export function getSessionMessages(id: string): Promise<SessionMessagesResponse>
   );
 }
 
+export function getSessionBranches(id: string): Promise<SessionBranchesResponse> {
+  return apiFetch<SessionBranchesResponse>(
+    `/api/sessions/${encodeURIComponent(id)}/branches`,
+  );
+}
+
+/** Continue a session from `messageId` (or from the start when `null`), keeping other branches. */
+export function checkoutSession(
+  id: string,
+  messageId: number | null,
+): Promise<SessionCheckoutResponse> {
+  return apiFetch<SessionCheckoutResponse>(
+    `/api/sessions/${encodeURIComponent(id)}/branches`,
+    {
+      method: 'POST',
+      body: JSON.stringify({ message_id: messageId }),
+    },
+  );
+}
+
 // ---------------------------------------------------------------------------
 // Channels (detailed)
 // ---------------------------------------------------------------------------
@@ -314,6 +314,10 @@ const translations: Record<Locale, Record<string, string>> = {
     'dashboard.inactive': '非活跃',
     'dashboard.no_components': '没有组件报告',
     'dashboard.restarts': '重启次数',
+    'dashboard.trust_scores': '信任评分',
+    'dashboard.trust_disabled': '自适应信任未启用',
+    'dashboard.no_trust_scores': '尚未记录信任事件',
+    'dashboard.autonomy_reduced': '自主权已降低',
     'dashboard.tab_overview': '概览',
     'dashboard.tab_sessions': '会话',
     'dashboard.tab_channels': '频道',
@@ -671,6 +675,10 @@ const translations: Record<Locale, Record<string, string>> = {
     'dashboard.inactive': 'Inactive',
     'dashboard.no_components': 'No components reporting',
     'dashboard.restarts': 'Restarts',
+    'dashboard.trust_scores': 'Trust Scores',
+    'dashboard.trust_disabled': 'Adaptive trust is disabled',
+    'dashboard.no_trust_scores': 'No trust events recorded yet',
+    'dashboard.autonomy_reduced': 'Autonomy reduced',
     'dashboard.tab_overview': 'Overview',
     'dashboard.tab_sessions': 'Sessions',
     'dashboard.tab_channels': 'Channels',
@@ -773,6 +781,10 @@ const translations: Record<Locale, Record<string, string>> = {
     'dashboard.inactive': 'Aktif Değil',
     'dashboard.no_components': 'Bileşen raporlamıyor',
     'dashboard.restarts': 'Yeniden Başlatmalar',
+    'dashboard.trust_scores': 'Güven Puanları',
+    'dashboard.trust_disabled': 'Uyarlanabilir güven devre dışı',
+    'dashboard.no_trust_scores': 'Henüz güven olayı kaydedilmedi',
+    'dashboard.autonomy_reduced': 'Özerklik azaltıldı',
     'dashboard.tab_overview': 'Genel Bakış',
     'dashboard.tab_sessions': 'Oturumlar',
     'dashboard.tab_channels': 'Kanallar',
@@ -13,6 +13,7 @@ import {
   ChevronRight,
   Hash,
   Wifi,
+  ShieldCheck,
 } from 'lucide-react';
 import type { StatusResponse, CostSummary, Session, ChannelDetail } from '@/types/api';
 import { getStatus, getCost, getSessions, getChannels } from '@/lib/api';
@@ -418,6 +419,60 @@ function OverviewTab({
             )}
           </div>
         </div>
+
+        <div className="card p-5 animate-slide-in-up">
+          <div className="flex items-center gap-2 mb-5">
+            <ShieldCheck className="h-5 w-5" style={{ color: "var(--pc-accent)" }} />
+            <h2
+              className="text-sm font-semibold uppercase tracking-wider"
+              style={{ color: "var(--pc-text-primary)" }}
+            >
+              {t("dashboard.trust_scores")}
+            </h2>
+          </div>
+          {!status.trust?.enabled || status.trust.scores.length === 0 ? (
+            <p className="text-sm" style={{ color: "var(--pc-text-faint)" }}>
+              {status.trust?.enabled
+                ? t("dashboard.no_trust_scores")
+                : t("dashboard.trust_disabled")}
+            </p>
+          ) : (
+            <div className="space-y-3">
+              {status.trust.scores.map((entry) => {
+                const regressed = entry.score < status.trust.regression_threshold;
+                const color = regressed
+                  ? "var(--color-status-error)"
+                  : "var(--color-status-success)";
+                return (
+                  <div key={entry.domain}>
+                    <div className="flex items-center justify-between text-sm mb-1">
+                      <span className="font-medium" style={{ color: "var(--pc-text-primary)" }}>
+                        {entry.domain}
+                      </span>
+                      <span className="font-mono" style={{ color }}>
+                        {entry.score.toFixed(2)}
+                      </span>
+                    </div>
+                    <div
+                      className="h-1.5 rounded-full overflow-hidden"
+                      style={{ background: "var(--pc-hover)" }}
+                    >
+                      <div
+                        className="h-full rounded-full"
+                        style={{ width: `${Math.round(entry.score * 100)}%`, background: color }}
+                      />
+                    </div>
+                    {regressed && (
+                      <p className="text-xs mt-1" style={{ color: "var(--color-status-error)" }}>
+                        {t("dashboard.autonomy_reduced")}
+                      </p>
+                    )}
+                  </div>
+                );
+              })}
+            </div>
+          )}
+        </div>
       </div>
     </>
   );
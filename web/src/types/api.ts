@@ -9,6 +9,20 @@ export interface StatusResponse {
   paired: boolean;
   channels: Record<string, boolean>;
   health: HealthSnapshot;
+  trust: TrustStatus;
+}
+
+export interface TrustStatus {
+  enabled: boolean;
+  regression_threshold: number;
+  scores: TrustScore[];
+}
+
+export interface TrustScore {
+  domain: string;
+  score: number;
+  last_updated: string;
+  event_count: number;
 }
 
 export interface HealthSnapshot {
@@ -142,7 +156,8 @@ export interface WsMessage {
     | 'done'
     | 'error'
     | 'session_start'
-    | 'connected';
+    | 'connected'
+    | 'history';
   content?: string;
   full_response?: string;
   name?: string;
@@ -153,14 +168,39 @@ export interface WsMessage {
   session_id?: string;
   resumed?: boolean;
   message_count?: number;
+  active_head?: number | null;
+  messages?: SessionMessageRow[];
 }
 
 /** Row from GET /api/sessions/{id}/messages */
 export interface SessionMessageRow {
+  id?: number;
+  parent_id?: number | null;
   role: string;
   content: string;
 }
 
+/** Branch from GET /api/sessions/{id}/branches */
+export interface SessionBranch {
+  head_id: number;
+  fork_id: number | null;
+  message_count: number;
+  last_activity: string;
+  active: boolean;
+}
+
+export interface SessionBranchesResponse {
+  session_id: string;
+  active_head: number | null;
+  branches: SessionBranch[];
+}
+
+export interface SessionCheckoutResponse {
+  session_id: string;
+  active_head: number | null;
+  messages: SessionMessageRow[];
+}
+
 export interface SessionMessagesResponse {
   session_id: string;
   messages: SessionMessageRow[];